@@ -0,0 +1,248 @@
+//! `camp_rebuild_artifact_index`: reconciles `artifacts/index.json` with the
+//! markdown files beside it. Entries whose file still exists keep their
+//! metadata; files the index has lost track of are added back with a title
+//! read from the markdown and timestamps from the file system. A corrupt index
+//! is set aside as `index.json.bak` and rebuilt from the files alone.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::{
+    camp_artifacts_dir, camp_artifacts_index_path, empty_artifacts_index, ensure_camps_root,
+    ensure_main_window, parse_artifact_markdown, read_json_file, resolve_existing_camp_dir,
+    validate_artifact_filename, validate_identifier, write_artifacts_index, AppState,
+    CampArtifactMetadata, CampArtifactsIndex, CAMP_ARTIFACTS_INDEX_FILE,
+};
+
+const RECOVERED_SOURCE_ROLE: &str = "recovered";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnreconciledArtifactFile {
+    pub filename: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ArtifactIndexRebuildReport {
+    /// Entries kept from the existing index.
+    pub kept: usize,
+    /// Markdown files that were missing from the index and have been added.
+    pub recovered: Vec<String>,
+    /// Index entries dropped because their markdown file is gone.
+    pub missing: Vec<String>,
+    /// Files in the artifacts folder that could not become artifacts.
+    pub unreconciled: Vec<UnreconciledArtifactFile>,
+    /// Set when the old index could not be parsed and was moved aside.
+    pub corrupt_index_backup: Option<String>,
+}
+
+pub(crate) fn file_timestamp_ms(time: std::io::Result<SystemTime>) -> Option<i64> {
+    time.ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+}
+
+/// Metadata for a markdown file the index no longer knows about. The id is
+/// the file stem when it is free, as it is for files Basecamp named itself.
+pub(crate) fn recover_metadata(
+    filename: &str,
+    markdown: &str,
+    created_at: i64,
+    updated_at: i64,
+    taken_ids: &HashSet<String>,
+) -> CampArtifactMetadata {
+    let stem = filename.trim_end_matches(".md");
+    let id = validate_identifier(stem, "artifact_id")
+        .ok()
+        .filter(|id| !taken_ids.contains(id))
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let (title, _) = parse_artifact_markdown(markdown, stem);
+    CampArtifactMetadata {
+        id,
+        title,
+        filename: filename.to_string(),
+        source_message_id: String::new(),
+        source_role: RECOVERED_SOURCE_ROLE.to_string(),
+        tags: Vec::new(),
+        created_at,
+        updated_at,
+        usage_count: 0,
+        archived: false,
+        source_path: None,
+        always_include: false,
+        include_order: None,
+    }
+}
+
+fn rebuild_index(
+    camp_dir: &Path,
+) -> Result<(CampArtifactsIndex, ArtifactIndexRebuildReport), String> {
+    let artifacts_dir = camp_artifacts_dir(camp_dir);
+    let index_path = camp_artifacts_index_path(camp_dir);
+    let mut report = ArtifactIndexRebuildReport::default();
+
+    let previous = if index_path.exists() {
+        match read_json_file::<CampArtifactsIndex>(&index_path) {
+            Ok(index) => index,
+            Err(_) => {
+                let backup_path = artifacts_dir.join(format!("{CAMP_ARTIFACTS_INDEX_FILE}.bak"));
+                fs::rename(&index_path, &backup_path)
+                    .map_err(|err| format!("Unable to set aside corrupt artifact index: {err}"))?;
+                report.corrupt_index_backup = Some(backup_path.to_string_lossy().into_owned());
+                empty_artifacts_index()
+            }
+        }
+    } else {
+        empty_artifacts_index()
+    };
+
+    let mut index = empty_artifacts_index();
+    let mut taken_ids = HashSet::new();
+    let mut indexed_files = HashSet::new();
+    for artifact in previous.artifacts {
+        let exists = validate_artifact_filename(&artifact.filename)
+            .is_ok_and(|filename| artifacts_dir.join(filename).is_file());
+        if !exists {
+            report.missing.push(artifact.filename);
+            continue;
+        }
+        if !taken_ids.insert(artifact.id.clone())
+            || !indexed_files.insert(artifact.filename.clone())
+        {
+            // A duplicate entry; the first one wins.
+            continue;
+        }
+        index.artifacts.push(artifact);
+    }
+    report.kept = index.artifacts.len();
+
+    let mut entries: Vec<_> = match fs::read_dir(&artifacts_dir) {
+        Ok(entries) => entries.flatten().collect(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(format!("Unable to read artifacts folder: {err}")),
+    };
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let Some(filename) = entry.file_name().to_str().map(ToString::to_string) else {
+            report.unreconciled.push(UnreconciledArtifactFile {
+                filename: entry.file_name().to_string_lossy().into_owned(),
+                reason: "File name is not valid UTF-8.".to_string(),
+            });
+            continue;
+        };
+        if filename.starts_with('.')
+            || filename.starts_with(CAMP_ARTIFACTS_INDEX_FILE)
+            || indexed_files.contains(&filename)
+        {
+            continue;
+        }
+        let unreconciled = |reason: String| UnreconciledArtifactFile {
+            filename: filename.clone(),
+            reason,
+        };
+        let Ok(file_metadata) = entry.metadata() else {
+            report
+                .unreconciled
+                .push(unreconciled("Unable to read file metadata.".to_string()));
+            continue;
+        };
+        if !file_metadata.is_file() {
+            report
+                .unreconciled
+                .push(unreconciled("Not a regular file.".to_string()));
+            continue;
+        }
+        if let Err(err) = validate_artifact_filename(&filename) {
+            report.unreconciled.push(unreconciled(err));
+            continue;
+        }
+        let markdown = match fs::read_to_string(entry.path()) {
+            Ok(markdown) => markdown,
+            Err(err) => {
+                report
+                    .unreconciled
+                    .push(unreconciled(format!("Unable to read file: {err}")));
+                continue;
+            }
+        };
+
+        let updated_at = file_timestamp_ms(file_metadata.modified()).unwrap_or(0);
+        let created_at = file_timestamp_ms(file_metadata.created())
+            .unwrap_or(updated_at)
+            .min(updated_at);
+        let metadata = recover_metadata(&filename, &markdown, created_at, updated_at, &taken_ids);
+        taken_ids.insert(metadata.id.clone());
+        report.recovered.push(filename);
+        index.artifacts.push(metadata);
+    }
+
+    Ok((index, report))
+}
+
+/// Rebuilds a camp's artifact index from the markdown files on disk and
+/// reports anything that could not be reconciled.
+#[tauri::command]
+pub fn camp_rebuild_artifact_index(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<ArtifactIndexRebuildReport, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let (index, report) = rebuild_index(&camp_dir)?;
+    write_artifacts_index(&camp_dir, &index)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn rebuild_index_should_keep_known_entries_and_recover_orphans() {
+        let camp_dir = make_temp_dir("basecamp-artifacts");
+        let artifacts_dir = camp_artifacts_dir(&camp_dir);
+        fs::create_dir_all(&artifacts_dir).expect("artifacts dir");
+        fs::write(artifacts_dir.join("known.md"), "# Known\n\nBody").expect("known");
+        fs::write(
+            artifacts_dir.join("orphan.md"),
+            "# Lost Notes\n\nStill here",
+        )
+        .expect("orphan");
+        fs::write(artifacts_dir.join("picture.png"), [0_u8, 1, 2]).expect("png");
+        fs::write(camp_artifacts_index_path(&camp_dir), "{ not json").expect("index");
+
+        let (index, report) = rebuild_index(&camp_dir).expect("rebuild");
+        assert!(report.corrupt_index_backup.is_some());
+        assert_eq!(report.recovered, vec!["known.md", "orphan.md"]);
+        assert_eq!(report.unreconciled.len(), 1);
+        assert_eq!(report.unreconciled[0].filename, "picture.png");
+        let orphan = index
+            .artifacts
+            .iter()
+            .find(|artifact| artifact.filename == "orphan.md")
+            .expect("orphan recovered");
+        assert_eq!(orphan.id, "orphan");
+        assert_eq!(orphan.title, "Lost Notes");
+
+        write_artifacts_index(&camp_dir, &index).expect("write");
+        fs::remove_file(artifacts_dir.join("known.md")).expect("remove");
+        let (index, report) = rebuild_index(&camp_dir).expect("second rebuild");
+        assert_eq!(report.kept, 1);
+        assert_eq!(report.missing, vec!["known.md"]);
+        assert_eq!(index.artifacts.len(), 1);
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+use crate::{
+    camp_transcript_path, ensure_camps_root, ensure_main_window, now_timestamp_ms, read_json_file,
+    read_transcript, resolve_existing_camp_dir, validate_non_empty, write_json_file, AppState,
+};
+
+const CAMP_BOOKMARKS_FILE: &str = "bookmarks.json";
+const CAMP_BOOKMARKS_SCHEMA_VERSION: &str = "0.1";
+const BOOKMARK_SNIPPET_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampBookmark {
+    pub message_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Keeps the message verbatim when the transcript is trimmed to fit a context window.
+    #[serde(default)]
+    pub exclude_from_compaction: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CampBookmarksIndex {
+    schema_version: String,
+    bookmarks: Vec<CampBookmark>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PinnedMessage {
+    pub bookmark: CampBookmark,
+    /// Zero-based position of the message in the transcript.
+    pub position: usize,
+    pub role: String,
+    pub snippet: String,
+    pub message_created_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampPinMessagePayload {
+    pub camp_id: String,
+    pub message_id: String,
+    pub note: Option<String>,
+    pub exclude_from_compaction: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampUnpinMessagePayload {
+    pub camp_id: String,
+    pub message_id: String,
+}
+
+fn camp_bookmarks_path(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_BOOKMARKS_FILE)
+}
+
+fn read_bookmarks_index(camp_dir: &Path) -> Result<CampBookmarksIndex, String> {
+    let path = camp_bookmarks_path(camp_dir);
+    if !path.exists() {
+        return Ok(CampBookmarksIndex {
+            schema_version: CAMP_BOOKMARKS_SCHEMA_VERSION.to_string(),
+            bookmarks: Vec::new(),
+        });
+    }
+
+    read_json_file(&path)
+}
+
+fn snippet(content: &str) -> String {
+    let collapsed = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= BOOKMARK_SNIPPET_CHARS {
+        return collapsed;
+    }
+    let mut shortened: String = collapsed.chars().take(BOOKMARK_SNIPPET_CHARS).collect();
+    shortened.push('…');
+    shortened
+}
+
+/// Pins a message, or updates the note and compaction flag of an existing pin.
+fn pin_message(
+    camp_dir: &Path,
+    message_id: &str,
+    note: Option<String>,
+    exclude_from_compaction: Option<bool>,
+) -> Result<CampBookmark, String> {
+    let message_id = validate_non_empty(message_id, "message_id")?;
+    let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+    if !transcript.iter().any(|message| message.id == message_id) {
+        return Err("Message not found in transcript.".to_string());
+    }
+    let note = note
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    let mut index = read_bookmarks_index(camp_dir)?;
+    let bookmark = match index
+        .bookmarks
+        .iter_mut()
+        .find(|bookmark| bookmark.message_id == message_id)
+    {
+        Some(existing) => {
+            existing.note = note;
+            if let Some(exclude) = exclude_from_compaction {
+                existing.exclude_from_compaction = exclude;
+            }
+            existing.clone()
+        }
+        None => {
+            let bookmark = CampBookmark {
+                message_id,
+                note,
+                exclude_from_compaction: exclude_from_compaction.unwrap_or(true),
+                created_at: now_timestamp_ms(),
+            };
+            index.bookmarks.push(bookmark.clone());
+            bookmark
+        }
+    };
+
+    write_json_file(&camp_bookmarks_path(camp_dir), &index)?;
+    Ok(bookmark)
+}
+
+fn unpin_message(camp_dir: &Path, message_id: &str) -> Result<(), String> {
+    let mut index = read_bookmarks_index(camp_dir)?;
+    let before = index.bookmarks.len();
+    index
+        .bookmarks
+        .retain(|bookmark| bookmark.message_id != message_id);
+    if index.bookmarks.len() == before {
+        return Err("Message is not pinned.".to_string());
+    }
+    write_json_file(&camp_bookmarks_path(camp_dir), &index)
+}
+
+/// Pinned messages in transcript order. Pins whose message no longer exists (for
+/// example after a rollback) are skipped rather than reported.
+fn list_pinned_messages(camp_dir: &Path) -> Result<Vec<PinnedMessage>, String> {
+    let index = read_bookmarks_index(camp_dir)?;
+    if index.bookmarks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+
+    let mut pinned: Vec<PinnedMessage> = index
+        .bookmarks
+        .into_iter()
+        .filter_map(|bookmark| {
+            let (position, message) = transcript
+                .iter()
+                .enumerate()
+                .find(|(_, message)| message.id == bookmark.message_id)?;
+            Some(PinnedMessage {
+                position,
+                role: message.role.clone(),
+                snippet: snippet(&message.content),
+                message_created_at: message.created_at,
+                bookmark,
+            })
+        })
+        .collect();
+    pinned.sort_by_key(|entry| entry.position);
+    Ok(pinned)
+}
+
+/// Message ids the context assembler must keep verbatim instead of dropping.
+pub(crate) fn compaction_protected_message_ids(camp_dir: &Path) -> Result<HashSet<String>, String> {
+    Ok(read_bookmarks_index(camp_dir)?
+        .bookmarks
+        .into_iter()
+        .filter(|bookmark| bookmark.exclude_from_compaction)
+        .map(|bookmark| bookmark.message_id)
+        .collect())
+}
+
+#[tauri::command]
+pub fn camp_pin_message(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampPinMessagePayload,
+) -> Result<CampBookmark, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    pin_message(
+        &camp_dir,
+        &payload.message_id,
+        payload.note,
+        payload.exclude_from_compaction,
+    )
+}
+
+#[tauri::command]
+pub fn camp_unpin_message(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampUnpinMessagePayload,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    unpin_message(&camp_dir, &payload.message_id)
+}
+
+#[tauri::command]
+pub fn camp_list_pinned_messages(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<PinnedMessage>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    list_pinned_messages(&camp_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use std::fs;
+
+    #[test]
+    fn pinned_messages_should_list_in_transcript_order_and_protect_from_compaction() {
+        let camp_dir = make_temp_dir("basecamp-bookmarks");
+        fs::write(
+            camp_transcript_path(&camp_dir),
+            concat!(
+                "{\"id\":\"m1\",\"role\":\"user\",\"content\":\"Plan the\\n  launch\",\"created_at\":1}\n",
+                "{\"id\":\"m2\",\"role\":\"assistant\",\"content\":\"Launch plan\",\"created_at\":2}\n",
+                "{\"id\":\"m3\",\"role\":\"user\",\"content\":\"Thanks\",\"created_at\":3}\n",
+            ),
+        )
+        .expect("transcript should write");
+
+        pin_message(&camp_dir, "m2", Some("final plan".to_string()), None)
+            .expect("pin should succeed");
+        pin_message(&camp_dir, "m1", None, Some(false)).expect("pin should succeed");
+        assert!(pin_message(&camp_dir, "missing", None, None).is_err());
+
+        let pinned = list_pinned_messages(&camp_dir).expect("pins should list");
+        let ids: Vec<&str> = pinned
+            .iter()
+            .map(|entry| entry.bookmark.message_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["m1", "m2"]);
+        assert_eq!(pinned[0].snippet, "Plan the launch");
+        assert_eq!(pinned[1].bookmark.note.as_deref(), Some("final plan"));
+
+        let protected =
+            compaction_protected_message_ids(&camp_dir).expect("protected ids should load");
+        assert_eq!(protected, HashSet::from(["m2".to_string()]));
+
+        unpin_message(&camp_dir, "m2").expect("unpin should succeed");
+        assert!(unpin_message(&camp_dir, "m2").is_err());
+        assert_eq!(
+            list_pinned_messages(&camp_dir)
+                .expect("pins should list")
+                .len(),
+            1
+        );
+    }
+}
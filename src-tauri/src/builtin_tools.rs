@@ -0,0 +1,581 @@
+//! Deterministic tools that run locally: `calculate`, `current_datetime` and
+//! `random_uuid`. Models are unreliable at arithmetic and don't know today's
+//! date, so camps with tools enabled can ask the app instead of guessing.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime, UtcOffset};
+use uuid::Uuid;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::tool_schema::{tool_parameters_schema, validate_tool_arguments};
+
+const MAX_EXPRESSION_CHARS: usize = 1_000;
+/// Deep enough for any real expression, shallow enough not to overflow the
+/// stack on `((((...`.
+const MAX_EXPRESSION_DEPTH: usize = 64;
+const MAX_UUIDS: u64 = 20;
+
+#[derive(Debug, Deserialize)]
+pub struct RunBuiltinToolPayload {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+    /// The user's UTC offset, used by `current_datetime` when the model
+    /// doesn't ask for one.
+    #[serde(default)]
+    pub local_utc_offset_minutes: i32,
+    /// IANA name of the user's time zone, echoed back for context.
+    pub local_time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(usize, usize),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let bytes = expression.as_bytes();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        match byte {
+            b' ' | b'\t' | b'\n' | b'\r' => index += 1,
+            b'0'..=b'9' | b'.' => {
+                let start = index;
+                while index < bytes.len() && (bytes[index].is_ascii_digit() || bytes[index] == b'.')
+                {
+                    index += 1;
+                }
+                if index < bytes.len() && (bytes[index] == b'e' || bytes[index] == b'E') {
+                    let mut end = index + 1;
+                    if end < bytes.len() && (bytes[end] == b'+' || bytes[end] == b'-') {
+                        end += 1;
+                    }
+                    if end < bytes.len() && bytes[end].is_ascii_digit() {
+                        index = end;
+                        while index < bytes.len() && bytes[index].is_ascii_digit() {
+                            index += 1;
+                        }
+                    }
+                }
+                let literal = &expression[start..index];
+                let value = literal
+                    .parse::<f64>()
+                    .map_err(|_| format!("`{literal}` is not a number."))?;
+                tokens.push(Token::Number(value));
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                let start = index;
+                while index < bytes.len()
+                    && (bytes[index].is_ascii_alphanumeric() || bytes[index] == b'_')
+                {
+                    index += 1;
+                }
+                tokens.push(Token::Ident(start, index));
+            }
+            b'+' | b'-' | b'*' | b'/' | b'%' | b'^' => {
+                // `**` is a common spelling of `^`.
+                if byte == b'*' && bytes.get(index + 1) == Some(&b'*') {
+                    tokens.push(Token::Op('^'));
+                    index += 2;
+                } else {
+                    tokens.push(Token::Op(byte as char));
+                    index += 1;
+                }
+            }
+            b'(' => {
+                tokens.push(Token::LParen);
+                index += 1;
+            }
+            b')' => {
+                tokens.push(Token::RParen);
+                index += 1;
+            }
+            b',' => {
+                tokens.push(Token::Comma);
+                index += 1;
+            }
+            _ => {
+                let ch = expression[index..].chars().next().unwrap_or('?');
+                return Err(format!("Unexpected character `{ch}`."));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over
+/// `expr := term (('+'|'-') term)*`,
+/// `term := unary (('*'|'/'|'%') unary)*`,
+/// `unary := '-' unary | '+' unary | power`,
+/// `power := atom ('^' unary)?` (right associative, binds tighter than
+/// unary minus on its left, so `-2^2` is `-4`).
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, what: &str) -> Result<(), String> {
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(format!("Expected {what}."))
+        }
+    }
+
+    fn descend(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_EXPRESSION_DEPTH {
+            return Err("Expression is nested too deeply.".to_string());
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<f64, String> {
+        self.descend()?;
+        let mut value = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek() {
+            self.position += 1;
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, String> {
+        let mut value = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek() {
+            self.position += 1;
+            let rhs = self.unary()?;
+            if op != '*' && rhs == 0.0 {
+                return Err("Division by zero.".to_string());
+            }
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, String> {
+        match self.peek() {
+            Some(Token::Op('-')) => {
+                self.position += 1;
+                self.descend()?;
+                let value = -self.unary()?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            Some(Token::Op('+')) => {
+                self.position += 1;
+                self.descend()?;
+                let value = self.unary()?;
+                self.depth -= 1;
+                Ok(value)
+            }
+            _ => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<f64, String> {
+        let base = self.atom()?;
+        if self.peek() == Some(Token::Op('^')) {
+            self.position += 1;
+            self.descend()?;
+            let exponent = self.unary()?;
+            self.depth -= 1;
+            return Ok(base.powf(exponent));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            Some(Token::LParen) => {
+                let value = self.expr()?;
+                self.expect(Token::RParen, "`)`")?;
+                Ok(value)
+            }
+            Some(Token::Ident(start, end)) => {
+                let name = self.source[start..end].to_ascii_lowercase();
+                if self.peek() == Some(Token::LParen) {
+                    self.position += 1;
+                    let mut args = Vec::new();
+                    if self.peek() != Some(Token::RParen) {
+                        args.push(self.expr()?);
+                        while self.peek() == Some(Token::Comma) {
+                            self.position += 1;
+                            args.push(self.expr()?);
+                        }
+                    }
+                    self.expect(Token::RParen, &format!("`)` to close `{name}(`"))?;
+                    call_function(&name, &args)
+                } else {
+                    match name.as_str() {
+                        "pi" => Ok(std::f64::consts::PI),
+                        "e" => Ok(std::f64::consts::E),
+                        "tau" => Ok(std::f64::consts::TAU),
+                        _ => Err(format!("Unknown name `{name}`.")),
+                    }
+                }
+            }
+            Some(_) => Err("Expected a number, name or `(`.".to_string()),
+            None => Err("Expression ended early.".to_string()),
+        }
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64, String> {
+    let unary = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => Err(format!("`{name}` takes one argument.")),
+    };
+    match name {
+        "sqrt" => unary(f64::sqrt),
+        "cbrt" => unary(f64::cbrt),
+        "abs" => unary(f64::abs),
+        "exp" => unary(f64::exp),
+        "ln" => unary(f64::ln),
+        "log10" | "log" if args.len() == 1 => unary(f64::log10),
+        "log2" => unary(f64::log2),
+        "log" => match args {
+            [x, base] => Ok(x.log(*base)),
+            _ => Err("`log` takes a value and an optional base.".to_string()),
+        },
+        "sin" => unary(f64::sin),
+        "cos" => unary(f64::cos),
+        "tan" => unary(f64::tan),
+        "asin" => unary(f64::asin),
+        "acos" => unary(f64::acos),
+        "atan" => unary(f64::atan),
+        "floor" => unary(f64::floor),
+        "ceil" => unary(f64::ceil),
+        "round" => unary(f64::round),
+        "trunc" => unary(f64::trunc),
+        "pow" => match args {
+            [base, exponent] => Ok(base.powf(*exponent)),
+            _ => Err("`pow` takes two arguments.".to_string()),
+        },
+        "min" | "max" if !args.is_empty() => Ok(args.iter().copied().fold(
+            if name == "min" {
+                f64::INFINITY
+            } else {
+                f64::NEG_INFINITY
+            },
+            if name == "min" { f64::min } else { f64::max },
+        )),
+        "min" | "max" => Err(format!("`{name}` needs at least one argument.")),
+        _ => Err(format!("Unknown function `{name}`.")),
+    }
+}
+
+/// Evaluates an arithmetic expression. Results are rounded to 12 significant
+/// digits so `0.1 + 0.2` reads as `0.3`.
+pub(crate) fn evaluate_expression(expression: &str) -> Result<f64, String> {
+    if expression.trim().is_empty() {
+        return Err("Expression is empty.".to_string());
+    }
+    if expression.chars().count() > MAX_EXPRESSION_CHARS {
+        return Err(format!(
+            "Expressions are limited to {MAX_EXPRESSION_CHARS} characters."
+        ));
+    }
+    let mut parser = Parser {
+        source: expression,
+        tokens: tokenize(expression)?,
+        position: 0,
+        depth: 0,
+    };
+    let value = parser.expr()?;
+    if parser.position < parser.tokens.len() {
+        return Err("Unexpected input after the end of the expression.".to_string());
+    }
+    if !value.is_finite() {
+        return Err("Result is not a finite number.".to_string());
+    }
+    let rounded: f64 = format!("{value:.11e}").parse().unwrap_or(value);
+    Ok(if rounded == 0.0 { 0.0 } else { rounded })
+}
+
+fn number_value(value: f64) -> Value {
+    if value.fract() == 0.0 && value.abs() < 9.0e15 {
+        json!(value as i64)
+    } else {
+        json!(value)
+    }
+}
+
+/// Parses `Z`, `+05:30`, `-0800` or `+9`.
+fn parse_utc_offset(raw: &str) -> Result<UtcOffset, String> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("z") || raw.eq_ignore_ascii_case("utc") {
+        return Ok(UtcOffset::UTC);
+    }
+    let invalid = || format!("`{raw}` is not a UTC offset like `+05:30`.");
+    let (sign, rest) = match raw.as_bytes().first() {
+        Some(b'+') => (1, &raw[1..]),
+        Some(b'-') => (-1, &raw[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours: i32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    UtcOffset::from_whole_seconds(sign * (hours * 3600 + minutes * 60)).map_err(|_| invalid())
+}
+
+fn format_offset(offset: UtcOffset) -> String {
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    format!("{sign}{:02}:{:02}", hours.abs(), minutes.abs())
+}
+
+fn describe_datetime(now: OffsetDateTime, time_zone: Option<&str>) -> CommandResult<Value> {
+    let iso = now
+        .format(&Rfc3339)
+        .map_err(|err| BasecampError::internal(format!("Unable to format time: {err}")))?;
+    let mut result = json!({
+        "iso": iso,
+        "date": format!("{:04}-{:02}-{:02}", now.year(), u8::from(now.month()), now.day()),
+        "time": format!("{:02}:{:02}:{:02}", now.hour(), now.minute(), now.second()),
+        "weekday": now.weekday().to_string(),
+        "utc_offset": format_offset(now.offset()),
+        "unix_ms": now.unix_timestamp() * 1000 + i64::from(now.millisecond()),
+    });
+    if let Some(time_zone) = time_zone {
+        result["time_zone"] = json!(time_zone);
+    }
+    Ok(result)
+}
+
+/// Runs one built-in tool and returns its JSON result. Bad arguments are
+/// validation errors so the model can read them and try again.
+pub(crate) fn execute_builtin_tool(
+    name: &str,
+    arguments: &Value,
+    local_utc_offset_minutes: i32,
+    local_time_zone: Option<&str>,
+) -> CommandResult<Value> {
+    match name {
+        "calculate" => {
+            let expression = arguments
+                .get("expression")
+                .and_then(Value::as_str)
+                .ok_or_else(|| {
+                    BasecampError::validation("calculate requires an `expression` string.")
+                })?;
+            let result = evaluate_expression(expression).map_err(|message| {
+                BasecampError::validation(message).with_context("expression", expression)
+            })?;
+            Ok(json!({ "expression": expression, "result": number_value(result) }))
+        }
+        "current_datetime" => {
+            let requested = arguments.get("utc_offset").and_then(Value::as_str);
+            let offset = match requested {
+                Some(raw) => parse_utc_offset(raw).map_err(BasecampError::validation)?,
+                None => UtcOffset::from_whole_seconds(local_utc_offset_minutes * 60)
+                    .unwrap_or(UtcOffset::UTC),
+            };
+            describe_datetime(
+                OffsetDateTime::now_utc().to_offset(offset),
+                requested.is_none().then_some(local_time_zone).flatten(),
+            )
+        }
+        "random_uuid" => {
+            let count = arguments.get("count").and_then(Value::as_u64).unwrap_or(1);
+            if !(1..=MAX_UUIDS).contains(&count) {
+                return Err(BasecampError::validation(format!(
+                    "random_uuid can generate between 1 and {MAX_UUIDS} ids."
+                )));
+            }
+            let uuids: Vec<String> = (0..count).map(|_| Uuid::new_v4().to_string()).collect();
+            Ok(json!({ "uuids": uuids }))
+        }
+        _ => Err(BasecampError::validation(format!(
+            "`{name}` is not a built-in tool."
+        ))),
+    }
+}
+
+/// The spec each built-in tool declares to the model; mirrors the frontend
+/// tool registry.
+fn builtin_tool_spec(name: &str) -> Option<Value> {
+    let parameters = match name {
+        "calculate" => json!({
+            "type": "object",
+            "properties": { "expression": { "type": "string" } },
+            "required": ["expression"],
+            "additionalProperties": false
+        }),
+        "current_datetime" => json!({
+            "type": "object",
+            "properties": { "utc_offset": { "type": "string" } },
+            "required": [],
+            "additionalProperties": false
+        }),
+        "random_uuid" => json!({
+            "type": "object",
+            "properties": {
+                "count": { "type": "integer", "minimum": 1, "maximum": MAX_UUIDS, "default": 1 }
+            },
+            "required": [],
+            "additionalProperties": false
+        }),
+        _ => return None,
+    };
+    Some(json!({ "type": "function", "function": { "name": name, "parameters": parameters } }))
+}
+
+/// Rejects arguments that don't match the tool's spec, listing every
+/// violation so the model can correct its call.
+fn check_builtin_arguments(name: &str, arguments: &Value) -> CommandResult<()> {
+    let Some(spec) = builtin_tool_spec(name) else {
+        return Ok(());
+    };
+    let Some(schema) = tool_parameters_schema(&spec) else {
+        return Ok(());
+    };
+    // A call without arguments is an empty object.
+    let empty = json!({});
+    let arguments = if arguments.is_null() {
+        &empty
+    } else {
+        arguments
+    };
+    validate_tool_arguments(schema, arguments).map_err(|errors| {
+        BasecampError::validation(format!(
+            "Invalid arguments for `{name}`; correct them and call the tool again."
+        ))
+        .with_context("tool", name)
+        .with_context(
+            "validation_errors",
+            serde_json::to_value(errors).unwrap_or(Value::Null),
+        )
+    })
+}
+
+#[tauri::command]
+pub fn run_builtin_tool(payload: RunBuiltinToolPayload) -> CommandResult<Value> {
+    check_builtin_arguments(&payload.name, &payload.arguments)?;
+    execute_builtin_tool(
+        &payload.name,
+        &payload.arguments,
+        payload.local_utc_offset_minutes,
+        payload.local_time_zone.as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_tools_should_calculate_and_describe_dates_deterministically() {
+        let eval = |expression: &str| evaluate_expression(expression).expect("should evaluate");
+        assert_eq!(eval("0.1 + 0.2"), 0.3);
+        assert_eq!(eval("2 + 3 * 4 ^ 2 / 8"), 8.0);
+        assert_eq!(eval("-2^2 + 2**3**2"), 508.0);
+        assert_eq!(eval("sqrt(16) + max(1, 7, 3) - log(8, 2) % 2"), 10.0);
+        assert_eq!(eval("floor(pi * 1e2) + cbrt(27)"), 317.0);
+        assert_eq!(eval("1.5e-3 * 2"), 0.003);
+        for bad in [
+            "1 / 0",
+            "2 +",
+            "foo(1)",
+            "(1",
+            "1 2",
+            "3 $ 4",
+            &"(".repeat(100),
+        ] {
+            assert!(evaluate_expression(bad).is_err(), "{bad} should fail");
+        }
+
+        let result = execute_builtin_tool("calculate", &json!({ "expression": "6*7" }), 0, None)
+            .expect("calculate runs");
+        assert_eq!(result["result"], json!(42));
+
+        assert_eq!(
+            format_offset(parse_utc_offset("+05:30").expect("offset parses")),
+            "+05:30"
+        );
+        assert_eq!(
+            format_offset(parse_utc_offset("-0800").expect("offset parses")),
+            "-08:00"
+        );
+        assert!(parse_utc_offset("05:30").is_err());
+        let moment = OffsetDateTime::from_unix_timestamp(1_700_000_000)
+            .expect("timestamp is valid")
+            .to_offset(parse_utc_offset("-05:00").expect("offset parses"));
+        let described = describe_datetime(moment, Some("America/New_York")).expect("formats");
+        assert_eq!(described["iso"], "2023-11-14T17:13:20-05:00");
+        assert_eq!(described["date"], "2023-11-14");
+        assert_eq!(described["weekday"], "Tuesday");
+        assert_eq!(described["time_zone"], "America/New_York");
+
+        let uuids = execute_builtin_tool("random_uuid", &json!({ "count": 3 }), 0, None)
+            .expect("uuids generate");
+        assert_eq!(uuids["uuids"].as_array().map(Vec::len), Some(3));
+        assert!(execute_builtin_tool("random_uuid", &json!({ "count": 0 }), 0, None).is_err());
+    }
+
+    #[test]
+    fn run_builtin_tool_should_reject_arguments_outside_the_declared_spec() {
+        let run = |name: &str, arguments: Value| {
+            run_builtin_tool(RunBuiltinToolPayload {
+                name: name.to_string(),
+                arguments,
+                local_utc_offset_minutes: 0,
+                local_time_zone: None,
+            })
+        };
+
+        let error = run("random_uuid", json!({ "count": "3", "prefix": "id" }))
+            .expect_err("arguments should be rejected");
+        assert_eq!(error.kind, crate::error::BasecampErrorKind::Validation);
+        let paths: Vec<&str> = error.context["validation_errors"]
+            .as_array()
+            .expect("violations are listed")
+            .iter()
+            .filter_map(|violation| violation["path"].as_str())
+            .collect();
+        assert!(paths.contains(&"/count"));
+        assert!(paths.contains(&""));
+
+        assert!(run("calculate", json!({})).is_err());
+        assert_eq!(
+            run("calculate", json!({ "expression": "1 + 1" })).expect("valid call runs")["result"],
+            json!(2)
+        );
+        assert!(run("current_datetime", Value::Null).is_ok());
+    }
+}
@@ -0,0 +1,243 @@
+//! `camp_activity_timeline`: a camp's transcript messages and run token usage
+//! bucketed by local calendar day, for the activity heatmap. Days without
+//! activity are included so the UI can lay the grid out directly.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::State;
+
+use crate::digest::{day_bounds, day_label, local_day};
+use crate::{
+    camp_transcript_path, ensure_camps_root, now_timestamp_ms, read_transcript,
+    resolve_existing_camp_dir, AppState, CampMessage,
+};
+
+const DEFAULT_TIMELINE_DAYS: u32 = 365;
+const MAX_TIMELINE_DAYS: u32 = 730;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ActivityDay {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub message_count: usize,
+    /// Message counts keyed by role (`user`, `assistant`, `tool`, ...).
+    pub messages_by_role: BTreeMap<String, usize>,
+    pub run_count: usize,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub total_tokens: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CampActivityTimeline {
+    pub camp_id: String,
+    /// Oldest day first, ending today.
+    pub days: Vec<ActivityDay>,
+    pub busiest_day_message_count: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RunUsage {
+    timestamp: i64,
+    prompt_tokens: Option<i64>,
+    completion_tokens: Option<i64>,
+    total_tokens: Option<i64>,
+}
+
+fn load_run_usage(
+    connection: &Connection,
+    camp_id: &str,
+    start: i64,
+    end: i64,
+) -> Result<Vec<RunUsage>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        "SELECT timestamp, prompt_tokens, completion_tokens, total_tokens
+         FROM runs
+         WHERE camp_id = ?1 AND timestamp >= ?2 AND timestamp < ?3",
+    )?;
+    let rows = statement.query_map(params![camp_id, start, end], |row| {
+        Ok(RunUsage {
+            timestamp: row.get(0)?,
+            prompt_tokens: row.get(1)?,
+            completion_tokens: row.get(2)?,
+            total_tokens: row.get(3)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Keeps a camp's runs on its timeline when its folder, and so its id, is
+/// renamed.
+pub(crate) fn rename_camp_runs(
+    connection: &Connection,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), rusqlite::Error> {
+    if from_id != to_id {
+        connection.execute(
+            "UPDATE runs SET camp_id = ?2 WHERE camp_id = ?1",
+            params![from_id, to_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Buckets messages and runs into the `day_count` local days ending on
+/// `last_day`. Anything outside that range is ignored.
+fn bucket_activity(
+    messages: &[CampMessage],
+    runs: &[RunUsage],
+    last_day: i64,
+    day_count: u32,
+    utc_offset_minutes: i32,
+) -> Vec<ActivityDay> {
+    let first_day = last_day - i64::from(day_count) + 1;
+    let mut days: Vec<ActivityDay> = (first_day..=last_day)
+        .map(|day| ActivityDay {
+            date: day_label(day),
+            ..ActivityDay::default()
+        })
+        .collect();
+    let slot = |timestamp: i64| {
+        let day = local_day(timestamp, utc_offset_minutes);
+        (first_day..=last_day)
+            .contains(&day)
+            .then(|| (day - first_day) as usize)
+    };
+
+    for message in messages {
+        if let Some(index) = slot(message.created_at) {
+            let day = &mut days[index];
+            day.message_count += 1;
+            *day.messages_by_role
+                .entry(message.role.clone())
+                .or_default() += 1;
+        }
+    }
+    for run in runs {
+        if let Some(index) = slot(run.timestamp) {
+            let day = &mut days[index];
+            let prompt = run.prompt_tokens.unwrap_or(0);
+            let completion = run.completion_tokens.unwrap_or(0);
+            day.run_count += 1;
+            day.prompt_tokens += prompt;
+            day.completion_tokens += completion;
+            day.total_tokens += run.total_tokens.unwrap_or(prompt + completion);
+        }
+    }
+    days
+}
+
+/// Daily message counts by role and token usage for one camp over the last
+/// `days` days (a year by default). `utc_offset_minutes` is minutes east of
+/// UTC, so days follow the user's calendar.
+#[tauri::command]
+pub fn camp_activity_timeline(
+    state: State<'_, AppState>,
+    camp_id: String,
+    days: Option<u32>,
+    utc_offset_minutes: Option<i32>,
+) -> Result<CampActivityTimeline, String> {
+    let day_count = days
+        .unwrap_or(DEFAULT_TIMELINE_DAYS)
+        .clamp(1, MAX_TIMELINE_DAYS);
+    let utc_offset_minutes = utc_offset_minutes.unwrap_or(0);
+    let last_day = local_day(now_timestamp_ms(), utc_offset_minutes);
+    let (start, _) = day_bounds(last_day - i64::from(day_count) + 1, utc_offset_minutes);
+    let (_, end) = day_bounds(last_day, utc_offset_minutes);
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let runs = load_run_usage(&connection, &camp_id, start, end)
+        .map_err(|err| format!("Unable to load run usage: {err}"))?;
+    drop(connection);
+    let messages = read_transcript(&camp_transcript_path(&camp_dir))?;
+
+    let days = bucket_activity(&messages, &runs, last_day, day_count, utc_offset_minutes);
+    let busiest_day_message_count = days.iter().map(|day| day.message_count).max().unwrap_or(0);
+    Ok(CampActivityTimeline {
+        camp_id,
+        days,
+        busiest_day_message_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR_MS: i64 = 3_600_000;
+
+    #[test]
+    fn bucket_activity_should_count_roles_and_tokens_per_local_day() {
+        let message = |role: &str, created_at: i64| {
+            CampMessage::for_test(&format!("{role}-{created_at}"), role, "hi")
+                .with_created_at(created_at)
+        };
+        // Day 19_000 is 2022-01-08 (UTC).
+        let day_start = 19_000 * 24 * HOUR_MS;
+        let messages = vec![
+            message("user", day_start + HOUR_MS),
+            message("assistant", day_start + 2 * HOUR_MS),
+            // 23:30 UTC is already the next day two hours east of UTC.
+            message("user", day_start + 23 * HOUR_MS + HOUR_MS / 2),
+            message("user", day_start - 40 * 24 * HOUR_MS),
+        ];
+        let runs = vec![RunUsage {
+            timestamp: day_start + 2 * HOUR_MS,
+            prompt_tokens: Some(120),
+            completion_tokens: Some(30),
+            total_tokens: None,
+        }];
+
+        let days = bucket_activity(&messages, &runs, 19_001, 3, 120);
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[1].date, "2022-01-08");
+        assert_eq!(days[1].message_count, 2);
+        assert_eq!(days[1].messages_by_role["assistant"], 1);
+        assert_eq!(days[1].run_count, 1);
+        assert_eq!(days[1].total_tokens, 150);
+        assert_eq!(days[2].message_count, 1);
+        assert_eq!(
+            days[0],
+            ActivityDay {
+                date: "2022-01-07".to_string(),
+                ..ActivityDay::default()
+            }
+        );
+    }
+
+    #[test]
+    fn rename_camp_runs_should_move_usage_to_the_new_id() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        crate::migrations::run_migrations(&connection, false).expect("schema should be created");
+        for (id, camp_id) in [("run-1", "old-camp"), ("run-2", "other-camp")] {
+            connection
+                .execute(
+                    "INSERT INTO runs (
+                       id, timestamp, model, requested_model, system_prompt, user_prompt,
+                       temperature, max_tokens, request_json, response_json, output_text,
+                       latency_ms, total_tokens, camp_id
+                     ) VALUES (?1, 10, 'm', 'm', '', 'hi', 0.2, 64, '{}', '{}', '', 1, 5, ?2)",
+                    params![id, camp_id],
+                )
+                .expect("run should insert");
+        }
+
+        rename_camp_runs(&connection, "old-camp", "new-camp").expect("rename should apply");
+
+        let usage = |camp_id: &str| {
+            load_run_usage(&connection, camp_id, 0, 100).expect("usage should load")
+        };
+        assert!(usage("old-camp").is_empty());
+        assert_eq!(usage("new-camp").len(), 1);
+        assert_eq!(usage("new-camp")[0].total_tokens, Some(5));
+        assert_eq!(usage("other-camp").len(), 1);
+    }
+}
@@ -0,0 +1,218 @@
+//! Orderings for the camp list. `updated_at` moves whenever a camp is written,
+//! which is not the same as the camp someone last looked at, so opens are
+//! tracked separately in the `camp_opens` table. That table is local to this
+//! machine, unlike `camp.json`, so browsing a camp never shows up in sync.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::Deserialize;
+use tauri::{State, Window};
+
+use crate::{
+    ensure_camps_root, ensure_main_window, now_timestamp_ms, read_camp_config,
+    resolve_existing_camp_dir, write_camp_config, AppState, CampSummary,
+};
+
+pub fn create_camp_opens_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS camp_opens (
+            camp_id TEXT PRIMARY KEY,
+            last_opened_at INTEGER NOT NULL,
+            open_count INTEGER NOT NULL DEFAULT 0
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// How `camp_list` orders camps. Pinned camps stay on top in every order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CampListSort {
+    /// Most recently written first.
+    #[default]
+    Updated,
+    /// Most recently opened first; camps never opened follow, by `updated_at`.
+    Recent,
+    /// By name, ignoring case.
+    Alphabetical,
+    /// Favorites, then everything else, each most recently opened first.
+    FavoritesFirst,
+}
+
+pub(crate) fn record_camp_open(
+    connection: &Connection,
+    camp_id: &str,
+    opened_at: i64,
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "INSERT INTO camp_opens (camp_id, last_opened_at, open_count) VALUES (?1, ?2, 1)
+         ON CONFLICT(camp_id) DO UPDATE SET
+            last_opened_at = excluded.last_opened_at,
+            open_count = open_count + 1",
+        params![camp_id, opened_at],
+    )?;
+    Ok(())
+}
+
+/// Keeps a camp's history when its folder, and so its id, is renamed.
+pub(crate) fn rename_camp_opens(
+    connection: &Connection,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), rusqlite::Error> {
+    if from_id != to_id {
+        connection.execute(
+            "UPDATE OR REPLACE camp_opens SET camp_id = ?2 WHERE camp_id = ?1",
+            params![from_id, to_id],
+        )?;
+    }
+    Ok(())
+}
+
+pub(crate) fn forget_camp_opens(
+    connection: &Connection,
+    camp_id: &str,
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "DELETE FROM camp_opens WHERE camp_id = ?1",
+        params![camp_id],
+    )?;
+    Ok(())
+}
+
+fn load_last_opened(connection: &Connection) -> Result<HashMap<String, i64>, rusqlite::Error> {
+    let mut statement = connection.prepare("SELECT camp_id, last_opened_at FROM camp_opens")?;
+    let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+fn by_recent_open(left: &CampSummary, right: &CampSummary) -> Ordering {
+    match (left.last_opened_at, right.last_opened_at) {
+        (Some(left_opened), Some(right_opened)) => right_opened.cmp(&left_opened),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => right.updated_at.cmp(&left.updated_at),
+    }
+}
+
+fn sort_camps(camps: &mut [CampSummary], sort: CampListSort) {
+    camps.sort_by(|left, right| {
+        let pinned = right.pinned.cmp(&left.pinned);
+        pinned.then_with(|| match sort {
+            CampListSort::Updated => right.updated_at.cmp(&left.updated_at),
+            CampListSort::Recent => by_recent_open(left, right),
+            CampListSort::Alphabetical => left
+                .name
+                .to_lowercase()
+                .cmp(&right.name.to_lowercase())
+                .then_with(|| left.id.cmp(&right.id)),
+            CampListSort::FavoritesFirst => right
+                .favorite
+                .cmp(&left.favorite)
+                .then_with(|| by_recent_open(left, right)),
+        })
+    });
+}
+
+/// Fills in `last_opened_at` and orders `camps` for `camp_list`.
+pub(crate) fn rank_camps(
+    connection: &Connection,
+    camps: &mut [CampSummary],
+    sort: CampListSort,
+) -> Result<(), String> {
+    let last_opened = load_last_opened(connection)
+        .map_err(|err| format!("Unable to load recently opened camps: {err}"))?;
+    for camp in camps.iter_mut() {
+        camp.last_opened_at = last_opened.get(&camp.id).copied();
+    }
+    sort_camps(camps, sort);
+    Ok(())
+}
+
+/// Records that the user opened a camp and returns the timestamp stored.
+#[tauri::command]
+pub fn camp_mark_opened(state: State<'_, AppState>, camp_id: String) -> Result<i64, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let opened_at = now_timestamp_ms();
+    record_camp_open(&connection, &camp_id, opened_at)
+        .map_err(|err| format!("Unable to record camp open: {err}"))?;
+    Ok(opened_at)
+}
+
+/// Stars or unstars a camp without touching its `updated_at`.
+#[tauri::command]
+pub fn camp_set_favorite(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    favorite: bool,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let mut config = read_camp_config(&camp_dir)?;
+    config.favorite = favorite;
+    write_camp_config(&camp_dir, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_camps_should_order_by_opens_names_and_favorites() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        create_camp_opens_table(&connection).expect("table should be created");
+        let camp = |id: &str, name: &str, updated_at: i64, favorite: bool| CampSummary {
+            id: id.to_string(),
+            name: name.to_string(),
+            model: "openrouter/auto".to_string(),
+            description: None,
+            color: None,
+            emoji: None,
+            pinned: false,
+            favorite,
+            tags: Vec::new(),
+            project_id: None,
+            last_opened_at: None,
+            updated_at,
+            path: format!("/camps/{id}"),
+        };
+        let mut camps = vec![
+            camp("a", "beta", 300, false),
+            camp("b", "Alpha", 100, true),
+            camp("c", "gamma", 200, false),
+            camp("d", "delta", 50, false),
+        ];
+        record_camp_open(&connection, "b", 10).expect("open should record");
+        record_camp_open(&connection, "old-d", 20).expect("open should record");
+        rename_camp_opens(&connection, "old-d", "d").expect("rename should apply");
+        let ids = |camps: &[CampSummary]| -> Vec<String> {
+            camps.iter().map(|camp| camp.id.clone()).collect()
+        };
+
+        rank_camps(&connection, &mut camps, CampListSort::Recent).expect("ranks");
+        assert_eq!(ids(&camps), vec!["d", "b", "a", "c"]);
+        assert_eq!(camps[0].last_opened_at, Some(20));
+
+        rank_camps(&connection, &mut camps, CampListSort::Alphabetical).expect("ranks");
+        assert_eq!(ids(&camps), vec!["b", "a", "d", "c"]);
+
+        camps[3].pinned = true;
+        rank_camps(&connection, &mut camps, CampListSort::FavoritesFirst).expect("ranks");
+        assert_eq!(ids(&camps), vec!["c", "b", "d", "a"]);
+    }
+}
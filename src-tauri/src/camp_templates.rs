@@ -0,0 +1,188 @@
+//! Bundled starter camps. A template pre-fills the system prompt, memory and
+//! its schema, a few starting artifacts and the tool settings, so a new camp
+//! is useful before the first message.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::{
+    create_artifact, ensure_artifacts_index, ensure_camps_root, ensure_main_window,
+    load_camp_from_dir, memory_schema, normalize_artifact_tags, normalize_camp_color,
+    read_camp_config, scaffold_camp, set_artifact_always_include, write_artifacts_index,
+    write_camp_config, AppState, ArtifactSource, Camp, CampCreatePayload,
+};
+
+const CAMP_TEMPLATES_JSON: &str = include_str!("camp_templates.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampTemplateArtifact {
+    pub title: String,
+    pub body: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub always_include: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub emoji: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub tools_enabled: bool,
+    #[serde(default)]
+    pub auto_extract_code_blocks: bool,
+    pub system_prompt: String,
+    #[serde(default)]
+    pub memory: Value,
+    #[serde(default)]
+    pub memory_schema: Option<Value>,
+    #[serde(default)]
+    pub artifacts: Vec<CampTemplateArtifact>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampCreateFromTemplatePayload {
+    pub template_id: String,
+    pub model: String,
+    /// Defaults to the template's name.
+    pub name: Option<String>,
+}
+
+fn load_camp_templates() -> CommandResult<Vec<CampTemplate>> {
+    serde_json::from_str(CAMP_TEMPLATES_JSON).map_err(|err| {
+        BasecampError::internal(format!("Bundled camp templates are invalid: {err}"))
+    })
+}
+
+/// Applies the parts of a template `scaffold_camp` doesn't cover: metadata,
+/// tool settings, the memory schema and the starting artifacts.
+fn apply_template(camp_dir: &Path, template: &CampTemplate) -> Result<(), String> {
+    let mut config = read_camp_config(camp_dir)?;
+    config.description = Some(template.description.clone());
+    config.emoji = template.emoji.clone();
+    config.color = template.color.as_deref().and_then(normalize_camp_color);
+    config.tags = normalize_artifact_tags(&template.tags);
+    config.tools_enabled = template.tools_enabled;
+    config.auto_extract_code_blocks = template.auto_extract_code_blocks;
+    config.memory_schema = template.memory_schema.clone();
+    write_camp_config(camp_dir, &config)?;
+
+    let mut pinned = Vec::new();
+    for artifact in &template.artifacts {
+        let created = create_artifact(
+            camp_dir,
+            ArtifactSource {
+                message_id: String::new(),
+                role: "user".to_string(),
+                path: None,
+            },
+            &artifact.title,
+            &artifact.body,
+            &artifact.tags,
+        )?;
+        if artifact.always_include {
+            pinned.push(created.metadata.id);
+        }
+    }
+    if !pinned.is_empty() {
+        let mut index = ensure_artifacts_index(camp_dir)?;
+        for artifact_id in &pinned {
+            set_artifact_always_include(&mut index, artifact_id, true)?;
+        }
+        write_artifacts_index(camp_dir, &index)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_camp_templates() -> CommandResult<Vec<CampTemplate>> {
+    load_camp_templates()
+}
+
+/// Creates a new camp from a bundled template, on `model`.
+#[tauri::command]
+pub fn camp_create_from_template(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampCreateFromTemplatePayload,
+) -> CommandResult<Camp> {
+    ensure_main_window(&window)?;
+    let template = load_camp_templates()?
+        .into_iter()
+        .find(|template| template.id == payload.template_id)
+        .ok_or_else(|| {
+            BasecampError::not_found(format!("No camp template `{}`", payload.template_id))
+                .with_context("template_id", payload.template_id.clone())
+        })?;
+
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&template.name)
+        .to_string();
+    let camp_dir = scaffold_camp(
+        &camps_root,
+        CampCreatePayload {
+            name,
+            model: payload.model,
+            system_prompt: template.system_prompt.clone(),
+            memory: Some(template.memory.clone()),
+            tools_enabled: Some(template.tools_enabled),
+        },
+    )?;
+    // Remove the half-made camp rather than leave one the user never asked for.
+    let camp = apply_template(&camp_dir, &template).and_then(|()| load_camp_from_dir(&camp_dir));
+    if camp.is_err() {
+        let _ = fs::remove_dir_all(&camp_dir);
+    }
+    Ok(camp?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn bundled_camp_templates_should_satisfy_their_own_memory_schemas() {
+        let templates = load_camp_templates().expect("templates should parse");
+        let ids: BTreeSet<&str> = templates
+            .iter()
+            .map(|template| template.id.as_str())
+            .collect();
+        assert_eq!(ids.len(), templates.len());
+
+        for template in &templates {
+            assert!(!template.system_prompt.trim().is_empty());
+            assert!(template.memory.is_object());
+            if let Some(color) = &template.color {
+                assert!(normalize_camp_color(color).is_some());
+            }
+            if let Some(schema) = &template.memory_schema {
+                memory_schema::validate_memory_schema(schema).expect("schema should be valid");
+                assert!(
+                    memory_schema::memory_schema_violations(schema, &template.memory).is_empty(),
+                    "{} memory should match its schema",
+                    template.id
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,598 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::{AppHandle, State};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::{
+    normalized_message_content, BasecampChatRequest, ChatStreamEvent, ProviderChatResponse,
+    ProviderUsage,
+};
+use crate::{
+    append_transcript_message, camp_transcript_path, compose_model_reference, dispatch_chat,
+    ensure_camps_root, get_developer_inspect_mode_db, get_setting_value, inspect, now_timestamp_ms,
+    post_processing, resolve_existing_camp_dir, set_setting_value, store_run,
+    touch_camp_updated_at, AppState, CampMessage, CampToolCall, RunInsertPayload,
+};
+
+/// How often buffered deltas are forwarded to the webview. Every channel send
+/// is a webview eval, so a fast local model would otherwise queue one per token.
+const STREAM_FLUSH_INTERVAL: Duration = Duration::from_millis(32);
+
+/// Follow-up requests allowed when a reply stops at `finish_reason: length`.
+/// Zero, the default, turns continuation off.
+const SETTING_MAX_CONTINUATIONS: &str = "max_continuations";
+const MAX_CONTINUATIONS_LIMIT: u8 = 5;
+const CONTINUE_PROMPT: &str =
+    "Continue exactly where you left off. Do not repeat anything you already wrote.";
+
+#[derive(Debug, Deserialize)]
+pub struct SendChatStreamingPayload {
+    pub camp_id: String,
+    pub request: BasecampChatRequest,
+    /// Stored on the run row; defaults to the last user message.
+    #[serde(default)]
+    pub user_prompt: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SendChatStreamingResult {
+    pub run_id: String,
+    pub response: ProviderChatResponse,
+    /// The assistant message appended to the camp transcript.
+    pub message: CampMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inspect_bundle_path: Option<String>,
+    /// Follow-up requests stitched into `response` after truncated replies.
+    pub continuations: u8,
+}
+
+/// Adds `event` to the pending batch, folding consecutive deltas of the same
+/// stream into one event so their order is kept but their count is bounded.
+/// A progress event replaces one still waiting to be sent.
+fn coalesce_event(pending: &mut Vec<ChatStreamEvent>, event: ChatStreamEvent) {
+    if matches!(event, ChatStreamEvent::ChatProgress { .. })
+        && matches!(pending.last(), Some(ChatStreamEvent::ChatProgress { .. }))
+    {
+        pending.pop();
+    }
+    match (pending.last_mut(), event) {
+        (
+            Some(ChatStreamEvent::ChatDelta {
+                role,
+                content_delta,
+                ..
+            }),
+            ChatStreamEvent::ChatDelta {
+                role: next_role,
+                content_delta: next_delta,
+                ..
+            },
+        ) if *role == next_role => content_delta.push_str(&next_delta),
+        (
+            Some(ChatStreamEvent::ReasoningDelta {
+                reasoning_delta, ..
+            }),
+            ChatStreamEvent::ReasoningDelta {
+                reasoning_delta: next_delta,
+                ..
+            },
+        ) => reasoning_delta.push_str(&next_delta),
+        (
+            Some(ChatStreamEvent::ToolCallDelta {
+                tool_call_id,
+                arguments_delta,
+                ..
+            }),
+            ChatStreamEvent::ToolCallDelta {
+                tool_call_id: next_id,
+                name: None,
+                arguments_delta: next_delta,
+                ..
+            },
+        ) if *tool_call_id == next_id => arguments_delta.push_str(&next_delta),
+        (_, event) => pending.push(event),
+    }
+}
+
+fn is_terminal_event(event: &ChatStreamEvent) -> bool {
+    matches!(
+        event,
+        ChatStreamEvent::ChatComplete { .. } | ChatStreamEvent::ChatError { .. }
+    )
+}
+
+/// Sends everything buffered so far. The lock is held while sending so a flush
+/// from the ticker and one from a terminal event can't interleave.
+fn flush_pending(pending: &Mutex<Vec<ChatStreamEvent>>, downstream: &Channel<ChatStreamEvent>) {
+    let Ok(mut pending) = pending.lock() else {
+        return;
+    };
+    for event in pending.drain(..) {
+        let _ = downstream.send(event);
+    }
+}
+
+/// Sits between the provider and the webview channel, batching deltas and
+/// flushing them on a fixed interval. Completion and error events flush
+/// immediately.
+struct CoalescingRelay {
+    upstream: Channel<ChatStreamEvent>,
+    pending: Arc<Mutex<Vec<ChatStreamEvent>>>,
+    downstream: Channel<ChatStreamEvent>,
+    ticker: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl CoalescingRelay {
+    fn new(downstream: Channel<ChatStreamEvent>) -> Self {
+        let pending = Arc::new(Mutex::new(Vec::new()));
+
+        let upstream = {
+            let pending = pending.clone();
+            let downstream = downstream.clone();
+            Channel::new(move |body: InvokeResponseBody| {
+                let event: ChatStreamEvent = body.deserialize()?;
+                let terminal = is_terminal_event(&event);
+                if let Ok(mut pending) = pending.lock() {
+                    coalesce_event(&mut pending, event);
+                }
+                if terminal {
+                    flush_pending(&pending, &downstream);
+                }
+                Ok(())
+            })
+        };
+
+        let ticker = {
+            let pending = pending.clone();
+            let downstream = downstream.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval = tokio::time::interval(STREAM_FLUSH_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    flush_pending(&pending, &downstream);
+                }
+            })
+        };
+
+        Self {
+            upstream,
+            pending,
+            downstream,
+            ticker,
+        }
+    }
+
+    fn finish(self) {
+        self.ticker.abort();
+        flush_pending(&self.pending, &self.downstream);
+    }
+}
+
+pub(crate) fn load_max_continuations(connection: &rusqlite::Connection) -> Result<u8, String> {
+    let value = get_setting_value(connection, SETTING_MAX_CONTINUATIONS)
+        .map_err(|err| format!("Failed to read max_continuations: {err}"))?;
+    Ok(value
+        .and_then(|value| value.parse::<u8>().ok())
+        .unwrap_or(0)
+        .min(MAX_CONTINUATIONS_LIMIT))
+}
+
+/// A reply that ran out of tokens mid-text. Truncated tool calls aren't
+/// continued; their arguments can't be stitched back into valid JSON.
+fn needs_continuation(response: &ProviderChatResponse) -> bool {
+    response.finish_reason.as_deref() == Some("length")
+        && response.assistant_message.tool_calls.is_empty()
+}
+
+/// The original request plus the text so far and a prompt to keep going.
+fn continuation_request(
+    request: &BasecampChatRequest,
+    stitched: &ProviderChatResponse,
+) -> BasecampChatRequest {
+    let mut next = request.clone();
+    next.messages.push(json!({
+        "role": "assistant",
+        "content": stitched.output_text,
+    }));
+    next.messages
+        .push(json!({ "role": "user", "content": CONTINUE_PROMPT }));
+    next
+}
+
+fn add_tokens(left: Option<i64>, right: Option<i64>) -> Option<i64> {
+    match (left, right) {
+        (None, None) => None,
+        (left, right) => Some(left.unwrap_or(0) + right.unwrap_or(0)),
+    }
+}
+
+/// Appends a continuation's output to the stitched response and adds its
+/// usage, so the turn reads and bills as a single reply.
+fn stitch_continuation(stitched: &mut ProviderChatResponse, next: ProviderChatResponse) {
+    stitched.output_text.push_str(&next.output_text);
+    stitched.assistant_message.content = Some(Value::String(stitched.output_text.clone()));
+    stitched.assistant_message.tool_calls = next.assistant_message.tool_calls;
+    stitched.assistant_message.reasoning = match (
+        stitched.assistant_message.reasoning.take(),
+        next.assistant_message.reasoning,
+    ) {
+        (Some(first), Some(second)) => Some(format!("{first}{second}")),
+        (first, second) => first.or(second),
+    };
+    stitched.usage = ProviderUsage {
+        prompt_tokens: add_tokens(stitched.usage.prompt_tokens, next.usage.prompt_tokens),
+        completion_tokens: add_tokens(
+            stitched.usage.completion_tokens,
+            next.usage.completion_tokens,
+        ),
+        total_tokens: add_tokens(stitched.usage.total_tokens, next.usage.total_tokens),
+    };
+    stitched.finish_reason = next.finish_reason;
+    stitched.resolved_model = next.resolved_model.or(stitched.resolved_model.take());
+    stitched.status = next.status;
+    stitched.duration_ms += next.duration_ms;
+    stitched.stream_chunk_count += next.stream_chunk_count;
+    stitched.response_payload = next.response_payload;
+    stitched.sanitized_response_payload = next.sanitized_response_payload;
+}
+
+/// Streams one turn through a coalescing relay, continuing a truncated reply
+/// up to `max_continuations` times. Returns the stitched outcome and how many
+/// follow-ups were sent.
+pub(crate) async fn stream_chat_turn(
+    state: &AppState,
+    request: &BasecampChatRequest,
+    on_event: Channel<ChatStreamEvent>,
+    max_continuations: u8,
+) -> (CommandResult<ProviderChatResponse>, u8) {
+    let relay = CoalescingRelay::new(on_event);
+    let mut outcome = dispatch_chat(state, request.clone(), &relay.upstream).await;
+    let mut continuations = 0;
+    if let Ok(stitched) = outcome.as_mut() {
+        while continuations < max_continuations && needs_continuation(stitched) {
+            let next_request = continuation_request(request, stitched);
+            continuations += 1;
+            // A failed follow-up keeps what was already streamed.
+            match dispatch_chat(state, next_request, &relay.upstream).await {
+                Ok(next) => stitch_continuation(stitched, next),
+                Err(_) => break,
+            }
+        }
+    }
+    relay.finish();
+    (outcome, continuations)
+}
+
+fn message_text(messages: &[Value], role: &str, last: bool) -> Option<String> {
+    let mut matching = messages
+        .iter()
+        .filter(|message| message.get("role").and_then(Value::as_str) == Some(role));
+    let message = if last {
+        matching.last()
+    } else {
+        matching.next()
+    }?;
+    Some(normalized_message_content(message.get("content")?))
+}
+
+pub(crate) fn run_payload(
+    run_id: &str,
+    camp_id: &str,
+    request: &BasecampChatRequest,
+    user_prompt: Option<String>,
+    outcome: Result<&ProviderChatResponse, &BasecampError>,
+) -> RunInsertPayload {
+    let model = compose_model_reference(request.provider_kind, &request.model_id);
+    let request_json = match outcome {
+        Ok(response) => response.sanitized_request_payload.to_string(),
+        Err(_) => serde_json::to_string(request).unwrap_or_default(),
+    };
+    let (response_json, output_text, latency_ms, usage, error) = match outcome {
+        Ok(response) => (
+            response.sanitized_response_payload.to_string(),
+            response.output_text.clone(),
+            response.duration_ms,
+            response.usage.clone(),
+            None,
+        ),
+        Err(err) => (
+            serde_json::to_string(err).unwrap_or_default(),
+            String::new(),
+            0,
+            Default::default(),
+            Some(err.message.clone()),
+        ),
+    };
+
+    RunInsertPayload {
+        id: run_id.to_string(),
+        timestamp: now_timestamp_ms(),
+        model: model.clone(),
+        requested_model: model,
+        resolved_model: outcome
+            .ok()
+            .and_then(|response| response.resolved_model.clone()),
+        system_prompt: message_text(&request.messages, "system", false).unwrap_or_default(),
+        user_prompt: user_prompt
+            .or_else(|| message_text(&request.messages, "user", true))
+            .unwrap_or_default(),
+        temperature: request.temperature.unwrap_or_default(),
+        max_tokens: request.max_tokens.unwrap_or_default(),
+        request_json,
+        response_json,
+        output_text,
+        latency_ms,
+        prompt_tokens: usage.prompt_tokens,
+        completion_tokens: usage.completion_tokens,
+        total_tokens: usage.total_tokens,
+        error,
+        rating: None,
+        tags: None,
+        generation_id: None,
+        camp_id: Some(camp_id.to_string()),
+        scrub_audit_json: outcome
+            .ok()
+            .and_then(|response| response.scrub_audit.as_ref())
+            .and_then(|audit| serde_json::to_string(audit).ok()),
+    }
+}
+
+/// The reply as a transcript message, linked to the run stored for it.
+pub(crate) fn assistant_message(response: &ProviderChatResponse, run_id: &str) -> CampMessage {
+    let assistant = &response.assistant_message;
+    let tool_calls: Vec<CampToolCall> = assistant
+        .tool_calls
+        .iter()
+        .filter_map(|call| serde_json::from_value(call.clone()).ok())
+        .collect();
+    CampMessage {
+        id: uuid::Uuid::new_v4().to_string(),
+        role: "assistant".to_string(),
+        content: assistant
+            .content
+            .as_ref()
+            .map(normalized_message_content)
+            .unwrap_or_else(|| response.output_text.clone()),
+        created_at: now_timestamp_ms(),
+        name: None,
+        tool_call_id: None,
+        tool_calls: (!tool_calls.is_empty()).then_some(tool_calls),
+        included_artifact_ids: None,
+        attachments: None,
+        reasoning: assistant
+            .reasoning
+            .clone()
+            .filter(|reasoning| !reasoning.trim().is_empty()),
+        run_id: Some(run_id.to_string()),
+    }
+}
+
+/// Writes the request, response and bundle turn files the inspect panel reads.
+fn write_inspect_files(
+    camp_dir: &std::path::Path,
+    correlation_id: &str,
+    response: &ProviderChatResponse,
+) -> Result<PathBuf, String> {
+    let requests = json!([response.sanitized_request_payload]);
+    let responses = json!([{
+        "provider_kind": response.provider_kind,
+        "base_url": response.base_url,
+        "status_code": response.status,
+        "duration_ms": response.duration_ms,
+        "headers": response.response_headers,
+        "body": response.sanitized_response_payload,
+        "stream": response.stream_chunk_count > 0,
+        "stream_chunk_count": response.stream_chunk_count,
+    }]);
+    inspect::write_turn_request_file(
+        camp_dir,
+        correlation_id,
+        &json!({ "correlation_id": correlation_id, "requests": requests }),
+    )?;
+    inspect::write_turn_response_file(
+        camp_dir,
+        correlation_id,
+        &json!({ "correlation_id": correlation_id, "responses": responses }),
+    )?;
+    inspect::write_turn_bundle_file(
+        camp_dir,
+        correlation_id,
+        &json!({
+            "correlation_id": correlation_id,
+            "openrouter_request_json": { "requests": requests },
+            "openrouter_response_json": { "responses": responses },
+            "event_timeline": [],
+            "files_written": [],
+        }),
+    )
+}
+
+/// Sends one camp chat turn and does the bookkeeping around it server-side:
+/// the run row is stored (failed turns included), inspect files are written
+/// when developer inspect mode is on, and the assistant reply is appended to
+/// the transcript. Stream events reach `on_event` in coalesced batches. When
+/// continuation is on, replies cut off at the token limit are continued and
+/// stitched into one message.
+#[tauri::command]
+pub async fn send_chat_streaming(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: SendChatStreamingPayload,
+    on_event: Channel<ChatStreamEvent>,
+) -> CommandResult<SendChatStreamingResult> {
+    let SendChatStreamingPayload {
+        camp_id,
+        mut request,
+        user_prompt,
+    } = payload;
+    request.metadata.camp_id = Some(camp_id.clone());
+    let correlation_id = request
+        .metadata
+        .correlation_id
+        .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+        .clone();
+
+    let (camp_dir, inspect_enabled, max_continuations) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+        (
+            camp_dir,
+            get_developer_inspect_mode_db(&connection)?,
+            load_max_continuations(&connection)?,
+        )
+    };
+
+    let (outcome, continuations) =
+        stream_chat_turn(&state, &request, on_event, max_continuations).await;
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    store_run(
+        &app,
+        &state,
+        run_payload(&run_id, &camp_id, &request, user_prompt, outcome.as_ref()),
+    )?;
+    let response = outcome?;
+
+    let inspect_bundle_path = if inspect_enabled {
+        let path = write_inspect_files(&camp_dir, &correlation_id, &response)?;
+        Some(path.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    let mut message = assistant_message(&response, &run_id);
+    post_processing::apply_to_assistant_message(&camp_dir, &mut message)?;
+    {
+        // Regenerate and council splice the transcript under this lock.
+        let _connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        append_transcript_message(&camp_transcript_path(&camp_dir), &message)?;
+    }
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(SendChatStreamingResult {
+        run_id,
+        response,
+        message,
+        inspect_bundle_path,
+        continuations,
+    })
+}
+
+#[tauri::command]
+pub fn set_max_continuations(state: State<'_, AppState>, value: u8) -> Result<u8, String> {
+    let clamped = value.min(MAX_CONTINUATIONS_LIMIT);
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(&connection, SETTING_MAX_CONTINUATIONS, &clamped.to_string())
+        .map_err(|err| format!("Failed to save max_continuations: {err}"))?;
+    Ok(clamped)
+}
+
+#[tauri::command]
+pub fn get_max_continuations(state: State<'_, AppState>) -> Result<u8, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_max_continuations(&connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta(text: &str) -> ChatStreamEvent {
+        ChatStreamEvent::ChatDelta {
+            correlation_id: "turn".to_string(),
+            role: "assistant".to_string(),
+            content_delta: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn coalesce_event_should_merge_runs_of_deltas_in_order() {
+        let mut pending = Vec::new();
+        coalesce_event(&mut pending, delta("Hel"));
+        coalesce_event(&mut pending, delta("lo"));
+        coalesce_event(
+            &mut pending,
+            ChatStreamEvent::ToolCallDelta {
+                correlation_id: "turn".to_string(),
+                tool_call_id: "call-1".to_string(),
+                name: Some("read_file".to_string()),
+                arguments_delta: "{\"pa".to_string(),
+            },
+        );
+        coalesce_event(
+            &mut pending,
+            ChatStreamEvent::ToolCallDelta {
+                correlation_id: "turn".to_string(),
+                tool_call_id: "call-1".to_string(),
+                name: None,
+                arguments_delta: "th\":1}".to_string(),
+            },
+        );
+        coalesce_event(&mut pending, delta("!"));
+
+        let events = serde_json::to_value(&pending).expect("events serialize");
+        assert_eq!(events.as_array().map(Vec::len), Some(3));
+        assert_eq!(events[0]["content_delta"], "Hello");
+        assert_eq!(events[1]["name"], "read_file");
+        assert_eq!(events[1]["arguments_delta"], "{\"path\":1}");
+        assert_eq!(events[2]["content_delta"], "!");
+        assert!(!is_terminal_event(&pending[2]));
+    }
+
+    fn truncated_response(text: &str, completion_tokens: i64) -> ProviderChatResponse {
+        ProviderChatResponse {
+            provider_kind: crate::providers::ProviderKind::Openrouter,
+            base_url: String::new(),
+            response_payload: Value::Null,
+            output_text: text.to_string(),
+            assistant_message: Default::default(),
+            usage: ProviderUsage {
+                prompt_tokens: Some(10),
+                completion_tokens: Some(completion_tokens),
+                total_tokens: None,
+            },
+            resolved_model: None,
+            finish_reason: Some("length".to_string()),
+            status: 200,
+            duration_ms: 100,
+            response_headers: Default::default(),
+            stream_chunk_count: 0,
+            sanitized_request_payload: Value::Null,
+            sanitized_response_payload: Value::Null,
+            scrub_audit: None,
+        }
+    }
+
+    #[test]
+    fn stitch_continuation_should_join_text_and_sum_usage() {
+        let mut stitched = truncated_response("The quick brown", 3);
+        assert!(needs_continuation(&stitched));
+
+        let mut next = truncated_response(" fox.", 2);
+        next.finish_reason = Some("stop".to_string());
+        stitch_continuation(&mut stitched, next);
+
+        assert_eq!(stitched.output_text, "The quick brown fox.");
+        assert_eq!(
+            stitched.assistant_message.content,
+            Some(Value::String("The quick brown fox.".to_string()))
+        );
+        assert_eq!(stitched.usage.prompt_tokens, Some(20));
+        assert_eq!(stitched.usage.completion_tokens, Some(5));
+        assert_eq!(stitched.usage.total_tokens, None);
+        assert_eq!(stitched.duration_ms, 200);
+        assert!(!needs_continuation(&stitched));
+    }
+}
@@ -0,0 +1,202 @@
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+use super::team::run_chat_completion;
+use crate::{
+    camp_transcript_path, ensure_camps_root, ensure_main_window, get_setting_value,
+    now_timestamp_ms, read_camp_config, read_transcript, resolve_existing_camp_dir,
+    set_setting_value, write_camp_config, AppState, CampMessage, DEFAULT_CAMP_NAME,
+};
+
+/// Model used for titles when the caller doesn't name one. Unset means camps
+/// are not titled automatically.
+const SETTING_TITLE_MODEL: &str = "title_model";
+const TITLE_SOURCE_MESSAGES: usize = 6;
+const TITLE_SOURCE_EXCERPT_CHARS: usize = 500;
+const MAX_TITLE_CHARS: usize = 60;
+
+const TITLE_INSTRUCTIONS: &str = "Name this conversation in 2 to 6 words, like a document title. Reply with the title only: no quotes, no trailing punctuation.";
+
+#[derive(Debug, Deserialize)]
+pub struct CampSuggestTitlePayload {
+    pub camp_id: String,
+    /// Defaults to the title model setting, then the camp's own model.
+    pub model: Option<String>,
+    /// Renames the camp when it still has the default name.
+    #[serde(default)]
+    pub apply: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CampTitleSuggestion {
+    pub title: String,
+    pub model: String,
+    pub applied: bool,
+}
+
+pub(super) fn load_title_model(connection: &Connection) -> Result<Option<String>, String> {
+    let value = get_setting_value(connection, SETTING_TITLE_MODEL)
+        .map_err(|err| format!("Failed to read title_model: {err}"))?;
+    Ok(value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty()))
+}
+
+/// The opening user and assistant messages, shortened, as a plain dialogue.
+fn render_title_source(transcript: &[CampMessage]) -> String {
+    transcript
+        .iter()
+        .filter(|message| matches!(message.role.as_str(), "user" | "assistant"))
+        .filter(|message| !message.content.trim().is_empty())
+        .take(TITLE_SOURCE_MESSAGES)
+        .map(|message| {
+            let content: String = message
+                .content
+                .trim()
+                .chars()
+                .take(TITLE_SOURCE_EXCERPT_CHARS)
+                .collect();
+            format!("{}: {content}", message.role)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// First line of the model's reply with quotes, a "Title:" label and trailing
+/// punctuation removed, cut to `MAX_TITLE_CHARS`.
+fn clean_title(raw: &str) -> Option<String> {
+    let line = raw.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line
+        .strip_prefix("Title:")
+        .or_else(|| line.strip_prefix("title:"))
+        .unwrap_or(line);
+    let title = line
+        .trim()
+        .trim_matches(|ch: char| matches!(ch, '"' | '\'' | '`' | '*' | '#'))
+        .trim_end_matches(['.', '!', ':', ';', ','])
+        .trim();
+    if title.is_empty() {
+        return None;
+    }
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return Some(title.to_string());
+    }
+    let shortened: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    Some(match shortened.rfind(' ') {
+        Some(cut) if cut > 0 => shortened[..cut].to_string(),
+        _ => shortened,
+    })
+}
+
+/// Suggests a short name from the start of the transcript. With `apply`, the
+/// camp is renamed only if it is still called "Untitled Camp", so a name the
+/// user picked is never replaced.
+#[tauri::command]
+pub async fn camp_suggest_title(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampSuggestTitlePayload,
+) -> Result<CampTitleSuggestion, String> {
+    ensure_main_window(&window)?;
+    let (camp_dir, camp_model, title_model, source) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let config = read_camp_config(&camp_dir)?;
+        let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+        (
+            camp_dir,
+            config.model,
+            load_title_model(&connection)?,
+            render_title_source(&transcript),
+        )
+    };
+    if source.is_empty() {
+        return Err("The camp has no messages to title yet.".to_string());
+    }
+
+    let model = payload
+        .model
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or(title_model)
+        .unwrap_or(camp_model);
+    let response = run_chat_completion(
+        state.inner(),
+        &model,
+        vec![
+            serde_json::json!({ "role": "system", "content": TITLE_INSTRUCTIONS }),
+            serde_json::json!({ "role": "user", "content": source }),
+        ],
+        None,
+        Some(&payload.camp_id),
+    )
+    .await?;
+    let title = clean_title(&response.output_text)
+        .ok_or_else(|| "Model returned an empty title.".to_string())?;
+
+    let mut applied = false;
+    if payload.apply {
+        let mut config = read_camp_config(&camp_dir)?;
+        if config.name == DEFAULT_CAMP_NAME {
+            config.name = title.clone();
+            config.updated_at = now_timestamp_ms();
+            write_camp_config(&camp_dir, &config)?;
+            applied = true;
+        }
+    }
+
+    Ok(CampTitleSuggestion {
+        title,
+        model,
+        applied,
+    })
+}
+
+#[tauri::command]
+pub fn set_title_model(state: State<'_, AppState>, model: Option<String>) -> Result<(), String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let value = model
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+    set_setting_value(&connection, SETTING_TITLE_MODEL, &value)
+        .map_err(|err| format!("Failed to save title_model: {err}"))
+}
+
+#[tauri::command]
+pub fn get_title_model(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_title_model(&connection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_title_should_strip_labels_quotes_and_punctuation() {
+        assert_eq!(
+            clean_title("Title: \"Trip planning for Lisbon.\"\n\nExtra").as_deref(),
+            Some("Trip planning for Lisbon")
+        );
+        assert_eq!(
+            clean_title("  \n**Rust lifetimes**").as_deref(),
+            Some("Rust lifetimes")
+        );
+        assert_eq!(clean_title("\"\""), None);
+
+        let long = clean_title(&"word ".repeat(30)).expect("long title is cut");
+        assert!(long.chars().count() <= MAX_TITLE_CHARS);
+        assert!(!long.ends_with(' '));
+    }
+}
@@ -1 +1,5 @@
+pub mod camp_titles;
+pub mod prompt_assistant;
+pub mod prompt_suggestions;
 pub mod team;
+pub mod translation;
@@ -0,0 +1,273 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use super::team::{parse_json_from_output, run_chat_completion};
+use crate::{
+    camp_system_prompt_path, camp_transcript_path, ensure_camps_root, ensure_main_window,
+    read_camp_config, read_text_file, read_transcript, resolve_existing_camp_dir, AppState,
+    CampMessage,
+};
+
+const DEFAULT_MAX_FAILURES: usize = 5;
+const MAX_FAILURES_LIMIT: usize = 20;
+const FAILURE_EXCERPT_CHARS: usize = 600;
+const LOW_RATING_THRESHOLD: i64 = 2;
+
+const PROMPT_ENGINEERING_RUBRIC: &str = "You are a prompt engineer reviewing the system prompt of an assistant.
+Evaluate the prompt against this rubric:
+1. Role and goal: the assistant's purpose and audience are explicit.
+2. Constraints: hard rules are stated plainly and do not contradict each other.
+3. Output format: expected structure, length and tone are specified where they matter.
+4. Tool use: when and how to call tools, and how to recover from tool errors.
+5. Failure handling: what to do when information is missing or a request is out of scope.
+6. Economy: no redundant or vague instructions; every sentence changes behavior.
+
+Use the failures provided to find concrete gaps. Preserve intent and any domain details that still apply.
+Return ONLY valid JSON with this shape: {\"revised_prompt\": string, \"rationale\": string}.
+The rationale should list the changes you made and which rubric item or failure each one addresses.";
+
+#[derive(Debug, Deserialize)]
+pub struct ImproveSystemPromptPayload {
+    pub camp_id: String,
+    /// Model reference used for the review; defaults to the camp's own model.
+    pub model: Option<String>,
+    pub max_failures: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptFailureSource {
+    ToolError,
+    RunError,
+    LowRating,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptFailure {
+    pub source: PromptFailureSource,
+    pub reference_id: String,
+    pub excerpt: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SystemPromptSuggestion {
+    pub model: String,
+    pub current_prompt: String,
+    pub suggested_prompt: String,
+    pub rationale: String,
+    pub failures: Vec<PromptFailure>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptRevisionOutput {
+    revised_prompt: String,
+    #[serde(default)]
+    rationale: String,
+}
+
+fn excerpt(text: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.chars().count() <= FAILURE_EXCERPT_CHARS {
+        return trimmed.to_string();
+    }
+    let mut shortened: String = trimmed.chars().take(FAILURE_EXCERPT_CHARS).collect();
+    shortened.push('…');
+    shortened
+}
+
+/// Tool results are recorded as `{"error": "..."}` when a tool call fails.
+fn transcript_tool_failures(transcript: &[CampMessage], limit: usize) -> Vec<PromptFailure> {
+    let mut failures = Vec::new();
+    for (position, message) in transcript.iter().enumerate().rev() {
+        if failures.len() >= limit {
+            break;
+        }
+        if message.role != "tool" {
+            continue;
+        }
+        let Ok(Value::Object(result)) = serde_json::from_str::<Value>(&message.content) else {
+            continue;
+        };
+        let Some(error) = result.get("error").and_then(Value::as_str) else {
+            continue;
+        };
+
+        let request = transcript[..position]
+            .iter()
+            .rev()
+            .find(|candidate| candidate.role == "user")
+            .map(|candidate| candidate.content.as_str())
+            .unwrap_or_default();
+        let tool_name = message.name.as_deref().unwrap_or("tool");
+        failures.push(PromptFailure {
+            source: PromptFailureSource::ToolError,
+            reference_id: message.id.clone(),
+            excerpt: excerpt(&format!(
+                "User asked: {request}\nTool `{tool_name}` failed: {error}"
+            )),
+        });
+    }
+    failures
+}
+
+/// Runs are not tied to a camp, so only runs recorded with this exact system
+/// prompt are treated as evidence against it.
+fn run_failures(
+    connection: &Connection,
+    system_prompt: &str,
+    limit: usize,
+) -> Result<Vec<PromptFailure>, String> {
+    let mut statement = connection
+        .prepare(
+            "SELECT id, user_prompt, output_text, error, rating
+             FROM runs
+             WHERE system_prompt = ?1 AND (error IS NOT NULL OR rating <= ?2)
+             ORDER BY timestamp DESC
+             LIMIT ?3",
+        )
+        .map_err(|err| format!("Unable to query runs: {err}"))?;
+
+    let rows = statement
+        .query_map(
+            params![system_prompt, LOW_RATING_THRESHOLD, limit as i64],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<i64>>(4)?,
+                ))
+            },
+        )
+        .map_err(|err| format!("Unable to query runs: {err}"))?;
+
+    let mut failures = Vec::new();
+    for row in rows {
+        let (id, user_prompt, output_text, error, rating) =
+            row.map_err(|err| format!("Unable to read run row: {err}"))?;
+        let (source, detail) = match error {
+            Some(error) => (PromptFailureSource::RunError, format!("Error: {error}")),
+            None => (
+                PromptFailureSource::LowRating,
+                format!(
+                    "Rated {}/5. Response: {output_text}",
+                    rating.unwrap_or_default()
+                ),
+            ),
+        };
+        failures.push(PromptFailure {
+            source,
+            reference_id: id,
+            excerpt: excerpt(&format!("User asked: {user_prompt}\n{detail}")),
+        });
+    }
+    Ok(failures)
+}
+
+fn render_review_request(current_prompt: &str, failures: &[PromptFailure]) -> String {
+    let mut request = format!("Current system prompt:\n<<<\n{current_prompt}\n>>>\n\n");
+    if failures.is_empty() {
+        request
+            .push_str("No recent failures were recorded; review the prompt on the rubric alone.\n");
+    } else {
+        request.push_str("Recent failures:\n");
+        for (index, failure) in failures.iter().enumerate() {
+            request.push_str(&format!("\n[{}] {}\n", index + 1, failure.excerpt));
+        }
+    }
+    request
+}
+
+#[tauri::command]
+pub async fn improve_system_prompt(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: ImproveSystemPromptPayload,
+) -> Result<SystemPromptSuggestion, String> {
+    ensure_main_window(&window)?;
+    let max_failures = payload
+        .max_failures
+        .unwrap_or(DEFAULT_MAX_FAILURES)
+        .min(MAX_FAILURES_LIMIT);
+
+    let (camp_model, current_prompt, failures) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let config = read_camp_config(&camp_dir)?;
+        let current_prompt = read_text_file(&camp_system_prompt_path(&camp_dir))?;
+        let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+
+        let mut failures = transcript_tool_failures(&transcript, max_failures);
+        let remaining = max_failures.saturating_sub(failures.len());
+        if remaining > 0 {
+            failures.extend(run_failures(&connection, &current_prompt, remaining)?);
+        }
+        (config.model, current_prompt, failures)
+    };
+
+    let model = payload
+        .model
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or(camp_model);
+
+    let response = run_chat_completion(
+        state.inner(),
+        &model,
+        vec![
+            serde_json::json!({ "role": "system", "content": PROMPT_ENGINEERING_RUBRIC }),
+            serde_json::json!({
+                "role": "user",
+                "content": render_review_request(&current_prompt, &failures),
+            }),
+        ],
+        None,
+        Some(&payload.camp_id),
+    )
+    .await?;
+
+    let parsed: PromptRevisionOutput = parse_json_from_output(&response.output_text)?;
+    let suggested_prompt = parsed.revised_prompt.trim().to_string();
+    if suggested_prompt.is_empty() {
+        return Err("Model returned an empty prompt revision.".to_string());
+    }
+
+    Ok(SystemPromptSuggestion {
+        model,
+        current_prompt,
+        suggested_prompt,
+        rationale: parsed.rationale.trim().to_string(),
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transcript_tool_failures_should_pair_errors_with_the_triggering_request() {
+        let transcript = vec![
+            CampMessage::for_test("u1", "user", "Read notes.md"),
+            CampMessage::for_test("t1", "tool", r#"{"error":"File not found"}"#)
+                .with_name("read_file"),
+            CampMessage::for_test("u2", "user", "List files"),
+            CampMessage::for_test("t2", "tool", r#"{"files":["a.md"]}"#).with_name("list_files"),
+        ];
+
+        let failures = transcript_tool_failures(&transcript, 5);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].reference_id, "t1");
+        assert_eq!(
+            failures[0].excerpt,
+            "User asked: Read notes.md\nTool `read_file` failed: File not found"
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::fs;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{State, Window};
+
+use super::camp_titles::load_title_model;
+use super::team::{parse_json_from_output, run_chat_completion};
+use crate::scratchpad::without_scratchpad;
+use crate::{
+    camp_memory_path, camp_system_prompt_path, camp_transcript_path, ensure_artifacts_index,
+    ensure_camps_root, ensure_main_window, read_camp_config, read_json_file, read_text_file,
+    read_transcript, resolve_existing_camp_dir, AppState, CampMessage,
+};
+
+const MIN_SUGGESTIONS: usize = 3;
+const MAX_SUGGESTIONS: usize = 5;
+const RECENT_MESSAGES: usize = 6;
+const MESSAGE_EXCERPT_CHARS: usize = 400;
+const SYSTEM_PROMPT_EXCERPT_CHARS: usize = 600;
+const MEMORY_EXCERPT_CHARS: usize = 1_500;
+const MAX_LISTED_ARTIFACTS: usize = 10;
+const MAX_SUGGESTION_CHARS: usize = 200;
+
+const SUGGESTION_INSTRUCTIONS: &str = "You suggest what the user could ask next in this workspace. Read the camp summary and propose 3 to 5 short, specific prompts written in the user's voice, each one a single sentence that builds on the memory, artifacts or recent conversation. Do not repeat questions already answered. Return ONLY valid JSON: {\"prompts\": [string]}.";
+
+/// Suggestions by camp, kept until the transcript they were made from changes.
+static SUGGESTION_CACHE: Mutex<Vec<CachedSuggestions>> = Mutex::new(Vec::new());
+
+struct CachedSuggestions {
+    camp_id: String,
+    transcript_hash: String,
+    suggestions: PromptSuggestions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptSuggestions {
+    pub camp_id: String,
+    pub prompts: Vec<String>,
+    pub model: String,
+    /// Returned from the cache rather than generated for this call.
+    pub cached: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SuggestionReply {
+    prompts: Vec<String>,
+}
+
+fn excerpt(text: &str, max_chars: usize) -> String {
+    let trimmed = text.trim();
+    if trimmed.chars().count() <= max_chars {
+        return trimmed.to_string();
+    }
+    let mut shortened: String = trimmed.chars().take(max_chars).collect();
+    shortened.push('…');
+    shortened
+}
+
+/// Everything the model sees: the camp's purpose, memory (scratchpad notes
+/// left out), artifact titles and the last few messages.
+fn render_suggestion_source(
+    camp_name: &str,
+    system_prompt: &str,
+    memory: &Value,
+    artifact_titles: &[String],
+    transcript: &[CampMessage],
+) -> String {
+    let mut sections = vec![format!("Camp: {camp_name}")];
+    if !system_prompt.trim().is_empty() {
+        sections.push(format!(
+            "System prompt:\n{}",
+            excerpt(system_prompt, SYSTEM_PROMPT_EXCERPT_CHARS)
+        ));
+    }
+    let memory = without_scratchpad(memory);
+    let memory_is_empty = match &memory {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    };
+    if !memory_is_empty {
+        let rendered = serde_json::to_string_pretty(&memory).unwrap_or_default();
+        sections.push(format!(
+            "Memory:\n{}",
+            excerpt(&rendered, MEMORY_EXCERPT_CHARS)
+        ));
+    }
+    if !artifact_titles.is_empty() {
+        let listed = artifact_titles
+            .iter()
+            .map(|title| format!("- {title}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sections.push(format!("Artifacts:\n{listed}"));
+    }
+
+    let recent: Vec<&CampMessage> = transcript
+        .iter()
+        .filter(|message| matches!(message.role.as_str(), "user" | "assistant"))
+        .filter(|message| !message.content.trim().is_empty())
+        .collect();
+    let recent = &recent[recent.len().saturating_sub(RECENT_MESSAGES)..];
+    if recent.is_empty() {
+        sections.push("No messages yet; suggest ways to get started.".to_string());
+    } else {
+        let rendered = recent
+            .iter()
+            .map(|message| {
+                format!(
+                    "{}: {}",
+                    message.role,
+                    excerpt(&message.content, MESSAGE_EXCERPT_CHARS)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        sections.push(format!("Recent messages:\n{rendered}"));
+    }
+    sections.join("\n\n")
+}
+
+/// Removes a leading `-`, `*` or `1.`/`1)` list marker.
+fn strip_list_marker(line: &str) -> &str {
+    let line = line.trim();
+    if let Some(rest) = line.strip_prefix(['-', '*']) {
+        return rest;
+    }
+    let digits = line.len()
+        - line
+            .trim_start_matches(|ch: char| ch.is_ascii_digit())
+            .len();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(['.', ')']) {
+            return rest;
+        }
+    }
+    line
+}
+
+/// Reads `{"prompts": [...]}`, or failing that one prompt per line with any
+/// list markers removed. Duplicates and empty lines are dropped.
+fn parse_suggestions(raw: &str) -> Vec<String> {
+    let candidates = match parse_json_from_output::<SuggestionReply>(raw) {
+        Ok(reply) => reply.prompts,
+        Err(_) => raw
+            .lines()
+            .map(|line| strip_list_marker(line).to_string())
+            .collect(),
+    };
+    let mut prompts: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let cleaned = candidate
+            .trim()
+            .trim_matches(|ch: char| matches!(ch, '"' | '\'' | '`'))
+            .trim();
+        if cleaned.is_empty() || cleaned.starts_with('{') || cleaned.starts_with('}') {
+            continue;
+        }
+        let cleaned = excerpt(cleaned, MAX_SUGGESTION_CHARS);
+        if prompts
+            .iter()
+            .any(|existing| existing.eq_ignore_ascii_case(&cleaned))
+        {
+            continue;
+        }
+        prompts.push(cleaned);
+        if prompts.len() == MAX_SUGGESTIONS {
+            break;
+        }
+    }
+    prompts
+}
+
+fn cached_suggestions(camp_id: &str, transcript_hash: &str) -> Option<PromptSuggestions> {
+    let cache = SUGGESTION_CACHE.lock().ok()?;
+    cache
+        .iter()
+        .find(|entry| entry.camp_id == camp_id && entry.transcript_hash == transcript_hash)
+        .map(|entry| PromptSuggestions {
+            cached: true,
+            ..entry.suggestions.clone()
+        })
+}
+
+fn store_suggestions(transcript_hash: String, suggestions: &PromptSuggestions) {
+    if let Ok(mut cache) = SUGGESTION_CACHE.lock() {
+        cache.retain(|entry| entry.camp_id != suggestions.camp_id);
+        cache.push(CachedSuggestions {
+            camp_id: suggestions.camp_id.clone(),
+            transcript_hash,
+            suggestions: suggestions.clone(),
+        });
+    }
+}
+
+/// Suggests 3 to 5 next prompts from the camp's memory, artifacts and recent
+/// messages, using the title model when one is set (it is meant to be a
+/// cheap one) and the camp's model otherwise. Results are reused until the
+/// transcript changes.
+#[tauri::command]
+pub async fn suggest_prompts(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<PromptSuggestions, String> {
+    ensure_main_window(&window)?;
+    let (model, source, transcript_hash) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+        let transcript_path = camp_transcript_path(&camp_dir);
+        let transcript_hash = format!(
+            "{:x}",
+            Sha256::digest(fs::read(&transcript_path).unwrap_or_default())
+        );
+        if let Some(cached) = cached_suggestions(&camp_id, &transcript_hash) {
+            return Ok(cached);
+        }
+
+        let config = read_camp_config(&camp_dir)?;
+        let system_prompt = read_text_file(&camp_system_prompt_path(&camp_dir)).unwrap_or_default();
+        let memory: Value = read_json_file(&camp_memory_path(&camp_dir)).unwrap_or(Value::Null);
+        let artifact_titles: Vec<String> = ensure_artifacts_index(&camp_dir)
+            .map(|index| {
+                index
+                    .artifacts
+                    .into_iter()
+                    .filter(|artifact| !artifact.archived)
+                    .take(MAX_LISTED_ARTIFACTS)
+                    .map(|artifact| artifact.title)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let transcript = read_transcript(&transcript_path)?;
+        let model = load_title_model(&connection)?.unwrap_or_else(|| config.model.clone());
+        let source = render_suggestion_source(
+            &config.name,
+            &system_prompt,
+            &memory,
+            &artifact_titles,
+            &transcript,
+        );
+        (model, source, transcript_hash)
+    };
+
+    let response = run_chat_completion(
+        state.inner(),
+        &model,
+        vec![
+            serde_json::json!({ "role": "system", "content": SUGGESTION_INSTRUCTIONS }),
+            serde_json::json!({ "role": "user", "content": source }),
+        ],
+        None,
+        Some(&camp_id),
+    )
+    .await?;
+    let prompts = parse_suggestions(&response.output_text);
+    if prompts.len() < MIN_SUGGESTIONS {
+        return Err(format!(
+            "Model returned {} usable suggestions; expected at least {MIN_SUGGESTIONS}.",
+            prompts.len()
+        ));
+    }
+
+    let suggestions = PromptSuggestions {
+        camp_id,
+        prompts,
+        model,
+        cached: false,
+    };
+    store_suggestions(transcript_hash, &suggestions);
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_suggestions_should_accept_json_or_lists_and_drop_duplicates() {
+        assert_eq!(
+            parse_suggestions(
+                "```json\n{\"prompts\": [\"Draft the launch email\", \"draft the launch email\", \"  \", \"Compare the two pricing plans\"]}\n```"
+            ),
+            vec!["Draft the launch email", "Compare the two pricing plans"]
+        );
+        assert_eq!(
+            parse_suggestions("1. \"Summarize the notes\"\n- Plan next week\n* 3D print a case\n\n2) Find gaps in the spec\n3. One\n4. Two"),
+            vec![
+                "Summarize the notes",
+                "Plan next week",
+                "3D print a case",
+                "Find gaps in the spec",
+                "One"
+            ]
+        );
+
+        let source = render_suggestion_source(
+            "Trip",
+            "",
+            &serde_json::json!({ "destination": "Lisbon" }),
+            &["Packing list".to_string()],
+            &[],
+        );
+        assert!(source.contains("\"destination\": \"Lisbon\""));
+        assert!(source.contains("- Packing list"));
+        assert!(source.contains("No messages yet"));
+        assert!(!source.contains("System prompt"));
+    }
+
+    #[test]
+    fn render_suggestion_source_should_leave_out_scratchpad_notes() {
+        let source = render_suggestion_source(
+            "Trip",
+            "",
+            &serde_json::json!({
+                "destination": "Lisbon",
+                "scratchpad": { "hunch": "Ask about the ferry" }
+            }),
+            &[],
+            &[],
+        );
+        assert!(source.contains("\"destination\": \"Lisbon\""));
+        assert!(!source.contains("scratchpad"));
+        assert!(!source.contains("ferry"));
+    }
+}
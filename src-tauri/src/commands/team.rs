@@ -6,28 +6,59 @@ use std::{
 };
 
 use base64::{engine::general_purpose, Engine as _};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use tauri::{AppHandle, Emitter, State};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use uuid::Uuid;
 
-use crate::providers::{registry, BasecampChatMetadata, BasecampChatRequest, ProviderUsage};
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::{
+    registry::{self, ModelPricing},
+    BasecampChatMetadata, BasecampChatRequest, ProviderKind, ProviderUsage, RequestPriority,
+};
+use crate::tool_loop_guard::{ToolLoopAbort, ToolLoopGuard};
+use crate::tool_schema::{
+    invalid_arguments_result, tool_parameters_schema, validate_tool_arguments,
+};
 use crate::{
-    ensure_camps_root, now_timestamp_ms, parse_model_reference, read_camp_config,
-    read_provider_runtime_settings, write_camp_config, write_json_file, AppState, CampConfig,
+    append_transcript_message, camp_transcript_path, ensure_camps_root, now_timestamp_ms,
+    parse_model_reference, privacy, read_camp_config, read_provider_runtime_settings, scrubber,
+    write_camp_config, write_file_atomic, write_json_file, AppState, CampConfig, CampMessage,
+    CampToolCall, CampToolFunction,
 };
 
 const TEAM_FILE_NAME: &str = "team.json";
-const TEAM_BUS_FILE_NAME: &str = "team_bus.jsonl";
+/// Single bus file used before runs were segmented; moved into `bus/` on load.
+const LEGACY_TEAM_BUS_FILE_NAME: &str = "team_bus.jsonl";
+const TEAM_BUS_DIR_NAME: &str = "bus";
+const TEAM_ACTIVE_RUN_FILE_NAME: &str = "active_run";
+const LEGACY_TEAM_RUN_ID: &str = "legacy";
+/// Runs kept uncompressed by `archive_team_runs` when no count is given.
+const TEAM_DEFAULT_KEEP_RECENT_RUNS: usize = 3;
 const SUPERVISOR_DIR_NAME: &str = "supervisor";
 const AGENTS_DIR_NAME: &str = "agents";
 const TEAM_ARTIFACTS_DIR_NAME: &str = "artifacts";
 const TEAM_DRAFTS_DIR_NAME: &str = "drafts";
 const TEAM_PROMOTED_DIR_NAME: &str = "promoted";
+const TEAM_SHARED_CONTEXT_DIR_NAME: &str = "team_context";
+const WRITE_SHARED_FILE_TOOL_NAME: &str = "write_shared_file";
+/// Tool name used for step results mirrored into the camp transcript.
+const TEAM_STEP_TOOL_NAME: &str = "team_step";
 const TEAM_DEFAULT_MAX_REFLECTION_ROUNDS: u8 = 2;
 const TEAM_MAX_AGENTS: usize = 8;
 const TEAM_MAX_TOOL_LOOPS: usize = 6;
+const ASK_USER_TOOL_NAME: &str = "ask_user";
+/// Upper bound on earlier-step messages replayed when an agent continues context.
+const TEAM_MAX_RESUMED_MESSAGES: usize = 40;
+const TEAM_MAX_RUBRIC_CRITERIA: usize = 12;
+/// Critics score each rubric criterion on this inclusive scale.
+const RUBRIC_MIN_SCORE: f64 = 1.0;
+const RUBRIC_MAX_SCORE: f64 = 5.0;
+const RUBRIC_DEFAULT_PASS_THRESHOLD: f64 = 4.0;
+/// Whole-team presets shipped with the app.
+const TEAM_TEMPLATES_JSON: &str = include_str!("team_templates.json");
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamAgentConfig {
@@ -38,6 +69,9 @@ pub struct TeamAgentConfig {
     pub tool_subset: Vec<String>,
     #[serde(default)]
     pub description: String,
+    /// Start each step from this agent's earlier steps in the same plan.
+    #[serde(default)]
+    pub continue_context: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +85,66 @@ pub struct TeamConfig {
     pub reflection_loops: bool,
     #[serde(default = "default_reflection_rounds")]
     pub max_reflection_rounds: u8,
+    /// Copy plan summaries, step results and promotions into the camp transcript.
+    #[serde(default = "default_true")]
+    pub mirror_to_transcript: bool,
+    #[serde(default)]
+    pub critique_rubric: CritiqueRubric,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubricCriterion {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// What the critic scores drafts against. Without criteria the critic only
+/// returns a pass/fail verdict.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CritiqueRubric {
+    #[serde(default)]
+    pub criteria: Vec<RubricCriterion>,
+    /// Score every criterion must reach for the draft to pass.
+    #[serde(default = "default_rubric_pass_threshold")]
+    pub pass_threshold: f64,
+}
+
+impl Default for CritiqueRubric {
+    fn default() -> Self {
+        Self {
+            criteria: Vec::new(),
+            pass_threshold: RUBRIC_DEFAULT_PASS_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamTemplateAgent {
+    pub id: String,
+    pub role: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tool_subset: Vec<String>,
+    /// Appended to the agent's default prompt.
+    #[serde(default)]
+    pub instructions: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Appended to the default supervisor prompt.
+    #[serde(default)]
+    pub supervisor_guidance: String,
+    pub agents: Vec<TeamTemplateAgent>,
+    #[serde(default = "default_true")]
+    pub reflection_loops: bool,
+    #[serde(default)]
+    pub critique_rubric: CritiqueRubric,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +156,9 @@ pub struct TeamAgentCreateInput {
     pub tool_subset: Vec<String>,
     #[serde(default)]
     pub description: String,
+    /// Start each step from this agent's earlier steps in the same plan.
+    #[serde(default)]
+    pub continue_context: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +166,12 @@ pub struct TeamSettingsUpdateInput {
     pub supervisor_model: String,
     pub reflection_loops: bool,
     pub max_reflection_rounds: u8,
+    /// Omitted keeps the current setting.
+    #[serde(default)]
+    pub mirror_to_transcript: Option<bool>,
+    /// Replaces the rubric when present; omitted keeps the current one.
+    #[serde(default)]
+    pub critique_rubric: Option<CritiqueRubric>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +181,7 @@ pub struct AgentMeta {
     pub model: String,
     pub tool_subset: Vec<String>,
     pub description: String,
+    pub continue_context: bool,
     pub path: String,
 }
 
@@ -93,11 +197,37 @@ pub struct DelegationStep {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecompositionPlan {
+    #[serde(default)]
     pub task_summary: String,
     #[serde(default)]
     pub steps: Vec<DelegationStep>,
     #[serde(default)]
     pub reflection_required: bool,
+    /// Set by the supervisor instead of steps when the task is too ambiguous to plan.
+    #[serde(default, skip_serializing)]
+    pub clarification_question: Option<String>,
+    /// Present when planning is paused until the user answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clarification: Option<ClarificationRequest>,
+}
+
+/// A question the supervisor or an agent asked the user. Its `request_id` is
+/// the id of the `clarification_request` bus entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClarificationRequest {
+    pub request_id: String,
+    pub from: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+    pub question: String,
+}
+
+/// What resumed after `answer_clarification`: a fresh plan or the paused step.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClarificationResolution {
+    Plan { plan: DecompositionPlan },
+    Step { result: AgentStepResult },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -115,6 +245,8 @@ pub enum BusEntryType {
     Critique,
     Promotion,
     Error,
+    ClarificationRequest,
+    ClarificationAnswer,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -140,6 +272,22 @@ pub struct AgentStepResult {
     #[serde(default)]
     pub context_writes: Vec<String>,
     pub token_usage: BusTokenUsage,
+    /// Present when the agent stopped to ask the user something; no draft is
+    /// written until it is answered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clarification: Option<ClarificationRequest>,
+    /// Set when the agent's tool loop was stopped for repeating itself or
+    /// overspending; the draft holds whatever it had produced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loop_aborted: Option<ToolLoopAbort>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CriterionScore {
+    pub criterion: String,
+    pub score: f64,
+    #[serde(default)]
+    pub comment: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -150,6 +298,9 @@ pub struct CritiqueResult {
     pub suggestions: Vec<String>,
     #[serde(default)]
     pub pass: bool,
+    /// One score per rubric criterion, when the camp defines a rubric.
+    #[serde(default)]
+    pub scores: Vec<CriterionScore>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,6 +312,18 @@ pub struct ReflectionSummary {
     pub critiques: Vec<CritiqueResult>,
 }
 
+/// One draft's result from `run_reflection_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftReflectionItem {
+    pub artifact_path: String,
+    pub rounds_completed: u8,
+    pub pass: bool,
+    #[serde(default)]
+    pub critiques: Vec<CritiqueResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TeamStepStatus {
     pub step_id: String,
@@ -198,12 +361,85 @@ pub struct TeamStatus {
     #[serde(default)]
     pub reflection_loops: bool,
     pub max_reflection_rounds: u8,
+    #[serde(default = "default_true")]
+    pub mirror_to_transcript: bool,
+    #[serde(default)]
+    pub critique_rubric: CritiqueRubric,
     #[serde(default)]
     pub agents: Vec<TeamAgentStatus>,
     #[serde(default)]
     pub steps: Vec<TeamStepStatus>,
     pub bus_entries: usize,
     pub artifacts: TeamArtifactsStatus,
+    #[serde(default)]
+    pub pending_clarifications: Vec<ClarificationRequest>,
+}
+
+/// Token usage and estimated USD cost for one participant in a run. The cost is
+/// `None` when a model with usage has no known pricing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRunAgentReport {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub token_usage: BusTokenUsage,
+    pub estimated_cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRunStepReport {
+    pub step_id: String,
+    pub assigned_to: String,
+    pub token_usage: BusTokenUsage,
+    pub estimated_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+}
+
+/// One bus segment under `bus/`. Each task the user starts gets its own run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRunSummary {
+    pub run_id: String,
+    pub active: bool,
+    pub archived: bool,
+    pub size_bytes: u64,
+}
+
+/// Usage, cost and timing for the latest plan, from its decomposition onward.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRunReport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_id: Option<String>,
+    #[serde(default)]
+    pub task_summary: String,
+    pub agents: Vec<TeamRunAgentReport>,
+    pub steps: Vec<TeamRunStepReport>,
+    pub total_usage: BusTokenUsage,
+    pub estimated_cost: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+}
+
+#[derive(Debug)]
+struct DraftReflection {
+    artifact_path: String,
+    draft_path: PathBuf,
+    critiques: Vec<CritiqueResult>,
+    pass: bool,
+    rounds_completed: u8,
+}
+
+/// Directories an agent's file tools operate on.
+#[derive(Debug)]
+struct AgentToolRoots {
+    context: PathBuf,
+    shared: PathBuf,
+    /// Whether the agent's tool subset grants `write_shared_file`.
+    shared_writable: bool,
 }
 
 #[derive(Debug)]
@@ -211,12 +447,30 @@ struct AgentRunOutput {
     output_text: String,
     token_usage: BusTokenUsage,
     context_writes: Vec<String>,
+    clarification_question: Option<String>,
+    messages: Vec<Value>,
+    loop_aborted: Option<ToolLoopAbort>,
+}
+
+/// One line of `agents/<id>/transcript.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTranscriptEntry {
+    pub timestamp: String,
+    /// Id of the decomposition bus entry the step belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_id: Option<String>,
+    pub step_id: String,
+    pub message: Value,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_rubric_pass_threshold() -> f64 {
+    RUBRIC_DEFAULT_PASS_THRESHOLD
+}
+
 fn default_reflection_rounds() -> u8 {
     TEAM_DEFAULT_MAX_REFLECTION_ROUNDS
 }
@@ -225,8 +479,24 @@ fn team_json_path(camp_dir: &Path) -> PathBuf {
     camp_dir.join(TEAM_FILE_NAME)
 }
 
-fn team_bus_path(camp_dir: &Path) -> PathBuf {
-    camp_dir.join(TEAM_BUS_FILE_NAME)
+fn legacy_team_bus_path(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(LEGACY_TEAM_BUS_FILE_NAME)
+}
+
+fn team_bus_dir(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(TEAM_BUS_DIR_NAME)
+}
+
+fn team_run_bus_path(camp_dir: &Path, run_id: &str) -> PathBuf {
+    team_bus_dir(camp_dir).join(format!("{run_id}.jsonl"))
+}
+
+fn team_run_archive_path(camp_dir: &Path, run_id: &str) -> PathBuf {
+    team_bus_dir(camp_dir).join(format!("{run_id}.jsonl.gz"))
+}
+
+fn active_run_path(camp_dir: &Path) -> PathBuf {
+    team_bus_dir(camp_dir).join(TEAM_ACTIVE_RUN_FILE_NAME)
 }
 
 fn supervisor_dir(camp_dir: &Path) -> PathBuf {
@@ -281,6 +551,10 @@ fn team_promoted_dir(camp_dir: &Path) -> PathBuf {
     team_artifacts_dir(camp_dir).join(TEAM_PROMOTED_DIR_NAME)
 }
 
+fn team_shared_context_dir(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(TEAM_SHARED_CONTEXT_DIR_NAME)
+}
+
 fn now_iso8601() -> String {
     OffsetDateTime::now_utc()
         .format(&Rfc3339)
@@ -344,7 +618,8 @@ Rules:
 - Assign each step to exactly one agent by their id
 - Respect dependencies — don't assign a step if its dependency isn't complete
 - Be precise in instructions — agents only read what you write here
-- If quality matters, set reflection_required: true"
+- If quality matters, set reflection_required: true
+- If the task is too ambiguous to plan, output ONLY {\"clarification_question\": \"string\"} instead"
         .to_string()
 }
 
@@ -357,6 +632,8 @@ Role summary: {description}
 Rules:
 - Follow the supervisor delegation exactly.
 - Use only available tools.
+- Material shared by the whole team is available through read_shared_file and list_shared_files.
+- If the instruction cannot be completed without information only the user has, call ask_user.
 - Write outputs that are deterministic and reproducible.
 - If asked for structured output, return valid JSON only.
 - Keep answers concise and implementation-focused.",
@@ -370,6 +647,45 @@ Rules:
     )
 }
 
+fn load_team_templates() -> CommandResult<Vec<TeamTemplate>> {
+    serde_json::from_str(TEAM_TEMPLATES_JSON).map_err(|err| {
+        BasecampError::internal(format!("Bundled team templates are invalid: {err}"))
+    })
+}
+
+/// Every agent in the template runs on `model`, as does the supervisor.
+fn team_config_from_template(template: &TeamTemplate, model: &str) -> TeamConfig {
+    TeamConfig {
+        is_team: true,
+        supervisor_model: model.to_string(),
+        agents: template
+            .agents
+            .iter()
+            .map(|agent| TeamAgentConfig {
+                id: agent.id.clone(),
+                role: agent.role.clone(),
+                model: model.to_string(),
+                tool_subset: normalize_tool_subset(&agent.tool_subset),
+                description: agent.description.clone(),
+                continue_context: false,
+            })
+            .collect(),
+        reflection_loops: template.reflection_loops,
+        max_reflection_rounds: TEAM_DEFAULT_MAX_REFLECTION_ROUNDS,
+        mirror_to_transcript: true,
+        critique_rubric: template.critique_rubric.clone(),
+    }
+}
+
+fn with_guidance(prompt: String, heading: &str, guidance: &str) -> String {
+    let guidance = guidance.trim();
+    if guidance.is_empty() {
+        prompt
+    } else {
+        format!("{prompt}\n\n{heading}:\n{guidance}")
+    }
+}
+
 fn write_text_if_missing(path: &Path, contents: &str) -> Result<(), String> {
     if path.exists() {
         return Ok(());
@@ -409,6 +725,8 @@ fn default_team_config(camp_config: &CampConfig) -> TeamConfig {
         agents: Vec::new(),
         reflection_loops: true,
         max_reflection_rounds: TEAM_DEFAULT_MAX_REFLECTION_ROUNDS,
+        mirror_to_transcript: true,
+        critique_rubric: CritiqueRubric::default(),
     }
 }
 
@@ -441,6 +759,8 @@ fn ensure_team_scaffold(camp_dir: &Path, team_config: &TeamConfig) -> Result<(),
         .map_err(|err| format!("Unable to create drafts folder: {err}"))?;
     fs::create_dir_all(team_promoted_dir(camp_dir))
         .map_err(|err| format!("Unable to create promoted folder: {err}"))?;
+    fs::create_dir_all(team_shared_context_dir(camp_dir))
+        .map_err(|err| format!("Unable to create shared team context folder: {err}"))?;
 
     write_text_if_missing(
         &supervisor_prompt_path(camp_dir),
@@ -451,9 +771,9 @@ fn ensure_team_scaffold(camp_dir: &Path, team_config: &TeamConfig) -> Result<(),
         write_json_file(&supervisor_tools_path(camp_dir), &Vec::<String>::new())?;
     }
 
-    if !team_bus_path(camp_dir).exists() {
-        write_text_if_missing(&team_bus_path(camp_dir), "")?;
-    }
+    fs::create_dir_all(team_bus_dir(camp_dir))
+        .map_err(|err| format!("Unable to create team bus folder: {err}"))?;
+    migrate_legacy_team_bus(camp_dir)?;
 
     for agent in &team_config.agents {
         ensure_agent_scaffold(camp_dir, agent)?;
@@ -522,21 +842,64 @@ fn save_team_config(camp_dir: &Path, team_config: &TeamConfig) -> Result<(), Str
     write_camp_config(camp_dir, &updated)
 }
 
-fn read_team_bus_entries(camp_dir: &Path) -> Result<Vec<BusEntry>, String> {
-    let path = team_bus_path(camp_dir);
-    if !path.exists() {
-        return Ok(Vec::new());
+/// Moves a pre-segmentation `team_bus.jsonl` into `bus/` as its own run.
+fn migrate_legacy_team_bus(camp_dir: &Path) -> Result<(), String> {
+    let legacy = legacy_team_bus_path(camp_dir);
+    if !legacy.exists() {
+        return Ok(());
     }
+    fs::rename(&legacy, team_run_bus_path(camp_dir, LEGACY_TEAM_RUN_ID))
+        .map_err(|err| format!("Unable to move legacy team bus: {err}"))?;
+    if !active_run_path(camp_dir).exists() {
+        write_file_atomic(&active_run_path(camp_dir), LEGACY_TEAM_RUN_ID.as_bytes())?;
+    }
+    Ok(())
+}
 
-    let file = fs::File::open(&path).map_err(|err| {
-        format!(
-            "Unable to open team bus file {}: {err}",
-            path.to_string_lossy()
-        )
-    })?;
-    let reader = BufReader::new(file);
-    let mut entries = Vec::new();
+/// Run ids sort chronologically, so the file listing doubles as run history.
+fn new_team_run_id() -> String {
+    format!("run-{}", now_timestamp_ms())
+}
+
+/// Begins a new bus segment and makes it the active run.
+fn start_team_run(camp_dir: &Path) -> Result<String, String> {
+    let mut run_id = new_team_run_id();
+    while team_run_bus_path(camp_dir, &run_id).exists()
+        || team_run_archive_path(camp_dir, &run_id).exists()
+    {
+        run_id = format!(
+            "{}-{}",
+            new_team_run_id(),
+            &Uuid::new_v4().simple().to_string()[..6]
+        );
+    }
+    write_text_if_missing(&team_run_bus_path(camp_dir, &run_id), "")?;
+    write_file_atomic(&active_run_path(camp_dir), run_id.as_bytes())?;
+    Ok(run_id)
+}
+
+fn active_run_id(camp_dir: &Path) -> Result<String, String> {
+    match fs::read_to_string(active_run_path(camp_dir)) {
+        Ok(raw) if !raw.trim().is_empty() => Ok(raw.trim().to_string()),
+        _ => start_team_run(camp_dir),
+    }
+}
+
+/// Starts a fresh run for a new task unless the active one is still empty.
+fn begin_run_for_new_task(camp_dir: &Path) -> Result<String, String> {
+    let run_id = active_run_id(camp_dir)?;
+    let is_empty = fs::metadata(team_run_bus_path(camp_dir, &run_id))
+        .map(|metadata| metadata.len() == 0)
+        .unwrap_or(false);
+    if is_empty {
+        Ok(run_id)
+    } else {
+        start_team_run(camp_dir)
+    }
+}
 
+fn parse_bus_lines(reader: impl BufRead) -> Result<Vec<BusEntry>, String> {
+    let mut entries = Vec::new();
     for line in reader.lines() {
         let line = line.map_err(|err| format!("Unable to read team bus line: {err}"))?;
         let trimmed = line.trim();
@@ -548,16 +911,107 @@ fn read_team_bus_entries(camp_dir: &Path) -> Result<Vec<BusEntry>, String> {
             .map_err(|err| format!("Unable to parse team bus entry: {err}"))?;
         entries.push(entry);
     }
-
     Ok(entries)
 }
 
+/// Reads one run, transparently decompressing it when archived.
+fn read_team_run_entries(camp_dir: &Path, run_id: &str) -> Result<Vec<BusEntry>, String> {
+    let path = team_run_bus_path(camp_dir, run_id);
+    if path.exists() {
+        let file = fs::File::open(&path).map_err(|err| {
+            format!(
+                "Unable to open team bus file {}: {err}",
+                path.to_string_lossy()
+            )
+        })?;
+        return parse_bus_lines(BufReader::new(file));
+    }
+
+    let archive = team_run_archive_path(camp_dir, run_id);
+    if archive.exists() {
+        let file = fs::File::open(&archive)
+            .map_err(|err| format!("Unable to open archived team run {run_id}: {err}"))?;
+        return parse_bus_lines(BufReader::new(GzDecoder::new(file)));
+    }
+
+    Ok(Vec::new())
+}
+
+/// Entries of the active run. Earlier runs are only read on request.
+fn read_team_bus_entries(camp_dir: &Path) -> Result<Vec<BusEntry>, String> {
+    read_team_run_entries(camp_dir, &active_run_id(camp_dir)?)
+}
+
+fn list_team_run_summaries(camp_dir: &Path) -> Result<Vec<TeamRunSummary>, String> {
+    let active = active_run_id(camp_dir)?;
+    let dir = fs::read_dir(team_bus_dir(camp_dir))
+        .map_err(|err| format!("Unable to read team bus folder: {err}"))?;
+    let mut runs = Vec::new();
+    for item in dir {
+        let item = item.map_err(|err| format!("Unable to read team bus folder: {err}"))?;
+        let name = item.file_name().to_string_lossy().into_owned();
+        let (run_id, archived) = if let Some(run_id) = name.strip_suffix(".jsonl.gz") {
+            (run_id.to_string(), true)
+        } else if let Some(run_id) = name.strip_suffix(".jsonl") {
+            (run_id.to_string(), false)
+        } else {
+            continue;
+        };
+        let size_bytes = item.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        runs.push(TeamRunSummary {
+            active: run_id == active,
+            run_id,
+            archived,
+            size_bytes,
+        });
+    }
+    // The legacy run predates every timestamped one.
+    runs.sort_by(|left, right| {
+        (left.run_id != LEGACY_TEAM_RUN_ID, &left.run_id)
+            .cmp(&(right.run_id != LEGACY_TEAM_RUN_ID, &right.run_id))
+    });
+    Ok(runs)
+}
+
+/// Gzips every inactive run except the `keep_recent` newest ones and returns
+/// the archived run ids.
+fn archive_inactive_runs(camp_dir: &Path, keep_recent: usize) -> Result<Vec<String>, String> {
+    let candidates: Vec<TeamRunSummary> = list_team_run_summaries(camp_dir)?
+        .into_iter()
+        .filter(|run| !run.active)
+        .collect();
+    let archive_count = candidates.len().saturating_sub(keep_recent);
+
+    let mut archived = Vec::new();
+    for run in candidates.into_iter().take(archive_count) {
+        if run.archived {
+            continue;
+        }
+        let source = team_run_bus_path(camp_dir, &run.run_id);
+        let target = team_run_archive_path(camp_dir, &run.run_id);
+        let contents = fs::read(&source)
+            .map_err(|err| format!("Unable to read team run {}: {err}", run.run_id))?;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(&contents)
+            .map_err(|err| format!("Unable to compress team run {}: {err}", run.run_id))?;
+        let compressed = encoder
+            .finish()
+            .map_err(|err| format!("Unable to compress team run {}: {err}", run.run_id))?;
+        write_file_atomic(&target, &compressed)?;
+        fs::remove_file(&source)
+            .map_err(|err| format!("Unable to remove archived team run {}: {err}", run.run_id))?;
+        archived.push(run.run_id);
+    }
+    Ok(archived)
+}
+
 fn append_team_bus_entry(
     camp_dir: &Path,
     entry: &BusEntry,
     app: Option<&AppHandle>,
 ) -> Result<(), String> {
-    let path = team_bus_path(camp_dir);
+    let path = team_run_bus_path(camp_dir, &active_run_id(camp_dir)?);
     let mut serialized = serde_json::to_string(entry)
         .map_err(|err| format!("Unable to serialize bus entry: {err}"))?;
     serialized.push('\n');
@@ -583,6 +1037,103 @@ fn append_team_bus_entry(
     Ok(())
 }
 
+fn read_agent_transcript(
+    camp_dir: &Path,
+    agent_id: &str,
+) -> Result<Vec<AgentTranscriptEntry>, String> {
+    let path = agent_transcript_path(camp_dir, agent_id);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path).map_err(|err| {
+        format!(
+            "Unable to open agent transcript {}: {err}",
+            path.to_string_lossy()
+        )
+    })?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|err| format!("Unable to read agent transcript line: {err}"))?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let entry: AgentTranscriptEntry = serde_json::from_str(trimmed)
+            .map_err(|err| format!("Unable to parse agent transcript entry: {err}"))?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn append_agent_transcript(
+    camp_dir: &Path,
+    agent_id: &str,
+    plan_id: Option<&str>,
+    step_id: &str,
+    messages: &[Value],
+) -> Result<(), String> {
+    let timestamp = now_iso8601();
+    let mut serialized = String::new();
+    for message in messages {
+        let entry = AgentTranscriptEntry {
+            timestamp: timestamp.clone(),
+            plan_id: plan_id.map(ToString::to_string),
+            step_id: step_id.to_string(),
+            message: message.clone(),
+        };
+        serialized.push_str(
+            &serde_json::to_string(&entry)
+                .map_err(|err| format!("Unable to serialize agent transcript entry: {err}"))?,
+        );
+        serialized.push('\n');
+    }
+
+    let path = agent_transcript_path(camp_dir, agent_id);
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|err| {
+            format!(
+                "Unable to open agent transcript {}: {err}",
+                path.to_string_lossy()
+            )
+        })?;
+    file.write_all(serialized.as_bytes())
+        .map_err(|err| format!("Unable to append agent transcript: {err}"))
+}
+
+fn latest_plan_id(entries: &[BusEntry]) -> Option<String> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| matches!(entry.entry_type, BusEntryType::Decomposition))
+        .map(|entry| entry.id.clone())
+}
+
+/// The agent's messages from earlier steps of the same plan, without their
+/// system prompts, trimmed so the replay starts on a user turn.
+fn resumable_history(
+    transcript: &[AgentTranscriptEntry],
+    plan_id: Option<&str>,
+    step_id: &str,
+) -> Vec<Value> {
+    let history: Vec<Value> = transcript
+        .iter()
+        .filter(|entry| entry.plan_id.as_deref() == plan_id && entry.step_id != step_id)
+        .map(|entry| entry.message.clone())
+        .filter(|message| message.get("role").and_then(Value::as_str) != Some("system"))
+        .collect();
+    let tail = &history[history.len().saturating_sub(TEAM_MAX_RESUMED_MESSAGES)..];
+    let start = tail
+        .iter()
+        .position(|message| message.get("role").and_then(Value::as_str) == Some("user"))
+        .unwrap_or(tail.len());
+    tail[start..].to_vec()
+}
+
 fn make_bus_entry(
     entry_type: BusEntryType,
     from: &str,
@@ -603,30 +1154,407 @@ fn make_bus_entry(
     }
 }
 
-fn usage_to_bus(usage: &ProviderUsage) -> BusTokenUsage {
-    BusTokenUsage {
-        input: usage.prompt_tokens.unwrap_or(0),
-        output: usage.completion_tokens.unwrap_or(0),
+fn mirrored_message(role: &str, content: String) -> CampMessage {
+    CampMessage {
+        id: Uuid::new_v4().to_string(),
+        role: role.to_string(),
+        content,
+        created_at: now_timestamp_ms(),
+        name: None,
+        tool_call_id: None,
+        tool_calls: None,
+        included_artifact_ids: None,
+        attachments: None,
+        reasoning: None,
+        run_id: None,
     }
 }
 
-fn accumulate_usage(into: &mut BusTokenUsage, usage: &ProviderUsage) {
-    into.input += usage.prompt_tokens.unwrap_or(0);
-    into.output += usage.completion_tokens.unwrap_or(0);
+fn mirrored_plan_messages(plan: &DecompositionPlan) -> Vec<CampMessage> {
+    let steps = plan
+        .steps
+        .iter()
+        .map(|step| {
+            format!(
+                "- {} → {}: {}",
+                step.step_id,
+                step.assigned_to,
+                step.instruction.trim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    vec![mirrored_message(
+        "assistant",
+        format!("Team plan: {}\n\n{steps}", plan.task_summary.trim()),
+    )]
 }
 
-async fn run_chat_completion(
-    state: &AppState,
-    model_reference: &str,
+/// A step result becomes an assistant tool call answered by a tool message, so
+/// the pair replays like any other tool exchange.
+fn mirrored_step_messages(result: &AgentStepResult) -> Vec<CampMessage> {
+    let call_id = format!("team-{}", Uuid::new_v4());
+    let mut call = mirrored_message("assistant", String::new());
+    call.tool_calls = Some(vec![CampToolCall {
+        id: call_id.clone(),
+        kind: "function".to_string(),
+        function: CampToolFunction {
+            name: TEAM_STEP_TOOL_NAME.to_string(),
+            arguments: serde_json::json!({
+                "step_id": result.step_id,
+                "agent_id": result.agent_id,
+            })
+            .to_string(),
+        },
+    }]);
+
+    let mut output = mirrored_message(
+        "tool",
+        format!(
+            "{}\n\nDraft: {}",
+            result.output_text.trim(),
+            result.draft_path
+        ),
+    );
+    output.name = Some(TEAM_STEP_TOOL_NAME.to_string());
+    output.tool_call_id = Some(call_id);
+    vec![call, output]
+}
+
+fn mirrored_promotion_messages(from_path: &str, promoted_path: &str) -> Vec<CampMessage> {
+    vec![mirrored_message(
+        "assistant",
+        format!("Team promoted `{from_path}` to `{promoted_path}`."),
+    )]
+}
+
+/// Appends team activity to the camp transcript when the team has opted in.
+fn mirror_to_camp_transcript(
+    camp_dir: &Path,
+    team_config: &TeamConfig,
+    messages: Vec<CampMessage>,
+) -> Result<(), String> {
+    if !team_config.mirror_to_transcript {
+        return Ok(());
+    }
+    let path = camp_transcript_path(camp_dir);
+    for message in &messages {
+        append_transcript_message(&path, message)?;
+    }
+    Ok(())
+}
+
+fn clarification_from_entry(entry: &BusEntry) -> Option<ClarificationRequest> {
+    if !matches!(entry.entry_type, BusEntryType::ClarificationRequest) {
+        return None;
+    }
+    Some(ClarificationRequest {
+        request_id: entry.id.clone(),
+        from: entry.from.clone(),
+        step_id: entry.step_id.clone(),
+        question: entry
+            .content
+            .get("question")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+    })
+}
+
+/// Answers recorded on the bus, keyed by the request they reply to.
+fn clarification_answers(entries: &[BusEntry]) -> HashMap<String, String> {
+    entries
+        .iter()
+        .filter(|entry| matches!(entry.entry_type, BusEntryType::ClarificationAnswer))
+        .filter_map(|entry| {
+            let request_id = entry.content.get("request_id").and_then(Value::as_str)?;
+            let answer = entry.content.get("answer").and_then(Value::as_str)?;
+            Some((request_id.to_string(), answer.to_string()))
+        })
+        .collect()
+}
+
+/// Entries since the latest decomposition; older clarifications belong to a
+/// previous plan.
+fn entries_since_decomposition(entries: &[BusEntry]) -> &[BusEntry] {
+    let start = entries
+        .iter()
+        .rposition(|entry| matches!(entry.entry_type, BusEntryType::Decomposition))
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    &entries[start..]
+}
+
+/// Question/answer pairs already exchanged with `from` for a step (or, for the
+/// supervisor, for the same task), oldest first.
+fn answered_clarifications(
+    entries: &[BusEntry],
+    from: &str,
+    step_id: Option<&str>,
+    user_task: Option<&str>,
+) -> Vec<(String, String)> {
+    let answers = clarification_answers(entries);
+    entries_since_decomposition(entries)
+        .iter()
+        .filter(|entry| entry.from == from && entry.step_id.as_deref() == step_id)
+        .filter(|entry| match user_task {
+            Some(task) => entry.content.get("user_task").and_then(Value::as_str) == Some(task),
+            None => true,
+        })
+        .filter_map(|entry| {
+            let request = clarification_from_entry(entry)?;
+            let answer = answers.get(&request.request_id)?;
+            Some((request.question, answer.clone()))
+        })
+        .collect()
+}
+
+fn pending_clarifications(entries: &[BusEntry]) -> Vec<ClarificationRequest> {
+    let answers = clarification_answers(entries);
+    entries_since_decomposition(entries)
+        .iter()
+        .filter_map(clarification_from_entry)
+        .filter(|request| !answers.contains_key(&request.request_id))
+        .collect()
+}
+
+fn render_clarifications(pairs: &[(String, String)]) -> String {
+    if pairs.is_empty() {
+        return String::new();
+    }
+    let rendered = pairs
+        .iter()
+        .map(|(question, answer)| format!("Q: {question}\nA: {answer}"))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!("\n\nClarifications from the user:\n{rendered}")
+}
+
+/// The latest plan's entries, starting with its decomposition.
+fn latest_run_entries(entries: &[BusEntry]) -> &[BusEntry] {
+    let start = entries
+        .iter()
+        .rposition(|entry| matches!(entry.entry_type, BusEntryType::Decomposition))
+        .unwrap_or(0);
+    &entries[start..]
+}
+
+fn parse_bus_timestamp(value: &str) -> Option<OffsetDateTime> {
+    OffsetDateTime::parse(value, &Rfc3339).ok()
+}
+
+fn elapsed_ms(start: &str, end: &str) -> Option<i64> {
+    let elapsed = parse_bus_timestamp(end)? - parse_bus_timestamp(start)?;
+    Some(elapsed.whole_milliseconds() as i64)
+}
+
+fn estimate_cost(usage: &BusTokenUsage, pricing: Option<&ModelPricing>) -> Option<f64> {
+    if usage.input == 0 && usage.output == 0 {
+        return Some(0.0);
+    }
+    pricing.map(|pricing| {
+        usage.input as f64 * pricing.prompt + usage.output as f64 * pricing.completion
+    })
+}
+
+/// Pricing per participant id. Models on local providers are free; OpenRouter
+/// models use the synced catalog and are left out when it has no price.
+fn participant_pricing(
+    connection: &rusqlite::Connection,
+    team_config: &TeamConfig,
+) -> Result<HashMap<String, ModelPricing>, rusqlite::Error> {
+    let participants = std::iter::once(("supervisor", &team_config.supervisor_model)).chain(
+        team_config
+            .agents
+            .iter()
+            .map(|agent| (agent.id.as_str(), &agent.model)),
+    );
+    let mut pricing = HashMap::new();
+    for (id, model) in participants {
+        let (provider_kind, model_id) = parse_model_reference(model);
+        let price = if provider_kind == ProviderKind::Openrouter {
+            registry::get_model_pricing(connection, provider_kind, &model_id)?
+        } else {
+            Some(ModelPricing {
+                prompt: 0.0,
+                completion: 0.0,
+            })
+        };
+        if let Some(price) = price {
+            pricing.insert(id.to_string(), price);
+        }
+    }
+    Ok(pricing)
+}
+
+fn build_run_report(
+    entries: &[BusEntry],
+    team_config: &TeamConfig,
+    pricing: &HashMap<String, ModelPricing>,
+) -> TeamRunReport {
+    let run = latest_run_entries(entries);
+    let plan_entry = run
+        .first()
+        .filter(|entry| matches!(entry.entry_type, BusEntryType::Decomposition));
+    let plan = plan_entry
+        .and_then(|entry| serde_json::from_value::<DecompositionPlan>(entry.content.clone()).ok());
+
+    let mut agent_usage = BTreeMap::<String, BusTokenUsage>::new();
+    let mut step_usage = HashMap::<String, BTreeMap<String, BusTokenUsage>>::new();
+    let mut step_started = HashMap::<String, String>::new();
+    let mut step_delegated = HashMap::<String, String>::new();
+    let mut step_finished = HashMap::<String, String>::new();
+    let mut total_usage = BusTokenUsage::default();
+
+    for entry in run {
+        let usage = agent_usage.entry(entry.from.clone()).or_default();
+        usage.input += entry.token_usage.input;
+        usage.output += entry.token_usage.output;
+        total_usage.input += entry.token_usage.input;
+        total_usage.output += entry.token_usage.output;
+
+        let Some(step_id) = &entry.step_id else {
+            continue;
+        };
+        let usage = step_usage
+            .entry(step_id.clone())
+            .or_default()
+            .entry(entry.from.clone())
+            .or_default();
+        usage.input += entry.token_usage.input;
+        usage.output += entry.token_usage.output;
+
+        match entry.entry_type {
+            BusEntryType::Delegation => {
+                step_delegated
+                    .entry(step_id.clone())
+                    .or_insert_with(|| entry.timestamp.clone());
+            }
+            _ => {
+                if let Some(started_at) = entry.content.get("started_at").and_then(Value::as_str) {
+                    let earliest = step_started
+                        .entry(step_id.clone())
+                        .or_insert_with(|| started_at.to_string());
+                    if parse_bus_timestamp(started_at) < parse_bus_timestamp(earliest) {
+                        *earliest = started_at.to_string();
+                    }
+                }
+            }
+        }
+        if matches!(entry.entry_type, BusEntryType::Result | BusEntryType::Error) {
+            step_finished.insert(step_id.clone(), entry.timestamp.clone());
+        }
+    }
+
+    let mut agents = Vec::new();
+    let mut participants = vec![(
+        "supervisor".to_string(),
+        team_config.supervisor_model.clone(),
+    )];
+    participants.extend(
+        team_config
+            .agents
+            .iter()
+            .map(|agent| (agent.id.clone(), agent.model.clone())),
+    );
+    for (id, model) in participants {
+        let token_usage = agent_usage.remove(&id).unwrap_or_default();
+        agents.push(TeamRunAgentReport {
+            estimated_cost: estimate_cost(&token_usage, pricing.get(&id)),
+            id,
+            model: Some(model),
+            token_usage,
+        });
+    }
+    // Agents removed from the roster since the run still count toward the total.
+    for (id, token_usage) in agent_usage {
+        agents.push(TeamRunAgentReport {
+            estimated_cost: estimate_cost(&token_usage, None),
+            id,
+            model: None,
+            token_usage,
+        });
+    }
+
+    let steps = plan
+        .as_ref()
+        .map(|plan| plan.steps.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .map(|step| {
+            let by_participant = step_usage.remove(&step.step_id).unwrap_or_default();
+            let mut token_usage = BusTokenUsage::default();
+            for usage in by_participant.values() {
+                token_usage.input += usage.input;
+                token_usage.output += usage.output;
+            }
+            let estimated_cost = by_participant
+                .iter()
+                .map(|(id, usage)| estimate_cost(usage, pricing.get(id)))
+                .sum();
+            // Results record when the agent actually started; steps that never
+            // reported one fall back to their delegation.
+            let started_at = step_started
+                .remove(&step.step_id)
+                .or_else(|| step_delegated.remove(&step.step_id));
+            let finished_at = step_finished.remove(&step.step_id);
+            let duration_ms = match (&started_at, &finished_at) {
+                (Some(start), Some(end)) => elapsed_ms(start, end),
+                _ => None,
+            };
+            TeamRunStepReport {
+                step_id: step.step_id.clone(),
+                assigned_to: step.assigned_to.clone(),
+                token_usage,
+                estimated_cost,
+                started_at,
+                finished_at,
+                duration_ms,
+            }
+        })
+        .collect();
+
+    let estimated_cost = agents.iter().map(|agent| agent.estimated_cost).sum();
+    let duration_ms = match (run.first(), run.last()) {
+        (Some(first), Some(last)) => elapsed_ms(&first.timestamp, &last.timestamp),
+        _ => None,
+    };
+
+    TeamRunReport {
+        plan_id: plan_entry.map(|entry| entry.id.clone()),
+        task_summary: plan.map(|plan| plan.task_summary).unwrap_or_default(),
+        agents,
+        steps,
+        total_usage,
+        estimated_cost,
+        duration_ms,
+    }
+}
+
+fn usage_to_bus(usage: &ProviderUsage) -> BusTokenUsage {
+    BusTokenUsage {
+        input: usage.prompt_tokens.unwrap_or(0),
+        output: usage.completion_tokens.unwrap_or(0),
+    }
+}
+
+fn accumulate_usage(into: &mut BusTokenUsage, usage: &ProviderUsage) {
+    into.input += usage.prompt_tokens.unwrap_or(0);
+    into.output += usage.completion_tokens.unwrap_or(0);
+}
+
+pub(crate) async fn run_chat_completion(
+    state: &AppState,
+    model_reference: &str,
     messages: Vec<Value>,
     tools: Option<Vec<Value>>,
     correlation_scope: Option<&str>,
 ) -> Result<crate::providers::ProviderChatResponse, String> {
     let (provider_kind, model_id) = parse_model_reference(model_reference);
     let has_tools = tools.as_ref().is_some_and(|items| !items.is_empty());
-    let mut request = BasecampChatRequest {
+    let request = BasecampChatRequest {
         provider_kind,
-        model_id: model_id.clone(),
+        model_id,
         messages,
         tools,
         tool_choice: if has_tools {
@@ -637,22 +1565,41 @@ async fn run_chat_completion(
         temperature: Some(0.2),
         max_tokens: Some(2_000),
         top_p: None,
+        stop: None,
+        logit_bias: None,
         stream: false,
         metadata: BasecampChatMetadata {
             camp_id: correlation_scope.map(ToString::to_string),
             correlation_id: Some(format!("team-{}", Uuid::new_v4())),
             provider_kind: Some(provider_kind),
+            priority: RequestPriority::Team,
         },
     };
+    send_chat_request(state, request).await
+}
 
-    let settings = {
+/// Sends a prepared request through the provider manager, dropping tools the
+/// model cannot use and applying outbound scrubbing first. Local-only camps
+/// are held to local providers.
+pub(crate) async fn send_chat_request(
+    state: &AppState,
+    mut request: BasecampChatRequest,
+) -> Result<crate::providers::ProviderChatResponse, String> {
+    let provider_kind = request.provider_kind;
+    let (settings, scrub_audit) = {
         let connection = state
             .connection
             .lock()
             .map_err(|_| "Database lock error".to_string())?;
+        privacy::enforce_camp_routing(
+            &connection,
+            request.metadata.camp_id.as_deref(),
+            provider_kind,
+        )
+        .map_err(|error| error.message)?;
 
         let model_capabilities =
-            registry::get_model_capabilities(&connection, provider_kind, &model_id)
+            registry::get_model_capabilities(&connection, provider_kind, &request.model_id)
                 .map_err(|err| format!("Unable to load model capabilities: {err}"))?;
 
         let provider = state.provider_manager.get(provider_kind);
@@ -666,7 +1613,11 @@ async fn run_chat_completion(
             request.tool_choice = None;
         }
 
-        read_provider_runtime_settings(&connection, provider_kind)?
+        let scrub_audit = scrubber::scrub_outbound_request(&connection, &mut request)?;
+        (
+            read_provider_runtime_settings(&connection, provider_kind)?,
+            scrub_audit,
+        )
     };
 
     if !settings.config.enabled {
@@ -676,8 +1627,12 @@ async fn run_chat_completion(
         ));
     }
 
+    let _slot = state
+        .provider_manager
+        .acquire_slot(provider_kind, request.metadata.priority, |_| {})
+        .await;
     let provider = state.provider_manager.get(provider_kind);
-    let response = provider
+    let mut response = provider
         .send_chat(&state.provider_client, &settings, &request, None)
         .await
         .map_err(|error| error.message)?;
@@ -686,10 +1641,11 @@ async fn run_chat_completion(
         let _ = registry::update_provider_health(&connection, provider_kind, true, None);
     }
 
+    response.scrub_audit = scrub_audit;
     Ok(response)
 }
 
-fn parse_json_from_output<T: DeserializeOwned>(raw: &str) -> Result<T, String> {
+pub(crate) fn parse_json_from_output<T: DeserializeOwned>(raw: &str) -> Result<T, String> {
     let trimmed = raw.trim();
     if trimmed.is_empty() {
         return Err("Model returned empty output where JSON was expected.".to_string());
@@ -873,6 +1829,23 @@ fn tool_spec_for_name(name: &str) -> Option<Value> {
                 }
             }
         })),
+        WRITE_SHARED_FILE_TOOL_NAME => Some(serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": WRITE_SHARED_FILE_TOOL_NAME,
+                "description": "Write a file in the shared team context directory that every agent can read.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "content": { "type": "string" },
+                        "encoding": { "type": "string", "enum": ["utf-8", "base64"] }
+                    },
+                    "required": ["path", "content"],
+                    "additionalProperties": false
+                }
+            }
+        })),
         "web_search" => Some(serde_json::json!({
             "type": "function",
             "function": {
@@ -892,6 +1865,73 @@ fn tool_spec_for_name(name: &str) -> Option<Value> {
     }
 }
 
+/// Read access to the shared team context is offered to every agent.
+fn shared_read_tool_specs() -> Vec<Value> {
+    vec![
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "read_shared_file",
+                "description": "Read a file from the shared team context directory.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": ["path"],
+                    "additionalProperties": false
+                }
+            }
+        }),
+        serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "list_shared_files",
+                "description": "List files in the shared team context directory.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" }
+                    },
+                    "required": [],
+                    "additionalProperties": false
+                }
+            }
+        }),
+    ]
+}
+
+/// Always offered to agents so they can pause their step on a question.
+fn ask_user_tool_spec() -> Value {
+    serde_json::json!({
+        "type": "function",
+        "function": {
+            "name": ASK_USER_TOOL_NAME,
+            "description": "Ask the user a clarifying question. Your step pauses until they answer.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "question": { "type": "string" }
+                },
+                "required": ["question"],
+                "additionalProperties": false
+            }
+        }
+    })
+}
+
+/// The spec offered to agents for `name`, whichever list it came from.
+fn team_tool_spec(name: &str) -> Option<Value> {
+    if name == ASK_USER_TOOL_NAME {
+        return Some(ask_user_tool_spec());
+    }
+    tool_spec_for_name(name).or_else(|| {
+        shared_read_tool_specs()
+            .into_iter()
+            .find(|spec| parse_tool_call_name(spec).as_deref() == Some(name))
+    })
+}
+
 fn tool_specs_for_subset(subset: &[String]) -> Vec<Value> {
     subset
         .iter()
@@ -1002,6 +2042,26 @@ fn list_files_recursive(root: &Path, current: &Path, out: &mut Vec<String>) -> R
     Ok(())
 }
 
+fn agent_tool_roots(
+    camp_dir: &Path,
+    agent_id: &str,
+    tool_subset: &[String],
+) -> Result<AgentToolRoots, String> {
+    let shared_dir = team_shared_context_dir(camp_dir);
+    fs::create_dir_all(&shared_dir)
+        .map_err(|err| format!("Unable to create shared team context directory: {err}"))?;
+    let shared = fs::canonicalize(&shared_dir)
+        .map_err(|err| format!("Unable to resolve shared team context directory: {err}"))?;
+
+    Ok(AgentToolRoots {
+        context: agent_context_root(camp_dir, agent_id)?,
+        shared,
+        shared_writable: tool_subset
+            .iter()
+            .any(|name| name == WRITE_SHARED_FILE_TOOL_NAME),
+    })
+}
+
 fn agent_context_root(camp_dir: &Path, agent_id: &str) -> Result<PathBuf, String> {
     let context_dir = agent_context_dir(camp_dir, agent_id);
     fs::create_dir_all(&context_dir)
@@ -1110,7 +2170,11 @@ fn write_file_tool(root: &Path, args: &Value, writes: &mut Vec<String>) -> Resul
     .to_string())
 }
 
-fn execute_team_tool_call(root: &Path, tool_call: &Value, writes: &mut Vec<String>) -> String {
+fn execute_team_tool_call(
+    roots: &AgentToolRoots,
+    tool_call: &Value,
+    writes: &mut Vec<String>,
+) -> String {
     let id = tool_call
         .get("id")
         .and_then(Value::as_str)
@@ -1140,13 +2204,40 @@ fn execute_team_tool_call(root: &Path, tool_call: &Value, writes: &mut Vec<Strin
         }
     };
 
+    // Report every schema violation at once so the model can fix its call.
+    if let Some(spec) = team_tool_spec(&name) {
+        if let Some(schema) = tool_parameters_schema(&spec) {
+            if let Err(errors) = validate_tool_arguments(schema, &args) {
+                let mut result = invalid_arguments_result(&name, &errors);
+                result["tool_call_id"] = Value::String(id);
+                return result.to_string();
+            }
+        }
+    }
+
     let result = match name.as_str() {
-        "read_file" => read_file_tool(root, &args),
-        "list_files" => list_files_tool(root, &args),
-        "write_file" => write_file_tool(root, &args, writes),
+        "read_file" => read_file_tool(&roots.context, &args),
+        "list_files" => list_files_tool(&roots.context, &args),
+        "write_file" => write_file_tool(&roots.context, &args, writes),
+        "read_shared_file" => read_file_tool(&roots.shared, &args),
+        "list_shared_files" => list_files_tool(&roots.shared, &args),
+        WRITE_SHARED_FILE_TOOL_NAME if roots.shared_writable => {
+            let mut shared_writes = Vec::new();
+            let result = write_file_tool(&roots.shared, &args, &mut shared_writes);
+            writes.extend(
+                shared_writes
+                    .into_iter()
+                    .map(|path| format!("{TEAM_SHARED_CONTEXT_DIR_NAME}/{path}")),
+            );
+            result
+        }
+        WRITE_SHARED_FILE_TOOL_NAME => {
+            Err("This agent is not allowed to write to the shared team context.".to_string())
+        }
         "web_search" => {
             Err("web_search is not available in local deterministic team mode.".to_string())
         }
+        ASK_USER_TOOL_NAME => Err("ask_user requires a non-empty question.".to_string()),
         _ => Err(format!("Unsupported tool `{name}`.")),
     };
 
@@ -1167,23 +2258,22 @@ async fn run_agent_inference_loop(
     model_reference: &str,
     mut messages: Vec<Value>,
     tool_subset: &[String],
-    context_root: &Path,
+    roots: &AgentToolRoots,
 ) -> Result<AgentRunOutput, String> {
-    let tools = tool_specs_for_subset(tool_subset);
+    let mut tools = tool_specs_for_subset(tool_subset);
+    tools.extend(shared_read_tool_specs());
+    tools.push(ask_user_tool_spec());
     let mut total_usage = BusTokenUsage::default();
     let mut final_output = String::new();
     let mut writes = Vec::new();
+    let mut guard = ToolLoopGuard::default();
 
     for _ in 0..TEAM_MAX_TOOL_LOOPS {
         let response = run_chat_completion(
             state,
             model_reference,
             messages.clone(),
-            if tools.is_empty() {
-                None
-            } else {
-                Some(tools.clone())
-            },
+            Some(tools.clone()),
             Some(camp_id),
         )
         .await?;
@@ -1214,6 +2304,80 @@ async fn run_agent_inference_loop(
             break;
         }
 
+        let question = assistant_tool_calls.iter().find_map(|tool_call| {
+            if parse_tool_call_name(tool_call).as_deref() != Some(ASK_USER_TOOL_NAME) {
+                return None;
+            }
+            parse_tool_call_args(tool_call)
+                .ok()?
+                .get("question")
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|question| !question.is_empty())
+                .map(ToString::to_string)
+        });
+        if question.is_some() {
+            // Answer every pending call so the persisted turn stays replayable.
+            for tool_call in &assistant_tool_calls {
+                messages.push(serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call.get("id").and_then(Value::as_str).unwrap_or_default(),
+                    "name": parse_tool_call_name(tool_call).unwrap_or_default(),
+                    "content": "Step paused until the user answers.",
+                }));
+            }
+            return Ok(AgentRunOutput {
+                output_text: final_output,
+                token_usage: total_usage,
+                context_writes: writes,
+                clarification_question: question,
+                messages,
+                loop_aborted: None,
+            });
+        }
+
+        let turn_tokens = response.usage.total_tokens.unwrap_or_else(|| {
+            response.usage.prompt_tokens.unwrap_or(0)
+                + response.usage.completion_tokens.unwrap_or(0)
+        });
+        let abort = guard
+            .record_tokens(turn_tokens.max(0) as u64)
+            .err()
+            .or_else(|| {
+                assistant_tool_calls.iter().find_map(|tool_call| {
+                    let name = parse_tool_call_name(tool_call).unwrap_or_default();
+                    let arguments = match tool_call.pointer("/function/arguments") {
+                        Some(Value::String(arguments)) => arguments.clone(),
+                        Some(other) => other.to_string(),
+                        None => String::new(),
+                    };
+                    guard.record_call(&name, &arguments).err()
+                })
+            });
+        if let Some(abort) = abort {
+            let message = abort.message();
+            for tool_call in &assistant_tool_calls {
+                messages.push(serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call.get("id").and_then(Value::as_str).unwrap_or_default(),
+                    "name": parse_tool_call_name(tool_call).unwrap_or_default(),
+                    "content": serde_json::json!({ "error": message }).to_string(),
+                }));
+            }
+            return Ok(AgentRunOutput {
+                output_text: if final_output.is_empty() {
+                    message
+                } else {
+                    final_output
+                },
+                token_usage: total_usage,
+                context_writes: writes,
+                clarification_question: None,
+                messages,
+                loop_aborted: Some(abort),
+            });
+        }
+
         for tool_call in assistant_tool_calls {
             let tool_name =
                 parse_tool_call_name(&tool_call).unwrap_or_else(|| "unknown_tool".to_string());
@@ -1222,7 +2386,7 @@ async fn run_agent_inference_loop(
                 .and_then(Value::as_str)
                 .map(ToString::to_string)
                 .unwrap_or_else(|| format!("tool-{}", Uuid::new_v4()));
-            let result = execute_team_tool_call(context_root, &tool_call, &mut writes);
+            let result = execute_team_tool_call(roots, &tool_call, &mut writes);
 
             messages.push(serde_json::json!({
                 "role": "tool",
@@ -1237,6 +2401,9 @@ async fn run_agent_inference_loop(
         output_text: final_output,
         token_usage: total_usage,
         context_writes: writes,
+        clarification_question: None,
+        messages,
+        loop_aborted: None,
     })
 }
 
@@ -1295,8 +2462,7 @@ fn write_step_draft(
         target = team_drafts_dir(camp_dir).join(&filename);
     }
 
-    fs::write(&target, output_text)
-        .map_err(|err| format!("Unable to write draft artifact: {err}"))?;
+    write_file_atomic(&target, output_text.as_bytes())?;
 
     Ok(format!(
         "{}/{}/{}",
@@ -1316,6 +2482,75 @@ fn parse_agent_tools_file(camp_dir: &Path, agent: &TeamAgentConfig) -> Vec<Strin
     }
 }
 
+fn normalize_rubric(rubric: CritiqueRubric) -> Result<CritiqueRubric, String> {
+    if !rubric.pass_threshold.is_finite()
+        || !(RUBRIC_MIN_SCORE..=RUBRIC_MAX_SCORE).contains(&rubric.pass_threshold)
+    {
+        return Err(format!(
+            "pass_threshold must be between {RUBRIC_MIN_SCORE} and {RUBRIC_MAX_SCORE}."
+        ));
+    }
+    let mut seen = BTreeSet::new();
+    let mut criteria = Vec::new();
+    for criterion in rubric.criteria {
+        let name = criterion.name.trim().to_string();
+        if name.is_empty() || !seen.insert(name.to_lowercase()) {
+            continue;
+        }
+        criteria.push(RubricCriterion {
+            name,
+            description: criterion.description.trim().to_string(),
+        });
+    }
+    if criteria.len() > TEAM_MAX_RUBRIC_CRITERIA {
+        return Err(format!(
+            "A rubric supports at most {TEAM_MAX_RUBRIC_CRITERIA} criteria."
+        ));
+    }
+    Ok(CritiqueRubric {
+        criteria,
+        pass_threshold: rubric.pass_threshold,
+    })
+}
+
+fn render_critic_request(rubric: &CritiqueRubric, artifact: &str) -> String {
+    if rubric.criteria.is_empty() {
+        return format!(
+            "Review this artifact. Output structured critique as JSON with fields: issues: string[], suggestions: string[], pass: boolean.\n\nArtifact:\n\n{artifact}"
+        );
+    }
+    let criteria = rubric
+        .criteria
+        .iter()
+        .map(|criterion| {
+            if criterion.description.is_empty() {
+                format!("- {}", criterion.name)
+            } else {
+                format!("- {}: {}", criterion.name, criterion.description)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Review this artifact against the rubric below. Score every criterion from {RUBRIC_MIN_SCORE} to {RUBRIC_MAX_SCORE}; the artifact passes only if each score is at least {threshold}.\n\nRubric:\n{criteria}\n\nOutput structured critique as JSON with fields: scores: {{criterion: string, score: number, comment: string}}[] (one per criterion, using the names above), issues: string[], suggestions: string[], pass: boolean.\n\nArtifact:\n\n{artifact}",
+        threshold = rubric.pass_threshold,
+    )
+}
+
+/// With a rubric, the verdict comes from the scores rather than the critic's
+/// own `pass`: every criterion must be scored and reach the threshold.
+fn apply_rubric(rubric: &CritiqueRubric, critique: &mut CritiqueResult) {
+    if rubric.criteria.is_empty() {
+        return;
+    }
+    critique.pass = rubric.criteria.iter().all(|criterion| {
+        critique.scores.iter().any(|score| {
+            score.criterion.trim().eq_ignore_ascii_case(&criterion.name)
+                && score.score >= rubric.pass_threshold
+        })
+    });
+}
+
 fn find_agent<'a>(team_config: &'a TeamConfig, agent_id: &str) -> Option<&'a TeamAgentConfig> {
     team_config.agents.iter().find(|agent| agent.id == agent_id)
 }
@@ -1358,6 +2593,18 @@ fn parse_draft_path(camp_dir: &Path, artifact_path: &str) -> Result<PathBuf, Str
     Ok(canonical)
 }
 
+/// The `artifacts/drafts/...` form of a resolved draft path.
+fn draft_display_path(camp_dir: &Path, draft_full_path: &Path) -> Result<String, String> {
+    let drafts_root = fs::canonicalize(team_drafts_dir(camp_dir))
+        .map_err(|err| format!("Unable to resolve drafts folder: {err}"))?;
+    Ok(format!(
+        "{}/{}/{}",
+        TEAM_ARTIFACTS_DIR_NAME,
+        TEAM_DRAFTS_DIR_NAME,
+        to_relative_display(&drafts_root, draft_full_path)?
+    ))
+}
+
 fn unique_promoted_target(camp_dir: &Path, source_filename: &str) -> PathBuf {
     let promoted_dir = team_promoted_dir(camp_dir);
     let initial = promoted_dir.join(source_filename);
@@ -1470,7 +2717,7 @@ pub fn create_team_agent(
     camp_id: String,
     agent_config: TeamAgentCreateInput,
     state: State<'_, AppState>,
-) -> Result<AgentMeta, String> {
+) -> CommandResult<AgentMeta> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
     let mut team_config = load_team_config(&camp_dir)?;
 
@@ -1478,19 +2725,20 @@ pub fn create_team_agent(
     if team_config.agents.len() >= TEAM_MAX_AGENTS
         && !team_config.agents.iter().any(|agent| agent.id == agent_id)
     {
-        return Err(format!(
+        return Err(BasecampError::validation(format!(
             "A team can have at most {TEAM_MAX_AGENTS} agents. Remove one before adding another."
-        ));
+        ))
+        .with_context("limit", TEAM_MAX_AGENTS));
     }
 
     let role = agent_config.role.trim();
     if role.is_empty() {
-        return Err("agent_config.role is required.".to_string());
+        return Err(BasecampError::validation("agent_config.role is required."));
     }
 
     let model = agent_config.model.trim();
     if model.is_empty() {
-        return Err("agent_config.model is required.".to_string());
+        return Err(BasecampError::validation("agent_config.model is required."));
     }
 
     let normalized = TeamAgentConfig {
@@ -1499,6 +2747,7 @@ pub fn create_team_agent(
         model: model.to_string(),
         tool_subset: normalize_tool_subset(&agent_config.tool_subset),
         description: agent_config.description.trim().to_string(),
+        continue_context: agent_config.continue_context,
     };
 
     if let Some(existing) = team_config
@@ -1524,6 +2773,7 @@ pub fn create_team_agent(
         model: normalized.model.clone(),
         tool_subset: normalized.tool_subset.clone(),
         description: normalized.description.clone(),
+        continue_context: normalized.continue_context,
         path: agent_dir(&camp_dir, &normalized.id)
             .to_string_lossy()
             .into_owned(),
@@ -1531,11 +2781,77 @@ pub fn create_team_agent(
 }
 
 #[tauri::command]
-pub fn remove_team_agent(
+pub fn list_team_templates() -> CommandResult<Vec<TeamTemplate>> {
+    load_team_templates()
+}
+
+/// Scaffolds a whole team from a bundled template: team.json, every agent's
+/// folder and tools, and the supervisor and agent prompts. Only applies to a
+/// team without agents, since it replaces the supervisor prompt.
+#[tauri::command]
+pub fn create_team_from_template(
+    camp_id: String,
+    template_id: String,
+    model: Option<String>,
+    state: State<'_, AppState>,
+) -> CommandResult<TeamStatus> {
+    let template = load_team_templates()?
+        .into_iter()
+        .find(|template| template.id == template_id)
+        .ok_or_else(|| {
+            BasecampError::not_found(format!("No team template `{template_id}`"))
+                .with_context("template_id", template_id.clone())
+        })?;
+
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let existing = load_team_config(&camp_dir)?;
+    if !existing.agents.is_empty() {
+        return Err(BasecampError::validation(
+            "This team already has agents. Remove them before applying a template.",
+        )
+        .with_context("template_id", template.id));
+    }
+
+    let model = model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .unwrap_or(&existing.supervisor_model)
+        .to_string();
+    let team_config = team_config_from_template(&template, &model);
+    save_team_config(&camp_dir, &team_config)?;
+
+    write_file_atomic(
+        &supervisor_prompt_path(&camp_dir),
+        with_guidance(
+            default_supervisor_prompt(),
+            "Team guidance",
+            &template.supervisor_guidance,
+        )
+        .as_bytes(),
+    )?;
+    for (agent, template_agent) in team_config.agents.iter().zip(&template.agents) {
+        write_file_atomic(
+            &agent_prompt_path(&camp_dir, &agent.id),
+            with_guidance(
+                default_agent_prompt(agent),
+                "Role guidance",
+                &template_agent.instructions,
+            )
+            .as_bytes(),
+        )?;
+        write_json_file(&agent_tools_path(&camp_dir, &agent.id), &agent.tool_subset)?;
+    }
+
+    get_team_status(camp_id, state)
+}
+
+#[tauri::command]
+pub fn remove_team_agent(
     camp_id: String,
     agent_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
     let mut team_config = load_team_config(&camp_dir)?;
     let normalized_id = validate_simple_identifier(&agent_id, "agent_id")?;
@@ -1544,7 +2860,8 @@ pub fn remove_team_agent(
     team_config.agents.retain(|agent| agent.id != normalized_id);
 
     if team_config.agents.len() == original_len {
-        return Err("Agent not found in team roster.".to_string());
+        return Err(BasecampError::not_found("Agent not found in team roster.")
+            .with_context("agent_id", normalized_id));
     }
 
     let target_dir = agent_dir(&camp_dir, &normalized_id);
@@ -1553,7 +2870,7 @@ pub fn remove_team_agent(
             .map_err(|err| format!("Unable to remove agent folder: {err}"))?;
     }
 
-    save_team_config(&camp_dir, &team_config)
+    Ok(save_team_config(&camp_dir, &team_config)?)
 }
 
 #[tauri::command]
@@ -1561,18 +2878,24 @@ pub fn update_team_settings(
     camp_id: String,
     settings: TeamSettingsUpdateInput,
     state: State<'_, AppState>,
-) -> Result<TeamStatus, String> {
+) -> CommandResult<TeamStatus> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
     let mut team_config = load_team_config(&camp_dir)?;
 
     let supervisor_model = settings.supervisor_model.trim();
     if supervisor_model.is_empty() {
-        return Err("supervisor_model is required.".to_string());
+        return Err(BasecampError::validation("supervisor_model is required."));
     }
 
     team_config.supervisor_model = supervisor_model.to_string();
     team_config.reflection_loops = settings.reflection_loops;
     team_config.max_reflection_rounds = settings.max_reflection_rounds.clamp(1, 8);
+    if let Some(mirror_to_transcript) = settings.mirror_to_transcript {
+        team_config.mirror_to_transcript = mirror_to_transcript;
+    }
+    if let Some(rubric) = settings.critique_rubric {
+        team_config.critique_rubric = normalize_rubric(rubric)?;
+    }
 
     save_team_config(&camp_dir, &team_config)?;
     get_team_status(camp_id, state)
@@ -1584,42 +2907,90 @@ pub async fn decompose_task(
     user_task: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<DecompositionPlan, String> {
+) -> CommandResult<DecompositionPlan> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
-    let team_config = load_team_config(&camp_dir)?;
+    load_team_config(&camp_dir)?;
+    begin_run_for_new_task(&camp_dir)?;
+    run_decomposition(state.inner(), &app, &camp_id, &camp_dir, &user_task).await
+}
+
+async fn run_decomposition(
+    state: &AppState,
+    app: &AppHandle,
+    camp_id: &str,
+    camp_dir: &Path,
+    user_task: &str,
+) -> CommandResult<DecompositionPlan> {
+    let team_config = load_team_config(camp_dir)?;
 
     if team_config.agents.is_empty() {
-        return Err("Team has no agents. Add at least one agent before decomposition.".to_string());
+        return Err(BasecampError::validation(
+            "Team has no agents. Add at least one agent before decomposition.",
+        ));
     }
 
     let user_task_trimmed = user_task.trim();
     if user_task_trimmed.is_empty() {
-        return Err("user_task is required.".to_string());
+        return Err(BasecampError::validation("user_task is required."));
     }
 
-    let supervisor_prompt = fs::read_to_string(supervisor_prompt_path(&camp_dir))
+    let supervisor_prompt = fs::read_to_string(supervisor_prompt_path(camp_dir))
         .unwrap_or_else(|_| default_supervisor_prompt());
     let agent_roster = render_agent_roster(&team_config);
     let rendered_prompt = supervisor_prompt.replace("{{agent_roster}}", &agent_roster);
 
+    let clarifications = answered_clarifications(
+        &read_team_bus_entries(camp_dir)?,
+        "supervisor",
+        None,
+        Some(user_task_trimmed),
+    );
     let message_payload = format!(
-        "User task:\n{task}\n\nReturn ONLY valid JSON. Do not wrap in markdown fences.",
-        task = user_task_trimmed
+        "User task:\n{task}{clarifications}\n\nReturn ONLY valid JSON. Do not wrap in markdown fences.",
+        task = user_task_trimmed,
+        clarifications = render_clarifications(&clarifications),
     );
 
     let response = run_chat_completion(
-        state.inner(),
+        state,
         &team_config.supervisor_model,
         vec![
             serde_json::json!({ "role": "system", "content": rendered_prompt }),
             serde_json::json!({ "role": "user", "content": message_payload }),
         ],
         None,
-        Some(&camp_id),
+        Some(camp_id),
     )
     .await?;
 
     let parsed: DecompositionPlan = parse_json_from_output(&response.output_text)?;
+    if let Some(question) = parsed
+        .clarification_question
+        .as_deref()
+        .map(str::trim)
+        .filter(|question| !question.is_empty())
+    {
+        let entry = make_bus_entry(
+            BusEntryType::ClarificationRequest,
+            "supervisor",
+            "user",
+            None,
+            serde_json::json!({
+                "question": question,
+                "user_task": user_task_trimmed,
+            }),
+            usage_to_bus(&response.usage),
+        );
+        append_team_bus_entry(camp_dir, &entry, Some(app))?;
+        return Ok(DecompositionPlan {
+            task_summary: user_task_trimmed.to_string(),
+            steps: Vec::new(),
+            reflection_required: false,
+            clarification_question: None,
+            clarification: clarification_from_entry(&entry),
+        });
+    }
+
     let plan = validate_decomposition_plan(&team_config, parsed)?;
 
     let decomposition_entry = make_bus_entry(
@@ -1630,7 +3001,7 @@ pub async fn decompose_task(
         serde_json::to_value(&plan).map_err(|err| format!("Unable to serialize plan: {err}"))?,
         usage_to_bus(&response.usage),
     );
-    append_team_bus_entry(&camp_dir, &decomposition_entry, Some(&app))?;
+    append_team_bus_entry(camp_dir, &decomposition_entry, Some(app))?;
 
     for step in &plan.steps {
         let delegation_entry = make_bus_entry(
@@ -1645,9 +3016,11 @@ pub async fn decompose_task(
             }),
             BusTokenUsage::default(),
         );
-        append_team_bus_entry(&camp_dir, &delegation_entry, Some(&app))?;
+        append_team_bus_entry(camp_dir, &delegation_entry, Some(app))?;
     }
 
+    mirror_to_camp_transcript(camp_dir, &team_config, mirrored_plan_messages(&plan))?;
+
     Ok(plan)
 }
 
@@ -1658,26 +3031,54 @@ pub async fn execute_agent_step(
     step: DelegationStep,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<AgentStepResult, String> {
+) -> CommandResult<AgentStepResult> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
-    let team_config = load_team_config(&camp_dir)?;
+    run_agent_step(state.inner(), &app, &camp_id, &camp_dir, &agent_id, step).await
+}
+
+async fn run_agent_step(
+    state: &AppState,
+    app: &AppHandle,
+    camp_id: &str,
+    camp_dir: &Path,
+    agent_id: &str,
+    step: DelegationStep,
+) -> CommandResult<AgentStepResult> {
+    let team_config = load_team_config(camp_dir)?;
 
-    let normalized_agent_id = validate_simple_identifier(&agent_id, "agent_id")?;
-    let agent = find_agent(&team_config, &normalized_agent_id)
-        .ok_or_else(|| "Agent not found in team roster.".to_string())?;
+    let normalized_agent_id = validate_simple_identifier(agent_id, "agent_id")?;
+    let agent = find_agent(&team_config, &normalized_agent_id).ok_or_else(|| {
+        BasecampError::not_found("Agent not found in team roster.")
+            .with_context("agent_id", normalized_agent_id.as_str())
+    })?;
 
     if step.assigned_to != normalized_agent_id {
-        return Err("Requested agent does not match step.assigned_to.".to_string());
+        return Err(BasecampError::validation(
+            "Requested agent does not match step.assigned_to.",
+        ));
     }
 
     let step_id = validate_simple_identifier(&step.step_id, "step.step_id")?;
-    let prompt = fs::read_to_string(agent_prompt_path(&camp_dir, &agent.id))
+    let started_at = now_iso8601();
+    let prompt = fs::read_to_string(agent_prompt_path(camp_dir, &agent.id))
         .unwrap_or_else(|_| default_agent_prompt(agent));
-    let tool_subset = parse_agent_tools_file(&camp_dir, agent);
-    let context_root = agent_context_root(&camp_dir, &agent.id)?;
+    let tool_subset = parse_agent_tools_file(camp_dir, agent);
+    let roots = agent_tool_roots(camp_dir, &agent.id, &tool_subset)?;
+    let bus_entries = read_team_bus_entries(camp_dir)?;
+    let plan_id = latest_plan_id(&bus_entries);
+    let clarifications = answered_clarifications(&bus_entries, &agent.id, Some(&step_id), None);
+    let history = if agent.continue_context {
+        resumable_history(
+            &read_agent_transcript(camp_dir, &agent.id)?,
+            plan_id.as_deref(),
+            &step_id,
+        )
+    } else {
+        Vec::new()
+    };
 
     let user_instruction = format!(
-        "Delegation step id: {step_id}\nAssigned role: {role}\n\nInstruction:\n{instruction}\n\nDependencies: {depends_on}\n\nExpected output:\n{expected_output}\n\nWhen complete, provide the final result text for this step.",
+        "Delegation step id: {step_id}\nAssigned role: {role}\n\nInstruction:\n{instruction}\n\nDependencies: {depends_on}\n\nExpected output:\n{expected_output}{clarifications}\n\nWhen complete, provide the final result text for this step.",
         step_id = step_id,
         role = agent.role,
         instruction = step.instruction.trim(),
@@ -1687,22 +3088,57 @@ pub async fn execute_agent_step(
             step.depends_on.join(", ")
         },
         expected_output = step.expected_output.trim(),
+        clarifications = render_clarifications(&clarifications),
     );
 
-    let run_output = run_agent_inference_loop(
-        state.inner(),
-        &camp_id,
-        &agent.model,
-        vec![
-            serde_json::json!({ "role": "system", "content": prompt }),
-            serde_json::json!({ "role": "user", "content": user_instruction }),
-        ],
-        &tool_subset,
-        &context_root,
-    )
-    .await?;
+    let history_len = history.len();
+    let mut messages = Vec::with_capacity(history_len + 2);
+    messages.push(serde_json::json!({ "role": "system", "content": prompt }));
+    messages.extend(history);
+    messages.push(serde_json::json!({ "role": "user", "content": user_instruction }));
+
+    let run_output =
+        run_agent_inference_loop(state, camp_id, &agent.model, messages, &tool_subset, &roots)
+            .await?;
+
+    // Replayed history is already on disk; record the system prompt and this step's turns.
+    let mut step_messages = vec![run_output.messages[0].clone()];
+    step_messages.extend_from_slice(&run_output.messages[1 + history_len..]);
+    append_agent_transcript(
+        camp_dir,
+        &agent.id,
+        plan_id.as_deref(),
+        &step_id,
+        &step_messages,
+    )?;
+
+    if let Some(question) = run_output.clarification_question {
+        let entry = make_bus_entry(
+            BusEntryType::ClarificationRequest,
+            &agent.id,
+            "user",
+            Some(&step_id),
+            serde_json::json!({
+                "question": question,
+                "step": step,
+                "started_at": started_at,
+            }),
+            run_output.token_usage.clone(),
+        );
+        append_team_bus_entry(camp_dir, &entry, Some(app))?;
+        return Ok(AgentStepResult {
+            step_id,
+            agent_id: agent.id.clone(),
+            output_text: String::new(),
+            draft_path: String::new(),
+            context_writes: run_output.context_writes,
+            token_usage: run_output.token_usage,
+            clarification: clarification_from_entry(&entry),
+            loop_aborted: None,
+        });
+    }
 
-    let draft_path = write_step_draft(&camp_dir, &step, &agent.id, &run_output.output_text)?;
+    let draft_path = write_step_draft(camp_dir, &step, &agent.id, &run_output.output_text)?;
 
     let result = AgentStepResult {
         step_id: step_id.clone(),
@@ -1711,6 +3147,8 @@ pub async fn execute_agent_step(
         draft_path: draft_path.clone(),
         context_writes: run_output.context_writes.clone(),
         token_usage: run_output.token_usage.clone(),
+        clarification: None,
+        loop_aborted: run_output.loop_aborted.clone(),
     };
 
     let bus_entry = make_bus_entry(
@@ -1722,17 +3160,100 @@ pub async fn execute_agent_step(
             "output_text": run_output.output_text,
             "draft_path": draft_path,
             "context_writes": run_output.context_writes,
+            "started_at": started_at,
+            "loop_aborted": run_output.loop_aborted,
         }),
         run_output.token_usage,
     );
-    append_team_bus_entry(&camp_dir, &bus_entry, Some(&app))?;
+    append_team_bus_entry(camp_dir, &bus_entry, Some(app))?;
+    mirror_to_camp_transcript(camp_dir, &team_config, mirrored_step_messages(&result))?;
 
     let _ = app.emit("team://step_complete", result.clone());
-    let _ = crate::touch_camp_updated_at(&camp_dir);
+    let _ = crate::touch_camp_updated_at(camp_dir);
 
     Ok(result)
 }
 
+/// Records the user's reply to a clarification request and resumes whatever
+/// was paused on it: the supervisor re-plans the task, or the agent re-runs
+/// its step with the answer in its instructions.
+#[tauri::command]
+pub async fn answer_clarification(
+    camp_id: String,
+    request_id: String,
+    answer: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> CommandResult<ClarificationResolution> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    load_team_config(&camp_dir)?;
+
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err(BasecampError::validation("answer is required."));
+    }
+
+    let entries = read_team_bus_entries(&camp_dir)?;
+    let request_entry = entries
+        .iter()
+        .find(|entry| {
+            entry.id == request_id && matches!(entry.entry_type, BusEntryType::ClarificationRequest)
+        })
+        .ok_or_else(|| {
+            BasecampError::not_found("Clarification request not found.")
+                .with_context("request_id", request_id.as_str())
+        })?;
+    if clarification_answers(&entries).contains_key(&request_id) {
+        return Err(
+            BasecampError::validation("Clarification request was already answered.")
+                .with_context("request_id", request_id.as_str()),
+        );
+    }
+
+    let answer_entry = make_bus_entry(
+        BusEntryType::ClarificationAnswer,
+        "user",
+        &request_entry.from,
+        request_entry.step_id.as_deref(),
+        serde_json::json!({
+            "request_id": request_id,
+            "answer": answer,
+        }),
+        BusTokenUsage::default(),
+    );
+    append_team_bus_entry(&camp_dir, &answer_entry, Some(&app))?;
+
+    if request_entry.from == "supervisor" {
+        let user_task = request_entry
+            .content
+            .get("user_task")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let plan = run_decomposition(state.inner(), &app, &camp_id, &camp_dir, user_task).await?;
+        return Ok(ClarificationResolution::Plan { plan });
+    }
+
+    let step = request_entry
+        .content
+        .get("step")
+        .cloned()
+        .and_then(|value| serde_json::from_value::<DelegationStep>(value).ok())
+        .ok_or_else(|| {
+            BasecampError::internal("Clarification request is missing its delegation step.")
+                .with_context("request_id", request_id.as_str())
+        })?;
+    let result = run_agent_step(
+        state.inner(),
+        &app,
+        &camp_id,
+        &camp_dir,
+        &request_entry.from,
+        step,
+    )
+    .await?;
+    Ok(ClarificationResolution::Step { result })
+}
+
 async fn run_agent_single_prompt(
     state: &AppState,
     camp_id: &str,
@@ -1758,38 +3279,49 @@ async fn run_agent_single_prompt(
     ))
 }
 
-#[tauri::command]
-pub async fn run_reflection_loop(
-    camp_id: String,
-    artifact_path: String,
-    rounds: u8,
-    state: State<'_, AppState>,
-    app: AppHandle,
-) -> Result<ReflectionSummary, String> {
-    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
-    let team_config = load_team_config(&camp_dir)?;
-
-    let writer = find_agent_by_role(&team_config, "writer").ok_or_else(|| {
-        "Team is missing a Writer agent required for reflection loop.".to_string()
+fn reflection_agents(
+    team_config: &TeamConfig,
+) -> CommandResult<(&TeamAgentConfig, &TeamAgentConfig)> {
+    let writer = find_agent_by_role(team_config, "writer").ok_or_else(|| {
+        BasecampError::validation("Team is missing a Writer agent required for reflection loop.")
     })?;
-    let critic = find_agent_by_role(&team_config, "critic").ok_or_else(|| {
-        "Team is missing a Critic agent required for reflection loop.".to_string()
+    let critic = find_agent_by_role(team_config, "critic").ok_or_else(|| {
+        BasecampError::validation("Team is missing a Critic agent required for reflection loop.")
     })?;
+    Ok((writer, critic))
+}
 
+fn requested_reflection_rounds(team_config: &TeamConfig, rounds: u8) -> u8 {
     let max_rounds = team_config.max_reflection_rounds.max(1);
-    let requested_rounds = if rounds == 0 {
+    if rounds == 0 {
         max_rounds
     } else {
         rounds.min(max_rounds)
-    };
+    }
+}
+
+/// Critiques and revises one draft in place; promotion is left to the caller.
+/// Critique entries carry the draft's path so `promote_all_passing` can find
+/// each draft's final verdict.
+async fn reflect_on_draft(
+    state: &AppState,
+    app: &AppHandle,
+    camp_id: &str,
+    camp_dir: &Path,
+    team_config: &TeamConfig,
+    artifact_path: &str,
+    requested_rounds: u8,
+) -> CommandResult<DraftReflection> {
+    let (writer, critic) = reflection_agents(team_config)?;
 
-    let draft_path = parse_draft_path(&camp_dir, &artifact_path)?;
+    let draft_path = parse_draft_path(camp_dir, artifact_path)?;
+    let artifact_path = draft_display_path(camp_dir, &draft_path)?;
     let mut artifact_body = fs::read_to_string(&draft_path)
         .map_err(|err| format!("Unable to read draft artifact: {err}"))?;
 
-    let writer_prompt = fs::read_to_string(agent_prompt_path(&camp_dir, &writer.id))
+    let writer_prompt = fs::read_to_string(agent_prompt_path(camp_dir, &writer.id))
         .unwrap_or_else(|_| default_agent_prompt(writer));
-    let critic_prompt = fs::read_to_string(agent_prompt_path(&camp_dir, &critic.id))
+    let critic_prompt = fs::read_to_string(agent_prompt_path(camp_dir, &critic.id))
         .unwrap_or_else(|_| default_agent_prompt(critic));
 
     let mut critiques = Vec::new();
@@ -1799,20 +3331,18 @@ pub async fn run_reflection_loop(
     for round in 1..=requested_rounds {
         rounds_completed = round;
 
-        let critic_request = format!(
-            "Review this artifact. Output structured critique as JSON with fields: issues: string[], suggestions: string[], pass: boolean.\n\nArtifact:\n\n{}",
-            artifact_body
-        );
+        let critic_request = render_critic_request(&team_config.critique_rubric, &artifact_body);
         let (critique_raw, critique_usage) = run_agent_single_prompt(
-            state.inner(),
-            &camp_id,
+            state,
+            camp_id,
             &critic.model,
             critic_prompt.clone(),
             critic_request,
         )
         .await?;
 
-        let critique = parse_json_from_output::<CritiqueResult>(&critique_raw)?;
+        let mut critique = parse_json_from_output::<CritiqueResult>(&critique_raw)?;
+        apply_rubric(&team_config.critique_rubric, &mut critique);
         critiques.push(critique.clone());
 
         let critique_entry = make_bus_entry(
@@ -1822,13 +3352,15 @@ pub async fn run_reflection_loop(
             None,
             serde_json::json!({
                 "round": round,
+                "artifact_path": artifact_path,
                 "issues": critique.issues,
                 "suggestions": critique.suggestions,
+                "scores": critique.scores,
                 "pass": critique.pass,
             }),
             critique_usage,
         );
-        append_team_bus_entry(&camp_dir, &critique_entry, Some(&app))?;
+        append_team_bus_entry(camp_dir, &critique_entry, Some(app))?;
 
         let _ = app.emit(
             "team://reflection_round",
@@ -1855,8 +3387,8 @@ pub async fn run_reflection_loop(
         );
 
         let (writer_output, writer_usage) = run_agent_single_prompt(
-            state.inner(),
-            &camp_id,
+            state,
+            camp_id,
             &writer.model,
             writer_prompt.clone(),
             writer_request,
@@ -1864,8 +3396,7 @@ pub async fn run_reflection_loop(
         .await?;
 
         artifact_body = writer_output.clone();
-        fs::write(&draft_path, &artifact_body)
-            .map_err(|err| format!("Unable to update draft during reflection: {err}"))?;
+        write_file_atomic(&draft_path, artifact_body.as_bytes())?;
 
         let writer_entry = make_bus_entry(
             BusEntryType::Result,
@@ -1879,94 +3410,329 @@ pub async fn run_reflection_loop(
             }),
             writer_usage,
         );
-        append_team_bus_entry(&camp_dir, &writer_entry, Some(&app))?;
+        append_team_bus_entry(camp_dir, &writer_entry, Some(app))?;
+    }
+
+    Ok(DraftReflection {
+        artifact_path,
+        draft_path,
+        critiques,
+        pass,
+        rounds_completed,
+    })
+}
+
+/// Moves a draft into `promoted/`, records it on the bus and in the camp
+/// transcript, and notifies the UI. `outcome` is the reflection verdict and
+/// round count when the promotion follows a critique.
+fn promote_draft(
+    camp_dir: &Path,
+    team_config: &TeamConfig,
+    draft_path: &Path,
+    outcome: Option<(bool, u8)>,
+    app: &AppHandle,
+) -> Result<(String, String), String> {
+    let (from_path, promoted_path) = move_draft_to_promoted(camp_dir, draft_path)?;
+    let mut details = serde_json::json!({
+        "from": from_path,
+        "to": promoted_path,
+    });
+    if let Some((pass, rounds_completed)) = outcome {
+        details["pass"] = Value::Bool(pass);
+        details["rounds_completed"] = Value::from(rounds_completed);
     }
 
-    let (from_path, promoted_path) = move_draft_to_promoted(&camp_dir, &draft_path)?;
-    let promotion_entry = make_bus_entry(
+    let entry = make_bus_entry(
         BusEntryType::Promotion,
         "supervisor",
         "all",
         None,
-        serde_json::json!({
-            "from": from_path,
-            "to": promoted_path,
-            "pass": pass,
-            "rounds_completed": rounds_completed,
-        }),
+        details.clone(),
         BusTokenUsage::default(),
     );
-    append_team_bus_entry(&camp_dir, &promotion_entry, Some(&app))?;
+    append_team_bus_entry(camp_dir, &entry, Some(app))?;
+    mirror_to_camp_transcript(
+        camp_dir,
+        team_config,
+        mirrored_promotion_messages(&from_path, &promoted_path),
+    )?;
 
-    let _ = app.emit(
-        "team://artifact_promoted",
-        serde_json::json!({
-            "from": from_path,
-            "to": promoted_path,
-            "pass": pass,
-            "rounds_completed": rounds_completed,
-        }),
-    );
+    let _ = app.emit("team://artifact_promoted", details);
+    let _ = crate::touch_camp_updated_at(camp_dir);
+
+    Ok((from_path, promoted_path))
+}
+
+/// Drafts written by the latest plan's steps, in the order they were produced.
+fn plan_draft_paths(entries: &[BusEntry]) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    latest_run_entries(entries)
+        .iter()
+        .filter(|entry| matches!(entry.entry_type, BusEntryType::Result) && entry.step_id.is_some())
+        .filter_map(|entry| entry.content.get("draft_path").and_then(Value::as_str))
+        .filter(|path| !path.is_empty() && seen.insert(path.to_string()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Drafts whose most recent critique passed, with the round it passed in.
+fn passing_draft_paths(entries: &[BusEntry]) -> Vec<(String, u8)> {
+    let mut latest = BTreeMap::<String, (bool, u8)>::new();
+    for entry in entries {
+        if !matches!(entry.entry_type, BusEntryType::Critique) {
+            continue;
+        }
+        let Some(path) = entry.content.get("artifact_path").and_then(Value::as_str) else {
+            continue;
+        };
+        let pass = entry
+            .content
+            .get("pass")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let round = entry
+            .content
+            .get("round")
+            .and_then(Value::as_u64)
+            .and_then(|round| u8::try_from(round).ok())
+            .unwrap_or(0);
+        latest.insert(path.to_string(), (pass, round));
+    }
+    latest
+        .into_iter()
+        .filter(|(_, (pass, _))| *pass)
+        .map(|(path, (_, round))| (path, round))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn run_reflection_loop(
+    camp_id: String,
+    artifact_path: String,
+    rounds: u8,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> CommandResult<ReflectionSummary> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let team_config = load_team_config(&camp_dir)?;
 
-    let _ = crate::touch_camp_updated_at(&camp_dir);
+    let reflection = reflect_on_draft(
+        state.inner(),
+        &app,
+        &camp_id,
+        &camp_dir,
+        &team_config,
+        &artifact_path,
+        requested_reflection_rounds(&team_config, rounds),
+    )
+    .await?;
+
+    let (from_path, promoted_path) = promote_draft(
+        &camp_dir,
+        &team_config,
+        &reflection.draft_path,
+        Some((reflection.pass, reflection.rounds_completed)),
+        &app,
+    )?;
 
     Ok(ReflectionSummary {
         artifact_path: from_path,
         promoted_path,
-        rounds_completed,
-        pass,
-        critiques,
+        rounds_completed: reflection.rounds_completed,
+        pass: reflection.pass,
+        critiques: reflection.critiques,
     })
 }
 
+/// Reflects over every draft the latest plan produced that is still in
+/// `drafts/`, without promoting any of them. A failing draft is reported in
+/// its item and does not stop the others.
 #[tauri::command]
-pub fn get_team_bus(camp_id: String, state: State<'_, AppState>) -> Result<Vec<BusEntry>, String> {
+pub async fn run_reflection_batch(
+    camp_id: String,
+    rounds: u8,
+    parallel: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> CommandResult<Vec<DraftReflectionItem>> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
-    load_team_config(&camp_dir)?;
-    read_team_bus_entries(&camp_dir)
+    let team_config = load_team_config(&camp_dir)?;
+    reflection_agents(&team_config)?;
+    let requested_rounds = requested_reflection_rounds(&team_config, rounds);
+
+    let draft_paths: Vec<String> = plan_draft_paths(&read_team_bus_entries(&camp_dir)?)
+        .into_iter()
+        .filter(|path| parse_draft_path(&camp_dir, path).is_ok())
+        .collect();
+
+    // Futures are created lazily, so the sequential branch starts each draft
+    // only after the previous one finished.
+    let reflections = draft_paths.iter().map(|path| {
+        reflect_on_draft(
+            state.inner(),
+            &app,
+            &camp_id,
+            &camp_dir,
+            &team_config,
+            path,
+            requested_rounds,
+        )
+    });
+    let outcomes = if parallel {
+        futures_util::future::join_all(reflections).await
+    } else {
+        let mut outcomes = Vec::with_capacity(draft_paths.len());
+        for reflection in reflections {
+            outcomes.push(reflection.await);
+        }
+        outcomes
+    };
+
+    Ok(draft_paths
+        .into_iter()
+        .zip(outcomes)
+        .map(|(artifact_path, outcome)| match outcome {
+            Ok(reflection) => DraftReflectionItem {
+                artifact_path: reflection.artifact_path,
+                rounds_completed: reflection.rounds_completed,
+                pass: reflection.pass,
+                critiques: reflection.critiques,
+                error: None,
+            },
+            Err(err) => DraftReflectionItem {
+                artifact_path,
+                rounds_completed: 0,
+                pass: false,
+                critiques: Vec::new(),
+                error: Some(err.to_string()),
+            },
+        })
+        .collect())
 }
 
+/// Promotes every draft still in `drafts/` whose final critique passed and
+/// returns the promoted paths.
 #[tauri::command]
-pub fn promote_artifact(
+pub fn promote_all_passing(
     camp_id: String,
-    draft_path: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<String, String> {
+) -> CommandResult<Vec<String>> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let team_config = load_team_config(&camp_dir)?;
+
+    let mut promoted = Vec::new();
+    for (artifact_path, round) in passing_draft_paths(&read_team_bus_entries(&camp_dir)?) {
+        // Drafts promoted or removed since their critique are skipped.
+        let Ok(draft_path) = parse_draft_path(&camp_dir, &artifact_path) else {
+            continue;
+        };
+        let (_, promoted_path) = promote_draft(
+            &camp_dir,
+            &team_config,
+            &draft_path,
+            Some((true, round)),
+            &app,
+        )?;
+        promoted.push(promoted_path);
+    }
+    Ok(promoted)
+}
+
+#[tauri::command]
+pub fn get_agent_transcript(
+    camp_id: String,
+    agent_id: String,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<AgentTranscriptEntry>> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let team_config = load_team_config(&camp_dir)?;
+    let normalized_id = validate_simple_identifier(&agent_id, "agent_id")?;
+    if find_agent(&team_config, &normalized_id).is_none() {
+        return Err(BasecampError::not_found("Agent not found in team roster.")
+            .with_context("agent_id", normalized_id));
+    }
+    Ok(read_agent_transcript(&camp_dir, &normalized_id)?)
+}
+
+/// Summarizes the latest run's token usage, estimated cost and step timings.
+#[tauri::command]
+pub fn get_team_run_report(
+    camp_id: String,
+    state: State<'_, AppState>,
+) -> CommandResult<TeamRunReport> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let team_config = load_team_config(&camp_dir)?;
+    let entries = read_team_bus_entries(&camp_dir)?;
+    let pricing = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        participant_pricing(&connection, &team_config)?
+    };
+    Ok(build_run_report(&entries, &team_config, &pricing))
+}
+
+/// Bus entries of the active run, or of `run_id` when given.
+#[tauri::command]
+pub fn get_team_bus(
+    camp_id: String,
+    run_id: Option<String>,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<BusEntry>> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
     load_team_config(&camp_dir)?;
+    match run_id {
+        Some(run_id) => {
+            let run_id = validate_simple_identifier(&run_id, "run_id")?;
+            Ok(read_team_run_entries(&camp_dir, &run_id)?)
+        }
+        None => Ok(read_team_bus_entries(&camp_dir)?),
+    }
+}
 
-    let source = parse_draft_path(&camp_dir, &draft_path)?;
-    let (from_path, promoted_path) = move_draft_to_promoted(&camp_dir, &source)?;
+#[tauri::command]
+pub fn list_team_runs(
+    camp_id: String,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<TeamRunSummary>> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    load_team_config(&camp_dir)?;
+    Ok(list_team_run_summaries(&camp_dir)?)
+}
 
-    let entry = make_bus_entry(
-        BusEntryType::Promotion,
-        "supervisor",
-        "all",
-        None,
-        serde_json::json!({
-            "from": from_path,
-            "to": promoted_path,
-        }),
-        BusTokenUsage::default(),
-    );
-    append_team_bus_entry(&camp_dir, &entry, Some(&app))?;
+/// Compresses finished runs, keeping the newest `keep_recent` (default 3)
+/// readable as plain JSONL. Archived runs stay readable through `get_team_bus`.
+#[tauri::command]
+pub fn archive_team_runs(
+    camp_id: String,
+    keep_recent: Option<usize>,
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<String>> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    load_team_config(&camp_dir)?;
+    Ok(archive_inactive_runs(
+        &camp_dir,
+        keep_recent.unwrap_or(TEAM_DEFAULT_KEEP_RECENT_RUNS),
+    )?)
+}
 
-    let _ = app.emit(
-        "team://artifact_promoted",
-        serde_json::json!({
-            "from": from_path,
-            "to": promoted_path,
-        }),
-    );
+#[tauri::command]
+pub fn promote_artifact(
+    camp_id: String,
+    draft_path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> CommandResult<String> {
+    let camp_dir = resolve_camp_dir(&state, &camp_id)?;
+    let team_config = load_team_config(&camp_dir)?;
 
-    let _ = crate::touch_camp_updated_at(&camp_dir);
+    let source = parse_draft_path(&camp_dir, &draft_path)?;
+    let (_, promoted_path) = promote_draft(&camp_dir, &team_config, &source, None, &app)?;
 
     Ok(promoted_path)
 }
 
 #[tauri::command]
-pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<TeamStatus, String> {
+pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> CommandResult<TeamStatus> {
     let camp_dir = resolve_camp_dir(&state, &camp_id)?;
     let camp_config = read_camp_config(&camp_dir)?;
 
@@ -1976,10 +3742,13 @@ pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<Te
             supervisor_model: String::new(),
             reflection_loops: false,
             max_reflection_rounds: TEAM_DEFAULT_MAX_REFLECTION_ROUNDS,
+            mirror_to_transcript: false,
+            critique_rubric: CritiqueRubric::default(),
             agents: Vec::new(),
             steps: Vec::new(),
             bus_entries: 0,
             artifacts: TeamArtifactsStatus::default(),
+            pending_clarifications: Vec::new(),
         });
     }
 
@@ -2023,6 +3792,12 @@ pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<Te
                 BusEntryType::Result => {
                     step_status_map.insert(step_id.clone(), "complete".to_string());
                 }
+                BusEntryType::ClarificationRequest => {
+                    step_status_map.insert(step_id.clone(), "awaiting_clarification".to_string());
+                }
+                BusEntryType::ClarificationAnswer => {
+                    step_status_map.insert(step_id.clone(), "running".to_string());
+                }
                 BusEntryType::Error => {
                     step_status_map.insert(step_id.clone(), "failed".to_string());
                 }
@@ -2053,6 +3828,11 @@ pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<Te
         let last_output_preview = agent_last_output.get(&agent.id).cloned();
 
         let status = if steps
+            .iter()
+            .any(|step| step.assigned_to == agent.id && step.status == "awaiting_clarification")
+        {
+            "waiting"
+        } else if steps
             .iter()
             .any(|step| step.assigned_to == agent.id && step.status == "running")
         {
@@ -2101,6 +3881,8 @@ pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<Te
         supervisor_model: team_config.supervisor_model,
         reflection_loops: team_config.reflection_loops,
         max_reflection_rounds: team_config.max_reflection_rounds,
+        mirror_to_transcript: team_config.mirror_to_transcript,
+        critique_rubric: team_config.critique_rubric,
         agents,
         steps,
         bus_entries: bus_entries.len(),
@@ -2108,5 +3890,422 @@ pub fn get_team_status(camp_id: String, state: State<'_, AppState>) -> Result<Te
             drafts: draft_files,
             promoted: promoted_files,
         },
+        pending_clarifications: pending_clarifications(&bus_entries),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn clarifications_should_pair_answers_and_reset_on_new_plan() {
+        let request = make_bus_entry(
+            BusEntryType::ClarificationRequest,
+            "writer",
+            "user",
+            Some("s1"),
+            serde_json::json!({ "question": "Which audience?" }),
+            BusTokenUsage::default(),
+        );
+        let mut entries = vec![request.clone()];
+        assert_eq!(pending_clarifications(&entries).len(), 1);
+        assert!(answered_clarifications(&entries, "writer", Some("s1"), None).is_empty());
+
+        entries.push(make_bus_entry(
+            BusEntryType::ClarificationAnswer,
+            "user",
+            "writer",
+            Some("s1"),
+            serde_json::json!({ "request_id": request.id, "answer": "Engineers" }),
+            BusTokenUsage::default(),
+        ));
+        assert!(pending_clarifications(&entries).is_empty());
+        assert_eq!(
+            answered_clarifications(&entries, "writer", Some("s1"), None),
+            vec![("Which audience?".to_string(), "Engineers".to_string())]
+        );
+        assert!(render_clarifications(&[("Q1".to_string(), "A1".to_string())]).contains("A: A1"));
+
+        entries.push(make_bus_entry(
+            BusEntryType::Decomposition,
+            "supervisor",
+            "all",
+            None,
+            serde_json::json!({}),
+            BusTokenUsage::default(),
+        ));
+        assert!(answered_clarifications(&entries, "writer", Some("s1"), None).is_empty());
+    }
+
+    #[test]
+    fn resumable_history_should_replay_earlier_steps_of_the_same_plan() {
+        let entry = |plan_id: &str, step_id: &str, role: &str| AgentTranscriptEntry {
+            timestamp: now_iso8601(),
+            plan_id: Some(plan_id.to_string()),
+            step_id: step_id.to_string(),
+            message: serde_json::json!({ "role": role, "content": step_id }),
+        };
+        let transcript = vec![
+            entry("old", "s1", "user"),
+            entry("plan", "s1", "system"),
+            entry("plan", "s1", "user"),
+            entry("plan", "s1", "assistant"),
+            entry("plan", "s2", "user"),
+        ];
+
+        let history = resumable_history(&transcript, Some("plan"), "s2");
+        let roles: Vec<&str> = history
+            .iter()
+            .filter_map(|message| message["role"].as_str())
+            .collect();
+        assert_eq!(roles, vec!["user", "assistant"]);
+        assert!(resumable_history(&transcript, Some("other"), "s2").is_empty());
+    }
+
+    #[test]
+    fn shared_context_should_be_readable_and_write_gated() {
+        let camp_dir = make_temp_dir("basecamp-team");
+        let call = |name: &str, args: Value| {
+            serde_json::json!({
+                "id": "call-1",
+                "function": { "name": name, "arguments": args.to_string() }
+            })
+        };
+        let write = call(
+            WRITE_SHARED_FILE_TOOL_NAME,
+            serde_json::json!({ "path": "notes.md", "content": "shared" }),
+        );
+
+        let reader = agent_tool_roots(&camp_dir, "reader", &[]).expect("roots should resolve");
+        let mut writes = Vec::new();
+        assert!(execute_team_tool_call(&reader, &write, &mut writes).contains("not allowed"));
+        assert!(writes.is_empty());
+
+        let writer = agent_tool_roots(
+            &camp_dir,
+            "writer",
+            &[WRITE_SHARED_FILE_TOOL_NAME.to_string()],
+        )
+        .expect("roots should resolve");
+        execute_team_tool_call(&writer, &write, &mut writes);
+        assert_eq!(writes, vec!["team_context/notes.md".to_string()]);
+
+        let read = call(
+            "read_shared_file",
+            serde_json::json!({ "path": "notes.md" }),
+        );
+        assert!(execute_team_tool_call(&reader, &read, &mut Vec::new()).contains("shared"));
+    }
+
+    #[test]
+    fn team_tool_calls_should_reject_arguments_outside_the_declared_schema() {
+        let camp_dir = make_temp_dir("basecamp-team-schema");
+        let roots = agent_tool_roots(&camp_dir, "reader", &[]).expect("roots should resolve");
+        let invalid = serde_json::json!({
+            "id": "call-1",
+            "function": { "name": "read_shared_file", "arguments": r#"{"path":3}"# }
+        });
+
+        let result: Value =
+            serde_json::from_str(&execute_team_tool_call(&roots, &invalid, &mut Vec::new()))
+                .expect("tool result should be JSON");
+        assert_eq!(result["validation_errors"][0]["path"], "/path");
+        assert_eq!(result["tool"], "read_shared_file");
+        assert_eq!(result["tool_call_id"], "call-1");
+    }
+
+    #[test]
+    fn mirrored_step_messages_should_pair_tool_call_and_result() {
+        let result = AgentStepResult {
+            step_id: "s1".to_string(),
+            agent_id: "writer".to_string(),
+            output_text: "Draft ready".to_string(),
+            draft_path: "artifacts/drafts/s1.md".to_string(),
+            context_writes: Vec::new(),
+            token_usage: BusTokenUsage::default(),
+            clarification: None,
+            loop_aborted: None,
+        };
+
+        let messages = mirrored_step_messages(&result);
+        assert_eq!(messages.len(), 2);
+        let call = &messages[0].tool_calls.as_ref().expect("tool call")[0];
+        assert_eq!(call.function.name, TEAM_STEP_TOOL_NAME);
+        assert_eq!(messages[1].role, "tool");
+        assert_eq!(messages[1].tool_call_id.as_deref(), Some(call.id.as_str()));
+        assert!(messages[1].content.contains("Draft ready"));
+    }
+
+    #[test]
+    fn rubric_should_render_criteria_and_gate_pass_on_scores() {
+        let rubric = normalize_rubric(CritiqueRubric {
+            criteria: vec![
+                RubricCriterion {
+                    name: " Accuracy ".to_string(),
+                    description: "Claims are sourced".to_string(),
+                },
+                RubricCriterion {
+                    name: "accuracy".to_string(),
+                    description: String::new(),
+                },
+                RubricCriterion {
+                    name: "Clarity".to_string(),
+                    description: String::new(),
+                },
+            ],
+            pass_threshold: 4.0,
+        })
+        .expect("rubric should be valid");
+        assert_eq!(rubric.criteria.len(), 2);
+        let request = render_critic_request(&rubric, "Body");
+        assert!(request.contains("- Accuracy: Claims are sourced"));
+        assert!(request.contains("at least 4"));
+        assert!(normalize_rubric(CritiqueRubric {
+            criteria: Vec::new(),
+            pass_threshold: 9.0,
+        })
+        .is_err());
+
+        let mut critique: CritiqueResult = serde_json::from_value(serde_json::json!({
+            "pass": true,
+            "scores": [
+                { "criterion": "accuracy", "score": 5 },
+                { "criterion": "Clarity", "score": 3, "comment": "Dense" },
+            ],
+        }))
+        .expect("critique should parse");
+        apply_rubric(&rubric, &mut critique);
+        assert!(!critique.pass);
+        critique.scores[1].score = 4.0;
+        apply_rubric(&rubric, &mut critique);
+        assert!(critique.pass);
+    }
+
+    #[test]
+    fn batch_helpers_should_find_plan_drafts_and_final_passing_critiques() {
+        let result = |step_id: Option<&str>, draft_path: &str| {
+            make_bus_entry(
+                BusEntryType::Result,
+                "writer",
+                "supervisor",
+                step_id,
+                serde_json::json!({ "draft_path": draft_path }),
+                BusTokenUsage::default(),
+            )
+        };
+        let critique = |artifact_path: &str, round: u8, pass: bool| {
+            make_bus_entry(
+                BusEntryType::Critique,
+                "critic",
+                "supervisor",
+                None,
+                serde_json::json!({ "artifact_path": artifact_path, "round": round, "pass": pass }),
+                BusTokenUsage::default(),
+            )
+        };
+        let entries = vec![
+            result(Some("old"), "artifacts/drafts/old.md"),
+            make_bus_entry(
+                BusEntryType::Decomposition,
+                "supervisor",
+                "all",
+                None,
+                serde_json::json!({}),
+                BusTokenUsage::default(),
+            ),
+            result(Some("s1"), "artifacts/drafts/a.md"),
+            result(Some("s2"), "artifacts/drafts/b.md"),
+            result(None, "artifacts/drafts/a.md"),
+            critique("artifacts/drafts/a.md", 1, true),
+            critique("artifacts/drafts/b.md", 1, true),
+            critique("artifacts/drafts/b.md", 2, false),
+        ];
+
+        assert_eq!(
+            plan_draft_paths(&entries),
+            vec!["artifacts/drafts/a.md", "artifacts/drafts/b.md"]
+        );
+        assert_eq!(
+            passing_draft_paths(&entries),
+            vec![("artifacts/drafts/a.md".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn bundled_team_templates_should_use_known_tools_and_reflection_roles() {
+        let templates = load_team_templates().expect("templates should parse");
+        let ids: BTreeSet<&str> = templates
+            .iter()
+            .map(|template| template.id.as_str())
+            .collect();
+        assert_eq!(ids.len(), templates.len());
+
+        for template in &templates {
+            assert!(template.agents.len() <= TEAM_MAX_AGENTS);
+            let team_config = team_config_from_template(template, "ollama/llama3");
+            assert_eq!(team_config.supervisor_model, "ollama/llama3");
+            for agent in &team_config.agents {
+                validate_simple_identifier(&agent.id, "id").expect("agent id should be valid");
+                assert!(agent
+                    .tool_subset
+                    .iter()
+                    .all(|tool| tool_spec_for_name(tool).is_some()));
+            }
+            if template.reflection_loops {
+                assert!(reflection_agents(&team_config).is_ok());
+            }
+            assert!(normalize_rubric(template.critique_rubric.clone()).is_ok());
+        }
+    }
+
+    #[test]
+    fn team_runs_should_segment_migrate_and_archive() {
+        let camp_dir = make_temp_dir("basecamp-team-runs");
+        fs::create_dir_all(team_bus_dir(&camp_dir)).expect("bus dir should be created");
+        let entry = make_bus_entry(
+            BusEntryType::Decomposition,
+            "supervisor",
+            "all",
+            None,
+            serde_json::json!({}),
+            BusTokenUsage::default(),
+        );
+        let line = format!("{}\n", serde_json::to_string(&entry).expect("serializes"));
+        fs::write(legacy_team_bus_path(&camp_dir), &line).expect("legacy bus should be written");
+
+        migrate_legacy_team_bus(&camp_dir).expect("legacy bus should migrate");
+        assert!(!legacy_team_bus_path(&camp_dir).exists());
+        assert_eq!(active_run_id(&camp_dir).unwrap(), LEGACY_TEAM_RUN_ID);
+        assert_eq!(read_team_bus_entries(&camp_dir).unwrap().len(), 1);
+
+        let run_id = begin_run_for_new_task(&camp_dir).expect("new run should start");
+        assert_ne!(run_id, LEGACY_TEAM_RUN_ID);
+        assert!(read_team_bus_entries(&camp_dir).unwrap().is_empty());
+        assert_eq!(begin_run_for_new_task(&camp_dir).unwrap(), run_id);
+
+        assert_eq!(
+            archive_inactive_runs(&camp_dir, 0).unwrap(),
+            vec![LEGACY_TEAM_RUN_ID.to_string()]
+        );
+        let runs = list_team_run_summaries(&camp_dir).unwrap();
+        assert!(runs[0].archived && !runs[0].active);
+        assert!(runs[1].active && !runs[1].archived);
+        assert_eq!(
+            read_team_run_entries(&camp_dir, LEGACY_TEAM_RUN_ID).unwrap()[0].id,
+            entry.id
+        );
+    }
+
+    #[test]
+    fn run_report_should_total_usage_cost_and_step_duration() {
+        let team_config = TeamConfig {
+            is_team: true,
+            supervisor_model: "openrouter/paid-model".to_string(),
+            agents: vec![TeamAgentConfig {
+                id: "writer".to_string(),
+                role: "Writer".to_string(),
+                model: "ollama/llama3".to_string(),
+                tool_subset: Vec::new(),
+                description: String::new(),
+                continue_context: false,
+            }],
+            reflection_loops: true,
+            max_reflection_rounds: TEAM_DEFAULT_MAX_REFLECTION_ROUNDS,
+            mirror_to_transcript: false,
+            critique_rubric: CritiqueRubric::default(),
+        };
+        let entry = |entry_type, from: &str, step_id, content, input, output, timestamp: &str| {
+            let mut entry = make_bus_entry(
+                entry_type,
+                from,
+                "all",
+                step_id,
+                content,
+                BusTokenUsage { input, output },
+            );
+            entry.timestamp = timestamp.to_string();
+            entry
+        };
+        let plan = serde_json::json!({
+            "task_summary": "Write a post",
+            "steps": [{
+                "step_id": "s1",
+                "assigned_to": "writer",
+                "instruction": "Draft it",
+                "expected_output": "A draft",
+            }],
+        });
+        let entries = vec![
+            entry(
+                BusEntryType::Decomposition,
+                "supervisor",
+                None,
+                serde_json::json!({}),
+                999,
+                999,
+                "2026-01-01T09:00:00Z",
+            ),
+            entry(
+                BusEntryType::Decomposition,
+                "supervisor",
+                None,
+                plan,
+                1_000,
+                200,
+                "2026-01-01T10:00:00Z",
+            ),
+            entry(
+                BusEntryType::Delegation,
+                "supervisor",
+                Some("s1"),
+                serde_json::json!({}),
+                0,
+                0,
+                "2026-01-01T10:00:01Z",
+            ),
+            entry(
+                BusEntryType::Result,
+                "writer",
+                Some("s1"),
+                serde_json::json!({ "started_at": "2026-01-01T10:00:05Z" }),
+                300,
+                100,
+                "2026-01-01T10:00:07.5Z",
+            ),
+        ];
+
+        let mut pricing = HashMap::new();
+        pricing.insert(
+            "writer".to_string(),
+            ModelPricing {
+                prompt: 0.0,
+                completion: 0.0,
+            },
+        );
+        let report = build_run_report(&entries, &team_config, &pricing);
+        assert_eq!(report.task_summary, "Write a post");
+        assert_eq!(report.total_usage.input, 1_300);
+        assert_eq!(report.total_usage.output, 300);
+        assert_eq!(report.duration_ms, Some(7_500));
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(report.steps[0].token_usage.input, 300);
+        assert_eq!(report.steps[0].duration_ms, Some(2_500));
+        assert_eq!(report.steps[0].estimated_cost, Some(0.0));
+        // The supervisor's model has no known price, so neither does the run.
+        assert_eq!(report.agents[0].estimated_cost, None);
+        assert_eq!(report.estimated_cost, None);
+
+        pricing.insert(
+            "supervisor".to_string(),
+            ModelPricing {
+                prompt: 0.000_001,
+                completion: 0.000_002,
+            },
+        );
+        let report = build_run_report(&entries, &team_config, &pricing);
+        let cost = report.estimated_cost.expect("every model is priced");
+        assert!((cost - 0.0014).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+use super::team::{parse_json_from_output, run_chat_completion};
+use crate::{
+    camp_transcript_path, ensure_camps_root, ensure_main_window, now_timestamp_ms,
+    read_camp_config, read_json_file, read_transcript, resolve_existing_camp_dir, write_json_file,
+    AppState, CampMessage,
+};
+
+const CAMP_TRANSLATIONS_FILE: &str = "translations.json";
+const CAMP_TRANSLATIONS_SCHEMA_VERSION: &str = "0.1";
+/// Messages are sent in batches of roughly this many characters so each
+/// translation fits in one completion.
+const TRANSLATION_BATCH_CHARS: usize = 4_000;
+
+const TRANSLATION_INSTRUCTIONS: &str = "Translate the text of each message into the target language.
+Keep markdown, code blocks, URLs, names and numbers unchanged. Do not add commentary.
+Return ONLY valid JSON with this shape: {\"translations\": [{\"id\": string, \"text\": string}]}, one entry per input message, using the input ids.";
+
+#[derive(Debug, Deserialize)]
+pub struct CampTranslateMessagesPayload {
+    pub camp_id: String,
+    pub target_language: String,
+    /// Messages to translate; the whole transcript when omitted.
+    pub message_ids: Option<Vec<String>>,
+    /// Defaults to the camp's model.
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampMessageTranslation {
+    pub text: String,
+    pub model: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranslatedMessage {
+    pub message_id: String,
+    pub language: String,
+    pub text: String,
+}
+
+/// Translations live beside the transcript, keyed by message id and then
+/// language, so originals are never rewritten.
+#[derive(Debug, Serialize, Deserialize)]
+struct CampTranslationsIndex {
+    schema_version: String,
+    messages: BTreeMap<String, BTreeMap<String, CampMessageTranslation>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslationOutput {
+    translations: Vec<TranslationOutputEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranslationOutputEntry {
+    id: String,
+    text: String,
+}
+
+fn camp_translations_path(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_TRANSLATIONS_FILE)
+}
+
+fn read_translations_index(camp_dir: &Path) -> Result<CampTranslationsIndex, String> {
+    let path = camp_translations_path(camp_dir);
+    if !path.exists() {
+        return Ok(CampTranslationsIndex {
+            schema_version: CAMP_TRANSLATIONS_SCHEMA_VERSION.to_string(),
+            messages: BTreeMap::new(),
+        });
+    }
+
+    read_json_file(&path)
+}
+
+fn language_key(language: &str) -> Result<String, String> {
+    let key = language.trim().to_lowercase();
+    if key.is_empty() {
+        return Err("target_language cannot be empty.".to_string());
+    }
+    Ok(key)
+}
+
+/// User and assistant messages with text, limited to `message_ids` when given.
+/// Tool traffic is never translated.
+fn translatable_messages<'a>(
+    transcript: &'a [CampMessage],
+    message_ids: Option<&[String]>,
+) -> Result<Vec<&'a CampMessage>, String> {
+    let wanted: Option<HashSet<&str>> =
+        message_ids.map(|ids| ids.iter().map(String::as_str).collect());
+    let selected: Vec<&CampMessage> = transcript
+        .iter()
+        .filter(|message| matches!(message.role.as_str(), "user" | "assistant"))
+        .filter(|message| !message.content.trim().is_empty())
+        .filter(|message| match &wanted {
+            Some(wanted) => wanted.contains(message.id.as_str()),
+            None => true,
+        })
+        .collect();
+    if let Some(wanted) = &wanted {
+        if selected.len() < wanted.len() {
+            return Err("Some messages were not found or have no text to translate.".to_string());
+        }
+    }
+    Ok(selected)
+}
+
+/// Splits messages into batches of about `TRANSLATION_BATCH_CHARS`. A single
+/// longer message still gets a batch of its own.
+fn batch_messages<'a>(messages: &[&'a CampMessage]) -> Vec<Vec<&'a CampMessage>> {
+    let mut batches: Vec<Vec<&CampMessage>> = Vec::new();
+    let mut batch_chars = 0;
+    for &message in messages {
+        let chars = message.content.chars().count();
+        match batches.last_mut() {
+            Some(batch) if batch_chars + chars <= TRANSLATION_BATCH_CHARS => {
+                batch.push(message);
+                batch_chars += chars;
+            }
+            _ => {
+                batches.push(vec![message]);
+                batch_chars = chars;
+            }
+        }
+    }
+    batches
+}
+
+fn render_batch(language: &str, batch: &[&CampMessage]) -> String {
+    let entries: Vec<serde_json::Value> = batch
+        .iter()
+        .map(|message| serde_json::json!({ "id": message.id, "text": message.content }))
+        .collect();
+    format!(
+        "Target language: {language}\n\nMessages:\n{}",
+        serde_json::Value::Array(entries)
+    )
+}
+
+/// Translates messages into `target_language` and stores the results next to
+/// the transcript. Messages already translated into that language are served
+/// from the store instead of being sent again.
+#[tauri::command]
+pub async fn camp_translate_messages(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampTranslateMessagesPayload,
+) -> Result<Vec<TranslatedMessage>, String> {
+    ensure_main_window(&window)?;
+    let language = language_key(&payload.target_language)?;
+    let (camp_dir, camp_model, transcript) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let config = read_camp_config(&camp_dir)?;
+        let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+        (camp_dir, config.model, transcript)
+    };
+    let model = payload
+        .model
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or(camp_model);
+
+    let selected = translatable_messages(&transcript, payload.message_ids.as_deref())?;
+    let mut index = read_translations_index(&camp_dir)?;
+    let pending: Vec<&CampMessage> = selected
+        .iter()
+        .copied()
+        .filter(|message| {
+            !index
+                .messages
+                .get(&message.id)
+                .is_some_and(|translations| translations.contains_key(&language))
+        })
+        .collect();
+
+    for batch in batch_messages(&pending) {
+        let response = run_chat_completion(
+            state.inner(),
+            &model,
+            vec![
+                serde_json::json!({ "role": "system", "content": TRANSLATION_INSTRUCTIONS }),
+                serde_json::json!({
+                    "role": "user",
+                    "content": render_batch(payload.target_language.trim(), &batch),
+                }),
+            ],
+            None,
+            Some(&payload.camp_id),
+        )
+        .await?;
+        let output: TranslationOutput = parse_json_from_output(&response.output_text)?;
+        let batch_ids: HashSet<&str> = batch.iter().map(|message| message.id.as_str()).collect();
+        for entry in output.translations {
+            if !batch_ids.contains(entry.id.as_str()) || entry.text.trim().is_empty() {
+                continue;
+            }
+            index.messages.entry(entry.id).or_default().insert(
+                language.clone(),
+                CampMessageTranslation {
+                    text: entry.text,
+                    model: model.clone(),
+                    created_at: now_timestamp_ms(),
+                },
+            );
+        }
+        // Saved per batch so a later failure keeps the work already paid for.
+        write_json_file(&camp_translations_path(&camp_dir), &index)?;
+    }
+
+    Ok(selected
+        .iter()
+        .filter_map(|message| {
+            let translation = index.messages.get(&message.id)?.get(&language)?;
+            Some(TranslatedMessage {
+                message_id: message.id.clone(),
+                language: language.clone(),
+                text: translation.text.clone(),
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn camp_list_translations(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    language: String,
+) -> Result<Vec<TranslatedMessage>, String> {
+    ensure_main_window(&window)?;
+    let language = language_key(&language)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let index = read_translations_index(&camp_dir)?;
+
+    Ok(index
+        .messages
+        .into_iter()
+        .filter_map(|(message_id, mut translations)| {
+            let translation = translations.remove(&language)?;
+            Some(TranslatedMessage {
+                message_id,
+                language: language.clone(),
+                text: translation.text,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translatable_messages_should_skip_tool_traffic_and_batch_by_size() {
+        let transcript = vec![
+            CampMessage::for_test("u1", "user", "Hello"),
+            CampMessage::for_test("t1", "tool", "{\"ok\":true}"),
+            CampMessage::for_test("a1", "assistant", &"x".repeat(TRANSLATION_BATCH_CHARS)),
+            CampMessage::for_test("a2", "assistant", "Bye"),
+        ];
+
+        let all = translatable_messages(&transcript, None).expect("selection works");
+        let ids: Vec<&str> = all.iter().map(|message| message.id.as_str()).collect();
+        assert_eq!(ids, vec!["u1", "a1", "a2"]);
+        assert_eq!(batch_messages(&all).len(), 3);
+
+        let picked = translatable_messages(&transcript, Some(&["a2".to_string()]))
+            .expect("known id selects");
+        assert_eq!(picked.len(), 1);
+        assert!(translatable_messages(&transcript, Some(&["t1".to_string()])).is_err());
+        assert_eq!(language_key(" French ").as_deref(), Ok("french"));
+    }
+}
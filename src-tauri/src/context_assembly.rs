@@ -0,0 +1,884 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::bookmarks::compaction_protected_message_ids;
+use crate::default_context::load_default_context_files;
+use crate::error::{BasecampError, BasecampErrorKind};
+use crate::providers::{registry, BasecampChatRequest};
+use crate::scratchpad::without_scratchpad;
+use crate::{
+    always_include_artifacts, camp_context_dir, canonicalize_context_root, ensure_artifacts_index,
+    ensure_camps_root, ensure_main_window, estimate_tokens, load_artifact, load_camp_from_dir,
+    parse_model_reference, resolve_existing_camp_dir, resolve_existing_context_target, AppState,
+    Camp, CampArtifact, CampMessage, CampMessageAttachment,
+};
+
+const DEFAULT_CONTEXT_LENGTH: i64 = 8_192;
+const DEFAULT_RESERVED_OUTPUT_TOKENS: usize = 1_024;
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+const MIN_SUMMARY_TOKENS: usize = 64;
+const RECENT_TRANSCRIPT_TURNS: usize = 4;
+const SUMMARY_MARKER: &str = "[SUMMARIZED]";
+const ARTIFACT_HEADER_PREFIX: &str = "Artifact: ";
+/// Rough cost of one image part; providers bill images by tile, not by text.
+const IMAGE_PART_TOKENS: usize = 765;
+
+const PRIORITY_SYSTEM_PROMPT: u8 = 100;
+const PRIORITY_MEMORY: u8 = 90;
+const PRIORITY_PINNED_TRANSCRIPT: u8 = 88;
+const PRIORITY_RECENT_TRANSCRIPT: u8 = 85;
+const PRIORITY_ALWAYS_INCLUDE_ARTIFACT: u8 = 80;
+const PRIORITY_SELECTED_ARTIFACT: u8 = 70;
+const PRIORITY_CONTEXT_FILE: u8 = 50;
+const PRIORITY_OLDER_TRANSCRIPT: u8 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextItemKind {
+    SystemPrompt,
+    Memory,
+    Artifact,
+    ContextFile,
+    Transcript,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextItemStatus {
+    Included,
+    Summarized,
+    Dropped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextAssemblyItem {
+    pub kind: ContextItemKind,
+    pub id: String,
+    pub label: String,
+    pub priority: u8,
+    pub estimated_tokens: usize,
+    pub included_tokens: usize,
+    pub status: ContextItemStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextAssemblyReport {
+    pub context_length: i64,
+    pub reserved_output_tokens: usize,
+    pub budget_tokens: usize,
+    pub used_tokens: usize,
+    pub items: Vec<ContextAssemblyItem>,
+    /// Default context files that were missing or too large to include.
+    pub unavailable_context_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextAssembly {
+    pub messages: Vec<Value>,
+    pub report: ContextAssemblyReport,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampAssembleContextPayload {
+    pub camp_id: String,
+    pub model: Option<String>,
+    #[serde(default)]
+    pub artifact_ids: Vec<String>,
+    #[serde(default)]
+    pub context_paths: Vec<String>,
+    pub context_length: Option<i64>,
+    pub reserved_output_tokens: Option<usize>,
+}
+
+/// Everything the assembler may draw from, already loaded from disk.
+struct ContextSources {
+    system_prompt: String,
+    memory: Value,
+    always_include_artifacts: Vec<CampArtifact>,
+    selected_artifacts: Vec<CampArtifact>,
+    context_files: Vec<(String, String)>,
+    transcript: Vec<CampMessage>,
+    /// Transcript messages pinned with compaction protection; never dropped first.
+    pinned_message_ids: HashSet<String>,
+}
+
+struct Candidate {
+    kind: ContextItemKind,
+    id: String,
+    label: String,
+    priority: u8,
+    summarizable: bool,
+    messages: Vec<Value>,
+    header: String,
+    body: String,
+    estimated_tokens: usize,
+}
+
+/// A text message: its content plus the per-message overhead.
+fn estimate_text_tokens(text: &str) -> usize {
+    estimate_tokens(text) + MESSAGE_OVERHEAD_TOKENS
+}
+
+fn system_message(content: String) -> Value {
+    serde_json::json!({ "role": "system", "content": content })
+}
+
+pub(crate) fn transcript_message_value(message: &CampMessage) -> Value {
+    let mut object = serde_json::Map::new();
+    object.insert("role".to_string(), Value::String(message.role.clone()));
+
+    let attachments = message.attachments.as_deref().unwrap_or_default();
+    if attachments.is_empty() {
+        object.insert(
+            "content".to_string(),
+            Value::String(message.content.clone()),
+        );
+    } else {
+        let mut parts = Vec::new();
+        if !message.content.is_empty() {
+            parts.push(serde_json::json!({ "type": "text", "text": message.content }));
+        }
+        for attachment in attachments {
+            match attachment {
+                CampMessageAttachment::ImageUrl { image_url } => parts.push(
+                    serde_json::json!({ "type": "image_url", "image_url": { "url": image_url.url } }),
+                ),
+            }
+        }
+        object.insert("content".to_string(), Value::Array(parts));
+    }
+
+    if let Some(tool_calls) = &message.tool_calls {
+        object.insert(
+            "tool_calls".to_string(),
+            serde_json::to_value(tool_calls).unwrap_or(Value::Null),
+        );
+    }
+    if let Some(tool_call_id) = &message.tool_call_id {
+        object.insert(
+            "tool_call_id".to_string(),
+            Value::String(tool_call_id.clone()),
+        );
+    }
+    if let Some(name) = &message.name {
+        object.insert("name".to_string(), Value::String(name.clone()));
+    }
+
+    Value::Object(object)
+}
+
+/// Groups the transcript so an assistant tool-call message always travels with
+/// the tool results that answer it; dropping only half of a pair breaks providers.
+fn group_transcript(transcript: &[CampMessage]) -> Vec<Vec<&CampMessage>> {
+    let mut groups: Vec<Vec<&CampMessage>> = Vec::new();
+    for message in transcript {
+        match groups.last_mut() {
+            Some(group) if message.role == "tool" => group.push(message),
+            _ => groups.push(vec![message]),
+        }
+    }
+    groups
+}
+
+/// Extractive summary: markdown headings and the first line of each paragraph,
+/// cut to fit `max_tokens`.
+fn summarize_text(text: &str, max_tokens: usize) -> String {
+    let max_chars = max_tokens
+        .saturating_sub(MESSAGE_OVERHEAD_TOKENS)
+        .saturating_mul(4)
+        .saturating_sub(SUMMARY_MARKER.len() + 1);
+
+    let mut outline = Vec::new();
+    let mut at_paragraph_start = true;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            at_paragraph_start = true;
+            continue;
+        }
+        if trimmed.starts_with('#') || at_paragraph_start {
+            outline.push(trimmed);
+        }
+        at_paragraph_start = false;
+    }
+
+    let mut summary = String::new();
+    for line in outline {
+        let needed = line.chars().count() + 1;
+        if summary.chars().count() + needed > max_chars {
+            let remaining = max_chars.saturating_sub(summary.chars().count());
+            summary.extend(line.chars().take(remaining));
+            break;
+        }
+        summary.push_str(line);
+        summary.push('\n');
+    }
+
+    format!("{}\n{SUMMARY_MARKER}", summary.trim_end())
+}
+
+fn artifact_candidate(artifact: &CampArtifact, priority: u8) -> Candidate {
+    let label = artifact.metadata.title.clone();
+    let header = format!(
+        "{ARTIFACT_HEADER_PREFIX}{} (id: {})\n\n",
+        artifact.metadata.title, artifact.metadata.id
+    );
+    let content = format!("{header}{}", artifact.body);
+    Candidate {
+        kind: ContextItemKind::Artifact,
+        id: artifact.metadata.id.clone(),
+        label,
+        priority,
+        summarizable: true,
+        estimated_tokens: estimate_text_tokens(&content),
+        messages: vec![system_message(content)],
+        header,
+        body: artifact.body.clone(),
+    }
+}
+
+fn build_candidates(sources: &ContextSources) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+
+    let system_prompt = sources.system_prompt.trim();
+    if !system_prompt.is_empty() {
+        candidates.push(Candidate {
+            kind: ContextItemKind::SystemPrompt,
+            id: "system_prompt".to_string(),
+            label: "System prompt".to_string(),
+            priority: PRIORITY_SYSTEM_PROMPT,
+            summarizable: false,
+            estimated_tokens: estimate_text_tokens(system_prompt),
+            messages: vec![system_message(system_prompt.to_string())],
+            header: String::new(),
+            body: system_prompt.to_string(),
+        });
+    }
+
+    let memory = format!(
+        "Structured memory (JSON):\n{}",
+        serde_json::to_string(&without_scratchpad(&sources.memory))
+            .unwrap_or_else(|_| "null".to_string())
+    );
+    candidates.push(Candidate {
+        kind: ContextItemKind::Memory,
+        id: "memory".to_string(),
+        label: "Memory".to_string(),
+        priority: PRIORITY_MEMORY,
+        summarizable: false,
+        estimated_tokens: estimate_text_tokens(&memory),
+        messages: vec![system_message(memory.clone())],
+        header: String::new(),
+        body: memory,
+    });
+
+    for artifact in &sources.always_include_artifacts {
+        candidates.push(artifact_candidate(
+            artifact,
+            PRIORITY_ALWAYS_INCLUDE_ARTIFACT,
+        ));
+    }
+    for artifact in &sources.selected_artifacts {
+        if sources
+            .always_include_artifacts
+            .iter()
+            .any(|pinned| pinned.metadata.id == artifact.metadata.id)
+        {
+            continue;
+        }
+        candidates.push(artifact_candidate(artifact, PRIORITY_SELECTED_ARTIFACT));
+    }
+
+    for (path, body) in &sources.context_files {
+        let header = format!("Context file: {path}\n\n");
+        let content = format!("{header}{body}");
+        candidates.push(Candidate {
+            kind: ContextItemKind::ContextFile,
+            id: path.clone(),
+            label: path.clone(),
+            priority: PRIORITY_CONTEXT_FILE,
+            summarizable: true,
+            estimated_tokens: estimate_text_tokens(&content),
+            messages: vec![system_message(content)],
+            header,
+            body: body.clone(),
+        });
+    }
+
+    let groups = group_transcript(&sources.transcript);
+    let recent_start = groups.len().saturating_sub(RECENT_TRANSCRIPT_TURNS);
+    for (index, group) in groups.iter().enumerate() {
+        let pinned = group
+            .iter()
+            .any(|message| sources.pinned_message_ids.contains(&message.id));
+        let priority = if pinned {
+            PRIORITY_PINNED_TRANSCRIPT
+        } else if index >= recent_start {
+            PRIORITY_RECENT_TRANSCRIPT
+        } else {
+            PRIORITY_OLDER_TRANSCRIPT
+        };
+        let messages: Vec<Value> = group
+            .iter()
+            .map(|message| transcript_message_value(message))
+            .collect();
+        let estimated_tokens = group
+            .iter()
+            .map(|message| estimate_text_tokens(&message.content))
+            .sum();
+        candidates.push(Candidate {
+            kind: ContextItemKind::Transcript,
+            id: group[0].id.clone(),
+            label: if pinned {
+                format!("pinned {} message", group[0].role)
+            } else {
+                format!("{} message", group[0].role)
+            },
+            priority,
+            summarizable: false,
+            messages,
+            header: String::new(),
+            body: String::new(),
+            estimated_tokens,
+        });
+    }
+
+    candidates
+}
+
+/// Fits `sources` into `budget_tokens`. Items are admitted by priority (newer
+/// transcript turns win ties), summarizable items are shortened when they do not
+/// fit whole, and the surviving messages are emitted in canonical request order.
+fn assemble_context(
+    sources: &ContextSources,
+    context_length: i64,
+    reserved_output_tokens: usize,
+) -> ContextAssembly {
+    let budget_tokens = usize::try_from(context_length)
+        .unwrap_or(0)
+        .saturating_sub(reserved_output_tokens);
+    let candidates = build_candidates(sources);
+
+    let mut admission_order: Vec<usize> = (0..candidates.len()).collect();
+    admission_order.sort_by(|left, right| {
+        candidates[*right]
+            .priority
+            .cmp(&candidates[*left].priority)
+            .then_with(|| {
+                // Among transcript turns of equal priority, keep the most recent.
+                if candidates[*left].kind == ContextItemKind::Transcript {
+                    right.cmp(left)
+                } else {
+                    left.cmp(right)
+                }
+            })
+    });
+
+    let mut decisions: Vec<(ContextItemStatus, usize, Vec<Value>)> = candidates
+        .iter()
+        .map(|_| (ContextItemStatus::Dropped, 0, Vec::new()))
+        .collect();
+    let mut used_tokens = 0usize;
+
+    for index in admission_order {
+        let candidate = &candidates[index];
+        let remaining = budget_tokens.saturating_sub(used_tokens);
+        let always_keep = candidate.kind == ContextItemKind::SystemPrompt;
+
+        if candidate.estimated_tokens <= remaining || always_keep {
+            used_tokens += candidate.estimated_tokens;
+            decisions[index] = (
+                ContextItemStatus::Included,
+                candidate.estimated_tokens,
+                candidate.messages.clone(),
+            );
+            continue;
+        }
+
+        if candidate.summarizable && remaining >= MIN_SUMMARY_TOKENS {
+            let header_tokens = estimate_tokens(&candidate.header);
+            let summary = summarize_text(&candidate.body, remaining.saturating_sub(header_tokens));
+            let content = format!("{}{summary}", candidate.header);
+            let tokens = estimate_text_tokens(&content);
+            if tokens <= remaining {
+                used_tokens += tokens;
+                decisions[index] = (
+                    ContextItemStatus::Summarized,
+                    tokens,
+                    vec![system_message(content)],
+                );
+            }
+        }
+    }
+
+    let mut messages = Vec::new();
+    let mut items = Vec::with_capacity(candidates.len());
+    for (candidate, (status, included_tokens, candidate_messages)) in
+        candidates.into_iter().zip(decisions)
+    {
+        messages.extend(candidate_messages);
+        items.push(ContextAssemblyItem {
+            kind: candidate.kind,
+            id: candidate.id,
+            label: candidate.label,
+            priority: candidate.priority,
+            estimated_tokens: candidate.estimated_tokens,
+            included_tokens,
+            status,
+        });
+    }
+
+    ContextAssembly {
+        messages,
+        report: ContextAssemblyReport {
+            context_length,
+            reserved_output_tokens,
+            budget_tokens,
+            used_tokens,
+            items,
+            unavailable_context_paths: Vec::new(),
+        },
+    }
+}
+
+/// Ways to bring an oversized request back under the context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowSuggestion {
+    /// Summarize older turns (`camp_assemble_context`) or start a new camp.
+    CompactTranscript,
+    /// Deselect artifacts or turn off always-include on some.
+    DropArtifacts,
+    /// Lower the reply's `max_tokens`.
+    ReduceMaxTokens,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextOverflow {
+    pub context_length: i64,
+    pub estimated_tokens: usize,
+    pub reserved_output_tokens: usize,
+    pub over_by: usize,
+    pub transcript_tokens: usize,
+    pub artifact_tokens: usize,
+    pub suggestions: Vec<OverflowSuggestion>,
+}
+
+impl ContextOverflow {
+    pub(crate) fn into_error(self, model_id: &str) -> BasecampError {
+        let message = format!(
+            "This request is about {} tokens over {model_id}'s {}-token context window. \
+             Compact the transcript or drop artifacts, then send again.",
+            self.over_by, self.context_length
+        );
+        let suggestions = serde_json::to_value(&self.suggestions).unwrap_or_default();
+        BasecampError::new(BasecampErrorKind::ContextOverflow, message)
+            .with_context("context_length", self.context_length)
+            .with_context("estimated_tokens", self.estimated_tokens)
+            .with_context("reserved_output_tokens", self.reserved_output_tokens)
+            .with_context("over_by", self.over_by)
+            .with_context("transcript_tokens", self.transcript_tokens)
+            .with_context("artifact_tokens", self.artifact_tokens)
+            .with_context("suggestions", suggestions)
+    }
+}
+
+fn estimate_content_tokens(content: Option<&Value>) -> usize {
+    match content {
+        Some(Value::String(text)) => estimate_text_tokens(text),
+        Some(Value::Array(parts)) => {
+            parts
+                .iter()
+                .map(|part| match part.get("type").and_then(Value::as_str) {
+                    Some("image_url") => IMAGE_PART_TOKENS,
+                    _ => part
+                        .get("text")
+                        .and_then(Value::as_str)
+                        .map(estimate_text_tokens)
+                        .unwrap_or(0),
+                })
+                .sum::<usize>()
+                + MESSAGE_OVERHEAD_TOKENS
+        }
+        _ => MESSAGE_OVERHEAD_TOKENS,
+    }
+}
+
+fn estimate_message_tokens(message: &Value) -> usize {
+    let tool_calls = message
+        .get("tool_calls")
+        .map(|calls| estimate_text_tokens(&calls.to_string()))
+        .unwrap_or(0);
+    estimate_content_tokens(message.get("content")) + tool_calls
+}
+
+fn is_artifact_message(message: &Value) -> bool {
+    message.get("role").and_then(Value::as_str) == Some("system")
+        && message
+            .get("content")
+            .and_then(Value::as_str)
+            .is_some_and(|content| content.starts_with(ARTIFACT_HEADER_PREFIX))
+}
+
+/// Estimates the prompt size of `request` and compares it, plus the tokens
+/// reserved for the reply, against `context_length`. Returns `None` when the
+/// request fits.
+pub(crate) fn check_context_window(
+    request: &BasecampChatRequest,
+    context_length: i64,
+) -> Option<ContextOverflow> {
+    let mut transcript_messages = 0;
+    let mut transcript_tokens = 0;
+    let mut artifact_tokens = 0;
+    let mut estimated_tokens = 0;
+    for message in &request.messages {
+        let tokens = estimate_message_tokens(message);
+        estimated_tokens += tokens;
+        if is_artifact_message(message) {
+            artifact_tokens += tokens;
+        } else if message.get("role").and_then(Value::as_str) != Some("system") {
+            transcript_messages += 1;
+            transcript_tokens += tokens;
+        }
+    }
+    if let Some(tools) = &request.tools {
+        estimated_tokens += estimate_text_tokens(&Value::Array(tools.clone()).to_string());
+    }
+
+    let reserved_output_tokens = request
+        .max_tokens
+        .and_then(|value| usize::try_from(value).ok())
+        .unwrap_or(0);
+    let budget = usize::try_from(context_length).unwrap_or(0);
+    let over_by = (estimated_tokens + reserved_output_tokens).checked_sub(budget)?;
+    if over_by == 0 {
+        return None;
+    }
+
+    let mut suggestions = Vec::new();
+    if transcript_messages > RECENT_TRANSCRIPT_TURNS {
+        suggestions.push(OverflowSuggestion::CompactTranscript);
+    }
+    if artifact_tokens > 0 {
+        suggestions.push(OverflowSuggestion::DropArtifacts);
+    }
+    if reserved_output_tokens > over_by {
+        suggestions.push(OverflowSuggestion::ReduceMaxTokens);
+    }
+    Some(ContextOverflow {
+        context_length,
+        estimated_tokens,
+        reserved_output_tokens,
+        over_by,
+        transcript_tokens,
+        artifact_tokens,
+        suggestions,
+    })
+}
+
+/// The context length of `model` and the tokens to leave for its reply, from
+/// the model registry and the camp's `generation_defaults` unless the caller
+/// overrides them.
+fn context_window(
+    connection: &Connection,
+    camp: &Camp,
+    model: &str,
+    context_length: Option<i64>,
+    reserved_output_tokens: Option<usize>,
+) -> Result<(i64, usize), String> {
+    let (provider_kind, model_id) = parse_model_reference(model);
+    let context_length = match context_length {
+        Some(value) => value,
+        None => registry::get_model_context_length(connection, provider_kind, &model_id)
+            .map_err(|err| format!("Unable to read model context length: {err}"))?
+            .unwrap_or(DEFAULT_CONTEXT_LENGTH),
+    };
+    let reserved_output_tokens = reserved_output_tokens
+        .or_else(|| {
+            camp.config
+                .generation_defaults
+                .as_ref()
+                .and_then(|defaults| defaults.max_tokens)
+                .and_then(|value| usize::try_from(value).ok())
+        })
+        .unwrap_or(DEFAULT_RESERVED_OUTPUT_TOKENS);
+    Ok((context_length, reserved_output_tokens))
+}
+
+/// Loads the camp's memory, always-include artifacts and default context files
+/// alongside `artifact_ids` and `context_paths`, the way the composer combines
+/// them. Also returns the default context files that could not be read.
+fn load_context_sources(
+    camp_dir: &Path,
+    camp: &Camp,
+    transcript: Vec<CampMessage>,
+    artifact_ids: &[String],
+    context_paths: &[String],
+) -> Result<(ContextSources, Vec<String>), String> {
+    let index = ensure_artifacts_index(camp_dir)?;
+    let always_include = always_include_artifacts(&index)
+        .into_iter()
+        .map(|metadata| load_artifact(camp_dir, &metadata.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let selected_artifacts = artifact_ids
+        .iter()
+        .map(|artifact_id| load_artifact(camp_dir, artifact_id))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut context_files = Vec::new();
+    let mut unavailable_context_paths = Vec::new();
+    for file in load_default_context_files(camp_dir, &camp.config.default_context_paths)? {
+        match file.content {
+            Some(body) => context_files.push((file.path, body)),
+            None => unavailable_context_paths.push(file.path),
+        }
+    }
+    let context_root = canonicalize_context_root(&camp_context_dir(camp_dir))?;
+    for path in context_paths {
+        if camp.config.default_context_paths.contains(path) {
+            continue;
+        }
+        let target = resolve_existing_context_target(&context_root, path, "path", false)?;
+        let body = std::fs::read_to_string(&target)
+            .map_err(|err| format!("Unable to read context file {path}: {err}"))?;
+        context_files.push((path.clone(), body));
+    }
+
+    let sources = ContextSources {
+        system_prompt: camp.request_system_prompt(),
+        memory: camp.memory.clone(),
+        always_include_artifacts: always_include,
+        selected_artifacts,
+        context_files,
+        transcript,
+        pinned_message_ids: compaction_protected_message_ids(camp_dir)?,
+    };
+    Ok((sources, unavailable_context_paths))
+}
+
+/// Messages for replaying the turn that ends at the user message at
+/// `position`: the same system prompt, memory, artifacts and context files the
+/// composer sends, with the artifacts that message included still selected,
+/// fitted to `model`'s context window. Artifacts deleted since are skipped.
+pub(crate) fn assemble_turn_messages(
+    connection: &Connection,
+    camp_dir: &Path,
+    camp: &Camp,
+    model: &str,
+    position: usize,
+) -> Result<Vec<Value>, String> {
+    let index = ensure_artifacts_index(camp_dir)?;
+    let always_include: HashSet<String> = always_include_artifacts(&index)
+        .into_iter()
+        .map(|metadata| metadata.id)
+        .collect();
+    let artifact_ids: Vec<String> = camp.transcript[position]
+        .included_artifact_ids
+        .iter()
+        .flatten()
+        .filter(|artifact_id| {
+            !always_include.contains(*artifact_id)
+                && index
+                    .artifacts
+                    .iter()
+                    .any(|artifact| &artifact.id == *artifact_id)
+        })
+        .cloned()
+        .collect();
+
+    let (context_length, reserved_output_tokens) =
+        context_window(connection, camp, model, None, None)?;
+    let (sources, _) = load_context_sources(
+        camp_dir,
+        camp,
+        camp.transcript[..=position].to_vec(),
+        &artifact_ids,
+        &[],
+    )?;
+    Ok(assemble_context(&sources, context_length, reserved_output_tokens).messages)
+}
+
+#[tauri::command]
+pub fn camp_assemble_context(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampAssembleContextPayload,
+) -> Result<ContextAssembly, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let mut camp = load_camp_from_dir(&camp_dir)?;
+
+    let model = payload.model.as_deref().unwrap_or(&camp.config.model);
+    let (context_length, reserved_output_tokens) = context_window(
+        &connection,
+        &camp,
+        model,
+        payload.context_length,
+        payload.reserved_output_tokens,
+    )?;
+    let transcript = std::mem::take(&mut camp.transcript);
+    let (sources, unavailable_context_paths) = load_context_sources(
+        &camp_dir,
+        &camp,
+        transcript,
+        &payload.artifact_ids,
+        &payload.context_paths,
+    )?;
+
+    let mut assembly = assemble_context(&sources, context_length, reserved_output_tokens);
+    assembly.report.unavailable_context_paths = unavailable_context_paths;
+    Ok(assembly)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CampArtifactMetadata;
+
+    fn artifact(id: &str, body: &str) -> CampArtifact {
+        CampArtifact {
+            metadata: CampArtifactMetadata {
+                id: id.to_string(),
+                title: id.to_string(),
+                filename: format!("{id}.md"),
+                source_message_id: String::new(),
+                source_role: "user".to_string(),
+                tags: Vec::new(),
+                created_at: 0,
+                updated_at: 0,
+                usage_count: 0,
+                archived: false,
+                source_path: None,
+                always_include: false,
+                include_order: None,
+            },
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn check_context_window_should_report_overflow_with_suggestions() {
+        let mut messages = vec![
+            serde_json::json!({ "role": "system", "content": "Be brief." }),
+            serde_json::json!({
+                "role": "system",
+                "content": format!("{ARTIFACT_HEADER_PREFIX}Spec (id: spec)\n\n{}", "x".repeat(4_000)),
+            }),
+        ];
+        for _ in 0..6 {
+            messages.push(serde_json::json!({ "role": "user", "content": "y".repeat(400) }));
+        }
+        let mut request: BasecampChatRequest = serde_json::from_value(serde_json::json!({
+            "provider_kind": "ollama",
+            "model_id": "llama3.2",
+            "messages": messages,
+            "max_tokens": 1_000,
+        }))
+        .expect("request parses");
+
+        assert!(check_context_window(&request, 8_192).is_none());
+
+        let overflow = check_context_window(&request, 2_000).expect("request overflows");
+        assert_eq!(overflow.estimated_tokens, 7 + 1_011 + 6 * 104);
+        assert_eq!(overflow.over_by, 642);
+        assert_eq!(overflow.artifact_tokens, 1_011);
+        assert_eq!(
+            overflow.suggestions,
+            vec![
+                OverflowSuggestion::CompactTranscript,
+                OverflowSuggestion::DropArtifacts,
+                OverflowSuggestion::ReduceMaxTokens,
+            ]
+        );
+
+        request.max_tokens = None;
+        let error = serde_json::to_value(
+            check_context_window(&request, 1_000)
+                .expect("still overflows")
+                .into_error("llama3.2"),
+        )
+        .expect("error serializes");
+        assert_eq!(error["kind"], "context_overflow");
+        assert_eq!(error["context"]["suggestions"][1], "drop_artifacts");
+    }
+
+    #[test]
+    fn assemble_context_should_keep_everything_when_budget_allows() {
+        let sources = ContextSources {
+            system_prompt: "Be brief.".to_string(),
+            memory: serde_json::json!({ "goal": "ship" }),
+            always_include_artifacts: vec![artifact("style", "Use sentence case.")],
+            selected_artifacts: vec![],
+            context_files: vec![("notes.md".to_string(), "Launch on Friday.".to_string())],
+            transcript: vec![
+                CampMessage::for_test("m1", "user", "hi"),
+                CampMessage::for_test("m2", "assistant", "hello"),
+            ],
+            pinned_message_ids: HashSet::new(),
+        };
+
+        let assembly = assemble_context(&sources, 8_192, 1_024);
+        assert!(assembly
+            .report
+            .items
+            .iter()
+            .all(|item| item.status == ContextItemStatus::Included));
+        assert_eq!(assembly.messages.len(), 6);
+        assert_eq!(assembly.messages[0]["content"], "Be brief.");
+        assert_eq!(assembly.messages[5]["content"], "hello");
+    }
+
+    #[test]
+    fn assemble_context_should_summarize_large_items_and_drop_old_turns() {
+        let long_body = format!("# Spec\n\n{}", "detail ".repeat(2_000));
+        let mut transcript = Vec::new();
+        for index in 0..10 {
+            transcript.push(CampMessage::for_test(
+                &format!("m{index}"),
+                if index % 2 == 0 { "user" } else { "assistant" },
+                &"turn text ".repeat(40),
+            ));
+        }
+        let sources = ContextSources {
+            system_prompt: "Be brief.".to_string(),
+            memory: serde_json::json!({}),
+            always_include_artifacts: vec![],
+            selected_artifacts: vec![artifact("spec", &long_body)],
+            context_files: vec![],
+            transcript,
+            pinned_message_ids: HashSet::from(["m0".to_string()]),
+        };
+
+        let assembly = assemble_context(&sources, 1_200, 200);
+        let report = &assembly.report;
+        assert!(report.used_tokens <= report.budget_tokens);
+
+        let spec = report
+            .items
+            .iter()
+            .find(|item| item.id == "spec")
+            .expect("artifact should be reported");
+        assert_eq!(spec.status, ContextItemStatus::Summarized);
+
+        let transcript_status = |id: &str| {
+            report
+                .items
+                .iter()
+                .find(|item| item.kind == ContextItemKind::Transcript && item.id == id)
+                .map(|item| item.status)
+        };
+        assert_eq!(transcript_status("m9"), Some(ContextItemStatus::Included));
+        assert_eq!(transcript_status("m0"), Some(ContextItemStatus::Included));
+        assert_eq!(transcript_status("m1"), Some(ContextItemStatus::Dropped));
+    }
+}
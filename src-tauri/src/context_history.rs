@@ -0,0 +1,249 @@
+//! Previous versions of camp context files. Every overwrite through
+//! `tauri_cmd_write_context_file` (which the agent `write_file` tool also goes
+//! through) first copies the old contents to
+//! `.context_history/<path>/<created_at>-<suffix>.bak` in the camp folder, so a
+//! bad rewrite can be rolled back. Only the newest `MAX_CONTEXT_FILE_VERSIONS`
+//! are kept per file. The history sits outside `context/` so agents never see it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::{
+    camp_context_dir, canonicalize_context_root, ensure_camps_root, ensure_main_window,
+    ensure_path_within_root, now_timestamp_ms, resolve_existing_camp_dir,
+    resolve_write_context_target, touch_camp_updated_at, validate_context_relative_path,
+    validate_identifier, write_file_atomic, AppState,
+};
+
+const CAMP_CONTEXT_HISTORY_DIR: &str = ".context_history";
+const CONTEXT_VERSION_EXTENSION: &str = "bak";
+const MAX_CONTEXT_FILE_VERSIONS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextFileVersion {
+    pub id: String,
+    /// When these contents were replaced.
+    pub created_at: i64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampRestoreContextFileVersionPayload {
+    pub camp_id: String,
+    pub path: String,
+    pub version_id: String,
+}
+
+fn context_history_dir(camp_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    let relative = validate_context_relative_path(path, "path", false)?;
+    Ok(camp_dir.join(CAMP_CONTEXT_HISTORY_DIR).join(relative))
+}
+
+/// Version ids are `<created_at>-<suffix>`, as with prompt versions.
+fn parse_version_created_at(version_id: &str) -> Option<i64> {
+    version_id.split_once('-')?.0.parse().ok()
+}
+
+fn context_version_path(camp_dir: &Path, path: &str, version_id: &str) -> Result<PathBuf, String> {
+    let validated = validate_identifier(version_id, "version_id")?;
+    let version_path = context_history_dir(camp_dir, path)?
+        .join(format!("{validated}.{CONTEXT_VERSION_EXTENSION}"));
+    if !version_path.is_file() {
+        return Err("Context file version not found.".to_string());
+    }
+    Ok(version_path)
+}
+
+/// Newest first.
+fn list_context_file_versions(
+    camp_dir: &Path,
+    path: &str,
+) -> Result<Vec<ContextFileVersion>, String> {
+    let history_dir = context_history_dir(camp_dir, path)?;
+    if !history_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut versions = Vec::new();
+    for entry in fs::read_dir(&history_dir)
+        .map_err(|err| format!("Unable to read context file history: {err}"))?
+    {
+        let entry = entry.map_err(|err| format!("Unable to read context file history: {err}"))?;
+        let version_path = entry.path();
+        if !version_path.is_file()
+            || version_path.extension().and_then(|value| value.to_str())
+                != Some(CONTEXT_VERSION_EXTENSION)
+        {
+            continue;
+        }
+        let Some(id) = version_path.file_stem().and_then(|value| value.to_str()) else {
+            continue;
+        };
+        let Some(created_at) = parse_version_created_at(id) else {
+            continue;
+        };
+        let size_bytes = entry
+            .metadata()
+            .map_err(|err| format!("Unable to read context file version {id}: {err}"))?
+            .len();
+        versions.push(ContextFileVersion {
+            id: id.to_string(),
+            created_at,
+            size_bytes,
+        });
+    }
+
+    versions.sort_by(|left, right| {
+        right
+            .created_at
+            .cmp(&left.created_at)
+            .then_with(|| right.id.cmp(&left.id))
+    });
+    Ok(versions)
+}
+
+fn snapshot_context_file(camp_dir: &Path, path: &str, contents: &[u8]) -> Result<(), String> {
+    let history_dir = context_history_dir(camp_dir, path)?;
+    fs::create_dir_all(&history_dir)
+        .map_err(|err| format!("Unable to create context file history folder: {err}"))?;
+
+    let existing = list_context_file_versions(camp_dir, path)?;
+    // Keep ids strictly increasing even when two writes land in the same millisecond.
+    let created_at = match existing.first() {
+        Some(latest) => now_timestamp_ms().max(latest.created_at + 1),
+        None => now_timestamp_ms(),
+    };
+    let suffix = Uuid::new_v4().simple().to_string();
+    let id = format!("{created_at}-{}", &suffix[..8]);
+    write_file_atomic(
+        &history_dir.join(format!("{id}.{CONTEXT_VERSION_EXTENSION}")),
+        contents,
+    )?;
+
+    for stale in existing
+        .iter()
+        .skip(MAX_CONTEXT_FILE_VERSIONS.saturating_sub(1))
+    {
+        fs::remove_file(history_dir.join(format!("{}.{CONTEXT_VERSION_EXTENSION}", stale.id)))
+            .map_err(|err| format!("Unable to prune context file history: {err}"))?;
+    }
+    Ok(())
+}
+
+/// Writes a context file, keeping its previous contents in the history when
+/// they differ. `target` must already be resolved inside the camp's context
+/// folder; `path` is the same file relative to it.
+pub(crate) fn write_context_file_with_history(
+    camp_dir: &Path,
+    target: &Path,
+    path: &str,
+    contents: &[u8],
+) -> Result<(), String> {
+    if target.is_file() {
+        let previous =
+            fs::read(target).map_err(|err| format!("Unable to read context file: {err}"))?;
+        if previous == contents {
+            return Ok(());
+        }
+        snapshot_context_file(camp_dir, path, &previous)?;
+    }
+    fs::write(target, contents).map_err(|err| format!("Unable to write context file: {err}"))
+}
+
+#[tauri::command]
+pub fn camp_list_context_file_versions(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    path: String,
+) -> Result<Vec<ContextFileVersion>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    list_context_file_versions(&camp_dir, &path)
+}
+
+/// Puts a previous version back. The contents being replaced go into the
+/// history too, so a restore can itself be undone. Works for files that have
+/// since been deleted.
+#[tauri::command]
+pub fn camp_restore_context_file_version(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampRestoreContextFileVersionPayload,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let restored = fs::read(context_version_path(
+        &camp_dir,
+        &payload.path,
+        &payload.version_id,
+    )?)
+    .map_err(|err| format!("Unable to read context file version: {err}"))?;
+    let context_root = canonicalize_context_root(&camp_context_dir(&camp_dir))?;
+    let target = resolve_write_context_target(&context_root, &payload.path)?;
+    if target.exists() {
+        let canonical_target = fs::canonicalize(&target)
+            .map_err(|err| format!("Unable to resolve destination path: {err}"))?;
+        ensure_path_within_root(&context_root, &canonical_target)?;
+        if canonical_target.is_dir() {
+            return Err("Requested path is a directory.".to_string());
+        }
+    }
+
+    write_context_file_with_history(&camp_dir, &target, &payload.path, &restored)?;
+    touch_camp_updated_at(&camp_dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn write_context_file_with_history_should_keep_previous_versions_up_to_the_limit() {
+        let camp_dir = make_temp_dir("basecamp-context-history");
+        let target = camp_context_dir(&camp_dir).join("notes.md");
+        fs::create_dir_all(target.parent().expect("parent")).expect("context folder");
+
+        write_context_file_with_history(&camp_dir, &target, "notes.md", b"v0").expect("create");
+        assert!(list_context_file_versions(&camp_dir, "notes.md")
+            .expect("versions should list")
+            .is_empty());
+
+        for revision in 1..=MAX_CONTEXT_FILE_VERSIONS + 2 {
+            let contents = format!("v{revision}");
+            write_context_file_with_history(&camp_dir, &target, "notes.md", contents.as_bytes())
+                .expect("overwrite");
+        }
+        write_context_file_with_history(&camp_dir, &target, "notes.md", b"v22").expect("no-op");
+
+        let versions = list_context_file_versions(&camp_dir, "notes.md").expect("versions");
+        assert_eq!(versions.len(), MAX_CONTEXT_FILE_VERSIONS);
+        let newest = context_version_path(&camp_dir, "notes.md", &versions[0].id).expect("path");
+        let oldest = context_version_path(
+            &camp_dir,
+            "notes.md",
+            &versions[MAX_CONTEXT_FILE_VERSIONS - 1].id,
+        )
+        .expect("path");
+        assert_eq!(fs::read(newest).expect("newest"), b"v21");
+        assert_eq!(fs::read(oldest).expect("oldest"), b"v2");
+        assert!(list_context_file_versions(&camp_dir, "../notes.md").is_err());
+    }
+}
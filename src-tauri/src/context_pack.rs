@@ -0,0 +1,287 @@
+//! `camp_export_context_pack`: bundles a camp's system prompt, memory and
+//! active artifacts into one Markdown or JSON document for pasting into other
+//! tools, such as a Claude Project or a custom GPT. Each section notes its
+//! estimated token count so the pack can be trimmed to fit.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{State, Window};
+
+use crate::{
+    always_include_artifacts, camp_memory_path, camp_system_prompt_path, ensure_artifacts_index,
+    ensure_camps_root, ensure_main_window, estimate_tokens, load_artifact, parse_artifact_markdown,
+    read_camp_config, read_json_file, read_text_file, resolve_existing_camp_dir, AppState,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextPackFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampExportContextPackPayload {
+    pub camp_id: String,
+    #[serde(default)]
+    pub format: ContextPackFormat,
+    /// Artifacts to include, in order. Defaults to every artifact that is not
+    /// archived, always-include ones first.
+    pub artifact_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContextPackSection {
+    pub title: String,
+    pub estimated_tokens: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContextPackExport {
+    pub format: ContextPackFormat,
+    pub content: String,
+    /// Estimate for the whole document, headings included.
+    pub estimated_tokens: usize,
+    pub sections: Vec<ContextPackSection>,
+}
+
+struct PackArtifact {
+    id: String,
+    title: String,
+    tags: Vec<String>,
+    body: String,
+}
+
+struct PackSources {
+    camp_id: String,
+    camp_name: String,
+    system_prompt: String,
+    memory: Value,
+    artifacts: Vec<PackArtifact>,
+}
+
+fn memory_is_empty(memory: &Value) -> bool {
+    match memory {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::String(text) => text.trim().is_empty(),
+        _ => false,
+    }
+}
+
+fn pretty_memory(memory: &Value) -> String {
+    serde_json::to_string_pretty(memory).unwrap_or_else(|_| memory.to_string())
+}
+
+fn push_section(
+    body: &mut String,
+    sections: &mut Vec<ContextPackSection>,
+    heading: &str,
+    title: &str,
+    content: &str,
+) {
+    let tokens = estimate_tokens(content);
+    body.push_str(&format!("{heading} {title} (~{tokens} tokens)\n\n"));
+    body.push_str(content.trim_end());
+    body.push_str("\n\n");
+    sections.push(ContextPackSection {
+        title: title.to_string(),
+        estimated_tokens: tokens,
+    });
+}
+
+fn build_markdown(sources: &PackSources) -> (String, Vec<ContextPackSection>) {
+    let mut sections = Vec::new();
+    let mut body = String::new();
+
+    if !sources.system_prompt.trim().is_empty() {
+        push_section(
+            &mut body,
+            &mut sections,
+            "##",
+            "System prompt",
+            &sources.system_prompt,
+        );
+    }
+    if !memory_is_empty(&sources.memory) {
+        push_section(
+            &mut body,
+            &mut sections,
+            "##",
+            "Memory",
+            &format!("```json\n{}\n```", pretty_memory(&sources.memory)),
+        );
+    }
+    if !sources.artifacts.is_empty() {
+        body.push_str("## Artifacts\n\n");
+        for artifact in &sources.artifacts {
+            let content = if artifact.tags.is_empty() {
+                artifact.body.clone()
+            } else {
+                format!("Tags: {}\n\n{}", artifact.tags.join(", "), artifact.body)
+            };
+            push_section(&mut body, &mut sections, "###", &artifact.title, &content);
+        }
+    }
+
+    let total = estimate_tokens(&body);
+    let document = format!(
+        "# {} context pack\n\n_About {total} tokens across {} sections._\n\n{}",
+        sources.camp_name,
+        sections.len(),
+        body.trim_end()
+    );
+    (format!("{document}\n"), sections)
+}
+
+fn build_json(sources: &PackSources) -> (String, Vec<ContextPackSection>) {
+    let mut sections = Vec::new();
+    let mut section = |title: &str, text: &str| {
+        let tokens = estimate_tokens(text);
+        sections.push(ContextPackSection {
+            title: title.to_string(),
+            estimated_tokens: tokens,
+        });
+        tokens
+    };
+
+    let system_prompt_tokens = section("System prompt", &sources.system_prompt);
+    let memory_text = pretty_memory(&sources.memory);
+    let memory_tokens = section("Memory", &memory_text);
+    let artifacts: Vec<Value> = sources
+        .artifacts
+        .iter()
+        .map(|artifact| {
+            json!({
+                "id": artifact.id,
+                "title": artifact.title,
+                "tags": artifact.tags,
+                "content": artifact.body,
+                "estimated_tokens": section(&artifact.title, &artifact.body),
+            })
+        })
+        .collect();
+    let document = json!({
+        "camp": { "id": sources.camp_id, "name": sources.camp_name },
+        "system_prompt": {
+            "content": sources.system_prompt,
+            "estimated_tokens": system_prompt_tokens,
+        },
+        "memory": { "content": sources.memory, "estimated_tokens": memory_tokens },
+        "artifacts": artifacts,
+    });
+    (
+        serde_json::to_string_pretty(&document).unwrap_or_else(|_| document.to_string()),
+        sections,
+    )
+}
+
+fn build_pack(sources: &PackSources, format: ContextPackFormat) -> ContextPackExport {
+    let (content, sections) = match format {
+        ContextPackFormat::Markdown => build_markdown(sources),
+        ContextPackFormat::Json => build_json(sources),
+    };
+    ContextPackExport {
+        format,
+        estimated_tokens: estimate_tokens(&content),
+        content,
+        sections,
+    }
+}
+
+/// Builds a context pack for a camp. Nothing is written to disk; the UI copies
+/// or saves the returned document.
+#[tauri::command]
+pub fn camp_export_context_pack(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampExportContextPackPayload,
+) -> Result<ContextPackExport, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    drop(connection);
+
+    let config = read_camp_config(&camp_dir)?;
+    let artifact_ids = match payload.artifact_ids {
+        Some(artifact_ids) => artifact_ids,
+        None => {
+            let index = ensure_artifacts_index(&camp_dir)?;
+            let mut ids: Vec<String> = always_include_artifacts(&index)
+                .into_iter()
+                .map(|artifact| artifact.id)
+                .collect();
+            for artifact in &index.artifacts {
+                if !artifact.archived && !ids.contains(&artifact.id) {
+                    ids.push(artifact.id.clone());
+                }
+            }
+            ids
+        }
+    };
+    let artifacts = artifact_ids
+        .iter()
+        .map(|artifact_id| {
+            let artifact = load_artifact(&camp_dir, artifact_id)?;
+            let (title, body) = parse_artifact_markdown(&artifact.body, &artifact.metadata.title);
+            Ok(PackArtifact {
+                id: artifact.metadata.id,
+                title,
+                tags: artifact.metadata.tags,
+                body,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let sources = PackSources {
+        camp_id: config.id,
+        camp_name: config.name,
+        system_prompt: read_text_file(&camp_system_prompt_path(&camp_dir))?,
+        memory: read_json_file(&camp_memory_path(&camp_dir))?,
+        artifacts,
+    };
+    Ok(build_pack(&sources, payload.format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_pack_should_annotate_sections_with_token_estimates() {
+        let sources = PackSources {
+            camp_id: "launch".to_string(),
+            camp_name: "Launch".to_string(),
+            system_prompt: "You are a careful release manager.".to_string(),
+            memory: json!({}),
+            artifacts: vec![PackArtifact {
+                id: "a1".to_string(),
+                title: "Rollout Plan".to_string(),
+                tags: vec!["plan".to_string()],
+                body: "Stage canaries first.".to_string(),
+            }],
+        };
+
+        let markdown = build_pack(&sources, ContextPackFormat::Markdown);
+        assert!(markdown.content.starts_with("# Launch context pack"));
+        assert!(markdown
+            .content
+            .contains("## System prompt (~9 tokens)\n\nYou are a careful release manager."));
+        assert!(!markdown.content.contains("## Memory"));
+        assert!(markdown
+            .content
+            .contains("### Rollout Plan (~9 tokens)\n\nTags: plan\n\nStage canaries first."));
+        assert_eq!(markdown.sections.len(), 2);
+
+        let exported = build_pack(&sources, ContextPackFormat::Json);
+        let parsed: Value = serde_json::from_str(&exported.content).expect("valid json");
+        assert_eq!(parsed["artifacts"][0]["estimated_tokens"], 6);
+        assert_eq!(exported.sections.len(), 3);
+    }
+}
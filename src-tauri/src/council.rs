@@ -0,0 +1,312 @@
+use std::collections::HashSet;
+
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::{AppHandle, State};
+
+use crate::chat_stream::{
+    assistant_message, load_max_continuations, run_payload, stream_chat_turn,
+};
+use crate::context_assembly::assemble_turn_messages;
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::{BasecampChatRequest, ChatStreamEvent, ProviderChatResponse, ProviderUsage};
+use crate::variants::{
+    commit_variants, last_user_position, turn_request, MessageVariant, VariantGroup,
+};
+use crate::{
+    ensure_camps_root, load_camp_from_dir, now_timestamp_ms, resolve_existing_camp_dir, store_run,
+    touch_camp_updated_at, AppState,
+};
+
+const COUNCIL_MIN_MODELS: usize = 2;
+const COUNCIL_MAX_MODELS: usize = 4;
+const SYNTHESIS_PROMPT: &str = "Several assistants answered the last user message. Merge their answers into one final reply: keep what they agree on, resolve disagreements by reasoning about which is right, and drop anything unsupported. Reply to the user directly without mentioning the other assistants.";
+
+#[derive(Debug, Deserialize)]
+pub struct RunCouncilPayload {
+    pub camp_id: String,
+    pub models: Vec<String>,
+    /// When set, this model merges the council's answers into the reply the
+    /// conversation continues from.
+    pub synthesizer_model: Option<String>,
+    /// Tool specs the composer would send with this turn.
+    #[serde(default)]
+    pub tools: Option<Vec<Value>>,
+}
+
+/// A stream event from one council member, tagged with the model that sent it.
+#[derive(Debug, Clone, Serialize)]
+pub struct CouncilStreamEvent {
+    pub model: String,
+    pub synthesizer: bool,
+    pub event: ChatStreamEvent,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CouncilMemberResult {
+    pub model: String,
+    pub run_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_id: Option<String>,
+    pub usage: ProviderUsage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CouncilResult {
+    pub members: Vec<CouncilMemberResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synthesis: Option<CouncilMemberResult>,
+    pub group: VariantGroup,
+}
+
+fn normalize_council_models(models: &[String]) -> CommandResult<Vec<String>> {
+    let mut seen = HashSet::new();
+    let models: Vec<String> = models
+        .iter()
+        .map(|model| model.trim().to_string())
+        .filter(|model| !model.is_empty() && seen.insert(model.clone()))
+        .collect();
+    if !(COUNCIL_MIN_MODELS..=COUNCIL_MAX_MODELS).contains(&models.len()) {
+        return Err(BasecampError::validation(format!(
+            "A council needs {COUNCIL_MIN_MODELS} to {COUNCIL_MAX_MODELS} different models."
+        ))
+        .with_context("models", models.len()));
+    }
+    Ok(models)
+}
+
+/// A per-model channel that forwards into the council channel with a tag.
+fn tagged_channel(
+    downstream: &Channel<CouncilStreamEvent>,
+    model: &str,
+    synthesizer: bool,
+) -> Channel<ChatStreamEvent> {
+    let downstream = downstream.clone();
+    let model = model.to_string();
+    Channel::new(move |body: InvokeResponseBody| {
+        let event: ChatStreamEvent = body.deserialize()?;
+        downstream.send(CouncilStreamEvent {
+            model: model.clone(),
+            synthesizer,
+            event,
+        })
+    })
+}
+
+/// The synthesizer's turn plus every council answer and a brief to merge them.
+fn synthesis_request(
+    mut request: BasecampChatRequest,
+    answers: &[(String, String)],
+) -> BasecampChatRequest {
+    let mut brief = String::from(SYNTHESIS_PROMPT);
+    for (position, (model, answer)) in answers.iter().enumerate() {
+        brief.push_str(&format!(
+            "\n\n--- Answer {} ({model}) ---\n{answer}",
+            position + 1
+        ));
+    }
+    request
+        .messages
+        .push(json!({ "role": "system", "content": brief }));
+    request
+}
+
+fn record_member(
+    app: &AppHandle,
+    state: &AppState,
+    camp_id: &str,
+    request: &BasecampChatRequest,
+    model: &str,
+    outcome: &CommandResult<ProviderChatResponse>,
+) -> CommandResult<(CouncilMemberResult, Option<MessageVariant>)> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    store_run(
+        app,
+        state,
+        run_payload(&run_id, camp_id, request, None, outcome.as_ref()),
+    )?;
+    let variant = outcome.as_ref().ok().map(|response| MessageVariant {
+        id: uuid::Uuid::new_v4().to_string(),
+        messages: vec![assistant_message(response, &run_id)],
+        model: Some(model.to_string()),
+        created_at: now_timestamp_ms(),
+    });
+    let member = CouncilMemberResult {
+        model: model.to_string(),
+        run_id,
+        variant_id: variant.as_ref().map(|variant| variant.id.clone()),
+        usage: outcome
+            .as_ref()
+            .map(|response| response.usage.clone())
+            .unwrap_or_default(),
+        error: outcome.as_ref().err().map(|err| err.message.clone()),
+    };
+    Ok((member, variant))
+}
+
+/// Sends the camp's last user message to 2–4 models at once and stores every
+/// answer as a variant of that turn. With a synthesizer, a merged answer is
+/// added and selected; otherwise the first successful answer is selected.
+/// Events for each model stream through `on_event` tagged with its name.
+#[tauri::command]
+pub async fn run_council(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: RunCouncilPayload,
+    on_event: Channel<CouncilStreamEvent>,
+) -> CommandResult<CouncilResult> {
+    let models = normalize_council_models(&payload.models)?;
+    let synthesizer_model = payload
+        .synthesizer_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty());
+    let (camp_dir, max_continuations, turn, requests, synthesizer_request) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let mut camp = load_camp_from_dir(&camp_dir)?;
+        let position = last_user_position(&camp.transcript).map_err(BasecampError::validation)?;
+        let build_request = |model: &str| -> CommandResult<BasecampChatRequest> {
+            let messages = assemble_turn_messages(&connection, &camp_dir, &camp, model, position)?;
+            Ok(turn_request(
+                &payload.camp_id,
+                model,
+                messages,
+                payload.tools.clone(),
+            ))
+        };
+        let requests = models
+            .iter()
+            .map(|model| build_request(model))
+            .collect::<CommandResult<Vec<_>>>()?;
+        let synthesizer_request = synthesizer_model.map(build_request).transpose()?;
+        let turn = camp.transcript.split_off(position);
+        let max_continuations = load_max_continuations(&connection)?;
+        (
+            camp_dir,
+            max_continuations,
+            turn,
+            requests,
+            synthesizer_request,
+        )
+    };
+
+    let outcomes = join_all(models.iter().zip(&requests).map(|(model, request)| {
+        let channel = tagged_channel(&on_event, model, false);
+        stream_chat_turn(&state, request, channel, max_continuations)
+    }))
+    .await;
+
+    let mut members = Vec::with_capacity(models.len());
+    let mut variants = Vec::new();
+    let mut answers = Vec::new();
+    let mut first_error = None;
+    for ((model, request), (outcome, _)) in models.iter().zip(&requests).zip(outcomes) {
+        let (member, variant) =
+            record_member(&app, &state, &payload.camp_id, request, model, &outcome)?;
+        match outcome {
+            Ok(response) => answers.push((model.clone(), response.output_text)),
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+        members.push(member);
+        variants.extend(variant);
+    }
+    if variants.is_empty() {
+        return Err(
+            first_error.unwrap_or_else(|| BasecampError::internal("No council model answered."))
+        );
+    }
+
+    let mut synthesis = None;
+    if let (Some(synthesizer_model), Some(request)) = (synthesizer_model, synthesizer_request) {
+        let request = synthesis_request(request, &answers);
+        let channel = tagged_channel(&on_event, synthesizer_model, true);
+        let (outcome, _) = stream_chat_turn(&state, &request, channel, max_continuations).await;
+        let (member, variant) = record_member(
+            &app,
+            &state,
+            &payload.camp_id,
+            &request,
+            synthesizer_model,
+            &outcome,
+        )?;
+        synthesis = Some(member);
+        variants.extend(variant);
+    }
+
+    let selected_id = match synthesis
+        .as_ref()
+        .and_then(|member| member.variant_id.clone())
+    {
+        Some(id) => id,
+        None => variants[0].id.clone(),
+    };
+    let group = {
+        let _connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        commit_variants(
+            &camp_dir,
+            &turn[0].id,
+            &turn[1..],
+            variants,
+            Some(&selected_id),
+        )?
+    };
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(CouncilResult {
+        members,
+        synthesis,
+        group,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn council_models_should_be_distinct_and_between_two_and_four() {
+        let models = normalize_council_models(&[
+            " openrouter/a ".to_string(),
+            "openrouter/b".to_string(),
+            "openrouter/a".to_string(),
+        ])
+        .expect("two distinct models are enough");
+        assert_eq!(models, vec!["openrouter/a", "openrouter/b"]);
+
+        assert!(normalize_council_models(&["openrouter/a".to_string()]).is_err());
+        let five: Vec<String> = (0..5).map(|n| format!("openrouter/m{n}")).collect();
+        assert!(normalize_council_models(&five).is_err());
+    }
+
+    #[test]
+    fn synthesis_request_should_list_every_answer_for_the_synthesizer() {
+        let turn = turn_request(
+            "camp",
+            "ollama/llama3",
+            vec![json!({ "role": "system", "content": "Be brief." })],
+            None,
+        );
+        let request = synthesis_request(
+            turn,
+            &[
+                ("openrouter/a".to_string(), "Paris".to_string()),
+                ("openrouter/b".to_string(), "Paris, France".to_string()),
+            ],
+        );
+        assert_eq!(request.model_id, "llama3");
+        let brief = request.messages.last().expect("brief is appended")["content"]
+            .as_str()
+            .expect("brief is text")
+            .to_string();
+        assert!(brief.contains("Answer 1 (openrouter/a)"));
+        assert!(brief.contains("Paris, France"));
+    }
+}
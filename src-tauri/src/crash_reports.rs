@@ -0,0 +1,380 @@
+//! Local crash reports. Panics and unexpected command failures are written as
+//! sanitized JSON under `<app data>/crash_reports`. Nothing is uploaded; the
+//! user exports a report and shares it themselves.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_dialog::DialogExt;
+use uuid::Uuid;
+
+use crate::{
+    now_timestamp_ms, providers::redact_json, read_json_file, scrubber, validate_identifier,
+    write_json_file,
+};
+
+const CRASH_REPORTS_DIR: &str = "crash_reports";
+const MAX_CRASH_REPORTS: usize = 100;
+const SUMMARY_MAX_CHARS: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    CommandError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backtrace: Option<String>,
+    /// Structured error payload for command failures, secrets redacted.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub kind: CrashKind,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    pub created_at: i64,
+}
+
+/// Where reports go, set once during setup. The panic hook can't reach managed
+/// state, so this lives in a static.
+struct ReportSink {
+    dir: PathBuf,
+    home: Option<String>,
+    app_version: String,
+}
+
+static SINK: OnceLock<ReportSink> = OnceLock::new();
+
+struct Sanitizer {
+    rules: Vec<(Regex, &'static str)>,
+}
+
+impl Sanitizer {
+    fn new() -> Self {
+        let rules = [
+            (r"(?i)bearer\s+[A-Za-z0-9._~+/=-]+", "Bearer [REDACTED]"),
+            (scrubber::API_KEY_PATTERN, "[API_KEY]"),
+            (scrubber::EMAIL_PATTERN, "[EMAIL]"),
+        ];
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|(pattern, mask)| {
+                    (
+                        Regex::new(pattern).expect("crash report pattern should compile"),
+                        mask,
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Masks keys and emails and replaces the home folder with `~` so paths
+    /// don't carry the user name.
+    fn text(&self, text: &str, home: Option<&str>) -> String {
+        let mut current = match home.filter(|home| home.len() > 1) {
+            Some(home) => text.replace(home, "~"),
+            None => text.to_string(),
+        };
+        for (regex, mask) in &self.rules {
+            current = regex.replace_all(&current, *mask).into_owned();
+        }
+        current
+    }
+
+    fn value(&self, value: &Value, home: Option<&str>) -> Value {
+        self.strings(redact_json(value), home)
+    }
+
+    fn strings(&self, value: Value, home: Option<&str>) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.text(&text, home)),
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.strings(item, home))
+                    .collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, item)| (key, self.strings(item, home)))
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    fn report(&self, report: CrashReport, home: Option<&str>) -> CrashReport {
+        let optional = |value: Option<String>| value.map(|value| self.text(&value, home));
+        CrashReport {
+            message: self.text(&report.message, home),
+            location: optional(report.location),
+            backtrace: optional(report.backtrace),
+            details: self.value(&report.details, home),
+            ..report
+        }
+    }
+}
+
+fn sanitizer() -> &'static Sanitizer {
+    static SANITIZER: OnceLock<Sanitizer> = OnceLock::new();
+    SANITIZER.get_or_init(Sanitizer::new)
+}
+
+fn new_report(kind: CrashKind, message: String, app_version: &str) -> CrashReport {
+    CrashReport {
+        id: Uuid::new_v4().to_string(),
+        kind,
+        message,
+        command: None,
+        location: None,
+        thread: None,
+        backtrace: None,
+        details: Value::Null,
+        app_version: app_version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        created_at: now_timestamp_ms(),
+    }
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.json"))
+}
+
+fn read_reports(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| read_json_file(&path).ok())
+        .collect();
+    reports.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    reports
+}
+
+/// Keeps the newest `MAX_CRASH_REPORTS` so a failure loop can't fill the disk.
+fn prune_reports(dir: &Path) {
+    for report in read_reports(dir).into_iter().skip(MAX_CRASH_REPORTS) {
+        let _ = fs::remove_file(report_path(dir, &report.id));
+    }
+}
+
+fn store(report: CrashReport) -> Result<CrashReport, String> {
+    let sink = SINK
+        .get()
+        .ok_or_else(|| "Crash reports are not set up yet.".to_string())?;
+    let report = sanitizer().report(report, sink.home.as_deref());
+    fs::create_dir_all(&sink.dir)
+        .map_err(|err| format!("Unable to create crash reports folder: {err}"))?;
+    write_json_file(&report_path(&sink.dir, &report.id), &report)?;
+    prune_reports(&sink.dir);
+    Ok(report)
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return (*message).to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    "Panic with a non-string payload".to_string()
+}
+
+/// Resolves the reports folder and installs a panic hook that writes a report
+/// before handing over to the previous hook.
+pub(crate) fn install(app: &AppHandle) {
+    let Ok(app_data_dir) = app.path().app_data_dir() else {
+        return;
+    };
+    let sink = ReportSink {
+        dir: app_data_dir.join(CRASH_REPORTS_DIR),
+        home: app
+            .path()
+            .home_dir()
+            .ok()
+            .map(|home| home.to_string_lossy().into_owned()),
+        app_version: app.package_info().version.to_string(),
+    };
+    if SINK.set(sink).is_err() {
+        return;
+    }
+
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if let Some(sink) = SINK.get() {
+            let mut report = new_report(
+                CrashKind::Panic,
+                panic_message(info.payload()),
+                &sink.app_version,
+            );
+            report.location = info
+                .location()
+                .map(|location| format!("{}:{}", location.file(), location.line()));
+            report.thread = std::thread::current().name().map(str::to_string);
+            report.backtrace = Some(Backtrace::force_capture().to_string());
+            let _ = store(report);
+        }
+        previous(info);
+    }));
+}
+
+fn error_message(error: &Value) -> String {
+    match error {
+        Value::String(message) => message.clone(),
+        Value::Object(map) => map
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+        other => other.to_string(),
+    }
+}
+
+fn summarize(report: &CrashReport) -> CrashReportSummary {
+    let first_line = report.message.lines().next().unwrap_or_default();
+    let summary = if first_line.chars().count() > SUMMARY_MAX_CHARS {
+        let shortened: String = first_line.chars().take(SUMMARY_MAX_CHARS).collect();
+        format!("{shortened}…")
+    } else {
+        first_line.to_string()
+    };
+    CrashReportSummary {
+        id: report.id.clone(),
+        kind: report.kind,
+        summary,
+        command: report.command.clone(),
+        created_at: report.created_at,
+    }
+}
+
+/// Called by the UI when a command rejects unexpectedly. Failures the user can
+/// fix, like validation errors, are filtered out before they get here.
+#[tauri::command]
+pub fn record_command_failure(command: String, error: Value) -> Result<CrashReportSummary, String> {
+    let sink = SINK
+        .get()
+        .ok_or_else(|| "Crash reports are not set up yet.".to_string())?;
+    let mut report = new_report(
+        CrashKind::CommandError,
+        error_message(&error),
+        &sink.app_version,
+    );
+    report.command = Some(validate_identifier(&command, "command")?);
+    report.details = error;
+    store(report).map(|report| summarize(&report))
+}
+
+#[tauri::command]
+pub fn list_crash_reports() -> Result<Vec<CrashReportSummary>, String> {
+    let Some(sink) = SINK.get() else {
+        return Ok(Vec::new());
+    };
+    Ok(read_reports(&sink.dir).iter().map(summarize).collect())
+}
+
+/// Saves a report wherever the user picks so they can attach it to an issue.
+/// Returns the saved path, or `None` if the dialog was cancelled.
+#[tauri::command]
+pub async fn export_crash_report(app: AppHandle, id: String) -> Result<Option<String>, String> {
+    let sink = SINK
+        .get()
+        .ok_or_else(|| "Crash reports are not set up yet.".to_string())?;
+    let id = validate_identifier(&id, "id")?;
+    let path = report_path(&sink.dir, &id);
+    if !path.exists() {
+        return Err(format!("Crash report {id} was not found."));
+    }
+    let report: CrashReport = read_json_file(&path)?;
+    // Stored reports are already sanitized; this catches ones written before
+    // a pattern was added.
+    let report = sanitizer().report(report, sink.home.as_deref());
+    let contents = serde_json::to_string_pretty(&report)
+        .map_err(|err| format!("Unable to serialize crash report: {err}"))?;
+
+    let (tx, mut rx) = tauri::async_runtime::channel::<Option<PathBuf>>(1);
+    app.dialog()
+        .file()
+        .set_title("Export Crash Report")
+        .set_file_name(format!("basecamp-crash-{id}.json"))
+        .add_filter("JSON", &["json"])
+        .save_file(move |selected| {
+            let _ = tx.blocking_send(selected.and_then(|path| path.into_path().ok()));
+        });
+    let Some(destination) = rx.recv().await.flatten() else {
+        return Ok(None);
+    };
+    fs::write(&destination, contents)
+        .map_err(|err| format!("Unable to export crash report: {err}"))?;
+    Ok(Some(destination.to_string_lossy().into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizer_should_mask_secrets_and_home_paths() {
+        let report = CrashReport {
+            location: Some("/home/ada/basecamp/src/lib.rs:42".to_string()),
+            details: serde_json::json!({
+                "message": "401 for ada@example.com",
+                "api_key": "sk-or-v1-abcdefghijklmnopqrstuvwxyz",
+                "context": { "path": "/home/ada/camps/trip" }
+            }),
+            ..new_report(
+                CrashKind::CommandError,
+                "Provider rejected sk-or-v1-abcdefghijklmnopqrstuvwxyz with Bearer abc.def"
+                    .to_string(),
+                "0.1.0",
+            )
+        };
+        let sanitized = sanitizer().report(report, Some("/home/ada"));
+
+        assert_eq!(
+            sanitized.message,
+            "Provider rejected [API_KEY] with Bearer [REDACTED]"
+        );
+        assert_eq!(
+            sanitized.location.as_deref(),
+            Some("~/basecamp/src/lib.rs:42")
+        );
+        assert_eq!(sanitized.details["message"], "401 for [EMAIL]");
+        assert_eq!(sanitized.details["api_key"], "[REDACTED]");
+        assert_eq!(sanitized.details["context"]["path"], "~/camps/trip");
+        assert_eq!(error_message(&sanitized.details), "401 for [EMAIL]");
+    }
+}
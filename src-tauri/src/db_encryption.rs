@@ -0,0 +1,197 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use keyring::{Entry, Error as KeyringError};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::{
+    ensure_main_window,
+    error::{BasecampError, CommandResult},
+    AppState, DB_FILE_NAME, KEYRING_SERVICE,
+};
+
+const KEYRING_DB_PASSPHRASE_ACCOUNT: &str = "database_passphrase";
+const MIN_PASSPHRASE_CHARS: usize = 12;
+
+/// Encryption needs a SQLCipher build of SQLite; plain SQLite silently ignores
+/// `PRAGMA key`, so every code path checks this before touching the key.
+const SQLCIPHER_ENABLED: bool = cfg!(feature = "sqlcipher");
+
+#[derive(Debug, Serialize)]
+pub struct DbEncryptionStatus {
+    /// Whether this build was compiled with the `sqlcipher` feature.
+    pub supported: bool,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DbSetEncryptionPayload {
+    pub passphrase: String,
+}
+
+fn passphrase_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, KEYRING_DB_PASSPHRASE_ACCOUNT)
+        .map_err(|err| format!("Keyring entry error: {err}"))
+}
+
+fn read_db_passphrase() -> Result<Option<String>, String> {
+    match passphrase_entry()?.get_password() {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(Some(passphrase)),
+        Ok(_) | Err(KeyringError::NoEntry) => Ok(None),
+        Err(err) => Err(format!("Unable to read database passphrase: {err}")),
+    }
+}
+
+fn validate_passphrase(passphrase: &str) -> Result<&str, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(format!(
+            "passphrase must be at least {MIN_PASSPHRASE_CHARS} characters."
+        ));
+    }
+    if passphrase.trim() != passphrase {
+        return Err("passphrase must not start or end with whitespace.".to_string());
+    }
+    Ok(passphrase)
+}
+
+/// Keys the connection and checks the passphrase by reading the schema, which
+/// fails with "file is not a database" when the key is wrong.
+fn apply_key(connection: &Connection, passphrase: &str) -> Result<(), rusqlite::Error> {
+    connection.pragma_update(None, "key", passphrase)?;
+    connection.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })?;
+    Ok(())
+}
+
+/// Opens the app database, applying the keyring passphrase when encryption is on.
+pub(crate) fn open_database(db_path: &Path) -> Result<Connection, Box<dyn std::error::Error>> {
+    let connection = Connection::open(db_path)?;
+    if SQLCIPHER_ENABLED {
+        if let Some(passphrase) = read_db_passphrase()? {
+            apply_key(&connection, &passphrase)?;
+        }
+    }
+    Ok(connection)
+}
+
+/// Copies a plaintext database into a new encrypted file with `sqlcipher_export`.
+fn export_encrypted(
+    connection: &Connection,
+    target: &Path,
+    passphrase: &str,
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+        rusqlite::params![target.to_string_lossy(), passphrase],
+    )?;
+    let exported = connection.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()));
+    connection.execute("DETACH DATABASE encrypted", [])?;
+    exported
+}
+
+fn database_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(DB_FILE_NAME))
+        .map_err(|err| format!("Unable to resolve app data folder: {err}"))
+}
+
+#[tauri::command]
+pub fn db_encryption_status(window: Window) -> CommandResult<DbEncryptionStatus> {
+    ensure_main_window(&window)?;
+    Ok(DbEncryptionStatus {
+        supported: SQLCIPHER_ENABLED,
+        enabled: SQLCIPHER_ENABLED && read_db_passphrase()?.is_some(),
+    })
+}
+
+/// Encrypts the database with a new passphrase, or re-keys it when it is already
+/// encrypted. The passphrase is stored in the OS keyring before the file changes
+/// so a crash mid-way never leaves an encrypted database without its key.
+#[tauri::command]
+pub fn db_set_encryption_passphrase(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: DbSetEncryptionPayload,
+) -> CommandResult<DbEncryptionStatus> {
+    ensure_main_window(&window)?;
+    if !SQLCIPHER_ENABLED {
+        return Err(BasecampError::unavailable(
+            "This build was compiled without SQLCipher support.",
+        ));
+    }
+    let passphrase = validate_passphrase(&payload.passphrase)?;
+    let db_path = database_path(&app)?;
+    let previous = read_db_passphrase()?;
+    let entry = passphrase_entry()?;
+
+    let mut connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+
+    entry
+        .set_password(passphrase)
+        .map_err(|err| format!("Unable to save database passphrase: {err}"))?;
+
+    let result = if previous.is_some() {
+        connection
+            .pragma_update(None, "rekey", passphrase)
+            .map_err(BasecampError::from)
+    } else {
+        encrypt_in_place(&mut connection, &db_path, passphrase)
+    };
+
+    if let Err(err) = result {
+        let _ = match previous {
+            Some(previous) => entry.set_password(&previous),
+            None => entry.delete_password(),
+        };
+        return Err(err);
+    }
+
+    Ok(DbEncryptionStatus {
+        supported: true,
+        enabled: true,
+    })
+}
+
+fn encrypt_in_place(
+    connection: &mut Connection,
+    db_path: &Path,
+    passphrase: &str,
+) -> CommandResult<()> {
+    let encrypted_path = db_path.with_extension("db.encrypting");
+    let _ = fs::remove_file(&encrypted_path);
+    export_encrypted(connection, &encrypted_path, passphrase)?;
+
+    // Release the plaintext file before replacing it; Windows refuses to rename
+    // over a file that is still open.
+    let plaintext = std::mem::replace(connection, Connection::open_in_memory()?);
+    drop(plaintext);
+
+    let swapped = fs::rename(&encrypted_path, db_path)
+        .map_err(|err| BasecampError::from(err).with_context("path", db_path.to_string_lossy()));
+    *connection = Connection::open(db_path)?;
+    swapped?;
+    apply_key(connection, passphrase)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_passphrase_should_reject_short_or_padded_values() {
+        assert!(validate_passphrase("short").is_err());
+        assert!(validate_passphrase(" padded passphrase ").is_err());
+        assert_eq!(
+            validate_passphrase("correct horse battery").expect("passphrase should validate"),
+            "correct horse battery"
+        );
+    }
+}
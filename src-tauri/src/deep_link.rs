@@ -0,0 +1,145 @@
+//! `basecamp://` links and single-instance handling. Opening a link, or
+//! launching Basecamp again while it runs, focuses the existing window; the
+//! link target is emitted on `deeplink://open` and kept until the UI takes it,
+//! so links that arrive during startup are not lost.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::{validate_camp_identifier, validate_identifier, AppState};
+
+const DEEP_LINK_SCHEME: &str = "basecamp";
+const DEEP_LINK_EVENT: &str = "deeplink://open";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLinkTarget {
+    Camp { id: String },
+    Run { id: String },
+}
+
+#[derive(Default)]
+pub struct PendingDeepLink(Mutex<Option<DeepLinkTarget>>);
+
+/// Accepts `basecamp://camp/<id>` and `basecamp://run/<id>`. Query strings and
+/// fragments are ignored.
+fn parse_deep_link(url: &str) -> Option<DeepLinkTarget> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    if !scheme.eq_ignore_ascii_case(DEEP_LINK_SCHEME) {
+        return None;
+    }
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    match segments.as_slice() {
+        ["camp", id] => validate_camp_identifier(id)
+            .ok()
+            .map(|id| DeepLinkTarget::Camp { id }),
+        ["run", id] => validate_identifier(id, "run_id")
+            .ok()
+            .map(|id| DeepLinkTarget::Run { id }),
+        _ => None,
+    }
+}
+
+/// Brings the main window forward, but only once startup has finished so a
+/// link can't skip the splash.
+pub(crate) fn focus_main_window(app: &AppHandle) {
+    if app.try_state::<AppState>().is_none() {
+        return;
+    }
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn open_urls<I, S>(app: &AppHandle, urls: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    if let Some(target) = urls
+        .into_iter()
+        .filter_map(|url| parse_deep_link(url.as_ref()))
+        .last()
+    {
+        open_target(app, target);
+    }
+}
+
+/// Hands `target` to the UI and brings the main window forward. Also used by
+/// the tray's recent camps.
+pub(crate) fn open_target(app: &AppHandle, target: DeepLinkTarget) {
+    if let Some(pending) = app.try_state::<PendingDeepLink>() {
+        if let Ok(mut pending) = pending.0.lock() {
+            *pending = Some(target.clone());
+        }
+    }
+    let _ = app.emit(DEEP_LINK_EVENT, target);
+    focus_main_window(app);
+}
+
+/// Called by the single-instance plugin with the second launch's arguments.
+pub(crate) fn on_second_instance(app: &AppHandle, argv: Vec<String>) {
+    focus_main_window(app);
+    open_urls(app, argv);
+}
+
+/// Registers the link handler and picks up a link Basecamp was launched with.
+pub(crate) fn setup(app: &AppHandle) {
+    app.manage(PendingDeepLink::default());
+
+    // Installed builds register the scheme from the bundle; this covers dev
+    // runs and AppImages.
+    #[cfg(any(windows, target_os = "linux"))]
+    let _ = app.deep_link().register_all();
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        open_urls(&handle, event.urls().iter().map(|url| url.as_str()));
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        open_urls(app, urls.iter().map(|url| url.as_str()));
+    }
+}
+
+/// Returns and clears the link that opened the app, if the UI has not
+/// handled it yet.
+#[tauri::command]
+pub fn take_pending_deep_link(
+    pending: State<'_, PendingDeepLink>,
+) -> Result<Option<DeepLinkTarget>, String> {
+    let mut pending = pending
+        .0
+        .lock()
+        .map_err(|_| "Deep link lock error".to_string())?;
+    Ok(pending.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_deep_link_should_accept_camp_and_run_targets() {
+        assert_eq!(
+            parse_deep_link("basecamp://camp/trip-plans?from=mail"),
+            Some(DeepLinkTarget::Camp {
+                id: "trip-plans".to_string()
+            })
+        );
+        assert_eq!(
+            parse_deep_link("BASECAMP://run/run-42/"),
+            Some(DeepLinkTarget::Run {
+                id: "run-42".to_string()
+            })
+        );
+        assert_eq!(parse_deep_link("basecamp://camp/../secrets"), None);
+        assert_eq!(parse_deep_link("https://camp/trip-plans"), None);
+        assert_eq!(parse_deep_link("basecamp://settings"), None);
+    }
+}
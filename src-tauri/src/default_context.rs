@@ -0,0 +1,313 @@
+//! Context files a camp attaches to every request. The paths are kept in
+//! `camp.json` as `default_context_paths`, relative to the camp's `context/`
+//! folder, and the files are read fresh each time a request is assembled.
+//! A file that has gone missing is reported rather than failing the request,
+//! and a camp copy of a workspace context file is flagged once the workspace
+//! file has been edited since it was attached.
+
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::tabular::{self, TableFormat};
+use crate::{
+    camp_context_dir, canonicalize_context_root, ensure_camps_root, ensure_main_window,
+    read_camp_config, resolve_existing_camp_dir, resolve_existing_context_target,
+    to_context_relative_display, validate_context_relative_path, workspace_context_dir,
+    write_camp_config, AppState,
+};
+
+const MAX_DEFAULT_CONTEXT_PATHS: usize = 16;
+/// Larger files are left out of requests; select them by hand instead.
+const MAX_DEFAULT_CONTEXT_FILE_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultContextStatus {
+    /// Read as-is from the camp's context folder.
+    Fresh,
+    /// Still attached, but the workspace file it was copied from has changed
+    /// since. Re-attach it to pick up the changes.
+    Outdated,
+    /// No longer in the camp's context folder; left out of the request.
+    Missing,
+    /// Over `MAX_DEFAULT_CONTEXT_FILE_BYTES`; left out of the request.
+    TooLarge,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DefaultContextFile {
+    pub path: String,
+    pub status: DefaultContextStatus,
+    /// `None` unless the file goes into the request.
+    pub content: Option<String>,
+    pub modified_at: Option<i64>,
+    pub bytes: u64,
+}
+
+pub(crate) fn parse_default_context_paths_field(value: Option<&Value>) -> (Vec<String>, bool) {
+    let Some(items) = value.and_then(Value::as_array) else {
+        return (Vec::new(), value.is_some_and(|value| !value.is_null()));
+    };
+    let mut paths: Vec<String> = Vec::new();
+    for path in items.iter().filter_map(Value::as_str) {
+        if validate_context_relative_path(path, "path", false).is_ok()
+            && !paths.iter().any(|existing| existing == path)
+        {
+            paths.push(path.to_string());
+        }
+    }
+    let migrated = paths.len() != items.len();
+    (paths, migrated)
+}
+
+/// Checks that every path names an existing file in the camp's context folder
+/// and returns them in canonical form, without duplicates.
+fn normalize_default_context_paths(
+    camp_dir: &Path,
+    paths: &[String],
+) -> Result<Vec<String>, String> {
+    let context_root = canonicalize_context_root(&camp_context_dir(camp_dir))?;
+    let mut normalized: Vec<String> = Vec::new();
+    for path in paths.iter().map(|path| path.trim()) {
+        if path.is_empty() {
+            continue;
+        }
+        let target = resolve_existing_context_target(&context_root, path, "path", false)
+            .map_err(|err| format!("{path}: {err}"))?;
+        if !target.is_file() {
+            return Err(format!("{path}: only files can be attached by default."));
+        }
+        let display = to_context_relative_display(&context_root, &target)?;
+        if !normalized.contains(&display) {
+            normalized.push(display);
+        }
+    }
+    if normalized.len() > MAX_DEFAULT_CONTEXT_PATHS {
+        return Err(format!(
+            "A camp can attach at most {MAX_DEFAULT_CONTEXT_PATHS} context files by default."
+        ));
+    }
+    Ok(normalized)
+}
+
+fn modified_at_ms(metadata: &fs::Metadata) -> Option<i64> {
+    let modified = metadata.modified().ok()?;
+    let elapsed = modified.duration_since(UNIX_EPOCH).ok()?;
+    i64::try_from(elapsed.as_millis()).ok()
+}
+
+/// Whether the workspace file at the same path was edited after the camp's
+/// copy was made and no longer matches it.
+fn workspace_copy_changed(
+    camp_dir: &Path,
+    path: &str,
+    body: &[u8],
+    copied_at: Option<i64>,
+) -> bool {
+    // Camps always sit at `<workspace>/camps/<id>`.
+    let Some(workspace_path) = camp_dir.parent().and_then(Path::parent) else {
+        return false;
+    };
+    let Ok(relative) = validate_context_relative_path(path, "path", false) else {
+        return false;
+    };
+    let source = workspace_context_dir(workspace_path).join(relative);
+    let Ok(metadata) = fs::metadata(&source) else {
+        return false;
+    };
+    if !metadata.is_file() || modified_at_ms(&metadata) <= copied_at {
+        return false;
+    }
+    fs::read(&source).is_ok_and(|source_body| source_body != body)
+}
+
+fn load_default_context_file(
+    camp_dir: &Path,
+    context_root: &Path,
+    path: &str,
+) -> DefaultContextFile {
+    let missing = DefaultContextFile {
+        path: path.to_string(),
+        status: DefaultContextStatus::Missing,
+        content: None,
+        modified_at: None,
+        bytes: 0,
+    };
+    let Ok(target) = resolve_existing_context_target(context_root, path, "path", false) else {
+        return missing;
+    };
+    let Ok(metadata) = fs::metadata(&target) else {
+        return missing;
+    };
+    if !metadata.is_file() {
+        return missing;
+    }
+    let modified_at = modified_at_ms(&metadata);
+    if metadata.len() > MAX_DEFAULT_CONTEXT_FILE_BYTES {
+        return DefaultContextFile {
+            status: DefaultContextStatus::TooLarge,
+            modified_at,
+            bytes: metadata.len(),
+            ..missing
+        };
+    }
+    let Ok(raw) = fs::read(&target) else {
+        return missing;
+    };
+    // Spreadsheets go in as a Markdown table rather than raw bytes.
+    let body = match TableFormat::from_path(path) {
+        Ok(Some(format)) => match tabular::read_table(&raw, format, None) {
+            Ok(table) => tabular::table_to_markdown(&table, tabular::DEFAULT_MARKDOWN_ROWS),
+            Err(_) => return missing,
+        },
+        _ => match std::str::from_utf8(&raw) {
+            Ok(body) => body.to_string(),
+            Err(_) => return missing,
+        },
+    };
+    let status = if workspace_copy_changed(camp_dir, path, &raw, modified_at) {
+        DefaultContextStatus::Outdated
+    } else {
+        DefaultContextStatus::Fresh
+    };
+    DefaultContextFile {
+        path: path.to_string(),
+        status,
+        bytes: metadata.len(),
+        content: Some(body),
+        modified_at,
+    }
+}
+
+/// Reads the camp's default context files, in the order they were set.
+pub(crate) fn load_default_context_files(
+    camp_dir: &Path,
+    paths: &[String],
+) -> Result<Vec<DefaultContextFile>, String> {
+    let context_root = canonicalize_context_root(&camp_context_dir(camp_dir))?;
+    Ok(paths
+        .iter()
+        .map(|path| load_default_context_file(camp_dir, &context_root, path))
+        .collect())
+}
+
+/// Drops `path` from the camp's defaults, for when the file itself is removed.
+pub(crate) fn forget_default_context_path(camp_dir: &Path, path: &str) -> Result<(), String> {
+    let mut config = read_camp_config(camp_dir)?;
+    let before = config.default_context_paths.len();
+    config
+        .default_context_paths
+        .retain(|existing| existing != path);
+    if config.default_context_paths.len() == before {
+        return Ok(());
+    }
+    write_camp_config(camp_dir, &config)
+}
+
+/// Replaces the camp's default context files and returns the stored paths.
+#[tauri::command]
+pub fn camp_set_default_context_paths(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    paths: Vec<String>,
+) -> Result<Vec<String>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let paths = normalize_default_context_paths(&camp_dir, &paths)?;
+
+    let mut config = read_camp_config(&camp_dir)?;
+    config.default_context_paths = paths.clone();
+    write_camp_config(&camp_dir, &config)?;
+    Ok(paths)
+}
+
+#[tauri::command]
+pub fn camp_load_default_context_files(
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<DefaultContextFile>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let config = read_camp_config(&camp_dir)?;
+    load_default_context_files(&camp_dir, &config.default_context_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn load_default_context_files_should_report_missing_large_and_outdated_files() {
+        let workspace = make_temp_dir("basecamp-default-context");
+        let camp_dir = workspace.join("camps").join("demo");
+        let camp_context = camp_context_dir(&camp_dir);
+        fs::create_dir_all(camp_context.join("docs")).expect("camp context should be created");
+        fs::create_dir_all(workspace_context_dir(&workspace)).expect("workspace context");
+        fs::write(camp_context.join("docs/notes.md"), "v1").expect("write notes");
+        fs::write(camp_context.join("spec.md"), "spec").expect("write spec");
+        fs::write(
+            camp_context.join("dump.txt"),
+            "x".repeat(MAX_DEFAULT_CONTEXT_FILE_BYTES as usize + 1),
+        )
+        .expect("write dump");
+        let source = workspace_context_dir(&workspace).join("spec.md");
+        fs::write(&source, "spec, revised").expect("write workspace spec");
+        fs::File::options()
+            .write(true)
+            .open(&source)
+            .and_then(|file| file.set_modified(SystemTime::now() + Duration::from_secs(60)))
+            .expect("bump workspace spec mtime");
+
+        let normalized = normalize_default_context_paths(
+            &camp_dir,
+            &[
+                "docs/notes.md".to_string(),
+                " docs/notes.md ".to_string(),
+                "spec.md".to_string(),
+            ],
+        )
+        .expect("paths should normalize");
+        assert_eq!(normalized, vec!["docs/notes.md", "spec.md"]);
+        assert!(normalize_default_context_paths(&camp_dir, &["docs".to_string()]).is_err());
+        assert!(normalize_default_context_paths(&camp_dir, &["../camp.json".to_string()]).is_err());
+
+        fs::remove_file(camp_context.join("docs/notes.md")).expect("remove notes");
+        let files = load_default_context_files(
+            &camp_dir,
+            &[
+                "docs/notes.md".to_string(),
+                "spec.md".to_string(),
+                "dump.txt".to_string(),
+            ],
+        )
+        .expect("files should load");
+        let statuses: Vec<DefaultContextStatus> = files.iter().map(|file| file.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                DefaultContextStatus::Missing,
+                DefaultContextStatus::Outdated,
+                DefaultContextStatus::TooLarge,
+            ]
+        );
+        assert_eq!(files[1].content.as_deref(), Some("spec"));
+        assert!(files[2].content.is_none());
+    }
+}
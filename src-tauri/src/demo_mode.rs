@@ -0,0 +1,249 @@
+use std::sync::atomic::Ordering;
+
+use rusqlite::Connection;
+use tauri::{ipc::Invoke, Manager, Runtime, State, Window};
+
+use crate::{
+    ensure_main_window,
+    error::{BasecampError, BasecampErrorKind, CommandResult},
+    get_setting_value, set_setting_value, AppState,
+};
+
+const SETTING_READ_ONLY_MODE: &str = "read_only_mode";
+
+/// Commands that write to the database, the workspace or an external system.
+/// Anything that only reads stays available in read-only mode. New mutating
+/// commands must be added here.
+const MUTATING_COMMANDS: &[&str] = &[
+    "save_api_key",
+    "openrouter_sync_models",
+    "provider_update",
+    "provider_refresh_models",
+    "insert_run",
+    "send_chat_streaming",
+    "openrouter_enrich_run",
+    "update_run_rating_and_tags",
+    "ensure_default_workspace",
+    "set_workspace_path",
+    "set_tools_enabled",
+    "set_default_model",
+    "set_developer_inspect_mode",
+    "inspect_write_turn_request",
+    "inspect_write_turn_response",
+    "inspect_write_turn_bundle",
+    "insert_tool_call_start",
+    "update_tool_call_result",
+    "update_tool_call_error",
+    "write_note_to_workspace",
+    "camp_attach_workspace_context_file",
+    "camp_detach_workspace_context_file",
+    "tauri_cmd_write_context_file",
+    "tauri_cmd_write_context_file_bytes",
+    "camp_delete",
+    "camp_create",
+    "camp_update_config",
+    "camp_suggest_title",
+    "camp_translate_messages",
+    "set_title_model",
+    "synthesize_speech",
+    "set_tts_engine",
+    "set_splash_duration_ms",
+    "install_update",
+    "set_update_channel",
+    "quick_capture",
+    "set_quick_capture_settings",
+    "set_tray_settings",
+    "set_digest_settings",
+    "run_digest_now",
+    "merge_sync_conflict",
+    "set_sync_settings",
+    "run_sync",
+    "generate_workspace_manifest",
+    "set_global_instructions",
+    "set_model_note",
+    "delete_model_note",
+    "camp_rebuild_artifact_index",
+    "workspace_gc",
+    "set_provider_concurrency",
+    "camp_extract_code_artifacts",
+    "camp_patch_memory",
+    "camp_scratchpad_remember",
+    "camp_fetch_url",
+    "camp_set_fetch_allowed_domains",
+    "register_user_database",
+    "unregister_user_database",
+    "set_embedding_backend",
+    "download_local_embedding_model",
+    "delete_local_embedding_model",
+    "camp_refresh_embedding_index",
+    "camp_create_from_template",
+    "project_create",
+    "project_delete",
+    "camp_set_project",
+    "camp_mark_opened",
+    "camp_set_favorite",
+    "camp_set_default_context_paths",
+    "camp_restore_context_file_version",
+    "camp_rename_folder",
+    "camp_update_system_prompt",
+    "camp_update_memory",
+    "camp_append_message",
+    "camp_undo_last_exchange",
+    "regenerate_message",
+    "select_variant",
+    "run_council",
+    "camp_create_artifact_from_message",
+    "camp_create_artifact",
+    "camp_create_artifact_from_file",
+    "camp_update_artifact",
+    "camp_toggle_artifact_archive",
+    "camp_increment_artifact_usage",
+    "camp_set_artifact_always_include",
+    "camp_reorder_always_include_artifacts",
+    "camp_delete_artifact",
+    "camp_restore_trash_entry",
+    "camp_empty_trash",
+    "camp_create_checkpoint",
+    "camp_rollback_to_checkpoint",
+    "create_team_agent",
+    "create_team_from_template",
+    "remove_team_agent",
+    "update_team_settings",
+    "decompose_task",
+    "execute_agent_step",
+    "answer_clarification",
+    "run_reflection_loop",
+    "run_reflection_batch",
+    "promote_artifact",
+    "promote_all_passing",
+    "archive_team_runs",
+    "mcp_register_server",
+    "mcp_update_server",
+    "mcp_delete_server",
+    "mcp_set_enabled",
+    "set_mcp_tool_cache_ttl",
+    "mcp_install_from_catalog",
+    "mcp_call_tool",
+    "camp_restore_prompt_version",
+    "camp_pin_message",
+    "camp_unpin_message",
+    "db_set_encryption_passphrase",
+    "set_privacy_settings",
+    "set_outbound_scrub_settings",
+    "set_approval_policy",
+    "set_max_iterations",
+    "set_max_continuations",
+    "set_mcp_sampling_policy",
+    "mcp_oauth_authorize",
+    "mcp_oauth_sign_out",
+];
+
+fn is_mutating_command(command: &str) -> bool {
+    MUTATING_COMMANDS.contains(&command)
+}
+
+pub(crate) fn load_read_only_mode(connection: &Connection) -> bool {
+    get_setting_value(connection, SETTING_READ_ONLY_MODE)
+        .ok()
+        .flatten()
+        .is_some_and(|value| value == "1")
+}
+
+fn read_only_error(command: &str) -> BasecampError {
+    BasecampError::new(
+        BasecampErrorKind::ReadOnly,
+        "Basecamp is in read-only mode. Turn it off in Settings to make changes.",
+    )
+    .with_context("command", command)
+}
+
+/// For commands that only write for some payloads, such as a transcript check
+/// that may also repair, and so cannot be listed in `MUTATING_COMMANDS`.
+pub(crate) fn ensure_writable(state: &AppState, command: &str) -> CommandResult<()> {
+    if state.read_only.load(Ordering::SeqCst) {
+        return Err(read_only_error(command));
+    }
+    Ok(())
+}
+
+/// Wraps the generated command handler so mutating commands are rejected while
+/// read-only mode is on, without every command having to check for itself.
+pub(crate) fn guard_read_only<R: Runtime>(
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let command = invoke.message.command().to_string();
+        let read_only = invoke
+            .message
+            .webview()
+            .try_state::<AppState>()
+            .is_some_and(|state| state.read_only.load(Ordering::SeqCst));
+        if read_only && is_mutating_command(&command) {
+            invoke.resolver.reject(read_only_error(&command));
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[tauri::command]
+pub fn get_read_only_mode(state: State<'_, AppState>) -> bool {
+    state.read_only.load(Ordering::SeqCst)
+}
+
+#[tauri::command]
+pub fn set_read_only_mode(
+    window: Window,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<bool, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(
+        &connection,
+        SETTING_READ_ONLY_MODE,
+        if enabled { "1" } else { "0" },
+    )
+    .map_err(|err| format!("Unable to save read-only mode: {err}"))?;
+    state.read_only.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_only_mode_should_block_writes_and_persist() {
+        assert!(is_mutating_command("camp_update_memory"));
+        assert!(is_mutating_command("mcp_call_tool"));
+        assert!(!is_mutating_command("camp_load"));
+        assert!(!is_mutating_command("list_runs"));
+        assert!(!is_mutating_command("set_read_only_mode"));
+
+        let registered: Vec<&str> = include_str!("lib.rs")
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .map(|line| line.rsplit("::").next().unwrap_or(line))
+            .collect();
+        for command in MUTATING_COMMANDS {
+            assert!(
+                registered.contains(command),
+                "{command} is not a registered command"
+            );
+        }
+
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        crate::migrations::run_migrations(&connection, false).expect("schema should be created");
+        assert!(!load_read_only_mode(&connection));
+        set_setting_value(&connection, SETTING_READ_ONLY_MODE, "1").expect("setting should save");
+        assert!(load_read_only_mode(&connection));
+
+        let error = serde_json::to_value(read_only_error("camp_delete")).expect("serializes");
+        assert_eq!(error["kind"], "read_only");
+        assert_eq!(error["context"]["command"], "camp_delete");
+    }
+}
@@ -0,0 +1,607 @@
+//! Daily digest: once a day a background job gathers the previous day's
+//! activity across camps (new messages, new artifacts, spend), asks the
+//! configured model to summarize it, and saves the summary as a note artifact
+//! in the digest camp.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+
+use crate::chat_stream::run_payload;
+use crate::providers::{BasecampChatMetadata, BasecampChatRequest, RequestPriority};
+use crate::{
+    camp_artifacts_index_path, camp_config_path, camp_transcript_path, create_artifact,
+    dispatch_chat, ensure_camps_root, get_setting_value, now_timestamp_ms, parse_model_reference,
+    read_camp_config, read_json_file, read_transcript, resolve_existing_camp_dir,
+    set_setting_value, store_run, touch_camp_updated_at, validate_camp_identifier, AppState,
+    ArtifactSource, CampArtifactsIndex, SETTING_DEFAULT_MODEL,
+};
+
+const SETTING_DIGEST: &str = "digest_settings";
+const SETTING_DIGEST_STATUS: &str = "digest_status";
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+const DIGEST_TAG: &str = "digest";
+const DEFAULT_DIGEST_HOUR: u8 = 7;
+const DAY_MS: i64 = 86_400_000;
+const EXCERPTS_PER_CAMP: usize = 3;
+const EXCERPT_MAX_CHARS: usize = 200;
+const DIGEST_PROMPT: &str = "You write a short daily digest of someone's work in Basecamp, a workspace of chat 'camps'. From the activity listed below, write a brief markdown summary: a one-line overview, then one bullet per camp covering what was worked on and any artifacts saved, then the day's total spend. Use only the facts given; don't invent details.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestSettings {
+    pub enabled: bool,
+    /// Camp that receives the digest notes.
+    pub camp_id: Option<String>,
+    /// Model reference such as `openrouter/openai/gpt-4o-mini`; falls back to
+    /// the default model.
+    pub model: Option<String>,
+    /// Local hour (0-23) after which yesterday's digest is written.
+    #[serde(default = "default_digest_hour")]
+    pub hour: u8,
+    /// Minutes east of UTC, taken from the UI when settings are saved, so
+    /// "yesterday" follows the user's calendar day.
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+fn default_digest_hour() -> u8 {
+    DEFAULT_DIGEST_HOUR
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            camp_id: None,
+            model: None,
+            hour: DEFAULT_DIGEST_HOUR,
+            utc_offset_minutes: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DigestStatus {
+    /// Local day (days since the epoch) of the last digest attempt that
+    /// finished, whether or not there was anything to summarize.
+    pub last_day: Option<i64>,
+    pub last_run_at: Option<i64>,
+    pub last_artifact_id: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CampActivity {
+    pub camp_id: String,
+    pub camp_name: String,
+    pub message_count: usize,
+    pub artifact_titles: Vec<String>,
+    pub run_count: i64,
+    pub total_cost: f64,
+    /// First lines of the day's user messages. Left empty for camps in
+    /// privacy mode, which only contribute counts.
+    pub excerpts: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DigestResult {
+    pub day: String,
+    pub camp_id: String,
+    /// `None` when there was no activity and nothing was written.
+    pub artifact_id: Option<String>,
+    pub camps: usize,
+}
+
+pub(crate) fn load_digest_settings(connection: &Connection) -> Result<DigestSettings, String> {
+    let raw = get_setting_value(connection, SETTING_DIGEST)
+        .map_err(|err| format!("Failed to read digest settings: {err}"))?;
+    Ok(raw
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+/// Points the digest at a camp's new id after its folder is renamed.
+pub(crate) fn rename_digest_camp(
+    connection: &Connection,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), String> {
+    let mut settings = load_digest_settings(connection)?;
+    if settings.camp_id.as_deref() != Some(from_id) || from_id == to_id {
+        return Ok(());
+    }
+    settings.camp_id = Some(to_id.to_string());
+    let serialized = serde_json::to_string(&settings)
+        .map_err(|err| format!("Unable to serialize digest settings: {err}"))?;
+    set_setting_value(connection, SETTING_DIGEST, &serialized)
+        .map_err(|err| format!("Failed to save digest settings: {err}"))
+}
+
+fn load_digest_status(connection: &Connection) -> DigestStatus {
+    get_setting_value(connection, SETTING_DIGEST_STATUS)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_digest_status(connection: &Connection, status: &DigestStatus) -> Result<(), String> {
+    let serialized = serde_json::to_string(status)
+        .map_err(|err| format!("Unable to serialize digest status: {err}"))?;
+    set_setting_value(connection, SETTING_DIGEST_STATUS, &serialized)
+        .map_err(|err| format!("Failed to save digest status: {err}"))
+}
+
+/// Local calendar day of `timestamp_ms`, as days since the epoch.
+pub(crate) fn local_day(timestamp_ms: i64, utc_offset_minutes: i32) -> i64 {
+    (timestamp_ms + i64::from(utc_offset_minutes) * 60_000).div_euclid(DAY_MS)
+}
+
+/// Local hour of `timestamp_ms`, 0-23.
+fn local_hour(timestamp_ms: i64, utc_offset_minutes: i32) -> u8 {
+    ((timestamp_ms + i64::from(utc_offset_minutes) * 60_000).rem_euclid(DAY_MS) / 3_600_000) as u8
+}
+
+/// Start and end (exclusive) of a local day in unix milliseconds.
+pub(crate) fn day_bounds(day: i64, utc_offset_minutes: i32) -> (i64, i64) {
+    let start = day * DAY_MS - i64::from(utc_offset_minutes) * 60_000;
+    (start, start + DAY_MS)
+}
+
+pub(crate) fn day_label(day: i64) -> String {
+    time::OffsetDateTime::from_unix_timestamp(day * 86_400)
+        .map(|moment| moment.date().to_string())
+        .unwrap_or_else(|_| day.to_string())
+}
+
+fn excerpt(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if line.chars().count() <= EXCERPT_MAX_CHARS {
+        return Some(line.to_string());
+    }
+    let shortened: String = line.chars().take(EXCERPT_MAX_CHARS).collect();
+    Some(format!("{shortened}…"))
+}
+
+fn camp_activity(camp_dir: &Path, start: i64, end: i64) -> Result<CampActivity, String> {
+    let config = read_camp_config(camp_dir)?;
+    let in_window = |timestamp: i64| timestamp >= start && timestamp < end;
+
+    let messages: Vec<_> = read_transcript(&camp_transcript_path(camp_dir))?
+        .into_iter()
+        .filter(|message| in_window(message.created_at))
+        .collect();
+    let index_path = camp_artifacts_index_path(camp_dir);
+    let artifact_titles = if index_path.exists() {
+        read_json_file::<CampArtifactsIndex>(&index_path)?
+            .artifacts
+            .into_iter()
+            .filter(|artifact| in_window(artifact.created_at))
+            .filter(|artifact| !artifact.tags.iter().any(|tag| tag == DIGEST_TAG))
+            .map(|artifact| artifact.title)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // Excerpts are message text; local-only camps never send theirs to a
+    // provider, and the digest model may be a cloud one.
+    let excerpts = if config.privacy_mode || config.local_only {
+        Vec::new()
+    } else {
+        messages
+            .iter()
+            .filter(|message| message.role == "user")
+            .filter_map(|message| excerpt(&message.content))
+            .take(EXCERPTS_PER_CAMP)
+            .collect()
+    };
+
+    Ok(CampActivity {
+        camp_id: config.id,
+        camp_name: config.name,
+        message_count: messages.len(),
+        artifact_titles,
+        run_count: 0,
+        total_cost: 0.0,
+        excerpts,
+    })
+}
+
+/// Activity for every camp touched between `start` and `end`, busiest first.
+fn collect_activity(
+    connection: &Connection,
+    start: i64,
+    end: i64,
+) -> Result<Vec<CampActivity>, String> {
+    let mut spend: HashMap<String, (i64, f64)> = HashMap::new();
+    let mut statement = connection
+        .prepare(
+            "SELECT camp_id, COUNT(*), COALESCE(SUM(total_cost), 0)
+             FROM runs
+             WHERE camp_id IS NOT NULL AND timestamp >= ?1 AND timestamp < ?2
+             GROUP BY camp_id",
+        )
+        .map_err(|err| format!("Unable to load run costs: {err}"))?;
+    let rows = statement
+        .query_map(params![start, end], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })
+        .map_err(|err| format!("Unable to load run costs: {err}"))?;
+    for row in rows {
+        let (camp_id, count, cost) =
+            row.map_err(|err| format!("Unable to load run costs: {err}"))?;
+        spend.insert(camp_id, (count, cost));
+    }
+
+    let camps_root = ensure_camps_root(connection)?;
+    let entries =
+        fs::read_dir(&camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+    let mut activity = Vec::new();
+    for entry in entries.flatten() {
+        let camp_dir = entry.path();
+        if !camp_dir.is_dir() || !camp_config_path(&camp_dir).exists() {
+            continue;
+        }
+        // One unreadable camp shouldn't cost the whole digest.
+        let Ok(mut camp) = camp_activity(&camp_dir, start, end) else {
+            continue;
+        };
+        if let Some((count, cost)) = spend.get(&camp.camp_id) {
+            camp.run_count = *count;
+            camp.total_cost = *cost;
+        }
+        if camp.message_count > 0 || !camp.artifact_titles.is_empty() || camp.run_count > 0 {
+            activity.push(camp);
+        }
+    }
+    activity.sort_by(|a, b| {
+        b.message_count
+            .cmp(&a.message_count)
+            .then_with(|| a.camp_name.cmp(&b.camp_name))
+    });
+    Ok(activity)
+}
+
+/// Plain-text listing of the day's activity that the model summarizes.
+fn activity_brief(label: &str, activity: &[CampActivity]) -> String {
+    let total_cost: f64 = activity.iter().map(|camp| camp.total_cost).sum();
+    let mut brief = format!("Activity for {label} (total spend ${total_cost:.4}):");
+    for camp in activity {
+        brief.push_str(&format!(
+            "\n\nCamp \"{}\": {} new messages, {} model requests, ${:.4} spent.",
+            camp.camp_name, camp.message_count, camp.run_count, camp.total_cost
+        ));
+        if !camp.artifact_titles.is_empty() {
+            brief.push_str(&format!(
+                "\nNew artifacts: {}.",
+                camp.artifact_titles.join(", ")
+            ));
+        }
+        for line in &camp.excerpts {
+            brief.push_str(&format!("\n- asked: {line}"));
+        }
+    }
+    brief
+}
+
+fn digest_request(camp_id: &str, model: &str, brief: String) -> BasecampChatRequest {
+    let (provider_kind, model_id) = parse_model_reference(model);
+    BasecampChatRequest {
+        provider_kind,
+        model_id,
+        messages: vec![
+            json!({ "role": "system", "content": DIGEST_PROMPT }),
+            json!({ "role": "user", "content": brief }),
+        ],
+        tools: None,
+        tool_choice: None,
+        temperature: None,
+        max_tokens: None,
+        top_p: None,
+        stop: None,
+        logit_bias: None,
+        stream: false,
+        metadata: BasecampChatMetadata {
+            camp_id: Some(camp_id.to_string()),
+            correlation_id: Some(uuid::Uuid::new_v4().to_string()),
+            provider_kind: Some(provider_kind),
+            priority: RequestPriority::Scheduled,
+        },
+    }
+}
+
+/// Summarizes local `day` into a note in the digest camp. Days without any
+/// activity produce no note.
+async fn write_digest(app: &AppHandle, state: &AppState, day: i64) -> Result<DigestResult, String> {
+    let (settings, camp_dir, activity, model) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let settings = load_digest_settings(&connection)?;
+        let camp_id = settings
+            .camp_id
+            .clone()
+            .ok_or_else(|| "Choose a digest camp in Settings first.".to_string())?;
+        let camp_dir = resolve_existing_camp_dir(&ensure_camps_root(&connection)?, &camp_id)?;
+        let (start, end) = day_bounds(day, settings.utc_offset_minutes);
+        let activity = collect_activity(&connection, start, end)?;
+        let model = match settings.model.clone() {
+            Some(model) => model,
+            None => get_setting_value(&connection, SETTING_DEFAULT_MODEL)
+                .map_err(|err| format!("Unable to load default model setting: {err}"))?
+                .ok_or_else(|| "Choose a digest model or a default model first.".to_string())?,
+        };
+        (settings, camp_dir, activity, model)
+    };
+    let camp_id = settings.camp_id.unwrap_or_default();
+    let label = day_label(day);
+    if activity.is_empty() {
+        return Ok(DigestResult {
+            day: label,
+            camp_id,
+            artifact_id: None,
+            camps: 0,
+        });
+    }
+
+    let request = digest_request(&camp_id, &model, activity_brief(&label, &activity));
+    let outcome = dispatch_chat(state, request.clone(), &Channel::new(|_| Ok(()))).await;
+    let run_id = uuid::Uuid::new_v4().to_string();
+    store_run(
+        app,
+        state,
+        run_payload(&run_id, &camp_id, &request, None, outcome.as_ref()),
+    )?;
+    let response = outcome.map_err(|err| err.message)?;
+    let summary = response.output_text.trim();
+    if summary.is_empty() {
+        return Err("The digest model returned an empty summary.".to_string());
+    }
+
+    let artifact = create_artifact(
+        &camp_dir,
+        ArtifactSource {
+            message_id: String::new(),
+            role: "assistant".to_string(),
+            path: None,
+        },
+        &format!("Daily Digest — {label}"),
+        summary,
+        &[DIGEST_TAG.to_string()],
+    )?;
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(DigestResult {
+        day: label,
+        camp_id,
+        artifact_id: Some(artifact.metadata.id),
+        camps: activity.len(),
+    })
+}
+
+/// Writes the digest for `day` and records the outcome in the digest status.
+async fn run_and_record(
+    app: &AppHandle,
+    state: &AppState,
+    day: i64,
+) -> Result<DigestResult, String> {
+    let outcome = write_digest(app, state, day).await;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let mut status = load_digest_status(&connection);
+    status.last_run_at = Some(now_timestamp_ms());
+    match &outcome {
+        Ok(result) => {
+            status.last_day = Some(day);
+            status.last_error = None;
+            if result.artifact_id.is_some() {
+                status.last_artifact_id = result.artifact_id.clone();
+            }
+        }
+        Err(err) => status.last_error = Some(err.clone()),
+    }
+    save_digest_status(&connection, &status)?;
+    outcome
+}
+
+/// The local day that is due for a digest, if any: yesterday, once the
+/// configured hour has passed and it hasn't been written yet.
+fn due_day(settings: &DigestSettings, status: &DigestStatus, now_ms: i64) -> Option<i64> {
+    if !settings.enabled || settings.camp_id.is_none() {
+        return None;
+    }
+    if local_hour(now_ms, settings.utc_offset_minutes) < settings.hour {
+        return None;
+    }
+    let yesterday = local_day(now_ms, settings.utc_offset_minutes) - 1;
+    match status.last_day {
+        Some(last_day) if last_day >= yesterday => None,
+        _ => Some(yesterday),
+    }
+}
+
+/// Checks every few minutes whether a digest is due. Failures are kept in the
+/// digest status and retried on the next check.
+pub(crate) fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Some(state) = app.try_state::<AppState>() else {
+                continue;
+            };
+            // Writing a digest creates an artifact, which read-only mode forbids.
+            if state.read_only.load(Ordering::SeqCst) {
+                continue;
+            }
+            let day = match state.connection.lock() {
+                Ok(connection) => load_digest_settings(&connection).ok().and_then(|settings| {
+                    due_day(
+                        &settings,
+                        &load_digest_status(&connection),
+                        now_timestamp_ms(),
+                    )
+                }),
+                Err(_) => None,
+            };
+            if let Some(day) = day {
+                let _ = run_and_record(&app, &state, day).await;
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_digest_settings(state: State<'_, AppState>) -> Result<DigestSettings, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_digest_settings(&connection)
+}
+
+#[tauri::command]
+pub fn set_digest_settings(
+    state: State<'_, AppState>,
+    settings: DigestSettings,
+) -> Result<DigestSettings, String> {
+    if settings.hour > 23 {
+        return Err("Digest hour must be between 0 and 23.".to_string());
+    }
+    let settings = DigestSettings {
+        camp_id: settings
+            .camp_id
+            .filter(|camp_id| !camp_id.trim().is_empty())
+            .map(|camp_id| validate_camp_identifier(&camp_id))
+            .transpose()?,
+        model: settings
+            .model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty()),
+        // Offsets run from UTC-12:00 to UTC+14:00.
+        utc_offset_minutes: settings.utc_offset_minutes.clamp(-12 * 60, 14 * 60),
+        ..settings
+    };
+    if settings.enabled && settings.camp_id.is_none() {
+        return Err("Choose a camp for the digest before enabling it.".to_string());
+    }
+    let serialized = serde_json::to_string(&settings)
+        .map_err(|err| format!("Unable to serialize digest settings: {err}"))?;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    if let Some(camp_id) = &settings.camp_id {
+        resolve_existing_camp_dir(&ensure_camps_root(&connection)?, camp_id)?;
+    }
+    set_setting_value(&connection, SETTING_DIGEST, &serialized)
+        .map_err(|err| format!("Failed to save digest settings: {err}"))?;
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_digest_status(state: State<'_, AppState>) -> Result<DigestStatus, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    Ok(load_digest_status(&connection))
+}
+
+/// Writes yesterday's digest now instead of waiting for the scheduled hour.
+#[tauri::command]
+pub async fn run_digest_now(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DigestResult, String> {
+    let offset = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        load_digest_settings(&connection)?.utc_offset_minutes
+    };
+    let yesterday = local_day(now_timestamp_ms(), offset) - 1;
+    run_and_record(&app, &state, yesterday).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_day_should_wait_for_the_hour_and_run_once_per_day() {
+        let settings = DigestSettings {
+            enabled: true,
+            camp_id: Some("journal".to_string()),
+            model: None,
+            hour: 7,
+            utc_offset_minutes: -300,
+        };
+        // 2024-03-10 11:30 UTC is 06:30 at UTC-5.
+        let before_hour = 1_710_070_200_000;
+        let after_hour = before_hour + 3_600_000;
+        let today = local_day(after_hour, settings.utc_offset_minutes);
+
+        assert_eq!(
+            due_day(&settings, &DigestStatus::default(), before_hour),
+            None
+        );
+        assert_eq!(
+            due_day(&settings, &DigestStatus::default(), after_hour),
+            Some(today - 1)
+        );
+        let done = DigestStatus {
+            last_day: Some(today - 1),
+            ..DigestStatus::default()
+        };
+        assert_eq!(due_day(&settings, &done, after_hour), None);
+
+        let (start, end) = day_bounds(today - 1, settings.utc_offset_minutes);
+        assert_eq!(end - start, DAY_MS);
+        assert_eq!(local_day(start, settings.utc_offset_minutes), today - 1);
+        assert_eq!(local_day(end, settings.utc_offset_minutes), today);
+        assert_eq!(day_label(today - 1), "2024-03-09");
+    }
+
+    #[test]
+    fn rename_digest_camp_should_follow_the_renamed_camp() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        crate::migrations::run_migrations(&connection, false).expect("schema should be created");
+        let settings = DigestSettings {
+            enabled: true,
+            camp_id: Some("old-camp".to_string()),
+            ..DigestSettings::default()
+        };
+        set_setting_value(
+            &connection,
+            SETTING_DIGEST,
+            &serde_json::to_string(&settings).expect("settings should serialize"),
+        )
+        .expect("settings should save");
+
+        rename_digest_camp(&connection, "other-camp", "elsewhere")
+            .expect("unrelated rename should succeed");
+        rename_digest_camp(&connection, "old-camp", "new-camp").expect("rename should succeed");
+
+        let renamed = load_digest_settings(&connection).expect("settings should load");
+        assert_eq!(renamed.camp_id.as_deref(), Some("new-camp"));
+        assert!(renamed.enabled);
+    }
+}
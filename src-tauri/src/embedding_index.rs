@@ -0,0 +1,704 @@
+//! Embedding index over each camp's context files. Files are split into
+//! paragraph-sized chunks and embedded with the model picked in settings
+//! (see `embedding_models`), through Ollama's `/api/embed` or in-process; the
+//! vectors are kept in the app database next to a SHA-256 of the file they
+//! came from. A refresh only re-embeds files whose hash (or the embedding
+//! model) changed, drops files that were removed and leaves the rest alone.
+//! Once a camp has been indexed, the frontend refreshes it again whenever
+//! the camp is opened or its context files are written.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+
+use crate::embedding_models::{self, EmbeddingBackendSetting};
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::{registry, ProviderKind};
+use crate::tabular::{self, TableFormat};
+use crate::{
+    camp_context_dir, ensure_camps_root, now_timestamp_ms, resolve_existing_camp_dir, AppState,
+    CAMP_CONTEXT_DIR,
+};
+
+pub(crate) const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+const MAX_EMBEDDED_FILE_BYTES: u64 = 1024 * 1024;
+/// Roughly 300 tokens of English, small enough for any embedding model.
+const CHUNK_TARGET_CHARS: usize = 1_200;
+const EMBED_BATCH_SIZE: usize = 32;
+
+/// Camps with a refresh in flight; a second refresh of the same camp returns
+/// straight away instead of embedding the same files twice.
+static REFRESHING_CAMPS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+pub fn create_embedding_tables(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS embedding_files (
+            camp_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            content_hash TEXT NOT NULL,
+            model TEXT NOT NULL,
+            chunk_count INTEGER NOT NULL,
+            indexed_at INTEGER NOT NULL,
+            PRIMARY KEY (camp_id, path)
+        );
+
+        CREATE TABLE IF NOT EXISTS embedding_chunks (
+            camp_id TEXT NOT NULL,
+            path TEXT NOT NULL,
+            chunk_index INTEGER NOT NULL,
+            text TEXT NOT NULL,
+            vector BLOB NOT NULL,
+            PRIMARY KEY (camp_id, path, chunk_index)
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+/// Where embeddings come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum EmbeddingBackend {
+    Ollama { base_url: String, model: String },
+    Local { models_dir: PathBuf, model: String },
+}
+
+impl EmbeddingBackend {
+    /// The `model` stored with indexed files; see `EmbeddingBackendSetting::model_key`.
+    pub fn model_key(&self) -> String {
+        match self {
+            Self::Ollama { model, .. } => model.clone(),
+            Self::Local { model, .. } => EmbeddingBackendSetting::Local {
+                model: model.clone(),
+            }
+            .model_key(),
+        }
+    }
+}
+
+pub(crate) fn resolve_backend(
+    connection: &Connection,
+    setting: &EmbeddingBackendSetting,
+    models_dir: &Path,
+) -> CommandResult<EmbeddingBackend> {
+    Ok(match setting {
+        EmbeddingBackendSetting::Ollama { model } => EmbeddingBackend::Ollama {
+            base_url: registry::get_provider(connection, ProviderKind::Ollama)?
+                .map(|provider| provider.base_url)
+                .unwrap_or_else(|| ProviderKind::Ollama.default_base_url().to_string()),
+            model: model.clone(),
+        },
+        EmbeddingBackendSetting::Local { model } => EmbeddingBackend::Local {
+            models_dir: models_dir.to_path_buf(),
+            model: model.clone(),
+        },
+    })
+}
+
+/// The backend picked in settings.
+pub(crate) fn embedding_backend(
+    connection: &Connection,
+    models_dir: &Path,
+) -> CommandResult<EmbeddingBackend> {
+    let setting = embedding_models::load_backend_setting(connection)?;
+    resolve_backend(connection, &setting, models_dir)
+}
+
+/// A context file as it is on disk now. `path` is relative to the camp
+/// folder (`context/...`), matching the paths workspace search uses.
+struct ContextSource {
+    path: String,
+    content_hash: String,
+    text: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn collect_context_sources(context_root: &Path, current_dir: &Path, out: &mut Vec<ContextSource>) {
+    let Ok(entries) = fs::read_dir(current_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_context_sources(context_root, &entry_path, out);
+            continue;
+        }
+        let Ok(relative) = entry_path.strip_prefix(context_root) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let too_large = fs::metadata(&entry_path)
+            .map(|metadata| metadata.len() > MAX_EMBEDDED_FILE_BYTES)
+            .unwrap_or(true);
+        if too_large {
+            continue;
+        }
+        let Ok(bytes) = fs::read(&entry_path) else {
+            continue;
+        };
+        let content_hash = sha256_hex(&bytes);
+        // Spreadsheets are embedded as the same Markdown table default
+        // context sends; other binary files are skipped.
+        let text = match TableFormat::from_path(&relative) {
+            Ok(Some(format)) => tabular::read_table(&bytes, format, None)
+                .ok()
+                .map(|table| tabular::table_to_markdown(&table, tabular::DEFAULT_MARKDOWN_ROWS)),
+            _ => String::from_utf8(bytes).ok(),
+        };
+        let Some(text) = text.filter(|text| !text.trim().is_empty()) else {
+            continue;
+        };
+        out.push(ContextSource {
+            path: format!("{CAMP_CONTEXT_DIR}/{relative}"),
+            content_hash,
+            text,
+        });
+    }
+}
+
+fn camp_context_sources(camp_dir: &Path) -> Vec<ContextSource> {
+    let context_root = camp_context_dir(camp_dir);
+    let mut sources = Vec::new();
+    collect_context_sources(&context_root, &context_root, &mut sources);
+    sources.sort_by(|left, right| left.path.cmp(&right.path));
+    sources
+}
+
+/// Splits text into chunks of about `CHUNK_TARGET_CHARS`, breaking between
+/// paragraphs where it can and between lines or characters where it must.
+pub(crate) fn chunk_text(text: &str) -> Vec<String> {
+    fn push_piece(piece: &str, chunks: &mut Vec<String>, current: &mut String) {
+        if !current.is_empty()
+            && current.chars().count() + piece.chars().count() + 2 > CHUNK_TARGET_CHARS
+        {
+            chunks.push(std::mem::take(current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(piece);
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if paragraph.chars().count() <= CHUNK_TARGET_CHARS {
+            push_piece(paragraph, &mut chunks, &mut current);
+            continue;
+        }
+        for line in paragraph.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let chars: Vec<char> = line.chars().collect();
+            for piece in chars.chunks(CHUNK_TARGET_CHARS) {
+                let piece: String = piece.iter().collect();
+                push_piece(&piece, &mut chunks, &mut current);
+            }
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+pub(crate) fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub(crate) fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+pub(crate) fn cosine_similarity(left: &[f32], right: &[f32]) -> f32 {
+    let (mut dot, mut left_norm, mut right_norm) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for (a, b) in left.iter().zip(right) {
+        dot += a * b;
+        left_norm += a * a;
+        right_norm += b * b;
+    }
+    if left_norm == 0.0 || right_norm == 0.0 {
+        return 0.0;
+    }
+    dot / (left_norm.sqrt() * right_norm.sqrt())
+}
+
+pub(crate) async fn embed_texts(
+    client: &reqwest::Client,
+    backend: &EmbeddingBackend,
+    texts: &[String],
+) -> CommandResult<Vec<Vec<f32>>> {
+    match backend {
+        EmbeddingBackend::Ollama { base_url, model } => {
+            embed_with_ollama(client, base_url, model, texts).await
+        }
+        EmbeddingBackend::Local { models_dir, model } => {
+            let (models_dir, model, texts) = (models_dir.clone(), model.clone(), texts.to_vec());
+            tauri::async_runtime::spawn_blocking(move || {
+                embedding_models::embed_locally(&models_dir, &model, &texts)
+            })
+            .await
+            .map_err(|err| BasecampError::internal(format!("Local embedding failed: {err}")))?
+        }
+    }
+}
+
+async fn embed_with_ollama(
+    client: &reqwest::Client,
+    base_url: &str,
+    model: &str,
+    texts: &[String],
+) -> CommandResult<Vec<Vec<f32>>> {
+    let url = format!("{}/api/embed", base_url.trim_end_matches('/'));
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(EMBED_BATCH_SIZE) {
+        let response = client
+            .post(&url)
+            .json(&json!({ "model": model, "input": batch }))
+            .send()
+            .await
+            .map_err(|err| {
+                BasecampError::unavailable(format!("Unable to reach Ollama for embeddings: {err}"))
+                    .retryable(true)
+            })?;
+        let status = response.status();
+        let payload: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            let message = payload
+                .get("error")
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("Embedding request failed with status {status}"));
+            return Err(
+                BasecampError::unavailable(message).with_context("model", model.to_string())
+            );
+        }
+        let embeddings: Vec<Vec<f32>> = payload
+            .get("embeddings")
+            .cloned()
+            .and_then(|embeddings| serde_json::from_value(embeddings).ok())
+            .filter(|embeddings: &Vec<Vec<f32>>| embeddings.len() == batch.len())
+            .ok_or_else(|| {
+                BasecampError::unavailable("Ollama returned no embeddings for the request.")
+                    .with_context("model", model.to_string())
+            })?;
+        vectors.extend(embeddings);
+    }
+    Ok(vectors)
+}
+
+/// `(content_hash, model, chunk_count)` by path for what is already indexed.
+fn indexed_files(
+    connection: &Connection,
+    camp_id: &str,
+) -> CommandResult<HashMap<String, (String, String, usize)>> {
+    let mut statement = connection.prepare(
+        "SELECT path, content_hash, model, chunk_count FROM embedding_files WHERE camp_id = ?1",
+    )?;
+    let rows = statement
+        .query_map(params![camp_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                (
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?.max(0) as usize,
+                ),
+            ))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(rows)
+}
+
+fn forget_file(connection: &Connection, camp_id: &str, path: &str) -> CommandResult<()> {
+    connection.execute(
+        "DELETE FROM embedding_chunks WHERE camp_id = ?1 AND path = ?2",
+        params![camp_id, path],
+    )?;
+    connection.execute(
+        "DELETE FROM embedding_files WHERE camp_id = ?1 AND path = ?2",
+        params![camp_id, path],
+    )?;
+    Ok(())
+}
+
+fn store_file(
+    connection: &mut Connection,
+    camp_id: &str,
+    source: &ContextSource,
+    model: &str,
+    chunks: &[(String, Vec<f32>)],
+) -> CommandResult<()> {
+    let transaction = connection.transaction()?;
+    forget_file(&transaction, camp_id, &source.path)?;
+    for (index, (text, vector)) in chunks.iter().enumerate() {
+        transaction.execute(
+            "INSERT INTO embedding_chunks (camp_id, path, chunk_index, text, vector)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                camp_id,
+                source.path,
+                index as i64,
+                text,
+                encode_vector(vector)
+            ],
+        )?;
+    }
+    transaction.execute(
+        "INSERT INTO embedding_files (camp_id, path, content_hash, model, chunk_count, indexed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            camp_id,
+            source.path,
+            source.content_hash,
+            model,
+            chunks.len() as i64,
+            now_timestamp_ms()
+        ],
+    )?;
+    transaction.commit()?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddingFileState {
+    Fresh,
+    /// Edited, or embedded with a different model, since it was indexed.
+    Changed,
+    /// Not indexed yet.
+    New,
+    /// Indexed, but no longer in the context folder.
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EmbeddingFileStatus {
+    pub path: String,
+    pub state: EmbeddingFileState,
+    pub chunks: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CampEmbeddingStatus {
+    pub camp_id: String,
+    pub model: String,
+    /// Chunks the camp's context files make up now.
+    pub total_chunks: usize,
+    /// Of those, chunks that still need (re-)embedding.
+    pub stale_chunks: usize,
+    pub files: Vec<EmbeddingFileStatus>,
+}
+
+fn plan_refresh(
+    sources: &[ContextSource],
+    indexed: &HashMap<String, (String, String, usize)>,
+    model: &str,
+) -> Vec<EmbeddingFileStatus> {
+    let mut files: Vec<EmbeddingFileStatus> = sources
+        .iter()
+        .map(|source| {
+            let state = match indexed.get(&source.path) {
+                Some((hash, indexed_model, _))
+                    if *hash == source.content_hash && indexed_model == model =>
+                {
+                    EmbeddingFileState::Fresh
+                }
+                Some(_) => EmbeddingFileState::Changed,
+                None => EmbeddingFileState::New,
+            };
+            let chunks = match state {
+                EmbeddingFileState::Fresh => indexed[&source.path].2,
+                _ => chunk_text(&source.text).len(),
+            };
+            EmbeddingFileStatus {
+                path: source.path.clone(),
+                state,
+                chunks,
+            }
+        })
+        .collect();
+    let mut removed: Vec<EmbeddingFileStatus> = indexed
+        .iter()
+        .filter(|(path, _)| !sources.iter().any(|source| &source.path == *path))
+        .map(|(path, (_, _, chunks))| EmbeddingFileStatus {
+            path: path.clone(),
+            state: EmbeddingFileState::Removed,
+            chunks: *chunks,
+        })
+        .collect();
+    removed.sort_by(|left, right| left.path.cmp(&right.path));
+    files.extend(removed);
+    files
+}
+
+fn camp_status(camp_id: &str, model: &str, files: Vec<EmbeddingFileStatus>) -> CampEmbeddingStatus {
+    let current = files
+        .iter()
+        .filter(|file| file.state != EmbeddingFileState::Removed);
+    CampEmbeddingStatus {
+        camp_id: camp_id.to_string(),
+        model: model.to_string(),
+        total_chunks: current.clone().map(|file| file.chunks).sum(),
+        stale_chunks: current
+            .filter(|file| file.state != EmbeddingFileState::Fresh)
+            .map(|file| file.chunks)
+            .sum(),
+        files,
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshEmbeddingIndexPayload {
+    pub camp_id: String,
+    /// Skip camps that have never been indexed, for automatic refreshes.
+    #[serde(default)]
+    pub only_if_indexed: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EmbeddingRefreshReport {
+    pub camp_id: String,
+    pub model: String,
+    pub embedded_files: usize,
+    pub embedded_chunks: usize,
+    pub unchanged_files: usize,
+    pub removed_files: usize,
+    /// Another refresh of this camp was already running.
+    pub skipped: bool,
+}
+
+struct RefreshGuard(String);
+
+impl RefreshGuard {
+    fn acquire(camp_id: &str) -> CommandResult<Option<Self>> {
+        let mut refreshing = REFRESHING_CAMPS.lock().map_err(|_| BasecampError::lock())?;
+        if refreshing.iter().any(|id| id == camp_id) {
+            return Ok(None);
+        }
+        refreshing.push(camp_id.to_string());
+        Ok(Some(Self(camp_id.to_string())))
+    }
+}
+
+impl Drop for RefreshGuard {
+    fn drop(&mut self) {
+        if let Ok(mut refreshing) = REFRESHING_CAMPS.lock() {
+            refreshing.retain(|id| id != &self.0);
+        }
+    }
+}
+
+/// Brings a camp's embeddings up to date with its context folder, embedding
+/// only new and changed files.
+#[tauri::command]
+pub async fn camp_refresh_embedding_index(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: RefreshEmbeddingIndexPayload,
+) -> CommandResult<EmbeddingRefreshReport> {
+    let camp_id = payload.camp_id;
+    let models_dir = embedding_models::models_dir(&app)?;
+    let Some(_guard) = RefreshGuard::acquire(&camp_id)? else {
+        return Ok(EmbeddingRefreshReport {
+            camp_id,
+            skipped: true,
+            ..EmbeddingRefreshReport::default()
+        });
+    };
+
+    let (backend, sources, plan) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+        let backend = embedding_backend(&connection, &models_dir)?;
+        let indexed = indexed_files(&connection, &camp_id)?;
+        if payload.only_if_indexed && indexed.is_empty() {
+            return Ok(EmbeddingRefreshReport {
+                camp_id,
+                model: backend.model_key(),
+                ..EmbeddingRefreshReport::default()
+            });
+        }
+        let sources = camp_context_sources(&camp_dir);
+        let plan = plan_refresh(&sources, &indexed, &backend.model_key());
+        for file in plan
+            .iter()
+            .filter(|file| file.state == EmbeddingFileState::Removed)
+        {
+            forget_file(&connection, &camp_id, &file.path)?;
+        }
+        (backend, sources, plan)
+    };
+
+    let model = backend.model_key();
+    let mut report = EmbeddingRefreshReport {
+        camp_id: camp_id.clone(),
+        model: model.clone(),
+        ..EmbeddingRefreshReport::default()
+    };
+    for file in &plan {
+        match file.state {
+            EmbeddingFileState::Fresh => report.unchanged_files += 1,
+            EmbeddingFileState::Removed => report.removed_files += 1,
+            EmbeddingFileState::Changed | EmbeddingFileState::New => {
+                let Some(source) = sources.iter().find(|source| source.path == file.path) else {
+                    continue;
+                };
+                let chunks = chunk_text(&source.text);
+                let vectors = embed_texts(&state.provider_client, &backend, &chunks).await?;
+                // Each file is stored as soon as it is embedded, so a failure
+                // part-way keeps the progress made so far.
+                let mut connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+                store_file(
+                    &mut connection,
+                    &camp_id,
+                    source,
+                    &model,
+                    &chunks.into_iter().zip(vectors).collect::<Vec<_>>(),
+                )?;
+                report.embedded_files += 1;
+                report.embedded_chunks += file.chunks;
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Stale and total chunks for one camp, or for every camp that has been
+/// indexed when `camp_id` is omitted.
+#[tauri::command]
+pub fn embedding_index_status(
+    state: State<'_, AppState>,
+    camp_id: Option<String>,
+) -> CommandResult<Vec<CampEmbeddingStatus>> {
+    // Reading the context folders can be slow, so only the database lookups
+    // happen under the lock.
+    let (model, camps) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let model = embedding_models::load_backend_setting(&connection)?.model_key();
+        let camp_ids = match camp_id {
+            Some(camp_id) => vec![camp_id],
+            None => {
+                let mut statement = connection
+                    .prepare("SELECT DISTINCT camp_id FROM embedding_files ORDER BY camp_id")?;
+                let ids = statement
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()?;
+                ids
+            }
+        };
+
+        let mut camps = Vec::with_capacity(camp_ids.len());
+        for camp_id in camp_ids {
+            // A camp deleted since it was indexed has nothing left to refresh.
+            let Ok(camp_dir) = resolve_existing_camp_dir(&camps_root, &camp_id) else {
+                continue;
+            };
+            let indexed = indexed_files(&connection, &camp_id)?;
+            camps.push((camp_id, camp_dir, indexed));
+        }
+        (model, camps)
+    };
+
+    let statuses = camps
+        .into_iter()
+        .map(|(camp_id, camp_dir, indexed)| {
+            let plan = plan_refresh(&camp_context_sources(&camp_dir), &indexed, &model);
+            camp_status(&camp_id, &model, plan)
+        })
+        .collect();
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_refresh_should_only_mark_changed_and_new_files_stale() {
+        let long_paragraph = "word ".repeat(400);
+        let text = format!("Intro line.\n\n{long_paragraph}\n\nClosing line.");
+        let chunks = chunk_text(&text);
+        assert!(chunks.len() >= 2);
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.chars().count() <= CHUNK_TARGET_CHARS));
+        assert!(chunks[0].starts_with("Intro line."));
+        assert!(chunks
+            .last()
+            .is_some_and(|chunk| chunk.ends_with("Closing line.")));
+
+        let vector = vec![0.25_f32, -1.5, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+
+        let source = |path: &str, body: &str| ContextSource {
+            path: format!("context/{path}"),
+            content_hash: sha256_hex(body.as_bytes()),
+            text: body.to_string(),
+        };
+        let sources = vec![
+            source("notes.md", "unchanged"),
+            source("plan.md", "edited since"),
+            source("brand-new.md", "first time"),
+        ];
+        let indexed = HashMap::from([
+            (
+                "context/notes.md".to_string(),
+                (sha256_hex(b"unchanged"), "nomic-embed-text".to_string(), 3),
+            ),
+            (
+                "context/plan.md".to_string(),
+                (sha256_hex(b"original"), "nomic-embed-text".to_string(), 2),
+            ),
+            (
+                "context/gone.md".to_string(),
+                (sha256_hex(b"gone"), "nomic-embed-text".to_string(), 4),
+            ),
+        ]);
+
+        let plan = plan_refresh(&sources, &indexed, "nomic-embed-text");
+        let states: Vec<(&str, EmbeddingFileState, usize)> = plan
+            .iter()
+            .map(|file| (file.path.as_str(), file.state, file.chunks))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ("context/notes.md", EmbeddingFileState::Fresh, 3),
+                ("context/plan.md", EmbeddingFileState::Changed, 1),
+                ("context/brand-new.md", EmbeddingFileState::New, 1),
+                ("context/gone.md", EmbeddingFileState::Removed, 4),
+            ]
+        );
+        let status = camp_status("camp-1", "nomic-embed-text", plan);
+        assert_eq!((status.total_chunks, status.stale_chunks), (5, 2));
+
+        let switched = plan_refresh(&sources[..1], &indexed, "mxbai-embed-large");
+        assert_eq!(switched[0].state, EmbeddingFileState::Changed);
+    }
+}
@@ -0,0 +1,544 @@
+//! Which model the embedding index uses, and the local models it can run
+//! without a server. Ollama models are pulled with Ollama itself. Local
+//! models are ONNX builds run in-process by fastembed, downloaded from
+//! Hugging Face into the app data folder; they need the `local-embeddings`
+//! feature, which app builds enable in `tauri.conf.json`. Benchmarks embed a small built-in set of questions and passages
+//! so backends can be compared on speed and on how often the right passage
+//! ranks first.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::embedding_index::{self, DEFAULT_EMBEDDING_MODEL};
+use crate::error::{BasecampError, CommandResult};
+use crate::{get_setting_value, set_setting_value, AppState};
+
+const SETTING_EMBEDDING_BACKEND: &str = "embedding_backend";
+const EMBEDDING_MODELS_DIR: &str = "embedding-models";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EmbeddingBackendSetting {
+    /// A model served by the configured Ollama provider.
+    Ollama { model: String },
+    /// A fastembed model code, e.g. `Xenova/bge-small-en-v1.5`.
+    Local { model: String },
+}
+
+impl Default for EmbeddingBackendSetting {
+    fn default() -> Self {
+        Self::Ollama {
+            model: DEFAULT_EMBEDDING_MODEL.to_string(),
+        }
+    }
+}
+
+impl EmbeddingBackendSetting {
+    /// Recorded with every indexed file, so switching models re-embeds them.
+    pub fn model_key(&self) -> String {
+        match self {
+            Self::Ollama { model } => model.clone(),
+            Self::Local { model } => format!("local:{model}"),
+        }
+    }
+}
+
+pub(crate) struct LocalModelSpec {
+    pub code: &'static str,
+    pub label: &'static str,
+    pub dimensions: usize,
+    pub note: &'static str,
+}
+
+/// The fastembed models offered in settings, fastest first.
+pub(crate) const LOCAL_MODELS: &[LocalModelSpec] = &[
+    LocalModelSpec {
+        code: "Qdrant/all-MiniLM-L6-v2-onnx",
+        label: "MiniLM L6",
+        dimensions: 384,
+        note: "Smallest and fastest; weakest on long or technical text.",
+    },
+    LocalModelSpec {
+        code: "Xenova/bge-small-en-v1.5",
+        label: "BGE small (English)",
+        dimensions: 384,
+        note: "Fast, with good general quality.",
+    },
+    LocalModelSpec {
+        code: "intfloat/multilingual-e5-small",
+        label: "Multilingual E5 small",
+        dimensions: 384,
+        note: "For context that isn't in English.",
+    },
+    LocalModelSpec {
+        code: "Xenova/bge-base-en-v1.5",
+        label: "BGE base (English)",
+        dimensions: 768,
+        note: "Slower, higher quality.",
+    },
+    LocalModelSpec {
+        code: "nomic-ai/nomic-embed-text-v1.5",
+        label: "Nomic embed v1.5",
+        dimensions: 768,
+        note: "Handles long chunks; the same model Ollama uses by default.",
+    },
+];
+
+fn local_model_spec(code: &str) -> CommandResult<&'static LocalModelSpec> {
+    LOCAL_MODELS
+        .iter()
+        .find(|spec| spec.code.eq_ignore_ascii_case(code.trim()))
+        .ok_or_else(|| {
+            BasecampError::validation(format!("Unknown local embedding model: {}", code.trim()))
+        })
+}
+
+fn local_unsupported() -> BasecampError {
+    BasecampError::unavailable(
+        "This build can't run local embedding models. Use an Ollama model instead.",
+    )
+}
+
+fn validate_backend_setting(
+    setting: EmbeddingBackendSetting,
+) -> CommandResult<EmbeddingBackendSetting> {
+    match setting {
+        EmbeddingBackendSetting::Ollama { model } => {
+            let model = model.trim();
+            if model.is_empty() {
+                return Err(BasecampError::validation(
+                    "Name the Ollama model to embed with.",
+                ));
+            }
+            Ok(EmbeddingBackendSetting::Ollama {
+                model: model.to_string(),
+            })
+        }
+        EmbeddingBackendSetting::Local { model } => {
+            if !cfg!(feature = "local-embeddings") {
+                return Err(local_unsupported());
+            }
+            Ok(EmbeddingBackendSetting::Local {
+                model: local_model_spec(&model)?.code.to_string(),
+            })
+        }
+    }
+}
+
+/// The saved backend; an unset or unreadable setting means Ollama's default.
+pub(crate) fn load_backend_setting(
+    connection: &Connection,
+) -> CommandResult<EmbeddingBackendSetting> {
+    Ok(get_setting_value(connection, SETTING_EMBEDDING_BACKEND)?
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default())
+}
+
+pub(crate) fn models_dir(app: &AppHandle) -> CommandResult<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(EMBEDDING_MODELS_DIR))
+        .map_err(|err| BasecampError::internal(format!("No app data folder: {err}")))
+}
+
+/// Where the Hugging Face cache keeps a model's files.
+fn model_cache_dir(models_dir: &Path, code: &str) -> PathBuf {
+    models_dir.join(format!("models--{}", code.replace('/', "--")))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => dir_size(&entry.path()),
+            Ok(file_type) if file_type.is_file() => {
+                entry.metadata().map(|metadata| metadata.len()).unwrap_or(0)
+            }
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(feature = "local-embeddings")]
+mod runtime {
+    use std::path::Path;
+    use std::sync::Mutex;
+
+    use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
+
+    use crate::error::{BasecampError, CommandResult};
+
+    /// Loading a model takes seconds, so the last one used stays in memory.
+    static LOADED_MODEL: Mutex<Option<(String, TextEmbedding)>> = Mutex::new(None);
+
+    pub fn embed(models_dir: &Path, code: &str, texts: &[String]) -> CommandResult<Vec<Vec<f32>>> {
+        let mut loaded = LOADED_MODEL.lock().map_err(|_| BasecampError::lock())?;
+        if loaded.as_ref().map(|(loaded_code, _)| loaded_code.as_str()) != Some(code) {
+            *loaded = None;
+            let model: EmbeddingModel = code.parse().map_err(BasecampError::validation)?;
+            let embedding = TextEmbedding::try_new(
+                InitOptions::new(model)
+                    .with_cache_dir(models_dir.to_path_buf())
+                    .with_show_download_progress(false),
+            )
+            .map_err(|err| {
+                BasecampError::unavailable(format!("Unable to load {code}: {err}")).retryable(true)
+            })?;
+            *loaded = Some((code.to_string(), embedding));
+        }
+        let Some((_, embedding)) = loaded.as_ref() else {
+            return Err(BasecampError::internal("Embedding model was not loaded."));
+        };
+        embedding
+            .embed(texts.to_vec(), None)
+            .map_err(|err| BasecampError::internal(format!("Local embedding failed: {err}")))
+    }
+
+    pub fn unload(code: &str) {
+        if let Ok(mut loaded) = LOADED_MODEL.lock() {
+            if loaded.as_ref().map(|(loaded_code, _)| loaded_code.as_str()) == Some(code) {
+                *loaded = None;
+            }
+        }
+    }
+}
+
+/// Embeds with a fastembed model, downloading it first if needed. Blocking.
+pub(crate) fn embed_locally(
+    models_dir: &Path,
+    code: &str,
+    texts: &[String],
+) -> CommandResult<Vec<Vec<f32>>> {
+    #[cfg(feature = "local-embeddings")]
+    {
+        runtime::embed(models_dir, code, texts)
+    }
+    #[cfg(not(feature = "local-embeddings"))]
+    {
+        let _ = (models_dir, code, texts);
+        Err(local_unsupported())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalEmbeddingModel {
+    pub code: String,
+    pub label: String,
+    pub dimensions: usize,
+    pub note: String,
+    pub downloaded: bool,
+    /// Size on disk once downloaded.
+    pub disk_bytes: u64,
+}
+
+fn describe_local_model(models_dir: &Path, spec: &LocalModelSpec) -> LocalEmbeddingModel {
+    let cache_dir = model_cache_dir(models_dir, spec.code);
+    let downloaded = fs::read_dir(cache_dir.join("snapshots"))
+        .map(|mut entries| entries.next().is_some())
+        .unwrap_or(false);
+    LocalEmbeddingModel {
+        code: spec.code.to_string(),
+        label: spec.label.to_string(),
+        dimensions: spec.dimensions,
+        note: spec.note.to_string(),
+        downloaded,
+        disk_bytes: if downloaded { dir_size(&cache_dir) } else { 0 },
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingSettings {
+    pub backend: EmbeddingBackendSetting,
+    /// Whether this build can run local models at all.
+    pub local_supported: bool,
+    pub local_models: Vec<LocalEmbeddingModel>,
+}
+
+#[tauri::command]
+pub fn get_embedding_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> CommandResult<EmbeddingSettings> {
+    let backend = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        load_backend_setting(&connection)?
+    };
+    let models_dir = models_dir(&app)?;
+    Ok(EmbeddingSettings {
+        backend,
+        local_supported: cfg!(feature = "local-embeddings"),
+        local_models: LOCAL_MODELS
+            .iter()
+            .map(|spec| describe_local_model(&models_dir, spec))
+            .collect(),
+    })
+}
+
+/// Switching models leaves existing vectors in place; they show up as stale
+/// until the next refresh re-embeds them.
+#[tauri::command]
+pub fn set_embedding_backend(
+    state: State<'_, AppState>,
+    backend: EmbeddingBackendSetting,
+) -> CommandResult<EmbeddingBackendSetting> {
+    let backend = validate_backend_setting(backend)?;
+    let value = serde_json::to_string(&backend)
+        .map_err(|err| BasecampError::internal(format!("Unable to save setting: {err}")))?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    set_setting_value(&connection, SETTING_EMBEDDING_BACKEND, &value)?;
+    Ok(backend)
+}
+
+#[tauri::command]
+pub async fn download_local_embedding_model(
+    app: AppHandle,
+    model: String,
+) -> CommandResult<LocalEmbeddingModel> {
+    if !cfg!(feature = "local-embeddings") {
+        return Err(local_unsupported());
+    }
+    let spec = local_model_spec(&model)?;
+    let models_dir = models_dir(&app)?;
+    fs::create_dir_all(&models_dir)?;
+    let blocking_dir = models_dir.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        embed_locally(&blocking_dir, spec.code, &["warm-up".to_string()])
+    })
+    .await
+    .map_err(|err| BasecampError::internal(format!("Model download failed: {err}")))??;
+    Ok(describe_local_model(&models_dir, spec))
+}
+
+#[tauri::command]
+pub fn delete_local_embedding_model(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    model: String,
+) -> CommandResult<()> {
+    let spec = local_model_spec(&model)?;
+    {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        if load_backend_setting(&connection)?
+            == (EmbeddingBackendSetting::Local {
+                model: spec.code.to_string(),
+            })
+        {
+            return Err(BasecampError::validation(
+                "Switch the embedding backend before deleting the model it uses.",
+            ));
+        }
+    }
+    #[cfg(feature = "local-embeddings")]
+    runtime::unload(spec.code);
+    let cache_dir = model_cache_dir(&models_dir(&app)?, spec.code);
+    if cache_dir.exists() {
+        fs::remove_dir_all(&cache_dir)?;
+    }
+    Ok(())
+}
+
+/// Question/passage pairs with little word overlap, so ranking the right
+/// passage first takes more than matching keywords.
+const BENCHMARK_PAIRS: &[(&str, &str)] = &[
+    (
+        "How do I get my money back for something that arrived broken?",
+        "Items that show up damaged can be returned within 30 days for a full reimbursement.",
+    ),
+    (
+        "When does the team get together each week?",
+        "The weekly sync is every Monday at 10:00 in the small conference room.",
+    ),
+    (
+        "Which system keeps user accounts?",
+        "Account records live in the Postgres cluster; sessions are cached in Redis.",
+    ),
+    (
+        "How can we spend less on cloud hosting?",
+        "We cut the bill by shutting down idle instances overnight and reserving capacity.",
+    ),
+    (
+        "Who may approve a production release?",
+        "Only on-call engineers and release managers can sign off on shipping to prod.",
+    ),
+    (
+        "What should I pack for a hike in the snow?",
+        "Bring insulated layers, waterproof boots, a headlamp and extra food for winter treks.",
+    ),
+    (
+        "Why does the app take so long to open?",
+        "Launch time regressed because every plugin is initialised before the first window.",
+    ),
+    (
+        "How do plants make their food?",
+        "Chloroplasts turn sunlight, water and carbon dioxide into glucose.",
+    ),
+];
+
+/// Share of queries whose most similar passage is the one at the same index.
+fn top1_accuracy(queries: &[Vec<f32>], passages: &[Vec<f32>]) -> f64 {
+    if queries.is_empty() {
+        return 0.0;
+    }
+    let hits = queries
+        .iter()
+        .enumerate()
+        .filter(|(index, query)| {
+            passages
+                .iter()
+                .map(|passage| embedding_index::cosine_similarity(query, passage))
+                .enumerate()
+                .max_by(|left, right| left.1.total_cmp(&right.1))
+                .map(|(best, _)| best)
+                == Some(*index)
+        })
+        .count();
+    hits as f64 / queries.len() as f64
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingBenchmark {
+    pub backend: EmbeddingBackendSetting,
+    pub dimensions: usize,
+    /// First request, which loads (and for local models may download) the model.
+    pub load_ms: u64,
+    pub embed_ms: u64,
+    pub texts_per_second: f64,
+    /// 0 to 1; how often a question's own passage ranked first.
+    pub top1_accuracy: f64,
+    pub error: Option<String>,
+}
+
+async fn run_benchmark(
+    client: &reqwest::Client,
+    backend: &embedding_index::EmbeddingBackend,
+) -> CommandResult<(usize, u64, u64, f64)> {
+    let started = Instant::now();
+    embedding_index::embed_texts(client, backend, &["warm-up".to_string()]).await?;
+    let load_ms = started.elapsed().as_millis() as u64;
+
+    let texts: Vec<String> = BENCHMARK_PAIRS
+        .iter()
+        .map(|(query, _)| query.to_string())
+        .chain(
+            BENCHMARK_PAIRS
+                .iter()
+                .map(|(_, passage)| passage.to_string()),
+        )
+        .collect();
+    let started = Instant::now();
+    let vectors = embedding_index::embed_texts(client, backend, &texts).await?;
+    let embed_ms = started.elapsed().as_millis() as u64;
+
+    let (queries, passages) = vectors.split_at(BENCHMARK_PAIRS.len());
+    let dimensions = vectors.first().map(Vec::len).unwrap_or(0);
+    Ok((
+        dimensions,
+        load_ms,
+        embed_ms,
+        top1_accuracy(queries, passages),
+    ))
+}
+
+/// Times each backend on the built-in pairs; defaults to the current one.
+/// A backend that fails is reported with its error instead of failing the run.
+#[tauri::command]
+pub async fn benchmark_embedding_backends(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    backends: Option<Vec<EmbeddingBackendSetting>>,
+) -> CommandResult<Vec<EmbeddingBenchmark>> {
+    let models_dir = models_dir(&app)?;
+    let settings = match backends.filter(|backends| !backends.is_empty()) {
+        Some(backends) => backends,
+        None => {
+            let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+            vec![load_backend_setting(&connection)?]
+        }
+    };
+
+    let mut results = Vec::with_capacity(settings.len());
+    for setting in settings {
+        let outcome = match validate_backend_setting(setting.clone()) {
+            Ok(setting) => {
+                let backend = {
+                    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+                    embedding_index::resolve_backend(&connection, &setting, &models_dir)?
+                };
+                run_benchmark(&state.provider_client, &backend).await
+            }
+            Err(err) => Err(err),
+        };
+        results.push(match outcome {
+            Ok((dimensions, load_ms, embed_ms, top1_accuracy)) => EmbeddingBenchmark {
+                backend: setting,
+                dimensions,
+                load_ms,
+                embed_ms,
+                texts_per_second: (BENCHMARK_PAIRS.len() * 2) as f64 * 1000.0
+                    / embed_ms.max(1) as f64,
+                top1_accuracy,
+                error: None,
+            },
+            Err(err) => EmbeddingBenchmark {
+                backend: setting,
+                dimensions: 0,
+                load_ms: 0,
+                embed_ms: 0,
+                texts_per_second: 0.0,
+                top1_accuracy: 0.0,
+                error: Some(err.message),
+            },
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_setting_should_round_trip_and_key_local_models_apart() {
+        let local: EmbeddingBackendSetting =
+            serde_json::from_str(r#"{"kind":"local","model":"Xenova/bge-small-en-v1.5"}"#)
+                .expect("local setting");
+        assert_eq!(local.model_key(), "local:Xenova/bge-small-en-v1.5");
+        assert_eq!(
+            EmbeddingBackendSetting::default().model_key(),
+            DEFAULT_EMBEDDING_MODEL
+        );
+
+        let trimmed = validate_backend_setting(EmbeddingBackendSetting::Ollama {
+            model: "  mxbai-embed-large ".to_string(),
+        })
+        .expect("ollama setting");
+        assert_eq!(
+            trimmed,
+            EmbeddingBackendSetting::Ollama {
+                model: "mxbai-embed-large".to_string()
+            }
+        );
+        assert!(validate_backend_setting(EmbeddingBackendSetting::Ollama {
+            model: " ".to_string()
+        })
+        .is_err());
+        assert!(local_model_spec("someone/not-a-model").is_err());
+        assert_eq!(
+            model_cache_dir(Path::new("/models"), "Xenova/bge-small-en-v1.5"),
+            Path::new("/models/models--Xenova--bge-small-en-v1.5")
+        );
+
+        let queries = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        assert_eq!(top1_accuracy(&queries, &queries), 1.0);
+        let swapped = vec![vec![0.0, 1.0], vec![1.0, 0.0]];
+        assert_eq!(top1_accuracy(&queries, &swapped), 0.0);
+    }
+}
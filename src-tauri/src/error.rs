@@ -0,0 +1,197 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::providers::ProviderError;
+
+pub type CommandResult<T> = Result<T, BasecampError>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BasecampErrorKind {
+    Validation,
+    NotFound,
+    Unavailable,
+    Io,
+    Database,
+    Provider,
+    Mcp,
+    /// Rejected because read-only mode is on.
+    ReadOnly,
+    /// The prompt plus reserved output won't fit the model's context window.
+    ContextOverflow,
+    /// A local-only camp tried to reach a provider off this machine.
+    LocalOnly,
+    /// The data changed after the caller read it; reload and try again.
+    Conflict,
+    Internal,
+}
+
+/// Structured error returned by Tauri commands. `status` and `response_payload`
+/// keep the shape the frontend already reads from provider failures.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BasecampError {
+    pub kind: BasecampErrorKind,
+    pub message: String,
+    pub retryable: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub response_payload: Value,
+}
+
+impl BasecampError {
+    pub fn new(kind: BasecampErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            retryable: false,
+            context: BTreeMap::new(),
+            status: None,
+            response_payload: Value::Null,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::Validation, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::NotFound, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::Unavailable, message)
+    }
+
+    pub fn database(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::Database, message)
+    }
+
+    /// MCP servers are external processes; failures talking to them are worth retrying.
+    pub fn mcp(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::Mcp, message).retryable(true)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(BasecampErrorKind::Internal, message)
+    }
+
+    /// The shared connection mutex was poisoned or contended.
+    pub fn lock() -> Self {
+        Self::database("Database lock error").retryable(true)
+    }
+
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.context.insert(key.to_string(), value.into());
+        self
+    }
+}
+
+impl fmt::Display for BasecampError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for BasecampError {}
+
+/// Commands not yet migrated still build `String` errors; classify the common
+/// wording so `?` keeps working while they move over.
+impl From<String> for BasecampError {
+    fn from(message: String) -> Self {
+        let lowered = message.to_ascii_lowercase();
+        if lowered.contains("lock error") {
+            return Self::database(message).retryable(true);
+        }
+        let kind = if lowered.contains("not found") {
+            BasecampErrorKind::NotFound
+        } else if lowered.contains("must ")
+            || lowered.contains("required")
+            || lowered.contains("invalid")
+            || lowered.contains("cannot be empty")
+        {
+            BasecampErrorKind::Validation
+        } else {
+            BasecampErrorKind::Internal
+        };
+        Self::new(kind, message)
+    }
+}
+
+impl From<&str> for BasecampError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+impl From<ProviderError> for BasecampError {
+    fn from(value: ProviderError) -> Self {
+        // No status means the request never got a response (connect/timeout).
+        let retryable = match value.status {
+            Some(status) => status == 408 || status == 429 || status >= 500,
+            None => true,
+        };
+        Self {
+            kind: BasecampErrorKind::Provider,
+            message: value.message,
+            retryable,
+            context: BTreeMap::new(),
+            status: value.status,
+            response_payload: value.response_payload,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for BasecampError {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::database(format!("Database error: {value}"))
+    }
+}
+
+impl From<std::io::Error> for BasecampError {
+    fn from(value: std::io::Error) -> Self {
+        Self::new(BasecampErrorKind::Io, format!("File system error: {value}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basecamp_error_should_classify_legacy_and_provider_errors() {
+        let lock = BasecampError::from("Database lock error".to_string());
+        assert_eq!(lock.kind, BasecampErrorKind::Database);
+        assert!(lock.retryable);
+        assert_eq!(
+            BasecampError::from("Camp not found.").kind,
+            BasecampErrorKind::NotFound
+        );
+
+        let rate_limited = BasecampError::from(ProviderError {
+            message: "Too many requests".to_string(),
+            status: Some(429),
+            response_payload: serde_json::json!({ "error": "slow down" }),
+        });
+        assert_eq!(rate_limited.kind, BasecampErrorKind::Provider);
+        assert!(rate_limited.retryable);
+
+        let serialized = serde_json::to_value(
+            BasecampError::validation("name is required").with_context("field", "name"),
+        )
+        .expect("error should serialize");
+        assert_eq!(serialized["kind"], "validation");
+        assert_eq!(serialized["retryable"], false);
+        assert_eq!(serialized["context"]["field"], "name");
+        assert_eq!(serialized["status"], Value::Null);
+    }
+}
@@ -0,0 +1,840 @@
+//! `fetch_url`: lets a camp's model read a web page. Requests are GET only and
+//! limited to the hosts in the camp's `fetch_allowed_domains` (an entry also
+//! admits its subdomains); every redirect hop is checked against the same
+//! list. Downloads stop at a byte cap, and HTML is cut down to the page's main
+//! content and converted to Markdown so navigation, scripts and footers don't
+//! eat the model's context. Fetches made during a run are recorded in
+//! `tool_calls` alongside the run's other tool calls.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use ego_tree::iter::Edge;
+use ego_tree::NodeRef;
+use reqwest::header::{CONTENT_TYPE, LOCATION};
+use reqwest::Url;
+use rusqlite::{params, Connection};
+use scraper::{node::Element, ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::{
+    ensure_camps_root, ensure_main_window, insert_tool_call_start_db, now_timestamp_ms,
+    read_camp_config, resolve_existing_camp_dir, update_tool_call_error_db,
+    update_tool_call_result_db, write_camp_config, AppState, ToolCallStartPayload,
+};
+
+const MAX_ALLOWED_DOMAINS: usize = 64;
+const DEFAULT_MAX_FETCH_BYTES: usize = 2 * 1024 * 1024;
+const MAX_FETCH_BYTES_CEILING: usize = 10 * 1024 * 1024;
+/// Longer pages are cut off; the model sees `truncated: true`.
+const MAX_MARKDOWN_CHARS: usize = 100_000;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_REDIRECTS: usize = 5;
+/// A candidate container needs at least this much text to count as the main
+/// content rather than a teaser or a card.
+const MIN_MAIN_CONTENT_CHARS: usize = 250;
+/// Elements nested deeper than this are flattened to their text, so a page of
+/// `<div><div><div>...` cannot overflow the stack of the recursive writer.
+const MAX_MARKDOWN_DEPTH: usize = 64;
+
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "form", "button",
+    "input", "select", "textarea", "nav", "header", "footer", "aside", "dialog", "object", "embed",
+    "head",
+];
+/// Matched against `class`, `id` and `role` to drop page chrome.
+const BOILERPLATE_HINTS: &[&str] = &[
+    "navigation",
+    "navbar",
+    "menu",
+    "footer",
+    "sidebar",
+    "comment",
+    "share",
+    "social",
+    "advert",
+    "promo",
+    "cookie",
+    "banner",
+    "related",
+    "newsletter",
+    "breadcrumb",
+];
+
+#[derive(Debug, Deserialize)]
+pub struct CampFetchUrlPayload {
+    pub camp_id: String,
+    pub url: String,
+    /// Run the fetch belongs to; when set it is recorded in `tool_calls`.
+    pub run_id: Option<String>,
+    pub max_bytes: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchedPage {
+    pub url: String,
+    pub final_url: String,
+    pub status: u16,
+    pub content_type: String,
+    pub title: Option<String>,
+    pub markdown: String,
+    pub bytes: usize,
+    /// The download or the Markdown hit its size limit.
+    pub truncated: bool,
+}
+
+/// Lowercase host from an allowlist entry, accepting pasted URLs and `*.`
+/// wildcards. `None` if nothing host-like is left.
+fn normalize_domain(raw: &str) -> Option<String> {
+    let mut domain = raw.trim().to_ascii_lowercase();
+    for prefix in ["https://", "http://"] {
+        if let Some(rest) = domain.strip_prefix(prefix) {
+            domain = rest.to_string();
+        }
+    }
+    let domain = domain
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('@')
+        .next()
+        .unwrap_or_default();
+    let domain = domain.split(':').next().unwrap_or_default();
+    let domain = domain.trim_start_matches("*.").trim_matches('.');
+    let valid = !domain.is_empty()
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+        });
+    valid.then(|| domain.to_string())
+}
+
+pub(crate) fn parse_fetch_allowed_domains_field(value: Option<&Value>) -> (Vec<String>, bool) {
+    let Some(items) = value.and_then(Value::as_array) else {
+        return (Vec::new(), value.is_some_and(|value| !value.is_null()));
+    };
+    let mut domains: Vec<String> = Vec::new();
+    for domain in items
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(normalize_domain)
+    {
+        if !domains.contains(&domain) {
+            domains.push(domain);
+        }
+    }
+    let migrated = domains.len() != items.len()
+        || domains
+            .iter()
+            .zip(items)
+            .any(|(domain, raw)| raw.as_str() != Some(domain.as_str()));
+    (domains, migrated)
+}
+
+fn host_allowed(host: &str, allowed: &[String]) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed.iter().any(|domain| {
+        host == *domain
+            || host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|rest| rest.ends_with('.'))
+    })
+}
+
+fn ensure_url_allowed(url: &Url, allowed: &[String]) -> CommandResult<()> {
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(BasecampError::validation(format!(
+            "Only http and https URLs can be fetched, not `{}`.",
+            url.scheme()
+        )));
+    }
+    let host = url.host_str().unwrap_or_default();
+    if !host_allowed(host, allowed) {
+        let message = if allowed.is_empty() {
+            "This camp has no fetch allowlist; add domains in the camp settings first.".to_string()
+        } else {
+            format!("`{host}` is not on this camp's fetch allowlist.")
+        };
+        return Err(BasecampError::validation(message).with_context("host", host));
+    }
+    Ok(())
+}
+
+fn is_html(content_type: &str) -> bool {
+    content_type == "text/html" || content_type == "application/xhtml+xml"
+}
+
+fn is_readable_content_type(content_type: &str) -> bool {
+    content_type.is_empty()
+        || content_type.starts_with("text/")
+        || is_html(content_type)
+        || matches!(
+            content_type,
+            "application/json" | "application/xml" | "application/rss+xml" | "application/atom+xml"
+        )
+        || content_type.ends_with("+json")
+}
+
+fn attribute_hints_boilerplate(element: &Element) -> bool {
+    if element.attr("hidden").is_some() || element.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    ["class", "id", "role"].iter().any(|name| {
+        element.attr(name).is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            BOILERPLATE_HINTS.iter().any(|hint| value.contains(hint))
+        })
+    })
+}
+
+fn is_boilerplate(element: &Element) -> bool {
+    SKIPPED_TAGS.contains(&element.name())
+        || (!matches!(element.name(), "html" | "body" | "main" | "article")
+            && attribute_hints_boilerplate(element))
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element
+        .text()
+        .map(|text| text.split_whitespace().map(str::len).sum::<usize>())
+        .sum()
+}
+
+/// The element most likely to hold the page's content: the largest
+/// `<article>`/`<main>` if it has real text, otherwise the container whose
+/// paragraphs carry the most text, otherwise `<body>`.
+fn main_content(document: &Html) -> ElementRef<'_> {
+    let landmarks = Selector::parse("article, main, [role=main]").expect("selector is valid");
+    if let Some(landmark) = document
+        .select(&landmarks)
+        .map(|element| (text_length(element), element))
+        .filter(|(length, _)| *length >= MIN_MAIN_CONTENT_CHARS)
+        .max_by_key(|(length, _)| *length)
+        .map(|(_, element)| element)
+    {
+        return landmark;
+    }
+
+    let paragraphs = Selector::parse("p, pre").expect("selector is valid");
+    let mut scores: HashMap<ego_tree::NodeId, usize> = HashMap::new();
+    for paragraph in document.select(&paragraphs) {
+        let length = text_length(paragraph);
+        if length < 25 {
+            continue;
+        }
+        if let Some(parent) = paragraph.parent() {
+            *scores.entry(parent.id()).or_default() += length;
+            if let Some(grandparent) = parent.parent() {
+                *scores.entry(grandparent.id()).or_default() += length / 2;
+            }
+        }
+    }
+    let best = scores
+        .into_iter()
+        .filter(|(_, score)| *score >= MIN_MAIN_CONTENT_CHARS)
+        .max_by_key(|(_, score)| *score)
+        .and_then(|(id, _)| document.tree.get(id))
+        .and_then(ElementRef::wrap);
+    if let Some(best) = best {
+        return best;
+    }
+
+    let body = Selector::parse("body").expect("selector is valid");
+    document
+        .select(&body)
+        .next()
+        .unwrap_or_else(|| document.root_element())
+}
+
+fn page_title(document: &Html) -> Option<String> {
+    let selector = Selector::parse("title, h1").expect("selector is valid");
+    document
+        .select(&selector)
+        .map(|element| element.text().collect::<Vec<_>>().join(" "))
+        .map(|title| title.split_whitespace().collect::<Vec<_>>().join(" "))
+        .find(|title| !title.is_empty())
+}
+
+struct MarkdownWriter<'a> {
+    base: Option<&'a Url>,
+    out: String,
+    list_depth: usize,
+    depth: usize,
+}
+
+impl<'a> MarkdownWriter<'a> {
+    fn new(base: Option<&'a Url>) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            list_depth: 0,
+            depth: 0,
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    /// Starts a new block: a blank line, or a plain line break inside lists.
+    fn block(&mut self) {
+        while self.out.ends_with(' ') {
+            self.out.pop();
+        }
+        if self.out.is_empty() {
+            return;
+        }
+        let wanted = if self.list_depth > 0 { "\n" } else { "\n\n" };
+        while !self.out.ends_with(wanted) {
+            self.out.push('\n');
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        let mut words = text.split_whitespace().peekable();
+        if words.peek().is_none() {
+            if !text.is_empty() && !self.at_line_start() && !self.out.ends_with(' ') {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace)
+            && !self.at_line_start()
+            && !self.out.ends_with(' ')
+        {
+            self.out.push(' ');
+        }
+        let joined = words.collect::<Vec<_>>().join(" ");
+        self.out.push_str(&joined);
+        if text.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        let href = href.trim();
+        if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+            return None;
+        }
+        match self.base {
+            Some(base) => base.join(href).ok().map(|url| url.to_string()),
+            None => Some(href.to_string()),
+        }
+    }
+
+    fn children(&mut self, node: NodeRef<'_, Node>) {
+        for child in node.children() {
+            self.node(child);
+        }
+    }
+
+    /// Renders the children on their own and returns them, trimmed.
+    fn capture(&mut self, node: NodeRef<'_, Node>) -> String {
+        let start = self.out.len();
+        self.children(node);
+        let inner = self.out.split_off(start);
+        inner.trim().to_string()
+    }
+
+    fn wrap_inline(&mut self, node: NodeRef<'_, Node>, marker: &str) {
+        let inner = self.capture(node);
+        if inner.is_empty() {
+            return;
+        }
+        if !self.at_line_start() && !self.out.ends_with(' ') {
+            self.out.push(' ');
+        }
+        self.out.push_str(marker);
+        self.out.push_str(&inner);
+        self.out.push_str(marker);
+    }
+
+    fn list(&mut self, node: NodeRef<'_, Node>, ordered: bool) {
+        self.block();
+        self.list_depth += 1;
+        let mut number = 0;
+        for item in node.children() {
+            let Some(element) = item.value().as_element() else {
+                continue;
+            };
+            if element.name() != "li" || is_boilerplate(element) {
+                continue;
+            }
+            number += 1;
+            if !self.at_line_start() {
+                self.out.push('\n');
+            }
+            self.out.push_str(&"  ".repeat(self.list_depth - 1));
+            if ordered {
+                self.out.push_str(&format!("{number}. "));
+            } else {
+                self.out.push_str("- ");
+            }
+            self.children(item);
+            while self.out.ends_with(' ') {
+                self.out.pop();
+            }
+        }
+        self.list_depth -= 1;
+        self.block();
+    }
+
+    fn table(&mut self, node: NodeRef<'_, Node>) {
+        let rows = Selector::parse("tr").expect("selector is valid");
+        let cells = Selector::parse("th, td").expect("selector is valid");
+        let Some(table) = ElementRef::wrap(node) else {
+            return;
+        };
+        self.block();
+        for (index, row) in table.select(&rows).enumerate() {
+            let values: Vec<String> = row
+                .select(&cells)
+                .map(|cell| {
+                    let mut writer = MarkdownWriter::new(self.base);
+                    writer.depth = self.depth;
+                    writer.children(*cell);
+                    writer
+                        .out
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .replace('|', "\\|")
+                })
+                .collect();
+            if values.is_empty() {
+                continue;
+            }
+            self.out.push_str(&format!("| {} |\n", values.join(" | ")));
+            if index == 0 {
+                self.out
+                    .push_str(&format!("|{}\n", " --- |".repeat(values.len())));
+            }
+        }
+        self.block();
+    }
+
+    /// Writes the text under `node` without recursing, still skipping
+    /// boilerplate such as scripts.
+    fn flatten(&mut self, node: NodeRef<'_, Node>) {
+        let mut skipping = 0usize;
+        for edge in node.traverse() {
+            match edge {
+                Edge::Open(child) => match child.value() {
+                    Node::Text(text) if skipping == 0 => self.push_text(text),
+                    Node::Element(element) if skipping > 0 || is_boilerplate(element) => {
+                        skipping += 1;
+                    }
+                    _ => {}
+                },
+                Edge::Close(child) => {
+                    if skipping > 0 && child.value().is_element() {
+                        skipping -= 1;
+                    }
+                }
+            }
+        }
+    }
+
+    fn node(&mut self, node: NodeRef<'_, Node>) {
+        if self.depth >= MAX_MARKDOWN_DEPTH {
+            self.flatten(node);
+            return;
+        }
+        self.depth += 1;
+        self.element(node);
+        self.depth -= 1;
+    }
+
+    fn element(&mut self, node: NodeRef<'_, Node>) {
+        let element = match node.value() {
+            Node::Text(text) => {
+                self.push_text(text);
+                return;
+            }
+            Node::Element(element) => element,
+            _ => return,
+        };
+        if is_boilerplate(element) {
+            return;
+        }
+        match element.name() {
+            name @ ("h1" | "h2" | "h3" | "h4" | "h5" | "h6") => {
+                let level = usize::from(name.as_bytes()[1] - b'0');
+                let inner = self.capture(node);
+                if !inner.is_empty() {
+                    self.block();
+                    self.out.push_str(&format!("{} {inner}", "#".repeat(level)));
+                    self.block();
+                }
+            }
+            "p" | "div" | "section" | "article" | "main" | "figure" | "figcaption" | "dl"
+            | "dt" | "dd" | "address" | "details" | "summary" => {
+                self.block();
+                self.children(node);
+                self.block();
+            }
+            "br" => {
+                while self.out.ends_with(' ') {
+                    self.out.pop();
+                }
+                self.out.push('\n');
+            }
+            "hr" => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            "strong" | "b" => self.wrap_inline(node, "**"),
+            "em" | "i" => self.wrap_inline(node, "_"),
+            "code" | "kbd" | "samp" => self.wrap_inline(node, "`"),
+            "pre" => {
+                let code = ElementRef::wrap(node)
+                    .map(|element| element.text().collect::<String>())
+                    .unwrap_or_default();
+                self.block();
+                self.out.push_str("```\n");
+                self.out.push_str(code.trim_end_matches('\n'));
+                self.out.push_str("\n```");
+                self.block();
+            }
+            "a" => {
+                let target = element.attr("href").and_then(|href| self.resolve(href));
+                let inner = self.capture(node);
+                if inner.is_empty() {
+                    return;
+                }
+                if !self.at_line_start() && !self.out.ends_with(' ') {
+                    self.out.push(' ');
+                }
+                match target {
+                    Some(target) => self.out.push_str(&format!("[{inner}]({target})")),
+                    None => self.out.push_str(&inner),
+                }
+            }
+            "img" => {
+                let alt = element.attr("alt").unwrap_or_default().trim();
+                if let Some(src) = element.attr("src").and_then(|src| self.resolve(src)) {
+                    if !src.starts_with("data:") {
+                        self.out.push_str(&format!("![{alt}]({src})"));
+                    }
+                }
+            }
+            "ul" | "menu" => self.list(node, false),
+            "ol" => self.list(node, true),
+            "blockquote" => {
+                let inner = self.capture(node);
+                if !inner.is_empty() {
+                    self.block();
+                    let quoted: Vec<String> = inner
+                        .lines()
+                        .map(|line| format!("> {line}").trim_end().to_string())
+                        .collect();
+                    self.out.push_str(&quoted.join("\n"));
+                    self.block();
+                }
+            }
+            "table" => self.table(node),
+            _ => self.children(node),
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        for line in self.out.lines().map(str::trim_end) {
+            if line.is_empty() && matches!(lines.last(), None | Some(&"")) {
+                continue;
+            }
+            lines.push(line);
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+/// Title and Markdown for the main content of an HTML page. Relative links
+/// are resolved against `base`.
+pub(crate) fn html_to_markdown(html: &str, base: Option<&Url>) -> (Option<String>, String) {
+    let document = Html::parse_document(html);
+    let mut writer = MarkdownWriter::new(base);
+    writer.node(*main_content(&document));
+    (page_title(&document), writer.finish())
+}
+
+fn truncate_chars(text: &mut String, max_chars: usize) -> bool {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => {
+            text.truncate(index);
+            true
+        }
+        None => false,
+    }
+}
+
+async fn fetch_page(
+    raw_url: &str,
+    allowed: &[String],
+    max_bytes: usize,
+) -> CommandResult<FetchedPage> {
+    let url = Url::parse(raw_url.trim()).map_err(|err| {
+        BasecampError::validation(format!("`{}` is not a valid URL: {err}", raw_url.trim()))
+    })?;
+    ensure_url_allowed(&url, allowed)?;
+
+    let redirect_allowlist = allowed.to_vec();
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!("Basecamp/", env!("CARGO_PKG_VERSION")))
+        .redirect(reqwest::redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() > MAX_REDIRECTS {
+                attempt.error("too many redirects")
+            } else if ensure_url_allowed(attempt.url(), &redirect_allowlist).is_ok() {
+                attempt.follow()
+            } else {
+                attempt.stop()
+            }
+        }))
+        .build()
+        .map_err(|err| BasecampError::internal(format!("Unable to build HTTP client: {err}")))?;
+
+    let mut response = client.get(url.clone()).send().await.map_err(|err| {
+        BasecampError::unavailable(format!("Unable to fetch `{url}`: {err}")).retryable(true)
+    })?;
+    let status = response.status();
+    if status.is_redirection() {
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("another site");
+        return Err(BasecampError::validation(format!(
+            "`{url}` redirects to `{location}`, which is not on this camp's fetch allowlist."
+        )));
+    }
+    let final_url = response.url().clone();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    if !is_readable_content_type(&content_type) {
+        return Err(BasecampError::validation(format!(
+            "`{url}` returned `{content_type}`, which isn't text and can't be read."
+        )));
+    }
+
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.map_err(|err| {
+        BasecampError::unavailable(format!("Download of `{url}` failed: {err}")).retryable(true)
+    })? {
+        let room = max_bytes - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    let text = String::from_utf8_lossy(&body);
+    let looks_like_html = content_type.is_empty()
+        && text.trim_start().get(..15).is_some_and(|start| {
+            let start = start.to_ascii_lowercase();
+            start.starts_with("<!doctype html") || start.starts_with("<html")
+        });
+    let (title, mut markdown) = if is_html(&content_type) || looks_like_html {
+        html_to_markdown(&text, Some(&final_url))
+    } else {
+        (None, text.into_owned())
+    };
+    truncated |= truncate_chars(&mut markdown, MAX_MARKDOWN_CHARS);
+
+    Ok(FetchedPage {
+        url: url.to_string(),
+        final_url: final_url.to_string(),
+        status: status.as_u16(),
+        content_type,
+        title,
+        markdown,
+        bytes: body.len(),
+        truncated,
+    })
+}
+
+fn start_fetch_audit(connection: &Connection, run_id: &str, url: &str) -> CommandResult<String> {
+    let step_index: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM tool_calls WHERE run_id = ?1",
+            params![run_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| BasecampError::database(format!("Unable to count tool calls: {err}")))?;
+    insert_tool_call_start_db(
+        connection,
+        &ToolCallStartPayload {
+            run_id: run_id.to_string(),
+            step_index,
+            tool_name: "fetch_url".to_string(),
+            args_json: serde_json::json!({ "url": url }).to_string(),
+            started_at: now_timestamp_ms(),
+        },
+    )
+    .map_err(|err| BasecampError::database(format!("Unable to record fetch: {err}")))
+}
+
+/// Replaces the camp's fetch allowlist and returns the stored domains.
+#[tauri::command]
+pub fn camp_set_fetch_allowed_domains(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    domains: Vec<String>,
+) -> CommandResult<Vec<String>> {
+    ensure_main_window(&window)?;
+    let mut normalized: Vec<String> = Vec::new();
+    for raw in domains.iter().filter(|raw| !raw.trim().is_empty()) {
+        let domain = normalize_domain(raw).ok_or_else(|| {
+            BasecampError::validation(format!("`{}` is not a domain name.", raw.trim()))
+        })?;
+        if !normalized.contains(&domain) {
+            normalized.push(domain);
+        }
+    }
+    if normalized.len() > MAX_ALLOWED_DOMAINS {
+        return Err(BasecampError::validation(format!(
+            "A camp can allow at most {MAX_ALLOWED_DOMAINS} domains."
+        )));
+    }
+
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let mut config = read_camp_config(&camp_dir)?;
+    config.fetch_allowed_domains = normalized.clone();
+    write_camp_config(&camp_dir, &config)?;
+    Ok(normalized)
+}
+
+/// Downloads a page from an allowlisted domain and returns it as Markdown.
+#[tauri::command]
+pub async fn camp_fetch_url(
+    state: State<'_, AppState>,
+    payload: CampFetchUrlPayload,
+) -> CommandResult<FetchedPage> {
+    let max_bytes = payload
+        .max_bytes
+        .unwrap_or(DEFAULT_MAX_FETCH_BYTES)
+        .clamp(1, MAX_FETCH_BYTES_CEILING);
+    let (allowed, audit_id) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let config = read_camp_config(&camp_dir)?;
+        let audit_id = match payload.run_id.as_deref() {
+            Some(run_id) => Some(start_fetch_audit(&connection, run_id, &payload.url)?),
+            None => None,
+        };
+        (config.fetch_allowed_domains, audit_id)
+    };
+
+    let result = fetch_page(&payload.url, &allowed, max_bytes).await;
+
+    if let Some(audit_id) = audit_id {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        // The page itself stays out of the log; what was fetched is enough.
+        let recorded = match &result {
+            Ok(page) => update_tool_call_result_db(
+                &connection,
+                &audit_id,
+                &serde_json::json!({
+                    "url": page.url,
+                    "final_url": page.final_url,
+                    "status": page.status,
+                    "content_type": page.content_type,
+                    "title": page.title,
+                    "bytes": page.bytes,
+                    "truncated": page.truncated,
+                })
+                .to_string(),
+                now_timestamp_ms(),
+            ),
+            Err(error) => update_tool_call_error_db(
+                &connection,
+                &audit_id,
+                &error.message,
+                now_timestamp_ms(),
+            ),
+        };
+        recorded?;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn html_to_markdown_should_keep_main_content() {
+        let html = r#"<!doctype html><html><head><title>Release notes</title>
+            <style>p { color: red }</style></head><body>
+            <nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+            <div class="sidebar-menu"><p>Popular posts you might like to read next week</p></div>
+            <article>
+              <h1>Version 2.0</h1>
+              <p>This release adds <strong>offline mode</strong> and a new
+                 <a href="/docs/sync">sync engine</a> that is much faster than before.</p>
+              <ul><li>Faster startup</li><li>Smaller <em>bundles</em></li></ul>
+              <pre><code>npm install app@2</code></pre>
+              <p>Upgrading takes a few minutes and keeps all of your existing settings and data.</p>
+              <script>track()</script>
+            </article>
+            <footer>Copyright</footer></body></html>"#;
+        let base = Url::parse("https://example.com/blog/v2").expect("url parses");
+        let (title, markdown) = html_to_markdown(html, Some(&base));
+
+        assert_eq!(title.as_deref(), Some("Release notes"));
+        assert!(markdown.starts_with("# Version 2.0\n\nThis release adds **offline mode**"));
+        assert!(markdown.contains("[sync engine](https://example.com/docs/sync)"));
+        assert!(markdown.contains("- Faster startup\n- Smaller _bundles_"));
+        assert!(markdown.contains("```\nnpm install app@2\n```"));
+        for dropped in ["Home", "Popular posts", "track()", "Copyright", "color"] {
+            assert!(!markdown.contains(dropped), "{dropped} should be dropped");
+        }
+    }
+
+    #[test]
+    fn html_to_markdown_should_flatten_deeply_nested_elements() {
+        let depth = 5_000;
+        let html = format!(
+            "<html><body>{}<p>Deep <em>text</em></p><script>track()</script>{}</body></html>",
+            "<div>".repeat(depth),
+            "</div>".repeat(depth)
+        );
+        let (_, markdown) = html_to_markdown(&html, None);
+
+        assert_eq!(markdown, "Deep text");
+    }
+
+    #[test]
+    fn ensure_url_allowed_should_admit_listed_domains_and_their_subdomains() {
+        let allowed = vec!["example.com".to_string()];
+        let check = |url: &str| ensure_url_allowed(&Url::parse(url).expect("url parses"), &allowed);
+        assert!(check("https://example.com/a").is_ok());
+        assert!(check("https://docs.example.com/a").is_ok());
+        assert!(check("https://badexample.com/a").is_err());
+        assert!(check("ftp://example.com/a").is_err());
+        assert_eq!(
+            normalize_domain(" https://*.Docs.Example.com:8080/path ").as_deref(),
+            Some("docs.example.com")
+        );
+        assert_eq!(normalize_domain("not a domain"), None);
+    }
+}
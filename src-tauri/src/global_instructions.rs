@@ -0,0 +1,92 @@
+//! Workspace-wide instructions layered above every camp's system prompt.
+//! They live in `global_instructions.md` at the top of the workspace, so a
+//! preference written once reaches every camp without copying it into each
+//! `system_prompt.md`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{State, Window};
+
+use crate::{
+    ensure_main_window, read_text_file, require_workspace_path, write_file_atomic, AppState,
+};
+
+const GLOBAL_INSTRUCTIONS_FILE: &str = "global_instructions.md";
+const GLOBAL_HEADING: &str = "# Global instructions";
+const CAMP_HEADING: &str = "# Camp instructions";
+
+fn global_instructions_path(workspace_path: &Path) -> PathBuf {
+    workspace_path.join(GLOBAL_INSTRUCTIONS_FILE)
+}
+
+/// Empty when the workspace has no global instructions file.
+pub(crate) fn read_global_instructions(workspace_path: &Path) -> Result<String, String> {
+    let path = global_instructions_path(workspace_path);
+    if !path.exists() {
+        return Ok(String::new());
+    }
+    read_text_file(&path)
+}
+
+/// The system prompt sent with a request: global instructions first, then the
+/// camp's own prompt, each under a heading so the model can tell them apart.
+/// Either layer alone is sent as-is.
+pub(crate) fn layer_system_prompt(global: &str, camp: &str) -> String {
+    match (global.trim(), camp.trim()) {
+        ("", camp) => camp.to_string(),
+        (global, "") => global.to_string(),
+        (global, camp) => {
+            format!("{GLOBAL_HEADING}\n\n{global}\n\n---\n\n{CAMP_HEADING}\n\n{camp}")
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_global_instructions(state: State<'_, AppState>) -> Result<String, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    read_global_instructions(&require_workspace_path(&connection)?)
+}
+
+#[tauri::command]
+pub fn set_global_instructions(
+    window: Window,
+    state: State<'_, AppState>,
+    content: String,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let path = global_instructions_path(&require_workspace_path(&connection)?);
+    if content.trim().is_empty() {
+        return match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(format!("Unable to clear global instructions: {err}")),
+        };
+    }
+    write_file_atomic(&path, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layer_system_prompt_should_separate_global_and_camp_layers() {
+        assert_eq!(
+            layer_system_prompt("Use British spelling.\n", "  Be brief. "),
+            "# Global instructions\n\nUse British spelling.\n\n---\n\n# Camp instructions\n\nBe brief."
+        );
+        assert_eq!(layer_system_prompt(" ", "Be brief."), "Be brief.");
+        assert_eq!(
+            layer_system_prompt("Use British spelling.", ""),
+            "Use British spelling."
+        );
+    }
+}
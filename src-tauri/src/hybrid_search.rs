@@ -0,0 +1,346 @@
+//! Hybrid ranking for workspace search and context retrieval. FTS5 hits from
+//! `search` (BM25) and nearest context files from `embedding_index` (cosine
+//! similarity of their best chunk) are merged with reciprocal rank fusion:
+//! each ranker adds `1 / (RRF_K + rank)`, so a document that both rankers
+//! place near the top beats one that only a single ranker likes. Raw scores
+//! are on different scales and are only reported, never added. When the
+//! embedding backend is unreachable the results fall back to BM25 alone.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, Window};
+
+use crate::embedding_index::{self, cosine_similarity, decode_vector};
+use crate::embedding_models;
+use crate::error::{BasecampError, CommandResult};
+use crate::search::{self, SearchHitKind, WorkspaceSearchHit, SEARCH_MAX_LIMIT};
+use crate::{ensure_camps_root, ensure_main_window, require_workspace_path, AppState};
+
+/// The usual RRF constant; larger values flatten the gap between top ranks.
+const RRF_K: f64 = 60.0;
+const HYBRID_DEFAULT_LIMIT: usize = 20;
+/// Candidates taken from each ranker before fusing.
+const HYBRID_CANDIDATES: usize = 50;
+const VECTOR_SNIPPET_CHARS: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct HybridSearchPayload {
+    pub query: String,
+    /// Limits results to one camp, for context retrieval.
+    pub camp_id: Option<String>,
+    pub limit: Option<usize>,
+    pub kinds: Option<Vec<SearchHitKind>>,
+}
+
+/// One ranker's view of a hit. `score` is the ranker's own (BM25 flipped so
+/// higher is better, or cosine similarity); `contribution` is what it added
+/// to the fused score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceScore {
+    pub rank: usize,
+    pub score: f64,
+    pub contribution: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HybridSearchHit {
+    pub camp_id: String,
+    pub kind: SearchHitKind,
+    pub path: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+    pub lexical: Option<SourceScore>,
+    pub vector: Option<SourceScore>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HybridSearchResults {
+    pub hits: Vec<HybridSearchHit>,
+    /// Model the vector side used; `None` when nothing was embedded to compare.
+    pub embedding_model: Option<String>,
+    /// Why the vector side was skipped, e.g. the embedding backend is offline.
+    pub vector_error: Option<String>,
+}
+
+struct VectorHit {
+    camp_id: String,
+    path: String,
+    similarity: f32,
+    text: String,
+}
+
+fn indexed_chunk_count(
+    connection: &Connection,
+    camp_id: Option<&str>,
+    model: &str,
+) -> CommandResult<i64> {
+    Ok(connection.query_row(
+        "SELECT COUNT(*) FROM embedding_chunks c
+         JOIN embedding_files f ON f.camp_id = c.camp_id AND f.path = c.path
+         WHERE f.model = ?1 AND (?2 IS NULL OR c.camp_id = ?2)",
+        params![model, camp_id],
+        |row| row.get(0),
+    )?)
+}
+
+/// Context files ranked by their most similar chunk.
+fn vector_candidates(
+    connection: &Connection,
+    camp_id: Option<&str>,
+    model: &str,
+    query_vector: &[f32],
+) -> CommandResult<Vec<VectorHit>> {
+    let mut statement = connection.prepare(
+        "SELECT c.camp_id, c.path, c.text, c.vector FROM embedding_chunks c
+         JOIN embedding_files f ON f.camp_id = c.camp_id AND f.path = c.path
+         WHERE f.model = ?1 AND (?2 IS NULL OR c.camp_id = ?2)",
+    )?;
+    let rows = statement.query_map(params![model, camp_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, Vec<u8>>(3)?,
+        ))
+    })?;
+
+    let mut best: HashMap<(String, String), VectorHit> = HashMap::new();
+    for row in rows {
+        let (camp_id, path, text, vector) = row?;
+        let similarity = cosine_similarity(query_vector, &decode_vector(&vector));
+        let key = (camp_id, path);
+        if best
+            .get(&key)
+            .is_some_and(|existing| existing.similarity >= similarity)
+        {
+            continue;
+        }
+        let (camp_id, path) = key.clone();
+        best.insert(
+            key,
+            VectorHit {
+                camp_id,
+                path,
+                similarity,
+                text,
+            },
+        );
+    }
+
+    let mut hits: Vec<VectorHit> = best.into_values().collect();
+    hits.sort_by(|left, right| {
+        right
+            .similarity
+            .total_cmp(&left.similarity)
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    hits.truncate(HYBRID_CANDIDATES);
+    Ok(hits)
+}
+
+fn vector_snippet(text: &str) -> String {
+    let flattened = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flattened.chars().count() <= VECTOR_SNIPPET_CHARS {
+        return flattened;
+    }
+    let mut snippet: String = flattened.chars().take(VECTOR_SNIPPET_CHARS).collect();
+    snippet.push('…');
+    snippet
+}
+
+fn rrf_contribution(rank: usize) -> f64 {
+    1.0 / (RRF_K + rank as f64)
+}
+
+/// Merges both ranked lists with reciprocal rank fusion. Ties keep BM25
+/// order ahead of vector-only hits.
+fn fuse(
+    lexical: Vec<WorkspaceSearchHit>,
+    vector: Vec<VectorHit>,
+    limit: usize,
+) -> Vec<HybridSearchHit> {
+    let mut hits: Vec<HybridSearchHit> = Vec::new();
+    let mut positions: HashMap<(String, String), usize> = HashMap::new();
+
+    for (index, hit) in lexical.into_iter().enumerate() {
+        let rank = index + 1;
+        positions.insert((hit.camp_id.clone(), hit.path.clone()), hits.len());
+        hits.push(HybridSearchHit {
+            score: rrf_contribution(rank),
+            lexical: Some(SourceScore {
+                rank,
+                score: hit.score,
+                contribution: rrf_contribution(rank),
+            }),
+            vector: None,
+            camp_id: hit.camp_id,
+            kind: hit.kind,
+            path: hit.path,
+            title: hit.title,
+            snippet: hit.snippet,
+        });
+    }
+
+    for (index, hit) in vector.into_iter().enumerate() {
+        let rank = index + 1;
+        let source = SourceScore {
+            rank,
+            score: f64::from(hit.similarity),
+            contribution: rrf_contribution(rank),
+        };
+        match positions.get(&(hit.camp_id.clone(), hit.path.clone())) {
+            Some(&position) => {
+                let existing = &mut hits[position];
+                existing.score += source.contribution;
+                existing.vector = Some(source);
+            }
+            None => hits.push(HybridSearchHit {
+                camp_id: hit.camp_id,
+                kind: SearchHitKind::ContextFile,
+                title: hit.path.clone(),
+                path: hit.path,
+                snippet: vector_snippet(&hit.text),
+                score: source.contribution,
+                lexical: None,
+                vector: Some(source),
+            }),
+        }
+    }
+
+    // A stable sort keeps insertion order (BM25 first) among equal scores.
+    hits.sort_by(|left, right| right.score.total_cmp(&left.score));
+    hits.truncate(limit);
+    hits
+}
+
+/// Workspace search, or context retrieval when `camp_id` is set, ranked by
+/// BM25 and embedding similarity together. Each hit carries the rank and
+/// score it got from each side.
+#[tauri::command]
+pub async fn hybrid_search(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: HybridSearchPayload,
+) -> CommandResult<HybridSearchResults> {
+    ensure_main_window(&window)?;
+    let query = payload.query.trim().to_string();
+    if query.is_empty() {
+        return Ok(HybridSearchResults::default());
+    }
+    let limit = payload
+        .limit
+        .unwrap_or(HYBRID_DEFAULT_LIMIT)
+        .clamp(1, SEARCH_MAX_LIMIT);
+    let camp_id = payload.camp_id.as_deref();
+    // Only context files are embedded.
+    let wants_vectors = payload.kinds.is_none()
+        || payload
+            .kinds
+            .as_ref()
+            .is_some_and(|kinds| kinds.contains(&SearchHitKind::ContextFile));
+    let models_dir = embedding_models::models_dir(&app)?;
+
+    let (lexical, backend, has_vectors) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let workspace_path = require_workspace_path(&connection)?;
+        let camps_root = ensure_camps_root(&connection)?;
+        search::refresh_workspace_index(&connection, &workspace_path, &camps_root)?;
+
+        let lexical = search::search_index(
+            &connection,
+            &workspace_path,
+            &query,
+            HYBRID_CANDIDATES.max(limit),
+            payload.kinds.as_deref(),
+            camp_id,
+        )?;
+
+        let backend = embedding_index::embedding_backend(&connection, &models_dir)?;
+        let has_vectors =
+            wants_vectors && indexed_chunk_count(&connection, camp_id, &backend.model_key())? > 0;
+        (lexical, backend, has_vectors)
+    };
+
+    let mut results = HybridSearchResults::default();
+    let mut vector = Vec::new();
+    if has_vectors {
+        let model = backend.model_key();
+        match embedding_index::embed_texts(&state.provider_client, &backend, &[query]).await {
+            Ok(vectors) => {
+                let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+                if let Some(query_vector) = vectors.first() {
+                    vector = vector_candidates(&connection, camp_id, &model, query_vector)?;
+                }
+            }
+            Err(err) => results.vector_error = Some(err.message),
+        }
+        results.embedding_model = Some(model);
+    }
+    results.hits = fuse(lexical, vector, limit);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuse_should_reward_documents_both_rankers_agree_on() {
+        let lexical_hit = |path: &str, score: f64| WorkspaceSearchHit {
+            camp_id: "camp-1".to_string(),
+            kind: SearchHitKind::ContextFile,
+            path: path.to_string(),
+            title: path.to_string(),
+            snippet: format!("[{path}]"),
+            score,
+        };
+        let vector_hit = |path: &str, similarity: f32| VectorHit {
+            camp_id: "camp-1".to_string(),
+            path: path.to_string(),
+            similarity,
+            text: format!("chunk   of\n{path}"),
+        };
+
+        let hits = fuse(
+            vec![
+                lexical_hit("context/keyword-only.md", 9.0),
+                lexical_hit("context/both.md", 4.0),
+            ],
+            vec![
+                vector_hit("context/meaning-only.md", 0.91),
+                vector_hit("context/both.md", 0.83),
+            ],
+            10,
+        );
+
+        let order: Vec<&str> = hits.iter().map(|hit| hit.path.as_str()).collect();
+        assert_eq!(
+            order,
+            vec![
+                "context/both.md",
+                "context/keyword-only.md",
+                "context/meaning-only.md"
+            ]
+        );
+
+        let both = &hits[0];
+        assert_eq!(both.lexical.as_ref().map(|score| score.rank), Some(2));
+        assert_eq!(both.vector.as_ref().map(|score| score.rank), Some(2));
+        assert!((both.score - 2.0 * rrf_contribution(2)).abs() < 1e-12);
+        assert_eq!(both.snippet, "[context/both.md]");
+
+        let meaning_only = &hits[2];
+        assert!(meaning_only.lexical.is_none());
+        assert_eq!(meaning_only.snippet, "chunk of context/meaning-only.md");
+        assert_eq!(
+            meaning_only.vector.as_ref().map(|score| score.score as f32),
+            Some(0.91)
+        );
+
+        assert_eq!(fuse(Vec::new(), vec![vector_hit("a", 0.5)], 0).len(), 0);
+    }
+}
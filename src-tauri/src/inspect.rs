@@ -9,11 +9,9 @@ use std::{
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use tauri::{AppHandle, Emitter};
-#[cfg(test)]
-use uuid::Uuid;
 
 pub const INSPECT_EVENT_CHANNEL: &str = "basecamp://inspect-event";
-const DEBUG_DIR_TOP: &str = ".camp";
+pub(crate) const DEBUG_DIR_TOP: &str = ".camp";
 const DEBUG_DIR_NAME: &str = "debug";
 const EVENTS_FILE_NAME: &str = "events.jsonl";
 const REDACTED: &str = "[REDACTED]";
@@ -237,6 +235,24 @@ pub fn write_turn_bundle_file(
     write_turn_file(camp_dir, correlation_id, "bundle", value)
 }
 
+/// Turn files (`turn_<id>_<suffix>.json`) currently in the camp's debug folder.
+pub fn list_turn_files(camp_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(debug_dir(camp_dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|value| value.to_str())
+                .is_some_and(|name| name.starts_with("turn_") && name.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
 pub fn collect_file_meta(path: &Path) -> InspectFileMeta {
     let metadata = fs::metadata(path).ok();
     let modified_at_ms = metadata
@@ -256,13 +272,8 @@ pub fn collect_file_meta(path: &Path) -> InspectFileMeta {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    fn make_temp_dir(prefix: &str) -> PathBuf {
-        let dir = std::env::temp_dir().join(format!("{prefix}-{}", Uuid::new_v4()));
-        fs::create_dir_all(&dir).expect("temporary directory should be created");
-        dir
-    }
+    use crate::test_support::make_temp_dir;
+    use std::time::SystemTime;
 
     #[test]
     fn redactor_masks_api_key_and_secret_fields() {
@@ -350,7 +361,5 @@ mod tests {
         let request_raw = fs::read_to_string(request_path).expect("request file should read");
         assert!(!request_raw.contains("openrouter_test_key_123"));
         assert!(request_raw.contains(REDACTED));
-
-        let _ = fs::remove_dir_all(camp_dir);
     }
 }
@@ -1,9 +1,12 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap, HashSet},
     fs,
     io::{BufRead, BufReader, Write},
     path::{Component, Path, PathBuf},
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
     time::Duration,
 };
 
@@ -12,19 +15,83 @@ use keyring::{Entry, Error as KeyringError};
 use rusqlite::{params, Connection, OptionalExtension, Row};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use tauri::{ipc::Channel, App, Manager, State, Window};
+use tauri::{ipc::Channel, Emitter, Manager, State, Window};
 use tauri_plugin_dialog::DialogExt;
 use uuid::Uuid;
 
+mod artifact_index;
+mod bookmarks;
+mod builtin_tools;
+mod camp_activity;
+mod camp_ranking;
+mod camp_templates;
+mod chat_stream;
 mod commands;
+mod context_assembly;
+mod context_history;
+mod context_pack;
+mod council;
+mod crash_reports;
+mod db_encryption;
+mod deep_link;
+mod default_context;
+mod demo_mode;
+mod digest;
+mod embedding_index;
+mod embedding_models;
+mod error;
+mod fetch_url;
+mod global_instructions;
+mod hybrid_search;
 mod inspect;
+mod markdown;
 pub mod mcp;
+mod mcp_auth;
+mod mcp_catalog;
+mod mcp_roots;
+mod mcp_sampling;
+mod memory_patch;
+mod memory_schema;
+mod metrics;
+mod migrations;
+mod model_notes;
+mod model_recommendation;
+mod post_processing;
+mod privacy;
+mod projects;
+mod prompt_versions;
+mod provider_limits;
 mod providers;
-
+mod quick_capture;
+mod recovery;
+mod rich_render;
+mod run_groups;
+mod scratchpad;
+mod scrubber;
+mod search;
+mod share_export;
+mod speech;
+mod startup;
+mod sync;
+mod sync_conflicts;
+mod tabular;
+#[cfg(test)]
+mod test_support;
+mod tool_loop_guard;
+mod tool_registry;
+mod tool_schema;
+mod tray;
+mod updater;
+mod user_databases;
+mod variants;
+mod workspace_gc;
+mod workspace_manifest;
+mod write_preview;
+
+use error::{BasecampError, CommandResult};
 use providers::{
     registry::{self, ProviderRegistryRow},
-    BasecampChatRequest, ChatStreamEvent, ProviderCommandError, ProviderKind, ProviderManager,
-    ProviderRuntimeSettings,
+    BasecampChatRequest, ChatStreamEvent, ProviderKind, ProviderManager, ProviderRuntimeSettings,
 };
 
 const KEYRING_SERVICE: &str = "com.basecamp.app";
@@ -39,26 +106,49 @@ const SETTING_MAX_ITERATIONS: &str = "max_iterations";
 const CAMP_RUN_STATE_FILE: &str = "run_state.jsonl";
 const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 30;
 const LEGACY_CAMP_SCHEMA_VERSION: &str = "0.0";
-const PREVIOUS_CAMP_SCHEMA_VERSION: &str = "0.1";
-const CAMP_SCHEMA_VERSION: &str = "0.2";
+const V0_1_CAMP_SCHEMA_VERSION: &str = "0.1";
+const PREVIOUS_CAMP_SCHEMA_VERSION: &str = "0.2";
+const CAMP_SCHEMA_VERSION: &str = "0.3";
 const CAMPS_DIR_NAME: &str = "camps";
 const WORKSPACE_CONTEXT_DIR: &str = "context";
 const CAMP_CONFIG_FILE: &str = "camp.json";
 const CAMP_SYSTEM_PROMPT_FILE: &str = "system_prompt.md";
 const CAMP_MEMORY_FILE: &str = "memory.json";
 const CAMP_TRANSCRIPT_FILE: &str = "transcript.jsonl";
+const CAMP_TRANSCRIPT_REJECTED_FILE: &str = "transcript.jsonl.rejected";
 const CAMP_CONTEXT_DIR: &str = "context";
 const CAMP_ARTIFACTS_DIR: &str = "artifacts";
 const CAMP_ARTIFACTS_INDEX_FILE: &str = "index.json";
 const CAMP_ARTIFACTS_SCHEMA_VERSION: &str = "0.1";
+const CAMP_TRASH_DIR: &str = ".trash";
+const CAMP_TRASH_INDEX_FILE: &str = "index.json";
+const CAMP_TRASH_SCHEMA_VERSION: &str = "0.1";
+const CAMP_CHECKPOINTS_DIR: &str = ".checkpoints";
+const CAMP_CHECKPOINTS_INDEX_FILE: &str = "index.json";
+const CAMP_CHECKPOINTS_SCHEMA_VERSION: &str = "0.1";
 const DEFAULT_CAMP_NAME: &str = "Untitled Camp";
+const CAMP_SLUG_MAX_LEN: usize = 40;
+const CAMP_SLUG_SUFFIX_LEN: usize = 4;
 const DEFAULT_CAMP_MODEL: &str = "openrouter/auto";
+const MAX_STOP_SEQUENCES: usize = 4;
+const LOGIT_BIAS_LIMIT: f64 = 100.0;
+const OPENROUTER_GENERATION_URL: &str = "https://openrouter.ai/api/v1/generation";
+const OPENROUTER_GENERATION_RETRY_DELAYS_MS: [u64; 4] = [500, 1_000, 2_000, 4_000];
+const RUN_GENERATION_ENRICHED_EVENT: &str = "runs://generation_enriched";
 
 pub struct AppState {
     pub connection: Mutex<Connection>,
     pub mcp: tokio::sync::Mutex<mcp::McpConnections>,
+    pub mcp_logs: mcp::McpServerLogs,
     pub provider_manager: ProviderManager,
     pub provider_client: reqwest::Client,
+    pub startup_diagnostics: Mutex<recovery::StartupDiagnostics>,
+    pub read_only: AtomicBool,
+    /// MCP sampling requests waiting on the user, keyed by request id.
+    pub mcp_sampling_approvals: Mutex<HashMap<String, tokio::sync::oneshot::Sender<bool>>>,
+    /// Camp whose context folder is offered to MCP servers as a root. Kept out of
+    /// `mcp` so `roots/list` can be answered while a connection is initializing.
+    pub mcp_active_camp: Mutex<Option<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -73,6 +163,9 @@ struct ModelRow {
     capabilities_json: String,
     raw_json: String,
     updated_at: i64,
+    /// The user's own rating and notes, when they have recorded any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    note: Option<model_notes::ModelNote>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -96,6 +189,14 @@ struct Run {
     error: Option<String>,
     rating: Option<i64>,
     tags: Option<String>,
+    generation_id: Option<String>,
+    total_cost: Option<f64>,
+    native_tokens_prompt: Option<i64>,
+    native_tokens_completion: Option<i64>,
+    native_tokens_reasoning: Option<i64>,
+    provider_name: Option<String>,
+    generation_json: Option<String>,
+    scrub_audit_json: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -139,6 +240,20 @@ struct CampModelOverrides {
     max_tokens: Option<i64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    logit_bias: Option<BTreeMap<String, f64>>,
+}
+
+impl CampModelOverrides {
+    fn is_empty(&self) -> bool {
+        self.temperature.is_none()
+            && self.max_tokens.is_none()
+            && self.top_p.is_none()
+            && self.stop.is_none()
+            && self.logit_bias.is_none()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -155,6 +270,49 @@ struct CampConfig {
     tools_enabled: bool,
     #[serde(default = "default_is_team")]
     is_team: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    emoji: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+    /// Sorted ahead of other camps by `camp_ranking::CampListSort::FavoritesFirst`.
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    generation_defaults: Option<CampModelOverrides>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Runs from this camp are logged without prompt or response content.
+    #[serde(default)]
+    privacy_mode: bool,
+    /// Requests from this camp may only go to providers running on this
+    /// machine; see `privacy::enforce_camp_routing`.
+    #[serde(default)]
+    local_only: bool,
+    /// Run over assistant replies before they are stored; see `post_processing`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    post_processors: Vec<post_processing::PostProcessor>,
+    /// Save fenced code blocks in assistant replies as artifacts.
+    #[serde(default)]
+    auto_extract_code_blocks: bool,
+    /// Hold agent `write_file` calls for approval with a diff; see `write_preview`.
+    #[serde(default)]
+    preview_file_writes: bool,
+    /// JSON Schema that `memory.json` writes must satisfy; see `memory_schema`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memory_schema: Option<Value>,
+    /// Project this camp is grouped under; see `projects`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project_id: Option<String>,
+    /// Context files attached to every request; see `default_context`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    default_context_paths: Vec<String>,
+    /// Hosts the `fetch_url` tool may download from; see `fetch_url`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    fetch_allowed_domains: Vec<String>,
     created_at: i64,
     updated_at: i64,
 }
@@ -190,11 +348,28 @@ struct CampSummary {
     id: String,
     name: String,
     model: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    emoji: Option<String>,
+    #[serde(default)]
+    pinned: bool,
+    #[serde(default)]
+    favorite: bool,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project_id: Option<String>,
+    /// When the camp was last opened on this machine; filled in by `camp_list`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_opened_at: Option<i64>,
     updated_at: i64,
     path: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct CampMessage {
     id: String,
     role: String,
@@ -210,6 +385,47 @@ struct CampMessage {
     included_artifact_ids: Option<Vec<String>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     attachments: Option<Vec<CampMessageAttachment>>,
+    /// Model reasoning kept for display only; never sent back to providers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reasoning: Option<String>,
+    /// The `runs` row behind an assistant reply, when the backend stored one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_id: Option<String>,
+}
+
+#[cfg(test)]
+impl CampMessage {
+    /// A plain message with every optional field unset, for tests to build on.
+    fn for_test(id: &str, role: &str, content: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            role: role.to_string(),
+            content: content.to_string(),
+            created_at: 0,
+            name: None,
+            tool_call_id: None,
+            tool_calls: None,
+            included_artifact_ids: None,
+            attachments: None,
+            reasoning: None,
+            run_id: None,
+        }
+    }
+
+    fn with_created_at(mut self, created_at: i64) -> Self {
+        self.created_at = created_at;
+        self
+    }
+
+    fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    fn with_reasoning(mut self, reasoning: &str) -> Self {
+        self.reasoning = Some(reasoning.to_string());
+        self
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -242,11 +458,22 @@ struct CampToolCall {
 struct Camp {
     config: CampConfig,
     system_prompt: String,
+    /// The workspace's `global_instructions.md`, layered above `system_prompt`
+    /// when a request is built.
+    #[serde(default)]
+    global_instructions: String,
     memory: Value,
     transcript: Vec<CampMessage>,
     context_path: String,
 }
 
+impl Camp {
+    /// Global instructions followed by the camp's own system prompt.
+    fn request_system_prompt(&self) -> String {
+        global_instructions::layer_system_prompt(&self.global_instructions, &self.system_prompt)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CampArtifactMetadata {
     id: String,
@@ -260,6 +487,12 @@ struct CampArtifactMetadata {
     usage_count: i64,
     #[serde(default)]
     archived: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_path: Option<String>,
+    #[serde(default)]
+    always_include: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    include_order: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -274,6 +507,66 @@ struct CampArtifact {
     body: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CampTrashKind {
+    Artifact,
+    ContextFile,
+    /// A sync service's conflicted copy, set aside after merging.
+    SyncConflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CampTrashEntry {
+    id: String,
+    kind: CampTrashKind,
+    original_path: String,
+    trashed_filename: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    artifact: Option<CampArtifactMetadata>,
+    trashed_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CampTrashIndex {
+    schema_version: String,
+    entries: Vec<CampTrashEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CampCheckpoint {
+    id: String,
+    name: String,
+    message_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_message_id: Option<String>,
+    memory_filename: String,
+    created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CampCheckpointsIndex {
+    schema_version: String,
+    checkpoints: Vec<CampCheckpoint>,
+}
+
+#[derive(Debug, Serialize)]
+struct CampRollbackResult {
+    checkpoint: CampCheckpoint,
+    archived_message_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive_filename: Option<String>,
+    removed_checkpoint_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CampUndoExchangeResult {
+    /// The removed user message and everything after it, in transcript order.
+    removed_messages: Vec<CampMessage>,
+    rolled_back_artifact_ids: Vec<String>,
+    removed_checkpoint_ids: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct RunInsertPayload {
     id: String,
@@ -295,6 +588,15 @@ struct RunInsertPayload {
     error: Option<String>,
     rating: Option<i64>,
     tags: Option<String>,
+    /// OpenRouter generation id; read from `response_json` when omitted.
+    #[serde(default)]
+    generation_id: Option<String>,
+    /// Camp the run belongs to, so its privacy mode applies.
+    #[serde(default)]
+    camp_id: Option<String>,
+    /// Serialized `ScrubAudit` from the chat response, when scrubbing ran.
+    #[serde(default)]
+    scrub_audit_json: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -364,6 +666,25 @@ struct CampUpdateConfigPayload {
     name: String,
     model: String,
     tools_enabled: bool,
+    description: Option<String>,
+    color: Option<String>,
+    emoji: Option<String>,
+    pinned: Option<bool>,
+    favorite: Option<bool>,
+    generation_defaults: Option<CampModelOverrides>,
+    tags: Option<Vec<String>>,
+    privacy_mode: Option<bool>,
+    local_only: Option<bool>,
+    post_processors: Option<Vec<post_processing::PostProcessor>>,
+    auto_extract_code_blocks: Option<bool>,
+    preview_file_writes: Option<bool>,
+    /// An empty object `{}` removes the schema.
+    memory_schema: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampRenameFolderPayload {
+    camp_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -388,6 +709,42 @@ struct CampAppendMessagePayload {
     tool_calls: Option<Vec<CampToolCall>>,
     included_artifact_ids: Option<Vec<String>>,
     attachments: Option<Vec<CampMessageAttachment>>,
+    reasoning: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampVerifyTranscriptPayload {
+    camp_id: String,
+    repair: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TranscriptIssueKind {
+    Malformed,
+    DuplicateId,
+    OutOfOrder,
+    OrphanedTool,
+}
+
+#[derive(Debug, Serialize)]
+struct TranscriptIssue {
+    line: usize,
+    kind: TranscriptIssueKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message_id: Option<String>,
+    detail: String,
+    quarantined: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TranscriptVerifyReport {
+    total_lines: usize,
+    valid_messages: usize,
+    issues: Vec<TranscriptIssue>,
+    repaired: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rejected_path: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -398,6 +755,22 @@ struct CampCreateArtifactFromMessagePayload {
     tags: Option<Vec<String>>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CampCreateArtifactPayload {
+    camp_id: String,
+    title: String,
+    body: String,
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampCreateArtifactFromFilePayload {
+    camp_id: String,
+    path: String,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CampUpdateArtifactPayload {
     camp_id: String,
@@ -407,6 +780,19 @@ struct CampUpdateArtifactPayload {
     tags: Option<Vec<String>>,
 }
 
+#[derive(Debug, Deserialize)]
+struct CampSetArtifactAlwaysIncludePayload {
+    camp_id: String,
+    artifact_id: String,
+    always_include: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampReorderAlwaysIncludePayload {
+    camp_id: String,
+    artifact_ids: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct CampToggleArtifactArchivePayload {
     camp_id: String,
@@ -414,6 +800,30 @@ struct CampToggleArtifactArchivePayload {
     archived: bool,
 }
 
+#[derive(Debug, Deserialize)]
+struct CampDeleteArtifactPayload {
+    camp_id: String,
+    artifact_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampRestoreTrashEntryPayload {
+    camp_id: String,
+    entry_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampCreateCheckpointPayload {
+    camp_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CampRollbackToCheckpointPayload {
+    camp_id: String,
+    checkpoint_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct InspectEmitEventPayload {
     camp_id: String,
@@ -461,6 +871,9 @@ enum RunEventKind {
     RunCompleted,
     RunCancelled,
     RunFailed,
+    /// The tool loop was stopped by `tool_loop_guard`; `result_json` holds the
+    /// `ToolLoopAbort`.
+    LoopAborted,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -490,6 +903,10 @@ struct RunStartConfig {
     tool_timeout_secs: u64,
     #[serde(default = "default_approval_policy_str")]
     approval_policy: String,
+    #[serde(default = "default_max_repeated_tool_calls")]
+    max_repeated_tool_calls: u32,
+    #[serde(default = "default_max_turn_tokens")]
+    max_turn_tokens: u64,
 }
 
 fn default_max_iterations() -> i64 {
@@ -504,6 +921,14 @@ fn default_approval_policy_str() -> String {
     "manual".to_string()
 }
 
+fn default_max_repeated_tool_calls() -> u32 {
+    tool_loop_guard::DEFAULT_MAX_REPEATED_TOOL_CALLS
+}
+
+fn default_max_turn_tokens() -> u64 {
+    tool_loop_guard::DEFAULT_MAX_TURN_TOKENS
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 enum ApprovalPolicy {
@@ -569,6 +994,8 @@ struct OpenRouterCompletionResult {
     duration_ms: i64,
     response_headers: BTreeMap<String, String>,
     stream_chunk_count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generation_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -605,6 +1032,14 @@ fn map_run_row(row: &Row<'_>) -> rusqlite::Result<Run> {
         error: row.get("error")?,
         rating: row.get("rating")?,
         tags: row.get("tags")?,
+        generation_id: row.get("generation_id")?,
+        total_cost: row.get("total_cost")?,
+        native_tokens_prompt: row.get("native_tokens_prompt")?,
+        native_tokens_completion: row.get("native_tokens_completion")?,
+        native_tokens_reasoning: row.get("native_tokens_reasoning")?,
+        provider_name: row.get("provider_name")?,
+        generation_json: row.get("generation_json")?,
+        scrub_audit_json: row.get("scrub_audit_json")?,
     })
 }
 
@@ -637,129 +1072,23 @@ fn map_search_runs_db_row(row: &Row<'_>) -> rusqlite::Result<SearchRunsDbRow> {
     })
 }
 
-fn has_column(
-    connection: &Connection,
-    table_name: &str,
-    column_name: &str,
-) -> Result<bool, rusqlite::Error> {
-    let mut statement = connection.prepare(&format!("PRAGMA table_info({table_name})"))?;
-    let mut rows = statement.query([])?;
-
-    while let Some(row) = rows.next()? {
-        let name: String = row.get(1)?;
-        if name == column_name {
-            return Ok(true);
-        }
-    }
-
-    Ok(false)
-}
-
-fn migrate_runs_table(connection: &Connection) -> Result<(), rusqlite::Error> {
-    if !has_column(connection, "runs", "requested_model")? {
-        connection.execute("ALTER TABLE runs ADD COLUMN requested_model TEXT", [])?;
-    }
-
-    if !has_column(connection, "runs", "resolved_model")? {
-        connection.execute("ALTER TABLE runs ADD COLUMN resolved_model TEXT", [])?;
+fn new_app_state(
+    connection: Connection,
+    startup_diagnostics: recovery::StartupDiagnostics,
+    read_only: bool,
+) -> AppState {
+    let provider_manager = provider_limits::provider_manager_with_saved_limits(&connection);
+    AppState {
+        connection: Mutex::new(connection),
+        mcp: tokio::sync::Mutex::new(mcp::McpConnections::new()),
+        mcp_logs: mcp::McpServerLogs::new(),
+        provider_manager,
+        provider_client: reqwest::Client::new(),
+        startup_diagnostics: Mutex::new(startup_diagnostics),
+        read_only: AtomicBool::new(read_only),
+        mcp_sampling_approvals: Mutex::new(HashMap::new()),
+        mcp_active_camp: Mutex::new(None),
     }
-
-    connection.execute(
-        "
-    UPDATE runs
-    SET requested_model = model
-    WHERE requested_model IS NULL OR requested_model = ''
-    ",
-        [],
-    )?;
-
-    connection.execute(
-        "
-    UPDATE runs
-    SET resolved_model = model
-    WHERE resolved_model IS NULL OR resolved_model = ''
-    ",
-        [],
-    )?;
-
-    Ok(())
-}
-
-fn create_tables(connection: &Connection) -> Result<(), rusqlite::Error> {
-    connection.execute_batch(
-        "
-    CREATE TABLE IF NOT EXISTS runs (
-      id TEXT PRIMARY KEY,
-      timestamp INTEGER NOT NULL,
-      model TEXT NOT NULL,
-      requested_model TEXT NOT NULL,
-      resolved_model TEXT,
-      system_prompt TEXT NOT NULL,
-      user_prompt TEXT NOT NULL,
-      temperature REAL NOT NULL,
-      max_tokens INTEGER NOT NULL,
-      request_json TEXT NOT NULL,
-      response_json TEXT NOT NULL,
-      output_text TEXT NOT NULL,
-      latency_ms INTEGER NOT NULL,
-      prompt_tokens INTEGER,
-      completion_tokens INTEGER,
-      total_tokens INTEGER,
-      error TEXT,
-      rating INTEGER,
-      tags TEXT
-    );
-
-    CREATE TABLE IF NOT EXISTS meta (
-      key TEXT PRIMARY KEY,
-      value INTEGER
-    );
-
-    CREATE TABLE IF NOT EXISTS settings (
-      key TEXT PRIMARY KEY,
-      value TEXT NOT NULL
-    );
-
-    CREATE TABLE IF NOT EXISTS tool_calls (
-      id TEXT PRIMARY KEY,
-      run_id TEXT NOT NULL,
-      step_index INTEGER NOT NULL,
-      tool_name TEXT NOT NULL,
-      args_json TEXT NOT NULL,
-      result_json TEXT,
-      error TEXT,
-      started_at INTEGER NOT NULL,
-      finished_at INTEGER
-    );
-
-    CREATE INDEX IF NOT EXISTS idx_tool_calls_run_id ON tool_calls(run_id);
-    CREATE INDEX IF NOT EXISTS idx_tool_calls_started_at ON tool_calls(started_at);
-    ",
-    )?;
-
-    registry::create_registry_tables(connection)?;
-    mcp::create_mcp_servers_table(connection)?;
-
-    Ok(())
-}
-
-fn migrate_database(connection: &Connection) -> Result<(), rusqlite::Error> {
-    migrate_runs_table(connection)?;
-    registry::create_registry_tables(connection)?;
-    Ok(())
-}
-
-fn init_database(app: &App) -> Result<Connection, Box<dyn std::error::Error>> {
-    let app_data_dir = app.path().app_data_dir()?;
-    fs::create_dir_all(&app_data_dir)?;
-
-    let db_path = app_data_dir.join(DB_FILE_NAME);
-    let connection = Connection::open(db_path)?;
-
-    create_tables(&connection)?;
-    migrate_database(&connection)?;
-
-    Ok(connection)
 }
 
 fn set_setting_value(
@@ -852,6 +1181,12 @@ fn now_timestamp_ms() -> i64 {
         .as_millis() as i64
 }
 
+/// Rough token count for when no tokenizer is at hand: four characters per
+/// token, rounded up.
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
 fn require_workspace_path(connection: &Connection) -> Result<PathBuf, String> {
     let workspace_path_value = get_setting_value(connection, SETTING_WORKSPACE_PATH)
         .map_err(|err| format!("Unable to load workspace path: {err}"))?
@@ -924,6 +1259,45 @@ fn validate_camp_identifier(camp_id: &str) -> Result<String, String> {
     Ok(trimmed.to_string())
 }
 
+fn slugify_camp_name(name: &str) -> String {
+    let mut slug = String::new();
+    for ch in name.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= CAMP_SLUG_MAX_LEN {
+            break;
+        }
+    }
+
+    let trimmed = slug.trim_end_matches('-');
+    if trimmed.is_empty() {
+        "camp".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Picks a human-readable folder name (`my-project-3f2a`) that does not collide
+/// with an existing camp folder.
+fn allocate_camp_folder_id(camps_root: &Path, name: &str) -> String {
+    let slug = slugify_camp_name(name);
+    loop {
+        let suffix: String = Uuid::new_v4()
+            .simple()
+            .to_string()
+            .chars()
+            .take(CAMP_SLUG_SUFFIX_LEN)
+            .collect();
+        let candidate = format!("{slug}-{suffix}");
+        if !camps_root.join(&candidate).exists() {
+            return candidate;
+        }
+    }
+}
+
 fn resolve_existing_camp_dir(camps_root: &Path, camp_id: &str) -> Result<PathBuf, String> {
     let validated_id = validate_camp_identifier(camp_id)?;
     let camp_dir = camps_root.join(validated_id);
@@ -951,6 +1325,10 @@ fn camp_transcript_path(camp_dir: &Path) -> PathBuf {
     camp_dir.join(CAMP_TRANSCRIPT_FILE)
 }
 
+fn camp_transcript_rejected_path(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_TRANSCRIPT_REJECTED_FILE)
+}
+
 fn camp_context_dir(camp_dir: &Path) -> PathBuf {
     camp_dir.join(CAMP_CONTEXT_DIR)
 }
@@ -1251,35 +1629,137 @@ fn write_artifact_body(
     body: &str,
 ) -> Result<(), String> {
     let path = artifact_markdown_path(camp_dir, &metadata.filename)?;
-    fs::write(path, body).map_err(|err| format!("Unable to write artifact markdown: {err}"))
+    write_file_atomic(&path, body.as_bytes())
 }
 
-fn format_artifact_markdown(title: &str, body: &str) -> String {
-    format!("# {}\n\n{}", title.trim(), body)
+/// Where a new artifact came from: a transcript message, direct input, or a context file.
+struct ArtifactSource {
+    message_id: String,
+    role: String,
+    path: Option<String>,
 }
 
-fn extract_default_artifact_title(content: &str, role: &str) -> String {
-    let first_line = content
-        .lines()
-        .find(|line| !line.trim().is_empty())
-        .map(str::trim)
-        .unwrap_or("");
+fn create_artifact(
+    camp_dir: &Path,
+    source: ArtifactSource,
+    title: &str,
+    body: &str,
+    tags: &[String],
+) -> Result<CampArtifact, String> {
+    let title = validate_non_empty(title, "title")?;
 
-    if !first_line.is_empty() {
-        let truncated: String = first_line.chars().take(80).collect();
-        return truncated;
-    }
+    let artifact_id = Uuid::new_v4().to_string();
+    let filename = format!("{artifact_id}.md");
+    let now = now_timestamp_ms();
+    let metadata = CampArtifactMetadata {
+        id: artifact_id,
+        title: title.clone(),
+        filename,
+        source_message_id: source.message_id,
+        source_role: source.role,
+        tags: normalize_artifact_tags(tags),
+        created_at: now,
+        updated_at: now,
+        usage_count: 0,
+        archived: false,
+        source_path: source.path,
+        always_include: false,
+        include_order: None,
+    };
 
-    format!("{} note", role.trim())
+    let markdown = format_artifact_markdown(&title, body);
+    write_artifact_body(camp_dir, &metadata, &markdown)?;
+
+    let mut index = ensure_artifacts_index(camp_dir)?;
+    index.artifacts.push(metadata.clone());
+    write_artifacts_index(camp_dir, &index)?;
+
+    Ok(CampArtifact {
+        metadata,
+        body: markdown,
+    })
 }
 
-fn find_artifact_index_entry<'a>(
-    index: &'a mut CampArtifactsIndex,
-    artifact_id: &str,
-) -> Result<&'a mut CampArtifactMetadata, String> {
-    index
+/// Always-include artifacts in injection order. Archived artifacts are skipped
+/// so archiving still works as a quick way to stop sending one.
+fn always_include_artifacts(index: &CampArtifactsIndex) -> Vec<CampArtifactMetadata> {
+    let mut pinned: Vec<CampArtifactMetadata> = index
         .artifacts
-        .iter_mut()
+        .iter()
+        .filter(|artifact| artifact.always_include && !artifact.archived)
+        .cloned()
+        .collect();
+    pinned.sort_by(|left, right| {
+        left.include_order
+            .unwrap_or(i64::MAX)
+            .cmp(&right.include_order.unwrap_or(i64::MAX))
+            .then_with(|| left.created_at.cmp(&right.created_at))
+    });
+    pinned
+}
+
+fn set_artifact_always_include(
+    index: &mut CampArtifactsIndex,
+    artifact_id: &str,
+    always_include: bool,
+) -> Result<CampArtifactMetadata, String> {
+    let next_order = index
+        .artifacts
+        .iter()
+        .filter_map(|artifact| artifact.include_order)
+        .max()
+        .map_or(0, |order| order + 1);
+    let metadata = find_artifact_index_entry(index, artifact_id)?;
+    if metadata.always_include != always_include {
+        metadata.always_include = always_include;
+        metadata.include_order = always_include.then_some(next_order);
+        metadata.updated_at = now_timestamp_ms();
+    }
+    Ok(metadata.clone())
+}
+
+fn reorder_always_include_artifacts(
+    index: &mut CampArtifactsIndex,
+    artifact_ids: &[String],
+) -> Result<(), String> {
+    for (position, artifact_id) in artifact_ids.iter().enumerate() {
+        let metadata = find_artifact_index_entry(index, artifact_id)?;
+        if !metadata.always_include {
+            return Err(format!(
+                "Artifact `{artifact_id}` is not marked as always include."
+            ));
+        }
+        metadata.include_order = Some(position as i64);
+    }
+    Ok(())
+}
+
+fn format_artifact_markdown(title: &str, body: &str) -> String {
+    format!("# {}\n\n{}", title.trim(), body)
+}
+
+fn extract_default_artifact_title(content: &str, role: &str) -> String {
+    let first_line = content
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(str::trim)
+        .unwrap_or("");
+
+    if !first_line.is_empty() {
+        let truncated: String = first_line.chars().take(80).collect();
+        return truncated;
+    }
+
+    format!("{} note", role.trim())
+}
+
+fn find_artifact_index_entry<'a>(
+    index: &'a mut CampArtifactsIndex,
+    artifact_id: &str,
+) -> Result<&'a mut CampArtifactMetadata, String> {
+    index
+        .artifacts
+        .iter_mut()
         .find(|artifact| artifact.id == artifact_id)
         .ok_or_else(|| "Artifact not found.".to_string())
 }
@@ -1312,11 +1792,384 @@ fn load_artifact(camp_dir: &Path, artifact_id: &str) -> Result<CampArtifact, Str
     Ok(CampArtifact { metadata, body })
 }
 
+/// Writes `contents` next to `path` in a temp file, fsyncs it, then renames it
+/// into place so a crash mid-write never leaves a truncated file behind.
+fn write_file_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Invalid path {}.", path.to_string_lossy()))?;
+    let file_name = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .ok_or_else(|| format!("Invalid path {}.", path.to_string_lossy()))?;
+    let temp_path = parent.join(format!(".{file_name}.{}.tmp", Uuid::new_v4()));
+
+    let write_result = (|| -> std::io::Result<()> {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+
+    if let Err(err) = write_result {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!(
+            "Unable to write file {}: {err}",
+            path.to_string_lossy()
+        ));
+    }
+
+    if let Ok(dir) = fs::File::open(parent) {
+        let _ = dir.sync_all();
+    }
+
+    Ok(())
+}
+
+fn camp_trash_dir(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_TRASH_DIR)
+}
+
+fn read_trash_index(camp_dir: &Path) -> Result<CampTrashIndex, String> {
+    let index_path = camp_trash_dir(camp_dir).join(CAMP_TRASH_INDEX_FILE);
+    if !index_path.exists() {
+        return Ok(CampTrashIndex {
+            schema_version: CAMP_TRASH_SCHEMA_VERSION.to_string(),
+            entries: Vec::new(),
+        });
+    }
+
+    read_json_file(&index_path)
+}
+
+fn write_trash_index(camp_dir: &Path, index: &CampTrashIndex) -> Result<(), String> {
+    let trash_dir = camp_trash_dir(camp_dir);
+    fs::create_dir_all(&trash_dir)
+        .map_err(|err| format!("Unable to create trash folder: {err}"))?;
+    write_json_file(&trash_dir.join(CAMP_TRASH_INDEX_FILE), index)
+}
+
+/// Moves `source` into the camp trash and records it so it can be restored later.
+fn move_to_trash(
+    camp_dir: &Path,
+    source: &Path,
+    kind: CampTrashKind,
+    original_path: String,
+    artifact: Option<CampArtifactMetadata>,
+) -> Result<CampTrashEntry, String> {
+    let trash_dir = camp_trash_dir(camp_dir);
+    fs::create_dir_all(&trash_dir)
+        .map_err(|err| format!("Unable to create trash folder: {err}"))?;
+
+    let entry_id = Uuid::new_v4().to_string();
+    let trashed_filename = format!("{entry_id}.trash");
+    fs::rename(source, trash_dir.join(&trashed_filename))
+        .map_err(|err| format!("Unable to move file to trash: {err}"))?;
+
+    let entry = CampTrashEntry {
+        id: entry_id,
+        kind,
+        original_path,
+        trashed_filename,
+        artifact,
+        trashed_at: now_timestamp_ms(),
+    };
+
+    let mut index = read_trash_index(camp_dir)?;
+    index.entries.push(entry.clone());
+    write_trash_index(camp_dir, &index)?;
+
+    Ok(entry)
+}
+
+fn trash_artifact(camp_dir: &Path, artifact_id: &str) -> Result<CampTrashEntry, String> {
+    let validated_artifact_id = validate_identifier(artifact_id, "artifact_id")?;
+    let mut index = ensure_artifacts_index(camp_dir)?;
+    let position = index
+        .artifacts
+        .iter()
+        .position(|artifact| artifact.id == validated_artifact_id)
+        .ok_or_else(|| "Artifact not found.".to_string())?;
+    let metadata = index.artifacts[position].clone();
+    let source = artifact_markdown_path(camp_dir, &metadata.filename)?;
+    let original_path = format!("{CAMP_ARTIFACTS_DIR}/{}", metadata.filename);
+
+    let entry = move_to_trash(
+        camp_dir,
+        &source,
+        CampTrashKind::Artifact,
+        original_path,
+        Some(metadata),
+    )?;
+    index.artifacts.remove(position);
+    write_artifacts_index(camp_dir, &index)?;
+
+    Ok(entry)
+}
+
+fn restore_trash_entry(camp_dir: &Path, entry_id: &str) -> Result<CampTrashEntry, String> {
+    let validated_entry_id = validate_identifier(entry_id, "entry_id")?;
+    let mut trash = read_trash_index(camp_dir)?;
+    let position = trash
+        .entries
+        .iter()
+        .position(|entry| entry.id == validated_entry_id)
+        .ok_or_else(|| "Trash entry not found.".to_string())?;
+    let entry = trash.entries[position].clone();
+    let trashed_path = camp_trash_dir(camp_dir).join(&entry.trashed_filename);
+
+    match entry.kind {
+        CampTrashKind::Artifact => {
+            let metadata = entry
+                .artifact
+                .clone()
+                .ok_or_else(|| "Trash entry is missing artifact metadata.".to_string())?;
+            let mut index = ensure_artifacts_index(camp_dir)?;
+            if index
+                .artifacts
+                .iter()
+                .any(|artifact| artifact.id == metadata.id)
+            {
+                return Err("An artifact with this id already exists.".to_string());
+            }
+            let destination = artifact_markdown_path(camp_dir, &metadata.filename)?;
+            if destination.exists() {
+                return Err("Artifact file already exists; refusing to overwrite.".to_string());
+            }
+            fs::rename(&trashed_path, &destination)
+                .map_err(|err| format!("Unable to restore artifact: {err}"))?;
+            index.artifacts.push(metadata);
+            write_artifacts_index(camp_dir, &index)?;
+        }
+        CampTrashKind::ContextFile => {
+            let relative = entry
+                .original_path
+                .strip_prefix(&format!("{CAMP_CONTEXT_DIR}/"))
+                .ok_or_else(|| "Trash entry has an invalid context path.".to_string())?;
+            let context_dir = camp_context_dir(camp_dir);
+            fs::create_dir_all(&context_dir)
+                .map_err(|err| format!("Unable to create context folder: {err}"))?;
+            let context_root = canonicalize_context_root(&context_dir)?;
+            let destination = resolve_write_context_target(&context_root, relative)?;
+            if destination.exists() {
+                return Err("Context file already exists; refusing to overwrite.".to_string());
+            }
+            fs::rename(&trashed_path, &destination)
+                .map_err(|err| format!("Unable to restore context file: {err}"))?;
+        }
+        CampTrashKind::SyncConflict => {
+            let file_name = Path::new(&entry.original_path)
+                .file_name()
+                .filter(|name| name.to_str() == Some(entry.original_path.as_str()))
+                .ok_or_else(|| "Trash entry has an invalid conflict path.".to_string())?;
+            let destination = camp_dir.join(file_name);
+            if destination.exists() {
+                return Err("Conflicted copy already exists; refusing to overwrite.".to_string());
+            }
+            fs::rename(&trashed_path, &destination)
+                .map_err(|err| format!("Unable to restore conflicted copy: {err}"))?;
+        }
+    }
+
+    trash.entries.remove(position);
+    write_trash_index(camp_dir, &trash)?;
+    Ok(entry)
+}
+
+fn camp_checkpoints_dir(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_CHECKPOINTS_DIR)
+}
+
+fn read_checkpoints_index(camp_dir: &Path) -> Result<CampCheckpointsIndex, String> {
+    let index_path = camp_checkpoints_dir(camp_dir).join(CAMP_CHECKPOINTS_INDEX_FILE);
+    if !index_path.exists() {
+        return Ok(CampCheckpointsIndex {
+            schema_version: CAMP_CHECKPOINTS_SCHEMA_VERSION.to_string(),
+            checkpoints: Vec::new(),
+        });
+    }
+
+    read_json_file(&index_path)
+}
+
+fn write_checkpoints_index(camp_dir: &Path, index: &CampCheckpointsIndex) -> Result<(), String> {
+    let checkpoints_dir = camp_checkpoints_dir(camp_dir);
+    fs::create_dir_all(&checkpoints_dir)
+        .map_err(|err| format!("Unable to create checkpoints folder: {err}"))?;
+    write_json_file(&checkpoints_dir.join(CAMP_CHECKPOINTS_INDEX_FILE), index)
+}
+
+/// Records the current transcript length and a copy of memory.json under a name.
+fn create_checkpoint(camp_dir: &Path, name: &str) -> Result<CampCheckpoint, String> {
+    let validated_name = validate_non_empty(name, "name")?;
+    let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+    let memory: Value = read_json_file(&camp_memory_path(camp_dir))?;
+
+    let checkpoint_id = Uuid::new_v4().to_string();
+    let memory_filename = format!("{checkpoint_id}.memory.json");
+    let checkpoints_dir = camp_checkpoints_dir(camp_dir);
+    fs::create_dir_all(&checkpoints_dir)
+        .map_err(|err| format!("Unable to create checkpoints folder: {err}"))?;
+    write_json_file(&checkpoints_dir.join(&memory_filename), &memory)?;
+
+    let checkpoint = CampCheckpoint {
+        id: checkpoint_id,
+        name: validated_name,
+        message_count: transcript.len(),
+        last_message_id: transcript.last().map(|message| message.id.clone()),
+        memory_filename,
+        created_at: now_timestamp_ms(),
+    };
+
+    let mut index = read_checkpoints_index(camp_dir)?;
+    index.checkpoints.push(checkpoint.clone());
+    write_checkpoints_index(camp_dir, &index)?;
+
+    Ok(checkpoint)
+}
+
+/// Truncates the transcript back to `checkpoint_id` and restores its memory snapshot.
+/// Truncated messages are archived next to the checkpoint rather than discarded, and
+/// checkpoints that pointed past the new end of the transcript are dropped.
+fn rollback_to_checkpoint(
+    camp_dir: &Path,
+    checkpoint_id: &str,
+) -> Result<CampRollbackResult, String> {
+    let validated_checkpoint_id = validate_identifier(checkpoint_id, "checkpoint_id")?;
+    let mut index = read_checkpoints_index(camp_dir)?;
+    let checkpoint = index
+        .checkpoints
+        .iter()
+        .find(|checkpoint| checkpoint.id == validated_checkpoint_id)
+        .cloned()
+        .ok_or_else(|| "Checkpoint not found.".to_string())?;
+
+    let transcript_path = camp_transcript_path(camp_dir);
+    let transcript = read_transcript(&transcript_path)?;
+    if transcript.len() < checkpoint.message_count {
+        return Err(
+            "Transcript is shorter than the checkpoint; it may have been edited.".to_string(),
+        );
+    }
+    let marker_id = checkpoint
+        .message_count
+        .checked_sub(1)
+        .map(|position| transcript[position].id.clone());
+    if marker_id != checkpoint.last_message_id {
+        return Err("Transcript no longer matches the checkpoint marker.".to_string());
+    }
+
+    let checkpoints_dir = camp_checkpoints_dir(camp_dir);
+    let memory: Value = read_json_file(&checkpoints_dir.join(&checkpoint.memory_filename))?;
+
+    let (kept, truncated) = transcript.split_at(checkpoint.message_count);
+    let archive_filename = if truncated.is_empty() {
+        None
+    } else {
+        let filename = format!("{}.rollback-{}.jsonl", checkpoint.id, now_timestamp_ms());
+        let mut archived = String::new();
+        for message in truncated {
+            let serialized = serde_json::to_string(message)
+                .map_err(|err| format!("Unable to serialize message: {err}"))?;
+            archived.push_str(&serialized);
+            archived.push('\n');
+        }
+        write_file_atomic(&checkpoints_dir.join(&filename), archived.as_bytes())?;
+        Some(filename)
+    };
+
+    write_transcript(&transcript_path, kept)?;
+    write_json_file(&camp_memory_path(camp_dir), &memory)?;
+
+    let mut removed_checkpoint_ids = Vec::new();
+    index.checkpoints.retain(|candidate| {
+        if candidate.message_count > checkpoint.message_count {
+            removed_checkpoint_ids.push(candidate.id.clone());
+            let _ = fs::remove_file(checkpoints_dir.join(&candidate.memory_filename));
+            false
+        } else {
+            true
+        }
+    });
+    write_checkpoints_index(camp_dir, &index)?;
+
+    Ok(CampRollbackResult {
+        checkpoint,
+        archived_message_count: truncated.len(),
+        archive_filename,
+        removed_checkpoint_ids,
+    })
+}
+
+/// Removes the last user message and the assistant/tool replies after it,
+/// then gives back the artifact usage that message counted. Checkpoints taken
+/// inside the removed exchange are dropped, as with a rollback.
+fn undo_last_exchange(camp_dir: &Path) -> Result<CampUndoExchangeResult, String> {
+    let transcript_path = camp_transcript_path(camp_dir);
+    let mut transcript = read_transcript(&transcript_path)?;
+    let start = transcript
+        .iter()
+        .rposition(|message| message.role == "user")
+        .ok_or_else(|| "There is no exchange to undo.".to_string())?;
+    let removed_messages = transcript.split_off(start);
+
+    // Load and adjust everything before writing so a bad index can't leave
+    // the transcript truncated with the counters untouched.
+    let included_ids = removed_messages[0]
+        .included_artifact_ids
+        .clone()
+        .unwrap_or_default();
+    let mut artifacts_index = if included_ids.is_empty() {
+        None
+    } else {
+        Some(ensure_artifacts_index(camp_dir)?)
+    };
+    let mut rolled_back_artifact_ids = Vec::new();
+    if let Some(index) = artifacts_index.as_mut() {
+        let now = now_timestamp_ms();
+        for artifact in &mut index.artifacts {
+            if included_ids.contains(&artifact.id) && artifact.usage_count > 0 {
+                artifact.usage_count -= 1;
+                artifact.updated_at = now;
+                rolled_back_artifact_ids.push(artifact.id.clone());
+            }
+        }
+    }
+
+    let mut checkpoints = read_checkpoints_index(camp_dir)?;
+    let (kept_checkpoints, dropped_checkpoints): (Vec<_>, Vec<_>) = checkpoints
+        .checkpoints
+        .into_iter()
+        .partition(|checkpoint| checkpoint.message_count <= transcript.len());
+    checkpoints.checkpoints = kept_checkpoints;
+
+    write_transcript(&transcript_path, &transcript)?;
+
+    if let Some(index) = artifacts_index.filter(|_| !rolled_back_artifact_ids.is_empty()) {
+        write_artifacts_index(camp_dir, &index)?;
+    }
+    if !dropped_checkpoints.is_empty() {
+        write_checkpoints_index(camp_dir, &checkpoints)?;
+        let checkpoints_dir = camp_checkpoints_dir(camp_dir);
+        for checkpoint in &dropped_checkpoints {
+            let _ = fs::remove_file(checkpoints_dir.join(&checkpoint.memory_filename));
+        }
+    }
+
+    Ok(CampUndoExchangeResult {
+        removed_messages,
+        rolled_back_artifact_ids,
+        removed_checkpoint_ids: dropped_checkpoints
+            .into_iter()
+            .map(|checkpoint| checkpoint.id)
+            .collect(),
+    })
+}
+
 fn write_json_file<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
     let serialized = serde_json::to_string_pretty(value)
         .map_err(|err| format!("Unable to serialize JSON: {err}"))?;
-    fs::write(path, serialized)
-        .map_err(|err| format!("Unable to write file {}: {err}", path.to_string_lossy()))
+    write_file_atomic(path, serialized.as_bytes())
 }
 
 fn read_json_file<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
@@ -1489,23 +2342,98 @@ fn parse_model_overrides_field(value: Option<&Value>) -> (Option<CampModelOverri
     migrated |= max_tokens_migrated;
     let (top_p, top_p_migrated) = parse_optional_f64_field(object.get("top_p"));
     migrated |= top_p_migrated;
+    let (stop, stop_migrated) = parse_stop_sequences_field(object.get("stop"));
+    migrated |= stop_migrated;
+    let (logit_bias, logit_bias_migrated) = parse_logit_bias_field(object.get("logit_bias"));
+    migrated |= logit_bias_migrated;
 
     let overrides = CampModelOverrides {
         temperature,
         max_tokens,
         top_p,
+        stop,
+        logit_bias,
     };
 
-    if overrides.temperature.is_none()
-        && overrides.max_tokens.is_none()
-        && overrides.top_p.is_none()
-    {
+    if overrides.is_empty() {
         return (None, migrated || !object.is_empty());
     }
 
     (Some(overrides), migrated)
 }
 
+/// Stop sequences are kept verbatim (whitespace is meaningful), deduplicated and
+/// capped at the four sequences OpenAI-compatible APIs accept.
+fn normalize_stop_sequences(sequences: Vec<String>) -> Option<Vec<String>> {
+    let mut normalized: Vec<String> = Vec::new();
+    for sequence in sequences {
+        if sequence.is_empty() || normalized.contains(&sequence) {
+            continue;
+        }
+        normalized.push(sequence);
+    }
+    normalized.truncate(MAX_STOP_SEQUENCES);
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn parse_stop_sequences_field(value: Option<&Value>) -> (Option<Vec<String>>, bool) {
+    let Some(raw_value) = value else {
+        return (None, false);
+    };
+    let raw_sequences: Vec<String> = match raw_value {
+        Value::String(sequence) => vec![sequence.clone()],
+        Value::Array(items) => items
+            .iter()
+            .filter_map(Value::as_str)
+            .map(ToString::to_string)
+            .collect(),
+        _ => return (None, true),
+    };
+
+    let normalized = normalize_stop_sequences(raw_sequences);
+    let migrated = !raw_value.is_array()
+        || normalized.as_ref().map_or(0, Vec::len) != raw_value.as_array().map_or(0, Vec::len);
+    (normalized, migrated)
+}
+
+/// Keys are token ids; biases are clamped to the -100..=100 range providers accept.
+fn normalize_logit_bias(bias: BTreeMap<String, f64>) -> Option<BTreeMap<String, f64>> {
+    let normalized: BTreeMap<String, f64> = bias
+        .into_iter()
+        .filter(|(token_id, value)| token_id.trim().parse::<u64>().is_ok() && value.is_finite())
+        .map(|(token_id, value)| {
+            (
+                token_id.trim().to_string(),
+                value.clamp(-LOGIT_BIAS_LIMIT, LOGIT_BIAS_LIMIT),
+            )
+        })
+        .collect();
+    (!normalized.is_empty()).then_some(normalized)
+}
+
+fn parse_logit_bias_field(value: Option<&Value>) -> (Option<BTreeMap<String, f64>>, bool) {
+    let Some(raw_value) = value else {
+        return (None, false);
+    };
+    let Some(object) = raw_value.as_object() else {
+        return (None, true);
+    };
+
+    let mut migrated = false;
+    let mut raw_bias = BTreeMap::new();
+    for (token_id, bias_value) in object {
+        let (bias, bias_migrated) = parse_optional_f64_field(Some(bias_value));
+        migrated |= bias_migrated;
+        if let Some(bias) = bias {
+            raw_bias.insert(token_id.clone(), bias);
+        }
+    }
+
+    let normalized = normalize_logit_bias(raw_bias.clone());
+    migrated |= normalized.as_ref() != Some(&raw_bias);
+    (normalized, migrated)
+}
+
 fn parse_string_list_field(value: Option<&Value>) -> Option<Vec<String>> {
     let array = value?.as_array()?;
     let mut normalized = Vec::new();
@@ -1533,6 +2461,45 @@ fn parse_string_list_field(value: Option<&Value>) -> Option<Vec<String>> {
     }
 }
 
+fn normalize_camp_color(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    let hex = trimmed.strip_prefix('#')?;
+    if !matches!(hex.len(), 3 | 6) || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    Some(format!("#{}", hex.to_ascii_lowercase()))
+}
+
+fn parse_camp_color_field(value: Option<&Value>) -> (Option<String>, bool) {
+    let (raw_color, mut migrated) = parse_non_empty_string_field(value);
+    let Some(raw_color) = raw_color else {
+        return (None, migrated);
+    };
+
+    let normalized = normalize_camp_color(&raw_color);
+    migrated |= normalized.as_deref() != Some(raw_color.as_str());
+    (normalized, migrated)
+}
+
+fn parse_camp_tags_field(value: Option<&Value>) -> (Vec<String>, bool) {
+    let Some(raw_value) = value else {
+        return (Vec::new(), false);
+    };
+    let Some(items) = raw_value.as_array() else {
+        return (Vec::new(), true);
+    };
+
+    let raw_tags: Vec<String> = items
+        .iter()
+        .filter_map(Value::as_str)
+        .map(ToString::to_string)
+        .collect();
+    let normalized = normalize_artifact_tags(&raw_tags);
+    let migrated = raw_tags.len() != items.len() || normalized != raw_tags;
+    (normalized, migrated)
+}
+
 fn parse_message_content_field(value: Option<&Value>) -> Option<String> {
     let raw_value = value?;
 
@@ -1746,6 +2713,15 @@ fn parse_loaded_transcript_message(
             .or_else(|| message_object.get("artifact_ids")),
     );
 
+    let (reasoning, run_id) = if role == "assistant" {
+        (
+            parse_non_empty_string_field(message_object.get("reasoning")).0,
+            parse_non_empty_string_field(message_object.get("run_id")).0,
+        )
+    } else {
+        (None, None)
+    };
+
     Ok(CampMessage {
         id: message_id,
         role,
@@ -1756,6 +2732,8 @@ fn parse_loaded_transcript_message(
         tool_calls,
         included_artifact_ids,
         attachments: None,
+        reasoning,
+        run_id,
     })
 }
 
@@ -1772,12 +2750,15 @@ fn parse_camp_config_from_json(
     let schema_version = parse_non_empty_string_field(config_object.get("schema_version")).0;
     match schema_version.as_deref() {
         Some(CAMP_SCHEMA_VERSION) => {}
-        Some(PREVIOUS_CAMP_SCHEMA_VERSION) | Some(LEGACY_CAMP_SCHEMA_VERSION) | None => {
+        Some(PREVIOUS_CAMP_SCHEMA_VERSION)
+        | Some(V0_1_CAMP_SCHEMA_VERSION)
+        | Some(LEGACY_CAMP_SCHEMA_VERSION)
+        | None => {
             migrated = true;
         }
         Some(other) => {
             return Err(format!(
-                "Unsupported camp schema_version `{other}`. Supported versions: {LEGACY_CAMP_SCHEMA_VERSION}, {PREVIOUS_CAMP_SCHEMA_VERSION}, {CAMP_SCHEMA_VERSION}."
+                "Unsupported camp schema_version `{other}`. Supported versions: {LEGACY_CAMP_SCHEMA_VERSION}, {V0_1_CAMP_SCHEMA_VERSION}, {PREVIOUS_CAMP_SCHEMA_VERSION}, {CAMP_SCHEMA_VERSION}."
             ));
         }
     }
@@ -1915,6 +2896,57 @@ fn parse_camp_config_from_json(
         }
     };
 
+    let (description, description_migrated) =
+        parse_non_empty_string_field(config_object.get("description"));
+    migrated |= description_migrated;
+    let (color, color_migrated) = parse_camp_color_field(config_object.get("color"));
+    migrated |= color_migrated;
+    let (emoji, emoji_migrated) = parse_non_empty_string_field(config_object.get("emoji"));
+    migrated |= emoji_migrated;
+    let (pinned_value, pinned_migrated) = parse_bool_field(config_object.get("pinned"));
+    migrated |= pinned_migrated;
+    let pinned = pinned_value.unwrap_or(false);
+    let (favorite_value, favorite_migrated) = parse_bool_field(config_object.get("favorite"));
+    migrated |= favorite_migrated;
+    let favorite = favorite_value.unwrap_or(false);
+    let (generation_defaults, generation_defaults_migrated) =
+        parse_model_overrides_field(config_object.get("generation_defaults"));
+    migrated |= generation_defaults_migrated;
+    let (tags, tags_migrated) = parse_camp_tags_field(config_object.get("tags"));
+    migrated |= tags_migrated;
+    let (privacy_mode_value, privacy_mode_migrated) =
+        parse_bool_field(config_object.get("privacy_mode"));
+    migrated |= privacy_mode_migrated;
+    let privacy_mode = privacy_mode_value.unwrap_or(false);
+    let (local_only_value, local_only_migrated) = parse_bool_field(config_object.get("local_only"));
+    migrated |= local_only_migrated;
+    let local_only = local_only_value.unwrap_or(false);
+    let (post_processors, post_processors_migrated) =
+        post_processing::parse_post_processors_field(config_object.get("post_processors"));
+    migrated |= post_processors_migrated;
+    let (auto_extract_value, auto_extract_migrated) =
+        parse_bool_field(config_object.get("auto_extract_code_blocks"));
+    migrated |= auto_extract_migrated;
+    let auto_extract_code_blocks = auto_extract_value.unwrap_or(false);
+    let (preview_file_writes_value, preview_file_writes_migrated) =
+        parse_bool_field(config_object.get("preview_file_writes"));
+    migrated |= preview_file_writes_migrated;
+    let preview_file_writes = preview_file_writes_value.unwrap_or(false);
+    let (memory_schema, memory_schema_migrated) =
+        memory_schema::parse_memory_schema_field(config_object.get("memory_schema"));
+    migrated |= memory_schema_migrated;
+    let (project_id, project_id_migrated) =
+        parse_non_empty_string_field(config_object.get("project_id"));
+    migrated |= project_id_migrated;
+    let (default_context_paths, default_context_paths_migrated) =
+        default_context::parse_default_context_paths_field(
+            config_object.get("default_context_paths"),
+        );
+    migrated |= default_context_paths_migrated;
+    let (fetch_allowed_domains, fetch_allowed_domains_migrated) =
+        fetch_url::parse_fetch_allowed_domains_field(config_object.get("fetch_allowed_domains"));
+    migrated |= fetch_allowed_domains_migrated;
+
     let (created_at_value, created_at_migrated) =
         parse_timestamp_field(config_object.get("created_at"));
     migrated |= created_at_migrated;
@@ -1955,6 +2987,22 @@ fn parse_camp_config_from_json(
             model_overrides,
             tools_enabled,
             is_team,
+            description,
+            color,
+            emoji,
+            pinned,
+            favorite,
+            generation_defaults,
+            tags,
+            privacy_mode,
+            local_only,
+            post_processors,
+            auto_extract_code_blocks,
+            preview_file_writes,
+            memory_schema,
+            project_id,
+            default_context_paths,
+            fetch_allowed_domains,
             created_at,
             updated_at,
         },
@@ -2012,49 +3060,199 @@ fn append_transcript_message(path: &Path, message: &CampMessage) -> Result<(), S
         .map_err(|err| format!("Unable to append transcript message: {err}"))
 }
 
-fn read_camp_config(camp_dir: &Path) -> Result<CampConfig, String> {
-    let config_path = camp_config_path(camp_dir);
-    let raw = fs::read_to_string(&config_path).map_err(|err| {
-        format!(
-            "Unable to read file {}: {err}",
-            config_path.to_string_lossy()
-        )
-    })?;
-    let parsed_value: Value = serde_json::from_str(&raw).map_err(|err| {
-        format!(
-            "Unable to parse JSON {}: {err}",
-            config_path.to_string_lossy()
-        )
-    })?;
-
-    let (config, migrated) = parse_camp_config_from_json(camp_dir, parsed_value)?;
-    if migrated {
-        write_json_file(&config_path, &config)?;
+/// Replaces the whole transcript with `messages` in one atomic write.
+fn write_transcript(path: &Path, messages: &[CampMessage]) -> Result<(), String> {
+    let mut rewritten = String::new();
+    for message in messages {
+        let serialized = serde_json::to_string(message)
+            .map_err(|err| format!("Unable to serialize message: {err}"))?;
+        rewritten.push_str(&serialized);
+        rewritten.push('\n');
     }
-
-    Ok(config)
-}
-
-fn write_camp_config(camp_dir: &Path, config: &CampConfig) -> Result<(), String> {
-    write_json_file(&camp_config_path(camp_dir), config)
+    write_file_atomic(path, rewritten.as_bytes())
 }
 
-fn touch_camp_updated_at(camp_dir: &Path) -> Result<(), String> {
-    let mut config = read_camp_config(camp_dir)?;
-    config.updated_at = now_timestamp_ms();
-    write_camp_config(camp_dir, &config)
-}
+fn verify_transcript(camp_dir: &Path, repair: bool) -> Result<TranscriptVerifyReport, String> {
+    let path = camp_transcript_path(camp_dir);
+    let raw = if path.exists() {
+        read_text_file(&path)?
+    } else {
+        String::new()
+    };
 
-fn validate_non_empty(value: &str, field_name: &str) -> Result<String, String> {
-    let trimmed = value.trim();
-    if trimmed.is_empty() {
-        return Err(format!("{field_name} cannot be empty."));
-    }
-    Ok(trimmed.to_string())
-}
+    let mut issues = Vec::new();
+    let mut kept_lines: Vec<&str> = Vec::new();
+    let mut rejected_lines: Vec<&str> = Vec::new();
+    let mut seen_ids: HashSet<String> = HashSet::new();
+    let mut known_tool_call_ids: HashSet<String> = HashSet::new();
+    let mut latest_created_at: Option<i64> = None;
+    let mut total_lines = 0;
 
-fn validate_camp_role(role: &str) -> Result<String, String> {
-    let normalized = role.trim().to_lowercase();
+    for (line_number, line) in raw.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        total_lines += 1;
+
+        let parsed = serde_json::from_str::<Value>(trimmed)
+            .map_err(|err| format!("Invalid JSON: {err}"))
+            .and_then(|value| parse_loaded_transcript_message(&value, line_number));
+        let message = match parsed {
+            Ok(message) => message,
+            Err(detail) => {
+                issues.push(TranscriptIssue {
+                    line: line_number + 1,
+                    kind: TranscriptIssueKind::Malformed,
+                    message_id: None,
+                    detail,
+                    quarantined: repair,
+                });
+                rejected_lines.push(trimmed);
+                continue;
+            }
+        };
+
+        if !seen_ids.insert(message.id.clone()) {
+            issues.push(TranscriptIssue {
+                line: line_number + 1,
+                kind: TranscriptIssueKind::DuplicateId,
+                message_id: Some(message.id.clone()),
+                detail: format!("Message id `{}` appears more than once.", message.id),
+                quarantined: repair,
+            });
+            rejected_lines.push(trimmed);
+            continue;
+        }
+
+        if message.role == "tool" {
+            let orphan_detail = match message.tool_call_id.as_deref() {
+                None => Some("Tool message is missing tool_call_id.".to_string()),
+                Some(call_id) if !known_tool_call_ids.contains(call_id) => Some(format!(
+                    "Tool message references unknown tool_call_id `{call_id}`."
+                )),
+                Some(_) => None,
+            };
+            if let Some(detail) = orphan_detail {
+                issues.push(TranscriptIssue {
+                    line: line_number + 1,
+                    kind: TranscriptIssueKind::OrphanedTool,
+                    message_id: Some(message.id.clone()),
+                    detail,
+                    quarantined: repair,
+                });
+                rejected_lines.push(trimmed);
+                continue;
+            }
+        }
+
+        if let Some(calls) = &message.tool_calls {
+            for call in calls {
+                known_tool_call_ids.insert(call.id.clone());
+            }
+        }
+
+        // Out-of-order timestamps are reported but kept; dropping them would lose content.
+        if let Some(previous) = latest_created_at {
+            if message.created_at < previous {
+                issues.push(TranscriptIssue {
+                    line: line_number + 1,
+                    kind: TranscriptIssueKind::OutOfOrder,
+                    message_id: Some(message.id.clone()),
+                    detail: format!(
+                        "created_at {} is earlier than the preceding message ({previous}).",
+                        message.created_at
+                    ),
+                    quarantined: false,
+                });
+            }
+        }
+        latest_created_at = Some(latest_created_at.map_or(message.created_at, |previous| {
+            previous.max(message.created_at)
+        }));
+
+        kept_lines.push(trimmed);
+    }
+
+    let mut report = TranscriptVerifyReport {
+        total_lines,
+        valid_messages: kept_lines.len(),
+        issues,
+        repaired: false,
+        rejected_path: None,
+    };
+
+    if !repair || rejected_lines.is_empty() {
+        return Ok(report);
+    }
+
+    let rejected_path = camp_transcript_rejected_path(camp_dir);
+    let mut rejected_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&rejected_path)
+        .map_err(|err| format!("Unable to open rejected transcript file: {err}"))?;
+    for line in &rejected_lines {
+        rejected_file
+            .write_all(line.as_bytes())
+            .and_then(|_| rejected_file.write_all(b"\n"))
+            .map_err(|err| format!("Unable to quarantine transcript line: {err}"))?;
+    }
+
+    let mut repaired = kept_lines.join("\n");
+    if !repaired.is_empty() {
+        repaired.push('\n');
+    }
+    write_file_atomic(&path, repaired.as_bytes())?;
+
+    report.repaired = true;
+    report.rejected_path = Some(rejected_path.to_string_lossy().into_owned());
+    Ok(report)
+}
+
+fn read_camp_config(camp_dir: &Path) -> Result<CampConfig, String> {
+    let config_path = camp_config_path(camp_dir);
+    let raw = fs::read_to_string(&config_path).map_err(|err| {
+        format!(
+            "Unable to read file {}: {err}",
+            config_path.to_string_lossy()
+        )
+    })?;
+    let parsed_value: Value = serde_json::from_str(&raw).map_err(|err| {
+        format!(
+            "Unable to parse JSON {}: {err}",
+            config_path.to_string_lossy()
+        )
+    })?;
+
+    let (config, migrated) = parse_camp_config_from_json(camp_dir, parsed_value)?;
+    if migrated {
+        write_json_file(&config_path, &config)?;
+    }
+
+    Ok(config)
+}
+
+fn write_camp_config(camp_dir: &Path, config: &CampConfig) -> Result<(), String> {
+    write_json_file(&camp_config_path(camp_dir), config)
+}
+
+fn touch_camp_updated_at(camp_dir: &Path) -> Result<(), String> {
+    let mut config = read_camp_config(camp_dir)?;
+    config.updated_at = now_timestamp_ms();
+    write_camp_config(camp_dir, &config)
+}
+
+fn validate_non_empty(value: &str, field_name: &str) -> Result<String, String> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(format!("{field_name} cannot be empty."));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn validate_camp_role(role: &str) -> Result<String, String> {
+    let normalized = role.trim().to_lowercase();
     match normalized.as_str() {
         "system" | "user" | "assistant" | "tool" => Ok(normalized),
         _ => Err("role must be one of: system, user, assistant, tool.".to_string()),
@@ -2174,6 +3372,11 @@ fn normalize_included_artifact_ids(
 fn load_camp_from_dir(camp_dir: &Path) -> Result<Camp, String> {
     let config = read_camp_config(camp_dir)?;
     let system_prompt = read_text_file(&camp_system_prompt_path(camp_dir))?;
+    // Camps always sit at `<workspace>/camps/<id>`.
+    let global_instructions = match camp_dir.parent().and_then(Path::parent) {
+        Some(workspace_path) => global_instructions::read_global_instructions(workspace_path)?,
+        None => String::new(),
+    };
     let memory: Value = read_json_file(&camp_memory_path(camp_dir))?;
     let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
     let context_path = camp_context_dir(camp_dir).to_string_lossy().into_owned();
@@ -2181,6 +3384,7 @@ fn load_camp_from_dir(camp_dir: &Path) -> Result<Camp, String> {
     Ok(Camp {
         config,
         system_prompt,
+        global_instructions,
         memory,
         transcript,
         context_path,
@@ -2373,6 +3577,152 @@ fn parse_openrouter_resolved_model(payload: &Value) -> Option<String> {
         .map(ToString::to_string)
 }
 
+fn parse_openrouter_generation_id(payload: &Value) -> Option<String> {
+    payload
+        .get("id")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Fetches `/api/v1/generation` for a finished completion. OpenRouter only
+/// publishes the record a moment after the response ends, so 404s are retried.
+async fn fetch_openrouter_generation(
+    client: &reqwest::Client,
+    api_key: &str,
+    generation_id: &str,
+) -> Result<Value, OpenRouterCommandError> {
+    let headers = to_reqwest_headers(&openrouter_headers(api_key));
+    let mut delays = OPENROUTER_GENERATION_RETRY_DELAYS_MS.iter();
+
+    loop {
+        let response = client
+            .get(OPENROUTER_GENERATION_URL)
+            .headers(headers.clone())
+            .query(&[("id", generation_id)])
+            .send()
+            .await
+            .map_err(|err| OpenRouterCommandError {
+                message: format!("OpenRouter generation request failed: {err}"),
+                status: None,
+                response_payload: Value::Null,
+            })?;
+
+        let status = response.status().as_u16();
+        if status == 404 {
+            if let Some(delay) = delays.next() {
+                tokio::time::sleep(Duration::from_millis(*delay)).await;
+                continue;
+            }
+        }
+
+        let is_success = response.status().is_success();
+        let payload = response.json::<Value>().await.unwrap_or(Value::Null);
+        if !is_success {
+            return Err(OpenRouterCommandError {
+                message: parse_openrouter_error_message(status, &payload),
+                status: Some(status),
+                response_payload: payload,
+            });
+        }
+
+        return payload
+            .get("data")
+            .cloned()
+            .ok_or_else(|| OpenRouterCommandError {
+                message: "OpenRouter generation response missing `data` object.".to_string(),
+                status: Some(status),
+                response_payload: payload,
+            });
+    }
+}
+
+fn store_openrouter_generation(
+    connection: &Connection,
+    run_id: &str,
+    generation: &Value,
+) -> Result<(), String> {
+    let generation_json = serde_json::to_string(generation)
+        .map_err(|err| format!("Unable to serialize generation metadata: {err}"))?;
+    let provider_name = generation
+        .get("provider_name")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+
+    connection
+        .execute(
+            "
+      UPDATE runs
+      SET total_cost = ?2,
+          native_tokens_prompt = ?3,
+          native_tokens_completion = ?4,
+          native_tokens_reasoning = ?5,
+          provider_name = ?6,
+          generation_json = ?7
+      WHERE id = ?1
+      ",
+            params![
+                run_id,
+                generation.get("total_cost").and_then(Value::as_f64),
+                generation
+                    .get("native_tokens_prompt")
+                    .and_then(Value::as_i64),
+                generation
+                    .get("native_tokens_completion")
+                    .and_then(Value::as_i64),
+                generation
+                    .get("native_tokens_reasoning")
+                    .and_then(Value::as_i64),
+                provider_name,
+                generation_json,
+            ],
+        )
+        .map_err(|err| format!("Unable to store generation metadata: {err}"))?;
+
+    Ok(())
+}
+
+/// Looks up the run's generation on OpenRouter and persists real cost, native
+/// token counts and the routed provider on the run row.
+async fn enrich_run_with_openrouter_generation(
+    state: &AppState,
+    run_id: &str,
+) -> Result<(), OpenRouterCommandError> {
+    let to_error = |message: String| OpenRouterCommandError {
+        message,
+        status: None,
+        response_payload: Value::Null,
+    };
+
+    let generation_id = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| to_error("Database lock error".to_string()))?;
+        connection
+            .query_row(
+                "SELECT generation_id FROM runs WHERE id = ?1",
+                params![run_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()
+            .map_err(|err| to_error(format!("Unable to fetch run: {err}")))?
+            .ok_or_else(|| to_error("Run not found.".to_string()))?
+            .ok_or_else(|| to_error("Run has no OpenRouter generation id.".to_string()))?
+    };
+
+    let api_key = require_api_key_from_keyring()?;
+    let generation =
+        fetch_openrouter_generation(&state.provider_client, &api_key, &generation_id).await?;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| to_error("Database lock error".to_string()))?;
+    store_openrouter_generation(&connection, run_id, &generation).map_err(to_error)
+}
+
 fn parse_openrouter_error_message(status: u16, payload: &Value) -> String {
     payload
         .get("error")
@@ -2531,12 +3881,14 @@ async fn stream_openrouter_completion(
             duration_ms: (now_timestamp_ms() - started_at).max(0),
             response_headers,
             stream_chunk_count: 0,
+            generation_id: parse_openrouter_generation_id(&payload),
         });
     }
 
     let mut stream = response.bytes_stream();
     let mut output_text = String::new();
     let mut resolved_model: Option<String> = None;
+    let mut generation_id: Option<String> = None;
     let mut usage = OpenRouterUsage {
         prompt_tokens: None,
         completion_tokens: None,
@@ -2575,6 +3927,9 @@ async fn stream_openrouter_completion(
                     if let Some(model) = parse_openrouter_resolved_model(&chunk_value) {
                         resolved_model = Some(model);
                     }
+                    if let Some(id) = parse_openrouter_generation_id(&chunk_value) {
+                        generation_id = Some(id);
+                    }
 
                     let next_usage = parse_openrouter_usage(&chunk_value);
                     usage.prompt_tokens = next_usage.prompt_tokens.or(usage.prompt_tokens);
@@ -2616,6 +3971,9 @@ async fn stream_openrouter_completion(
                 if let Some(model) = parse_openrouter_resolved_model(&chunk_value) {
                     resolved_model = Some(model);
                 }
+                if let Some(id) = parse_openrouter_generation_id(&chunk_value) {
+                    generation_id = Some(id);
+                }
 
                 let next_usage = parse_openrouter_usage(&chunk_value);
                 usage.prompt_tokens = next_usage.prompt_tokens.or(usage.prompt_tokens);
@@ -2644,6 +4002,7 @@ async fn stream_openrouter_completion(
         "output_text": output_text,
         "usage": usage,
         "resolved_model": resolved_model,
+        "id": generation_id,
     });
 
     Ok(OpenRouterCompletionResult {
@@ -2655,6 +4014,7 @@ async fn stream_openrouter_completion(
         duration_ms: (now_timestamp_ms() - started_at).max(0),
         response_headers,
         stream_chunk_count,
+        generation_id,
     })
 }
 
@@ -2730,20 +4090,10 @@ async fn openrouter_sync_models(
 async fn refresh_models_for_provider(
     state: &AppState,
     provider_kind: ProviderKind,
-) -> Result<usize, ProviderCommandError> {
+) -> CommandResult<usize> {
     let settings = {
-        let connection = state.connection.lock().map_err(|_| ProviderCommandError {
-            message: "Database lock error".to_string(),
-            status: None,
-            response_payload: Value::Null,
-        })?;
-        read_provider_runtime_settings(&connection, provider_kind).map_err(|message| {
-            ProviderCommandError {
-                message,
-                status: None,
-                response_payload: Value::Null,
-            }
-        })?
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        read_provider_runtime_settings(&connection, provider_kind).map_err(BasecampError::from)?
     };
 
     if !settings.config.enabled {
@@ -2769,18 +4119,10 @@ async fn refresh_models_for_provider(
         }
     };
 
-    let mut connection = state.connection.lock().map_err(|_| ProviderCommandError {
-        message: "Database lock error".to_string(),
-        status: None,
-        response_payload: Value::Null,
-    })?;
+    let mut connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
 
     let count = registry::replace_models_for_provider(&mut connection, provider_kind, &models)
-        .map_err(|err| ProviderCommandError {
-            message: format!("Unable to update models cache: {err}"),
-            status: None,
-            response_payload: Value::Null,
-        })?;
+        .map_err(|err| BasecampError::database(format!("Unable to update models cache: {err}")))?;
     let _ = registry::update_provider_health(&connection, provider_kind, true, None);
     Ok(count)
 }
@@ -2788,39 +4130,20 @@ async fn refresh_models_for_provider(
 async fn run_provider_health_check(
     state: &AppState,
     provider_kind: ProviderKind,
-) -> Result<ProviderRegistryRow, ProviderCommandError> {
+) -> CommandResult<ProviderRegistryRow> {
     let settings = {
-        let connection = state.connection.lock().map_err(|_| ProviderCommandError {
-            message: "Database lock error".to_string(),
-            status: None,
-            response_payload: Value::Null,
-        })?;
-        read_provider_runtime_settings(&connection, provider_kind).map_err(|message| {
-            ProviderCommandError {
-                message,
-                status: None,
-                response_payload: Value::Null,
-            }
-        })?
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        read_provider_runtime_settings(&connection, provider_kind).map_err(BasecampError::from)?
     };
 
     let provider = state.provider_manager.get(provider_kind);
     let health = provider
         .health_check(&state.provider_client, &settings)
-        .await
-        .map_err(ProviderCommandError::from)?;
+        .await?;
 
-    let connection = state.connection.lock().map_err(|_| ProviderCommandError {
-        message: "Database lock error".to_string(),
-        status: None,
-        response_payload: Value::Null,
-    })?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
     let mut row = registry::get_provider(&connection, provider_kind)
-        .map_err(|err| ProviderCommandError {
-            message: format!("Unable to load provider row: {err}"),
-            status: None,
-            response_payload: Value::Null,
-        })?
+        .map_err(|err| BasecampError::database(format!("Unable to load provider row: {err}")))?
         .unwrap_or(ProviderRegistryRow {
             provider_kind,
             base_url: provider_kind.default_base_url().to_string(),
@@ -2838,40 +4161,42 @@ async fn run_provider_health_check(
     } else {
         health.message.clone()
     };
-    registry::upsert_provider(&connection, &row).map_err(|err| ProviderCommandError {
-        message: format!("Unable to persist provider health: {err}"),
-        status: None,
-        response_payload: Value::Null,
+    registry::upsert_provider(&connection, &row).map_err(|err| {
+        BasecampError::database(format!("Unable to persist provider health: {err}"))
     })?;
     Ok(row)
 }
 
 #[tauri::command]
-fn providers_list(state: State<'_, AppState>) -> Result<Vec<ProviderRegistryRow>, String> {
-    let connection = state
-        .connection
-        .lock()
-        .map_err(|_| "Database lock error".to_string())?;
-    registry::list_providers(&connection).map_err(|err| format!("Unable to list providers: {err}"))
+fn providers_list(state: State<'_, AppState>) -> CommandResult<Vec<ProviderRegistryRow>> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    registry::list_providers(&connection)
+        .map_err(|err| BasecampError::database(format!("Unable to list providers: {err}")))
+}
+
+/// Running and waiting requests per provider, split by priority lane.
+#[tauri::command]
+fn get_queue_status(state: State<'_, AppState>) -> Vec<providers::ProviderQueueStatus> {
+    state.provider_manager.queue_status()
 }
 
 #[tauri::command]
 fn provider_update(
     state: State<'_, AppState>,
     payload: ProviderUpdatePayload,
-) -> Result<ProviderRegistryRow, String> {
-    let provider_kind = parse_provider_kind(&payload.provider_kind)?;
+) -> CommandResult<ProviderRegistryRow> {
+    let provider_kind =
+        parse_provider_kind(&payload.provider_kind).map_err(BasecampError::validation)?;
     let base_url = payload.base_url.trim();
     if base_url.is_empty() {
-        return Err("base_url cannot be empty.".to_string());
+        return Err(BasecampError::validation("base_url cannot be empty.")
+            .with_context("field", "base_url"));
     }
 
-    let connection = state
-        .connection
-        .lock()
-        .map_err(|_| "Database lock error".to_string())?;
-    let existing = registry::get_provider(&connection, provider_kind)
-        .map_err(|err| format!("Unable to read existing provider settings: {err}"))?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let existing = registry::get_provider(&connection, provider_kind).map_err(|err| {
+        BasecampError::database(format!("Unable to read existing provider settings: {err}"))
+    })?;
     let row = ProviderRegistryRow {
         provider_kind,
         base_url: base_url.to_string(),
@@ -2879,35 +4204,27 @@ fn provider_update(
         last_ok_at: existing.as_ref().and_then(|value| value.last_ok_at),
         last_error: existing.and_then(|value| value.last_error),
     };
-    registry::upsert_provider(&connection, &row)
-        .map_err(|err| format!("Unable to save provider settings: {err}"))?;
+    registry::upsert_provider(&connection, &row).map_err(|err| {
+        BasecampError::database(format!("Unable to save provider settings: {err}"))
+    })?;
     registry::get_provider(&connection, provider_kind)
-        .map_err(|err| format!("Unable to load provider after update: {err}"))?
-        .ok_or_else(|| "Provider row missing after update.".to_string())
+        .map_err(|err| {
+            BasecampError::database(format!("Unable to load provider after update: {err}"))
+        })?
+        .ok_or_else(|| BasecampError::internal("Provider row missing after update."))
 }
 
 #[tauri::command]
 async fn provider_health_check(
     state: State<'_, AppState>,
     provider_kind: Option<String>,
-) -> Result<Vec<ProviderRegistryRow>, ProviderCommandError> {
+) -> CommandResult<Vec<ProviderRegistryRow>> {
     let specific_kind_requested = provider_kind.is_some();
     let kinds = match provider_kind.as_deref() {
         Some(value) => {
-            vec![
-                parse_provider_kind(value).map_err(|message| ProviderCommandError {
-                    message,
-                    status: None,
-                    response_payload: Value::Null,
-                })?,
-            ]
+            vec![parse_provider_kind(value).map_err(BasecampError::validation)?]
         }
-        None => vec![
-            ProviderKind::Openrouter,
-            ProviderKind::Lmstudio,
-            ProviderKind::Ollama,
-            ProviderKind::LlamaCpp,
-        ],
+        None => ProviderKind::ALL.to_vec(),
     };
 
     let mut rows = Vec::new();
@@ -2928,30 +4245,16 @@ async fn provider_health_check(
 async fn provider_refresh_models(
     state: State<'_, AppState>,
     provider_kind: Option<String>,
-) -> Result<ProviderModelsRefreshResult, ProviderCommandError> {
+) -> CommandResult<ProviderModelsRefreshResult> {
     let specific_kind_requested = provider_kind.is_some();
     let target_kinds = match provider_kind.as_deref() {
         Some(value) => {
-            vec![
-                parse_provider_kind(value).map_err(|message| ProviderCommandError {
-                    message,
-                    status: None,
-                    response_payload: Value::Null,
-                })?,
-            ]
+            vec![parse_provider_kind(value).map_err(BasecampError::validation)?]
         }
         None => {
-            let connection = state.connection.lock().map_err(|_| ProviderCommandError {
-                message: "Database lock error".to_string(),
-                status: None,
-                response_payload: Value::Null,
-            })?;
+            let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
             registry::list_providers(&connection)
-                .map_err(|err| ProviderCommandError {
-                    message: format!("Unable to list providers: {err}"),
-                    status: None,
-                    response_payload: Value::Null,
-                })?
+                .map_err(|err| BasecampError::database(format!("Unable to list providers: {err}")))?
                 .into_iter()
                 .filter(|provider| provider.enabled)
                 .map(|provider| provider.provider_kind)
@@ -2985,27 +4288,83 @@ async fn provider_refresh_models(
     })
 }
 
+/// Fills sampling fields the caller left unset from the camp's
+/// `generation_defaults`. A camp that cannot be read leaves the request as is.
+fn apply_camp_generation_defaults(
+    connection: &Connection,
+    camp_id: &str,
+    request: &mut BasecampChatRequest,
+) {
+    let Some(defaults) = ensure_camps_root(connection)
+        .and_then(|camps_root| resolve_existing_camp_dir(&camps_root, camp_id))
+        .and_then(|camp_dir| read_camp_config(&camp_dir))
+        .ok()
+        .and_then(|config| config.generation_defaults)
+    else {
+        return;
+    };
+
+    request.temperature = request.temperature.or(defaults.temperature);
+    request.max_tokens = request.max_tokens.or(defaults.max_tokens);
+    request.top_p = request.top_p.or(defaults.top_p);
+    if request.stop.is_none() {
+        request.stop = defaults.stop;
+    }
+    if request.logit_bias.is_none() {
+        request.logit_bias = defaults.logit_bias;
+    }
+}
+
 #[tauri::command]
 async fn cmd_send_chat(
     state: State<'_, AppState>,
     request: BasecampChatRequest,
     on_event: Channel<ChatStreamEvent>,
-) -> Result<providers::ProviderChatResponse, ProviderCommandError> {
+) -> CommandResult<providers::ProviderChatResponse> {
+    dispatch_chat(&state, request, &on_event).await
+}
+
+/// `cmd_send_chat` for a request made on behalf of a camp. The camp id is a
+/// required argument, so a caller that forgets to tag the request cannot slip
+/// past the camp's routing policy.
+#[tauri::command]
+async fn camp_send_chat(
+    state: State<'_, AppState>,
+    camp_id: String,
+    mut request: BasecampChatRequest,
+    on_event: Channel<ChatStreamEvent>,
+) -> CommandResult<providers::ProviderChatResponse> {
+    privacy::scope_chat_to_camp(&mut request, &camp_id)?;
+    dispatch_chat(&state, request, &on_event).await
+}
+
+/// Applies camp routing policy, defaults, scrubbing and model capabilities to
+/// a chat request, sends it, and records provider health. Stream events go to
+/// `on_event` when the request streams.
+async fn dispatch_chat(
+    state: &AppState,
+    request: BasecampChatRequest,
+    on_event: &Channel<ChatStreamEvent>,
+) -> CommandResult<providers::ProviderChatResponse> {
     let mut effective_request = request.clone();
-    let settings = {
-        let connection = state.connection.lock().map_err(|_| ProviderCommandError {
-            message: "Database lock error".to_string(),
-            status: None,
-            response_payload: Value::Null,
-        })?;
+    let (settings, scrub_audit) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        privacy::enforce_camp_routing(
+            &connection,
+            request.metadata.camp_id.as_deref(),
+            request.provider_kind,
+        )?;
         let model_capabilities =
             registry::get_model_capabilities(&connection, request.provider_kind, &request.model_id)
-                .map_err(|err| ProviderCommandError {
-                    message: format!("Unable to load model capabilities: {err}"),
-                    status: None,
-                    response_payload: Value::Null,
+                .map_err(|err| {
+                    BasecampError::database(format!("Unable to load model capabilities: {err}"))
                 })?;
 
+        if let Some(camp_id) = request.metadata.camp_id.as_deref() {
+            apply_camp_generation_defaults(&connection, camp_id, &mut effective_request);
+        }
+        let scrub_audit = scrubber::scrub_outbound_request(&connection, &mut effective_request)?;
+
         let provider = state.provider_manager.get(request.provider_kind);
         let supports_tools = model_capabilities
             .as_ref()
@@ -3023,40 +4382,89 @@ async fn cmd_send_chat(
             effective_request.stream = false;
         }
 
-        read_provider_runtime_settings(&connection, request.provider_kind).map_err(|message| {
-            ProviderCommandError {
-                message,
-                status: None,
-                response_payload: Value::Null,
+        // Checked here so an oversized prompt gets an actionable error rather
+        // than the provider's opaque 400.
+        let context_length = model_capabilities
+            .as_ref()
+            .and_then(|caps| caps.max_context_tokens)
+            .or_else(|| {
+                registry::get_model_context_length(
+                    &connection,
+                    request.provider_kind,
+                    &request.model_id,
+                )
+                .ok()
+                .flatten()
+            });
+        if let Some(context_length) = context_length.filter(|length| *length > 0) {
+            if let Some(overflow) =
+                context_assembly::check_context_window(&effective_request, context_length)
+            {
+                return Err(overflow.into_error(&request.model_id));
             }
-        })?
+        }
+
+        let settings = read_provider_runtime_settings(&connection, request.provider_kind)
+            .map_err(BasecampError::from)?;
+        (settings, scrub_audit)
     };
 
     if !settings.config.enabled {
-        return Err(ProviderCommandError {
-            message: format!(
-                "Provider `{}` is disabled in Settings.",
-                request.provider_kind.as_str()
-            ),
-            status: None,
-            response_payload: Value::Null,
-        });
-    }
-
+        return Err(BasecampError::unavailable(format!(
+            "Provider `{}` is disabled in Settings.",
+            request.provider_kind.as_str()
+        )));
+    }
+
+    // Held until the response is in; waiting for a slot does not count
+    // towards the request's latency.
+    let correlation_id = providers::correlation_id_for(&request);
+    let _slot = state
+        .provider_manager
+        .acquire_slot(
+            request.provider_kind,
+            request.metadata.priority,
+            |position| {
+                let _ = on_event.send(ChatStreamEvent::ChatQueued {
+                    correlation_id: correlation_id.clone(),
+                    position,
+                });
+            },
+        )
+        .await;
     let provider = state.provider_manager.get(request.provider_kind);
-    let response = match provider
+    let (timed_channel, first_token_at) = metrics::first_token_timer(on_event);
+    let started_at = now_timestamp_ms();
+    let outcome = provider
         .send_chat(
             &state.provider_client,
             &settings,
             &effective_request,
             if effective_request.stream {
-                Some(&on_event)
+                Some(&timed_channel)
             } else {
                 None
             },
         )
-        .await
-    {
+        .await;
+    let first_token_at = first_token_at.load(Ordering::SeqCst);
+    let metric = metrics::RequestMetric {
+        provider_kind: request.provider_kind,
+        model_id: request.model_id.clone(),
+        streamed: effective_request.stream,
+        success: outcome.is_ok(),
+        latency_ms: (now_timestamp_ms() - started_at).max(0),
+        ttfb_ms: (first_token_at > 0).then(|| (first_token_at - started_at).max(0)),
+        completion_tokens: outcome
+            .as_ref()
+            .ok()
+            .and_then(|response| response.usage.completion_tokens),
+    };
+    if let Ok(connection) = state.connection.lock() {
+        let _ = metrics::record_request(&connection, &metric);
+    }
+
+    let mut response = match outcome {
         Ok(response) => response,
         Err(error) => {
             if let Ok(connection) = state.connection.lock() {
@@ -3074,16 +4482,41 @@ async fn cmd_send_chat(
     if let Ok(connection) = state.connection.lock() {
         let _ = registry::update_provider_health(&connection, request.provider_kind, true, None);
     }
+    response.scrub_audit = scrub_audit;
     Ok(response)
 }
 
 #[tauri::command]
-fn insert_run(state: State<'_, AppState>, payload: RunInsertPayload) -> Result<(), String> {
-    let connection = state
-        .connection
-        .lock()
-        .map_err(|_| "Database lock error".to_string())?;
-
+fn insert_run(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    payload: RunInsertPayload,
+) -> Result<(), String> {
+    store_run(&app, &state, payload)
+}
+
+/// Inserts a run row and, for OpenRouter runs, fetches generation stats in
+/// the background.
+fn store_run(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    mut payload: RunInsertPayload,
+) -> Result<(), String> {
+    let generation_id = payload.generation_id.clone().or_else(|| {
+        serde_json::from_str::<Value>(&payload.response_json)
+            .ok()
+            .and_then(|response| parse_openrouter_generation_id(&response))
+    });
+    let is_openrouter_run =
+        parse_model_reference(&payload.requested_model).0 == ProviderKind::Openrouter;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    privacy::apply_run_privacy(&connection, &mut payload)?;
+    let prompt_hash = run_groups::prompt_hash(&payload.system_prompt, &payload.user_prompt);
+
     connection
         .execute(
             "
@@ -3106,9 +4539,13 @@ fn insert_run(state: State<'_, AppState>, payload: RunInsertPayload) -> Result<(
         total_tokens,
         error,
         rating,
-        tags
+        tags,
+        generation_id,
+        scrub_audit_json,
+        camp_id,
+        prompt_hash
       )
-      VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)
+      VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)
       ",
             params![
                 payload.id,
@@ -3130,13 +4567,40 @@ fn insert_run(state: State<'_, AppState>, payload: RunInsertPayload) -> Result<(
                 payload.error,
                 payload.rating,
                 payload.tags,
+                generation_id,
+                payload.scrub_audit_json,
+                payload.camp_id,
+                prompt_hash,
             ],
         )
         .map_err(|err| format!("Unable to insert run: {err}"))?;
+    drop(connection);
+
+    if is_openrouter_run && generation_id.is_some() && payload.error.is_none() {
+        let run_id = payload.id;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<AppState>();
+            if enrich_run_with_openrouter_generation(&state, &run_id)
+                .await
+                .is_ok()
+            {
+                let _ = app.emit(RUN_GENERATION_ENRICHED_EVENT, run_id);
+            }
+        });
+    }
 
     Ok(())
 }
 
+#[tauri::command]
+async fn openrouter_enrich_run(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<(), OpenRouterCommandError> {
+    enrich_run_with_openrouter_generation(&state, &run_id).await
+}
+
 #[tauri::command]
 fn list_runs(state: State<'_, AppState>) -> Result<Vec<Run>, String> {
     let connection = state
@@ -3165,7 +4629,15 @@ fn list_runs(state: State<'_, AppState>) -> Result<Vec<Run>, String> {
         total_tokens,
         error,
         rating,
-        tags
+        tags,
+        generation_id,
+        total_cost,
+        native_tokens_prompt,
+        native_tokens_completion,
+        native_tokens_reasoning,
+        provider_name,
+        generation_json,
+        scrub_audit_json
       FROM runs
       ORDER BY timestamp DESC
       ",
@@ -3180,12 +4652,7 @@ fn list_runs(state: State<'_, AppState>) -> Result<Vec<Run>, String> {
         .map_err(|err| format!("Unable to map run rows: {err}"))
 }
 
-#[tauri::command]
-fn get_run_by_id(state: State<'_, AppState>, id: String) -> Result<Option<Run>, String> {
-    let connection = state
-        .connection
-        .lock()
-        .map_err(|_| "Database lock error".to_string())?;
+fn query_run_by_id(connection: &Connection, id: &str) -> Result<Option<Run>, String> {
     connection
         .query_row(
             "
@@ -3208,7 +4675,15 @@ fn get_run_by_id(state: State<'_, AppState>, id: String) -> Result<Option<Run>,
         total_tokens,
         error,
         rating,
-        tags
+        tags,
+        generation_id,
+        total_cost,
+        native_tokens_prompt,
+        native_tokens_completion,
+        native_tokens_reasoning,
+        provider_name,
+        generation_json,
+        scrub_audit_json
       FROM runs
       WHERE id = ?1
       ",
@@ -3219,6 +4694,49 @@ fn get_run_by_id(state: State<'_, AppState>, id: String) -> Result<Option<Run>,
         .map_err(|err| format!("Unable to fetch run: {err}"))
 }
 
+#[tauri::command]
+fn get_run_by_id(state: State<'_, AppState>, id: String) -> Result<Option<Run>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    query_run_by_id(&connection, &id)
+}
+
+/// The run behind a transcript message, for jumping from a reply to its full
+/// record. `None` for messages with no stored run, such as user messages or
+/// replies from before runs were linked.
+#[tauri::command]
+fn get_run_for_message(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    message_id: String,
+) -> Result<Option<Run>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+    let run_id = match transcript.iter().find(|message| message.id == message_id) {
+        Some(message) => message.run_id.clone(),
+        None => {
+            variants::find_variant_message(&variants::read_variants_index(&camp_dir)?, &message_id)
+                .ok_or_else(|| "Message not found.".to_string())?
+                .run_id
+                .clone()
+        }
+    };
+    match run_id {
+        Some(run_id) => query_run_by_id(&connection, &run_id),
+        None => Ok(None),
+    }
+}
+
 #[tauri::command]
 fn update_run_rating_and_tags(
     state: State<'_, AppState>,
@@ -3251,6 +4769,8 @@ fn db_list_models(state: State<'_, AppState>) -> Result<Vec<ModelRow>, String> {
         .map_err(|_| "Database lock error".to_string())?;
     let rows = registry::list_models(&connection, None)
         .map_err(|err| format!("Unable to query model rows: {err}"))?;
+    let mut notes = model_notes::model_notes_by_model(&connection)
+        .map_err(|err| format!("Unable to load model notes: {err}"))?;
     Ok(rows
         .into_iter()
         .map(|row| ModelRow {
@@ -3264,6 +4784,7 @@ fn db_list_models(state: State<'_, AppState>) -> Result<Vec<ModelRow>, String> {
             capabilities_json: row.capabilities.to_json_string(),
             raw_json: serde_json::to_string(&row.raw_json).unwrap_or_else(|_| "null".to_string()),
             updated_at: row.last_seen_at,
+            note: notes.remove(&(row.provider_kind.as_str().to_string(), row.model_id.clone())),
         })
         .collect())
 }
@@ -3817,8 +5338,9 @@ fn camp_attach_workspace_context_file(
     let camp_context_root = canonicalize_context_root(&camp_context_dir(&camp_dir))?;
     let destination = resolve_write_context_target(&camp_context_root, &path)?;
 
-    fs::copy(&source, &destination)
-        .map_err(|err| format!("Unable to attach context file to camp: {err}"))?;
+    let contents =
+        fs::read(&source).map_err(|err| format!("Unable to attach context file to camp: {err}"))?;
+    context_history::write_context_file_with_history(&camp_dir, &destination, &path, &contents)?;
     touch_camp_updated_at(&camp_dir)?;
     Ok(())
 }
@@ -3854,8 +5376,16 @@ fn camp_detach_workspace_context_file(
         return Err("Requested path is not a file.".to_string());
     }
 
-    fs::remove_file(&canonical_target)
-        .map_err(|err| format!("Unable to detach context file from camp: {err}"))?;
+    let display_path = to_context_relative_display(&camp_context_root, &canonical_target)?;
+    let original_path = format!("{CAMP_CONTEXT_DIR}/{display_path}");
+    move_to_trash(
+        &camp_dir,
+        &canonical_target,
+        CampTrashKind::ContextFile,
+        original_path,
+        None,
+    )?;
+    default_context::forget_default_context_path(&camp_dir, &display_path)?;
 
     if let Some(parent) = canonical_target.parent() {
         prune_empty_context_parents(&camp_context_root, parent)?;
@@ -3984,7 +5514,12 @@ fn tauri_cmd_write_context_file(
         }
     }
 
-    fs::write(&target, content).map_err(|err| format!("Unable to write context file: {err}"))?;
+    context_history::write_context_file_with_history(
+        &camp_dir,
+        &target,
+        &path,
+        content.as_bytes(),
+    )?;
     touch_camp_updated_at(&camp_dir)?;
     Ok(())
 }
@@ -4021,8 +5556,7 @@ fn tauri_cmd_write_context_file_bytes(
         .decode(content_base64)
         .map_err(|err| format!("Invalid base64 encoding: {err}"))?;
 
-    fs::write(&target, bytes)
-        .map_err(|err| format!("Unable to write binary context file: {err}"))?;
+    context_history::write_context_file_with_history(&camp_dir, &target, &path, &bytes)?;
     touch_camp_updated_at(&camp_dir)?;
     Ok(())
 }
@@ -4043,12 +5577,18 @@ fn camp_delete(window: Window, state: State<'_, AppState>, camp_id: String) -> R
     }
 
     fs::remove_dir_all(&camp_dir).map_err(|e| format!("Failed to delete camp directory: {}", e))?;
+    camp_ranking::forget_camp_opens(&connection, &camp_id)
+        .map_err(|err| format!("Unable to clear camp history: {err}"))?;
 
     Ok(())
 }
 
 #[tauri::command]
-fn camp_list(window: Window, state: State<'_, AppState>) -> Result<Vec<CampSummary>, String> {
+fn camp_list(
+    window: Window,
+    state: State<'_, AppState>,
+    sort: Option<camp_ranking::CampListSort>,
+) -> Result<Vec<CampSummary>, String> {
     ensure_main_window(&window)?;
     let connection = state
         .connection
@@ -4056,10 +5596,18 @@ fn camp_list(window: Window, state: State<'_, AppState>) -> Result<Vec<CampSumma
         .map_err(|_| "Database lock error".to_string())?;
 
     let camps_root = ensure_camps_root(&connection)?;
+    let mut camps = list_camp_summaries(&camps_root)?;
+    camp_ranking::rank_camps(&connection, &mut camps, sort.unwrap_or_default())?;
+    Ok(camps)
+}
+
+/// Every readable camp under `camps_root`, pinned first, then most recently
+/// updated.
+fn list_camp_summaries(camps_root: &Path) -> Result<Vec<CampSummary>, String> {
     let mut camps = Vec::new();
 
     let entries =
-        fs::read_dir(&camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+        fs::read_dir(camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
     for entry_result in entries {
         let entry = match entry_result {
             Ok(entry) => entry,
@@ -4085,12 +5633,25 @@ fn camp_list(window: Window, state: State<'_, AppState>) -> Result<Vec<CampSumma
             id: config.id,
             name: config.name,
             model: config.model,
+            description: config.description,
+            color: config.color,
+            emoji: config.emoji,
+            pinned: config.pinned,
+            favorite: config.favorite,
+            tags: config.tags,
+            project_id: config.project_id,
+            last_opened_at: None,
             updated_at: config.updated_at,
             path: camp_dir.to_string_lossy().into_owned(),
         });
     }
 
-    camps.sort_by(|left, right| right.updated_at.cmp(&left.updated_at));
+    camps.sort_by(|left, right| {
+        right
+            .pinned
+            .cmp(&left.pinned)
+            .then_with(|| right.updated_at.cmp(&left.updated_at))
+    });
     Ok(camps)
 }
 
@@ -4107,10 +5668,17 @@ fn camp_create(
         .map_err(|_| "Database lock error".to_string())?;
 
     let camps_root = ensure_camps_root(&connection)?;
-    let camp_id = Uuid::new_v4().to_string();
-    let camp_dir = camps_root.join(&camp_id);
+    let camp_dir = scaffold_camp(&camps_root, payload)?;
+    load_camp_from_dir(&camp_dir)
+}
 
+/// Creates a camp folder with its config, prompt, memory, empty transcript and
+/// artifacts index, and returns the folder.
+fn scaffold_camp(camps_root: &Path, payload: CampCreatePayload) -> Result<PathBuf, String> {
     let name = validate_non_empty(&payload.name, "name")?;
+    let camp_id = allocate_camp_folder_id(camps_root, &name);
+    let camp_dir = camps_root.join(&camp_id);
+
     let model_value = validate_non_empty(&payload.model, "model")?;
     let (provider_kind, model_id) = parse_model_reference(&model_value);
     let model = compose_model_reference(provider_kind, &model_id);
@@ -4125,6 +5693,22 @@ fn camp_create(
         model_overrides: None,
         tools_enabled: payload.tools_enabled.unwrap_or(default_tools_enabled()),
         is_team: default_is_team(),
+        description: None,
+        color: None,
+        emoji: None,
+        pinned: false,
+        favorite: false,
+        generation_defaults: None,
+        tags: Vec::new(),
+        privacy_mode: false,
+        local_only: false,
+        post_processors: Vec::new(),
+        auto_extract_code_blocks: false,
+        preview_file_writes: false,
+        memory_schema: None,
+        project_id: None,
+        default_context_paths: Vec::new(),
+        fetch_allowed_domains: Vec::new(),
         created_at: now,
         updated_at: now,
     };
@@ -4136,8 +5720,7 @@ fn camp_create(
         .map_err(|err| format!("Unable to create artifacts folder: {err}"))?;
 
     write_camp_config(&camp_dir, &config)?;
-    fs::write(camp_system_prompt_path(&camp_dir), payload.system_prompt)
-        .map_err(|err| format!("Unable to write system prompt: {err}"))?;
+    prompt_versions::write_system_prompt_with_history(&camp_dir, &payload.system_prompt)?;
     write_json_file(
         &camp_memory_path(&camp_dir),
         &payload
@@ -4148,7 +5731,7 @@ fn camp_create(
         .map_err(|err| format!("Unable to initialize transcript: {err}"))?;
     write_artifacts_index(&camp_dir, &empty_artifacts_index())?;
 
-    load_camp_from_dir(&camp_dir)
+    Ok(camp_dir)
 }
 
 #[tauri::command]
@@ -4186,11 +5769,127 @@ fn camp_update_config(
     config.provider_kind = provider_kind.as_str().to_string();
     config.model_id = model_id;
     config.tools_enabled = payload.tools_enabled;
+    if let Some(description) = payload.description {
+        let trimmed = description.trim();
+        config.description = (!trimmed.is_empty()).then(|| trimmed.to_string());
+    }
+    if let Some(color) = payload.color {
+        config.color = if color.trim().is_empty() {
+            None
+        } else {
+            Some(
+                normalize_camp_color(&color)
+                    .ok_or_else(|| "color must be a hex value like #3a7bd5.".to_string())?,
+            )
+        };
+    }
+    if let Some(emoji) = payload.emoji {
+        let trimmed = emoji.trim();
+        config.emoji = (!trimmed.is_empty()).then(|| trimmed.to_string());
+    }
+    if let Some(pinned) = payload.pinned {
+        config.pinned = pinned;
+    }
+    if let Some(favorite) = payload.favorite {
+        config.favorite = favorite;
+    }
+    if let Some(mut generation_defaults) = payload.generation_defaults {
+        generation_defaults.stop = generation_defaults.stop.and_then(normalize_stop_sequences);
+        generation_defaults.logit_bias = generation_defaults
+            .logit_bias
+            .and_then(normalize_logit_bias);
+        config.generation_defaults =
+            (!generation_defaults.is_empty()).then_some(generation_defaults);
+    }
+    if let Some(tags) = payload.tags {
+        config.tags = normalize_artifact_tags(&tags);
+    }
+    if let Some(privacy_mode) = payload.privacy_mode {
+        config.privacy_mode = privacy_mode;
+    }
+    if let Some(local_only) = payload.local_only {
+        config.local_only = local_only;
+    }
+    if let Some(post_processors) = payload.post_processors {
+        post_processing::validate_post_processors(&post_processors)?;
+        config.post_processors = post_processors;
+    }
+    if let Some(auto_extract_code_blocks) = payload.auto_extract_code_blocks {
+        config.auto_extract_code_blocks = auto_extract_code_blocks;
+    }
+    if let Some(preview_file_writes) = payload.preview_file_writes {
+        config.preview_file_writes = preview_file_writes;
+    }
+    if let Some(memory_schema) = payload.memory_schema {
+        let is_empty = memory_schema
+            .as_object()
+            .is_some_and(|schema| schema.is_empty());
+        if !is_empty {
+            memory_schema::validate_memory_schema(&memory_schema)?;
+        }
+        config.memory_schema = (!is_empty).then_some(memory_schema);
+    }
     config.updated_at = now_timestamp_ms();
 
     write_camp_config(&camp_dir, &config)
 }
 
+fn rename_camp_folder(camps_root: &Path, camp_dir: &Path) -> Result<PathBuf, String> {
+    let mut config = read_camp_config(camp_dir)?;
+    let current_id = camp_dir
+        .file_name()
+        .and_then(|value| value.to_str())
+        .ok_or_else(|| "Unable to derive camp id from folder name.".to_string())?
+        .to_string();
+
+    let slug = slugify_camp_name(&config.name);
+    let already_slugged = current_id
+        .rsplit_once('-')
+        .is_some_and(|(prefix, suffix)| prefix == slug && suffix.len() == CAMP_SLUG_SUFFIX_LEN);
+    if already_slugged && config.id == current_id {
+        return Ok(camp_dir.to_path_buf());
+    }
+
+    let next_id = allocate_camp_folder_id(camps_root, &config.name);
+    let next_dir = camps_root.join(&next_id);
+    fs::rename(camp_dir, &next_dir)
+        .map_err(|err| format!("Unable to rename camp folder: {err}"))?;
+
+    config.id = next_id;
+    config.updated_at = now_timestamp_ms();
+    if let Err(err) = write_camp_config(&next_dir, &config) {
+        let _ = fs::rename(&next_dir, camp_dir);
+        return Err(err);
+    }
+
+    Ok(next_dir)
+}
+
+#[tauri::command]
+fn camp_rename_folder(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampRenameFolderPayload,
+) -> Result<Camp, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let next_dir = rename_camp_folder(&camps_root, &camp_dir)?;
+    let camp = load_camp_from_dir(&next_dir)?;
+    camp_ranking::rename_camp_opens(&connection, &payload.camp_id, &camp.config.id)
+        .map_err(|err| format!("Unable to move camp history: {err}"))?;
+    camp_activity::rename_camp_runs(&connection, &payload.camp_id, &camp.config.id)
+        .map_err(|err| format!("Unable to move camp history: {err}"))?;
+    quick_capture::rename_quick_capture_camp(&connection, &payload.camp_id, &camp.config.id)?;
+    digest::rename_digest_camp(&connection, &payload.camp_id, &camp.config.id)?;
+    Ok(camp)
+}
+
 #[tauri::command]
 fn camp_update_system_prompt(
     window: Window,
@@ -4205,8 +5904,7 @@ fn camp_update_system_prompt(
     let camps_root = ensure_camps_root(&connection)?;
     let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
 
-    fs::write(camp_system_prompt_path(&camp_dir), payload.system_prompt)
-        .map_err(|err| format!("Unable to update system prompt: {err}"))?;
+    prompt_versions::write_system_prompt_with_history(&camp_dir, &payload.system_prompt)?;
     touch_camp_updated_at(&camp_dir)
 }
 
@@ -4215,17 +5913,15 @@ fn camp_update_memory(
     window: Window,
     state: State<'_, AppState>,
     payload: CampUpdateMemoryPayload,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     ensure_main_window(&window)?;
-    let connection = state
-        .connection
-        .lock()
-        .map_err(|_| "Database lock error".to_string())?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
     let camps_root = ensure_camps_root(&connection)?;
     let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
 
+    memory_schema::ensure_memory_matches_schema(&camp_dir, &payload.memory)?;
     write_json_file(&camp_memory_path(&camp_dir), &payload.memory)?;
-    touch_camp_updated_at(&camp_dir)
+    Ok(touch_camp_updated_at(&camp_dir)?)
 }
 
 #[tauri::command]
@@ -4258,7 +5954,15 @@ fn camp_append_message(
     } else {
         None
     };
-    let message = CampMessage {
+    let reasoning = if role == "assistant" {
+        payload
+            .reasoning
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    } else {
+        None
+    };
+    let mut message = CampMessage {
         id: Uuid::new_v4().to_string(),
         role,
         content,
@@ -4268,7 +5972,10 @@ fn camp_append_message(
         tool_calls,
         included_artifact_ids,
         attachments,
+        reasoning,
+        run_id: None,
     };
+    post_processing::apply_to_assistant_message(&camp_dir, &mut message)?;
 
     append_transcript_message(&camp_transcript_path(&camp_dir), &message)?;
     touch_camp_updated_at(&camp_dir)?;
@@ -4276,6 +5983,51 @@ fn camp_append_message(
     Ok(message)
 }
 
+#[tauri::command]
+fn camp_verify_transcript(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampVerifyTranscriptPayload,
+) -> CommandResult<TranscriptVerifyReport> {
+    ensure_main_window(&window)?;
+    let repair = payload.repair.unwrap_or(false);
+    if repair {
+        demo_mode::ensure_writable(&state, "camp_verify_transcript")?;
+    }
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let report = verify_transcript(&camp_dir, repair)?;
+    if report.repaired {
+        touch_camp_updated_at(&camp_dir)?;
+    }
+
+    Ok(report)
+}
+
+#[tauri::command]
+fn camp_undo_last_exchange(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<CampUndoExchangeResult, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let result = undo_last_exchange(&camp_dir)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(result)
+}
+
 #[tauri::command]
 fn camp_list_artifacts(
     window: Window,
@@ -4341,36 +6093,103 @@ fn camp_create_artifact_from_message(
         .unwrap_or_else(|| {
             extract_default_artifact_title(&source_message.content, &source_message.role)
         });
-    let title = validate_non_empty(&title, "title")?;
+    let artifact = create_artifact(
+        &camp_dir,
+        ArtifactSource {
+            message_id: source_message.id.clone(),
+            role: source_message.role.clone(),
+            path: None,
+        },
+        &title,
+        &source_message.content,
+        &payload.tags.unwrap_or_default(),
+    )?;
+    touch_camp_updated_at(&camp_dir)?;
 
-    let artifact_id = Uuid::new_v4().to_string();
-    let filename = format!("{artifact_id}.md");
-    let now = now_timestamp_ms();
-    let metadata = CampArtifactMetadata {
-        id: artifact_id.clone(),
-        title: title.clone(),
-        filename,
-        source_message_id: source_message.id.clone(),
-        source_role: source_message.role.clone(),
-        tags: normalize_artifact_tags(&payload.tags.unwrap_or_default()),
-        created_at: now,
-        updated_at: now,
-        usage_count: 0,
-        archived: false,
-    };
+    Ok(artifact)
+}
 
-    let markdown = format_artifact_markdown(&title, &source_message.content);
-    write_artifact_body(&camp_dir, &metadata, &markdown)?;
+#[tauri::command]
+fn camp_create_artifact(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampCreateArtifactPayload,
+) -> Result<CampArtifact, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
 
-    let mut index = ensure_artifacts_index(&camp_dir)?;
-    index.artifacts.push(metadata.clone());
-    write_artifacts_index(&camp_dir, &index)?;
+    let artifact = create_artifact(
+        &camp_dir,
+        ArtifactSource {
+            message_id: String::new(),
+            role: "user".to_string(),
+            path: None,
+        },
+        &payload.title,
+        &payload.body,
+        &payload.tags.unwrap_or_default(),
+    )?;
     touch_camp_updated_at(&camp_dir)?;
 
-    Ok(CampArtifact {
-        metadata,
-        body: markdown,
-    })
+    Ok(artifact)
+}
+
+#[tauri::command]
+fn camp_create_artifact_from_file(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampCreateArtifactFromFilePayload,
+) -> Result<CampArtifact, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let context_root = canonicalize_context_root(&camp_context_dir(&camp_dir))?;
+    let target = resolve_existing_context_target(&context_root, &payload.path, "path", false)?;
+
+    if !target.is_file() {
+        return Err("Requested path is not a file.".to_string());
+    }
+
+    let bytes = fs::read(&target).map_err(|err| format!("Unable to read context file: {err}"))?;
+    let contents = String::from_utf8(bytes)
+        .map_err(|_| "Only UTF-8 text files can be turned into artifacts.".to_string())?;
+    let fallback_title = target
+        .file_stem()
+        .and_then(|value| value.to_str())
+        .unwrap_or("Imported file")
+        .to_string();
+    let (parsed_title, parsed_body) = parse_artifact_markdown(&contents, &fallback_title);
+    let title = payload
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+        .unwrap_or(parsed_title);
+
+    let artifact = create_artifact(
+        &camp_dir,
+        ArtifactSource {
+            message_id: String::new(),
+            role: "file".to_string(),
+            path: Some(to_context_relative_display(&context_root, &target)?),
+        },
+        &title,
+        &parsed_body,
+        &payload.tags.unwrap_or_default(),
+    )?;
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(artifact)
 }
 
 #[tauri::command]
@@ -4457,6 +6276,214 @@ fn camp_toggle_artifact_archive(
     Ok(result_metadata)
 }
 
+#[tauri::command]
+fn camp_delete_artifact(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampDeleteArtifactPayload,
+) -> Result<CampTrashEntry, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let entry = trash_artifact(&camp_dir, &payload.artifact_id)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+fn camp_list_trash(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<CampTrashEntry>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let mut entries = read_trash_index(&camp_dir)?.entries;
+    entries.sort_by(|left, right| right.trashed_at.cmp(&left.trashed_at));
+    Ok(entries)
+}
+
+#[tauri::command]
+fn camp_restore_trash_entry(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampRestoreTrashEntryPayload,
+) -> Result<CampTrashEntry, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let entry = restore_trash_entry(&camp_dir, &payload.entry_id)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(entry)
+}
+
+#[tauri::command]
+fn camp_empty_trash(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let trash_dir = camp_trash_dir(&camp_dir);
+    if trash_dir.exists() {
+        fs::remove_dir_all(&trash_dir)
+            .map_err(|err| format!("Unable to empty camp trash: {err}"))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+fn camp_create_checkpoint(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampCreateCheckpointPayload,
+) -> Result<CampCheckpoint, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    create_checkpoint(&camp_dir, &payload.name)
+}
+
+#[tauri::command]
+fn camp_list_checkpoints(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<CampCheckpoint>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let mut checkpoints = read_checkpoints_index(&camp_dir)?.checkpoints;
+    checkpoints.sort_by(|left, right| right.created_at.cmp(&left.created_at));
+    Ok(checkpoints)
+}
+
+#[tauri::command]
+fn camp_rollback_to_checkpoint(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampRollbackToCheckpointPayload,
+) -> Result<CampRollbackResult, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let result = rollback_to_checkpoint(&camp_dir, &payload.checkpoint_id)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(result)
+}
+
+#[tauri::command]
+fn camp_set_artifact_always_include(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampSetArtifactAlwaysIncludePayload,
+) -> Result<CampArtifactMetadata, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let artifact_id = validate_identifier(&payload.artifact_id, "artifact_id")?;
+
+    let mut index = ensure_artifacts_index(&camp_dir)?;
+    let metadata = set_artifact_always_include(&mut index, &artifact_id, payload.always_include)?;
+    write_artifacts_index(&camp_dir, &index)?;
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(metadata)
+}
+
+#[tauri::command]
+fn camp_reorder_always_include_artifacts(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampReorderAlwaysIncludePayload,
+) -> Result<Vec<CampArtifactMetadata>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let artifact_ids = payload
+        .artifact_ids
+        .iter()
+        .map(|artifact_id| validate_identifier(artifact_id, "artifact_id"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut index = ensure_artifacts_index(&camp_dir)?;
+    reorder_always_include_artifacts(&mut index, &artifact_ids)?;
+    write_artifacts_index(&camp_dir, &index)?;
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(always_include_artifacts(&index))
+}
+
+#[tauri::command]
+fn camp_list_always_include_artifacts(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<CampArtifact>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let index = ensure_artifacts_index(&camp_dir)?;
+
+    always_include_artifacts(&index)
+        .into_iter()
+        .map(|metadata| {
+            let body = read_artifact_body(&camp_dir, &metadata)?;
+            Ok(CampArtifact { metadata, body })
+        })
+        .collect()
+}
+
 #[tauri::command]
 fn camp_increment_artifact_usage(
     window: Window,
@@ -4556,6 +6583,8 @@ fn run_start(
     let clamped_iterations = config.max_iterations.clamp(1, 50);
     let clamped_timeout = config.tool_timeout_secs.max(1);
     let policy = ApprovalPolicy::from_str_lenient(&config.approval_policy);
+    let (max_repeated_tool_calls, max_turn_tokens) =
+        tool_loop_guard::clamp_loop_limits(config.max_repeated_tool_calls, config.max_turn_tokens);
 
     let run_id = Uuid::new_v4().to_string();
     let now = now_timestamp_ms();
@@ -4564,6 +6593,8 @@ fn run_start(
         "max_iterations": clamped_iterations,
         "tool_timeout_secs": clamped_timeout,
         "approval_policy": policy.as_str(),
+        "max_repeated_tool_calls": max_repeated_tool_calls,
+        "max_turn_tokens": max_turn_tokens,
     });
 
     let event = RunStateEvent {
@@ -4703,45 +6734,132 @@ fn get_max_iterations(state: State<'_, AppState>) -> Result<i64, String> {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        // Must be registered first so a second launch exits before doing any work.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            deep_link::on_second_instance(app, argv);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(quick_capture::plugin())
+        .plugin(updater::plugin())
         .setup(|app| {
-            let connection = init_database(app)?;
-            app.manage(AppState {
-                connection: Mutex::new(connection),
-                mcp: tokio::sync::Mutex::new(mcp::McpConnections::new()),
-                provider_manager: ProviderManager::new(),
-                provider_client: reqwest::Client::new(),
-            });
-
-            // Handle the splash screen
-            let splash_window = app.get_webview_window("splashscreen").unwrap();
-            let main_window = app.get_webview_window("main").unwrap();
-
-            tauri::async_runtime::spawn(async move {
-                // Keep the splash screen open for a bit
-                std::thread::sleep(Duration::from_millis(2500));
-
-                // Then show the main app
-                splash_window.close().unwrap();
-                main_window.show().unwrap();
-            });
+            // Commands that need `AppState` are unavailable until this
+            // finishes; the UI waits on `get_init_status`.
+            crash_reports::install(app.handle());
+            app.manage(startup::InitState::default());
+            app.manage(tray::TrayState::default());
+            deep_link::setup(app.handle());
+            startup::spawn_initialization(app.handle().clone());
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .on_window_event(tray::on_window_event)
+        .invoke_handler(demo_mode::guard_read_only(tauri::generate_handler![
             save_api_key,
             has_api_key,
             stream_openrouter_completion,
             openrouter_fetch_key_info,
             openrouter_sync_models,
             providers_list,
+            get_queue_status,
+            provider_limits::get_provider_concurrency,
+            provider_limits::set_provider_concurrency,
             provider_update,
             provider_health_check,
             provider_refresh_models,
             cmd_send_chat,
+            camp_send_chat,
+            chat_stream::send_chat_streaming,
+            chat_stream::set_max_continuations,
+            chat_stream::get_max_continuations,
+            startup::set_splash_duration_ms,
+            startup::get_splash_duration_ms,
+            startup::get_init_status,
+            updater::check_for_updates,
+            updater::install_update,
+            updater::get_update_channel,
+            updater::set_update_channel,
+            deep_link::take_pending_deep_link,
+            quick_capture::quick_capture,
+            quick_capture::dismiss_quick_capture,
+            quick_capture::get_quick_capture_settings,
+            quick_capture::set_quick_capture_settings,
+            tray::get_tray_settings,
+            tray::set_tray_settings,
+            crash_reports::record_command_failure,
+            crash_reports::list_crash_reports,
+            crash_reports::export_crash_report,
+            metrics::get_performance_stats,
+            digest::get_digest_settings,
+            digest::set_digest_settings,
+            digest::get_digest_status,
+            digest::run_digest_now,
+            sync_conflicts::detect_sync_conflicts,
+            sync_conflicts::merge_sync_conflict,
+            sync::get_sync_settings,
+            sync::set_sync_settings,
+            sync::run_sync,
+            workspace_manifest::generate_workspace_manifest,
+            global_instructions::get_global_instructions,
+            global_instructions::set_global_instructions,
+            model_notes::list_model_notes,
+            model_notes::set_model_note,
+            model_notes::delete_model_note,
+            model_recommendation::recommend_model,
+            artifact_index::camp_rebuild_artifact_index,
+            workspace_gc::workspace_gc,
+            post_processing::camp_extract_code_artifacts,
+            search::camp_search_transcript,
+            camp_activity::camp_activity_timeline,
+            context_pack::camp_export_context_pack,
+            memory_patch::camp_get_memory,
+            memory_patch::camp_patch_memory,
+            scratchpad::camp_scratchpad_remember,
+            scratchpad::camp_scratchpad_recall,
+            builtin_tools::run_builtin_tool,
+            fetch_url::camp_set_fetch_allowed_domains,
+            fetch_url::camp_fetch_url,
+            user_databases::register_user_database,
+            user_databases::list_user_databases,
+            user_databases::unregister_user_database,
+            user_databases::query_user_database,
+            tabular::camp_preview_table,
+            tabular::camp_table_to_markdown,
+            embedding_index::camp_refresh_embedding_index,
+            embedding_index::embedding_index_status,
+            embedding_models::get_embedding_settings,
+            embedding_models::set_embedding_backend,
+            embedding_models::download_local_embedding_model,
+            embedding_models::delete_local_embedding_model,
+            embedding_models::benchmark_embedding_backends,
+            camp_templates::list_camp_templates,
+            camp_templates::camp_create_from_template,
+            projects::project_list,
+            projects::project_create,
+            projects::project_delete,
+            projects::camp_set_project,
+            projects::camp_list_grouped,
+            camp_ranking::camp_mark_opened,
+            camp_ranking::camp_set_favorite,
+            default_context::camp_set_default_context_paths,
+            default_context::camp_load_default_context_files,
+            context_history::camp_list_context_file_versions,
+            context_history::camp_restore_context_file_version,
+            write_preview::camp_preview_context_file_write,
+            markdown::render_markdown,
+            markdown::open_external_url,
+            rich_render::render_artifact_rich,
+            share_export::camp_share_export,
+            variants::regenerate_message,
+            variants::select_variant,
+            variants::list_message_variants,
+            council::run_council,
             insert_run,
+            openrouter_enrich_run,
             list_runs,
+            run_groups::list_run_groups,
             get_run_by_id,
+            get_run_for_message,
             update_run_rating_and_tags,
             db_list_models,
             db_get_models_last_sync,
@@ -4780,28 +6898,95 @@ pub fn run() {
             camp_create,
             camp_load,
             camp_update_config,
+            camp_rename_folder,
             camp_update_system_prompt,
             camp_update_memory,
             camp_append_message,
+            camp_verify_transcript,
+            camp_undo_last_exchange,
             camp_list_artifacts,
             camp_get_artifact,
             camp_create_artifact_from_message,
+            camp_create_artifact,
+            camp_create_artifact_from_file,
             camp_update_artifact,
             camp_toggle_artifact_archive,
             camp_increment_artifact_usage,
+            camp_set_artifact_always_include,
+            camp_reorder_always_include_artifacts,
+            camp_list_always_include_artifacts,
+            camp_delete_artifact,
+            camp_list_trash,
+            camp_restore_trash_entry,
+            camp_empty_trash,
+            camp_create_checkpoint,
+            camp_list_checkpoints,
+            camp_rollback_to_checkpoint,
             commands::team::create_team_agent,
+            commands::team::list_team_templates,
+            commands::team::create_team_from_template,
             commands::team::remove_team_agent,
             commands::team::update_team_settings,
             commands::team::decompose_task,
             commands::team::execute_agent_step,
             commands::team::run_reflection_loop,
+            commands::team::run_reflection_batch,
+            commands::team::promote_all_passing,
+            commands::team::answer_clarification,
+            commands::team::get_agent_transcript,
             commands::team::get_team_bus,
+            commands::team::list_team_runs,
+            commands::team::archive_team_runs,
+            commands::team::get_team_run_report,
             commands::team::promote_artifact,
             commands::team::get_team_status,
             mcp::mcp_register_server,
+            mcp::mcp_update_server,
+            mcp::mcp_delete_server,
+            mcp::mcp_set_enabled,
             mcp::mcp_list_servers,
             mcp::mcp_discover_tools,
             mcp::mcp_call_tool,
+            mcp::mcp_get_server_logs,
+            mcp_catalog::mcp_list_catalog,
+            mcp_catalog::mcp_install_from_catalog,
+            mcp_roots::mcp_set_active_camp,
+            mcp::get_mcp_tool_cache_ttl,
+            mcp::set_mcp_tool_cache_ttl,
+            mcp_auth::mcp_oauth_authorize,
+            mcp_auth::mcp_oauth_status,
+            mcp_auth::mcp_oauth_sign_out,
+            mcp_sampling::get_mcp_sampling_policy,
+            mcp_sampling::set_mcp_sampling_policy,
+            mcp_sampling::mcp_resolve_sampling_request,
+            search::search_workspace,
+            hybrid_search::hybrid_search,
+            context_assembly::camp_assemble_context,
+            prompt_versions::camp_list_prompt_versions,
+            prompt_versions::camp_diff_prompt_versions,
+            prompt_versions::camp_restore_prompt_version,
+            bookmarks::camp_pin_message,
+            bookmarks::camp_unpin_message,
+            bookmarks::camp_list_pinned_messages,
+            recovery::get_startup_diagnostics,
+            migrations::db_migration_status,
+            db_encryption::db_encryption_status,
+            db_encryption::db_set_encryption_passphrase,
+            privacy::get_privacy_settings,
+            privacy::set_privacy_settings,
+            scrubber::get_outbound_scrub_settings,
+            scrubber::set_outbound_scrub_settings,
+            commands::prompt_assistant::improve_system_prompt,
+            commands::camp_titles::camp_suggest_title,
+            commands::camp_titles::set_title_model,
+            commands::camp_titles::get_title_model,
+            commands::prompt_suggestions::suggest_prompts,
+            commands::translation::camp_translate_messages,
+            commands::translation::camp_list_translations,
+            speech::synthesize_speech,
+            speech::list_speech_clips,
+            speech::get_tts_engine,
+            speech::set_tts_engine,
             run_start,
             run_cancel,
             run_get_state,
@@ -4810,7 +6995,9 @@ pub fn run() {
             get_approval_policy,
             set_max_iterations,
             get_max_iterations,
-        ])
+            demo_mode::get_read_only_mode,
+            demo_mode::set_read_only_mode,
+        ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -4818,23 +7005,14 @@ pub fn run() {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use crate::test_support::make_temp_dir;
 
     const LEGACY_CAMP_CONFIG_FIXTURE: &str =
         include_str!("../tests/fixtures/camp_config_legacy_v0.json");
+    const V0_2_CAMP_CONFIG_FIXTURE: &str = include_str!("../tests/fixtures/camp_config_v0_2.json");
     const LEGACY_TRANSCRIPT_FIXTURE: &str =
         include_str!("../tests/fixtures/transcript_legacy_shapes.jsonl");
 
-    fn make_temp_dir(prefix: &str) -> PathBuf {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("time should move forward")
-            .as_nanos();
-        let dir = std::env::temp_dir().join(format!("{prefix}-{timestamp}-{}", Uuid::new_v4()));
-        fs::create_dir_all(&dir).expect("temporary directory should be created");
-        dir
-    }
-
     #[test]
     fn note_filename_validation_and_path_join_are_sandboxed() {
         assert!(validate_note_filename("../escape.md").is_err());
@@ -4862,7 +7040,7 @@ mod tests {
     #[test]
     fn tool_calls_insert_update_flow_round_trip() {
         let connection = Connection::open_in_memory().expect("in-memory db should open");
-        create_tables(&connection).expect("schema should be created");
+        migrations::run_migrations(&connection, false).expect("schema should be created");
 
         let payload = ToolCallStartPayload {
             run_id: "run-1".to_string(),
@@ -4940,6 +7118,9 @@ mod tests {
             updated_at: 3,
             usage_count: 1,
             archived: false,
+            source_path: None,
+            always_include: false,
+            include_order: None,
         };
 
         let next = CampArtifactsIndex {
@@ -4954,8 +7135,6 @@ mod tests {
             loaded.artifacts[0].tags,
             vec!["Alpha".to_string(), "beta".to_string()]
         );
-
-        let _ = fs::remove_dir_all(camp_dir);
     }
 
     #[test]
@@ -4964,19 +7143,24 @@ mod tests {
         let transcript_path = transcript_dir.join(CAMP_TRANSCRIPT_FILE);
         let old_line = r#"{"id":"m1","role":"user","content":"legacy","created_at":1}"#;
         let new_line = r#"{"id":"m2","role":"user","content":"new","created_at":2,"included_artifact_ids":["a1","a2"]}"#;
+        let reply_line =
+            r#"{"id":"m3","role":"assistant","content":"reply","created_at":3,"run_id":"run-1"}"#;
 
-        fs::write(&transcript_path, format!("{old_line}\n{new_line}\n"))
-            .expect("transcript should write");
+        fs::write(
+            &transcript_path,
+            format!("{old_line}\n{new_line}\n{reply_line}\n"),
+        )
+        .expect("transcript should write");
 
         let parsed = read_transcript(&transcript_path).expect("transcript should parse");
-        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.len(), 3);
         assert_eq!(parsed[0].included_artifact_ids, None);
         assert_eq!(
             parsed[1].included_artifact_ids,
             Some(vec!["a1".to_string(), "a2".to_string()])
         );
-
-        let _ = fs::remove_dir_all(transcript_dir);
+        assert_eq!(parsed[1].run_id, None);
+        assert_eq!(parsed[2].run_id.as_deref(), Some("run-1"));
     }
 
     #[test]
@@ -5003,8 +7187,76 @@ mod tests {
         assert!(!persisted.tools_enabled);
         assert_eq!(persisted.created_at, 1_700_000_000_000);
         assert_eq!(persisted.updated_at, 1_700_000_001_000);
+    }
+
+    #[test]
+    fn read_camp_config_should_migrate_v0_2_and_default_rich_metadata() {
+        let camp_dir = make_temp_dir("basecamp-config-migrate-v02");
+        fs::write(camp_config_path(&camp_dir), V0_2_CAMP_CONFIG_FIXTURE)
+            .expect("v0.2 camp config fixture should write");
+
+        let loaded = read_camp_config(&camp_dir).expect("v0.2 config should load");
+        assert_eq!(loaded.schema_version, CAMP_SCHEMA_VERSION);
+        assert_eq!(loaded.id, "camp-v02");
+        assert_eq!(loaded.model_id, "anthropic/claude-3.5-sonnet");
+        assert_eq!(
+            loaded
+                .model_overrides
+                .as_ref()
+                .and_then(|overrides| overrides.temperature),
+            Some(0.4)
+        );
+        assert!(loaded.tools_enabled);
+        assert_eq!(loaded.description, None);
+        assert_eq!(loaded.color, None);
+        assert_eq!(loaded.emoji, None);
+        assert!(!loaded.pinned);
+        assert!(loaded.generation_defaults.is_none());
+        assert!(loaded.tags.is_empty());
+
+        let persisted: Value =
+            read_json_file(&camp_config_path(&camp_dir)).expect("migrated config should persist");
+        assert_eq!(persisted["schema_version"], CAMP_SCHEMA_VERSION);
+        assert_eq!(persisted["pinned"], false);
+    }
+
+    #[test]
+    fn read_camp_config_should_normalize_rich_metadata_fields() {
+        let camp_dir = make_temp_dir("basecamp-config-metadata");
+        fs::write(
+            camp_config_path(&camp_dir),
+            r##"{
+  "schema_version": "0.3",
+  "id": "camp-rich",
+  "name": "Rich",
+  "model": "openrouter/auto",
+  "provider_kind": "openrouter",
+  "model_id": "auto",
+  "tools_enabled": false,
+  "is_team": false,
+  "description": "  Notes on the launch  ",
+  "color": "#3A7BD5",
+  "emoji": "🏕",
+  "pinned": "true",
+  "generation_defaults": { "temperature": 0.2, "max_tokens": "512" },
+  "tags": ["Launch", "launch", " ops ", 4],
+  "created_at": 1700000000000,
+  "updated_at": 1700000000000
+}"##,
+        )
+        .expect("rich config should write");
 
-        let _ = fs::remove_dir_all(camp_dir);
+        let loaded = read_camp_config(&camp_dir).expect("rich config should load");
+        assert_eq!(loaded.description.as_deref(), Some("Notes on the launch"));
+        assert_eq!(loaded.color.as_deref(), Some("#3a7bd5"));
+        assert_eq!(loaded.emoji.as_deref(), Some("🏕"));
+        assert!(loaded.pinned);
+        let defaults = loaded
+            .generation_defaults
+            .expect("generation defaults should parse");
+        assert_eq!(defaults.temperature, Some(0.2));
+        assert_eq!(defaults.max_tokens, Some(512));
+        assert_eq!(loaded.tags, vec!["Launch".to_string(), "ops".to_string()]);
     }
 
     #[test]
@@ -5026,8 +7278,6 @@ mod tests {
 
         let error = read_camp_config(&camp_dir).expect_err("unsupported schema should fail");
         assert!(error.contains("Unsupported camp schema_version"));
-
-        let _ = fs::remove_dir_all(camp_dir);
     }
 
     #[test]
@@ -5075,7 +7325,385 @@ mod tests {
             Some(vec!["a1".to_string(), "a2".to_string()])
         );
         assert_eq!(parsed[3].created_at, 1_700_000_005_000);
+    }
+
+    #[test]
+    fn verify_transcript_should_report_and_quarantine_bad_lines() {
+        let camp_dir = make_temp_dir("basecamp-transcript-verify");
+        let lines = [
+            r#"{"id":"m1","role":"user","content":"hello","created_at":10}"#,
+            r#"{"id":"m2","role":"assistant","content":"","created_at":20,"tool_calls":[{"id":"call-1","type":"function","function":{"name":"read_file","arguments":"{}"}}]}"#,
+            r#"{"id":"m3","role":"tool","content":"ok","created_at":30,"tool_call_id":"call-1","name":"read_file"}"#,
+            r#"{"id":"m1","role":"user","content":"dupe","created_at":40}"#,
+            r#"{"id":"m4","role":"tool","content":"lost","created_at":50,"tool_call_id":"call-missing","name":"read_file"}"#,
+            r#"{not json"#,
+            r#"{"id":"m5","role":"user","content":"late","created_at":5}"#,
+        ];
+        fs::write(
+            camp_transcript_path(&camp_dir),
+            format!("{}\n", lines.join("\n")),
+        )
+        .expect("transcript should write");
+
+        let report = verify_transcript(&camp_dir, false).expect("verify should succeed");
+        assert_eq!(report.total_lines, 7);
+        assert_eq!(report.valid_messages, 4);
+        assert!(!report.repaired);
+        let kinds: Vec<TranscriptIssueKind> =
+            report.issues.iter().map(|issue| issue.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TranscriptIssueKind::DuplicateId,
+                TranscriptIssueKind::OrphanedTool,
+                TranscriptIssueKind::Malformed,
+                TranscriptIssueKind::OutOfOrder,
+            ]
+        );
 
-        let _ = fs::remove_dir_all(transcript_dir);
+        let repaired = verify_transcript(&camp_dir, true).expect("repair should succeed");
+        assert!(repaired.repaired);
+        let transcript = read_transcript(&camp_transcript_path(&camp_dir))
+            .expect("repaired transcript should load");
+        assert_eq!(transcript.len(), 4);
+        let rejected = fs::read_to_string(camp_transcript_rejected_path(&camp_dir))
+            .expect("rejected file should exist");
+        assert_eq!(rejected.lines().count(), 3);
+    }
+
+    #[test]
+    fn write_json_file_should_replace_atomically_without_leftover_temp_files() {
+        let dir = make_temp_dir("basecamp-atomic-write");
+        let path = dir.join(CAMP_MEMORY_FILE);
+
+        write_json_file(&path, &serde_json::json!({ "version": 1 }))
+            .expect("first write should succeed");
+        write_json_file(&path, &serde_json::json!({ "version": 2 }))
+            .expect("second write should succeed");
+
+        let loaded: Value = read_json_file(&path).expect("written JSON should parse");
+        assert_eq!(loaded["version"], 2);
+
+        let entries: Vec<String> = fs::read_dir(&dir)
+            .expect("temp dir should list")
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(entries, vec![CAMP_MEMORY_FILE.to_string()]);
+    }
+
+    #[test]
+    fn rename_camp_folder_should_move_uuid_folder_to_slug_and_update_config_id() {
+        let camps_root = make_temp_dir("basecamp-camp-rename");
+        let legacy_id = Uuid::new_v4().to_string();
+        let camp_dir = camps_root.join(&legacy_id);
+        fs::create_dir_all(&camp_dir).expect("camp folder should be created");
+        fs::write(
+            camp_config_path(&camp_dir),
+            format!(
+                r#"{{"schema_version":"0.3","id":"{legacy_id}","name":"My Project!","model":"openrouter/auto","provider_kind":"openrouter","model_id":"auto","tools_enabled":false,"is_team":false,"created_at":1700000000000,"updated_at":1700000000000}}"#
+            ),
+        )
+        .expect("camp config should write");
+
+        let next_dir = rename_camp_folder(&camps_root, &camp_dir).expect("rename should succeed");
+        assert!(!camp_dir.exists());
+        let next_id = next_dir
+            .file_name()
+            .and_then(|value| value.to_str())
+            .expect("renamed folder should have a name")
+            .to_string();
+        assert!(next_id.starts_with("my-project-"));
+        assert_eq!(next_id.len(), "my-project-".len() + CAMP_SLUG_SUFFIX_LEN);
+
+        let config = read_camp_config(&next_dir).expect("renamed config should load");
+        assert_eq!(config.id, next_id);
+
+        let unchanged =
+            rename_camp_folder(&camps_root, &next_dir).expect("second rename is a no-op");
+        assert_eq!(unchanged, next_dir);
+    }
+
+    #[test]
+    fn trashed_artifact_should_restore_with_original_metadata() {
+        let camp_dir = make_temp_dir("basecamp-artifact-trash");
+        fs::create_dir_all(camp_artifacts_dir(&camp_dir))
+            .expect("artifacts folder should be created");
+
+        let metadata = CampArtifactMetadata {
+            id: "a1".to_string(),
+            title: "Spec".to_string(),
+            filename: "a1.md".to_string(),
+            source_message_id: "m1".to_string(),
+            source_role: "assistant".to_string(),
+            tags: vec!["spec".to_string()],
+            created_at: 1,
+            updated_at: 1,
+            usage_count: 3,
+            archived: false,
+            source_path: None,
+            always_include: false,
+            include_order: None,
+        };
+        write_artifact_body(&camp_dir, &metadata, "# Spec\n\nbody")
+            .expect("artifact body should write");
+        write_artifacts_index(
+            &camp_dir,
+            &CampArtifactsIndex {
+                schema_version: CAMP_ARTIFACTS_SCHEMA_VERSION.to_string(),
+                artifacts: vec![metadata],
+            },
+        )
+        .expect("index should write");
+
+        let entry = trash_artifact(&camp_dir, "a1").expect("artifact should move to trash");
+        assert_eq!(entry.kind, CampTrashKind::Artifact);
+        assert!(ensure_artifacts_index(&camp_dir)
+            .expect("index should load")
+            .artifacts
+            .is_empty());
+        assert!(!camp_artifacts_dir(&camp_dir).join("a1.md").exists());
+
+        restore_trash_entry(&camp_dir, &entry.id).expect("artifact should restore");
+        let restored = load_artifact(&camp_dir, "a1").expect("restored artifact should load");
+        assert_eq!(restored.metadata.usage_count, 3);
+        assert_eq!(restored.body, "# Spec\n\nbody");
+        assert!(read_trash_index(&camp_dir)
+            .expect("trash index should load")
+            .entries
+            .is_empty());
+    }
+
+    #[test]
+    fn always_include_artifacts_should_follow_explicit_order_and_skip_archived() {
+        let artifact = |id: &str, created_at: i64| CampArtifactMetadata {
+            id: id.to_string(),
+            title: id.to_string(),
+            filename: format!("{id}.md"),
+            source_message_id: String::new(),
+            source_role: "user".to_string(),
+            tags: Vec::new(),
+            created_at,
+            updated_at: created_at,
+            usage_count: 0,
+            archived: false,
+            source_path: None,
+            always_include: false,
+            include_order: None,
+        };
+        let mut index = CampArtifactsIndex {
+            schema_version: CAMP_ARTIFACTS_SCHEMA_VERSION.to_string(),
+            artifacts: vec![
+                artifact("style", 1),
+                artifact("spec", 2),
+                artifact("old", 3),
+            ],
+        };
+
+        for id in ["style", "spec", "old"] {
+            set_artifact_always_include(&mut index, id, true).expect("artifact should be marked");
+        }
+        index.artifacts[2].archived = true;
+
+        let ids = |index: &CampArtifactsIndex| -> Vec<String> {
+            always_include_artifacts(index)
+                .into_iter()
+                .map(|artifact| artifact.id)
+                .collect()
+        };
+        assert_eq!(ids(&index), vec!["style".to_string(), "spec".to_string()]);
+
+        reorder_always_include_artifacts(&mut index, &["spec".to_string(), "style".to_string()])
+            .expect("reorder should succeed");
+        assert_eq!(ids(&index), vec!["spec".to_string(), "style".to_string()]);
+
+        set_artifact_always_include(&mut index, "spec", false)
+            .expect("artifact should be unmarked");
+        assert_eq!(ids(&index), vec!["style".to_string()]);
+        assert!(reorder_always_include_artifacts(&mut index, &["spec".to_string()]).is_err());
+    }
+
+    #[test]
+    fn rollback_to_checkpoint_should_archive_truncated_messages_and_restore_memory() {
+        let camp_dir = make_temp_dir("basecamp-checkpoints");
+        let transcript_path = camp_transcript_path(&camp_dir);
+        let message = |id: &str| CampMessage::for_test(id, "user", &format!("message {id}"));
+
+        write_json_file(
+            &camp_memory_path(&camp_dir),
+            &serde_json::json!({ "step": 1 }),
+        )
+        .expect("memory should write");
+        append_transcript_message(&transcript_path, &message("m1")).expect("message should append");
+        let checkpoint = create_checkpoint(&camp_dir, "before experiment")
+            .expect("checkpoint should be created");
+        assert_eq!(checkpoint.message_count, 1);
+
+        append_transcript_message(&transcript_path, &message("m2")).expect("message should append");
+        write_json_file(
+            &camp_memory_path(&camp_dir),
+            &serde_json::json!({ "step": 2 }),
+        )
+        .expect("memory should write");
+        let later = create_checkpoint(&camp_dir, "after").expect("checkpoint should be created");
+
+        let result =
+            rollback_to_checkpoint(&camp_dir, &checkpoint.id).expect("rollback should succeed");
+        assert_eq!(result.archived_message_count, 1);
+        assert_eq!(result.removed_checkpoint_ids, vec![later.id]);
+
+        let transcript = read_transcript(&transcript_path).expect("transcript should load");
+        assert_eq!(transcript.len(), 1);
+        assert_eq!(transcript[0].id, "m1");
+        let memory: Value =
+            read_json_file(&camp_memory_path(&camp_dir)).expect("memory should load");
+        assert_eq!(memory, serde_json::json!({ "step": 1 }));
+
+        let archive_filename = result
+            .archive_filename
+            .expect("truncated messages should be archived");
+        let archived = read_transcript(&camp_checkpoints_dir(&camp_dir).join(archive_filename))
+            .expect("archive should load");
+        assert_eq!(archived[0].id, "m2");
+    }
+
+    #[test]
+    fn undo_last_exchange_should_truncate_transcript_and_roll_back_artifact_usage() {
+        let camp_dir = make_temp_dir("basecamp-undo-exchange");
+        let transcript_path = camp_transcript_path(&camp_dir);
+        let message = |id: &str, role: &str, included: Option<Vec<String>>| CampMessage {
+            included_artifact_ids: included,
+            ..CampMessage::for_test(id, role, &format!("message {id}"))
+        };
+
+        write_artifacts_index(
+            &camp_dir,
+            &CampArtifactsIndex {
+                schema_version: CAMP_ARTIFACTS_SCHEMA_VERSION.to_string(),
+                artifacts: vec![CampArtifactMetadata {
+                    id: "spec".to_string(),
+                    title: "Spec".to_string(),
+                    filename: "spec.md".to_string(),
+                    source_message_id: String::new(),
+                    source_role: "user".to_string(),
+                    tags: Vec::new(),
+                    created_at: 1,
+                    updated_at: 1,
+                    usage_count: 2,
+                    archived: false,
+                    source_path: None,
+                    always_include: false,
+                    include_order: None,
+                }],
+            },
+        )
+        .expect("artifacts index should write");
+        for entry in [
+            message("u1", "user", None),
+            message("a1", "assistant", None),
+            message("u2", "user", Some(vec!["spec".to_string()])),
+            message("a2", "assistant", None),
+        ] {
+            append_transcript_message(&transcript_path, &entry).expect("message should append");
+        }
+
+        let result = undo_last_exchange(&camp_dir).expect("undo should succeed");
+        let removed: Vec<&str> = result
+            .removed_messages
+            .iter()
+            .map(|message| message.id.as_str())
+            .collect();
+        assert_eq!(removed, vec!["u2", "a2"]);
+        assert_eq!(result.rolled_back_artifact_ids, vec!["spec".to_string()]);
+
+        let transcript = read_transcript(&transcript_path).expect("transcript should load");
+        assert_eq!(transcript.len(), 2);
+        assert_eq!(transcript[1].id, "a1");
+        let index = ensure_artifacts_index(&camp_dir).expect("artifacts index should load");
+        assert_eq!(index.artifacts[0].usage_count, 1);
+
+        undo_last_exchange(&camp_dir).expect("second undo should succeed");
+        assert!(undo_last_exchange(&camp_dir).is_err());
+    }
+
+    #[test]
+    fn store_openrouter_generation_should_persist_cost_and_native_tokens() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        migrations::run_migrations(&connection, false).expect("schema should be created");
+        connection
+            .execute(
+                "
+        INSERT INTO runs (
+          id, timestamp, model, requested_model, system_prompt, user_prompt,
+          temperature, max_tokens, request_json, response_json, output_text, latency_ms,
+          generation_id
+        )
+        VALUES ('run-1', 1, 'openrouter/auto', 'openrouter/auto', '', 'hi', 0.2, 256, '{}', '{}', 'hello', 10, 'gen-1')
+        ",
+                [],
+            )
+            .expect("run should insert");
+
+        let generation = serde_json::json!({
+            "id": "gen-1",
+            "total_cost": 0.00042,
+            "native_tokens_prompt": 12,
+            "native_tokens_completion": 34,
+            "native_tokens_reasoning": 5,
+            "provider_name": "Anthropic"
+        });
+        store_openrouter_generation(&connection, "run-1", &generation)
+            .expect("generation should store");
+
+        let run = connection
+            .query_row(
+                "
+        SELECT
+          id, timestamp, model, requested_model, resolved_model, system_prompt, user_prompt,
+          temperature, max_tokens, request_json, response_json, output_text, latency_ms,
+          prompt_tokens, completion_tokens, total_tokens, error, rating, tags,
+          generation_id, total_cost, native_tokens_prompt, native_tokens_completion,
+          native_tokens_reasoning, provider_name, generation_json
+        FROM runs
+        WHERE id = 'run-1'
+        ",
+                [],
+                map_run_row,
+            )
+            .expect("run should load");
+        assert_eq!(run.generation_id.as_deref(), Some("gen-1"));
+        assert_eq!(run.total_cost, Some(0.00042));
+        assert_eq!(run.native_tokens_prompt, Some(12));
+        assert_eq!(run.native_tokens_completion, Some(34));
+        assert_eq!(run.native_tokens_reasoning, Some(5));
+        assert_eq!(run.provider_name.as_deref(), Some("Anthropic"));
+    }
+
+    #[test]
+    fn parse_model_overrides_field_should_normalize_stop_and_logit_bias() {
+        let raw = serde_json::json!({
+            "stop": ["\n\n", "", "\n\n", "END", "a", "b", "c"],
+            "logit_bias": { "50256": -250, "not-a-token": 5, "1234": "2.5" }
+        });
+
+        let (overrides, migrated) = parse_model_overrides_field(Some(&raw));
+        let overrides = overrides.expect("overrides should parse");
+        assert!(migrated);
+        assert_eq!(
+            overrides.stop,
+            Some(vec![
+                "\n\n".to_string(),
+                "END".to_string(),
+                "a".to_string(),
+                "b".to_string()
+            ])
+        );
+        assert_eq!(
+            overrides.logit_bias,
+            Some(BTreeMap::from([
+                ("1234".to_string(), 2.5),
+                ("50256".to_string(), -100.0)
+            ]))
+        );
     }
 }
@@ -0,0 +1,129 @@
+//! Markdown to HTML for messages and artifacts. Rendering happens here rather
+//! than in the webview so every view gets the same output, and the HTML is
+//! always run through ammonia first: model output is untrusted, and anything
+//! that could run script or restyle the app is stripped before it reaches the
+//! DOM.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+use reqwest::Url;
+use tauri::Window;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::{ensure_main_window, mcp_auth};
+
+/// Artifacts can be long, but a multi-megabyte "message" is almost certainly a
+/// pasted blob that is better shown as plain text.
+const MAX_MARKDOWN_BYTES: usize = 2 * 1024 * 1024;
+
+pub(crate) fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+pub(crate) fn sanitizer() -> &'static Builder<'static> {
+    static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+        builder
+            .url_schemes(HashSet::from(LINK_SCHEMES))
+            .link_rel(Some("noopener noreferrer nofollow"))
+            // Task list items render as disabled checkboxes.
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            // Code fences carry their language as `class="language-…"`; keep
+            // that for highlighting and drop any other class.
+            .add_tag_attributes("code", ["class"])
+            .attribute_filter(|element, attribute, value| match (element, attribute) {
+                ("code", "class") => value
+                    .split_whitespace()
+                    .find(|class| class.starts_with("language-"))
+                    .map(|class| Cow::Owned(class.to_string())),
+                ("input", "type") => (value == "checkbox").then_some(Cow::Borrowed(value)),
+                _ => Some(Cow::Borrowed(value)),
+            });
+        builder
+    })
+}
+
+/// Schemes rendered links may use, and so the only ones handed to the system.
+const LINK_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// For text placed into HTML around rendered markdown, such as titles.
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn render_markdown_html(markdown: &str) -> String {
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(
+        &mut unsafe_html,
+        Parser::new_ext(markdown, markdown_options()),
+    );
+    sanitizer().clean(&unsafe_html).to_string()
+}
+
+/// Renders markdown to sanitized HTML, safe to set as `innerHTML`.
+#[tauri::command]
+pub fn render_markdown(markdown: String) -> CommandResult<String> {
+    if markdown.len() > MAX_MARKDOWN_BYTES {
+        return Err(BasecampError::validation(format!(
+            "Markdown is too large to render ({} bytes; the limit is {MAX_MARKDOWN_BYTES}).",
+            markdown.len()
+        ))
+        .with_context("bytes", markdown.len()));
+    }
+    Ok(render_markdown_html(&markdown))
+}
+
+/// Opens a link from rendered markdown in the system browser or mail client.
+/// The webview never follows these links itself, so a message can't navigate
+/// the app away from its own pages.
+#[tauri::command]
+pub fn open_external_url(window: Window, url: String) -> CommandResult<()> {
+    ensure_main_window(&window)?;
+    let parsed = Url::parse(&url).map_err(|err| {
+        BasecampError::validation(format!("Invalid link: {err}")).with_context("url", url.clone())
+    })?;
+    if !LINK_SCHEMES.contains(&parsed.scheme()) {
+        return Err(
+            BasecampError::validation("Only web and mail links can be opened.")
+                .with_context("url", url),
+        );
+    }
+    mcp_auth::open_in_browser(parsed.as_str()).map_err(BasecampError::internal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_markdown_html_should_keep_formatting_and_strip_script() {
+        let html = render_markdown_html(
+            "# Title\n\n- [x] done\n\n```rust\nfn main() {}\n```\n\n\
+             <script>alert(1)</script><img src=x onerror=alert(1)>\n\n\
+             [bad](javascript:alert(1)) [good](https://example.com)",
+        );
+
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<input"));
+        assert!(html.contains("type=\"checkbox\""));
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("javascript:"));
+        assert!(html.contains(
+            "<a href=\"https://example.com\" rel=\"noopener noreferrer nofollow\">good</a>"
+        ));
+    }
+}
@@ -1,18 +1,46 @@
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use keyring::Entry;
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::State;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader as TokioBufReader, BufWriter};
-use tokio::process::{Child, Command};
-
-use crate::AppState;
+use tauri::{AppHandle, Manager, State, Window};
+use tokio::io::{
+    AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader as TokioBufReader, BufWriter,
+};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::oneshot;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::mcp_auth;
+use crate::mcp_roots;
+use crate::mcp_sampling::{self, SamplingContext};
+use crate::tool_registry::{qualify_mcp_tool_name, ToolRegistry};
+use crate::tool_schema::{invalid_arguments_result, validate_tool_arguments, ToolArgumentError};
+use crate::{ensure_main_window, get_setting_value, set_setting_value, AppState};
 
 const KEYRING_SERVICE: &str = "com.basecamp.app";
 const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
+const JSON_RPC_METHOD_NOT_FOUND: i64 = -32601;
+const JSON_RPC_INTERNAL_ERROR: i64 = -32603;
+const MAX_STDERR_LINES: usize = 500;
+const STDERR_TAIL_LINES: usize = 10;
+/// Gives the stderr reader a moment to drain a crashing child before its output
+/// is attached to the connection error.
+const STDERR_SETTLE: Duration = Duration::from_millis(150);
+const SETTING_MCP_TOOL_CACHE_TTL: &str = "mcp_tool_cache_ttl_secs";
+const DEFAULT_TOOL_CACHE_TTL_SECS: u64 = 30;
+const MAX_TOOL_CACHE_TTL_SECS: u64 = 600;
+/// Error code the MCP spec uses for sampling requests the client declines.
+const SAMPLING_ERROR_CODE: i64 = -1;
+/// How long a stdio caller waits for its response. Generous, since a tool call
+/// may be waiting on the user to approve a sampling request.
+const STDIO_RESPONSE_TIMEOUT: Duration = Duration::from_secs(300);
 
 // ---------------------------------------------------------------------------
 // Public types
@@ -90,13 +118,12 @@ struct JsonRpcRequest {
 
 #[derive(Deserialize)]
 struct JsonRpcResponse {
-    #[allow(dead_code)]
     id: Option<u64>,
     result: Option<Value>,
     error: Option<JsonRpcError>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct JsonRpcError {
     code: i64,
     message: String,
@@ -110,50 +137,489 @@ struct JsonRpcNotification {
     params: Option<Value>,
 }
 
+/// Our answer to a request the server sent us, such as `sampling/createMessage`.
+#[derive(Serialize)]
+struct JsonRpcReply {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonRpcError>,
+}
+
+/// A line read from a stdio server while waiting for one of our responses.
+enum IncomingMessage {
+    Response(JsonRpcResponse),
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    Notification,
+}
+
+fn classify_incoming(line: &str) -> Result<IncomingMessage, String> {
+    let message: Value = serde_json::from_str(line)
+        .map_err(|e| format!("Failed to parse MCP server response: {e}"))?;
+    match (
+        message.get("method").and_then(Value::as_str),
+        message.get("id"),
+    ) {
+        (Some(method), Some(id)) => Ok(IncomingMessage::Request {
+            id: id.clone(),
+            method: method.to_string(),
+            params: message.get("params").cloned().unwrap_or(Value::Null),
+        }),
+        (Some(_), None) => Ok(IncomingMessage::Notification),
+        (None, _) => serde_json::from_value(message)
+            .map(IncomingMessage::Response)
+            .map_err(|e| format!("Failed to parse MCP server response: {e}")),
+    }
+}
+
+async fn answer_server_request(
+    method: &str,
+    params: &Value,
+    sampling: &SamplingContext<'_>,
+) -> Result<Value, JsonRpcError> {
+    match method {
+        "sampling/createMessage" => mcp_sampling::create_message(sampling, params)
+            .await
+            .map_err(|message| JsonRpcError {
+                code: SAMPLING_ERROR_CODE,
+                message,
+            }),
+        "roots/list" => mcp_roots::list_roots(sampling.state).map_err(|message| JsonRpcError {
+            code: JSON_RPC_INTERNAL_ERROR,
+            message,
+        }),
+        _ => Err(JsonRpcError {
+            code: JSON_RPC_METHOD_NOT_FOUND,
+            message: format!("Method not supported by Basecamp: {method}"),
+        }),
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(
+    stdin: &mut W,
+    message: &impl Serialize,
+) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write to MCP server stdin: {e}"))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("Failed to flush MCP server stdin: {e}"))
+}
+
 // ---------------------------------------------------------------------------
 // Connection types
 // ---------------------------------------------------------------------------
 
+/// Live connections. Each one is shared so a call can release this map's lock
+/// before awaiting its response, letting requests to one server run in parallel.
 pub struct McpConnections {
-    connections: HashMap<String, McpConnection>,
+    connections: HashMap<String, Arc<McpConnection>>,
+    /// Tools each server marked `readOnlyHint`, recorded at discovery.
+    read_only_tools: HashMap<String, HashSet<String>>,
+    /// Each tool's `inputSchema` by server, recorded at discovery.
+    input_schemas: HashMap<String, HashMap<String, Value>>,
+    result_cache: HashMap<ToolCacheKey, CachedToolResult>,
+    /// Bumped whenever cached results are dropped, so a call that started
+    /// before then does not store its now-stale result.
+    cache_generation: u64,
+    tool_registry: ToolRegistry,
+}
+
+/// (server id, tool name, hash of the arguments).
+type ToolCacheKey = (String, String, u64);
+
+struct CachedToolResult {
+    stored_at: Instant,
+    result: McpToolResult,
+}
+
+fn tool_cache_key(server_id: &str, tool_name: &str, arguments: &Value) -> ToolCacheKey {
+    // serde_json maps are sorted by key, so equal arguments serialize identically.
+    let mut hasher = DefaultHasher::new();
+    arguments.to_string().hash(&mut hasher);
+    (
+        server_id.to_string(),
+        tool_name.to_string(),
+        hasher.finish(),
+    )
 }
 
 impl McpConnections {
     pub fn new() -> Self {
         Self {
             connections: HashMap::new(),
+            read_only_tools: HashMap::new(),
+            input_schemas: HashMap::new(),
+            result_cache: HashMap::new(),
+            cache_generation: 0,
+            tool_registry: ToolRegistry::new(),
+        }
+    }
+
+    fn is_read_only_tool(&self, server_id: &str, tool_name: &str) -> bool {
+        self.read_only_tools
+            .get(server_id)
+            .is_some_and(|tools| tools.contains(tool_name))
+    }
+
+    /// Checks `arguments` against the schema the tool declared; tools that
+    /// weren't discovered are left to the server.
+    fn validate_arguments(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        arguments: &Value,
+    ) -> Result<(), Vec<ToolArgumentError>> {
+        match self
+            .input_schemas
+            .get(server_id)
+            .and_then(|tools| tools.get(tool_name))
+        {
+            Some(schema) => validate_tool_arguments(schema, arguments),
+            None => Ok(()),
         }
     }
 
+    fn cached_result(&self, key: &ToolCacheKey, ttl: Duration) -> Option<McpToolResult> {
+        self.result_cache
+            .get(key)
+            .filter(|cached| cached.stored_at.elapsed() < ttl)
+            .map(|cached| cached.result.clone())
+    }
+
+    fn cache_result(
+        &mut self,
+        key: ToolCacheKey,
+        result: McpToolResult,
+        ttl: Duration,
+        generation: u64,
+    ) {
+        if generation != self.cache_generation {
+            return;
+        }
+        self.result_cache
+            .retain(|_, cached| cached.stored_at.elapsed() < ttl);
+        self.result_cache.insert(
+            key,
+            CachedToolResult {
+                stored_at: Instant::now(),
+                result,
+            },
+        );
+    }
+
+    /// Drops cached results for one server, or for every server when
+    /// `server_id` is `None`.
+    pub(crate) fn clear_cached_results(&mut self, server_id: Option<&str>) {
+        match server_id {
+            Some(server_id) => self.result_cache.retain(|(id, _, _), _| id != server_id),
+            None => self.result_cache.clear(),
+        }
+        self.cache_generation += 1;
+    }
+
+    fn forget_server_tools(&mut self, server_id: &str) {
+        self.read_only_tools.remove(server_id);
+        self.input_schemas.remove(server_id);
+        self.clear_cached_results(Some(server_id));
+        self.tool_registry.forget_server(server_id);
+    }
+
     pub fn is_connected(&self, server_id: &str) -> bool {
-        self.connections.contains_key(server_id)
+        self.connections
+            .get(server_id)
+            .is_some_and(|conn| conn.is_open())
+    }
+
+    fn get(&self, server_id: &str) -> Option<Arc<McpConnection>> {
+        self.connections
+            .get(server_id)
+            .filter(|conn| conn.is_open())
+            .cloned()
+    }
+
+    fn open_connections(&self) -> Vec<Arc<McpConnection>> {
+        self.connections
+            .values()
+            .filter(|conn| conn.is_open())
+            .cloned()
+            .collect()
+    }
+
+    /// Drops a connection; stdio children are killed once in-flight calls finish
+    /// and the last handle drops.
+    pub fn disconnect(&mut self, server_id: &str) -> bool {
+        self.forget_server_tools(server_id);
+        self.connections.remove(server_id).is_some()
+    }
+}
+
+/// Ring buffer of a stdio server's stderr lines.
+#[derive(Clone, Default)]
+struct StderrLog(Arc<Mutex<VecDeque<String>>>);
+
+impl StderrLog {
+    fn push(&self, line: String) {
+        if let Ok(mut lines) = self.0.lock() {
+            if lines.len() == MAX_STDERR_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    fn tail(&self, count: usize) -> Vec<String> {
+        self.0
+            .lock()
+            .map(|lines| {
+                lines
+                    .iter()
+                    .skip(lines.len().saturating_sub(count))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 }
 
+/// Captured stderr per server. Kept outside the connection lock so logs stay
+/// readable while a call is stuck, and kept after disconnects so a crashed
+/// server can still be diagnosed.
+#[derive(Default)]
+pub struct McpServerLogs {
+    logs: Mutex<HashMap<String, StderrLog>>,
+}
+
+impl McpServerLogs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn for_server(&self, server_id: &str) -> StderrLog {
+        self.logs
+            .lock()
+            .map(|mut logs| logs.entry(server_id.to_string()).or_default().clone())
+            .unwrap_or_default()
+    }
+
+    fn remove(&self, server_id: &str) {
+        if let Ok(mut logs) = self.logs.lock() {
+            logs.remove(server_id);
+        }
+    }
+}
+
+async fn with_stderr_tail(err: String, log: &StderrLog) -> String {
+    tokio::time::sleep(STDERR_SETTLE).await;
+    let tail = log.tail(STDERR_TAIL_LINES);
+    if tail.is_empty() {
+        return err;
+    }
+    format!("{err}\n\nLast stderr output:\n{}", tail.join("\n"))
+}
+
+type PendingRequests = Mutex<HashMap<u64, oneshot::Sender<JsonRpcResponse>>>;
+
+fn register_waiter(
+    pending: &PendingRequests,
+    id: u64,
+) -> Result<oneshot::Receiver<JsonRpcResponse>, String> {
+    let (sender, receiver) = oneshot::channel();
+    pending
+        .lock()
+        .map_err(|_| "MCP pending request lock error".to_string())?
+        .insert(id, sender);
+    Ok(receiver)
+}
+
+/// Hands a response to the caller waiting on its id. Responses nobody waits
+/// for any more, such as one arriving after its caller timed out, are dropped.
+fn deliver_response(pending: &PendingRequests, response: JsonRpcResponse) {
+    let waiter = response.id.and_then(|id| {
+        pending
+            .lock()
+            .ok()
+            .and_then(|mut pending| pending.remove(&id))
+    });
+    if let Some(waiter) = waiter {
+        let _ = waiter.send(response);
+    }
+}
+
+async fn await_response(
+    receiver: oneshot::Receiver<JsonRpcResponse>,
+    timeout: Duration,
+) -> Result<JsonRpcResponse, String> {
+    match tokio::time::timeout(timeout, receiver).await {
+        Ok(Ok(response)) => Ok(response),
+        Ok(Err(_)) => Err("MCP server closed stdout unexpectedly".to_string()),
+        Err(_) => Err(format!(
+            "MCP server did not respond within {} seconds",
+            timeout.as_secs()
+        )),
+    }
+}
+
+/// State shared between callers and the stdout reader task of a stdio server.
+struct StdioShared {
+    writer: tokio::sync::Mutex<BufWriter<ChildStdin>>,
+    /// Callers waiting on a response, keyed by JSON-RPC id.
+    pending: PendingRequests,
+    /// Camps of in-flight tool calls, so sampling requests use a camp's model.
+    call_camps: Mutex<BTreeMap<u64, String>>,
+    closed: AtomicBool,
+}
+
+impl StdioShared {
+    async fn write(&self, message: &impl Serialize) -> Result<(), String> {
+        let mut writer = self.writer.lock().await;
+        write_line(&mut *writer, message).await
+    }
+
+    /// Servers do not say which call a sampling request belongs to; the most
+    /// recent in-flight call with a camp is the best guess.
+    fn sampling_camp(&self) -> Option<String> {
+        self.call_camps
+            .lock()
+            .ok()
+            .and_then(|camps| camps.values().next_back().cloned())
+    }
+
+    fn forget(&self, id: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&id);
+        }
+        if let Ok(mut camps) = self.call_camps.lock() {
+            camps.remove(&id);
+        }
+    }
+}
+
+/// Routes everything the server writes to stdout: responses go to the caller
+/// waiting on that id, server requests are answered on their own task so a slow
+/// sampling approval never blocks other responses, and notifications are
+/// dropped. When stdout closes every waiting caller is failed.
+async fn read_stdio_messages(
+    mut stdout: TokioBufReader<ChildStdout>,
+    shared: Arc<StdioShared>,
+    app: AppHandle,
+    server_id: String,
+    stderr_log: StderrLog,
+) {
+    loop {
+        let mut line = String::new();
+        match stdout.read_line(&mut line).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match classify_incoming(&line) {
+            Ok(IncomingMessage::Response(response)) => deliver_response(&shared.pending, response),
+            Ok(IncomingMessage::Request { id, method, params }) => {
+                let shared = shared.clone();
+                let app = app.clone();
+                let server_id = server_id.clone();
+                tauri::async_runtime::spawn(async move {
+                    let state = app.state::<AppState>();
+                    let camp_id = shared.sampling_camp();
+                    let sampling = SamplingContext {
+                        state: state.inner(),
+                        app: &app,
+                        server_id: &server_id,
+                        camp_id: camp_id.as_deref(),
+                    };
+                    let (result, error) =
+                        match answer_server_request(&method, &params, &sampling).await {
+                            Ok(result) => (Some(result), None),
+                            Err(error) => (None, Some(error)),
+                        };
+                    let reply = JsonRpcReply {
+                        jsonrpc: "2.0",
+                        id,
+                        result,
+                        error,
+                    };
+                    let _ = shared.write(&reply).await;
+                });
+            }
+            Ok(IncomingMessage::Notification) => {}
+            Err(err) => stderr_log.push(format!("[basecamp] ignored stdout line: {err}")),
+        }
+    }
+
+    shared.closed.store(true, Ordering::SeqCst);
+    if let Ok(mut pending) = shared.pending.lock() {
+        // Dropping the senders wakes every waiter with an error.
+        pending.clear();
+    }
+}
+
+fn into_result(response: JsonRpcResponse) -> Result<Value, String> {
+    if let Some(err) = response.error {
+        return Err(format!(
+            "MCP server error (code {}): {}",
+            err.code, err.message
+        ));
+    }
+    Ok(response.result.unwrap_or(Value::Null))
+}
+
 enum McpConnection {
     Stdio {
         _child: Child,
-        stdin: BufWriter<tokio::process::ChildStdin>,
-        stdout: TokioBufReader<tokio::process::ChildStdout>,
+        shared: Arc<StdioShared>,
         next_id: AtomicU64,
     },
     Sse {
+        server_id: String,
         url: String,
         client: reqwest::Client,
         next_id: AtomicU64,
-        auth_token: Option<String>,
+        auth_token: Mutex<Option<String>>,
     },
 }
 
 impl McpConnection {
-    async fn send_request(&mut self, method: &str, params: Option<Value>) -> Result<Value, String> {
+    fn is_open(&self) -> bool {
+        match self {
+            McpConnection::Stdio { shared, .. } => !shared.closed.load(Ordering::SeqCst),
+            McpConnection::Sse { .. } => true,
+        }
+    }
+
+    async fn send_request(&self, method: &str, params: Option<Value>) -> Result<Value, String> {
+        self.send_request_for_camp(method, params, None).await
+    }
+
+    /// Sends a request and waits for its response. `camp_id` marks a tool call
+    /// made on behalf of a camp so sampling requests it triggers use that camp.
+    async fn send_request_for_camp(
+        &self,
+        method: &str,
+        params: Option<Value>,
+        camp_id: Option<&str>,
+    ) -> Result<Value, String> {
         match self {
             McpConnection::Stdio {
-                stdin,
-                stdout,
-                next_id,
-                ..
+                shared, next_id, ..
             } => {
+                if shared.closed.load(Ordering::SeqCst) {
+                    return Err("MCP server closed stdout unexpectedly".to_string());
+                }
                 let id = next_id.fetch_add(1, Ordering::SeqCst);
                 let request = JsonRpcRequest {
                     jsonrpc: "2.0",
@@ -161,40 +627,26 @@ impl McpConnection {
                     method: method.to_string(),
                     params,
                 };
-                let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
-                line.push('\n');
-                stdin
-                    .write_all(line.as_bytes())
-                    .await
-                    .map_err(|e| format!("Failed to write to MCP server stdin: {e}"))?;
-                stdin
-                    .flush()
-                    .await
-                    .map_err(|e| format!("Failed to flush MCP server stdin: {e}"))?;
-
-                let mut response_line = String::new();
-                stdout
-                    .read_line(&mut response_line)
-                    .await
-                    .map_err(|e| format!("Failed to read from MCP server stdout: {e}"))?;
 
-                if response_line.is_empty() {
-                    return Err("MCP server closed stdout unexpectedly".to_string());
+                let receiver = register_waiter(&shared.pending, id)?;
+                if let Some(camp_id) = camp_id {
+                    if let Ok(mut camps) = shared.call_camps.lock() {
+                        camps.insert(id, camp_id.to_string());
+                    }
                 }
 
-                let response: JsonRpcResponse = serde_json::from_str(&response_line)
-                    .map_err(|e| format!("Failed to parse MCP server response: {e}"))?;
-
-                if let Some(err) = response.error {
-                    return Err(format!(
-                        "MCP server error (code {}): {}",
-                        err.code, err.message
-                    ));
+                if let Err(err) = shared.write(&request).await {
+                    shared.forget(id);
+                    return Err(err);
                 }
-
-                Ok(response.result.unwrap_or(Value::Null))
+                // Forgetting the id on every outcome, a timeout included, keeps
+                // a server that never answers from growing the pending map.
+                let response = await_response(receiver, STDIO_RESPONSE_TIMEOUT).await;
+                shared.forget(id);
+                into_result(response?)
             }
             McpConnection::Sse {
+                server_id,
                 url,
                 client,
                 next_id,
@@ -208,16 +660,31 @@ impl McpConnection {
                     params,
                 };
 
-                let mut builder = client.post(url.as_str()).json(&request);
-                if let Some(token) = auth_token {
-                    builder = builder.bearer_auth(token);
-                }
+                let send = |token: Option<String>| {
+                    let mut builder = client.post(url.as_str()).json(&request);
+                    if let Some(token) = token {
+                        builder = builder.bearer_auth(token);
+                    }
+                    builder.send()
+                };
+                let current_token = auth_token.lock().ok().and_then(|token| token.clone());
 
-                let resp = builder
-                    .send()
+                let mut resp = send(current_token)
                     .await
                     .map_err(|e| format!("SSE HTTP request failed: {e}"))?;
 
+                // An expired OAuth access token: refresh once and retry.
+                if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+                    if let Some(token) = mcp_auth::refresh_access_token(client, server_id).await? {
+                        if let Ok(mut stored) = auth_token.lock() {
+                            *stored = Some(token.clone());
+                        }
+                        resp = send(Some(token))
+                            .await
+                            .map_err(|e| format!("SSE HTTP request failed: {e}"))?;
+                    }
+                }
+
                 if !resp.status().is_success() {
                     return Err(format!("SSE server returned status {}", resp.status()));
                 }
@@ -230,41 +697,20 @@ impl McpConnection {
                 let response: JsonRpcResponse = serde_json::from_str(&body)
                     .map_err(|e| format!("Failed to parse SSE response: {e}"))?;
 
-                if let Some(err) = response.error {
-                    return Err(format!(
-                        "MCP server error (code {}): {}",
-                        err.code, err.message
-                    ));
-                }
-
-                Ok(response.result.unwrap_or(Value::Null))
+                into_result(response)
             }
         }
     }
 
-    async fn send_notification(
-        &mut self,
-        method: &str,
-        params: Option<Value>,
-    ) -> Result<(), String> {
+    async fn send_notification(&self, method: &str, params: Option<Value>) -> Result<(), String> {
         match self {
-            McpConnection::Stdio { stdin, .. } => {
+            McpConnection::Stdio { shared, .. } => {
                 let notification = JsonRpcNotification {
                     jsonrpc: "2.0",
                     method: method.to_string(),
                     params,
                 };
-                let mut line = serde_json::to_string(&notification).map_err(|e| e.to_string())?;
-                line.push('\n');
-                stdin
-                    .write_all(line.as_bytes())
-                    .await
-                    .map_err(|e| format!("Failed to write notification: {e}"))?;
-                stdin
-                    .flush()
-                    .await
-                    .map_err(|e| format!("Failed to flush notification: {e}"))?;
-                Ok(())
+                shared.write(&notification).await
             }
             McpConnection::Sse { .. } => {
                 // SSE transport: notifications are fire-and-forget, no response expected
@@ -274,6 +720,15 @@ impl McpConnection {
     }
 }
 
+/// Sends a parameterless notification to every open connection. Delivery is
+/// best effort; a server that has gone away is noticed on its next call.
+pub(crate) async fn broadcast_notification(state: &AppState, method: &str) {
+    let connections = state.mcp.lock().await.open_connections();
+    for conn in connections {
+        let _ = conn.send_notification(method, None).await;
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Database helpers
 // ---------------------------------------------------------------------------
@@ -352,14 +807,29 @@ fn load_server_configs(connection: &Connection) -> Result<Vec<McpServerConfig>,
     Ok(configs)
 }
 
-fn insert_server_config(connection: &Connection, config: &McpServerConfig) -> Result<(), String> {
-    let (transport_type, transport_config) = match &config.transport {
+pub(crate) fn find_server_config(
+    connection: &Connection,
+    server_id: &str,
+) -> CommandResult<McpServerConfig> {
+    load_server_configs(connection)
+        .map_err(BasecampError::database)?
+        .into_iter()
+        .find(|c| c.id == server_id)
+        .ok_or_else(|| server_not_found(server_id))
+}
+
+fn transport_columns(transport: &McpTransport) -> (&'static str, String) {
+    match transport {
         McpTransport::Stdio { command, args } => (
             "stdio",
             serde_json::json!({ "command": command, "args": args }).to_string(),
         ),
         McpTransport::Sse { url } => ("sse", serde_json::json!({ "url": url }).to_string()),
-    };
+    }
+}
+
+fn insert_server_config(connection: &Connection, config: &McpServerConfig) -> Result<(), String> {
+    let (transport_type, transport_config) = transport_columns(&config.transport);
 
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -376,6 +846,44 @@ fn insert_server_config(connection: &Connection, config: &McpServerConfig) -> Re
     Ok(())
 }
 
+/// Rewrites an existing row, keeping its `created_at`. Returns whether it existed.
+fn update_server_config(connection: &Connection, config: &McpServerConfig) -> Result<bool, String> {
+    let (transport_type, transport_config) = transport_columns(&config.transport);
+    let updated = connection
+        .execute(
+            "UPDATE mcp_servers SET name = ?2, transport = ?3, transport_config = ?4, enabled = ?5 WHERE id = ?1",
+            params![config.id, config.name, transport_type, transport_config, config.enabled],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+fn set_server_enabled(
+    connection: &Connection,
+    server_id: &str,
+    enabled: bool,
+) -> Result<bool, String> {
+    let updated = connection
+        .execute(
+            "UPDATE mcp_servers SET enabled = ?2 WHERE id = ?1",
+            params![server_id, enabled],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(updated > 0)
+}
+
+fn delete_server_config(connection: &Connection, server_id: &str) -> Result<bool, String> {
+    let deleted = connection
+        .execute("DELETE FROM mcp_servers WHERE id = ?1", params![server_id])
+        .map_err(|e| e.to_string())?;
+    Ok(deleted > 0)
+}
+
+fn server_not_found(server_id: &str) -> BasecampError {
+    BasecampError::not_found(format!("Server not found: {server_id}"))
+        .with_context("server_id", server_id)
+}
+
 // ---------------------------------------------------------------------------
 // Keyring helpers
 // ---------------------------------------------------------------------------
@@ -397,12 +905,30 @@ fn read_auth_token(server_id: &str) -> Option<String> {
     entry.get_password().ok()
 }
 
+fn delete_auth_token(server_id: &str) -> Result<(), String> {
+    match mcp_keyring_entry(server_id)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete auth token: {e}")),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Connection management
 // ---------------------------------------------------------------------------
 
-async fn connect_server(config: &McpServerConfig) -> Result<McpConnection, String> {
-    let auth_token = read_auth_token(&config.id);
+async fn connect_server(
+    app: &AppHandle,
+    config: &McpServerConfig,
+    stderr_log: &StderrLog,
+) -> Result<McpConnection, String> {
+    let client = reqwest::Client::new();
+    // An OAuth session takes precedence over a static token.
+    let auth_token = match &config.transport {
+        McpTransport::Sse { .. } => mcp_auth::access_token(&client, &config.id)
+            .await?
+            .or_else(|| read_auth_token(&config.id)),
+        McpTransport::Stdio { .. } => read_auth_token(&config.id),
+    };
 
     match &config.transport {
         McpTransport::Stdio { command, args } => {
@@ -410,7 +936,8 @@ async fn connect_server(config: &McpServerConfig) -> Result<McpConnection, Strin
             cmd.args(args)
                 .stdin(std::process::Stdio::piped())
                 .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::null());
+                .stderr(std::process::Stdio::piped())
+                .kill_on_drop(true);
 
             // Pass auth token as env var if present
             if let Some(ref token) = auth_token {
@@ -429,30 +956,58 @@ async fn connect_server(config: &McpServerConfig) -> Result<McpConnection, Strin
                 .stdout
                 .take()
                 .ok_or("Failed to capture MCP server stdout")?;
+            let stderr = child
+                .stderr
+                .take()
+                .ok_or("Failed to capture MCP server stderr")?;
+
+            stderr_log.push(format!("[basecamp] started `{command}` {}", args.join(" ")));
+            let log = stderr_log.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut lines = TokioBufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log.push(line);
+                }
+            });
+
+            let shared = Arc::new(StdioShared {
+                writer: tokio::sync::Mutex::new(BufWriter::new(stdin)),
+                pending: Mutex::new(HashMap::new()),
+                call_camps: Mutex::new(BTreeMap::new()),
+                closed: AtomicBool::new(false),
+            });
+            tauri::async_runtime::spawn(read_stdio_messages(
+                TokioBufReader::new(stdout),
+                shared.clone(),
+                app.clone(),
+                config.id.clone(),
+                stderr_log.clone(),
+            ));
 
             Ok(McpConnection::Stdio {
                 _child: child,
-                stdin: BufWriter::new(stdin),
-                stdout: TokioBufReader::new(stdout),
-                next_id: AtomicU64::new(1),
-            })
-        }
-        McpTransport::Sse { url } => {
-            let client = reqwest::Client::new();
-            Ok(McpConnection::Sse {
-                url: url.clone(),
-                client,
+                shared,
                 next_id: AtomicU64::new(1),
-                auth_token,
             })
         }
+        McpTransport::Sse { url } => Ok(McpConnection::Sse {
+            server_id: config.id.clone(),
+            url: url.clone(),
+            client,
+            next_id: AtomicU64::new(1),
+            auth_token: Mutex::new(auth_token),
+        }),
     }
 }
 
-async fn initialize_connection(conn: &mut McpConnection) -> Result<(), String> {
+async fn initialize_connection(conn: &McpConnection) -> Result<(), String> {
     let init_params = serde_json::json!({
         "protocolVersion": MCP_PROTOCOL_VERSION,
-        "capabilities": {},
+        "capabilities": {
+            // Only honoured over stdio; HTTP servers cannot reach us mid-call.
+            "sampling": {},
+            "roots": { "listChanged": true }
+        },
         "clientInfo": {
             "name": "Basecamp",
             "version": "0.1.0"
@@ -496,7 +1051,7 @@ fn parse_tool_defs(server_id: &str, result: &Value) -> Vec<McpToolDef> {
             Some(McpToolDef {
                 server_id: server_id.to_string(),
                 name: name.to_string(),
-                qualified_name: format!("{}/{}", server_id, name),
+                qualified_name: qualify_mcp_tool_name(server_id, name),
                 description,
                 input_schema,
                 read_only,
@@ -513,18 +1068,25 @@ fn parse_tool_defs(server_id: &str, result: &Value) -> Vec<McpToolDef> {
 pub async fn mcp_register_server(
     state: State<'_, AppState>,
     config: McpServerConfig,
-) -> Result<McpServerConfig, String> {
+) -> CommandResult<McpServerConfig> {
+    register_server(&state, config)
+}
+
+pub(crate) fn register_server(
+    state: &AppState,
+    config: McpServerConfig,
+) -> CommandResult<McpServerConfig> {
     // Store auth token in keyring if provided
     if let Some(ref token) = config.auth_token {
         if !token.is_empty() {
-            store_auth_token(&config.id, token)?;
+            store_auth_token(&config.id, token).map_err(BasecampError::internal)?;
         }
     }
 
     // Insert into database
     {
-        let db = state.connection.lock().map_err(|e| e.to_string())?;
-        insert_server_config(&db, &config)?;
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        insert_server_config(&db, &config).map_err(BasecampError::database)?;
     }
 
     // Return config without auth token
@@ -534,11 +1096,91 @@ pub async fn mcp_register_server(
     })
 }
 
+/// Replaces a server's name, transport and enabled flag. `auth_token` follows the
+/// register semantics, except that an empty string clears the stored token. The
+/// open connection is dropped so the next use reconnects with the new settings.
 #[tauri::command]
-pub async fn mcp_list_servers(state: State<'_, AppState>) -> Result<Vec<McpServerStatus>, String> {
+pub async fn mcp_update_server(
+    state: State<'_, AppState>,
+    config: McpServerConfig,
+) -> CommandResult<McpServerConfig> {
+    {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        if !update_server_config(&db, &config).map_err(BasecampError::database)? {
+            return Err(server_not_found(&config.id));
+        }
+    }
+
+    match config.auth_token.as_deref() {
+        Some("") => delete_auth_token(&config.id).map_err(BasecampError::internal)?,
+        Some(token) => store_auth_token(&config.id, token).map_err(BasecampError::internal)?,
+        None => {}
+    }
+
+    state.mcp.lock().await.disconnect(&config.id);
+
+    Ok(McpServerConfig {
+        auth_token: None,
+        ..config
+    })
+}
+
+/// Disconnects a server, removes it and deletes every credential stored for it.
+#[tauri::command]
+pub async fn mcp_delete_server(state: State<'_, AppState>, server_id: String) -> CommandResult<()> {
+    remove_server(&state, &server_id).await
+}
+
+pub(crate) async fn remove_server(state: &AppState, server_id: &str) -> CommandResult<()> {
+    state.mcp.lock().await.disconnect(server_id);
+
+    {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        if !delete_server_config(&db, server_id).map_err(BasecampError::database)? {
+            return Err(server_not_found(server_id));
+        }
+    }
+
+    state.mcp_logs.remove(server_id);
+    delete_auth_token(server_id).map_err(BasecampError::internal)?;
+    mcp_auth::clear_tokens(server_id).map_err(BasecampError::internal)?;
+    Ok(())
+}
+
+/// Turns a server on or off. Disabling also drops its connection.
+#[tauri::command]
+pub async fn mcp_set_enabled(
+    state: State<'_, AppState>,
+    server_id: String,
+    enabled: bool,
+) -> CommandResult<McpServerStatus> {
+    let config = {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        if !set_server_enabled(&db, &server_id, enabled).map_err(BasecampError::database)? {
+            return Err(server_not_found(&server_id));
+        }
+        find_server_config(&db, &server_id)?
+    };
+
+    let mut mcp = state.mcp.lock().await;
+    if !enabled {
+        mcp.disconnect(&server_id);
+    }
+
+    Ok(McpServerStatus {
+        connected: mcp.is_connected(&server_id),
+        id: config.id,
+        name: config.name,
+        transport: config.transport,
+        enabled: config.enabled,
+    })
+}
+
+#[tauri::command]
+pub async fn mcp_list_servers(state: State<'_, AppState>) -> CommandResult<Vec<McpServerStatus>> {
     let configs = {
-        let db = state.connection.lock().map_err(|e| e.to_string())?;
-        load_server_configs(&db)?
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        load_server_configs(&db).map_err(BasecampError::database)?
     };
 
     let mcp = state.mcp.lock().await;
@@ -557,60 +1199,148 @@ pub async fn mcp_list_servers(state: State<'_, AppState>) -> Result<Vec<McpServe
 
 #[tauri::command]
 pub async fn mcp_discover_tools(
+    app: AppHandle,
     state: State<'_, AppState>,
     server_id: String,
-) -> Result<Vec<McpToolDef>, String> {
+) -> CommandResult<Vec<McpToolDef>> {
+    discover_tools(&app, &state, &server_id).await
+}
+
+/// Connects to a server if needed and lists its tools.
+pub(crate) async fn discover_tools(
+    app: &AppHandle,
+    state: &AppState,
+    server_id: &str,
+) -> CommandResult<Vec<McpToolDef>> {
     // Load config from database
     let config = {
-        let db = state.connection.lock().map_err(|e| e.to_string())?;
-        let configs = load_server_configs(&db)?;
-        configs
-            .into_iter()
-            .find(|c| c.id == server_id)
-            .ok_or_else(|| format!("Server not found: {server_id}"))?
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        find_server_config(&db, server_id)?
     };
 
-    let mut mcp = state.mcp.lock().await;
-
-    // Connect if not already connected
-    if !mcp.is_connected(&server_id) {
-        let mut conn = connect_server(&config).await?;
-        initialize_connection(&mut conn).await?;
-        mcp.connections.insert(server_id.clone(), conn);
-    }
+    // Connect if not already connected (or if the server has since exited)
+    let conn = {
+        let mut mcp = state.mcp.lock().await;
+        match mcp.get(server_id) {
+            Some(conn) => conn,
+            None => {
+                let stderr_log = state.mcp_logs.for_server(server_id);
+                let connected = async {
+                    let conn = connect_server(app, &config, &stderr_log).await?;
+                    initialize_connection(&conn).await?;
+                    Ok::<_, String>(Arc::new(conn))
+                }
+                .await;
+                match connected {
+                    Ok(conn) => {
+                        // A restarted server may answer differently.
+                        mcp.clear_cached_results(Some(server_id));
+                        mcp.connections.insert(server_id.to_string(), conn.clone());
+                        conn
+                    }
+                    Err(err) => {
+                        return Err(BasecampError::mcp(with_stderr_tail(err, &stderr_log).await)
+                            .with_context("server_id", server_id));
+                    }
+                }
+            }
+        }
+    };
 
     // Fetch tools
-    let conn = mcp
-        .connections
-        .get_mut(&server_id)
-        .ok_or("Connection lost")?;
+    let result = conn
+        .send_request("tools/list", None)
+        .await
+        .map_err(BasecampError::mcp)?;
+    let mut tools = parse_tool_defs(server_id, &result);
 
-    let result = conn.send_request("tools/list", None).await?;
-    let tools = parse_tool_defs(&server_id, &result);
+    let mut mcp = state.mcp.lock().await;
+    mcp.forget_server_tools(server_id);
+    mcp.tool_registry
+        .register_server_tools(server_id, &mut tools);
+    mcp.read_only_tools.insert(
+        server_id.to_string(),
+        tools
+            .iter()
+            .filter(|tool| tool.read_only)
+            .map(|tool| tool.name.clone())
+            .collect(),
+    );
+    mcp.input_schemas.insert(
+        server_id.to_string(),
+        tools
+            .iter()
+            .map(|tool| (tool.name.clone(), tool.input_schema.clone()))
+            .collect(),
+    );
 
     Ok(tools)
 }
 
+/// Returns the captured stderr lines of a stdio server, oldest first.
+#[tauri::command]
+pub fn mcp_get_server_logs(
+    state: State<'_, AppState>,
+    server_id: String,
+) -> CommandResult<Vec<String>> {
+    Ok(state.mcp_logs.for_server(&server_id).tail(MAX_STDERR_LINES))
+}
+
 #[tauri::command]
 pub async fn mcp_call_tool(
     state: State<'_, AppState>,
     server_id: String,
     tool_name: String,
     arguments: Value,
-) -> Result<McpToolResult, String> {
-    let mut mcp = state.mcp.lock().await;
+    camp_id: Option<String>,
+) -> CommandResult<McpToolResult> {
+    let cache_ttl = {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        load_tool_cache_ttl(&db)
+    };
 
-    let conn = mcp
-        .connections
-        .get_mut(&server_id)
-        .ok_or_else(|| format!("Server not connected: {server_id}"))?;
+    // Release the map lock before awaiting so parallel calls are not serialized.
+    let (conn, tool_name, cache_key, cacheable, cache_generation) = {
+        let mcp = state.mcp.lock().await;
+        // Callers may pass the qualified name the provider saw.
+        let tool_name = match mcp.tool_registry.resolve(&tool_name) {
+            Some((owner, raw_name)) if owner == server_id => raw_name.to_string(),
+            _ => tool_name,
+        };
+        // Hand schema violations back as the tool's result so the model can
+        // fix its call.
+        if let Err(errors) = mcp.validate_arguments(&server_id, &tool_name, &arguments) {
+            return Ok(McpToolResult {
+                content: vec![McpContent {
+                    content_type: "text".to_string(),
+                    text: Some(invalid_arguments_result(&tool_name, &errors).to_string()),
+                }],
+                is_error: true,
+            });
+        }
+        let cache_key = tool_cache_key(&server_id, &tool_name, &arguments);
+        let cacheable = !cache_ttl.is_zero() && mcp.is_read_only_tool(&server_id, &tool_name);
+        if cacheable {
+            if let Some(cached) = mcp.cached_result(&cache_key, cache_ttl) {
+                return Ok(cached);
+            }
+        }
+        let conn = mcp.get(&server_id).ok_or_else(|| {
+            BasecampError::unavailable(format!("Server not connected: {server_id}"))
+                .with_context("server_id", server_id.clone())
+        })?;
+        (conn, tool_name, cache_key, cacheable, mcp.cache_generation)
+    };
 
     let params = serde_json::json!({
-        "name": tool_name,
+        "name": &tool_name,
         "arguments": arguments,
     });
 
-    let result = conn.send_request("tools/call", Some(params)).await?;
+    let result = conn
+        .send_request_for_camp("tools/call", Some(params), camp_id.as_deref())
+        .await
+        .map_err(|err| BasecampError::mcp(err).with_context("tool_name", tool_name.clone()))?;
 
     // Parse MCP tool result format
     let is_error = result
@@ -641,7 +1371,56 @@ pub async fn mcp_call_tool(
             }]
         });
 
-    Ok(McpToolResult { content, is_error })
+    let tool_result = McpToolResult { content, is_error };
+    if cacheable && !is_error {
+        state.mcp.lock().await.cache_result(
+            cache_key,
+            tool_result.clone(),
+            cache_ttl,
+            cache_generation,
+        );
+    }
+
+    Ok(tool_result)
+}
+
+fn load_tool_cache_ttl(connection: &Connection) -> Duration {
+    let seconds = get_setting_value(connection, SETTING_MCP_TOOL_CACHE_TTL)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_TOOL_CACHE_TTL_SECS);
+    Duration::from_secs(seconds.min(MAX_TOOL_CACHE_TTL_SECS))
+}
+
+/// How long results of read-only MCP tools are reused, in seconds. 0 is off.
+#[tauri::command]
+pub fn get_mcp_tool_cache_ttl(window: Window, state: State<'_, AppState>) -> CommandResult<u64> {
+    ensure_main_window(&window)?;
+    let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    Ok(load_tool_cache_ttl(&db).as_secs())
+}
+
+#[tauri::command]
+pub async fn set_mcp_tool_cache_ttl(
+    window: Window,
+    state: State<'_, AppState>,
+    seconds: u64,
+) -> CommandResult<u64> {
+    ensure_main_window(&window)?;
+    if seconds > MAX_TOOL_CACHE_TTL_SECS {
+        return Err(BasecampError::validation(format!(
+            "Cache TTL must be at most {MAX_TOOL_CACHE_TTL_SECS} seconds."
+        )));
+    }
+    {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        set_setting_value(&db, SETTING_MCP_TOOL_CACHE_TTL, &seconds.to_string())?;
+    }
+    if seconds == 0 {
+        state.mcp.lock().await.clear_cached_results(None);
+    }
+    Ok(seconds)
 }
 
 // ---------------------------------------------------------------------------
@@ -701,6 +1480,59 @@ mod tests {
         assert_eq!(err.message, "Invalid request");
     }
 
+    #[test]
+    fn test_classify_incoming_separates_server_requests() {
+        let request = r#"{"jsonrpc":"2.0","id":"s-1","method":"sampling/createMessage","params":{"maxTokens":10}}"#;
+        match classify_incoming(request).unwrap() {
+            IncomingMessage::Request { id, method, params } => {
+                assert_eq!(id, "s-1");
+                assert_eq!(method, "sampling/createMessage");
+                assert_eq!(params["maxTokens"], 10);
+            }
+            _ => panic!("Expected server request"),
+        }
+
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/message","params":{}}"#;
+        assert!(matches!(
+            classify_incoming(notification).unwrap(),
+            IncomingMessage::Notification
+        ));
+
+        let response = r#"{"jsonrpc":"2.0","id":3,"result":{}}"#;
+        match classify_incoming(response).unwrap() {
+            IncomingMessage::Response(response) => assert_eq!(response.id, Some(3)),
+            _ => panic!("Expected response"),
+        }
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_responses_reach_their_own_waiters_in_any_order() {
+        let pending = PendingRequests::default();
+        let first = register_waiter(&pending, 1).unwrap();
+        let second = register_waiter(&pending, 2).unwrap();
+        let response = |id: u64| JsonRpcResponse {
+            id: Some(id),
+            result: Some(serde_json::json!({ "for": id })),
+            error: None,
+        };
+
+        deliver_response(&pending, response(2));
+        deliver_response(&pending, response(1));
+        let second = await_response(second, Duration::from_secs(1))
+            .await
+            .unwrap();
+        let first = await_response(first, Duration::from_secs(1)).await.unwrap();
+        assert_eq!(second.result.unwrap()["for"], 2);
+        assert_eq!(first.result.unwrap()["for"], 1);
+        assert!(pending.lock().unwrap().is_empty());
+
+        let silent = register_waiter(&pending, 3).unwrap();
+        let err = await_response(silent, Duration::from_millis(10))
+            .await
+            .unwrap_err();
+        assert!(err.contains("did not respond"));
+    }
+
     #[test]
     fn test_transport_serde_roundtrip_stdio() {
         let transport = McpTransport::Stdio {
@@ -754,7 +1586,7 @@ mod tests {
         let tools = parse_tool_defs("test-server", &result);
         assert_eq!(tools.len(), 1);
         assert_eq!(tools[0].name, "echo");
-        assert_eq!(tools[0].qualified_name, "test-server/echo");
+        assert_eq!(tools[0].qualified_name, "mcp__test-server__echo");
         assert_eq!(tools[0].description, "Echo a message");
         assert!(!tools[0].read_only);
     }
@@ -797,7 +1629,130 @@ mod tests {
             "tools": [{ "name": "my_tool", "inputSchema": {} }]
         });
         let tools = parse_tool_defs("my-server", &result);
-        assert_eq!(tools[0].qualified_name, "my-server/my_tool");
+        assert_eq!(tools[0].qualified_name, "mcp__my-server__my_tool");
+    }
+
+    #[test]
+    fn test_server_config_update_enable_and_delete() {
+        let connection = Connection::open_in_memory().unwrap();
+        create_mcp_servers_table(&connection).unwrap();
+        let mut config = McpServerConfig {
+            id: "srv".to_string(),
+            name: "Server".to_string(),
+            transport: McpTransport::Stdio {
+                command: "node".to_string(),
+                args: vec![],
+            },
+            enabled: true,
+            auth_token: None,
+        };
+        insert_server_config(&connection, &config).unwrap();
+
+        config.name = "Renamed".to_string();
+        config.transport = McpTransport::Sse {
+            url: "http://localhost:3000/mcp".to_string(),
+        };
+        assert!(update_server_config(&connection, &config).unwrap());
+        assert!(set_server_enabled(&connection, "srv", false).unwrap());
+        let stored = find_server_config(&connection, "srv").unwrap();
+        assert_eq!(stored.name, "Renamed");
+        assert!(matches!(stored.transport, McpTransport::Sse { .. }));
+        assert!(!stored.enabled);
+
+        assert!(delete_server_config(&connection, "srv").unwrap());
+        assert!(!delete_server_config(&connection, "srv").unwrap());
+        assert!(!set_server_enabled(&connection, "srv", true).unwrap());
+        assert!(find_server_config(&connection, "srv").is_err());
+    }
+
+    #[test]
+    fn test_stderr_log_keeps_most_recent_lines() {
+        let log = StderrLog::default();
+        for index in 0..MAX_STDERR_LINES + 5 {
+            log.push(format!("line {index}"));
+        }
+        let all = log.tail(MAX_STDERR_LINES);
+        assert_eq!(all.len(), MAX_STDERR_LINES);
+        assert_eq!(all[0], "line 5");
+        assert_eq!(
+            log.tail(2),
+            vec![
+                format!("line {}", MAX_STDERR_LINES + 3),
+                format!("line {}", MAX_STDERR_LINES + 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tool_result_cache_is_keyed_by_arguments_and_expires() {
+        let first = tool_cache_key("srv", "list", &serde_json::json!({"a": 1, "b": [2]}));
+        let reordered = tool_cache_key(
+            "srv",
+            "list",
+            &serde_json::from_str(r#"{"b":[2],"a":1}"#).unwrap(),
+        );
+        assert_eq!(first, reordered);
+        assert_ne!(
+            first,
+            tool_cache_key("srv", "list", &serde_json::json!({"a": 2}))
+        );
+
+        let mut mcp = McpConnections::new();
+        mcp.read_only_tools
+            .insert("srv".to_string(), HashSet::from(["list".to_string()]));
+        assert!(mcp.is_read_only_tool("srv", "list"));
+        assert!(!mcp.is_read_only_tool("srv", "delete"));
+
+        let result = McpToolResult {
+            content: vec![],
+            is_error: false,
+        };
+        mcp.cache_result(first.clone(), result.clone(), Duration::from_secs(30), 0);
+        assert!(mcp.cached_result(&first, Duration::from_secs(30)).is_some());
+        assert!(mcp.cached_result(&first, Duration::ZERO).is_none());
+
+        // Once results are cleared, a call that began before then cannot put
+        // its result back.
+        mcp.clear_cached_results(None);
+        assert!(mcp.cached_result(&first, Duration::from_secs(30)).is_none());
+        mcp.cache_result(first.clone(), result.clone(), Duration::from_secs(30), 0);
+        assert!(mcp.cached_result(&first, Duration::from_secs(30)).is_none());
+        let generation = mcp.cache_generation;
+        mcp.cache_result(first.clone(), result, Duration::from_secs(30), generation);
+
+        mcp.disconnect("srv");
+        assert!(mcp.cached_result(&first, Duration::from_secs(30)).is_none());
+        assert!(!mcp.is_read_only_tool("srv", "list"));
+    }
+
+    #[test]
+    fn test_tool_arguments_are_checked_against_the_discovered_schema() {
+        let mut mcp = McpConnections::new();
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": { "query": { "type": "string" } },
+            "required": ["query"]
+        });
+        mcp.input_schemas.insert(
+            "srv".to_string(),
+            HashMap::from([("search".to_string(), schema)]),
+        );
+
+        assert!(mcp
+            .validate_arguments("srv", "search", &serde_json::json!({"query": "tents"}))
+            .is_ok());
+        let errors = mcp
+            .validate_arguments("srv", "search", &serde_json::json!({"query": 7}))
+            .expect_err("arguments should be rejected");
+        assert_eq!(errors[0].path, "/query");
+        assert!(mcp
+            .validate_arguments("srv", "unknown", &serde_json::json!({"query": 7}))
+            .is_ok());
+
+        mcp.disconnect("srv");
+        assert!(mcp
+            .validate_arguments("srv", "search", &serde_json::json!({}))
+            .is_ok());
     }
 
     #[test]
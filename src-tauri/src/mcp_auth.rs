@@ -0,0 +1,573 @@
+//! OAuth 2.1 authorization-code flow with PKCE for remote MCP servers.
+//!
+//! The user signs in through the system browser; the authorization code comes
+//! back to a one-shot listener on 127.0.0.1. Tokens are kept in the OS keyring
+//! per server and refreshed shortly before they expire.
+
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use keyring::{Entry, Error as KeyringError};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::State;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::mcp::{find_server_config, McpTransport};
+use crate::{now_timestamp_ms, AppState};
+
+const KEYRING_SERVICE: &str = "com.basecamp.app";
+const CALLBACK_PATH: &str = "/callback";
+const CALLBACK_TIMEOUT: Duration = Duration::from_secs(300);
+/// Refresh this long before the access token actually expires.
+const REFRESH_MARGIN_MS: i64 = 60_000;
+const CALLBACK_PAGE: &str = "<!doctype html><html><body style=\"font-family:sans-serif\">\
+<h3>Basecamp is connected.</h3><p>You can close this window.</p></body></html>";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredOAuthTokens {
+    client_id: String,
+    token_endpoint: String,
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    /// Unix milliseconds; `None` when the server did not say.
+    #[serde(default)]
+    expires_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpOAuthStatus {
+    pub server_id: String,
+    pub authorized: bool,
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorizationServerMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    registration_endpoint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<i64>,
+}
+
+struct PkcePair {
+    verifier: String,
+    challenge: String,
+}
+
+fn pkce_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+fn new_pkce_pair() -> PkcePair {
+    // Two v4 UUIDs give 244 random bits in 64 unreserved characters.
+    let verifier = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    PkcePair {
+        challenge: pkce_challenge(&verifier),
+        verifier,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Keyring
+// ---------------------------------------------------------------------------
+
+fn oauth_entry(server_id: &str) -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, &format!("mcp_oauth:{server_id}"))
+        .map_err(|e| format!("Keyring error: {e}"))
+}
+
+fn load_tokens(server_id: &str) -> Result<Option<StoredOAuthTokens>, String> {
+    match oauth_entry(server_id)?.get_password() {
+        Ok(raw) => Ok(serde_json::from_str(&raw).ok()),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Unable to read OAuth tokens: {e}")),
+    }
+}
+
+fn store_tokens(server_id: &str, tokens: &StoredOAuthTokens) -> Result<(), String> {
+    let raw = serde_json::to_string(tokens).map_err(|e| e.to_string())?;
+    oauth_entry(server_id)?
+        .set_password(&raw)
+        .map_err(|e| format!("Unable to store OAuth tokens: {e}"))
+}
+
+/// Removes any stored OAuth tokens for a server.
+pub(crate) fn clear_tokens(server_id: &str) -> Result<(), String> {
+    match oauth_entry(server_id)?.delete_password() {
+        Ok(()) | Err(KeyringError::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Unable to delete OAuth tokens: {e}")),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Token endpoint
+// ---------------------------------------------------------------------------
+
+fn tokens_from_response(
+    response: TokenResponse,
+    client_id: &str,
+    token_endpoint: &str,
+    previous_refresh_token: Option<String>,
+) -> StoredOAuthTokens {
+    StoredOAuthTokens {
+        client_id: client_id.to_string(),
+        token_endpoint: token_endpoint.to_string(),
+        access_token: response.access_token,
+        // Servers that do not rotate refresh tokens omit them on refresh.
+        refresh_token: response.refresh_token.or(previous_refresh_token),
+        expires_at: response
+            .expires_in
+            .map(|seconds| now_timestamp_ms() + seconds * 1_000),
+    }
+}
+
+async fn post_token_request(
+    client: &reqwest::Client,
+    token_endpoint: &str,
+    form: &[(&str, &str)],
+) -> Result<TokenResponse, String> {
+    let response = client
+        .post(token_endpoint)
+        .form(form)
+        .send()
+        .await
+        .map_err(|e| format!("OAuth token request failed: {e}"))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("Unable to read OAuth token response: {e}"))?;
+    if !status.is_success() {
+        return Err(format!("OAuth token endpoint returned {status}: {body}"));
+    }
+    serde_json::from_str(&body).map_err(|e| format!("Invalid OAuth token response: {e}"))
+}
+
+async fn refresh_tokens(
+    client: &reqwest::Client,
+    server_id: &str,
+    tokens: StoredOAuthTokens,
+) -> Result<StoredOAuthTokens, String> {
+    let refresh_token = tokens
+        .refresh_token
+        .clone()
+        .ok_or("OAuth session expired; sign in to the server again.")?;
+    let response = post_token_request(
+        client,
+        &tokens.token_endpoint,
+        &[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("client_id", &tokens.client_id),
+        ],
+    )
+    .await?;
+    let refreshed = tokens_from_response(
+        response,
+        &tokens.client_id,
+        &tokens.token_endpoint,
+        Some(refresh_token),
+    );
+    store_tokens(server_id, &refreshed)?;
+    Ok(refreshed)
+}
+
+fn needs_refresh(tokens: &StoredOAuthTokens, now_ms: i64) -> bool {
+    tokens
+        .expires_at
+        .is_some_and(|expires_at| expires_at - REFRESH_MARGIN_MS <= now_ms)
+}
+
+/// Returns a usable access token for the server, refreshing it first when it is
+/// close to expiry. `Ok(None)` means the server has no OAuth session.
+pub(crate) async fn access_token(
+    client: &reqwest::Client,
+    server_id: &str,
+) -> Result<Option<String>, String> {
+    let Some(tokens) = load_tokens(server_id)? else {
+        return Ok(None);
+    };
+    if !needs_refresh(&tokens, now_timestamp_ms()) {
+        return Ok(Some(tokens.access_token));
+    }
+    Ok(Some(
+        refresh_tokens(client, server_id, tokens)
+            .await?
+            .access_token,
+    ))
+}
+
+/// Forces a refresh after the server rejected the current access token.
+pub(crate) async fn refresh_access_token(
+    client: &reqwest::Client,
+    server_id: &str,
+) -> Result<Option<String>, String> {
+    match load_tokens(server_id)? {
+        Some(tokens) => Ok(Some(
+            refresh_tokens(client, server_id, tokens)
+                .await?
+                .access_token,
+        )),
+        None => Ok(None),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Authorization flow
+// ---------------------------------------------------------------------------
+
+/// Reads the server's OAuth metadata, falling back to the default endpoint paths
+/// the MCP spec prescribes when the well-known document is missing.
+async fn discover_metadata(
+    client: &reqwest::Client,
+    server_url: &Url,
+) -> Result<AuthorizationServerMetadata, String> {
+    let origin = server_url.origin().ascii_serialization();
+    let well_known = format!("{origin}/.well-known/oauth-authorization-server");
+    if let Ok(response) = client.get(&well_known).send().await {
+        if response.status().is_success() {
+            return response
+                .json::<AuthorizationServerMetadata>()
+                .await
+                .map_err(|e| format!("Invalid OAuth metadata at {well_known}: {e}"));
+        }
+    }
+    Ok(AuthorizationServerMetadata {
+        authorization_endpoint: format!("{origin}/authorize"),
+        token_endpoint: format!("{origin}/token"),
+        registration_endpoint: Some(format!("{origin}/register")),
+    })
+}
+
+async fn register_client(
+    client: &reqwest::Client,
+    registration_endpoint: &str,
+    redirect_uri: &str,
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "client_name": "Basecamp",
+        "redirect_uris": [redirect_uri],
+        "grant_types": ["authorization_code", "refresh_token"],
+        "response_types": ["code"],
+        "token_endpoint_auth_method": "none",
+    });
+    let response = client
+        .post(registration_endpoint)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("OAuth client registration failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "OAuth client registration returned {}",
+            response.status()
+        ));
+    }
+    let registered: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid OAuth registration response: {e}"))?;
+    registered
+        .get("client_id")
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+        .ok_or_else(|| "OAuth registration response has no client_id".to_string())
+}
+
+pub(crate) fn open_in_browser(url: &str) -> Result<(), String> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        // `cmd /C start` would reparse the URL, splitting it at every `&`.
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Unable to open the system browser: {e}"))
+}
+
+/// Pulls `code` out of the callback request line, checking `state` matches.
+fn parse_callback(request_line: &str, expected_state: &str) -> Result<String, String> {
+    let target = request_line
+        .split_whitespace()
+        .nth(1)
+        .ok_or("Malformed OAuth callback request")?;
+    let url = Url::parse(&format!("http://127.0.0.1{target}"))
+        .map_err(|e| format!("Malformed OAuth callback URL: {e}"))?;
+    if url.path() != CALLBACK_PATH {
+        return Err(format!("Unexpected OAuth callback path: {}", url.path()));
+    }
+    let mut code = None;
+    let mut state = None;
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "code" => code = Some(value.into_owned()),
+            "state" => state = Some(value.into_owned()),
+            "error" => return Err(format!("Authorization was denied: {value}")),
+            _ => {}
+        }
+    }
+    if state.as_deref() != Some(expected_state) {
+        return Err("OAuth callback state did not match; try signing in again.".to_string());
+    }
+    code.ok_or_else(|| "OAuth callback did not include a code".to_string())
+}
+
+/// True when the request line targets the callback path, whatever its query.
+fn is_callback_request(request_line: &str) -> bool {
+    request_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|target| target.split('?').next())
+        .is_some_and(|path| path == CALLBACK_PATH)
+}
+
+/// Serves requests until the browser lands on the callback path. Anything
+/// else the browser fetches first, such as `/favicon.ico`, gets a 404.
+async fn wait_for_callback(listener: TcpListener, expected_state: &str) -> Result<String, String> {
+    let deadline = tokio::time::Instant::now() + CALLBACK_TIMEOUT;
+    loop {
+        let (mut socket, _) = tokio::time::timeout_at(deadline, listener.accept())
+            .await
+            .map_err(|_| "Timed out waiting for the browser sign-in to finish.".to_string())?
+            .map_err(|e| format!("OAuth callback listener failed: {e}"))?;
+
+        let (reader, mut writer) = socket.split();
+        let mut request_line = String::new();
+        let read = tokio::time::timeout_at(
+            deadline,
+            BufReader::new(reader).read_line(&mut request_line),
+        )
+        .await
+        .map_err(|_| "Timed out waiting for the browser sign-in to finish.".to_string())?;
+        if read.is_err() {
+            continue;
+        }
+
+        let is_callback = is_callback_request(&request_line);
+        let response = if is_callback {
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{CALLBACK_PAGE}",
+                CALLBACK_PAGE.len()
+            )
+        } else {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        };
+        let _ = writer.write_all(response.as_bytes()).await;
+        let _ = writer.shutdown().await;
+
+        if is_callback {
+            return parse_callback(&request_line, expected_state);
+        }
+    }
+}
+
+async fn authorize(
+    client: &reqwest::Client,
+    server_id: &str,
+    server_url: &str,
+) -> Result<StoredOAuthTokens, String> {
+    let server_url = Url::parse(server_url).map_err(|e| format!("Invalid server URL: {e}"))?;
+    let metadata = discover_metadata(client, &server_url).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Unable to start the OAuth callback listener: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Unable to read the OAuth callback port: {e}"))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{CALLBACK_PATH}");
+
+    let client_id = match &metadata.registration_endpoint {
+        Some(endpoint) => register_client(client, endpoint, &redirect_uri).await?,
+        None => {
+            return Err("This MCP server does not support dynamic client registration.".to_string())
+        }
+    };
+
+    let pkce = new_pkce_pair();
+    let state = Uuid::new_v4().simple().to_string();
+    let authorize_url = Url::parse_with_params(
+        &metadata.authorization_endpoint,
+        &[
+            ("response_type", "code"),
+            ("client_id", client_id.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("code_challenge", pkce.challenge.as_str()),
+            ("code_challenge_method", "S256"),
+            ("state", state.as_str()),
+        ],
+    )
+    .map_err(|e| format!("Invalid authorization endpoint: {e}"))?;
+
+    open_in_browser(authorize_url.as_str())?;
+    let code = wait_for_callback(listener, &state).await?;
+
+    let response = post_token_request(
+        client,
+        &metadata.token_endpoint,
+        &[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect_uri),
+            ("client_id", &client_id),
+            ("code_verifier", &pkce.verifier),
+        ],
+    )
+    .await?;
+    let tokens = tokens_from_response(response, &client_id, &metadata.token_endpoint, None);
+    store_tokens(server_id, &tokens)?;
+    Ok(tokens)
+}
+
+// ---------------------------------------------------------------------------
+// Tauri commands
+// ---------------------------------------------------------------------------
+
+fn oauth_status(server_id: &str, tokens: Option<&StoredOAuthTokens>) -> McpOAuthStatus {
+    McpOAuthStatus {
+        server_id: server_id.to_string(),
+        authorized: tokens.is_some(),
+        expires_at: tokens.and_then(|tokens| tokens.expires_at),
+    }
+}
+
+/// Signs in to a remote MCP server in the system browser. Any open connection is
+/// dropped so the next request reconnects with the new token.
+#[tauri::command]
+pub async fn mcp_oauth_authorize(
+    state: State<'_, AppState>,
+    server_id: String,
+) -> CommandResult<McpOAuthStatus> {
+    let config = {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        find_server_config(&db, &server_id)?
+    };
+    let McpTransport::Sse { url } = &config.transport else {
+        return Err(
+            BasecampError::validation("OAuth is only used by remote (SSE) MCP servers.")
+                .with_context("server_id", server_id),
+        );
+    };
+
+    let tokens = authorize(&state.provider_client, &server_id, url)
+        .await
+        .map_err(|err| BasecampError::mcp(err).with_context("server_id", server_id.clone()))?;
+    state.mcp.lock().await.disconnect(&server_id);
+    Ok(oauth_status(&server_id, Some(&tokens)))
+}
+
+#[tauri::command]
+pub fn mcp_oauth_status(server_id: String) -> CommandResult<McpOAuthStatus> {
+    let tokens = load_tokens(&server_id).map_err(BasecampError::internal)?;
+    Ok(oauth_status(&server_id, tokens.as_ref()))
+}
+
+#[tauri::command]
+pub async fn mcp_oauth_sign_out(
+    state: State<'_, AppState>,
+    server_id: String,
+) -> CommandResult<McpOAuthStatus> {
+    clear_tokens(&server_id).map_err(BasecampError::internal)?;
+    state.mcp.lock().await.disconnect(&server_id);
+    Ok(oauth_status(&server_id, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pkce_challenge_matches_rfc_7636_example() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        let pair = new_pkce_pair();
+        assert_eq!(pair.verifier.len(), 64);
+        assert_eq!(pair.challenge, pkce_challenge(&pair.verifier));
+    }
+
+    #[test]
+    fn parse_callback_checks_state_and_extracts_code() {
+        let line = "GET /callback?code=abc%2F123&state=s1 HTTP/1.1\r\n";
+        assert_eq!(parse_callback(line, "s1").unwrap(), "abc/123");
+        assert!(parse_callback(line, "other").is_err());
+        assert!(
+            parse_callback("GET /callback?error=access_denied&state=s1 HTTP/1.1", "s1").is_err()
+        );
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn wait_for_callback_should_skip_requests_for_other_paths() {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("listener should bind");
+        let address = listener.local_addr().expect("listener has an address");
+        let waiter = tokio::spawn(async move { wait_for_callback(listener, "s1").await });
+
+        for target in ["/favicon.ico", "/callback?code=abc&state=s1"] {
+            let mut stream = tokio::net::TcpStream::connect(address)
+                .await
+                .expect("listener should accept");
+            stream
+                .write_all(format!("GET {target} HTTP/1.1\r\n\r\n").as_bytes())
+                .await
+                .expect("request should send");
+            let mut response = String::new();
+            tokio::io::AsyncReadExt::read_to_string(&mut stream, &mut response)
+                .await
+                .expect("response should arrive");
+            let expected = if target == "/favicon.ico" {
+                "404"
+            } else {
+                "200"
+            };
+            assert!(response.starts_with(&format!("HTTP/1.1 {expected}")));
+        }
+
+        assert_eq!(waiter.await.expect("waiter should finish").unwrap(), "abc");
+        assert!(!is_callback_request("GET /callbacks?code=x HTTP/1.1"));
+    }
+
+    #[test]
+    fn refresh_keeps_previous_refresh_token_and_respects_margin() {
+        let tokens = tokens_from_response(
+            TokenResponse {
+                access_token: "a2".to_string(),
+                refresh_token: None,
+                expires_in: Some(3_600),
+            },
+            "client",
+            "https://auth.example.com/token",
+            Some("r1".to_string()),
+        );
+        assert_eq!(tokens.refresh_token.as_deref(), Some("r1"));
+        let expires_at = tokens.expires_at.expect("expiry should be set");
+        assert!(!needs_refresh(&tokens, expires_at - REFRESH_MARGIN_MS - 1));
+        assert!(needs_refresh(&tokens, expires_at - REFRESH_MARGIN_MS));
+    }
+}
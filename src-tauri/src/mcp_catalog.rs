@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::mcp::{
+    discover_tools, find_server_config, register_server, remove_server, McpServerConfig,
+    McpToolDef, McpTransport,
+};
+use crate::AppState;
+
+/// Curated servers shipped with the app. Every entry installs through `npx` or
+/// `uvx`, so nothing is downloaded until the user picks one.
+const CATALOG_JSON: &str = include_str!("mcp_catalog.json");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpCatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    pub transport: McpTransport,
+    /// Set when the server needs user-supplied arguments, e.g. a directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct McpInstallFromCatalogPayload {
+    pub entry_id: String,
+    /// Appended to the catalog entry's own arguments.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    #[serde(default)]
+    pub auth_token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct McpCatalogInstallResult {
+    pub server: McpServerConfig,
+    pub tools: Vec<McpToolDef>,
+}
+
+fn load_catalog() -> CommandResult<Vec<McpCatalogEntry>> {
+    serde_json::from_str(CATALOG_JSON)
+        .map_err(|err| BasecampError::internal(format!("Bundled MCP catalog is invalid: {err}")))
+}
+
+fn server_config_for(
+    entry: McpCatalogEntry,
+    extra_args: Vec<String>,
+    auth_token: Option<String>,
+) -> CommandResult<McpServerConfig> {
+    let extra_args: Vec<String> = extra_args
+        .into_iter()
+        .map(|arg| arg.trim().to_string())
+        .filter(|arg| !arg.is_empty())
+        .collect();
+    if let Some(hint) = entry.argument_hint.as_deref() {
+        if extra_args.is_empty() {
+            return Err(
+                BasecampError::validation(format!("{} needs: {hint}", entry.name))
+                    .with_context("entry_id", entry.id),
+            );
+        }
+    }
+
+    let transport = match entry.transport {
+        McpTransport::Stdio { command, mut args } => {
+            args.extend(extra_args);
+            McpTransport::Stdio { command, args }
+        }
+        transport @ McpTransport::Sse { .. } => transport,
+    };
+    Ok(McpServerConfig {
+        id: entry.id,
+        name: entry.name,
+        transport,
+        enabled: true,
+        auth_token,
+    })
+}
+
+#[tauri::command]
+pub fn mcp_list_catalog() -> CommandResult<Vec<McpCatalogEntry>> {
+    load_catalog()
+}
+
+/// Registers a catalog server and connects to it once to list its tools. When
+/// the test connection fails the registration is rolled back, so a missing
+/// `npx`/`uvx` never leaves a broken server behind.
+#[tauri::command]
+pub async fn mcp_install_from_catalog(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: McpInstallFromCatalogPayload,
+) -> CommandResult<McpCatalogInstallResult> {
+    let entry = load_catalog()?
+        .into_iter()
+        .find(|entry| entry.id == payload.entry_id)
+        .ok_or_else(|| {
+            BasecampError::not_found(format!("No catalog entry `{}`", payload.entry_id))
+                .with_context("entry_id", payload.entry_id.clone())
+        })?;
+
+    {
+        let db = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        if find_server_config(&db, &entry.id).is_ok() {
+            return Err(
+                BasecampError::validation(format!("{} is already installed.", entry.name))
+                    .with_context("server_id", entry.id),
+            );
+        }
+    }
+
+    let config = server_config_for(entry, payload.extra_args, payload.auth_token)?;
+    let server = register_server(&state, config)?;
+    match discover_tools(&app, &state, &server.id).await {
+        Ok(tools) => Ok(McpCatalogInstallResult { server, tools }),
+        Err(err) => {
+            let _ = remove_server(&state, &server.id).await;
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn bundled_catalog_should_parse_with_unique_npx_or_uvx_entries() {
+        let catalog = load_catalog().expect("catalog should parse");
+        let ids: HashSet<&str> = catalog.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids.len(), catalog.len());
+        assert!(catalog.iter().all(|entry| matches!(
+            &entry.transport,
+            McpTransport::Stdio { command, .. } if command == "npx" || command == "uvx"
+        )));
+
+        let filesystem = catalog
+            .iter()
+            .find(|entry| entry.id == "filesystem")
+            .cloned()
+            .expect("filesystem entry should exist");
+        assert!(server_config_for(filesystem.clone(), vec![" ".to_string()], None).is_err());
+        let config = server_config_for(filesystem, vec!["/tmp/notes".to_string()], None)
+            .expect("config should build");
+        match config.transport {
+            McpTransport::Stdio { args, .. } => assert_eq!(args.last().unwrap(), "/tmp/notes"),
+            _ => panic!("Expected Stdio transport"),
+        }
+    }
+}
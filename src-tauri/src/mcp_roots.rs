@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use reqwest::Url;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use tauri::State;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::mcp::broadcast_notification;
+use crate::{
+    camp_context_dir, ensure_camps_root, read_camp_config, require_workspace_path,
+    resolve_existing_camp_dir, workspace_context_dir, AppState,
+};
+
+fn root_entry(path: &Path, name: &str) -> Option<Value> {
+    if !path.is_dir() {
+        return None;
+    }
+    let uri = Url::from_directory_path(path).ok()?;
+    Some(json!({ "uri": uri.as_str(), "name": name }))
+}
+
+/// The filesystem roots offered to MCP servers: the active camp's context folder
+/// and the shared workspace context folder, when they exist.
+fn collect_roots(connection: &Connection, active_camp: Option<&str>) -> Vec<Value> {
+    let mut roots = Vec::new();
+    if let Some(camp_id) = active_camp {
+        let camp_dir = ensure_camps_root(connection)
+            .and_then(|camps_root| resolve_existing_camp_dir(&camps_root, camp_id));
+        if let Ok(camp_dir) = camp_dir {
+            let name = read_camp_config(&camp_dir)
+                .map(|config| format!("{} context", config.name))
+                .unwrap_or_else(|_| "Camp context".to_string());
+            roots.extend(root_entry(&camp_context_dir(&camp_dir), &name));
+        }
+    }
+    if let Ok(workspace_path) = require_workspace_path(connection) {
+        roots.extend(root_entry(
+            &workspace_context_dir(&workspace_path),
+            "Workspace context",
+        ));
+    }
+    roots
+}
+
+/// Answers a server's `roots/list` request.
+pub(crate) fn list_roots(state: &AppState) -> Result<Value, String> {
+    let active_camp = state
+        .mcp_active_camp
+        .lock()
+        .map_err(|_| "Active camp lock error".to_string())?
+        .clone();
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    Ok(json!({ "roots": collect_roots(&connection, active_camp.as_deref()) }))
+}
+
+/// Records the camp the user is looking at and tells connected servers their
+/// roots changed so they can ask again.
+#[tauri::command]
+pub async fn mcp_set_active_camp(
+    state: State<'_, AppState>,
+    camp_id: Option<String>,
+) -> CommandResult<()> {
+    {
+        let mut active_camp = state
+            .mcp_active_camp
+            .lock()
+            .map_err(|_| BasecampError::lock())?;
+        if *active_camp == camp_id {
+            return Ok(());
+        }
+        *active_camp = camp_id;
+    }
+    // Read-only results may depend on the roots a server was given.
+    state.mcp.lock().await.clear_cached_results(None);
+    broadcast_notification(&state, "notifications/roots/list_changed").await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn root_entry_should_use_file_uris_for_existing_directories() {
+        let dir = make_temp_dir("basecamp-roots");
+
+        let root = root_entry(&dir, "Camp context").expect("existing dir should be a root");
+        let uri = root["uri"].as_str().expect("uri should be a string");
+        assert!(uri.starts_with("file://"));
+        assert!(uri.ends_with('/'));
+        assert_eq!(root["name"], "Camp context");
+        assert!(root_entry(&dir.join("missing"), "Missing").is_none());
+    }
+}
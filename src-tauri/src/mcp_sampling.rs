@@ -0,0 +1,368 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, State, Window};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use crate::{
+    commands::team::send_chat_request,
+    ensure_camps_root, ensure_main_window,
+    error::{BasecampError, CommandResult},
+    get_setting_value, parse_model_reference,
+    providers::{BasecampChatMetadata, BasecampChatRequest, RequestPriority},
+    read_camp_config, resolve_existing_camp_dir, set_setting_value, AppState, DEFAULT_CAMP_MODEL,
+    SETTING_DEFAULT_MODEL,
+};
+
+const SETTING_MCP_SAMPLING_POLICY: &str = "mcp_sampling_policy";
+const MCP_SAMPLING_REQUEST_EVENT: &str = "mcp://sampling_request";
+const SAMPLING_APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+const MAX_SAMPLING_TOKENS: i64 = 4_096;
+
+/// How `sampling/createMessage` requests from MCP servers are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpSamplingPolicy {
+    Deny,
+    #[default]
+    Ask,
+    Allow,
+}
+
+impl McpSamplingPolicy {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Deny => "deny",
+            Self::Ask => "ask",
+            Self::Allow => "allow",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "deny" => Some(Self::Deny),
+            "ask" => Some(Self::Ask),
+            "allow" => Some(Self::Allow),
+            _ => None,
+        }
+    }
+}
+
+/// Sent to the renderer when a server asks for a completion under the `ask` policy.
+#[derive(Debug, Clone, Serialize)]
+struct SamplingRequestEvent {
+    request_id: String,
+    server_id: String,
+    camp_id: Option<String>,
+    model: String,
+    system_prompt: Option<String>,
+    messages: Vec<Value>,
+    max_tokens: i64,
+}
+
+/// Who is asking and on behalf of which camp, threaded into the MCP transport
+/// while a tool call is in flight.
+pub(crate) struct SamplingContext<'a> {
+    pub state: &'a AppState,
+    pub app: &'a AppHandle,
+    pub server_id: &'a str,
+    pub camp_id: Option<&'a str>,
+}
+
+fn load_sampling_policy(connection: &Connection) -> Result<McpSamplingPolicy, String> {
+    Ok(get_setting_value(connection, SETTING_MCP_SAMPLING_POLICY)
+        .map_err(|err| format!("Unable to load MCP sampling policy: {err}"))?
+        .and_then(|value| McpSamplingPolicy::parse(&value))
+        .unwrap_or_default())
+}
+
+/// Maps MCP sampling messages onto the OpenAI-style messages the providers take.
+fn to_chat_messages(params: &Value) -> Result<Vec<Value>, String> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = params
+        .get("systemPrompt")
+        .and_then(Value::as_str)
+        .filter(|prompt| !prompt.trim().is_empty())
+    {
+        messages.push(json!({ "role": "system", "content": system_prompt }));
+    }
+
+    let items = params
+        .get("messages")
+        .and_then(Value::as_array)
+        .ok_or("sampling/createMessage requires a messages array.")?;
+    for item in items {
+        let role = match item.get("role").and_then(Value::as_str) {
+            Some(role @ ("user" | "assistant")) => role,
+            _ => return Err("Sampling messages must have role `user` or `assistant`.".to_string()),
+        };
+        let content = item.get("content").unwrap_or(&Value::Null);
+        let content = match content.get("type").and_then(Value::as_str) {
+            Some("text") => json!(content
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default()),
+            Some("image") => {
+                let data = content
+                    .get("data")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                let mime_type = content
+                    .get("mimeType")
+                    .and_then(Value::as_str)
+                    .unwrap_or("image/png");
+                json!([{
+                    "type": "image_url",
+                    "image_url": { "url": format!("data:{mime_type};base64,{data}") },
+                }])
+            }
+            other => {
+                return Err(format!(
+                    "Unsupported sampling content type: {}",
+                    other.unwrap_or("missing")
+                ))
+            }
+        };
+        messages.push(json!({ "role": role, "content": content }));
+    }
+
+    if messages.iter().all(|message| message["role"] == "system") {
+        return Err("sampling/createMessage requires at least one message.".to_string());
+    }
+    Ok(messages)
+}
+
+fn stop_reason(finish_reason: Option<&str>) -> &str {
+    match finish_reason {
+        Some("length") => "maxTokens",
+        Some("stop") | None => "endTurn",
+        Some(other) => other,
+    }
+}
+
+/// Uses the camp's model when the tool call came from a camp, otherwise the
+/// default model from Settings.
+fn resolve_sampling_model(connection: &Connection, camp_id: Option<&str>) -> String {
+    let camp_model = camp_id.and_then(|camp_id| {
+        ensure_camps_root(connection)
+            .and_then(|camps_root| resolve_existing_camp_dir(&camps_root, camp_id))
+            .and_then(|camp_dir| read_camp_config(&camp_dir))
+            .ok()
+            .map(|config| config.model)
+    });
+    camp_model
+        .or_else(|| {
+            get_setting_value(connection, SETTING_DEFAULT_MODEL)
+                .ok()
+                .flatten()
+        })
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_CAMP_MODEL.to_string())
+}
+
+async fn wait_for_approval(
+    context: &SamplingContext<'_>,
+    event: SamplingRequestEvent,
+) -> Result<bool, String> {
+    let request_id = event.request_id.clone();
+    let (sender, receiver) = oneshot::channel();
+    context
+        .state
+        .mcp_sampling_approvals
+        .lock()
+        .map_err(|_| "Sampling approval lock error".to_string())?
+        .insert(request_id.clone(), sender);
+
+    if let Err(err) = context.app.emit(MCP_SAMPLING_REQUEST_EVENT, event) {
+        if let Ok(mut pending) = context.state.mcp_sampling_approvals.lock() {
+            pending.remove(&request_id);
+        }
+        return Err(format!("Unable to ask for sampling approval: {err}"));
+    }
+
+    match tokio::time::timeout(SAMPLING_APPROVAL_TIMEOUT, receiver).await {
+        Ok(Ok(approved)) => Ok(approved),
+        Ok(Err(_)) => Ok(false),
+        Err(_) => {
+            if let Ok(mut pending) = context.state.mcp_sampling_approvals.lock() {
+                pending.remove(&request_id);
+            }
+            Ok(false)
+        }
+    }
+}
+
+/// Handles a `sampling/createMessage` request from an MCP server and returns the
+/// JSON-RPC result. Errors are sent back to the server as-is.
+pub(crate) async fn create_message(
+    context: &SamplingContext<'_>,
+    params: &Value,
+) -> Result<Value, String> {
+    let (policy, model_reference) = {
+        let connection = context
+            .state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        (
+            load_sampling_policy(&connection)?,
+            resolve_sampling_model(&connection, context.camp_id),
+        )
+    };
+    if policy == McpSamplingPolicy::Deny {
+        return Err("Sampling requests are disabled in Basecamp Settings.".to_string());
+    }
+
+    let messages = to_chat_messages(params)?;
+    let max_tokens = params
+        .get("maxTokens")
+        .and_then(Value::as_i64)
+        .unwrap_or(MAX_SAMPLING_TOKENS)
+        .clamp(1, MAX_SAMPLING_TOKENS);
+
+    if policy == McpSamplingPolicy::Ask {
+        let event = SamplingRequestEvent {
+            request_id: Uuid::new_v4().to_string(),
+            server_id: context.server_id.to_string(),
+            camp_id: context.camp_id.map(ToString::to_string),
+            model: model_reference.clone(),
+            system_prompt: params
+                .get("systemPrompt")
+                .and_then(Value::as_str)
+                .map(ToString::to_string),
+            messages: params
+                .get("messages")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default(),
+            max_tokens,
+        };
+        if !wait_for_approval(context, event).await? {
+            return Err("User rejected sampling request".to_string());
+        }
+    }
+
+    let (provider_kind, model_id) = parse_model_reference(&model_reference);
+    let request = BasecampChatRequest {
+        provider_kind,
+        model_id,
+        messages,
+        tools: None,
+        tool_choice: None,
+        temperature: params.get("temperature").and_then(Value::as_f64),
+        max_tokens: Some(max_tokens),
+        top_p: None,
+        stop: params.get("stopSequences").and_then(|value| {
+            serde_json::from_value::<Vec<String>>(value.clone())
+                .ok()
+                .filter(|stop| !stop.is_empty())
+        }),
+        logit_bias: None,
+        stream: false,
+        metadata: BasecampChatMetadata {
+            camp_id: context.camp_id.map(ToString::to_string),
+            correlation_id: Some(format!("mcp-sampling-{}", Uuid::new_v4())),
+            provider_kind: Some(provider_kind),
+            priority: RequestPriority::Interactive,
+        },
+    };
+
+    let response = send_chat_request(context.state, request).await?;
+    Ok(json!({
+        "role": "assistant",
+        "content": { "type": "text", "text": response.output_text },
+        "model": response.resolved_model.unwrap_or(model_reference),
+        "stopReason": stop_reason(response.finish_reason.as_deref()),
+    }))
+}
+
+#[tauri::command]
+pub fn get_mcp_sampling_policy(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<McpSamplingPolicy, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_sampling_policy(&connection)
+}
+
+#[tauri::command]
+pub fn set_mcp_sampling_policy(
+    window: Window,
+    state: State<'_, AppState>,
+    policy: McpSamplingPolicy,
+) -> Result<McpSamplingPolicy, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(&connection, SETTING_MCP_SAMPLING_POLICY, policy.as_str())
+        .map_err(|err| format!("Unable to save MCP sampling policy: {err}"))?;
+    Ok(policy)
+}
+
+#[tauri::command]
+pub fn mcp_resolve_sampling_request(
+    window: Window,
+    state: State<'_, AppState>,
+    request_id: String,
+    approved: bool,
+) -> CommandResult<()> {
+    ensure_main_window(&window)?;
+    let sender = state
+        .mcp_sampling_approvals
+        .lock()
+        .map_err(|_| BasecampError::lock())?
+        .remove(&request_id)
+        .ok_or_else(|| {
+            BasecampError::not_found("Sampling request is no longer pending.")
+                .with_context("request_id", request_id.clone())
+        })?;
+    // The server may have given up already; there is nothing left to answer.
+    let _ = sender.send(approved);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_chat_messages_should_map_mcp_content_to_provider_messages() {
+        let params = json!({
+            "systemPrompt": "You summarize files.",
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "Summarize this" } },
+                { "role": "user", "content": { "type": "image", "data": "AAAA", "mimeType": "image/jpeg" } },
+            ],
+            "maxTokens": 200,
+        });
+
+        let messages = to_chat_messages(&params).expect("messages should convert");
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0]["role"], "system");
+        assert_eq!(messages[1]["content"], "Summarize this");
+        assert_eq!(
+            messages[2]["content"][0]["image_url"]["url"],
+            "data:image/jpeg;base64,AAAA"
+        );
+
+        assert!(to_chat_messages(&json!({ "messages": [] })).is_err());
+        assert!(to_chat_messages(&json!({
+            "messages": [{ "role": "tool", "content": { "type": "text", "text": "x" } }]
+        }))
+        .is_err());
+        assert_eq!(stop_reason(Some("length")), "maxTokens");
+        assert_eq!(
+            McpSamplingPolicy::parse("allow"),
+            Some(McpSamplingPolicy::Allow)
+        );
+    }
+}
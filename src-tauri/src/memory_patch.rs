@@ -0,0 +1,348 @@
+//! Incremental edits to a camp's `memory.json`. `camp_update_memory` replaces
+//! the whole document, so two writers that each read, edit and save lose one
+//! another's changes. `camp_patch_memory` instead applies an RFC 6902 JSON
+//! Patch or an RFC 7386 merge patch, and when the caller passes the version it
+//! last read, refuses to write over a newer one.
+//!
+//! The version is a hash of the memory's canonical JSON, so it changes with
+//! every edit no matter which command made it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, BasecampErrorKind, CommandResult};
+use crate::memory_schema;
+use crate::{
+    camp_memory_path, ensure_camps_root, ensure_main_window, read_json_file,
+    resolve_existing_camp_dir, touch_camp_updated_at, write_json_file, AppState,
+};
+
+/// One RFC 6902 operation.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "format", content = "patch", rename_all = "snake_case")]
+pub enum MemoryPatch {
+    JsonPatch(Vec<PatchOperation>),
+    MergePatch(Value),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampPatchMemoryPayload {
+    pub camp_id: String,
+    /// Version the caller last read. When set and the memory has changed
+    /// since, nothing is written and a `conflict` error comes back.
+    pub expected_version: Option<String>,
+    #[serde(flatten)]
+    pub patch: MemoryPatch,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemorySnapshot {
+    pub memory: Value,
+    pub version: String,
+}
+
+fn memory_version(memory: &Value) -> String {
+    // serde_json keeps object keys sorted, so equal documents hash the same
+    // however they were formatted on disk.
+    Sha256::digest(serde_json::to_vec(memory).unwrap_or_default())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// RFC 6901 pointer tokens; `~1` is `/` and `~0` is `~`.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("JSON pointer `{pointer}` must start with `/`."));
+    };
+    Ok(rest
+        .split('/')
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn array_index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    if token == "-" && allow_end {
+        return Ok(len);
+    }
+    let valid = !token.is_empty()
+        && token.bytes().all(|byte| byte.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'));
+    let index = valid
+        .then(|| token.parse::<usize>().ok())
+        .flatten()
+        .ok_or_else(|| format!("`{token}` is not a valid array index."))?;
+    let limit = if allow_end {
+        len
+    } else {
+        len.saturating_sub(1)
+    };
+    if index > limit || (!allow_end && len == 0) {
+        return Err(format!("Array index {index} is out of bounds."));
+    }
+    Ok(index)
+}
+
+fn resolve_mut<'a>(document: &'a mut Value, tokens: &[String]) -> Result<&'a mut Value, String> {
+    let mut current = document;
+    for token in tokens {
+        current = match current {
+            Value::Object(map) => map
+                .get_mut(token)
+                .ok_or_else(|| format!("Path segment `{token}` was not found."))?,
+            Value::Array(items) => {
+                let index = array_index(token, items.len(), false)?;
+                &mut items[index]
+            }
+            _ => {
+                return Err(format!(
+                    "Path segment `{token}` is not inside an object or array."
+                ))
+            }
+        };
+    }
+    Ok(current)
+}
+
+fn get_value(document: &Value, pointer: &str) -> Result<Value, String> {
+    let mut document = document.clone();
+    resolve_mut(&mut document, &parse_pointer(pointer)?).map(|value| value.clone())
+}
+
+fn add_value(document: &mut Value, pointer: &str, value: Value) -> Result<(), String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    match resolve_mut(document, parent_tokens)? {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(items) => {
+            let index = array_index(last, items.len(), true)?;
+            items.insert(index, value);
+        }
+        _ => {
+            return Err(format!(
+                "Cannot add at `{pointer}`: parent is not a container."
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn remove_value(document: &mut Value, pointer: &str) -> Result<Value, String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parent_tokens)) = tokens.split_last() else {
+        return Err("Cannot remove the whole memory document.".to_string());
+    };
+    match resolve_mut(document, parent_tokens)? {
+        Value::Object(map) => map
+            .remove(last)
+            .ok_or_else(|| format!("Path `{pointer}` was not found.")),
+        Value::Array(items) => {
+            let index = array_index(last, items.len(), false)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(format!("Path `{pointer}` was not found.")),
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &PatchOperation) -> Result<(), String> {
+    match operation {
+        PatchOperation::Add { path, value } => add_value(document, path, value.clone()),
+        PatchOperation::Remove { path } => remove_value(document, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            *resolve_mut(document, &parse_pointer(path)?)? = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path != from && path.starts_with(&format!("{from}/")) {
+                return Err(format!("Cannot move `{from}` into its own child `{path}`."));
+            }
+            let value = remove_value(document, from)?;
+            add_value(document, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = get_value(document, from)?;
+            add_value(document, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            if get_value(document, path)? == *value {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Test failed: `{path}` does not hold the expected value."
+                ))
+            }
+        }
+    }
+}
+
+/// RFC 7386: objects merge key by key, `null` deletes, anything else replaces.
+fn apply_merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target_map) = target {
+        for (key, value) in patch_map {
+            if value.is_null() {
+                target_map.remove(key);
+            } else {
+                apply_merge_patch(target_map.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Applies `patch` to a copy of `memory`. A JSON Patch is all or nothing: if
+/// any operation fails, the error names it and the memory is untouched.
+fn apply_patch(memory: &Value, patch: &MemoryPatch) -> CommandResult<Value> {
+    let mut patched = memory.clone();
+    match patch {
+        MemoryPatch::JsonPatch(operations) => {
+            for (index, operation) in operations.iter().enumerate() {
+                apply_operation(&mut patched, operation).map_err(|message| {
+                    BasecampError::validation(message).with_context("operation_index", index)
+                })?;
+            }
+        }
+        MemoryPatch::MergePatch(merge) => apply_merge_patch(&mut patched, merge),
+    }
+    Ok(patched)
+}
+
+/// Current memory with the version to pass back to `camp_patch_memory`.
+#[tauri::command]
+pub fn camp_get_memory(
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> CommandResult<MemorySnapshot> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let memory: Value = read_json_file(&camp_memory_path(&camp_dir))?;
+    Ok(MemorySnapshot {
+        version: memory_version(&memory),
+        memory,
+    })
+}
+
+/// Patches a camp's memory in place. The read, version check and write all
+/// happen under the database lock, so concurrent patches apply one after the
+/// other rather than overwriting each other.
+#[tauri::command]
+pub fn camp_patch_memory(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampPatchMemoryPayload,
+) -> CommandResult<MemorySnapshot> {
+    ensure_main_window(&window)?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let memory_path = camp_memory_path(&camp_dir);
+
+    let memory: Value = read_json_file(&memory_path)?;
+    let current_version = memory_version(&memory);
+    if let Some(expected) = payload.expected_version.as_deref() {
+        if expected != current_version {
+            return Err(BasecampError::new(
+                BasecampErrorKind::Conflict,
+                "Memory changed since it was read; reload it and apply the patch again.",
+            )
+            .retryable(true)
+            .with_context("expected_version", expected)
+            .with_context("current_version", current_version));
+        }
+    }
+
+    let patched = apply_patch(&memory, &payload.patch)?;
+    if patched != memory {
+        memory_schema::ensure_memory_matches_schema(&camp_dir, &patched)?;
+        write_json_file(&memory_path, &patched)?;
+        touch_camp_updated_at(&camp_dir)?;
+    }
+    Ok(MemorySnapshot {
+        version: memory_version(&patched),
+        memory: patched,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn apply_patch_should_follow_rfc_6902_and_7386() {
+        let memory = json!({ "facts": ["a", "b"], "owner": { "name": "Sam", "team": "ops" } });
+        let patch: MemoryPatch = serde_json::from_value(json!({
+            "format": "json_patch",
+            "patch": [
+                { "op": "test", "path": "/owner/name", "value": "Sam" },
+                { "op": "add", "path": "/facts/-", "value": "c" },
+                { "op": "remove", "path": "/facts/0" },
+                { "op": "move", "from": "/owner/team", "path": "/team" },
+                { "op": "copy", "from": "/team", "path": "/owner/previous~1team" },
+                { "op": "replace", "path": "/owner/name", "value": "Ari" }
+            ]
+        }))
+        .expect("json patch should parse");
+        assert_eq!(
+            apply_patch(&memory, &patch).expect("patch applies"),
+            json!({
+                "facts": ["b", "c"],
+                "owner": { "name": "Ari", "previous/team": "ops" },
+                "team": "ops"
+            })
+        );
+
+        let failing: MemoryPatch = serde_json::from_value(json!({
+            "format": "json_patch",
+            "patch": [
+                { "op": "add", "path": "/x", "value": 1 },
+                { "op": "test", "path": "/owner/name", "value": "Nobody" }
+            ]
+        }))
+        .expect("json patch should parse");
+        let error = apply_patch(&memory, &failing).expect_err("test op fails");
+        assert_eq!(error.context["operation_index"], 1);
+
+        let merge: MemoryPatch = serde_json::from_value(json!({
+            "format": "merge_patch",
+            "patch": { "owner": { "team": null, "role": "lead" }, "facts": null }
+        }))
+        .expect("merge patch should parse");
+        assert_eq!(
+            apply_patch(&memory, &merge).expect("merge applies"),
+            json!({ "owner": { "name": "Sam", "role": "lead" } })
+        );
+
+        assert_eq!(
+            memory_version(&json!({ "b": 1, "a": 2 })),
+            memory_version(&serde_json::from_str("{\"a\":2,\n \"b\":1}").expect("json"))
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Optional JSON Schema a camp declares for its `memory.json`. Writes through
+//! `camp_update_memory` and `camp_patch_memory` are checked against it, so an
+//! agent with the memory tool can't replace structured memory with something
+//! the rest of the camp doesn't expect.
+//!
+//! Schemas are compiled with the `jsonschema` crate, the same validator tool
+//! arguments go through, so every standard keyword (`anyOf`, `$ref`,
+//! `format`, ...) is enforced rather than quietly ignored. References must
+//! point inside the schema; remote `$ref`s are rejected when the schema is
+//! saved.
+
+use std::path::Path;
+
+use jsonschema::Validator;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::read_camp_config;
+
+const MAX_REPORTED_VIOLATIONS: usize = 20;
+
+/// `format` is only an annotation by default in recent drafts; memory asks
+/// for it to be checked like any other keyword.
+fn compile(schema: &Value) -> Result<Validator, String> {
+    jsonschema::options()
+        .should_validate_formats(true)
+        .build(schema)
+        .map_err(|err| err.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MemorySchemaViolation {
+    /// JSON pointer to the offending value; empty for the document root.
+    pub path: String,
+    pub message: String,
+}
+
+/// Checks that `schema` is a well-formed JSON Schema the validator can
+/// compile, so a typo doesn't quietly accept everything.
+pub(crate) fn validate_memory_schema(schema: &Value) -> Result<(), String> {
+    if !schema.is_object() {
+        return Err("The memory schema must be an object.".to_string());
+    }
+    compile(schema)
+        .map(|_| ())
+        .map_err(|err| format!("The memory schema is invalid: {err}"))
+}
+
+/// Reads `memory_schema` from a raw camp config. A schema that no longer
+/// passes `validate_memory_schema` is dropped and reported as migrated.
+pub(crate) fn parse_memory_schema_field(value: Option<&Value>) -> (Option<Value>, bool) {
+    match value {
+        None | Some(Value::Null) => (None, false),
+        Some(schema) if validate_memory_schema(schema).is_ok() => (Some(schema.clone()), false),
+        Some(_) => (None, true),
+    }
+}
+
+/// Every way `memory` fails `schema`. A schema that doesn't compile was
+/// already refused when it was saved, so it reports nothing here.
+pub(crate) fn memory_schema_violations(
+    schema: &Value,
+    memory: &Value,
+) -> Vec<MemorySchemaViolation> {
+    let Ok(validator) = compile(schema) else {
+        return Vec::new();
+    };
+    validator
+        .iter_errors(memory)
+        .map(|error| MemorySchemaViolation {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+/// Rejects `memory` when the camp declares a schema it doesn't satisfy. The
+/// error is a `validation` error whose `violations` context lists each
+/// problem with its JSON pointer.
+pub(crate) fn ensure_memory_matches_schema(camp_dir: &Path, memory: &Value) -> CommandResult<()> {
+    let config = read_camp_config(camp_dir)?;
+    let Some(schema) = config.memory_schema.as_ref() else {
+        return Ok(());
+    };
+    let violations = memory_schema_violations(schema, memory);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    let total = violations.len();
+    let first = &violations[0];
+    let location = if first.path.is_empty() {
+        "/"
+    } else {
+        first.path.as_str()
+    };
+    let reported: Vec<&MemorySchemaViolation> =
+        violations.iter().take(MAX_REPORTED_VIOLATIONS).collect();
+    Err(BasecampError::validation(format!(
+        "Memory does not match the camp's schema ({total} problem{}): {location}: {}",
+        if total == 1 { "" } else { "s" },
+        first.message
+    ))
+    .with_context("violation_count", total)
+    .with_context(
+        "violations",
+        serde_json::to_value(reported).unwrap_or(Value::Null),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn memory_schema_violations_should_report_each_problem_with_its_path() {
+        let schema = json!({
+            "type": "object",
+            "required": ["owner", "facts"],
+            "additionalProperties": false,
+            "properties": {
+                "owner": { "type": "string", "minLength": 2 },
+                "facts": {
+                    "type": "array",
+                    "maxItems": 2,
+                    "items": { "type": "string", "pattern": "^[A-Z]" }
+                },
+                "priority": { "enum": ["low", "high"] },
+                "score": { "type": "number", "maximum": 10 }
+            }
+        });
+        validate_memory_schema(&schema).expect("schema is well formed");
+
+        let valid = json!({ "owner": "Sam", "facts": ["Ships Fridays"], "score": 7 });
+        assert!(memory_schema_violations(&schema, &valid).is_empty());
+
+        let invalid = json!({
+            "facts": ["ok", "Fine", "Third"],
+            "priority": "urgent",
+            "score": 11.5,
+            "notes/old": 1
+        });
+        let mut paths: Vec<String> = memory_schema_violations(&schema, &invalid)
+            .into_iter()
+            .map(|violation| violation.path)
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec!["", "", "/facts", "/facts/0", "/priority", "/score"]
+        );
+        let violations = memory_schema_violations(&schema, &invalid);
+        for expected in ["owner", "notes/old"] {
+            assert!(
+                violations
+                    .iter()
+                    .any(|violation| violation.path.is_empty()
+                        && violation.message.contains(expected))
+            );
+        }
+
+        assert!(validate_memory_schema(&json!({ "type": "text" })).is_err());
+        assert!(
+            validate_memory_schema(&json!({ "properties": { "a": { "pattern": "(" } } })).is_err()
+        );
+    }
+
+    #[test]
+    fn memory_schema_should_enforce_combinators_references_and_formats() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "contact": { "type": "string", "format": "email" },
+                "status": { "anyOf": [{ "const": "open" }, { "const": "closed" }] },
+                "owner": { "$ref": "#/$defs/name" }
+            },
+            "$defs": { "name": { "type": "string", "minLength": 2 } }
+        });
+        validate_memory_schema(&schema).expect("schema is well formed");
+
+        let valid = json!({ "contact": "sam@example.com", "status": "open", "owner": "Sam" });
+        assert!(memory_schema_violations(&schema, &valid).is_empty());
+
+        let invalid = json!({ "contact": "not an address", "status": "pending", "owner": "S" });
+        let mut paths: Vec<String> = memory_schema_violations(&schema, &invalid)
+            .into_iter()
+            .map(|violation| violation.path)
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["/contact", "/owner", "/status"]);
+
+        assert!(
+            validate_memory_schema(&json!({ "$ref": "https://example.com/memory.json" })).is_err()
+        );
+        assert!(validate_memory_schema(&json!(true)).is_err());
+    }
+}
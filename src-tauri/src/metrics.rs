@@ -0,0 +1,319 @@
+//! Per-request provider performance. Every chat request records its latency,
+//! time to first streamed token and generation speed in `request_metrics`;
+//! `get_performance_stats` turns those rows into percentiles per model so
+//! local and cloud providers can be compared.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::State;
+
+use crate::providers::{ChatStreamEvent, ProviderKind};
+use crate::{now_timestamp_ms, AppState};
+
+/// Upper bounds of the latency histogram buckets, in milliseconds. Anything
+/// slower lands in a final open-ended bucket.
+const LATENCY_BUCKETS_MS: &[i64] = &[250, 500, 1_000, 2_000, 5_000, 10_000, 30_000];
+
+pub fn create_request_metrics_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS request_metrics (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            created_at INTEGER NOT NULL,
+            provider_kind TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            streamed INTEGER NOT NULL,
+            success INTEGER NOT NULL,
+            latency_ms INTEGER NOT NULL,
+            ttfb_ms INTEGER,
+            completion_tokens INTEGER,
+            tokens_per_second REAL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_request_metrics_model
+            ON request_metrics (provider_kind, model_id, created_at);
+        ",
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RequestMetric {
+    pub provider_kind: ProviderKind,
+    pub model_id: String,
+    pub streamed: bool,
+    pub success: bool,
+    pub latency_ms: i64,
+    pub ttfb_ms: Option<i64>,
+    pub completion_tokens: Option<i64>,
+}
+
+impl RequestMetric {
+    /// Completion tokens over the time spent generating them. Streamed
+    /// requests don't count the wait for the first token.
+    fn tokens_per_second(&self) -> Option<f64> {
+        let tokens = self.completion_tokens.filter(|tokens| *tokens > 0)?;
+        let generating_ms = self.latency_ms - self.ttfb_ms.unwrap_or(0);
+        if generating_ms <= 0 {
+            return None;
+        }
+        Some(tokens as f64 * 1_000.0 / generating_ms as f64)
+    }
+}
+
+pub(crate) fn record_request(
+    connection: &Connection,
+    metric: &RequestMetric,
+) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "INSERT INTO request_metrics (
+            created_at, provider_kind, model_id, streamed, success, latency_ms, ttfb_ms,
+            completion_tokens, tokens_per_second
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            now_timestamp_ms(),
+            metric.provider_kind.as_str(),
+            metric.model_id,
+            metric.streamed,
+            metric.success,
+            metric.latency_ms,
+            metric.ttfb_ms,
+            metric.completion_tokens,
+            metric.tokens_per_second(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Forwards stream events to `downstream` and notes when the first token
+/// (content, reasoning or tool call) arrived. The returned cell holds that
+/// timestamp in milliseconds, or zero while nothing has arrived.
+pub(crate) fn first_token_timer(
+    downstream: &Channel<ChatStreamEvent>,
+) -> (Channel<ChatStreamEvent>, Arc<AtomicI64>) {
+    let first_token_at = Arc::new(AtomicI64::new(0));
+    let upstream = {
+        let first_token_at = first_token_at.clone();
+        let downstream = downstream.clone();
+        Channel::new(move |body: InvokeResponseBody| {
+            let event: ChatStreamEvent = body.deserialize()?;
+            if matches!(
+                event,
+                ChatStreamEvent::ChatDelta { .. }
+                    | ChatStreamEvent::ReasoningDelta { .. }
+                    | ChatStreamEvent::ToolCallDelta { .. }
+            ) {
+                let _ = first_token_at.compare_exchange(
+                    0,
+                    now_timestamp_ms(),
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                );
+            }
+            downstream.send(event)
+        })
+    };
+    (upstream, first_token_at)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Percentiles {
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyBucket {
+    /// `None` for the open-ended last bucket.
+    pub upper_ms: Option<i64>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelPerformanceStats {
+    pub provider_kind: String,
+    pub model_id: String,
+    pub is_local: bool,
+    pub request_count: usize,
+    pub error_count: usize,
+    /// Successful requests only; failures often return early.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<Percentiles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<Percentiles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokens_per_second: Option<Percentiles>,
+    pub latency_histogram: Vec<LatencyBucket>,
+}
+
+/// Nearest-rank percentile of `sorted`, which must be in ascending order.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn percentiles(mut values: Vec<f64>) -> Option<Percentiles> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    Some(Percentiles {
+        p50: percentile(&values, 50.0),
+        p90: percentile(&values, 90.0),
+        p99: percentile(&values, 99.0),
+    })
+}
+
+fn latency_histogram(latencies: &[i64]) -> Vec<LatencyBucket> {
+    let mut buckets: Vec<LatencyBucket> = LATENCY_BUCKETS_MS
+        .iter()
+        .map(|upper| LatencyBucket {
+            upper_ms: Some(*upper),
+            count: 0,
+        })
+        .chain(std::iter::once(LatencyBucket {
+            upper_ms: None,
+            count: 0,
+        }))
+        .collect();
+    for latency in latencies {
+        let index = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|upper| latency <= upper)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+#[derive(Default)]
+struct ModelSamples {
+    request_count: usize,
+    error_count: usize,
+    latencies: Vec<i64>,
+    ttfbs: Vec<f64>,
+    tokens_per_second: Vec<f64>,
+}
+
+fn performance_stats(
+    connection: &Connection,
+    since: Option<i64>,
+) -> Result<Vec<ModelPerformanceStats>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        "SELECT provider_kind, model_id, success, latency_ms, ttfb_ms, tokens_per_second
+         FROM request_metrics
+         WHERE created_at >= ?1",
+    )?;
+    let rows = statement.query_map(params![since.unwrap_or(0)], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, bool>(2)?,
+            row.get::<_, i64>(3)?,
+            row.get::<_, Option<i64>>(4)?,
+            row.get::<_, Option<f64>>(5)?,
+        ))
+    })?;
+
+    let mut samples: BTreeMap<(String, String), ModelSamples> = BTreeMap::new();
+    for row in rows {
+        let (provider_kind, model_id, success, latency_ms, ttfb_ms, tokens_per_second) = row?;
+        let entry = samples.entry((provider_kind, model_id)).or_default();
+        entry.request_count += 1;
+        if !success {
+            entry.error_count += 1;
+            continue;
+        }
+        entry.latencies.push(latency_ms);
+        entry.ttfbs.extend(ttfb_ms.map(|value| value as f64));
+        entry.tokens_per_second.extend(tokens_per_second);
+    }
+
+    Ok(samples
+        .into_iter()
+        .map(
+            |((provider_kind, model_id), samples)| ModelPerformanceStats {
+                is_local: ProviderKind::parse(&provider_kind).is_some_and(ProviderKind::is_local),
+                latency_histogram: latency_histogram(&samples.latencies),
+                latency_ms: percentiles(
+                    samples
+                        .latencies
+                        .iter()
+                        .map(|value| *value as f64)
+                        .collect(),
+                ),
+                ttfb_ms: percentiles(samples.ttfbs),
+                tokens_per_second: percentiles(samples.tokens_per_second),
+                request_count: samples.request_count,
+                error_count: samples.error_count,
+                provider_kind,
+                model_id,
+            },
+        )
+        .collect())
+}
+
+/// Latency, time to first token and tokens/sec percentiles for each model,
+/// optionally limited to requests made at or after `since` (unix ms).
+#[tauri::command]
+pub fn get_performance_stats(
+    state: State<'_, AppState>,
+    since: Option<i64>,
+) -> Result<Vec<ModelPerformanceStats>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    performance_stats(&connection, since)
+        .map_err(|err| format!("Unable to load performance stats: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(latency_ms: i64, ttfb_ms: Option<i64>, success: bool) -> RequestMetric {
+        RequestMetric {
+            provider_kind: ProviderKind::Ollama,
+            model_id: "llama3.2".to_string(),
+            streamed: ttfb_ms.is_some(),
+            success,
+            latency_ms,
+            ttfb_ms,
+            completion_tokens: Some(100),
+        }
+    }
+
+    #[test]
+    fn performance_stats_should_report_percentiles_per_model() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        create_request_metrics_table(&connection).expect("table should be created");
+        for latency in [600, 1_200, 2_200, 4_000] {
+            record_request(&connection, &metric(latency, Some(200), true)).expect("insert");
+        }
+        record_request(&connection, &metric(50, None, false)).expect("insert");
+
+        let stats = performance_stats(&connection, None).expect("stats load");
+        assert_eq!(stats.len(), 1);
+        let stats = &stats[0];
+        assert!(stats.is_local);
+        assert_eq!(stats.request_count, 5);
+        assert_eq!(stats.error_count, 1);
+
+        let latency = stats.latency_ms.as_ref().expect("latency percentiles");
+        assert_eq!(latency.p50, 1_200.0);
+        assert_eq!(latency.p99, 4_000.0);
+        // 100 tokens over the 1000ms after the first token.
+        let speed = stats.tokens_per_second.as_ref().expect("speed percentiles");
+        assert_eq!(speed.p99, 100.0 * 1_000.0 / 400.0);
+
+        let counts: Vec<usize> = stats.latency_histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![0, 0, 1, 1, 2, 0, 0, 0]);
+    }
+}
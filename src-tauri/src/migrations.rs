@@ -0,0 +1,437 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::{
+    camp_ranking, embedding_index, ensure_main_window, mcp, metrics, model_notes, now_timestamp_ms,
+    providers::registry, run_groups, search, user_databases, AppState,
+};
+
+struct Migration {
+    version: u32,
+    name: &'static str,
+    up: fn(&Connection) -> Result<(), rusqlite::Error>,
+}
+
+/// Ordered schema history. Append new entries; never renumber or edit one that has
+/// shipped. Databases created before this table existed may already have some of
+/// these columns, so column additions stay idempotent.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "core_tables",
+        up: create_core_tables,
+    },
+    Migration {
+        version: 2,
+        name: "runs_model_resolution",
+        up: add_runs_model_resolution_columns,
+    },
+    Migration {
+        version: 3,
+        name: "runs_openrouter_generation",
+        up: add_runs_generation_columns,
+    },
+    Migration {
+        version: 4,
+        name: "provider_registry",
+        up: registry::create_registry_tables,
+    },
+    Migration {
+        version: 5,
+        name: "mcp_servers",
+        up: mcp::create_mcp_servers_table,
+    },
+    Migration {
+        version: 6,
+        name: "search_index",
+        up: search::create_search_tables,
+    },
+    Migration {
+        version: 7,
+        name: "runs_scrub_audit",
+        up: add_runs_scrub_audit_column,
+    },
+    Migration {
+        version: 8,
+        name: "request_metrics",
+        up: metrics::create_request_metrics_table,
+    },
+    Migration {
+        version: 9,
+        name: "model_notes",
+        up: model_notes::create_model_notes_table,
+    },
+    Migration {
+        version: 10,
+        name: "runs_camp_id",
+        up: add_runs_camp_id_column,
+    },
+    Migration {
+        version: 11,
+        name: "camp_opens",
+        up: camp_ranking::create_camp_opens_table,
+    },
+    Migration {
+        version: 12,
+        name: "user_databases",
+        up: user_databases::create_user_databases_table,
+    },
+    Migration {
+        version: 13,
+        name: "embedding_index",
+        up: embedding_index::create_embedding_tables,
+    },
+    Migration {
+        version: 14,
+        name: "runs_prompt_hash",
+        up: add_runs_prompt_hash_column,
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationStatus {
+    pub version: u32,
+    pub name: String,
+    /// `None` while the migration is still pending.
+    pub applied_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    pub from_version: u32,
+    pub to_version: u32,
+    pub applied: Vec<MigrationStatus>,
+}
+
+fn ensure_migrations_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+      version INTEGER PRIMARY KEY,
+      name TEXT NOT NULL,
+      applied_at INTEGER NOT NULL
+    );
+    ",
+    )
+}
+
+fn current_version(connection: &Connection) -> Result<u32, rusqlite::Error> {
+    connection.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
+
+fn applied_at(connection: &Connection, version: u32) -> Result<Option<i64>, rusqlite::Error> {
+    connection
+        .query_row(
+            "SELECT applied_at FROM schema_migrations WHERE version = ?1",
+            params![version],
+            |row| row.get(0),
+        )
+        .optional()
+}
+
+fn has_column(
+    connection: &Connection,
+    table_name: &str,
+    column_name: &str,
+) -> Result<bool, rusqlite::Error> {
+    let mut statement = connection.prepare(&format!("PRAGMA table_info({table_name})"))?;
+    let mut rows = statement.query([])?;
+
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        if name == column_name {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn add_column_if_missing(
+    connection: &Connection,
+    table_name: &str,
+    column_name: &str,
+    column_type: &str,
+) -> Result<(), rusqlite::Error> {
+    if !has_column(connection, table_name, column_name)? {
+        connection.execute(
+            &format!("ALTER TABLE {table_name} ADD COLUMN {column_name} {column_type}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn create_core_tables(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+    CREATE TABLE IF NOT EXISTS runs (
+      id TEXT PRIMARY KEY,
+      timestamp INTEGER NOT NULL,
+      model TEXT NOT NULL,
+      requested_model TEXT NOT NULL,
+      resolved_model TEXT,
+      system_prompt TEXT NOT NULL,
+      user_prompt TEXT NOT NULL,
+      temperature REAL NOT NULL,
+      max_tokens INTEGER NOT NULL,
+      request_json TEXT NOT NULL,
+      response_json TEXT NOT NULL,
+      output_text TEXT NOT NULL,
+      latency_ms INTEGER NOT NULL,
+      prompt_tokens INTEGER,
+      completion_tokens INTEGER,
+      total_tokens INTEGER,
+      error TEXT,
+      rating INTEGER,
+      tags TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS meta (
+      key TEXT PRIMARY KEY,
+      value INTEGER
+    );
+
+    CREATE TABLE IF NOT EXISTS settings (
+      key TEXT PRIMARY KEY,
+      value TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS tool_calls (
+      id TEXT PRIMARY KEY,
+      run_id TEXT NOT NULL,
+      step_index INTEGER NOT NULL,
+      tool_name TEXT NOT NULL,
+      args_json TEXT NOT NULL,
+      result_json TEXT,
+      error TEXT,
+      started_at INTEGER NOT NULL,
+      finished_at INTEGER
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_tool_calls_run_id ON tool_calls(run_id);
+    CREATE INDEX IF NOT EXISTS idx_tool_calls_started_at ON tool_calls(started_at);
+    ",
+    )
+}
+
+fn add_runs_model_resolution_columns(connection: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(connection, "runs", "requested_model", "TEXT")?;
+    add_column_if_missing(connection, "runs", "resolved_model", "TEXT")?;
+
+    connection.execute(
+        "
+    UPDATE runs
+    SET requested_model = model
+    WHERE requested_model IS NULL OR requested_model = ''
+    ",
+        [],
+    )?;
+
+    connection.execute(
+        "
+    UPDATE runs
+    SET resolved_model = model
+    WHERE resolved_model IS NULL OR resolved_model = ''
+    ",
+        [],
+    )?;
+
+    Ok(())
+}
+
+fn add_runs_generation_columns(connection: &Connection) -> Result<(), rusqlite::Error> {
+    for (column, column_type) in [
+        ("generation_id", "TEXT"),
+        ("total_cost", "REAL"),
+        ("native_tokens_prompt", "INTEGER"),
+        ("native_tokens_completion", "INTEGER"),
+        ("native_tokens_reasoning", "INTEGER"),
+        ("provider_name", "TEXT"),
+        ("generation_json", "TEXT"),
+    ] {
+        add_column_if_missing(connection, "runs", column, column_type)?;
+    }
+    Ok(())
+}
+
+fn add_runs_scrub_audit_column(connection: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(connection, "runs", "scrub_audit_json", "TEXT")
+}
+
+fn add_runs_camp_id_column(connection: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(connection, "runs", "camp_id", "TEXT")?;
+    connection.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runs_camp_timestamp ON runs (camp_id, timestamp)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn add_runs_prompt_hash_column(connection: &Connection) -> Result<(), rusqlite::Error> {
+    add_column_if_missing(connection, "runs", "prompt_hash", "TEXT")?;
+    connection.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runs_prompt_hash ON runs (prompt_hash)",
+        [],
+    )?;
+    run_groups::backfill_prompt_hashes(connection)
+}
+
+/// Applies every pending migration in order. Each one runs in its own transaction
+/// together with its `schema_migrations` row, so a failure leaves the database at
+/// the last good version. With `dry_run` all pending migrations run in a single
+/// transaction that is rolled back, which proves they apply without keeping them.
+pub(crate) fn run_migrations(
+    connection: &Connection,
+    dry_run: bool,
+) -> Result<MigrationReport, rusqlite::Error> {
+    ensure_migrations_table(connection)?;
+    let from_version = current_version(connection)?;
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > from_version);
+
+    let dry_run_transaction = if dry_run {
+        Some(connection.unchecked_transaction()?)
+    } else {
+        None
+    };
+
+    let mut applied = Vec::new();
+    for migration in pending {
+        let transaction = if dry_run {
+            None
+        } else {
+            Some(connection.unchecked_transaction()?)
+        };
+        let applied_at = now_timestamp_ms();
+        (migration.up)(connection)?;
+        connection.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, applied_at],
+        )?;
+        if let Some(transaction) = transaction {
+            transaction.commit()?;
+        }
+        applied.push(MigrationStatus {
+            version: migration.version,
+            name: migration.name.to_string(),
+            applied_at: Some(applied_at),
+        });
+    }
+
+    if let Some(transaction) = dry_run_transaction {
+        transaction.rollback()?;
+    }
+
+    Ok(MigrationReport {
+        dry_run,
+        from_version,
+        to_version: applied
+            .last()
+            .map(|status| status.version)
+            .unwrap_or(from_version),
+        applied,
+    })
+}
+
+fn migration_statuses(connection: &Connection) -> Result<Vec<MigrationStatus>, rusqlite::Error> {
+    ensure_migrations_table(connection)?;
+    MIGRATIONS
+        .iter()
+        .map(|migration| {
+            Ok(MigrationStatus {
+                version: migration.version,
+                name: migration.name.to_string(),
+                applied_at: applied_at(connection, migration.version)?,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn db_migration_status(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<Vec<MigrationStatus>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    migration_statuses(&connection)
+        .map_err(|err| format!("Unable to read schema migrations: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_migrations_should_upgrade_legacy_runs_and_support_dry_run() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        connection
+            .execute_batch(
+                "
+        CREATE TABLE runs (
+          id TEXT PRIMARY KEY,
+          timestamp INTEGER NOT NULL,
+          model TEXT NOT NULL,
+          system_prompt TEXT NOT NULL,
+          user_prompt TEXT NOT NULL,
+          temperature REAL NOT NULL,
+          max_tokens INTEGER NOT NULL,
+          request_json TEXT NOT NULL,
+          response_json TEXT NOT NULL,
+          output_text TEXT NOT NULL,
+          latency_ms INTEGER NOT NULL
+        );
+        INSERT INTO runs VALUES ('run-1', 1, 'openrouter/auto', '', 'hi', 0.2, 256, '{}', '{}', 'hello', 10);
+        ",
+            )
+            .expect("legacy runs table should be created");
+
+        let preview = run_migrations(&connection, true).expect("dry run should succeed");
+        assert!(preview.dry_run);
+        assert_eq!(preview.to_version, MIGRATIONS.len() as u32);
+        assert!(!has_column(&connection, "runs", "requested_model").expect("pragma should run"));
+        assert_eq!(
+            current_version(&connection).expect("version should load"),
+            0
+        );
+
+        let report = run_migrations(&connection, false).expect("migrations should apply");
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.applied.len(), MIGRATIONS.len());
+        let requested_model: String = connection
+            .query_row(
+                "SELECT requested_model FROM runs WHERE id = 'run-1'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("backfilled run should load");
+        assert_eq!(requested_model, "openrouter/auto");
+        assert!(has_column(&connection, "runs", "generation_json").expect("pragma should run"));
+        let prompt_hash: Option<String> = connection
+            .query_row(
+                "SELECT prompt_hash FROM runs WHERE id = 'run-1'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("backfilled run should load");
+        assert_eq!(prompt_hash, run_groups::prompt_hash("", "hi"));
+
+        let again = run_migrations(&connection, false).expect("rerun should be a no-op");
+        assert!(again.applied.is_empty());
+        assert!(migration_statuses(&connection)
+            .expect("statuses should load")
+            .iter()
+            .all(|status| status.applied_at.is_some()));
+    }
+}
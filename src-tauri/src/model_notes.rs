@@ -0,0 +1,236 @@
+//! The user's own verdicts on models: a 1-5 rating for quality and speed, a
+//! rough cost tier and free-form notes. `db_list_models` attaches them to each
+//! model row so pickers can show them next to the model name.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::providers::ProviderKind;
+use crate::{now_timestamp_ms, AppState};
+
+const MAX_NOTES_CHARS: usize = 2_000;
+
+pub fn create_model_notes_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS model_notes (
+            provider_kind TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            quality INTEGER,
+            speed INTEGER,
+            cost_tier TEXT,
+            notes TEXT NOT NULL DEFAULT '',
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (provider_kind, model_id)
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostTier {
+    Free,
+    Low,
+    Medium,
+    High,
+}
+
+impl CostTier {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Free => "free",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "free" => Some(Self::Free),
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelNote {
+    pub provider_kind: String,
+    pub model_id: String,
+    /// 1 (poor) to 5 (excellent).
+    pub quality: Option<u8>,
+    /// 1 (slow) to 5 (fast).
+    pub speed: Option<u8>,
+    pub cost_tier: Option<CostTier>,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+fn validate_rating(rating: Option<u8>, field_name: &str) -> Result<Option<u8>, String> {
+    match rating {
+        Some(value) if !(1..=5).contains(&value) => {
+            Err(format!("{field_name} must be between 1 and 5."))
+        }
+        other => Ok(other),
+    }
+}
+
+fn upsert_model_note(connection: &Connection, note: &ModelNote) -> Result<(), rusqlite::Error> {
+    connection.execute(
+        "INSERT INTO model_notes (provider_kind, model_id, quality, speed, cost_tier, notes, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(provider_kind, model_id) DO UPDATE SET
+           quality = excluded.quality,
+           speed = excluded.speed,
+           cost_tier = excluded.cost_tier,
+           notes = excluded.notes,
+           updated_at = excluded.updated_at",
+        params![
+            note.provider_kind,
+            note.model_id,
+            note.quality,
+            note.speed,
+            note.cost_tier.map(CostTier::as_str),
+            note.notes,
+            note.updated_at,
+        ],
+    )?;
+    Ok(())
+}
+
+fn list_notes(connection: &Connection) -> Result<Vec<ModelNote>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        "SELECT provider_kind, model_id, quality, speed, cost_tier, notes, updated_at
+         FROM model_notes
+         ORDER BY provider_kind, model_id",
+    )?;
+    let rows = statement.query_map([], |row| {
+        Ok(ModelNote {
+            provider_kind: row.get(0)?,
+            model_id: row.get(1)?,
+            quality: row.get(2)?,
+            speed: row.get(3)?,
+            cost_tier: row
+                .get::<_, Option<String>>(4)?
+                .as_deref()
+                .and_then(CostTier::parse),
+            notes: row.get(5)?,
+            updated_at: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Notes keyed by `(provider_kind, model_id)`, for joining onto model rows.
+pub(crate) fn model_notes_by_model(
+    connection: &Connection,
+) -> Result<HashMap<(String, String), ModelNote>, rusqlite::Error> {
+    Ok(list_notes(connection)?
+        .into_iter()
+        .map(|note| ((note.provider_kind.clone(), note.model_id.clone()), note))
+        .collect())
+}
+
+#[tauri::command]
+pub fn list_model_notes(state: State<'_, AppState>) -> Result<Vec<ModelNote>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    list_notes(&connection).map_err(|err| format!("Unable to load model notes: {err}"))
+}
+
+/// Creates or replaces the note for one model.
+#[tauri::command]
+pub fn set_model_note(state: State<'_, AppState>, note: ModelNote) -> Result<ModelNote, String> {
+    let provider_kind = ProviderKind::parse(note.provider_kind.trim())
+        .ok_or_else(|| format!("Unknown provider: {}", note.provider_kind))?;
+    let model_id = note.model_id.trim();
+    if model_id.is_empty() {
+        return Err("model_id cannot be empty.".to_string());
+    }
+    let notes = note.notes.trim();
+    if notes.chars().count() > MAX_NOTES_CHARS {
+        return Err(format!(
+            "Model notes are limited to {MAX_NOTES_CHARS} characters."
+        ));
+    }
+    let note = ModelNote {
+        provider_kind: provider_kind.as_str().to_string(),
+        model_id: model_id.to_string(),
+        quality: validate_rating(note.quality, "quality")?,
+        speed: validate_rating(note.speed, "speed")?,
+        cost_tier: note.cost_tier,
+        notes: notes.to_string(),
+        updated_at: now_timestamp_ms(),
+    };
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    upsert_model_note(&connection, &note)
+        .map_err(|err| format!("Unable to save model note: {err}"))?;
+    Ok(note)
+}
+
+#[tauri::command]
+pub fn delete_model_note(
+    state: State<'_, AppState>,
+    provider_kind: String,
+    model_id: String,
+) -> Result<bool, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let deleted = connection
+        .execute(
+            "DELETE FROM model_notes WHERE provider_kind = ?1 AND model_id = ?2",
+            params![provider_kind, model_id],
+        )
+        .map_err(|err| format!("Unable to delete model note: {err}"))?;
+    Ok(deleted > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_notes_should_upsert_and_key_by_model() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        create_model_notes_table(&connection).expect("table should be created");
+        let mut note = ModelNote {
+            provider_kind: "ollama".to_string(),
+            model_id: "llama3.2".to_string(),
+            quality: Some(3),
+            speed: Some(5),
+            cost_tier: Some(CostTier::Free),
+            notes: "Fine for drafts.".to_string(),
+            updated_at: 1,
+        };
+        upsert_model_note(&connection, &note).expect("insert");
+        note.quality = Some(4);
+        note.updated_at = 2;
+        upsert_model_note(&connection, &note).expect("update");
+
+        let notes = model_notes_by_model(&connection).expect("notes load");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            notes.get(&("ollama".to_string(), "llama3.2".to_string())),
+            Some(&note)
+        );
+        assert!(validate_rating(Some(6), "quality").is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! `recommend_model`: ranks the models in the local registry for a prompt.
+//! Hard requirements (tool calls, image input, a context window that fits,
+//! local-only camps) filter the list; the rest is a score built from the
+//! user's own model notes, price and where the model runs, with a short
+//! reason for every adjustment.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::model_notes::{model_notes_by_model, CostTier, ModelNote};
+use crate::providers::registry::{self, ModelRegistryRow};
+use crate::{
+    compose_model_reference, ensure_camps_root, estimate_tokens, read_camp_config,
+    resolve_existing_camp_dir, AppState,
+};
+
+const DEFAULT_RECOMMENDATIONS: usize = 5;
+const MAX_RECOMMENDATIONS: usize = 20;
+/// Room left for the reply when checking whether a prompt fits.
+const RESERVED_OUTPUT_TOKENS: i64 = 1_024;
+/// Prompts above this many estimated tokens favour large context windows.
+const LONG_PROMPT_TOKENS: i64 = 16_000;
+const BASE_SCORE: f64 = 50.0;
+
+#[derive(Debug, Deserialize)]
+pub struct RecommendModelPayload {
+    pub prompt: String,
+    /// Camp the prompt is for; supplies tool, privacy and local-only settings.
+    pub camp_id: Option<String>,
+    /// Set when images are attached to the message.
+    #[serde(default)]
+    pub has_images: bool,
+    /// Overrides the camp's tools setting.
+    pub needs_tools: Option<bool>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct TaskProfile {
+    estimated_tokens: i64,
+    needs_tools: bool,
+    has_images: bool,
+    prefer_local: bool,
+    local_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRecommendation {
+    /// Model reference such as `ollama/llama3.2`.
+    pub model: String,
+    pub display_name: String,
+    pub is_local: bool,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecommendModelResult {
+    pub estimated_prompt_tokens: i64,
+    pub needs_tools: bool,
+    pub has_images: bool,
+    pub local_only: bool,
+    pub recommendations: Vec<ModelRecommendation>,
+    /// Registry models ruled out by a hard requirement.
+    pub excluded_count: usize,
+}
+
+/// Inline image data or links to image files in the prompt text.
+fn mentions_images(prompt: &str) -> bool {
+    static IMAGE_PATTERN: OnceLock<Regex> = OnceLock::new();
+    IMAGE_PATTERN
+        .get_or_init(|| {
+            Regex::new(r"(?i)data:image/|\.(png|jpe?g|gif|webp|heic)\b").expect("valid regex")
+        })
+        .is_match(prompt)
+}
+
+fn format_tokens(tokens: i64) -> String {
+    if tokens >= 1_000 {
+        format!("{}k", tokens / 1_000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Hard requirements; a model failing any of them is never recommended.
+fn meets_requirements(model: &ModelRegistryRow, task: &TaskProfile) -> bool {
+    let fits_context = !matches!(
+        model.context_length,
+        Some(length) if length < task.estimated_tokens + RESERVED_OUTPUT_TOKENS
+    );
+    (!task.local_only || model.provider_kind.is_local())
+        && (!task.needs_tools || model.capabilities.supports_tools)
+        && (!task.has_images || model.capabilities.supports_images)
+        && fits_context
+}
+
+fn score_model(
+    model: &ModelRegistryRow,
+    note: Option<&ModelNote>,
+    task: &TaskProfile,
+) -> (f64, Vec<String>) {
+    let mut score = BASE_SCORE;
+    let mut reasons = Vec::new();
+    let is_local = model.provider_kind.is_local();
+
+    if task.needs_tools {
+        reasons.push("Supports tool calls.".to_string());
+    }
+    if task.has_images {
+        reasons.push("Accepts images.".to_string());
+    }
+    if is_local {
+        if task.prefer_local {
+            score += 15.0;
+            reasons.push("Runs on this machine, matching the camp's privacy mode.".to_string());
+        } else {
+            score += 5.0;
+            reasons.push("Runs on this machine with no per-token cost.".to_string());
+        }
+    }
+
+    match model.context_length {
+        Some(length) if task.estimated_tokens > LONG_PROMPT_TOKENS => {
+            let headroom = length as f64 / (task.estimated_tokens + RESERVED_OUTPUT_TOKENS) as f64;
+            score += (headroom.log2() * 5.0).min(15.0);
+            reasons.push(format!(
+                "{} context window fits this ~{} token prompt.",
+                format_tokens(length),
+                format_tokens(task.estimated_tokens)
+            ));
+        }
+        Some(_) => {}
+        None => {
+            score -= 5.0;
+            reasons.push("Context window unknown.".to_string());
+        }
+    }
+
+    if !is_local {
+        if let Some(pricing) = registry::pricing_from_raw(&model.raw_json) {
+            let cost = pricing.prompt * task.estimated_tokens as f64
+                + pricing.completion * RESERVED_OUTPUT_TOKENS as f64;
+            if cost == 0.0 {
+                score += 8.0;
+                reasons.push("Free to use.".to_string());
+            } else {
+                // A tenth of a cent or less scores best; a dime or more costs points.
+                score += (-(cost.log10() + 2.0) * 4.0).clamp(-8.0, 4.0);
+                reasons.push(format!("About ${cost:.4} for this prompt."));
+            }
+        }
+    }
+
+    if let Some(note) = note {
+        if let Some(quality) = note.quality {
+            score += (f64::from(quality) - 3.0) * 8.0;
+            reasons.push(format!("You rated its quality {quality}/5."));
+        }
+        if let Some(speed) = note.speed {
+            score += (f64::from(speed) - 3.0) * 4.0;
+            reasons.push(format!("You rated its speed {speed}/5."));
+        }
+        match note.cost_tier {
+            Some(CostTier::Free) => score += 4.0,
+            Some(CostTier::Low) => score += 2.0,
+            Some(CostTier::High) => score -= 4.0,
+            Some(CostTier::Medium) | None => {}
+        }
+    }
+
+    (score, reasons)
+}
+
+fn rank_models(
+    models: &[ModelRegistryRow],
+    notes: &HashMap<(String, String), ModelNote>,
+    task: &TaskProfile,
+) -> (Vec<ModelRecommendation>, usize) {
+    let mut excluded = 0;
+    let mut ranked = Vec::new();
+    for model in models {
+        if !meets_requirements(model, task) {
+            excluded += 1;
+            continue;
+        }
+        let note = notes.get(&(
+            model.provider_kind.as_str().to_string(),
+            model.model_id.clone(),
+        ));
+        let (score, reasons) = score_model(model, note, task);
+        ranked.push(ModelRecommendation {
+            model: compose_model_reference(model.provider_kind, &model.model_id),
+            display_name: model
+                .display_name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| model.model_id.clone()),
+            is_local: model.provider_kind.is_local(),
+            score: (score * 10.0).round() / 10.0,
+            reasons,
+        });
+    }
+    ranked.sort_by(|left, right| {
+        right
+            .score
+            .total_cmp(&left.score)
+            .then_with(|| left.model.cmp(&right.model))
+    });
+    (ranked, excluded)
+}
+
+/// Ranked model suggestions for `prompt`. Camps marked local-only never get
+/// a cloud model back.
+#[tauri::command]
+pub fn recommend_model(
+    state: State<'_, AppState>,
+    payload: RecommendModelPayload,
+) -> Result<RecommendModelResult, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let config = match payload.camp_id.as_deref() {
+        Some(camp_id) => {
+            let camp_dir = resolve_existing_camp_dir(&ensure_camps_root(&connection)?, camp_id)?;
+            Some(read_camp_config(&camp_dir)?)
+        }
+        None => None,
+    };
+    let task = TaskProfile {
+        estimated_tokens: estimate_tokens(&payload.prompt) as i64,
+        needs_tools: payload
+            .needs_tools
+            .unwrap_or_else(|| config.as_ref().is_some_and(|config| config.tools_enabled)),
+        has_images: payload.has_images || mentions_images(&payload.prompt),
+        prefer_local: config.as_ref().is_some_and(|config| config.privacy_mode),
+        local_only: config.as_ref().is_some_and(|config| config.local_only),
+    };
+
+    let models = registry::list_models(&connection, None)
+        .map_err(|err| format!("Unable to query model rows: {err}"))?;
+    let notes = model_notes_by_model(&connection)
+        .map_err(|err| format!("Unable to load model notes: {err}"))?;
+    let (mut recommendations, excluded_count) = rank_models(&models, &notes, &task);
+    recommendations.truncate(
+        payload
+            .limit
+            .unwrap_or(DEFAULT_RECOMMENDATIONS)
+            .clamp(1, MAX_RECOMMENDATIONS),
+    );
+
+    Ok(RecommendModelResult {
+        estimated_prompt_tokens: task.estimated_tokens,
+        needs_tools: task.needs_tools,
+        has_images: task.has_images,
+        local_only: task.local_only,
+        recommendations,
+        excluded_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ProviderCapabilities, ProviderKind};
+
+    fn model(
+        provider_kind: ProviderKind,
+        model_id: &str,
+        supports_tools: bool,
+        context_length: Option<i64>,
+    ) -> ModelRegistryRow {
+        ModelRegistryRow {
+            provider_kind,
+            model_id: model_id.to_string(),
+            id: format!("{}/{model_id}", provider_kind.as_str()),
+            display_name: None,
+            context_length,
+            capabilities: ProviderCapabilities {
+                supports_tools,
+                ..ProviderCapabilities::default()
+            },
+            raw_json: serde_json::json!({}),
+            last_seen_at: 0,
+        }
+    }
+
+    #[test]
+    fn rank_models_should_filter_requirements_and_keep_local_only_camps_local() {
+        let models = vec![
+            model(
+                ProviderKind::Openrouter,
+                "openai/gpt-4o",
+                true,
+                Some(128_000),
+            ),
+            model(ProviderKind::Ollama, "llama3.2", true, Some(8_192)),
+            model(ProviderKind::Ollama, "tiny", false, Some(2_048)),
+        ];
+        let notes = HashMap::from([(
+            ("ollama".to_string(), "llama3.2".to_string()),
+            ModelNote {
+                provider_kind: "ollama".to_string(),
+                model_id: "llama3.2".to_string(),
+                quality: Some(5),
+                speed: None,
+                cost_tier: None,
+                notes: String::new(),
+                updated_at: 0,
+            },
+        )]);
+        let task = TaskProfile {
+            estimated_tokens: 100,
+            needs_tools: true,
+            ..TaskProfile::default()
+        };
+
+        let (ranked, excluded) = rank_models(&models, &notes, &task);
+        assert_eq!(excluded, 1);
+        assert_eq!(ranked[0].model, "ollama/llama3.2");
+        assert!(ranked[0]
+            .reasons
+            .contains(&"You rated its quality 5/5.".to_string()));
+
+        let local_only = TaskProfile {
+            local_only: true,
+            needs_tools: false,
+            ..task
+        };
+        let (ranked, _) = rank_models(&models, &notes, &local_only);
+        assert!(ranked.iter().all(|recommendation| recommendation.is_local));
+        assert_eq!(ranked.len(), 2);
+
+        assert!(mentions_images("what is in ~/Desktop/shot.PNG?"));
+        assert!(!mentions_images("summarize notes.md"));
+    }
+}
@@ -0,0 +1,464 @@
+//! Per-camp post-processing of assistant replies. A camp's `camp.json` can
+//! list processors that run in order over each reply before it is written to
+//! the transcript: strip markdown code fences, hard-wrap long lines, apply a
+//! regex replacement, or save fenced code blocks as artifacts. Camps with
+//! `auto_extract_code_blocks` on get every code block saved as a
+//! language-tagged artifact without setting up a pipeline.
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use tauri::{State, Window};
+
+use crate::{
+    camp_transcript_path, create_artifact, ensure_camps_root, ensure_main_window, read_camp_config,
+    read_transcript, resolve_existing_camp_dir, touch_camp_updated_at, validate_identifier,
+    AppState, ArtifactSource, CampArtifact, CampMessage,
+};
+
+const MAX_POST_PROCESSORS: usize = 10;
+const MIN_LINE_WIDTH: usize = 20;
+const MAX_LINE_WIDTH: usize = 400;
+const MAX_PATTERN_LENGTH: usize = 500;
+const CODE_BLOCK_TAG: &str = "code";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PostProcessor {
+    /// Removes ``` and ~~~ fence lines, keeping the code between them.
+    StripMarkdownFences,
+    /// Hard-wraps prose lines longer than `width` characters at whitespace.
+    /// Lines inside code fences are left alone.
+    WrapLines { width: usize },
+    /// Replaces every match of `pattern`; `$1`-style group references work.
+    RegexReplace {
+        pattern: String,
+        #[serde(default)]
+        replacement: String,
+    },
+    /// Saves each fenced code block with at least `min_lines` lines as an
+    /// artifact. The reply itself is not changed.
+    ExtractCodeBlocks {
+        #[serde(default = "default_min_lines")]
+        min_lines: usize,
+    },
+}
+
+fn default_min_lines() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CodeBlock {
+    language: Option<String>,
+    code: String,
+    /// Prose line just above the block, used for its title.
+    context: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct PipelineOutput {
+    content: String,
+    code_blocks: Vec<CodeBlock>,
+}
+
+pub(crate) fn validate_post_processors(processors: &[PostProcessor]) -> Result<(), String> {
+    if processors.len() > MAX_POST_PROCESSORS {
+        return Err(format!(
+            "A camp can have at most {MAX_POST_PROCESSORS} post-processors."
+        ));
+    }
+    for processor in processors {
+        match processor {
+            PostProcessor::WrapLines { width } => {
+                if !(MIN_LINE_WIDTH..=MAX_LINE_WIDTH).contains(width) {
+                    return Err(format!(
+                        "Line width must be between {MIN_LINE_WIDTH} and {MAX_LINE_WIDTH}."
+                    ));
+                }
+            }
+            PostProcessor::RegexReplace { pattern, .. } => {
+                if pattern.is_empty() || pattern.len() > MAX_PATTERN_LENGTH {
+                    return Err(format!(
+                        "Regex pattern must be 1 to {MAX_PATTERN_LENGTH} characters."
+                    ));
+                }
+                Regex::new(pattern).map_err(|err| format!("Invalid regex pattern: {err}"))?;
+            }
+            PostProcessor::ExtractCodeBlocks { min_lines } => {
+                if *min_lines == 0 {
+                    return Err("min_lines must be at least 1.".to_string());
+                }
+            }
+            PostProcessor::StripMarkdownFences => {}
+        }
+    }
+    Ok(())
+}
+
+/// Reads `post_processors` from a raw camp config, dropping entries that
+/// are not a known processor. The flag reports whether any were dropped.
+pub(crate) fn parse_post_processors_field(value: Option<&Value>) -> (Vec<PostProcessor>, bool) {
+    let Some(items) = value.and_then(Value::as_array) else {
+        return (Vec::new(), value.is_some_and(|value| !value.is_null()));
+    };
+    let processors: Vec<PostProcessor> = items
+        .iter()
+        .filter_map(|item| serde_json::from_value(item.clone()).ok())
+        .collect();
+    let migrated = processors.len() != items.len();
+    (processors, migrated)
+}
+
+/// The fence marker (``` or ~~~) that opens or closes a block on this line.
+fn fence_marker(line: &str) -> Option<&'static str> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") {
+        Some("```")
+    } else if trimmed.starts_with("~~~") {
+        Some("~~~")
+    } else {
+        None
+    }
+}
+
+/// Splits `content` into lines tagged with whether they are a fence line or
+/// sit inside a fenced block.
+fn classify_lines(content: &str) -> Vec<(&str, bool, bool)> {
+    let mut open: Option<&str> = None;
+    content
+        .lines()
+        .map(|line| match (fence_marker(line), open) {
+            (Some(marker), None) => {
+                open = Some(marker);
+                (line, true, false)
+            }
+            (Some(marker), Some(current)) if marker == current => {
+                open = None;
+                (line, true, false)
+            }
+            (_, inside) => (line, false, inside.is_some()),
+        })
+        .collect()
+}
+
+fn strip_markdown_fences(content: &str) -> String {
+    classify_lines(content)
+        .into_iter()
+        .filter(|(_, is_fence, _)| !is_fence)
+        .map(|(line, _, _)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let indent: String = line.chars().take_while(|ch| ch.is_whitespace()).collect();
+    let mut wrapped = Vec::new();
+    let mut current = String::new();
+    for word in line.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+            wrapped.push(std::mem::take(&mut current));
+        }
+        if current.is_empty() {
+            current.push_str(&indent);
+        } else {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    wrapped.push(current);
+    wrapped
+}
+
+fn wrap_lines(content: &str, width: usize) -> String {
+    classify_lines(content)
+        .into_iter()
+        .flat_map(|(line, is_fence, in_code)| {
+            if is_fence || in_code || line.chars().count() <= width {
+                vec![line.to_string()]
+            } else {
+                wrap_line(line, width)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Language from a fence's info string: the first word, lowercased, so
+/// "```Rust,ignore" and "```rust title=main.rs" both give "rust".
+fn fence_language(fence_line: &str) -> Option<String> {
+    let info = fence_line
+        .trim()
+        .trim_start_matches(|ch| ch == '`' || ch == '~');
+    info.split(|ch: char| ch.is_whitespace() || ch == ',' || ch == '{')
+        .next()
+        .map(str::trim)
+        .filter(|language| !language.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn extract_code_blocks(content: &str, min_lines: usize) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+    // The last line of prose since the previous block; it usually says what
+    // the code is.
+    let mut context: Option<&str> = None;
+    for (line, is_fence, in_code) in classify_lines(content) {
+        if is_fence {
+            match current.take() {
+                Some((language, lines)) => {
+                    if lines.len() >= min_lines {
+                        blocks.push(CodeBlock {
+                            language,
+                            code: lines.join("\n"),
+                            context: context.map(ToString::to_string),
+                        });
+                    }
+                    context = None;
+                }
+                None => current = Some((fence_language(line), Vec::new())),
+            }
+        } else if in_code {
+            if let Some((_, lines)) = current.as_mut() {
+                lines.push(line);
+            }
+        } else if !line.trim().is_empty() {
+            context = Some(line);
+        }
+    }
+    blocks
+}
+
+/// Runs `processors` over `content` in order. Processors were validated when
+/// the camp config was saved; one that no longer compiles is skipped.
+fn run_pipeline(processors: &[PostProcessor], content: &str) -> PipelineOutput {
+    let mut output = PipelineOutput {
+        content: content.to_string(),
+        code_blocks: Vec::new(),
+    };
+    for processor in processors {
+        match processor {
+            PostProcessor::StripMarkdownFences => {
+                output.content = strip_markdown_fences(&output.content);
+            }
+            PostProcessor::WrapLines { width } => {
+                output.content = wrap_lines(&output.content, (*width).max(MIN_LINE_WIDTH));
+            }
+            PostProcessor::RegexReplace {
+                pattern,
+                replacement,
+            } => {
+                if let Ok(regex) = Regex::new(pattern) {
+                    output.content = regex
+                        .replace_all(&output.content, replacement.as_str())
+                        .into_owned();
+                }
+            }
+            PostProcessor::ExtractCodeBlocks { min_lines } => {
+                output
+                    .code_blocks
+                    .extend(extract_code_blocks(&output.content, (*min_lines).max(1)));
+            }
+        }
+    }
+    output
+}
+
+const MAX_TITLE_CHARS: usize = 80;
+
+/// Titles a snippet after the prose line above it ("Add this to main.rs:"),
+/// falling back to its language.
+fn code_block_title(block: &CodeBlock, index: usize, total: usize) -> String {
+    let from_context = block
+        .context
+        .as_deref()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|ch: char| {
+                    matches!(ch, '#' | '>' | '-' | '*') || ch.is_whitespace()
+                })
+                .trim_end_matches(|ch: char| ch == ':' || ch.is_whitespace())
+                .chars()
+                .take(MAX_TITLE_CHARS)
+                .collect::<String>()
+        })
+        .filter(|title| !title.is_empty());
+    if let Some(title) = from_context {
+        return title;
+    }
+    let base = match &block.language {
+        Some(language) => format!("{language} snippet"),
+        None => "Code snippet".to_string(),
+    };
+    if total > 1 {
+        format!("{base} ({} of {total})", index + 1)
+    } else {
+        base
+    }
+}
+
+/// Saves each block as an artifact tagged `code` and with its language,
+/// linked to `message` the way `camp_create_artifact_from_message` links
+/// whole messages.
+fn save_code_blocks(
+    camp_dir: &Path,
+    message: &CampMessage,
+    blocks: &[CodeBlock],
+) -> Result<Vec<CampArtifact>, String> {
+    let mut artifacts = Vec::with_capacity(blocks.len());
+    for (index, block) in blocks.iter().enumerate() {
+        let mut tags = vec![CODE_BLOCK_TAG.to_string()];
+        tags.extend(block.language.clone());
+        artifacts.push(create_artifact(
+            camp_dir,
+            ArtifactSource {
+                message_id: message.id.clone(),
+                role: message.role.clone(),
+                path: None,
+            },
+            &code_block_title(block, index, blocks.len()),
+            &format!(
+                "```{}\n{}\n```\n",
+                block.language.as_deref().unwrap_or(""),
+                block.code
+            ),
+            &tags,
+        )?);
+    }
+    Ok(artifacts)
+}
+
+/// Applies the camp's post-processors to an assistant reply before it is
+/// stored, and saves its code blocks as artifacts when the camp has
+/// `auto_extract_code_blocks` on. Auto-extraction reads the reply as the model
+/// wrote it, before any processor strips fences. A pipeline that would leave
+/// the reply empty keeps the original.
+pub(crate) fn apply_to_assistant_message(
+    camp_dir: &Path,
+    message: &mut CampMessage,
+) -> Result<(), String> {
+    if message.role != "assistant" || message.content.trim().is_empty() {
+        return Ok(());
+    }
+    let config = read_camp_config(camp_dir)?;
+    if config.post_processors.is_empty() && !config.auto_extract_code_blocks {
+        return Ok(());
+    }
+
+    let mut code_blocks = if config.auto_extract_code_blocks {
+        extract_code_blocks(&message.content, 1)
+    } else {
+        Vec::new()
+    };
+    let output = run_pipeline(&config.post_processors, &message.content);
+    if !output.content.trim().is_empty() {
+        message.content = output.content;
+    }
+    for block in output.code_blocks {
+        if !code_blocks
+            .iter()
+            .any(|existing| existing.code == block.code)
+        {
+            code_blocks.push(block);
+        }
+    }
+    save_code_blocks(camp_dir, message, &code_blocks)?;
+    Ok(())
+}
+
+/// Saves the code blocks of an existing transcript message as artifacts.
+#[tauri::command]
+pub fn camp_extract_code_artifacts(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    message_id: String,
+) -> Result<Vec<CampArtifact>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let message_id = validate_identifier(&message_id, "message_id")?;
+    let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+    let message = transcript
+        .iter()
+        .find(|message| message.id == message_id)
+        .ok_or_else(|| "Source message not found.".to_string())?;
+
+    let blocks = extract_code_blocks(&message.content, 1);
+    if blocks.is_empty() {
+        return Err("That message has no fenced code blocks.".to_string());
+    }
+    let artifacts = save_code_blocks(&camp_dir, message, &blocks)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(artifacts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_pipeline_should_apply_processors_in_order() {
+        let reply = "Here is the fix, explained in a sentence that runs long.\n```rust\nfn main() {}\n```\n~~~\nnote\n~~~";
+        let processors = vec![
+            PostProcessor::ExtractCodeBlocks { min_lines: 1 },
+            PostProcessor::StripMarkdownFences,
+            PostProcessor::RegexReplace {
+                pattern: r"(?i)\bfix\b".to_string(),
+                replacement: "patch".to_string(),
+            },
+            PostProcessor::WrapLines { width: 30 },
+        ];
+        validate_post_processors(&processors).expect("valid pipeline");
+
+        let output = run_pipeline(&processors, reply);
+        assert_eq!(
+            output.code_blocks,
+            vec![
+                CodeBlock {
+                    language: Some("rust".to_string()),
+                    code: "fn main() {}".to_string(),
+                    context: Some(
+                        "Here is the fix, explained in a sentence that runs long.".to_string()
+                    ),
+                },
+                CodeBlock {
+                    language: None,
+                    code: "note".to_string(),
+                    context: None,
+                },
+            ]
+        );
+        assert_eq!(
+            output.content,
+            "Here is the patch, explained\nin a sentence that runs long.\nfn main() {}\nnote"
+        );
+
+        assert!(validate_post_processors(&[PostProcessor::RegexReplace {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+        }])
+        .is_err());
+        assert!(validate_post_processors(&[PostProcessor::WrapLines { width: 5 }]).is_err());
+
+        assert_eq!(fence_language("```Rust,ignore"), Some("rust".to_string()));
+        let block = CodeBlock {
+            language: Some("rust".to_string()),
+            code: String::new(),
+            context: Some("## Add this to `main.rs`:".to_string()),
+        };
+        assert_eq!(code_block_title(&block, 0, 2), "Add this to `main.rs`");
+        let untitled = CodeBlock {
+            context: None,
+            ..block
+        };
+        assert_eq!(code_block_title(&untitled, 1, 2), "rust snippet (2 of 2)");
+    }
+}
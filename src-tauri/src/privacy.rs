@@ -0,0 +1,434 @@
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, BasecampErrorKind, CommandResult};
+use crate::providers::{redact_json, BasecampChatRequest, ProviderKind};
+use crate::{
+    ensure_camps_root, ensure_main_window, get_setting_value, read_camp_config,
+    resolve_existing_camp_dir, set_setting_value, AppState, RunInsertPayload,
+};
+
+const SETTING_PRIVACY_MODE: &str = "privacy_mode";
+const SETTING_PRIVACY_SCRUB_PATTERNS: &str = "privacy_scrub_patterns";
+pub(crate) const REDACTED_CONTENT: &str = "[REDACTED]";
+const MAX_SCRUB_PATTERNS: usize = 32;
+
+/// Keys whose string values describe a run rather than its content, so they stay
+/// readable when content logging is off.
+const METADATA_KEYS: &[&str] = &[
+    "id",
+    "model",
+    "object",
+    "role",
+    "type",
+    "finish_reason",
+    "native_finish_reason",
+    "provider",
+    "system_fingerprint",
+    "tool_choice",
+];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacySettings {
+    /// Store runs with metadata only; prompts and responses become placeholders.
+    pub do_not_log_content: bool,
+    /// Regexes masked out of logged runs when content logging is on.
+    #[serde(default)]
+    pub scrub_patterns: Vec<String>,
+}
+
+pub(crate) fn compile_scrub_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|err| format!("Invalid scrub pattern `{pattern}`: {err}"))
+        })
+        .collect()
+}
+
+fn normalize_scrub_patterns(patterns: Vec<String>) -> Result<Vec<String>, String> {
+    let patterns: Vec<String> = patterns
+        .into_iter()
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+    if patterns.len() > MAX_SCRUB_PATTERNS {
+        return Err(format!(
+            "scrub_patterns must contain at most {MAX_SCRUB_PATTERNS} entries."
+        ));
+    }
+    compile_scrub_patterns(&patterns)?;
+    Ok(patterns)
+}
+
+fn load_privacy_settings(connection: &Connection) -> Result<PrivacySettings, String> {
+    let do_not_log_content = get_setting_value(connection, SETTING_PRIVACY_MODE)
+        .map_err(|err| format!("Unable to load privacy setting: {err}"))?
+        .is_some_and(|value| value == "1");
+    let scrub_patterns = get_setting_value(connection, SETTING_PRIVACY_SCRUB_PATTERNS)
+        .map_err(|err| format!("Unable to load scrub patterns: {err}"))?
+        .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
+        .unwrap_or_default();
+    Ok(PrivacySettings {
+        do_not_log_content,
+        scrub_patterns,
+    })
+}
+
+fn camp_privacy_mode(connection: &Connection, camp_id: &str) -> bool {
+    ensure_camps_root(connection)
+        .and_then(|camps_root| resolve_existing_camp_dir(&camps_root, camp_id))
+        .and_then(|camp_dir| read_camp_config(&camp_dir))
+        .is_ok_and(|config| config.privacy_mode)
+}
+
+/// Fails closed: a camp id that can't be resolved is an error rather than a
+/// camp without a policy. Requests outside any camp carry no camp id.
+pub(crate) fn camp_local_only(connection: &Connection, camp_id: &str) -> Result<bool, String> {
+    let camp_dir = ensure_camps_root(connection)
+        .and_then(|camps_root| resolve_existing_camp_dir(&camps_root, camp_id))
+        .map_err(|err| format!("Unable to check the routing policy of camp {camp_id}: {err}"))?;
+    Ok(read_camp_config(&camp_dir)?.local_only)
+}
+
+fn check_local_only(
+    local_only: bool,
+    camp_id: &str,
+    provider_kind: ProviderKind,
+) -> CommandResult<()> {
+    if !local_only || provider_kind.is_local() {
+        return Ok(());
+    }
+    Err(BasecampError::new(
+        BasecampErrorKind::LocalOnly,
+        format!(
+            "This camp is local-only, so requests cannot be sent to `{}`. Pick a model that runs on this machine or turn off local-only for the camp.",
+            provider_kind.as_str()
+        ),
+    )
+    .with_context("camp_id", camp_id)
+    .with_context("provider_kind", provider_kind.as_str()))
+}
+
+/// Rejects a request from a local-only camp to a cloud provider. Every path
+/// that hands a request to a provider calls this first, so chats, team steps
+/// and artifact helpers share one policy.
+pub(crate) fn enforce_camp_routing(
+    connection: &Connection,
+    camp_id: Option<&str>,
+    provider_kind: ProviderKind,
+) -> CommandResult<()> {
+    let Some(camp_id) = camp_id else {
+        return Ok(());
+    };
+    check_local_only(
+        camp_local_only(connection, camp_id)?,
+        camp_id,
+        provider_kind,
+    )
+}
+
+/// Tags a request sent on behalf of `camp_id` so `enforce_camp_routing` sees
+/// the camp. Camp-scoped commands take the id as a required argument instead
+/// of trusting the caller to fill in `metadata.camp_id`.
+pub(crate) fn scope_chat_to_camp(
+    request: &mut BasecampChatRequest,
+    camp_id: &str,
+) -> CommandResult<()> {
+    let camp_id = camp_id.trim();
+    if camp_id.is_empty() {
+        return Err(BasecampError::validation("camp_id is required."));
+    }
+    if let Some(tagged) = request.metadata.camp_id.as_deref() {
+        if tagged != camp_id {
+            return Err(BasecampError::validation(format!(
+                "Request is tagged for camp {tagged}, not {camp_id}."
+            )));
+        }
+    }
+    request.metadata.camp_id = Some(camp_id.to_string());
+    Ok(())
+}
+
+/// Replaces every string in a request/response body with a placeholder, keeping
+/// numbers, booleans and metadata such as model and finish reason.
+fn redact_content_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| {
+                    let next = match item {
+                        Value::String(_) if METADATA_KEYS.contains(&key.as_str()) => item.clone(),
+                        _ => redact_content_json(item),
+                    };
+                    (key.clone(), next)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(redact_content_json).collect()),
+        Value::String(_) => Value::String(REDACTED_CONTENT.to_string()),
+        _ => value.clone(),
+    }
+}
+
+fn scrub_text(text: &str, patterns: &[Regex]) -> String {
+    patterns.iter().fold(text.to_string(), |current, pattern| {
+        pattern.replace_all(&current, REDACTED_CONTENT).into_owned()
+    })
+}
+
+fn scrub_json_strings(value: &Value, patterns: &[Regex]) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), scrub_json_strings(item, patterns)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| scrub_json_strings(item, patterns))
+                .collect(),
+        ),
+        Value::String(text) => Value::String(scrub_text(text, patterns)),
+        _ => value.clone(),
+    }
+}
+
+/// Rewrites a stored JSON body. Bodies that are not JSON are treated as content.
+fn rewrite_json_body(raw: &str, rewrite: impl Fn(&Value) -> Value, fallback: String) -> String {
+    match serde_json::from_str::<Value>(raw) {
+        Ok(value) => rewrite(&redact_json(&value)).to_string(),
+        Err(_) => fallback,
+    }
+}
+
+fn apply_privacy(payload: &mut RunInsertPayload, settings: &PrivacySettings, redact: bool) {
+    if redact {
+        payload.system_prompt = REDACTED_CONTENT.to_string();
+        payload.user_prompt = REDACTED_CONTENT.to_string();
+        payload.output_text = REDACTED_CONTENT.to_string();
+        payload.request_json = rewrite_json_body(
+            &payload.request_json,
+            redact_content_json,
+            Value::String(REDACTED_CONTENT.to_string()).to_string(),
+        );
+        payload.response_json = rewrite_json_body(
+            &payload.response_json,
+            redact_content_json,
+            Value::String(REDACTED_CONTENT.to_string()).to_string(),
+        );
+        return;
+    }
+
+    // Patterns were validated when saved; any that no longer compile are skipped.
+    let patterns: Vec<Regex> = settings
+        .scrub_patterns
+        .iter()
+        .filter_map(|pattern| Regex::new(pattern).ok())
+        .collect();
+    if patterns.is_empty() {
+        return;
+    }
+    payload.system_prompt = scrub_text(&payload.system_prompt, &patterns);
+    payload.user_prompt = scrub_text(&payload.user_prompt, &patterns);
+    payload.output_text = scrub_text(&payload.output_text, &patterns);
+    payload.request_json = rewrite_json_body(
+        &payload.request_json,
+        |value| scrub_json_strings(value, &patterns),
+        scrub_text(&payload.request_json, &patterns),
+    );
+    payload.response_json = rewrite_json_body(
+        &payload.response_json,
+        |value| scrub_json_strings(value, &patterns),
+        scrub_text(&payload.response_json, &patterns),
+    );
+}
+
+/// Applies the global and per-camp privacy settings to a run before it is stored.
+pub(crate) fn apply_run_privacy(
+    connection: &Connection,
+    payload: &mut RunInsertPayload,
+) -> Result<(), String> {
+    let settings = load_privacy_settings(connection)?;
+    let redact = settings.do_not_log_content
+        || payload
+            .camp_id
+            .as_deref()
+            .is_some_and(|camp_id| camp_privacy_mode(connection, camp_id));
+    apply_privacy(payload, &settings, redact);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_privacy_settings(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<PrivacySettings, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_privacy_settings(&connection)
+}
+
+#[tauri::command]
+pub fn set_privacy_settings(
+    window: Window,
+    state: State<'_, AppState>,
+    settings: PrivacySettings,
+) -> Result<PrivacySettings, String> {
+    ensure_main_window(&window)?;
+    let scrub_patterns = normalize_scrub_patterns(settings.scrub_patterns)?;
+    let serialized_patterns = serde_json::to_string(&scrub_patterns)
+        .map_err(|err| format!("Unable to serialize scrub patterns: {err}"))?;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(
+        &connection,
+        SETTING_PRIVACY_MODE,
+        if settings.do_not_log_content {
+            "1"
+        } else {
+            "0"
+        },
+    )
+    .map_err(|err| format!("Unable to save privacy setting: {err}"))?;
+    set_setting_value(
+        &connection,
+        SETTING_PRIVACY_SCRUB_PATTERNS,
+        &serialized_patterns,
+    )
+    .map_err(|err| format!("Unable to save scrub patterns: {err}"))?;
+
+    Ok(PrivacySettings {
+        do_not_log_content: settings.do_not_log_content,
+        scrub_patterns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use std::fs;
+
+    fn run_payload() -> RunInsertPayload {
+        RunInsertPayload {
+            id: "run-1".to_string(),
+            timestamp: 1,
+            model: "openrouter/auto".to_string(),
+            requested_model: "openrouter/auto".to_string(),
+            resolved_model: None,
+            system_prompt: "Be brief.".to_string(),
+            user_prompt: "Email jane@example.com about the launch".to_string(),
+            temperature: 0.2,
+            max_tokens: 256,
+            request_json: r#"{"model":"openrouter/auto","messages":[{"role":"user","content":"Email jane@example.com"}],"api_key":"sk-1"}"#.to_string(),
+            response_json: r#"{"choices":[{"finish_reason":"stop","message":{"role":"assistant","content":"Done"}}],"usage":{"total_tokens":12}}"#.to_string(),
+            output_text: "Done".to_string(),
+            latency_ms: 10,
+            prompt_tokens: None,
+            completion_tokens: None,
+            total_tokens: Some(12),
+            error: None,
+            rating: None,
+            tags: None,
+            generation_id: None,
+            camp_id: None,
+            scrub_audit_json: None,
+        }
+    }
+
+    #[test]
+    fn apply_privacy_should_redact_content_but_keep_run_metadata() {
+        let mut payload = run_payload();
+        apply_privacy(&mut payload, &PrivacySettings::default(), true);
+
+        assert_eq!(payload.user_prompt, REDACTED_CONTENT);
+        assert_eq!(payload.output_text, REDACTED_CONTENT);
+        let request: Value = serde_json::from_str(&payload.request_json).expect("request json");
+        assert_eq!(request["model"], "openrouter/auto");
+        assert_eq!(request["messages"][0]["role"], "user");
+        assert_eq!(request["messages"][0]["content"], REDACTED_CONTENT);
+        assert_eq!(request["api_key"], REDACTED_CONTENT);
+        let response: Value = serde_json::from_str(&payload.response_json).expect("response json");
+        assert_eq!(response["choices"][0]["finish_reason"], "stop");
+        assert_eq!(response["usage"]["total_tokens"], 12);
+    }
+
+    #[test]
+    fn apply_privacy_should_scrub_configured_patterns_when_content_is_logged() {
+        let mut payload = run_payload();
+        let settings = PrivacySettings {
+            do_not_log_content: false,
+            scrub_patterns: vec![r"[\w.+-]+@[\w-]+\.[\w.]+".to_string()],
+        };
+        apply_privacy(&mut payload, &settings, false);
+
+        assert_eq!(payload.user_prompt, "Email [REDACTED] about the launch");
+        let request: Value = serde_json::from_str(&payload.request_json).expect("request json");
+        assert_eq!(request["messages"][0]["content"], "Email [REDACTED]");
+        assert_eq!(payload.output_text, "Done");
+        assert!(normalize_scrub_patterns(vec!["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn check_local_only_should_reject_cloud_providers_for_local_only_camps() {
+        assert!(check_local_only(true, "notes", ProviderKind::Ollama).is_ok());
+        assert!(check_local_only(false, "notes", ProviderKind::Openrouter).is_ok());
+
+        let error = check_local_only(true, "notes", ProviderKind::Openrouter)
+            .expect_err("cloud provider should be rejected");
+        assert_eq!(error.kind, BasecampErrorKind::LocalOnly);
+        assert_eq!(error.context["provider_kind"], "openrouter");
+        assert_eq!(error.context["camp_id"], "notes");
+    }
+
+    #[test]
+    fn scope_chat_to_camp_should_hold_untagged_requests_to_the_camp_policy() {
+        let workspace = make_temp_dir("basecamp-routing");
+        let camp_dir = workspace.join("camps/notes");
+        fs::create_dir_all(&camp_dir).expect("camp folder should be created");
+        fs::write(
+            crate::camp_config_path(&camp_dir),
+            r#"{"schema_version":"0.3","id":"notes","name":"Notes","model":"openrouter/auto","provider_kind":"openrouter","model_id":"auto","tools_enabled":false,"is_team":false,"local_only":true,"created_at":1,"updated_at":1}"#,
+        )
+        .expect("camp config should write");
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        crate::migrations::run_migrations(&connection, false).expect("schema should be created");
+        set_setting_value(
+            &connection,
+            crate::SETTING_WORKSPACE_PATH,
+            &workspace.to_string_lossy(),
+        )
+        .expect("workspace should save");
+
+        let mut request: BasecampChatRequest = serde_json::from_value(serde_json::json!({
+            "provider_kind": "openrouter",
+            "model_id": "auto",
+            "messages": [{ "role": "user", "content": "hi" }]
+        }))
+        .expect("request should parse");
+        assert!(enforce_camp_routing(&connection, None, request.provider_kind).is_ok());
+
+        scope_chat_to_camp(&mut request, "notes").expect("untagged request should be scoped");
+        let error = enforce_camp_routing(
+            &connection,
+            request.metadata.camp_id.as_deref(),
+            request.provider_kind,
+        )
+        .expect_err("local-only camp should refuse a cloud provider");
+        assert_eq!(error.kind, BasecampErrorKind::LocalOnly);
+
+        assert!(scope_chat_to_camp(&mut request, "other").is_err());
+        assert!(scope_chat_to_camp(&mut request, " ").is_err());
+    }
+}
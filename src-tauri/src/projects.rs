@@ -0,0 +1,287 @@
+//! Projects group camps for people with more than a screenful of them. The
+//! project list lives in `projects.json` at the top of the workspace, and each
+//! camp names its project in `camp.json` as `project_id`. A camp belongs to at
+//! most one project; camps without one are listed as ungrouped.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::{
+    ensure_camps_root, ensure_main_window, list_camp_summaries, normalize_camp_color,
+    now_timestamp_ms, read_camp_config, read_json_file, require_workspace_path,
+    resolve_existing_camp_dir, validate_non_empty, write_camp_config, write_json_file, AppState,
+    CampSummary,
+};
+
+const PROJECTS_FILE: &str = "projects.json";
+const PROJECTS_SCHEMA_VERSION: &str = "0.1";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CampProject {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectsFile {
+    schema_version: String,
+    #[serde(default)]
+    projects: Vec<CampProject>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CampProjectGroup {
+    /// `None` for camps that aren't in any project.
+    pub project: Option<CampProject>,
+    pub camps: Vec<CampSummary>,
+}
+
+fn projects_path(workspace_path: &Path) -> PathBuf {
+    workspace_path.join(PROJECTS_FILE)
+}
+
+/// Empty when the workspace has no projects file yet.
+fn read_projects(workspace_path: &Path) -> Result<Vec<CampProject>, String> {
+    let path = projects_path(workspace_path);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let file: ProjectsFile = read_json_file(&path)?;
+    Ok(file.projects)
+}
+
+fn write_projects(workspace_path: &Path, projects: Vec<CampProject>) -> Result<(), String> {
+    write_json_file(
+        &projects_path(workspace_path),
+        &ProjectsFile {
+            schema_version: PROJECTS_SCHEMA_VERSION.to_string(),
+            projects,
+        },
+    )
+}
+
+/// One group per project, in project order and including empty ones, then the
+/// ungrouped camps if there are any. A camp whose project no longer exists
+/// counts as ungrouped. Camps keep the order they came in.
+fn group_camps(projects: &[CampProject], camps: Vec<CampSummary>) -> Vec<CampProjectGroup> {
+    let mut groups: Vec<CampProjectGroup> = projects
+        .iter()
+        .map(|project| CampProjectGroup {
+            project: Some(project.clone()),
+            camps: Vec::new(),
+        })
+        .collect();
+    let mut ungrouped = Vec::new();
+    for camp in camps {
+        let slot = camp
+            .project_id
+            .as_deref()
+            .and_then(|project_id| projects.iter().position(|project| project.id == project_id));
+        match slot {
+            Some(index) => groups[index].camps.push(camp),
+            None => ungrouped.push(camp),
+        }
+    }
+    if !ungrouped.is_empty() {
+        groups.push(CampProjectGroup {
+            project: None,
+            camps: ungrouped,
+        });
+    }
+    groups
+}
+
+#[tauri::command]
+pub fn project_list(state: State<'_, AppState>) -> Result<Vec<CampProject>, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    read_projects(&require_workspace_path(&connection)?)
+}
+
+#[tauri::command]
+pub fn project_create(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+    color: Option<String>,
+) -> Result<CampProject, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let name = validate_non_empty(&name, "name")?;
+    let color = match color.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(color) => Some(
+            normalize_camp_color(color)
+                .ok_or_else(|| "color must be a hex value like #3a7bd5.".to_string())?,
+        ),
+    };
+
+    let mut projects = read_projects(&workspace_path)?;
+    if projects
+        .iter()
+        .any(|project| project.name.eq_ignore_ascii_case(&name))
+    {
+        return Err(format!("A project named `{name}` already exists."));
+    }
+    let project = CampProject {
+        id: Uuid::new_v4().to_string(),
+        name,
+        color,
+        created_at: now_timestamp_ms(),
+    };
+    projects.push(project.clone());
+    write_projects(&workspace_path, projects)?;
+    Ok(project)
+}
+
+/// Deletes a project. Its camps are kept and become ungrouped.
+#[tauri::command]
+pub fn project_delete(
+    window: Window,
+    state: State<'_, AppState>,
+    project_id: String,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let camps_root = ensure_camps_root(&connection)?;
+
+    let mut projects = read_projects(&workspace_path)?;
+    let before = projects.len();
+    projects.retain(|project| project.id != project_id);
+    if projects.len() == before {
+        return Err(format!("Project `{project_id}` not found."));
+    }
+    for camp in list_camp_summaries(&camps_root)? {
+        if camp.project_id.as_deref() == Some(project_id.as_str()) {
+            let camp_dir = PathBuf::from(&camp.path);
+            let mut config = read_camp_config(&camp_dir)?;
+            config.project_id = None;
+            write_camp_config(&camp_dir, &config)?;
+        }
+    }
+    write_projects(&workspace_path, projects)
+}
+
+/// Moves a camp into a project, or out of any project when `project_id` is
+/// omitted. The camp's `updated_at` is left alone so it keeps its place.
+#[tauri::command]
+pub fn camp_set_project(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    project_id: Option<String>,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let project_id = project_id.filter(|project_id| !project_id.trim().is_empty());
+    if let Some(project_id) = project_id.as_deref() {
+        if !read_projects(&workspace_path)?
+            .iter()
+            .any(|project| project.id == project_id)
+        {
+            return Err(format!("Project `{project_id}` not found."));
+        }
+    }
+    let mut config = read_camp_config(&camp_dir)?;
+    config.project_id = project_id;
+    write_camp_config(&camp_dir, &config)
+}
+
+/// `camp_list` grouped by project; see `group_camps` for the order.
+#[tauri::command]
+pub fn camp_list_grouped(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<Vec<CampProjectGroup>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let projects = read_projects(&require_workspace_path(&connection)?)?;
+    let camps = list_camp_summaries(&ensure_camps_root(&connection)?)?;
+    Ok(group_camps(&projects, camps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_camps_should_keep_empty_projects_and_collect_orphans_last() {
+        let project = |id: &str| CampProject {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            color: None,
+            created_at: 0,
+        };
+        let camp = |id: &str, project_id: Option<&str>| CampSummary {
+            id: id.to_string(),
+            name: id.to_string(),
+            model: "openrouter/auto".to_string(),
+            description: None,
+            color: None,
+            emoji: None,
+            pinned: false,
+            favorite: false,
+            tags: Vec::new(),
+            project_id: project_id.map(str::to_string),
+            last_opened_at: None,
+            updated_at: 0,
+            path: format!("/camps/{id}"),
+        };
+
+        let groups = group_camps(
+            &[project("work"), project("home")],
+            vec![
+                camp("a", Some("work")),
+                camp("b", None),
+                camp("c", Some("deleted")),
+                camp("d", Some("work")),
+            ],
+        );
+        let summary: Vec<(Option<String>, Vec<String>)> = groups
+            .iter()
+            .map(|group| {
+                (
+                    group.project.as_ref().map(|project| project.id.clone()),
+                    group.camps.iter().map(|camp| camp.id.clone()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    Some("work".to_string()),
+                    vec!["a".to_string(), "d".to_string()]
+                ),
+                (Some("home".to_string()), Vec::new()),
+                (None, vec!["b".to_string(), "c".to_string()]),
+            ]
+        );
+    }
+}
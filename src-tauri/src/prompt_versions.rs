@@ -0,0 +1,436 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::{
+    camp_system_prompt_path, ensure_camps_root, ensure_main_window, now_timestamp_ms,
+    read_text_file, resolve_existing_camp_dir, touch_camp_updated_at, validate_identifier,
+    write_file_atomic, AppState,
+};
+
+const CAMP_PROMPT_VERSIONS_DIR: &str = ".prompt_versions";
+const PROMPT_VERSION_EXTENSION: &str = "md";
+const DIFF_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptVersion {
+    pub id: String,
+    pub created_at: i64,
+    pub size_bytes: u64,
+    pub current: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampDiffPromptVersionsPayload {
+    pub camp_id: String,
+    pub from_version_id: String,
+    /// Defaults to the live system prompt when omitted.
+    pub to_version_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampRestorePromptVersionPayload {
+    pub camp_id: String,
+    pub version_id: String,
+}
+
+fn prompt_versions_dir(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_PROMPT_VERSIONS_DIR)
+}
+
+fn prompt_version_path(camp_dir: &Path, version_id: &str) -> Result<PathBuf, String> {
+    let validated = validate_identifier(version_id, "version_id")?;
+    let path =
+        prompt_versions_dir(camp_dir).join(format!("{validated}.{PROMPT_VERSION_EXTENSION}"));
+    if !path.is_file() {
+        return Err("Prompt version not found.".to_string());
+    }
+    Ok(path)
+}
+
+/// Version ids are `<created_at>-<suffix>` so a plain directory listing sorts and
+/// dates them without a separate index file.
+fn parse_version_created_at(version_id: &str) -> Option<i64> {
+    version_id.split_once('-')?.0.parse().ok()
+}
+
+fn list_prompt_versions(camp_dir: &Path) -> Result<Vec<PromptVersion>, String> {
+    let versions_dir = prompt_versions_dir(camp_dir);
+    if !versions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let current = fs::read(camp_system_prompt_path(camp_dir)).unwrap_or_default();
+    let entries = fs::read_dir(&versions_dir)
+        .map_err(|err| format!("Unable to read prompt versions: {err}"))?;
+
+    let mut versions = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|err| format!("Unable to read prompt versions: {err}"))?;
+        let path = entry.path();
+        if path.extension().and_then(|value| value.to_str()) != Some(PROMPT_VERSION_EXTENSION) {
+            continue;
+        }
+        let Some(id) = path.file_stem().and_then(|value| value.to_str()) else {
+            continue;
+        };
+        let Some(created_at) = parse_version_created_at(id) else {
+            continue;
+        };
+        let contents =
+            fs::read(&path).map_err(|err| format!("Unable to read prompt version {id}: {err}"))?;
+        versions.push(PromptVersion {
+            id: id.to_string(),
+            created_at,
+            size_bytes: contents.len() as u64,
+            current: contents == current,
+        });
+    }
+
+    versions.sort_by(|left, right| {
+        right
+            .created_at
+            .cmp(&left.created_at)
+            .then_with(|| right.id.cmp(&left.id))
+    });
+    Ok(versions)
+}
+
+fn snapshot_prompt_version(camp_dir: &Path, contents: &str) -> Result<PromptVersion, String> {
+    let versions_dir = prompt_versions_dir(camp_dir);
+    fs::create_dir_all(&versions_dir)
+        .map_err(|err| format!("Unable to create prompt versions folder: {err}"))?;
+
+    // Keep ids strictly increasing even when two saves land in the same millisecond.
+    let created_at = match list_prompt_versions(camp_dir)?.first() {
+        Some(latest) => now_timestamp_ms().max(latest.created_at + 1),
+        None => now_timestamp_ms(),
+    };
+    let suffix = Uuid::new_v4().simple().to_string();
+    let id = format!("{created_at}-{}", &suffix[..8]);
+    write_file_atomic(
+        &versions_dir.join(format!("{id}.{PROMPT_VERSION_EXTENSION}")),
+        contents.as_bytes(),
+    )?;
+
+    Ok(PromptVersion {
+        id,
+        created_at,
+        size_bytes: contents.len() as u64,
+        current: true,
+    })
+}
+
+/// Writes the camp system prompt and records it in the version history. Camps
+/// created before history existed get their previous prompt captured first so the
+/// first edit is still diffable.
+pub(crate) fn write_system_prompt_with_history(
+    camp_dir: &Path,
+    system_prompt: &str,
+) -> Result<(), String> {
+    let prompt_path = camp_system_prompt_path(camp_dir);
+    let existing = list_prompt_versions(camp_dir)?;
+
+    if existing.is_empty() && prompt_path.exists() {
+        let previous = read_text_file(&prompt_path)?;
+        if previous != system_prompt {
+            snapshot_prompt_version(camp_dir, &previous)?;
+        }
+    }
+
+    write_file_atomic(&prompt_path, system_prompt.as_bytes())?;
+
+    let latest_matches = match existing.first() {
+        Some(latest) => {
+            read_text_file(&prompt_version_path(camp_dir, &latest.id)?)? == system_prompt
+        }
+        None => false,
+    };
+    if !latest_matches {
+        snapshot_prompt_version(camp_dir, system_prompt)?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Line-level LCS diff. Common prefix and suffix are trimmed first so typical
+/// prompt edits only pay for the changed region. Returns `None` when the
+/// changed region would need a table of more than `max_cells` entries.
+fn diff_lines<'a>(
+    old: &[&'a str],
+    new: &[&'a str],
+    max_cells: usize,
+) -> Option<Vec<(DiffOp, &'a str)>> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let rows = old_mid.len();
+    let cols = new_mid.len();
+    let cells = (rows + 1)
+        .checked_mul(cols + 1)
+        .filter(|cells| *cells <= max_cells)?;
+    let mut lengths = vec![0u32; cells];
+    for row in (0..rows).rev() {
+        for col in (0..cols).rev() {
+            lengths[row * (cols + 1) + col] = if old_mid[row] == new_mid[col] {
+                lengths[(row + 1) * (cols + 1) + col + 1] + 1
+            } else {
+                lengths[(row + 1) * (cols + 1) + col].max(lengths[row * (cols + 1) + col + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(DiffOp, &str)> = old[..prefix]
+        .iter()
+        .map(|line| (DiffOp::Equal, *line))
+        .collect();
+    let (mut row, mut col) = (0, 0);
+    while row < rows && col < cols {
+        if old_mid[row] == new_mid[col] {
+            ops.push((DiffOp::Equal, old_mid[row]));
+            row += 1;
+            col += 1;
+        } else if lengths[(row + 1) * (cols + 1) + col] >= lengths[row * (cols + 1) + col + 1] {
+            ops.push((DiffOp::Delete, old_mid[row]));
+            row += 1;
+        } else {
+            ops.push((DiffOp::Insert, new_mid[col]));
+            col += 1;
+        }
+    }
+    ops.extend(old_mid[row..].iter().map(|line| (DiffOp::Delete, *line)));
+    ops.extend(new_mid[col..].iter().map(|line| (DiffOp::Insert, *line)));
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (DiffOp::Equal, *line)),
+    );
+    Some(ops)
+}
+
+fn hunk_range(start: usize, len: usize) -> String {
+    // Unified diff numbers an empty range by the line before it.
+    if len == 0 {
+        format!("{start},0")
+    } else {
+        format!("{},{}", start + 1, len)
+    }
+}
+
+/// Renders a unified diff (`diff -u` layout, three lines of context). Returns an
+/// empty string when the inputs are identical.
+pub(crate) fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    bounded_unified_diff(old, new, old_label, new_label, usize::MAX).unwrap_or_default()
+}
+
+/// Like [`unified_diff`], but gives up with `None` instead of allocating a
+/// diff table larger than `max_cells` entries.
+pub(crate) fn bounded_unified_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    max_cells: usize,
+) -> Option<String> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines, max_cells)?;
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != DiffOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+    if changes.is_empty() {
+        return Some(String::new());
+    }
+
+    let mut old_positions = Vec::with_capacity(ops.len() + 1);
+    let mut new_positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_position, mut new_position) = (0usize, 0usize);
+    for (op, _) in &ops {
+        old_positions.push(old_position);
+        new_positions.push(new_position);
+        if *op != DiffOp::Insert {
+            old_position += 1;
+        }
+        if *op != DiffOp::Delete {
+            new_position += 1;
+        }
+    }
+    old_positions.push(old_position);
+    new_positions.push(new_position);
+
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &change in &changes {
+        match hunks.last_mut() {
+            Some((_, last)) if change <= *last + 2 * DIFF_CONTEXT_LINES => *last = change,
+            _ => hunks.push((change, change)),
+        }
+    }
+
+    let mut output = format!("--- {old_label}\n+++ {new_label}\n");
+    for (first, last) in hunks {
+        let start = first.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (last + 1 + DIFF_CONTEXT_LINES).min(ops.len());
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(
+                old_positions[start],
+                old_positions[end] - old_positions[start]
+            ),
+            hunk_range(
+                new_positions[start],
+                new_positions[end] - new_positions[start]
+            ),
+        ));
+        for (op, line) in &ops[start..end] {
+            let marker = match op {
+                DiffOp::Equal => ' ',
+                DiffOp::Delete => '-',
+                DiffOp::Insert => '+',
+            };
+            output.push(marker);
+            output.push_str(line);
+            output.push('\n');
+        }
+    }
+    Some(output)
+}
+
+#[tauri::command]
+pub fn camp_list_prompt_versions(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<PromptVersion>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    list_prompt_versions(&camp_dir)
+}
+
+#[tauri::command]
+pub fn camp_diff_prompt_versions(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampDiffPromptVersionsPayload,
+) -> Result<String, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let from = read_text_file(&prompt_version_path(&camp_dir, &payload.from_version_id)?)?;
+    let (to, to_label) = match payload.to_version_id.as_deref() {
+        Some(version_id) => (
+            read_text_file(&prompt_version_path(&camp_dir, version_id)?)?,
+            version_id.to_string(),
+        ),
+        None => (
+            read_text_file(&camp_system_prompt_path(&camp_dir))?,
+            "current".to_string(),
+        ),
+    };
+
+    Ok(unified_diff(
+        &from,
+        &to,
+        &payload.from_version_id,
+        &to_label,
+    ))
+}
+
+#[tauri::command]
+pub fn camp_restore_prompt_version(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampRestorePromptVersionPayload,
+) -> Result<String, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+
+    let restored = read_text_file(&prompt_version_path(&camp_dir, &payload.version_id)?)?;
+    write_system_prompt_with_history(&camp_dir, &restored)?;
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn unified_diff_should_emit_hunks_with_context() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\n";
+        let new = "one\ntwo\nthree\nfour\nFIVE\nsix\nseven\neight\nnine\nten\n";
+
+        let diff = unified_diff(old, new, "a", "b");
+        assert_eq!(
+            diff,
+            "--- a\n+++ b\n@@ -2,8 +2,9 @@\n two\n three\n four\n-five\n+FIVE\n six\n seven\n eight\n nine\n+ten\n"
+        );
+        assert!(unified_diff(old, old, "a", "b").is_empty());
+        assert_eq!(bounded_unified_diff(old, new, "a", "b", 41), None);
+        assert_eq!(bounded_unified_diff(old, new, "a", "b", 42), Some(diff));
+    }
+
+    #[test]
+    fn write_system_prompt_with_history_should_capture_legacy_prompt_and_skip_duplicates() {
+        let camp_dir = make_temp_dir("basecamp-prompts");
+        fs::write(camp_system_prompt_path(&camp_dir), "original")
+            .expect("legacy prompt should write");
+
+        write_system_prompt_with_history(&camp_dir, "revised")
+            .expect("prompt update should succeed");
+        write_system_prompt_with_history(&camp_dir, "revised")
+            .expect("repeated update should succeed");
+
+        let versions = list_prompt_versions(&camp_dir).expect("versions should list");
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0].current);
+        assert!(!versions[1].current);
+        assert_eq!(
+            read_text_file(&prompt_version_path(&camp_dir, &versions[1].id).expect("path"))
+                .expect("old version should read"),
+            "original"
+        );
+    }
+}
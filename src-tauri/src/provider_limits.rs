@@ -0,0 +1,129 @@
+//! How many requests each local provider may run at once. Local llama.cpp,
+//! Ollama and LM Studio servers slow down sharply under parallel requests, so
+//! they default to one at a time; extra requests wait in the provider's queue
+//! and report their place in line to the chat stream.
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::queue::DEFAULT_LOCAL_CONCURRENCY;
+use crate::providers::{ProviderKind, ProviderManager};
+use crate::{
+    ensure_main_window, get_setting_value, parse_provider_kind, set_setting_value, AppState,
+};
+
+const MAX_LOCAL_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderConcurrency {
+    pub provider_kind: ProviderKind,
+    pub max_concurrent_requests: usize,
+}
+
+fn setting_key(kind: ProviderKind) -> String {
+    format!("max_concurrent_requests_{}", kind.as_str())
+}
+
+fn local_kinds() -> impl Iterator<Item = ProviderKind> {
+    ProviderKind::ALL.into_iter().filter(|kind| kind.is_local())
+}
+
+/// Saved limit for `kind`; unset or unreadable values fall back to the default.
+fn load_limit(connection: &Connection, kind: ProviderKind) -> usize {
+    get_setting_value(connection, &setting_key(kind))
+        .ok()
+        .flatten()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .map(|limit| limit.clamp(1, MAX_LOCAL_CONCURRENCY))
+        .unwrap_or(DEFAULT_LOCAL_CONCURRENCY)
+}
+
+pub(crate) fn provider_manager_with_saved_limits(connection: &Connection) -> ProviderManager {
+    let manager = ProviderManager::new();
+    for kind in local_kinds() {
+        manager.set_max_concurrent_requests(kind, load_limit(connection, kind));
+    }
+    manager
+}
+
+#[tauri::command]
+pub fn get_provider_concurrency(
+    state: State<'_, AppState>,
+) -> CommandResult<Vec<ProviderConcurrency>> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    Ok(local_kinds()
+        .map(|provider_kind| ProviderConcurrency {
+            provider_kind,
+            max_concurrent_requests: load_limit(&connection, provider_kind),
+        })
+        .collect())
+}
+
+/// Saves a local provider's limit and applies it to its queue immediately.
+#[tauri::command]
+pub fn set_provider_concurrency(
+    window: Window,
+    state: State<'_, AppState>,
+    provider_kind: String,
+    max_concurrent_requests: usize,
+) -> CommandResult<ProviderConcurrency> {
+    ensure_main_window(&window)?;
+    let provider_kind = parse_provider_kind(&provider_kind).map_err(BasecampError::validation)?;
+    if !provider_kind.is_local() {
+        return Err(BasecampError::validation(format!(
+            "`{}` is a cloud provider; only local providers have a concurrency limit.",
+            provider_kind.as_str()
+        ))
+        .with_context("field", "provider_kind"));
+    }
+    if !(1..=MAX_LOCAL_CONCURRENCY).contains(&max_concurrent_requests) {
+        return Err(BasecampError::validation(format!(
+            "max_concurrent_requests must be between 1 and {MAX_LOCAL_CONCURRENCY}."
+        ))
+        .with_context("field", "max_concurrent_requests"));
+    }
+
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    set_setting_value(
+        &connection,
+        &setting_key(provider_kind),
+        &max_concurrent_requests.to_string(),
+    )
+    .map_err(|err| BasecampError::database(format!("Unable to save concurrency limit: {err}")))?;
+    state
+        .provider_manager
+        .set_max_concurrent_requests(provider_kind, max_concurrent_requests);
+
+    Ok(ProviderConcurrency {
+        provider_kind,
+        max_concurrent_requests,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_limit_should_default_and_clamp_saved_values() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        connection
+            .execute_batch("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL);")
+            .expect("settings table");
+        assert_eq!(
+            load_limit(&connection, ProviderKind::Ollama),
+            DEFAULT_LOCAL_CONCURRENCY
+        );
+
+        set_setting_value(&connection, &setting_key(ProviderKind::Ollama), "3").expect("save");
+        assert_eq!(load_limit(&connection, ProviderKind::Ollama), 3);
+        set_setting_value(&connection, &setting_key(ProviderKind::Ollama), "64").expect("save");
+        assert_eq!(
+            load_limit(&connection, ProviderKind::Ollama),
+            MAX_LOCAL_CONCURRENCY
+        );
+        assert!(local_kinds().all(|kind| kind != ProviderKind::Openrouter));
+    }
+}
@@ -10,6 +10,13 @@ pub enum ProviderKind {
 }
 
 impl ProviderKind {
+    pub const ALL: [ProviderKind; 4] = [
+        ProviderKind::Openrouter,
+        ProviderKind::Lmstudio,
+        ProviderKind::Ollama,
+        ProviderKind::LlamaCpp,
+    ];
+
     pub fn as_str(self) -> &'static str {
         match self {
             ProviderKind::Openrouter => "openrouter",
@@ -29,6 +36,11 @@ impl ProviderKind {
         }
     }
 
+    /// Providers that run on the user's machine; requests to them never leave it.
+    pub fn is_local(self) -> bool {
+        !matches!(self, ProviderKind::Openrouter)
+    }
+
     pub fn default_base_url(self) -> &'static str {
         match self {
             ProviderKind::Openrouter => "https://openrouter.ai/api/v1",
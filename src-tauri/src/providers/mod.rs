@@ -1,4 +1,5 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -10,9 +11,11 @@ pub mod llama_cpp;
 pub mod lmstudio;
 pub mod ollama;
 pub mod openrouter;
+pub mod queue;
 pub mod registry;
 
 pub use capabilities::{ProviderCapabilities, ProviderKind, StreamProtocol};
+pub use queue::{ProviderQueueStatus, QueuePermit, RequestPriority};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ProviderUsage {
@@ -39,6 +42,11 @@ pub struct BasecampChatRequest {
     pub max_tokens: Option<i64>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Token id to bias (-100..=100). Ollama has no equivalent and ignores it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<BTreeMap<String, f64>>,
     #[serde(default)]
     pub stream: bool,
     #[serde(default)]
@@ -53,6 +61,10 @@ pub struct BasecampChatMetadata {
     pub correlation_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub provider_kind: Option<ProviderKind>,
+    /// Queue lane for the request; anything the user is waiting on is
+    /// interactive.
+    #[serde(default)]
+    pub priority: RequestPriority,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +111,9 @@ pub struct NormalizedAssistantMessage {
     pub content: Option<Value>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tool_calls: Vec<Value>,
+    /// Model "thinking" kept apart from `content` so it is shown but never resent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
 }
 
 impl Default for NormalizedAssistantMessage {
@@ -107,6 +122,7 @@ impl Default for NormalizedAssistantMessage {
             role: "assistant".to_string(),
             content: Some(Value::String(String::new())),
             tool_calls: Vec::new(),
+            reasoning: None,
         }
     }
 }
@@ -119,6 +135,10 @@ pub enum ChatStreamEvent {
         role: String,
         content_delta: String,
     },
+    ReasoningDelta {
+        correlation_id: String,
+        reasoning_delta: String,
+    },
     ToolCallDelta {
         correlation_id: String,
         tool_call_id: String,
@@ -136,6 +156,24 @@ pub enum ChatStreamEvent {
         correlation_id: String,
         message: String,
     },
+    /// The provider is busy; sent while the request waits for a slot, each
+    /// time its place in line changes.
+    ChatQueued {
+        correlation_id: String,
+        /// 1 means next in line.
+        position: usize,
+    },
+    /// Sent a few times a second while tokens arrive, and once more at the end
+    /// with the provider's final count when it reports one.
+    ChatProgress {
+        correlation_id: String,
+        tokens: u64,
+        /// Throughput since the previous progress event; the final event
+        /// carries the average for the whole reply.
+        tokens_per_second: f64,
+        /// Time since the first token.
+        elapsed_ms: u64,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +194,9 @@ pub struct ProviderChatResponse {
     pub stream_chunk_count: usize,
     pub sanitized_request_payload: Value,
     pub sanitized_response_payload: Value,
+    /// Set when outbound scrubbing ran on the request before it was sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scrub_audit: Option<crate::scrubber::ScrubAudit>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,24 +225,6 @@ impl std::fmt::Display for ProviderError {
 
 impl std::error::Error for ProviderError {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProviderCommandError {
-    pub message: String,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub status: Option<u16>,
-    pub response_payload: Value,
-}
-
-impl From<ProviderError> for ProviderCommandError {
-    fn from(value: ProviderError) -> Self {
-        Self {
-            message: value.message,
-            status: value.status,
-            response_payload: value.response_payload,
-        }
-    }
-}
-
 #[async_trait]
 pub trait Provider: Send + Sync {
     fn kind(&self) -> ProviderKind;
@@ -247,6 +270,7 @@ pub struct ProviderManager {
     lmstudio: lmstudio::LmStudioProvider,
     ollama: ollama::OllamaProvider,
     llama_cpp: llama_cpp::LlamaCppProvider,
+    queues: HashMap<ProviderKind, queue::RequestQueue>,
 }
 
 impl ProviderManager {
@@ -256,6 +280,10 @@ impl ProviderManager {
             lmstudio: lmstudio::LmStudioProvider::new(),
             ollama: ollama::OllamaProvider::new(),
             llama_cpp: llama_cpp::LlamaCppProvider::new(),
+            queues: ProviderKind::ALL
+                .into_iter()
+                .map(|kind| (kind, queue::RequestQueue::for_provider(kind)))
+                .collect(),
         }
     }
 
@@ -267,6 +295,32 @@ impl ProviderManager {
             ProviderKind::LlamaCpp => &self.llama_cpp,
         }
     }
+
+    /// Waits for a free request slot on `kind`'s queue, reporting the
+    /// request's place in line through `on_position` while it waits. Hold the
+    /// permit for as long as the request runs.
+    pub async fn acquire_slot(
+        &self,
+        kind: ProviderKind,
+        priority: RequestPriority,
+        on_position: impl FnMut(usize),
+    ) -> QueuePermit<'_> {
+        self.queues[&kind]
+            .acquire_with_position(priority, on_position)
+            .await
+    }
+
+    /// How many requests `kind` may run at once.
+    pub fn set_max_concurrent_requests(&self, kind: ProviderKind, limit: usize) {
+        self.queues[&kind].set_capacity(limit);
+    }
+
+    pub fn queue_status(&self) -> Vec<ProviderQueueStatus> {
+        ProviderKind::ALL
+            .into_iter()
+            .map(|kind| self.queues[&kind].status(kind))
+            .collect()
+    }
 }
 
 impl Default for ProviderManager {
@@ -372,7 +426,7 @@ pub fn parse_openai_assistant_message(payload: &Value) -> NormalizedAssistantMes
         .and_then(|choice| choice.get("message"))
         .unwrap_or(&default_message);
 
-    let content = message.get("content").cloned();
+    let (content, reasoning) = split_message_reasoning(message);
     let tool_calls = message
         .get("tool_calls")
         .and_then(Value::as_array)
@@ -382,7 +436,252 @@ pub fn parse_openai_assistant_message(payload: &Value) -> NormalizedAssistantMes
         role: "assistant".to_string(),
         content,
         tool_calls,
+        reasoning,
+    }
+}
+
+const THINK_OPEN_TAG: &str = "<think>";
+const THINK_CLOSE_TAG: &str = "</think>";
+
+/// Reads reasoning from the provider-specific field: `reasoning_content`
+/// (DeepSeek, LM Studio, llama.cpp), `reasoning` (OpenRouter) or `thinking` (Ollama).
+pub fn parse_reasoning_field(message: &Value) -> Option<String> {
+    ["reasoning_content", "reasoning", "thinking"]
+        .iter()
+        .filter_map(|key| message.get(*key).and_then(Value::as_str))
+        .find(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Returns the message content with any `<think>` blocks removed, plus the
+/// reasoning gathered from both the dedicated field and those blocks.
+pub fn split_message_reasoning(message: &Value) -> (Option<Value>, Option<String>) {
+    let mut splitter = ReasoningSplitter::default();
+    if let Some(reasoning) = parse_reasoning_field(message) {
+        splitter.push_reasoning(&reasoning);
+    }
+
+    let content = match message.get("content") {
+        Some(Value::String(text)) => {
+            splitter.push_content(text);
+            splitter.finish();
+            Some(Value::String(splitter.content().to_string()))
+        }
+        other => other.cloned(),
+    };
+    (content, splitter.reasoning())
+}
+
+/// Separates reasoning from answer text while streaming. Reasoning arrives either
+/// in its own delta field or inline as `<think>...</think>`, and a tag may be
+/// split across chunks, so a possible partial tag is held back until resolved.
+#[derive(Debug, Default)]
+pub struct ReasoningSplitter {
+    in_think: bool,
+    pending: String,
+    content: String,
+    reasoning: String,
+}
+
+impl ReasoningSplitter {
+    /// Feeds a content delta; returns the `(content, reasoning)` text it released.
+    pub fn push_content(&mut self, delta: &str) -> (String, String) {
+        self.pending.push_str(delta);
+        let mut released = (String::new(), String::new());
+
+        loop {
+            let tag = if self.in_think {
+                THINK_CLOSE_TAG
+            } else {
+                THINK_OPEN_TAG
+            };
+            if let Some(index) = self.pending.find(tag) {
+                let before: String = self.pending.drain(..index).collect();
+                self.pending.drain(..tag.len());
+                self.release(&before, &mut released);
+                self.in_think = !self.in_think;
+                continue;
+            }
+
+            let keep = (1..tag.len())
+                .rev()
+                .find(|len| self.pending.ends_with(&tag[..*len]))
+                .unwrap_or(0);
+            let split_at = self.pending.len() - keep;
+            let ready: String = self.pending.drain(..split_at).collect();
+            self.release(&ready, &mut released);
+            return released;
+        }
+    }
+
+    pub fn push_reasoning(&mut self, delta: &str) -> String {
+        self.reasoning.push_str(delta);
+        delta.to_string()
+    }
+
+    /// Flushes any held-back text once the stream ends.
+    pub fn finish(&mut self) -> (String, String) {
+        let remaining = std::mem::take(&mut self.pending);
+        let mut released = (String::new(), String::new());
+        self.release(&remaining, &mut released);
+        released
+    }
+
+    pub fn content(&self) -> &str {
+        self.content.trim_start()
+    }
+
+    pub fn reasoning(&self) -> Option<String> {
+        let trimmed = self.reasoning.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+
+    fn release(&mut self, text: &str, released: &mut (String, String)) {
+        if text.is_empty() {
+            return;
+        }
+        if self.in_think {
+            self.reasoning.push_str(text);
+            released.1.push_str(text);
+        } else {
+            self.content.push_str(text);
+            released.0.push_str(text);
+        }
+    }
+}
+
+/// Emits the content and reasoning released by a [`ReasoningSplitter`] as
+/// separate stream events.
+pub fn emit_split_deltas(
+    on_event: Option<&Channel<ChatStreamEvent>>,
+    correlation_id: &str,
+    content_delta: String,
+    reasoning_delta: String,
+) {
+    let Some(channel) = on_event else {
+        return;
+    };
+    if !reasoning_delta.is_empty() {
+        let _ = channel.send(ChatStreamEvent::ReasoningDelta {
+            correlation_id: correlation_id.to_string(),
+            reasoning_delta,
+        });
+    }
+    if !content_delta.is_empty() {
+        let _ = channel.send(ChatStreamEvent::ChatDelta {
+            correlation_id: correlation_id.to_string(),
+            role: "assistant".to_string(),
+            content_delta,
+        });
+    }
+}
+
+/// Minimum time between `chat_progress` events.
+const STREAM_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Counts streamed tokens and reports throughput as `chat_progress` events.
+/// Providers don't send token counts mid-stream, so each chunk that carries
+/// text, reasoning or tool arguments counts as one token.
+pub struct StreamProgress {
+    correlation_id: String,
+    tokens: u64,
+    first_token_at: Option<Instant>,
+    /// When the last event went out and the count it reported.
+    last_report: Option<(Instant, u64)>,
+}
+
+impl StreamProgress {
+    pub fn new(correlation_id: &str) -> Self {
+        Self {
+            correlation_id: correlation_id.to_string(),
+            tokens: 0,
+            first_token_at: None,
+            last_report: None,
+        }
+    }
+
+    pub fn record(&mut self, on_event: Option<&Channel<ChatStreamEvent>>, tokens: u64) {
+        let Some(channel) = on_event else {
+            return;
+        };
+        if let Some(event) = self.sample(tokens, Instant::now()) {
+            let _ = channel.send(event);
+        }
+    }
+
+    fn sample(&mut self, tokens: u64, now: Instant) -> Option<ChatStreamEvent> {
+        if tokens == 0 {
+            return None;
+        }
+        let first_token_at = *self.first_token_at.get_or_insert(now);
+        self.tokens += tokens;
+        let (since, reported) = self.last_report.unwrap_or((first_token_at, 0));
+        let window = now.saturating_duration_since(since);
+        if window < STREAM_PROGRESS_INTERVAL {
+            return None;
+        }
+        self.last_report = Some((now, self.tokens));
+        Some(self.event(
+            self.tokens,
+            (self.tokens - reported) as f64 / window.as_secs_f64(),
+            now.saturating_duration_since(first_token_at),
+        ))
     }
+
+    /// Reports the whole reply, preferring the provider's `completion_tokens`
+    /// over the chunk count.
+    pub fn finish(
+        &mut self,
+        on_event: Option<&Channel<ChatStreamEvent>>,
+        completion_tokens: Option<i64>,
+    ) {
+        let Some(channel) = on_event else {
+            return;
+        };
+        if let Some(event) = self.summary(completion_tokens, Instant::now()) {
+            let _ = channel.send(event);
+        }
+    }
+
+    fn summary(&self, completion_tokens: Option<i64>, now: Instant) -> Option<ChatStreamEvent> {
+        let first_token_at = self.first_token_at?;
+        let tokens = completion_tokens
+            .and_then(|tokens| u64::try_from(tokens).ok())
+            .unwrap_or(self.tokens);
+        let elapsed = now.saturating_duration_since(first_token_at);
+        let tokens_per_second = if elapsed.is_zero() {
+            0.0
+        } else {
+            tokens as f64 / elapsed.as_secs_f64()
+        };
+        Some(self.event(tokens, tokens_per_second, elapsed))
+    }
+
+    fn event(&self, tokens: u64, tokens_per_second: f64, elapsed: Duration) -> ChatStreamEvent {
+        ChatStreamEvent::ChatProgress {
+            correlation_id: self.correlation_id.clone(),
+            tokens,
+            tokens_per_second,
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+/// Drops reasoning fields from outgoing messages; prior thinking is never resent.
+pub fn strip_reasoning_fields(messages: &[Value]) -> Vec<Value> {
+    messages
+        .iter()
+        .map(|message| match message {
+            Value::Object(object) => {
+                let mut next = object.clone();
+                next.remove("reasoning");
+                next.remove("reasoning_content");
+                next.remove("thinking");
+                Value::Object(next)
+            }
+            other => other.clone(),
+        })
+        .collect()
 }
 
 pub fn correlation_id_for(request: &BasecampChatRequest) -> String {
@@ -473,4 +772,77 @@ mod tests {
             Some("[REDACTED]")
         );
     }
+
+    #[test]
+    fn reasoning_splitter_should_separate_think_tags_split_across_chunks() {
+        let mut splitter = ReasoningSplitter::default();
+        let mut content = String::new();
+        let mut reasoning = String::new();
+        for chunk in ["<thi", "nk>plan the", " answer</th", "ink>\n\nAnswer"] {
+            let (content_delta, reasoning_delta) = splitter.push_content(chunk);
+            content.push_str(&content_delta);
+            reasoning.push_str(&reasoning_delta);
+        }
+        let (content_tail, reasoning_tail) = splitter.finish();
+        content.push_str(&content_tail);
+        reasoning.push_str(&reasoning_tail);
+
+        assert_eq!(content.trim(), "Answer");
+        assert_eq!(reasoning, "plan the answer");
+        assert_eq!(splitter.content(), "Answer");
+        assert_eq!(splitter.reasoning().as_deref(), Some("plan the answer"));
+    }
+
+    #[test]
+    fn stream_progress_should_report_throughput_at_intervals() {
+        let start = Instant::now();
+        let mut progress = StreamProgress::new("turn");
+        assert!(progress.sample(0, start).is_none());
+        assert!(progress.sample(1, start).is_none());
+        assert!(progress
+            .sample(4, start + Duration::from_millis(100))
+            .is_none());
+
+        let event = progress
+            .sample(5, start + Duration::from_millis(500))
+            .expect("an interval has passed");
+        let ChatStreamEvent::ChatProgress {
+            tokens,
+            tokens_per_second,
+            elapsed_ms,
+            ..
+        } = event
+        else {
+            panic!("expected a progress event");
+        };
+        assert_eq!(tokens, 10);
+        assert_eq!(tokens_per_second, 20.0);
+        assert_eq!(elapsed_ms, 500);
+
+        let Some(ChatStreamEvent::ChatProgress {
+            tokens,
+            tokens_per_second,
+            ..
+        }) = progress.summary(Some(30), start + Duration::from_secs(1))
+        else {
+            panic!("expected a summary event");
+        };
+        assert_eq!(tokens, 30);
+        assert_eq!(tokens_per_second, 30.0);
+    }
+
+    #[test]
+    fn strip_reasoning_fields_should_drop_reasoning_from_outgoing_messages() {
+        let messages = vec![serde_json::json!({
+            "role": "assistant",
+            "content": "Answer",
+            "reasoning": "plan",
+            "reasoning_content": "plan"
+        })];
+        let stripped = strip_reasoning_fields(&messages);
+        assert_eq!(
+            stripped[0],
+            serde_json::json!({ "role": "assistant", "content": "Answer" })
+        );
+    }
 }
@@ -2,10 +2,11 @@ use futures_util::StreamExt;
 use serde_json::Value;
 
 use super::{
-    correlation_id_for, now_timestamp_ms, sanitize_headers, BasecampChatRequest, ChatStreamEvent,
+    correlation_id_for, emit_split_deltas, now_timestamp_ms, parse_reasoning_field,
+    sanitize_headers, split_message_reasoning, BasecampChatRequest, ChatStreamEvent,
     NormalizedAssistantMessage, Provider, ProviderCapabilities, ProviderChatResponse,
     ProviderError, ProviderHealthStatus, ProviderKind, ProviderModel, ProviderRuntimeSettings,
-    ProviderUsage, StreamProtocol,
+    ProviderUsage, ReasoningSplitter, StreamProgress, StreamProtocol,
 };
 
 pub struct OllamaProvider;
@@ -209,7 +210,7 @@ pub(crate) fn map_ollama_tool_calls_to_openai(tool_calls: &[Value]) -> Vec<Value
 pub(crate) fn normalize_ollama_message(payload: &Value) -> NormalizedAssistantMessage {
     let default_message = Value::Object(serde_json::Map::new());
     let message = payload.get("message").unwrap_or(&default_message);
-    let content = message.get("content").cloned();
+    let (content, reasoning) = split_message_reasoning(message);
     let tool_calls = message
         .get("tool_calls")
         .and_then(Value::as_array)
@@ -219,6 +220,7 @@ pub(crate) fn normalize_ollama_message(payload: &Value) -> NormalizedAssistantMe
         role: "assistant".to_string(),
         content,
         tool_calls,
+        reasoning,
     }
 }
 
@@ -372,6 +374,9 @@ impl Provider for OllamaProvider {
         if let Some(top_p) = request.top_p {
             options.insert("top_p".to_string(), Value::from(top_p));
         }
+        if let Some(stop) = &request.stop {
+            options.insert("stop".to_string(), Value::from(stop.clone()));
+        }
 
         let mut payload = serde_json::Map::new();
         payload.insert("model".to_string(), Value::String(request.model_id.clone()));
@@ -417,15 +422,14 @@ impl Provider for OllamaProvider {
             let payload = response.json::<Value>().await.map_err(|err| {
                 ProviderError::new(format!("Unable to parse Ollama JSON response: {err}"))
             })?;
-            let output_text = payload
-                .get("message")
-                .and_then(Value::as_object)
-                .and_then(|message| message.get("content"))
+            let assistant_message = self.normalize_response(&payload);
+            let output_text = assistant_message
+                .content
+                .as_ref()
                 .and_then(Value::as_str)
                 .unwrap_or_default()
                 .trim()
                 .to_string();
-            let assistant_message = self.normalize_response(&payload);
             return Ok(ProviderChatResponse {
                 provider_kind: ProviderKind::Ollama,
                 base_url: settings.config.base_url.clone(),
@@ -447,6 +451,7 @@ impl Provider for OllamaProvider {
                 stream_chunk_count: 0,
                 sanitized_request_payload: self.sanitize_for_logs(&request_json),
                 sanitized_response_payload: self.sanitize_for_logs(&payload),
+                scrub_audit: None,
             });
         }
 
@@ -458,6 +463,8 @@ impl Provider for OllamaProvider {
         let mut stream_chunk_count = 0usize;
         let mut buffer = String::new();
         let mut observed_tool_calls = Vec::<Value>::new();
+        let mut splitter = ReasoningSplitter::default();
+        let mut progress = StreamProgress::new(&correlation_id);
 
         while let Some(next_chunk) = stream.next().await {
             let bytes = next_chunk.map_err(|err| {
@@ -496,23 +503,21 @@ impl Provider for OllamaProvider {
                     finish_reason = Some(reason);
                 }
 
-                let token = chunk_value
-                    .get("message")
-                    .and_then(Value::as_object)
+                let message = chunk_value.get("message");
+                let token = message
                     .and_then(|message| message.get("content"))
                     .and_then(Value::as_str)
-                    .unwrap_or_default()
-                    .to_string();
-                if !token.is_empty() {
-                    output_text.push_str(&token);
-                    let _ = on_event.map(|channel| {
-                        channel.send(ChatStreamEvent::ChatDelta {
-                            correlation_id: correlation_id.clone(),
-                            role: "assistant".to_string(),
-                            content_delta: token,
-                        })
-                    });
-                }
+                    .unwrap_or_default();
+                let mut reasoning_delta = splitter
+                    .push_reasoning(&message.and_then(parse_reasoning_field).unwrap_or_default());
+                let (content_delta, inline_reasoning) = splitter.push_content(token);
+                reasoning_delta.push_str(&inline_reasoning);
+                progress.record(
+                    on_event,
+                    u64::from(!token.is_empty() || !reasoning_delta.is_empty()),
+                );
+                output_text.push_str(&content_delta);
+                emit_split_deltas(on_event, &correlation_id, content_delta, reasoning_delta);
 
                 if let Some(tool_calls) = chunk_value
                     .get("message")
@@ -554,14 +559,21 @@ impl Provider for OllamaProvider {
             }
         }
 
+        let (content_tail, reasoning_tail) = splitter.finish();
+        output_text.push_str(&content_tail);
+        emit_split_deltas(on_event, &correlation_id, content_tail, reasoning_tail);
+        let reasoning = splitter.reasoning();
+
         let summary_payload = serde_json::json!({
             "chunks_processed": stream_chunk_count,
             "output_text": output_text,
+            "reasoning": reasoning,
             "usage": usage,
             "resolved_model": resolved_model,
             "finish_reason": finish_reason,
             "tool_calls": observed_tool_calls,
         });
+        progress.finish(on_event, usage.completion_tokens);
         let _ = on_event.map(|channel| {
             channel.send(ChatStreamEvent::ChatComplete {
                 correlation_id: correlation_id.clone(),
@@ -579,6 +591,7 @@ impl Provider for OllamaProvider {
                 role: "assistant".to_string(),
                 content: Some(Value::String(output_text)),
                 tool_calls: observed_tool_calls,
+                reasoning,
             },
             usage,
             resolved_model,
@@ -589,6 +602,7 @@ impl Provider for OllamaProvider {
             stream_chunk_count,
             sanitized_request_payload: self.sanitize_for_logs(&request_json),
             sanitized_response_payload: self.sanitize_for_logs(&summary_payload),
+            scrub_audit: None,
         })
     }
 
@@ -600,7 +614,7 @@ impl Provider for OllamaProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::providers::BasecampChatMetadata;
+    use crate::providers::{BasecampChatMetadata, RequestPriority};
     use httpmock::{Method::GET, Method::POST, MockServer};
     use serde_json::Value;
 
@@ -733,7 +747,9 @@ mod tests {
     async fn ollama_stream_chat_smoke() {
         let server = MockServer::start();
         let _mock = server.mock(|when, then| {
-            when.method(POST).path("/api/chat");
+            when.method(POST)
+                .path("/api/chat")
+                .body_contains("\"stop\":[\"###\"]");
             then.status(200)
                 .header("content-type", "application/x-ndjson")
                 .body(
@@ -756,11 +772,14 @@ mod tests {
             temperature: Some(0.1),
             max_tokens: Some(32),
             top_p: None,
+            stop: Some(vec!["###".to_string()]),
+            logit_bias: None,
             stream: true,
             metadata: BasecampChatMetadata {
                 camp_id: Some("camp-ollama".to_string()),
                 correlation_id: Some("corr-ollama-smoke".to_string()),
                 provider_kind: Some(ProviderKind::Ollama),
+                priority: RequestPriority::Interactive,
             },
         };
 
@@ -2,11 +2,13 @@ use futures_util::StreamExt;
 use serde_json::Value;
 
 use super::{
-    correlation_id_for, normalized_message_content, now_timestamp_ms, parse_finish_reason,
-    parse_openai_assistant_message, parse_resolved_model, parse_usage, sanitize_headers,
+    correlation_id_for, emit_split_deltas, normalized_message_content, now_timestamp_ms,
+    parse_finish_reason, parse_openai_assistant_message, parse_reasoning_field,
+    parse_resolved_model, parse_usage, sanitize_headers, strip_reasoning_fields,
     BasecampChatRequest, ChatStreamEvent, NormalizedAssistantMessage, Provider,
     ProviderCapabilities, ProviderChatResponse, ProviderError, ProviderHealthStatus, ProviderKind,
-    ProviderModel, ProviderRuntimeSettings, ProviderUsage, StreamProtocol,
+    ProviderModel, ProviderRuntimeSettings, ProviderUsage, ReasoningSplitter, StreamProgress,
+    StreamProtocol,
 };
 
 pub struct OpenRouterProvider;
@@ -235,7 +237,7 @@ impl Provider for OpenRouterProvider {
         payload.insert("model".to_string(), Value::String(request.model_id.clone()));
         payload.insert(
             "messages".to_string(),
-            Value::Array(request.messages.clone()),
+            Value::Array(strip_reasoning_fields(&request.messages)),
         );
         payload.insert("stream".to_string(), Value::Bool(request.stream));
         if let Some(temperature) = request.temperature {
@@ -247,6 +249,15 @@ impl Provider for OpenRouterProvider {
         if let Some(top_p) = request.top_p {
             payload.insert("top_p".to_string(), Value::from(top_p));
         }
+        if let Some(stop) = &request.stop {
+            payload.insert("stop".to_string(), Value::from(stop.clone()));
+        }
+        if let Some(logit_bias) = &request.logit_bias {
+            payload.insert(
+                "logit_bias".to_string(),
+                serde_json::to_value(logit_bias).unwrap_or(Value::Null),
+            );
+        }
         if let Some(tools) = &request.tools {
             payload.insert("tools".to_string(), Value::Array(tools.clone()));
         }
@@ -288,18 +299,14 @@ impl Provider for OpenRouterProvider {
             let payload = response.json::<Value>().await.map_err(|err| {
                 ProviderError::new(format!("Unable to parse OpenRouter response JSON: {err}"))
             })?;
-            let output_text = payload
-                .get("choices")
-                .and_then(Value::as_array)
-                .and_then(|choices| choices.first())
-                .and_then(|choice| choice.get("message"))
-                .and_then(|message| message.get("content"))
+            let assistant_message = self.normalize_response(&payload);
+            let output_text = assistant_message
+                .content
+                .as_ref()
                 .map(normalized_message_content)
                 .unwrap_or_default()
                 .trim()
                 .to_string();
-
-            let assistant_message = self.normalize_response(&payload);
             return Ok(ProviderChatResponse {
                 provider_kind: ProviderKind::Openrouter,
                 base_url: settings.config.base_url.clone(),
@@ -315,6 +322,7 @@ impl Provider for OpenRouterProvider {
                 stream_chunk_count: 0,
                 sanitized_request_payload: self.sanitize_for_logs(&request_json),
                 sanitized_response_payload: self.sanitize_for_logs(&payload),
+                scrub_audit: None,
             });
         }
 
@@ -325,6 +333,8 @@ impl Provider for OpenRouterProvider {
         let mut finish_reason: Option<String> = None;
         let mut stream_chunk_count = 0usize;
         let mut buffer = String::new();
+        let mut splitter = ReasoningSplitter::default();
+        let mut progress = StreamProgress::new(&correlation_id);
 
         while let Some(next_chunk) = stream.next().await {
             let bytes = next_chunk.map_err(|err| {
@@ -361,28 +371,26 @@ impl Provider for OpenRouterProvider {
                     usage.total_tokens = next_usage.total_tokens.or(usage.total_tokens);
                     finish_reason = parse_finish_reason(&chunk_value).or(finish_reason);
 
-                    let token = chunk_value
+                    let delta = chunk_value
                         .get("choices")
                         .and_then(Value::as_array)
                         .and_then(|choices| choices.first())
-                        .and_then(|choice| choice.get("delta"))
+                        .and_then(|choice| choice.get("delta"));
+                    let token = delta
                         .and_then(|delta| delta.get("content"))
                         .map(normalized_message_content)
                         .unwrap_or_default();
-                    if !token.is_empty() {
-                        output_text.push_str(&token);
-                        let _ = on_event.map(|channel| {
-                            channel.send(ChatStreamEvent::ChatDelta {
-                                correlation_id: correlation_id.clone(),
-                                role: "assistant".to_string(),
-                                content_delta: token,
-                            })
-                        });
-                    }
-
-                    for (tool_call_id, name, arguments_delta) in
-                        parse_openai_tool_deltas(&chunk_value)
-                    {
+                    let mut reasoning_delta = splitter
+                        .push_reasoning(&delta.and_then(parse_reasoning_field).unwrap_or_default());
+                    let (content_delta, inline_reasoning) = splitter.push_content(&token);
+                    reasoning_delta.push_str(&inline_reasoning);
+                    let had_token = !token.is_empty() || !reasoning_delta.is_empty();
+                    output_text.push_str(&content_delta);
+                    emit_split_deltas(on_event, &correlation_id, content_delta, reasoning_delta);
+
+                    let tool_deltas = parse_openai_tool_deltas(&chunk_value);
+                    progress.record(on_event, u64::from(had_token || !tool_deltas.is_empty()));
+                    for (tool_call_id, name, arguments_delta) in tool_deltas {
                         let _ = on_event.map(|channel| {
                             channel.send(ChatStreamEvent::ToolCallDelta {
                                 correlation_id: correlation_id.clone(),
@@ -396,14 +404,21 @@ impl Provider for OpenRouterProvider {
             }
         }
 
+        let (content_tail, reasoning_tail) = splitter.finish();
+        output_text.push_str(&content_tail);
+        emit_split_deltas(on_event, &correlation_id, content_tail, reasoning_tail);
+        let reasoning = splitter.reasoning();
+
         let summary_payload = serde_json::json!({
             "chunks_processed": stream_chunk_count,
             "output_text": output_text,
+            "reasoning": reasoning,
             "usage": usage,
             "resolved_model": resolved_model,
             "finish_reason": finish_reason,
         });
 
+        progress.finish(on_event, usage.completion_tokens);
         let _ = on_event.map(|channel| {
             channel.send(ChatStreamEvent::ChatComplete {
                 correlation_id: correlation_id.clone(),
@@ -421,6 +436,7 @@ impl Provider for OpenRouterProvider {
                 role: "assistant".to_string(),
                 content: Some(Value::String(output_text)),
                 tool_calls: vec![],
+                reasoning,
             },
             usage,
             resolved_model,
@@ -431,6 +447,7 @@ impl Provider for OpenRouterProvider {
             stream_chunk_count,
             sanitized_request_payload: self.sanitize_for_logs(&request_json),
             sanitized_response_payload: self.sanitize_for_logs(&summary_payload),
+            scrub_audit: None,
         })
     }
 
@@ -0,0 +1,325 @@
+//! Per-provider request queue. Each provider runs a limited number of
+//! requests at once; when every slot is busy, waiting requests are served by
+//! priority (interactive chat, then team work, then scheduled jobs) and in
+//! arrival order within a priority, so a long team run never holds up the
+//! user's own chat.
+
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use super::ProviderKind;
+
+/// Local servers usually run one model on one GPU; more parallel requests
+/// only slow each other down.
+pub const DEFAULT_LOCAL_CONCURRENCY: usize = 1;
+const CLOUD_CONCURRENCY: usize = 4;
+/// How often a waiting request re-checks its place in line.
+const POSITION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestPriority {
+    Scheduled,
+    Team,
+    #[default]
+    Interactive,
+}
+
+struct Waiter {
+    priority: RequestPriority,
+    sequence: u64,
+    wake: oneshot::Sender<()>,
+}
+
+struct QueueState {
+    capacity: usize,
+    running: usize,
+    next_sequence: u64,
+    waiting: Vec<Waiter>,
+}
+
+impl QueueState {
+    /// 1-based place in line of the waiter with `sequence`.
+    fn position(&self, sequence: u64) -> Option<usize> {
+        let waiter = self
+            .waiting
+            .iter()
+            .find(|waiter| waiter.sequence == sequence)?;
+        let ahead = self
+            .waiting
+            .iter()
+            .filter(|other| {
+                other.priority > waiter.priority
+                    || (other.priority == waiter.priority && other.sequence < waiter.sequence)
+            })
+            .count();
+        Some(ahead + 1)
+    }
+
+    /// Hands free slots to waiters in service order.
+    fn serve_waiters(&mut self) {
+        while self.running < self.capacity {
+            let Some(index) = self.next_waiter() else {
+                return;
+            };
+            let waiter = self.waiting.swap_remove(index);
+            // Cancelled waiters remove themselves, but skip any that are
+            // mid-drop and pass the slot on.
+            if waiter.wake.send(()).is_ok() {
+                self.running += 1;
+            }
+        }
+    }
+
+    /// Index of the waiter to serve next: highest priority, then oldest.
+    fn next_waiter(&self) -> Option<usize> {
+        self.waiting
+            .iter()
+            .enumerate()
+            .max_by(|(_, left), (_, right)| {
+                left.priority
+                    .cmp(&right.priority)
+                    .then_with(|| right.sequence.cmp(&left.sequence))
+            })
+            .map(|(index, _)| index)
+    }
+}
+
+pub struct RequestQueue {
+    state: Mutex<QueueState>,
+}
+
+/// A request waiting for a slot. If it is dropped before being served (the
+/// caller gave up), it leaves the queue, and a slot handed to it in the
+/// meantime is passed on.
+struct PendingSlot<'a> {
+    queue: &'a RequestQueue,
+    sequence: u64,
+    receiver: oneshot::Receiver<()>,
+}
+
+/// Holds a provider slot; dropping it hands the slot to the next waiter.
+pub struct QueuePermit<'a> {
+    queue: &'a RequestQueue,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct QueueLaneCounts {
+    pub interactive: usize,
+    pub team: usize,
+    pub scheduled: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderQueueStatus {
+    pub provider_kind: ProviderKind,
+    pub capacity: usize,
+    pub running: usize,
+    pub waiting: QueueLaneCounts,
+}
+
+impl RequestQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                capacity: capacity.max(1),
+                running: 0,
+                next_sequence: 0,
+                waiting: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn for_provider(kind: ProviderKind) -> Self {
+        Self::new(if kind.is_local() {
+            DEFAULT_LOCAL_CONCURRENCY
+        } else {
+            CLOUD_CONCURRENCY
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        // The state is only counters and senders; a panic elsewhere cannot
+        // leave it half-updated, so a poisoned lock is still usable.
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Changes how many requests may run at once. Raising it starts waiting
+    /// requests straight away; lowering it lets running ones finish.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.lock();
+        state.capacity = capacity.max(1);
+        state.serve_waiters();
+    }
+
+    /// Waits for a free slot. Requests of a higher priority that arrive while
+    /// this one waits are served first.
+    pub async fn acquire(&self, priority: RequestPriority) -> QueuePermit<'_> {
+        self.acquire_with_position(priority, |_| {}).await
+    }
+
+    /// Like `acquire`, calling `on_position` with the request's place in line
+    /// when it has to wait and again whenever that place changes.
+    pub async fn acquire_with_position(
+        &self,
+        priority: RequestPriority,
+        mut on_position: impl FnMut(usize),
+    ) -> QueuePermit<'_> {
+        let mut pending = {
+            let mut state = self.lock();
+            if state.running < state.capacity && state.waiting.is_empty() {
+                state.running += 1;
+                return QueuePermit { queue: self };
+            }
+            let (wake, receiver) = oneshot::channel();
+            let sequence = state.next_sequence;
+            state.next_sequence += 1;
+            state.waiting.push(Waiter {
+                priority,
+                sequence,
+                wake,
+            });
+            PendingSlot {
+                queue: self,
+                sequence,
+                receiver,
+            }
+        };
+
+        let mut last_position = None;
+        loop {
+            let position = self.lock().position(pending.sequence);
+            if let Some(position) = position.filter(|position| last_position != Some(*position)) {
+                on_position(position);
+                last_position = Some(position);
+            }
+            tokio::select! {
+                // A waiter only leaves the queue by being handed a slot.
+                _ = &mut pending.receiver => break,
+                _ = tokio::time::sleep(POSITION_POLL_INTERVAL) => {}
+            }
+        }
+        QueuePermit { queue: self }
+    }
+
+    fn release(&self) {
+        let mut state = self.lock();
+        state.running = state.running.saturating_sub(1);
+        state.serve_waiters();
+    }
+
+    pub fn status(&self, provider_kind: ProviderKind) -> ProviderQueueStatus {
+        let state = self.lock();
+        let mut waiting = QueueLaneCounts::default();
+        for waiter in &state.waiting {
+            match waiter.priority {
+                RequestPriority::Interactive => waiting.interactive += 1,
+                RequestPriority::Team => waiting.team += 1,
+                RequestPriority::Scheduled => waiting.scheduled += 1,
+            }
+        }
+        ProviderQueueStatus {
+            provider_kind,
+            capacity: state.capacity,
+            running: state.running,
+            waiting,
+        }
+    }
+}
+
+impl Drop for PendingSlot<'_> {
+    fn drop(&mut self) {
+        // Slots are handed over while the state lock is held, so checking
+        // under the lock cannot miss one in flight.
+        let handed_over = {
+            let mut state = self.queue.lock();
+            state
+                .waiting
+                .retain(|waiter| waiter.sequence != self.sequence);
+            self.receiver.try_recv().is_ok()
+        };
+        if handed_over {
+            self.queue.release();
+        }
+    }
+}
+
+impl Drop for QueuePermit<'_> {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn request_queue_should_serve_interactive_before_background_work() {
+        let queue = RequestQueue::new(1);
+        let first = queue.acquire(RequestPriority::Team).await;
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        let waiters = [
+            RequestPriority::Scheduled,
+            RequestPriority::Team,
+            RequestPriority::Interactive,
+        ]
+        .into_iter()
+        .map(|priority| {
+            let queue = &queue;
+            let order_tx = order_tx.clone();
+            async move {
+                let _permit = queue.acquire(priority).await;
+                order_tx.send(priority).expect("receiver alive");
+            }
+        });
+        let all_waiters = futures_util::future::join_all(waiters);
+        tokio::pin!(all_waiters);
+
+        // Let every waiter register before the slot is released.
+        assert!(futures_util::poll!(all_waiters.as_mut()).is_pending());
+        let status = queue.status(ProviderKind::Ollama);
+        assert_eq!(status.running, 1);
+        assert_eq!(status.waiting.interactive, 1);
+        assert_eq!(status.waiting.team, 1);
+        assert_eq!(status.waiting.scheduled, 1);
+
+        drop(first);
+        all_waiters.await;
+        drop(order_tx);
+        let mut order = Vec::new();
+        while let Some(priority) = order_rx.recv().await {
+            order.push(priority);
+        }
+        assert_eq!(
+            order,
+            vec![
+                RequestPriority::Interactive,
+                RequestPriority::Team,
+                RequestPriority::Scheduled
+            ]
+        );
+        assert_eq!(queue.status(ProviderKind::Ollama).running, 0);
+
+        let positions = std::cell::RefCell::new(Vec::new());
+        let held = queue.acquire(RequestPriority::Interactive).await;
+        let waiting = queue.acquire_with_position(RequestPriority::Team, |position| {
+            positions.borrow_mut().push(position)
+        });
+        tokio::pin!(waiting);
+        assert!(futures_util::poll!(waiting.as_mut()).is_pending());
+        assert_eq!(*positions.borrow(), vec![1]);
+
+        // Raising the limit starts the waiting request without a release.
+        queue.set_capacity(2);
+        let second = waiting.await;
+        assert_eq!(queue.status(ProviderKind::Ollama).running, 2);
+        drop((held, second));
+    }
+}
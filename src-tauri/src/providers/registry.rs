@@ -29,6 +29,13 @@ pub struct ModelRegistryRow {
     pub last_seen_at: i64,
 }
 
+/// Per-token USD prices as published in a model's `pricing` object.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub prompt: f64,
+    pub completion: f64,
+}
+
 fn table_exists(connection: &Connection, table_name: &str) -> Result<bool, rusqlite::Error> {
     let exists: Option<i64> = connection
         .query_row(
@@ -353,6 +360,64 @@ pub fn get_model_capabilities(
     Ok(value.and_then(|raw| serde_json::from_str::<ProviderCapabilities>(&raw).ok()))
 }
 
+pub fn get_model_context_length(
+    connection: &Connection,
+    provider_kind: ProviderKind,
+    model_id: &str,
+) -> Result<Option<i64>, rusqlite::Error> {
+    let value: Option<Option<i64>> = connection
+        .query_row(
+            "
+            SELECT context_length
+            FROM models
+            WHERE provider_kind = ?1 AND model_id = ?2
+            ",
+            params![provider_kind.as_str(), model_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.flatten())
+}
+
+fn pricing_value(pricing: &Value, key: &str) -> Option<f64> {
+    match pricing.get(key)? {
+        Value::String(raw) => raw.trim().parse::<f64>().ok(),
+        value => value.as_f64(),
+    }
+}
+
+/// Reads prompt/completion prices from the stored model payload. Providers that
+/// don't publish pricing yield `None`.
+pub fn get_model_pricing(
+    connection: &Connection,
+    provider_kind: ProviderKind,
+    model_id: &str,
+) -> Result<Option<ModelPricing>, rusqlite::Error> {
+    let raw: Option<String> = connection
+        .query_row(
+            "
+            SELECT raw_json
+            FROM models
+            WHERE provider_kind = ?1 AND model_id = ?2
+            ",
+            params![provider_kind.as_str(), model_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(raw
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|value| pricing_from_raw(&value)))
+}
+
+/// Prices from a model's stored provider payload, as in `get_model_pricing`.
+pub fn pricing_from_raw(raw: &Value) -> Option<ModelPricing> {
+    let pricing = raw.get("pricing")?;
+    Some(ModelPricing {
+        prompt: pricing_value(pricing, "prompt")?,
+        completion: pricing_value(pricing, "completion")?,
+    })
+}
+
 pub fn replace_models_for_provider(
     connection: &mut Connection,
     provider_kind: ProviderKind,
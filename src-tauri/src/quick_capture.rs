@@ -0,0 +1,345 @@
+//! Quick capture: a global shortcut opens a small always-on-top input, and
+//! whatever is typed there lands in a chosen camp as a user message or a note
+//! artifact without opening the main window.
+
+use std::str::FromStr;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State, WebviewUrl, WebviewWindowBuilder, Window};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use uuid::Uuid;
+
+use crate::{
+    append_transcript_message, camp_transcript_path, create_artifact, ensure_camps_root,
+    get_setting_value, now_timestamp_ms, resolve_existing_camp_dir, set_setting_value,
+    touch_camp_updated_at, validate_camp_identifier, AppState, ArtifactSource, CampMessage,
+};
+
+const SETTING_QUICK_CAPTURE: &str = "quick_capture_settings";
+const QUICK_CAPTURE_WINDOW_LABEL: &str = "quick-capture";
+const DEFAULT_QUICK_CAPTURE_SHORTCUT: &str = "CommandOrControl+Shift+Space";
+const NOTE_TITLE_MAX_CHARS: usize = 60;
+const QUICK_CAPTURE_TAG: &str = "quick-capture";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickCaptureTarget {
+    #[default]
+    Message,
+    Note,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickCaptureSettings {
+    /// Camp that receives captures. Capturing fails until one is chosen.
+    pub camp_id: Option<String>,
+    /// Accelerator such as `CommandOrControl+Shift+Space`; `None` disables it.
+    pub shortcut: Option<String>,
+    #[serde(default)]
+    pub target: QuickCaptureTarget,
+}
+
+impl Default for QuickCaptureSettings {
+    fn default() -> Self {
+        Self {
+            camp_id: None,
+            shortcut: Some(DEFAULT_QUICK_CAPTURE_SHORTCUT.to_string()),
+            target: QuickCaptureTarget::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuickCaptureResult {
+    pub camp_id: String,
+    pub target: QuickCaptureTarget,
+    /// Message id or artifact id, depending on `target`.
+    pub id: String,
+}
+
+pub(crate) fn load_quick_capture_settings(
+    connection: &Connection,
+) -> Result<QuickCaptureSettings, String> {
+    let raw = get_setting_value(connection, SETTING_QUICK_CAPTURE)
+        .map_err(|err| format!("Failed to read quick capture settings: {err}"))?;
+    Ok(raw
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+/// Points captures at a camp's new id after its folder is renamed.
+pub(crate) fn rename_quick_capture_camp(
+    connection: &Connection,
+    from_id: &str,
+    to_id: &str,
+) -> Result<(), String> {
+    let mut settings = load_quick_capture_settings(connection)?;
+    if settings.camp_id.as_deref() != Some(from_id) || from_id == to_id {
+        return Ok(());
+    }
+    settings.camp_id = Some(to_id.to_string());
+    let serialized = serde_json::to_string(&settings)
+        .map_err(|err| format!("Unable to serialize quick capture settings: {err}"))?;
+    set_setting_value(connection, SETTING_QUICK_CAPTURE, &serialized)
+        .map_err(|err| format!("Failed to save quick capture settings: {err}"))
+}
+
+fn parse_shortcut(value: &str) -> Result<Shortcut, String> {
+    Shortcut::from_str(value.trim())
+        .map_err(|err| format!("Invalid shortcut `{}`: {err}", value.trim()))
+}
+
+/// Title for a captured note: its first line, cut at a word boundary.
+fn note_title(text: &str) -> String {
+    let first_line = text.lines().map(str::trim).find(|line| !line.is_empty());
+    let Some(line) = first_line else {
+        return "Quick capture".to_string();
+    };
+    if line.chars().count() <= NOTE_TITLE_MAX_CHARS {
+        return line.to_string();
+    }
+    let shortened: String = line.chars().take(NOTE_TITLE_MAX_CHARS).collect();
+    match shortened.rfind(' ') {
+        Some(cut) if cut > 0 => format!("{}…", &shortened[..cut]),
+        _ => format!("{shortened}…"),
+    }
+}
+
+/// Shows the capture window, creating it on first use.
+fn open_quick_capture_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(QUICK_CAPTURE_WINDOW_LABEL) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    let _ = WebviewWindowBuilder::new(
+        app,
+        QUICK_CAPTURE_WINDOW_LABEL,
+        WebviewUrl::App("index.html#/quick-capture".into()),
+    )
+    .title("Quick Capture")
+    .inner_size(560.0, 132.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .focused(true)
+    .build();
+}
+
+/// Swaps the registered shortcut. The new one is registered before the old
+/// one is dropped so a bad accelerator leaves the previous shortcut working.
+pub(crate) fn replace_shortcut(
+    app: &AppHandle,
+    previous: Option<&str>,
+    next: Option<&str>,
+) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    if let Some(next) = next {
+        let shortcut = parse_shortcut(next)?;
+        if !shortcuts.is_registered(shortcut) {
+            shortcuts
+                .register(shortcut)
+                .map_err(|err| format!("Unable to register shortcut `{next}`: {err}"))?;
+        }
+    }
+    if let Some(previous) = previous.filter(|previous| Some(*previous) != next) {
+        if let Ok(shortcut) = parse_shortcut(previous) {
+            let _ = shortcuts.unregister(shortcut);
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn plugin<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                open_quick_capture_window(app);
+            }
+        })
+        .build()
+}
+
+/// Appends `text` to the quick capture camp. Callable from the capture window
+/// as well as the main window, so it works while the main window is closed.
+#[tauri::command]
+pub fn quick_capture(
+    window: Window,
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<QuickCaptureResult, String> {
+    if !matches!(window.label(), "main" | QUICK_CAPTURE_WINDOW_LABEL) {
+        return Err("Quick capture is not available from this window.".to_string());
+    }
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err("Nothing to capture.".to_string());
+    }
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let settings = load_quick_capture_settings(&connection)?;
+    let camp_id = settings
+        .camp_id
+        .ok_or_else(|| "Choose a quick capture camp in Settings first.".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let id = match settings.target {
+        QuickCaptureTarget::Message => {
+            let message = CampMessage {
+                id: Uuid::new_v4().to_string(),
+                role: "user".to_string(),
+                content: text,
+                created_at: now_timestamp_ms(),
+                name: None,
+                tool_call_id: None,
+                tool_calls: None,
+                included_artifact_ids: None,
+                attachments: None,
+                reasoning: None,
+                run_id: None,
+            };
+            append_transcript_message(&camp_transcript_path(&camp_dir), &message)?;
+            message.id
+        }
+        QuickCaptureTarget::Note => {
+            let artifact = create_artifact(
+                &camp_dir,
+                ArtifactSource {
+                    message_id: String::new(),
+                    role: "user".to_string(),
+                    path: None,
+                },
+                &note_title(&text),
+                &text,
+                &[QUICK_CAPTURE_TAG.to_string()],
+            )?;
+            artifact.metadata.id
+        }
+    };
+    touch_camp_updated_at(&camp_dir)?;
+
+    if window.label() == QUICK_CAPTURE_WINDOW_LABEL {
+        let _ = window.close();
+    }
+    Ok(QuickCaptureResult {
+        camp_id,
+        target: settings.target,
+        id,
+    })
+}
+
+#[tauri::command]
+pub fn dismiss_quick_capture(window: Window) -> Result<(), String> {
+    if window.label() != QUICK_CAPTURE_WINDOW_LABEL {
+        return Ok(());
+    }
+    window
+        .close()
+        .map_err(|err| format!("Unable to close quick capture: {err}"))
+}
+
+#[tauri::command]
+pub fn get_quick_capture_settings(
+    state: State<'_, AppState>,
+) -> Result<QuickCaptureSettings, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_quick_capture_settings(&connection)
+}
+
+#[tauri::command]
+pub fn set_quick_capture_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: QuickCaptureSettings,
+) -> Result<QuickCaptureSettings, String> {
+    let settings = QuickCaptureSettings {
+        camp_id: settings
+            .camp_id
+            .filter(|camp_id| !camp_id.trim().is_empty())
+            .map(|camp_id| validate_camp_identifier(&camp_id))
+            .transpose()?,
+        shortcut: settings
+            .shortcut
+            .map(|shortcut| shortcut.trim().to_string())
+            .filter(|shortcut| !shortcut.is_empty()),
+        target: settings.target,
+    };
+    if let Some(shortcut) = &settings.shortcut {
+        parse_shortcut(shortcut)?;
+    }
+    let serialized = serde_json::to_string(&settings)
+        .map_err(|err| format!("Unable to serialize quick capture settings: {err}"))?;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    if let Some(camp_id) = &settings.camp_id {
+        resolve_existing_camp_dir(&ensure_camps_root(&connection)?, camp_id)?;
+    }
+    let previous = load_quick_capture_settings(&connection)?;
+    replace_shortcut(
+        &app,
+        previous.shortcut.as_deref(),
+        settings.shortcut.as_deref(),
+    )?;
+    set_setting_value(&connection, SETTING_QUICK_CAPTURE, &serialized)
+        .map_err(|err| format!("Failed to save quick capture settings: {err}"))?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn note_title_should_use_the_first_line_cut_at_a_word() {
+        assert_eq!(
+            note_title("\n  Call the venue\nabout Friday"),
+            "Call the venue"
+        );
+        assert_eq!(note_title("   "), "Quick capture");
+
+        let long = note_title(&"remember ".repeat(12));
+        assert!(long.ends_with('…'));
+        assert!(long.chars().count() <= NOTE_TITLE_MAX_CHARS + 1);
+
+        assert!(parse_shortcut("CommandOrControl+Shift+Space").is_ok());
+        assert!(parse_shortcut("Shift+Nope").is_err());
+    }
+
+    #[test]
+    fn rename_quick_capture_camp_should_follow_the_renamed_camp() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        crate::migrations::run_migrations(&connection, false).expect("schema should be created");
+        let settings = QuickCaptureSettings {
+            camp_id: Some("old-camp".to_string()),
+            ..QuickCaptureSettings::default()
+        };
+        set_setting_value(
+            &connection,
+            SETTING_QUICK_CAPTURE,
+            &serde_json::to_string(&settings).expect("settings should serialize"),
+        )
+        .expect("settings should save");
+
+        rename_quick_capture_camp(&connection, "other-camp", "elsewhere")
+            .expect("unrelated rename should succeed");
+        rename_quick_capture_camp(&connection, "old-camp", "new-camp")
+            .expect("rename should succeed");
+
+        let renamed = load_quick_capture_settings(&connection).expect("settings should load");
+        assert_eq!(renamed.camp_id.as_deref(), Some("new-camp"));
+        assert_eq!(renamed.shortcut, settings.shortcut);
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::{
+    camp_artifacts_dir, camp_config_path, camp_context_dir, camp_memory_path,
+    camp_system_prompt_path, camp_transcript_path, ensure_main_window, get_setting_value, inspect,
+    now_timestamp_ms, read_camp_config, validate_workspace_path, write_file_atomic, AppState,
+    CAMPS_DIR_NAME, SETTING_WORKSPACE_PATH,
+};
+
+/// Deepest folder level searched for leftover temp files inside a camp.
+const TEMP_SCAN_MAX_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupIssueKind {
+    MissingCampConfig,
+    UnreadableCampConfig,
+    MissingCampFile,
+    StaleTempFile,
+    CorruptInspectFile,
+    InterruptedInspectTurn,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupIssue {
+    pub kind: StartupIssueKind,
+    pub camp_id: String,
+    pub path: String,
+    pub message: String,
+    pub repaired: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupDiagnostics {
+    pub scanned_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_path: Option<String>,
+    pub camps_scanned: usize,
+    pub issues: Vec<StartupIssue>,
+    /// Set when the scan itself could not run, e.g. an unreadable workspace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn issue(
+    kind: StartupIssueKind,
+    camp_id: &str,
+    path: &Path,
+    message: impl Into<String>,
+    repaired: bool,
+) -> StartupIssue {
+    StartupIssue {
+        kind,
+        camp_id: camp_id.to_string(),
+        path: path.to_string_lossy().into_owned(),
+        message: message.into(),
+        repaired,
+    }
+}
+
+/// Matches the `.<name>.<uuid>.tmp` files `write_file_atomic` leaves behind when
+/// the process dies between create and rename. Other dot-files ending in
+/// `.tmp` may be the user's own and are left alone.
+fn is_atomic_temp_file(path: &Path) -> bool {
+    let Some(stem) = path
+        .file_name()
+        .and_then(|value| value.to_str())
+        .and_then(|name| name.strip_prefix('.'))
+        .and_then(|name| name.strip_suffix(".tmp"))
+    else {
+        return false;
+    };
+    stem.rsplit_once('.').is_some_and(|(file_name, id)| {
+        !file_name.is_empty() && Uuid::parse_str(id).is_ok_and(|uuid| uuid.to_string() == id)
+    })
+}
+
+fn collect_temp_files(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth < TEMP_SCAN_MAX_DEPTH {
+                collect_temp_files(&path, depth + 1, found);
+            }
+        } else if is_atomic_temp_file(&path) {
+            found.push(path);
+        }
+    }
+}
+
+/// Recreates files a camp cannot load without. Only empty defaults are written, so
+/// nothing the user authored is ever overwritten.
+fn repair_missing_camp_files(camp_dir: &Path, camp_id: &str, issues: &mut Vec<StartupIssue>) {
+    let required_files: [(PathBuf, &str); 3] = [
+        (camp_transcript_path(camp_dir), ""),
+        (camp_memory_path(camp_dir), "{}"),
+        (camp_system_prompt_path(camp_dir), ""),
+    ];
+    for (path, default_contents) in required_files {
+        if path.exists() {
+            continue;
+        }
+        let repaired = write_file_atomic(&path, default_contents.as_bytes()).is_ok();
+        issues.push(issue(
+            StartupIssueKind::MissingCampFile,
+            camp_id,
+            &path,
+            "Required camp file was missing; recreated empty.",
+            repaired,
+        ));
+    }
+
+    for dir in [camp_context_dir(camp_dir), camp_artifacts_dir(camp_dir)] {
+        if dir.is_dir() {
+            continue;
+        }
+        let repaired = fs::create_dir_all(&dir).is_ok();
+        issues.push(issue(
+            StartupIssueKind::MissingCampFile,
+            camp_id,
+            &dir,
+            "Required camp folder was missing; recreated.",
+            repaired,
+        ));
+    }
+}
+
+/// Turn files are written non-atomically, so a crash can leave truncated JSON.
+/// They are debug output only and safe to delete; a request with no matching
+/// response or bundle is reported so the interrupted turn can be inspected.
+fn scan_inspect_files(camp_dir: &Path, camp_id: &str, issues: &mut Vec<StartupIssue>) {
+    let mut completed_turns = HashSet::new();
+    let mut requested_turns = Vec::new();
+
+    for path in inspect::list_turn_files(camp_dir) {
+        let parses = fs::read_to_string(&path)
+            .ok()
+            .is_some_and(|raw| serde_json::from_str::<Value>(&raw).is_ok());
+        if !parses {
+            let repaired = fs::remove_file(&path).is_ok();
+            issues.push(issue(
+                StartupIssueKind::CorruptInspectFile,
+                camp_id,
+                &path,
+                "Inspect turn file was not valid JSON; removed.",
+                repaired,
+            ));
+            continue;
+        }
+
+        let Some((turn, suffix)) = path
+            .file_stem()
+            .and_then(|value| value.to_str())
+            .and_then(|stem| stem.strip_prefix("turn_"))
+            .and_then(|rest| rest.rsplit_once('_'))
+        else {
+            continue;
+        };
+        if suffix == "request" {
+            requested_turns.push((turn.to_string(), path.clone()));
+        } else {
+            completed_turns.insert(turn.to_string());
+        }
+    }
+
+    for (turn, path) in requested_turns {
+        if !completed_turns.contains(&turn) {
+            issues.push(issue(
+                StartupIssueKind::InterruptedInspectTurn,
+                camp_id,
+                &path,
+                format!("Turn {turn} has a request but no response or bundle."),
+                false,
+            ));
+        }
+    }
+}
+
+fn scan_camp(camp_dir: &Path, camp_id: &str, issues: &mut Vec<StartupIssue>) {
+    let mut temp_files = Vec::new();
+    collect_temp_files(camp_dir, 0, &mut temp_files);
+    for path in temp_files {
+        let repaired = fs::remove_file(&path).is_ok();
+        issues.push(issue(
+            StartupIssueKind::StaleTempFile,
+            camp_id,
+            &path,
+            "Half-written temp file from an interrupted save; removed.",
+            repaired,
+        ));
+    }
+
+    let config_path = camp_config_path(camp_dir);
+    if !config_path.exists() {
+        issues.push(issue(
+            StartupIssueKind::MissingCampConfig,
+            camp_id,
+            &config_path,
+            "Camp folder has no camp.json and will not be listed.",
+            false,
+        ));
+        return;
+    }
+    if let Err(err) = read_camp_config(camp_dir) {
+        issues.push(issue(
+            StartupIssueKind::UnreadableCampConfig,
+            camp_id,
+            &config_path,
+            err,
+            false,
+        ));
+        return;
+    }
+
+    repair_missing_camp_files(camp_dir, camp_id, issues);
+    scan_inspect_files(camp_dir, camp_id, issues);
+}
+
+/// Scans every camp folder and returns how many were visited with the issues found.
+fn scan_camps_root(camps_root: &Path) -> Result<(usize, Vec<StartupIssue>), String> {
+    let entries =
+        fs::read_dir(camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+
+    let mut camp_dirs: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && !path
+                    .file_name()
+                    .and_then(|value| value.to_str())
+                    .is_some_and(|name| name.starts_with('.'))
+        })
+        .collect();
+    camp_dirs.sort();
+
+    let mut issues = Vec::new();
+    for camp_dir in &camp_dirs {
+        let camp_id = camp_dir
+            .file_name()
+            .and_then(|value| value.to_str())
+            .unwrap_or_default()
+            .to_string();
+        scan_camp(camp_dir, &camp_id, &mut issues);
+    }
+    Ok((camp_dirs.len(), issues))
+}
+
+/// Startup recovery pass. Never fails: problems with the scan itself are recorded
+/// on the diagnostics so a damaged workspace cannot block the app from opening.
+pub(crate) fn run_startup_recovery(connection: &Connection) -> StartupDiagnostics {
+    let mut diagnostics = StartupDiagnostics {
+        scanned_at: now_timestamp_ms(),
+        ..StartupDiagnostics::default()
+    };
+
+    let workspace_path = match get_setting_value(connection, SETTING_WORKSPACE_PATH) {
+        Ok(Some(value)) => value,
+        Ok(None) => return diagnostics,
+        Err(err) => {
+            diagnostics.error = Some(format!("Unable to load workspace path: {err}"));
+            return diagnostics;
+        }
+    };
+    let workspace_path = match validate_workspace_path(&workspace_path) {
+        Ok(path) => path,
+        Err(err) => {
+            diagnostics.error = Some(err);
+            return diagnostics;
+        }
+    };
+    diagnostics.workspace_path = Some(workspace_path.to_string_lossy().into_owned());
+
+    let camps_root = workspace_path.join(CAMPS_DIR_NAME);
+    if !camps_root.is_dir() {
+        return diagnostics;
+    }
+    match scan_camps_root(&camps_root) {
+        Ok((camps_scanned, issues)) => {
+            diagnostics.camps_scanned = camps_scanned;
+            diagnostics.issues = issues;
+        }
+        Err(err) => diagnostics.error = Some(err),
+    }
+    diagnostics
+}
+
+#[tauri::command]
+pub fn get_startup_diagnostics(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<StartupDiagnostics, String> {
+    ensure_main_window(&window)?;
+    let diagnostics = state
+        .startup_diagnostics
+        .lock()
+        .map_err(|_| "Startup diagnostics lock error".to_string())?;
+    Ok(diagnostics.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+
+    const V0_2_CAMP_CONFIG_FIXTURE: &str = include_str!("../tests/fixtures/camp_config_v0_2.json");
+
+    #[test]
+    fn scan_camps_root_should_repair_safe_issues_and_report_the_rest() {
+        let camps_root = make_temp_dir("basecamp-recovery");
+        let camp_dir = camps_root.join("camp-v02");
+        let orphan_dir = camps_root.join("orphan");
+        fs::create_dir_all(&camp_dir).expect("camp folder should be created");
+        fs::create_dir_all(&orphan_dir).expect("orphan folder should be created");
+        fs::write(camp_config_path(&camp_dir), V0_2_CAMP_CONFIG_FIXTURE)
+            .expect("camp config should write");
+        fs::write(camp_memory_path(&camp_dir), "{\"kept\":true}").expect("memory should write");
+        let temp_file = camp_dir.join(format!(".memory.json.{}.tmp", Uuid::new_v4()));
+        fs::write(&temp_file, "{\"ha").expect("temp file should write");
+        let user_temp_file = camp_dir.join(".draft.1234.tmp");
+        fs::write(&user_temp_file, "mine").expect("user temp file should write");
+
+        let debug_dir = inspect::ensure_debug_dir(&camp_dir).expect("debug dir should exist");
+        fs::write(debug_dir.join("turn_a_request.json"), "{}").expect("request should write");
+        fs::write(debug_dir.join("turn_b_request.json"), "{}").expect("request should write");
+        fs::write(debug_dir.join("turn_b_bundle.json"), "{\"trunc").expect("bundle should write");
+
+        let (camps_scanned, issues) = scan_camps_root(&camps_root).expect("camps root should scan");
+        assert_eq!(camps_scanned, 2);
+
+        let kinds = |kind: StartupIssueKind| {
+            issues
+                .iter()
+                .filter(|entry| entry.kind == kind)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds(StartupIssueKind::MissingCampConfig)[0].camp_id,
+            "orphan"
+        );
+        assert!(!temp_file.exists());
+        assert!(user_temp_file.exists());
+        assert_eq!(kinds(StartupIssueKind::StaleTempFile).len(), 1);
+        assert!(camp_transcript_path(&camp_dir).is_file());
+        assert_eq!(
+            fs::read_to_string(camp_memory_path(&camp_dir)).expect("memory should read"),
+            "{\"kept\":true}"
+        );
+        assert!(!debug_dir.join("turn_b_bundle.json").exists());
+        let interrupted = kinds(StartupIssueKind::InterruptedInspectTurn);
+        assert_eq!(interrupted.len(), 2);
+        assert!(issues
+            .iter()
+            .filter(|entry| entry.kind != StartupIssueKind::MissingCampConfig
+                && entry.kind != StartupIssueKind::InterruptedInspectTurn)
+            .all(|entry| entry.repaired));
+    }
+}
@@ -0,0 +1,423 @@
+//! `render_artifact_rich`: artifact HTML for exports, where there is no
+//! webview to run Mermaid or KaTeX. Math (`$…$`, `$$…$$` and ```` ```math ````
+//! fences) is rendered to MathML by the KaTeX build bundled with the `katex`
+//! crate. Mermaid needs a browser to lay out diagrams, so ```` ```mermaid ````
+//! fences are turned into SVG by the Mermaid CLI (`mmdc`, or the program named
+//! in `BASECAMP_MERMAID_CLI`) when it is installed, and otherwise left as
+//! source with a warning.
+//!
+//! The markdown goes through the same sanitizer as `render_markdown`. Rendered
+//! math and diagrams come from our own renderers rather than the document, so
+//! they are spliced in after sanitizing, through placeholders ammonia leaves
+//! alone. Diagram labels still come from the document, so the SVG is filtered
+//! on its own before it is spliced in.
+
+use std::fs;
+use std::process::Command;
+
+use pulldown_cmark::{html, CodeBlockKind, CowStr, Event, Parser, Tag, TagEnd};
+use quick_xml::events::{BytesStart, Event as XmlEvent};
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+use uuid::Uuid;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::markdown::{escape_html, markdown_options, sanitizer};
+use crate::{
+    ensure_camps_root, ensure_main_window, load_artifact, parse_artifact_markdown,
+    resolve_existing_camp_dir, AppState,
+};
+
+const MERMAID_CLI_ENV: &str = "BASECAMP_MERMAID_CLI";
+const DEFAULT_MERMAID_CLI: &str = "mmdc";
+/// Draws labels as SVG text rather than HTML in a `foreignObject`, which the
+/// SVG filter drops.
+const MERMAID_CONFIG: &str = r#"{"htmlLabels":false,"flowchart":{"htmlLabels":false}}"#;
+/// Dropped from rendered diagrams along with everything inside them.
+const SVG_BLOCKED_ELEMENTS: [&str; 7] = [
+    "script",
+    "foreignobject",
+    "iframe",
+    "object",
+    "embed",
+    "set",
+    "animate",
+];
+/// Placeholders are private-use characters, which are stripped from the source
+/// first so a document can't forge one.
+const PLACEHOLDER_OPEN: char = '\u{E000}';
+const PLACEHOLDER_CLOSE: char = '\u{E001}';
+
+#[derive(Debug, Deserialize)]
+pub struct RenderArtifactRichPayload {
+    pub camp_id: String,
+    pub artifact_id: String,
+    /// Wrap the result in a complete HTML document, ready to save or print.
+    #[serde(default)]
+    pub standalone: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RichRender {
+    pub html: String,
+    pub math_rendered: usize,
+    pub diagrams_rendered: usize,
+    /// One entry per block or formula left as source, and why.
+    pub warnings: Vec<String>,
+}
+
+enum RichBlock {
+    Math { source: String, display: bool },
+    Mermaid(String),
+}
+
+#[cfg(unix)]
+fn render_math(source: &str, display: bool) -> Result<String, String> {
+    let opts = katex::Opts::builder()
+        .display_mode(display)
+        .output_type(katex::OutputType::Mathml)
+        .build()
+        .map_err(|err| err.to_string())?;
+    katex::render_with_opts(source, &opts).map_err(|err| err.to_string())
+}
+
+#[cfg(not(unix))]
+fn render_math(_source: &str, _display: bool) -> Result<String, String> {
+    Err("math rendering is not available on this platform".to_string())
+}
+
+fn render_mermaid(source: &str) -> Result<String, String> {
+    let program =
+        std::env::var(MERMAID_CLI_ENV).unwrap_or_else(|_| DEFAULT_MERMAID_CLI.to_string());
+    let work_dir = std::env::temp_dir().join(format!("basecamp-mermaid-{}", Uuid::new_v4()));
+    fs::create_dir_all(&work_dir)
+        .map_err(|err| format!("unable to create a scratch folder: {err}"))?;
+    let input = work_dir.join("diagram.mmd");
+    let config = work_dir.join("config.json");
+    let output = work_dir.join("diagram.svg");
+
+    let result = fs::write(&input, source)
+        .and_then(|()| fs::write(&config, MERMAID_CONFIG))
+        .map_err(|err| format!("unable to write the diagram: {err}"))
+        .and_then(|()| {
+            Command::new(&program)
+                .arg("--quiet")
+                .arg("--input")
+                .arg(&input)
+                .arg("--configFile")
+                .arg(&config)
+                .arg("--output")
+                .arg(&output)
+                .arg("--backgroundColor")
+                .arg("transparent")
+                .output()
+                .map_err(|err| match err.kind() {
+                    std::io::ErrorKind::NotFound => format!(
+                        "`{program}` is not installed; install @mermaid-js/mermaid-cli or set {MERMAID_CLI_ENV}"
+                    ),
+                    _ => format!("unable to run `{program}`: {err}"),
+                })
+        })
+        .and_then(|run| {
+            if run.status.success() {
+                fs::read_to_string(&output)
+                    .map_err(|err| format!("`{program}` produced no SVG: {err}"))
+                    .and_then(|svg| sanitize_svg(&svg))
+            } else {
+                Err(format!(
+                    "`{program}` failed: {}",
+                    String::from_utf8_lossy(&run.stderr).trim()
+                ))
+            }
+        });
+    let _ = fs::remove_dir_all(&work_dir);
+    result
+}
+
+/// Strips anything in a diagram that could run script or load a resource:
+/// the blocked elements, `on*` handlers, and links that don't point within
+/// the diagram. Comments, doctypes and processing instructions go too.
+fn sanitize_svg(svg: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(svg);
+    let mut writer = Writer::new(Vec::with_capacity(svg.len()));
+    // Depth inside a blocked element, whose whole subtree is dropped.
+    let mut skipping = 0usize;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| format!("the SVG could not be read: {err}"))?;
+        let kept = match event {
+            XmlEvent::Eof => break,
+            XmlEvent::Start(_) if skipping > 0 => {
+                skipping += 1;
+                None
+            }
+            XmlEvent::End(_) if skipping > 0 => {
+                skipping -= 1;
+                None
+            }
+            _ if skipping > 0 => None,
+            XmlEvent::Start(element) if is_blocked_svg_element(&element) => {
+                skipping = 1;
+                None
+            }
+            XmlEvent::Empty(element) if is_blocked_svg_element(&element) => None,
+            XmlEvent::Start(element) => Some(XmlEvent::Start(safe_svg_element(&element)?)),
+            XmlEvent::Empty(element) => Some(XmlEvent::Empty(safe_svg_element(&element)?)),
+            // HTML only honours CDATA inside SVG, so write it as plain text.
+            XmlEvent::CData(data) => {
+                Some(XmlEvent::Text(data.escape().map_err(|err| {
+                    format!("the SVG could not be read: {err}")
+                })?))
+            }
+            XmlEvent::Comment(_) | XmlEvent::Decl(_) | XmlEvent::PI(_) | XmlEvent::DocType(_) => {
+                None
+            }
+            other => Some(other),
+        };
+        if let Some(event) = kept {
+            writer
+                .write_event(event)
+                .map_err(|err| format!("the SVG could not be written: {err}"))?;
+        }
+    }
+    String::from_utf8(writer.into_inner())
+        .map_err(|err| format!("the SVG could not be written: {err}"))
+}
+
+fn is_blocked_svg_element(element: &BytesStart) -> bool {
+    let name = String::from_utf8_lossy(element.local_name().as_ref()).to_ascii_lowercase();
+    SVG_BLOCKED_ELEMENTS.contains(&name.as_str())
+}
+
+fn safe_svg_element(element: &BytesStart) -> Result<BytesStart<'static>, String> {
+    let mut safe = BytesStart::new(String::from_utf8_lossy(element.name().as_ref()).into_owned());
+    for attribute in element.attributes() {
+        let attribute =
+            attribute.map_err(|err| format!("the SVG has a malformed attribute: {err}"))?;
+        let name =
+            String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_ascii_lowercase();
+        let is_link = name == "href" || name == "src";
+        if name.starts_with("on") || (is_link && attribute.value.first() != Some(&b'#')) {
+            continue;
+        }
+        safe.push_attribute(attribute);
+    }
+    Ok(safe)
+}
+
+/// Swaps math and Mermaid blocks for placeholder text and collects them.
+fn extract_rich_blocks(markdown: &str) -> (Vec<Event<'_>>, Vec<RichBlock>) {
+    let mut events = Vec::new();
+    let mut blocks = Vec::new();
+    let mut fence: Option<(bool, String)> = None;
+    let placeholder = |index: usize| {
+        Event::Text(CowStr::from(format!(
+            "{PLACEHOLDER_OPEN}{index}{PLACEHOLDER_CLOSE}"
+        )))
+    };
+
+    for event in Parser::new_ext(
+        markdown,
+        markdown_options() | pulldown_cmark::Options::ENABLE_MATH,
+    ) {
+        match (&mut fence, event) {
+            (None, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))) => {
+                match info.split_whitespace().next() {
+                    Some("math") => fence = Some((true, String::new())),
+                    Some("mermaid") => fence = Some((false, String::new())),
+                    _ => events.push(Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))),
+                }
+            }
+            (Some((_, source)), Event::Text(text)) => source.push_str(&text),
+            (Some(_), Event::End(TagEnd::CodeBlock)) => {
+                let (is_math, source) = fence.take().unwrap_or_default();
+                events.push(Event::Start(Tag::Paragraph));
+                events.push(placeholder(blocks.len()));
+                events.push(Event::End(TagEnd::Paragraph));
+                blocks.push(if is_math {
+                    RichBlock::Math {
+                        source,
+                        display: true,
+                    }
+                } else {
+                    RichBlock::Mermaid(source)
+                });
+            }
+            (Some(_), _) => {}
+            (None, Event::InlineMath(source)) => {
+                events.push(placeholder(blocks.len()));
+                blocks.push(RichBlock::Math {
+                    source: source.to_string(),
+                    display: false,
+                });
+            }
+            (None, Event::DisplayMath(source)) => {
+                events.push(placeholder(blocks.len()));
+                blocks.push(RichBlock::Math {
+                    source: source.to_string(),
+                    display: true,
+                });
+            }
+            (None, event) => events.push(event),
+        }
+    }
+    (events, blocks)
+}
+
+fn render_rich_html_with(
+    markdown: &str,
+    render_math: impl Fn(&str, bool) -> Result<String, String>,
+    render_mermaid: impl Fn(&str) -> Result<String, String>,
+) -> RichRender {
+    let markdown: String = markdown
+        .chars()
+        .filter(|ch| *ch != PLACEHOLDER_OPEN && *ch != PLACEHOLDER_CLOSE)
+        .collect();
+    let (events, blocks) = extract_rich_blocks(&markdown);
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, events.into_iter());
+    let mut output = sanitizer().clean(&unsafe_html).to_string();
+
+    let mut math_rendered = 0;
+    let mut diagrams_rendered = 0;
+    let mut warnings = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        let rendered = match block {
+            RichBlock::Math { source, display } => match render_math(source, *display) {
+                Ok(mathml) => {
+                    math_rendered += 1;
+                    mathml
+                }
+                Err(err) => {
+                    warnings.push(format!("Math `{}` left as source: {err}", source.trim()));
+                    format!("<code class=\"math\">{}</code>", escape_html(source))
+                }
+            },
+            RichBlock::Mermaid(source) => match render_mermaid(source) {
+                Ok(svg) => {
+                    diagrams_rendered += 1;
+                    format!("<figure class=\"mermaid\">{svg}</figure>")
+                }
+                Err(err) => {
+                    warnings.push(format!(
+                        "Mermaid diagram {} left as source: {err}",
+                        index + 1
+                    ));
+                    format!(
+                        "<pre class=\"mermaid\"><code>{}</code></pre>",
+                        escape_html(source)
+                    )
+                }
+            },
+        };
+        output = output.replacen(
+            &format!("{PLACEHOLDER_OPEN}{index}{PLACEHOLDER_CLOSE}"),
+            &rendered,
+            1,
+        );
+    }
+
+    RichRender {
+        html: output,
+        math_rendered,
+        diagrams_rendered,
+        warnings,
+    }
+}
+
+fn standalone_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>body{{font-family:system-ui,sans-serif;line-height:1.5;max-width:48rem;margin:2rem auto;padding:0 1rem}}\
+         pre{{overflow-x:auto}}table{{border-collapse:collapse}}th,td{{border:1px solid #ccc;padding:.25rem .5rem}}\
+         figure.mermaid{{margin:1rem 0;text-align:center}}</style>\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+        escape_html(title),
+        escape_html(title),
+        body
+    )
+}
+
+/// Renders an artifact to HTML with math and diagrams pre-rendered, for
+/// exports. Nothing is written to disk.
+#[tauri::command]
+pub async fn render_artifact_rich(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: RenderArtifactRichPayload,
+) -> CommandResult<RichRender> {
+    ensure_main_window(&window)?;
+    let artifact = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        load_artifact(&camp_dir, &payload.artifact_id)?
+    };
+    let (title, body) = parse_artifact_markdown(&artifact.body, &artifact.metadata.title);
+
+    let mut rendered = tauri::async_runtime::spawn_blocking(move || {
+        render_rich_html_with(&body, render_math, render_mermaid)
+    })
+    .await
+    .map_err(|err| BasecampError::internal(format!("Rendering failed: {err}")))?;
+    if payload.standalone {
+        rendered.html = standalone_document(&title, &rendered.html);
+    }
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_rich_html_should_splice_rendered_blocks_after_sanitizing() {
+        let rendered = render_rich_html_with(
+            "Euler: $e^{i\\pi}$\n\n```mermaid\ngraph TD; A-->B\n```\n\n\
+             ```rust\nlet x = 1;\n```\n\n<script>alert(1)</script>\u{E000}0\u{E001}",
+            |source, display| Ok(format!("<math display=\"{display}\">{source}</math>")),
+            |_| Err("not installed".to_string()),
+        );
+
+        assert!(rendered
+            .html
+            .contains("Euler: <math display=\"false\">e^{i\\pi}</math>"));
+        assert!(rendered
+            .html
+            .contains("<pre class=\"mermaid\"><code>graph TD; A--&gt;B\n</code></pre>"));
+        assert!(rendered
+            .html
+            .contains("<code class=\"language-rust\">let x = 1;\n</code>"));
+        assert!(!rendered.html.contains("<script"));
+        assert!(!rendered.html.contains(PLACEHOLDER_OPEN));
+        assert_eq!((rendered.math_rendered, rendered.diagrams_rendered), (1, 0));
+        assert_eq!(rendered.warnings.len(), 1);
+    }
+
+    #[test]
+    fn sanitize_svg_should_drop_scripts_handlers_and_external_links() {
+        let svg = sanitize_svg(concat!(
+            r#"<?xml version="1.0"?><!-- note --><svg xmlns="http://www.w3.org/2000/svg" "#,
+            r#"xmlns:xlink="http://www.w3.org/1999/xlink" onload="alert(1)">"#,
+            r#"<style><![CDATA[.node{fill:#fff}]]></style>"#,
+            r#"<script>alert(2)</script><g class="node" ONCLICK="alert(3)">"#,
+            r#"<foreignObject><div><img src="x" onerror="alert(4)"/></div></foreignObject>"#,
+            r#"<a xlink:href="javascript:alert(5)"><text>A &amp; B</text></a>"#,
+            r##"<image href="https://example.com/track.png"/><use href="#arrow"/>"##,
+            r#"<set attributeName="href" to="javascript:alert(6)"/></g></svg>"#,
+        ))
+        .expect("well-formed SVG should sanitize");
+
+        assert_eq!(
+            svg,
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" "#,
+                r#"xmlns:xlink="http://www.w3.org/1999/xlink">"#,
+                r#"<style>.node{fill:#fff}</style><g class="node">"#,
+                r##"<a><text>A &amp; B</text></a><image/><use href="#arrow"/></g></svg>"##,
+            )
+        );
+        assert!(sanitize_svg("<svg><g></svg>").is_err());
+    }
+}
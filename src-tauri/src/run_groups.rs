@@ -0,0 +1,255 @@
+//! Groups runs that share a prompt. Each run stores a hash of its system and
+//! user prompt with whitespace collapsed, so re-sending the same prompt to
+//! another model or with other parameters lands in the same family. History
+//! can then show a prompt-iteration session as one collapsible row.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::privacy::REDACTED_CONTENT;
+use crate::AppState;
+
+const RUN_GROUPS_DEFAULT_LIMIT: usize = 50;
+const RUN_GROUPS_MAX_LIMIT: usize = 500;
+const PROMPT_PREVIEW_CHARS: usize = 120;
+
+#[derive(Debug, Deserialize)]
+pub struct ListRunGroupsPayload {
+    pub camp_id: Option<String>,
+    pub limit: Option<usize>,
+    /// Hides families smaller than this; `2` lists only repeated prompts.
+    pub min_runs: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunGroup {
+    /// The prompt hash, or the run id for runs that have none.
+    pub key: String,
+    pub prompt_hash: Option<String>,
+    pub system_prompt_preview: String,
+    pub user_prompt_preview: String,
+    pub run_count: usize,
+    /// Distinct model, temperature and max token combinations.
+    pub variant_count: usize,
+    pub models: Vec<String>,
+    /// Newest first.
+    pub run_ids: Vec<String>,
+    pub first_timestamp: i64,
+    pub last_timestamp: i64,
+    pub best_rating: Option<i64>,
+    pub error_count: usize,
+}
+
+struct RunGroupRow {
+    id: String,
+    timestamp: i64,
+    model: String,
+    temperature: f64,
+    max_tokens: i64,
+    system_prompt: String,
+    user_prompt: String,
+    rating: Option<i64>,
+    has_error: bool,
+    prompt_hash: Option<String>,
+}
+
+fn normalize_prompt(prompt: &str) -> String {
+    prompt.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Hash shared by runs with the same prompts. Redacted runs get none: their
+/// stored prompts are all the same placeholder.
+pub(crate) fn prompt_hash(system_prompt: &str, user_prompt: &str) -> Option<String> {
+    if user_prompt == REDACTED_CONTENT {
+        return None;
+    }
+    let mut hasher = Sha256::new();
+    hasher.update(normalize_prompt(system_prompt).as_bytes());
+    hasher.update([0]);
+    hasher.update(normalize_prompt(user_prompt).as_bytes());
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// Fills in hashes for runs stored before the column existed.
+pub(crate) fn backfill_prompt_hashes(connection: &Connection) -> Result<(), rusqlite::Error> {
+    let pending = {
+        let mut statement = connection
+            .prepare("SELECT id, system_prompt, user_prompt FROM runs WHERE prompt_hash IS NULL")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+    for (id, system_prompt, user_prompt) in pending {
+        if let Some(hash) = prompt_hash(&system_prompt, &user_prompt) {
+            connection.execute(
+                "UPDATE runs SET prompt_hash = ?1 WHERE id = ?2",
+                params![hash, id],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn preview(prompt: &str) -> String {
+    let normalized = normalize_prompt(prompt);
+    if normalized.chars().count() <= PROMPT_PREVIEW_CHARS {
+        return normalized;
+    }
+    let mut shortened: String = normalized.chars().take(PROMPT_PREVIEW_CHARS).collect();
+    shortened.push('…');
+    shortened
+}
+
+/// Folds newest-first rows into families, newest family first.
+fn group_runs(rows: Vec<RunGroupRow>) -> Vec<RunGroup> {
+    let mut groups: Vec<RunGroup> = Vec::new();
+    let mut variants: Vec<Vec<(String, u64, i64)>> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for row in rows {
+        let key = row.prompt_hash.clone().unwrap_or_else(|| row.id.clone());
+        let position = *positions.entry(key.clone()).or_insert_with(|| {
+            groups.push(RunGroup {
+                key,
+                prompt_hash: row.prompt_hash.clone(),
+                system_prompt_preview: preview(&row.system_prompt),
+                user_prompt_preview: preview(&row.user_prompt),
+                run_count: 0,
+                variant_count: 0,
+                models: Vec::new(),
+                run_ids: Vec::new(),
+                first_timestamp: row.timestamp,
+                last_timestamp: row.timestamp,
+                best_rating: None,
+                error_count: 0,
+            });
+            variants.push(Vec::new());
+            groups.len() - 1
+        });
+
+        let group = &mut groups[position];
+        group.run_count += 1;
+        group.run_ids.push(row.id);
+        group.first_timestamp = group.first_timestamp.min(row.timestamp);
+        group.last_timestamp = group.last_timestamp.max(row.timestamp);
+        group.best_rating = group.best_rating.max(row.rating);
+        if row.has_error {
+            group.error_count += 1;
+        }
+        let variant = (row.model.clone(), row.temperature.to_bits(), row.max_tokens);
+        if !variants[position].contains(&variant) {
+            variants[position].push(variant);
+            group.variant_count += 1;
+        }
+        if !group.models.contains(&row.model) {
+            group.models.push(row.model);
+        }
+    }
+
+    groups
+}
+
+/// Runs grouped by prompt, newest family first. Every run belongs to exactly
+/// one group; runs without a prompt hash stand alone.
+#[tauri::command]
+pub fn list_run_groups(
+    state: State<'_, AppState>,
+    payload: ListRunGroupsPayload,
+) -> CommandResult<Vec<RunGroup>> {
+    let limit = payload
+        .limit
+        .unwrap_or(RUN_GROUPS_DEFAULT_LIMIT)
+        .clamp(1, RUN_GROUPS_MAX_LIMIT);
+    let min_runs = payload.min_runs.unwrap_or(1);
+
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let mut statement = connection.prepare(
+        "SELECT id, timestamp, COALESCE(requested_model, model), temperature, max_tokens,
+                substr(system_prompt, 1, 1000), substr(user_prompt, 1, 1000), rating,
+                error IS NOT NULL, prompt_hash
+         FROM runs
+         WHERE ?1 IS NULL OR camp_id = ?1
+         ORDER BY timestamp DESC",
+    )?;
+    let rows = statement.query_map(params![payload.camp_id], |row| {
+        Ok(RunGroupRow {
+            id: row.get(0)?,
+            timestamp: row.get(1)?,
+            model: row.get(2)?,
+            temperature: row.get(3)?,
+            max_tokens: row.get(4)?,
+            system_prompt: row.get(5)?,
+            user_prompt: row.get(6)?,
+            rating: row.get(7)?,
+            has_error: row.get(8)?,
+            prompt_hash: row.get(9)?,
+        })
+    })?;
+    let rows = rows.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(group_runs(rows)
+        .into_iter()
+        .filter(|group| group.run_count >= min_runs)
+        .take(limit)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_runs_should_fold_reworded_whitespace_and_keep_redacted_runs_apart() {
+        let row =
+            |id: &str, timestamp: i64, model: &str, temperature: f64, user: &str| RunGroupRow {
+                id: id.to_string(),
+                timestamp,
+                model: model.to_string(),
+                temperature,
+                max_tokens: 256,
+                system_prompt: "Be brief.".to_string(),
+                user_prompt: user.to_string(),
+                rating: (id == "run-2").then_some(4),
+                has_error: id == "run-4",
+                prompt_hash: prompt_hash("Be brief.", user),
+            };
+
+        let groups = group_runs(vec![
+            row("run-5", 50, "openai/gpt-4o", 0.2, "Summarize\n  the memo"),
+            row("run-4", 40, "openai/gpt-4o", 0.2, REDACTED_CONTENT),
+            row("run-3", 30, "anthropic/claude", 0.2, "Summarize the memo"),
+            row("run-2", 20, "openai/gpt-4o", 0.7, "Summarize the memo "),
+            row("run-1", 10, "openai/gpt-4o", 0.2, "Summarize the memo"),
+            row("run-0", 5, "openai/gpt-4o", 0.2, REDACTED_CONTENT),
+        ]);
+
+        assert_eq!(groups.len(), 3);
+        let family = &groups[0];
+        assert_eq!(family.run_ids, vec!["run-5", "run-3", "run-2", "run-1"]);
+        assert_eq!(family.run_count, 4);
+        assert_eq!(family.variant_count, 3);
+        assert_eq!(family.models, vec!["openai/gpt-4o", "anthropic/claude"]);
+        assert_eq!((family.first_timestamp, family.last_timestamp), (10, 50));
+        assert_eq!(family.best_rating, Some(4));
+        assert_eq!(family.user_prompt_preview, "Summarize the memo");
+
+        assert_eq!(groups[1].key, "run-4");
+        assert_eq!(groups[1].prompt_hash, None);
+        assert_eq!(groups[1].error_count, 1);
+        assert_eq!(groups[2].key, "run-0");
+        assert_ne!(
+            prompt_hash("Be brief.", "Summarize the memo"),
+            prompt_hash("", "Be brief. Summarize the memo")
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! Working memory for the model. The `remember` and `recall` camp tools keep
+//! short keyed notes under the `scratchpad` key of `memory.json`, so a model
+//! can carry state between turns. Unlike the rest of memory, the notes are
+//! left out of every request (see `without_scratchpad`); the model reads them
+//! back with `recall` when it needs them.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::memory_schema;
+use crate::{
+    camp_memory_path, ensure_camps_root, ensure_main_window, read_json_file,
+    resolve_existing_camp_dir, touch_camp_updated_at, write_json_file, AppState,
+};
+
+const SCRATCHPAD_MEMORY_KEY: &str = "scratchpad";
+const MAX_NOTE_KEY_CHARS: usize = 100;
+const MAX_NOTE_CHARS: usize = 8_000;
+const MAX_NOTES: usize = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct ScratchpadRememberPayload {
+    pub camp_id: String,
+    pub key: String,
+    /// An empty note forgets the key.
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScratchpadRememberResult {
+    pub key: String,
+    pub stored: bool,
+    pub note_count: usize,
+}
+
+fn normalize_note_key(key: &str) -> CommandResult<String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(BasecampError::validation(
+            "Scratchpad key must not be empty.",
+        ));
+    }
+    if key.chars().count() > MAX_NOTE_KEY_CHARS {
+        return Err(BasecampError::validation(format!(
+            "Scratchpad keys are limited to {MAX_NOTE_KEY_CHARS} characters."
+        )));
+    }
+    Ok(key.to_string())
+}
+
+fn scratchpad(memory: &Value) -> Option<&Map<String, Value>> {
+    memory.get(SCRATCHPAD_MEMORY_KEY).and_then(Value::as_object)
+}
+
+/// Memory as it is sent to the model. A `scratchpad` key that holds anything
+/// other than notes is the user's own data and stays.
+pub(crate) fn without_scratchpad(memory: &Value) -> Value {
+    let mut sent = memory.clone();
+    if scratchpad(memory).is_some() {
+        if let Value::Object(map) = &mut sent {
+            map.remove(SCRATCHPAD_MEMORY_KEY);
+        }
+    }
+    sent
+}
+
+/// Stores or forgets one note, creating the namespace on first use. Returns
+/// how many notes the scratchpad holds afterwards.
+fn remember_note(memory: &mut Value, key: &str, note: &str) -> CommandResult<usize> {
+    if note.chars().count() > MAX_NOTE_CHARS {
+        return Err(BasecampError::validation(format!(
+            "Scratchpad notes are limited to {MAX_NOTE_CHARS} characters."
+        ))
+        .with_context("key", key));
+    }
+    let Value::Object(memory_map) = memory else {
+        return Err(BasecampError::validation(
+            "Camp memory is not a JSON object, so it has no scratchpad.",
+        ));
+    };
+    let notes = memory_map
+        .entry(SCRATCHPAD_MEMORY_KEY)
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Value::Object(notes) = notes else {
+        return Err(BasecampError::validation(format!(
+            "Camp memory already uses `{SCRATCHPAD_MEMORY_KEY}` for something other than notes."
+        )));
+    };
+
+    if note.is_empty() {
+        notes.remove(key);
+    } else {
+        if !notes.contains_key(key) && notes.len() >= MAX_NOTES {
+            return Err(BasecampError::validation(format!(
+                "The scratchpad is full ({MAX_NOTES} notes); forget one before adding another."
+            )));
+        }
+        notes.insert(key.to_string(), Value::String(note.to_string()));
+    }
+    Ok(notes.len())
+}
+
+/// Saves a note to the camp's scratchpad, or removes it when `note` is empty.
+#[tauri::command]
+pub fn camp_scratchpad_remember(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: ScratchpadRememberPayload,
+) -> CommandResult<ScratchpadRememberResult> {
+    ensure_main_window(&window)?;
+    let key = normalize_note_key(&payload.key)?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+    let memory_path = camp_memory_path(&camp_dir);
+
+    let memory: Value = read_json_file(&memory_path)?;
+    let mut updated = memory.clone();
+    let note_count = remember_note(&mut updated, &key, &payload.note)?;
+    if updated != memory {
+        memory_schema::ensure_memory_matches_schema(&camp_dir, &updated)?;
+        write_json_file(&memory_path, &updated)?;
+        touch_camp_updated_at(&camp_dir)?;
+    }
+    Ok(ScratchpadRememberResult {
+        key,
+        stored: !payload.note.is_empty(),
+        note_count,
+    })
+}
+
+/// One note when `key` is given (`null` if there is none), otherwise every
+/// note as an object.
+#[tauri::command]
+pub fn camp_scratchpad_recall(
+    state: State<'_, AppState>,
+    camp_id: String,
+    key: Option<String>,
+) -> CommandResult<Value> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    let memory: Value = read_json_file(&camp_memory_path(&camp_dir))?;
+    let notes = scratchpad(&memory);
+
+    Ok(
+        match key.as_deref().map(str::trim).filter(|key| !key.is_empty()) {
+            Some(key) => notes
+                .and_then(|notes| notes.get(key))
+                .cloned()
+                .unwrap_or(Value::Null),
+            None => Value::Object(notes.cloned().unwrap_or_default()),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn remember_note_should_keep_notes_under_the_scratchpad_key() {
+        let mut memory = json!({ "goal": "ship v2" });
+        let mut remember = |key: &str, note: &str| {
+            remember_note(&mut memory, key, note).expect("note should be stored")
+        };
+        assert_eq!(remember("todo", "write tests"), 1);
+        assert_eq!(remember("owner", "Ari"), 2);
+        assert_eq!(remember("todo", ""), 1);
+        assert_eq!(
+            memory,
+            json!({ "goal": "ship v2", "scratchpad": { "owner": "Ari" } })
+        );
+
+        let too_long = "x".repeat(MAX_NOTE_CHARS + 1);
+        assert!(remember_note(&mut memory, "big", &too_long).is_err());
+        assert!(remember_note(&mut json!({ "scratchpad": [] }), "a", "b").is_err());
+        assert_eq!(without_scratchpad(&memory), json!({ "goal": "ship v2" }));
+        assert_eq!(
+            without_scratchpad(&json!({ "scratchpad": "mine" })),
+            json!({ "scratchpad": "mine" })
+        );
+        assert!(normalize_note_key("   ").is_err());
+        assert_eq!(
+            normalize_note_key(" plan ").expect("key should be valid"),
+            "plan"
+        );
+    }
+}
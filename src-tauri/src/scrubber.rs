@@ -0,0 +1,312 @@
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::{
+    ensure_main_window, get_setting_value, privacy::compile_scrub_patterns,
+    providers::BasecampChatRequest, set_setting_value, AppState,
+};
+
+const SETTING_OUTBOUND_SCRUB: &str = "outbound_scrub";
+
+pub(crate) const EMAIL_PATTERN: &str =
+    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}";
+const PHONE_PATTERN: &str = r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{3}\)|\b\d{3})[\s.-]?\d{3}[\s.-]?\d{4}\b";
+pub(crate) const API_KEY_PATTERN: &str = concat!(
+    r"\b(?:sk|pk|rk)-[A-Za-z0-9_-]{16,}",
+    r"|\bAKIA[0-9A-Z]{16}\b",
+    r"|\bgh[pousr]_[A-Za-z0-9]{36,}",
+    r"|\bxox[abprs]-[A-Za-z0-9-]{10,}",
+    r"|\bAIza[0-9A-Za-z_-]{35}",
+);
+
+/// Message keys that carry protocol data rather than user text.
+const STRUCTURAL_KEYS: &[&str] = &["role", "type", "id", "tool_call_id", "name"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboundScrubSettings {
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub mask_emails: bool,
+    #[serde(default = "default_true")]
+    pub mask_phone_numbers: bool,
+    #[serde(default = "default_true")]
+    pub mask_api_keys: bool,
+    #[serde(default)]
+    pub custom_patterns: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for OutboundScrubSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mask_emails: true,
+            mask_phone_numbers: true,
+            mask_api_keys: true,
+            custom_patterns: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrubCategory {
+    Email,
+    PhoneNumber,
+    ApiKey,
+    Custom,
+}
+
+/// What was masked, never the masked values themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrubAuditEntry {
+    pub category: ScrubCategory,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScrubAudit {
+    pub total_masked: usize,
+    pub entries: Vec<ScrubAuditEntry>,
+}
+
+struct ScrubRule {
+    category: ScrubCategory,
+    pattern: Option<String>,
+    regex: Regex,
+    mask: &'static str,
+}
+
+fn builtin_rule(category: ScrubCategory, pattern: &str, mask: &'static str) -> ScrubRule {
+    ScrubRule {
+        category,
+        pattern: None,
+        regex: Regex::new(pattern).expect("built-in scrub pattern should compile"),
+        mask,
+    }
+}
+
+fn build_rules(settings: &OutboundScrubSettings) -> Vec<ScrubRule> {
+    // Keys first so an email-like token inside a key is counted once.
+    let mut rules = Vec::new();
+    if settings.mask_api_keys {
+        rules.push(builtin_rule(
+            ScrubCategory::ApiKey,
+            API_KEY_PATTERN,
+            "[API_KEY]",
+        ));
+    }
+    if settings.mask_emails {
+        rules.push(builtin_rule(ScrubCategory::Email, EMAIL_PATTERN, "[EMAIL]"));
+    }
+    if settings.mask_phone_numbers {
+        rules.push(builtin_rule(
+            ScrubCategory::PhoneNumber,
+            PHONE_PATTERN,
+            "[PHONE]",
+        ));
+    }
+    for pattern in &settings.custom_patterns {
+        if let Ok(regex) = Regex::new(pattern) {
+            rules.push(ScrubRule {
+                category: ScrubCategory::Custom,
+                pattern: Some(pattern.clone()),
+                regex,
+                mask: "[REDACTED]",
+            });
+        }
+    }
+    rules
+}
+
+fn scrub_text(text: &str, rules: &[ScrubRule], counts: &mut [usize]) -> String {
+    let mut current = text.to_string();
+    for (index, rule) in rules.iter().enumerate() {
+        let matches = rule.regex.find_iter(&current).count();
+        if matches > 0 {
+            counts[index] += matches;
+            current = rule.regex.replace_all(&current, rule.mask).into_owned();
+        }
+    }
+    current
+}
+
+fn scrub_value(value: &mut Value, rules: &[ScrubRule], counts: &mut [usize]) {
+    match value {
+        Value::String(text) => *text = scrub_text(text, rules, counts),
+        Value::Array(items) => {
+            for item in items {
+                scrub_value(item, rules, counts);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if !STRUCTURAL_KEYS.contains(&key.as_str()) {
+                    scrub_value(item, rules, counts);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn summarize_scrub(rules: Vec<ScrubRule>, counts: Vec<usize>) -> ScrubAudit {
+    let entries: Vec<ScrubAuditEntry> = rules
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|(rule, count)| ScrubAuditEntry {
+            category: rule.category,
+            pattern: rule.pattern,
+            count,
+        })
+        .collect();
+    ScrubAudit {
+        total_masked: entries.iter().map(|entry| entry.count).sum(),
+        entries,
+    }
+}
+
+/// Masks matches in message text, tool-call arguments and content parts.
+fn scrub_messages(messages: &mut [Value], settings: &OutboundScrubSettings) -> ScrubAudit {
+    let rules = build_rules(settings);
+    let mut counts = vec![0; rules.len()];
+    for message in messages.iter_mut() {
+        scrub_value(message, &rules, &mut counts);
+    }
+    summarize_scrub(rules, counts)
+}
+
+/// Masks matches in plain strings, such as an exported transcript.
+pub(crate) fn scrub_texts<'a>(
+    texts: impl IntoIterator<Item = &'a mut String>,
+    settings: &OutboundScrubSettings,
+) -> ScrubAudit {
+    let rules = build_rules(settings);
+    let mut counts = vec![0; rules.len()];
+    for text in texts {
+        *text = scrub_text(text, &rules, &mut counts);
+    }
+    summarize_scrub(rules, counts)
+}
+
+fn load_outbound_scrub_settings(connection: &Connection) -> Result<OutboundScrubSettings, String> {
+    Ok(get_setting_value(connection, SETTING_OUTBOUND_SCRUB)
+        .map_err(|err| format!("Unable to load outbound scrub settings: {err}"))?
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+/// Scrubs a request bound for a cloud provider. Local providers never leave the
+/// machine and are sent untouched.
+pub(crate) fn scrub_outbound_request(
+    connection: &Connection,
+    request: &mut BasecampChatRequest,
+) -> Result<Option<ScrubAudit>, String> {
+    if request.provider_kind.is_local() {
+        return Ok(None);
+    }
+    let settings = load_outbound_scrub_settings(connection)?;
+    if !settings.enabled {
+        return Ok(None);
+    }
+    Ok(Some(scrub_messages(&mut request.messages, &settings)))
+}
+
+#[tauri::command]
+pub fn get_outbound_scrub_settings(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<OutboundScrubSettings, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_outbound_scrub_settings(&connection)
+}
+
+#[tauri::command]
+pub fn set_outbound_scrub_settings(
+    window: Window,
+    state: State<'_, AppState>,
+    settings: OutboundScrubSettings,
+) -> Result<OutboundScrubSettings, String> {
+    ensure_main_window(&window)?;
+    let custom_patterns: Vec<String> = settings
+        .custom_patterns
+        .iter()
+        .map(|pattern| pattern.trim().to_string())
+        .filter(|pattern| !pattern.is_empty())
+        .collect();
+    compile_scrub_patterns(&custom_patterns)?;
+    let settings = OutboundScrubSettings {
+        custom_patterns,
+        ..settings
+    };
+    let serialized = serde_json::to_string(&settings)
+        .map_err(|err| format!("Unable to serialize outbound scrub settings: {err}"))?;
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(&connection, SETTING_OUTBOUND_SCRUB, &serialized)
+        .map_err(|err| format!("Unable to save outbound scrub settings: {err}"))?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn scrub_messages_should_mask_pii_and_report_counts_only() {
+        let mut messages = vec![
+            json!({
+                "role": "user",
+                "content": "Mail ana@example.org or call (555) 123-4567, key sk-abcdefghijklmnop1234",
+            }),
+            json!({
+                "role": "user",
+                "content": [{ "type": "text", "text": "Ticket ACME-4411 from bob@example.com" }],
+            }),
+        ];
+        let settings = OutboundScrubSettings {
+            enabled: true,
+            custom_patterns: vec![r"ACME-\d+".to_string()],
+            ..OutboundScrubSettings::default()
+        };
+
+        let audit = scrub_messages(&mut messages, &settings);
+
+        assert_eq!(
+            messages[0]["content"],
+            "Mail [EMAIL] or call [PHONE], key [API_KEY]"
+        );
+        assert_eq!(
+            messages[1]["content"][0]["text"],
+            "Ticket [REDACTED] from [EMAIL]"
+        );
+        assert_eq!(messages[1]["content"][0]["type"], "text");
+        assert_eq!(audit.total_masked, 5);
+        let email = audit
+            .entries
+            .iter()
+            .find(|entry| entry.category == ScrubCategory::Email)
+            .expect("email entry should exist");
+        assert_eq!(email.count, 2);
+        assert!(!serde_json::to_string(&audit)
+            .expect("audit should serialize")
+            .contains("example"));
+    }
+}
@@ -0,0 +1,825 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use regex::{Regex, RegexBuilder};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+use crate::{
+    camp_artifacts_dir, camp_context_dir, camp_system_prompt_path, camp_transcript_path,
+    ensure_camps_root, ensure_main_window, parse_artifact_markdown, read_camp_config,
+    read_transcript, require_workspace_path, resolve_existing_camp_dir, AppState, CampMessage,
+};
+
+const SEARCH_DEFAULT_LIMIT: usize = 50;
+pub(crate) const SEARCH_MAX_LIMIT: usize = 200;
+const SEARCH_MAX_FILE_BYTES: u64 = 1024 * 1024;
+const SEARCH_SNIPPET_TOKENS: i64 = 16;
+/// Characters of context kept on each side of a transcript match.
+const TRANSCRIPT_SNIPPET_CONTEXT_CHARS: usize = 60;
+const TRANSCRIPT_REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchHitKind {
+    CampName,
+    SystemPrompt,
+    Transcript,
+    Artifact,
+    ContextFile,
+}
+
+impl SearchHitKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            SearchHitKind::CampName => "camp_name",
+            SearchHitKind::SystemPrompt => "system_prompt",
+            SearchHitKind::Transcript => "transcript",
+            SearchHitKind::Artifact => "artifact",
+            SearchHitKind::ContextFile => "context_file",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "camp_name" => Some(SearchHitKind::CampName),
+            "system_prompt" => Some(SearchHitKind::SystemPrompt),
+            "transcript" => Some(SearchHitKind::Transcript),
+            "artifact" => Some(SearchHitKind::Artifact),
+            "context_file" => Some(SearchHitKind::ContextFile),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceSearchHit {
+    pub camp_id: String,
+    pub kind: SearchHitKind,
+    pub path: String,
+    pub title: String,
+    pub snippet: String,
+    pub score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchWorkspacePayload {
+    pub query: String,
+    pub limit: Option<usize>,
+    pub kinds: Option<Vec<SearchHitKind>>,
+}
+
+/// A searchable unit discovered on disk. `doc_key` is `<camp_id>/<relative path>`
+/// (or `<camp_id>/camp.json` for the name entry) and stays stable across refreshes.
+struct SearchSource {
+    doc_key: String,
+    camp_id: String,
+    kind: SearchHitKind,
+    path: String,
+    absolute_path: PathBuf,
+    modified_at_ms: i64,
+    size_bytes: i64,
+}
+
+pub fn create_search_tables(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS search_documents (
+            workspace_path TEXT NOT NULL,
+            doc_key TEXT NOT NULL,
+            camp_id TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            path TEXT NOT NULL,
+            modified_at_ms INTEGER NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            PRIMARY KEY (workspace_path, doc_key)
+        );
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS search_fts USING fts5(
+            workspace_path UNINDEXED,
+            doc_key UNINDEXED,
+            title,
+            body,
+            tokenize = 'porter unicode61'
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+fn file_stamp(path: &Path) -> Option<(i64, i64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified_at_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0);
+    Some((modified_at_ms, metadata.len() as i64))
+}
+
+fn push_source(
+    sources: &mut Vec<SearchSource>,
+    camp_id: &str,
+    kind: SearchHitKind,
+    path: String,
+    absolute_path: PathBuf,
+) {
+    let Some((modified_at_ms, size_bytes)) = file_stamp(&absolute_path) else {
+        return;
+    };
+
+    sources.push(SearchSource {
+        doc_key: format!("{camp_id}/{path}"),
+        camp_id: camp_id.to_string(),
+        kind,
+        path,
+        absolute_path,
+        modified_at_ms,
+        size_bytes,
+    });
+}
+
+fn collect_context_sources(
+    sources: &mut Vec<SearchSource>,
+    camp_id: &str,
+    context_root: &Path,
+    current_dir: &Path,
+) {
+    let Ok(entries) = fs::read_dir(current_dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let entry_path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_symlink() {
+            continue;
+        }
+        if file_type.is_dir() {
+            collect_context_sources(sources, camp_id, context_root, &entry_path);
+            continue;
+        }
+
+        let Ok(relative) = entry_path.strip_prefix(context_root) else {
+            continue;
+        };
+        let path = format!(
+            "{}/{}",
+            crate::CAMP_CONTEXT_DIR,
+            relative.to_string_lossy().replace('\\', "/")
+        );
+        push_source(
+            sources,
+            camp_id,
+            SearchHitKind::ContextFile,
+            path,
+            entry_path,
+        );
+    }
+}
+
+fn collect_camp_sources(camp_dir: &Path, camp_id: &str) -> Vec<SearchSource> {
+    let mut sources = Vec::new();
+
+    push_source(
+        &mut sources,
+        camp_id,
+        SearchHitKind::CampName,
+        crate::CAMP_CONFIG_FILE.to_string(),
+        crate::camp_config_path(camp_dir),
+    );
+    push_source(
+        &mut sources,
+        camp_id,
+        SearchHitKind::SystemPrompt,
+        crate::CAMP_SYSTEM_PROMPT_FILE.to_string(),
+        camp_system_prompt_path(camp_dir),
+    );
+    push_source(
+        &mut sources,
+        camp_id,
+        SearchHitKind::Transcript,
+        crate::CAMP_TRANSCRIPT_FILE.to_string(),
+        camp_transcript_path(camp_dir),
+    );
+
+    if let Ok(entries) = fs::read_dir(camp_artifacts_dir(camp_dir)) {
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let is_markdown = entry_path
+                .extension()
+                .and_then(|value| value.to_str())
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("md"));
+            if !entry_path.is_file() || !is_markdown {
+                continue;
+            }
+            let Some(filename) = entry_path.file_name().and_then(|value| value.to_str()) else {
+                continue;
+            };
+            let path = format!("{}/{filename}", crate::CAMP_ARTIFACTS_DIR);
+            push_source(
+                &mut sources,
+                camp_id,
+                SearchHitKind::Artifact,
+                path,
+                entry_path,
+            );
+        }
+    }
+
+    let context_dir = camp_context_dir(camp_dir);
+    collect_context_sources(&mut sources, camp_id, &context_dir, &context_dir);
+
+    sources
+}
+
+/// Extracts the `(title, body)` pair that gets written to the FTS table.
+/// Returns `None` for files that should not be indexed (binary, oversized).
+fn extract_document(camp_dir: &Path, source: &SearchSource) -> Option<(String, String)> {
+    match source.kind {
+        SearchHitKind::CampName => {
+            let config = read_camp_config(camp_dir).ok()?;
+            let mut body = config.description.unwrap_or_default();
+            if !config.tags.is_empty() {
+                body.push('\n');
+                body.push_str(&config.tags.join(" "));
+            }
+            Some((config.name, body))
+        }
+        SearchHitKind::SystemPrompt => {
+            let body = fs::read_to_string(&source.absolute_path).ok()?;
+            Some(("System prompt".to_string(), body))
+        }
+        SearchHitKind::Transcript => {
+            let body = match read_transcript(&source.absolute_path) {
+                Ok(messages) => messages
+                    .into_iter()
+                    .map(|message| message.content)
+                    .filter(|content| !content.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Err(_) => fs::read_to_string(&source.absolute_path).ok()?,
+            };
+            Some(("Transcript".to_string(), body))
+        }
+        SearchHitKind::Artifact => {
+            let markdown = fs::read_to_string(&source.absolute_path).ok()?;
+            Some(parse_artifact_markdown(&markdown, &source.path))
+        }
+        SearchHitKind::ContextFile => {
+            if source.size_bytes as u64 > SEARCH_MAX_FILE_BYTES {
+                return None;
+            }
+            let bytes = fs::read(&source.absolute_path).ok()?;
+            let body = String::from_utf8(bytes).ok()?;
+            Some((source.path.clone(), body))
+        }
+    }
+}
+
+fn delete_document(
+    connection: &Connection,
+    workspace_key: &str,
+    doc_key: &str,
+) -> Result<(), String> {
+    connection
+        .execute(
+            "DELETE FROM search_fts WHERE workspace_path = ?1 AND doc_key = ?2",
+            params![workspace_key, doc_key],
+        )
+        .map_err(|err| format!("Unable to update search index: {err}"))?;
+    connection
+        .execute(
+            "DELETE FROM search_documents WHERE workspace_path = ?1 AND doc_key = ?2",
+            params![workspace_key, doc_key],
+        )
+        .map_err(|err| format!("Unable to update search index: {err}"))?;
+    Ok(())
+}
+
+/// Brings the index for `workspace_path` up to date with the files on disk.
+/// Only documents whose mtime or size changed are re-read; files that cannot
+/// be indexed keep their stamp so they are skipped until they change. All
+/// writes land in one transaction. Returns the number of documents that were
+/// (re)indexed or removed.
+pub fn refresh_workspace_index(
+    connection: &Connection,
+    workspace_path: &Path,
+    camps_root: &Path,
+) -> Result<usize, String> {
+    let workspace_key = workspace_path.to_string_lossy().into_owned();
+
+    let mut known: HashMap<String, (i64, i64)> = HashMap::new();
+    {
+        let mut statement = connection
+            .prepare(
+                "SELECT doc_key, modified_at_ms, size_bytes FROM search_documents WHERE workspace_path = ?1",
+            )
+            .map_err(|err| format!("Unable to read search index: {err}"))?;
+        let rows = statement
+            .query_map(params![workspace_key], |row| {
+                Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+            })
+            .map_err(|err| format!("Unable to read search index: {err}"))?;
+        for row in rows {
+            let (doc_key, stamp) =
+                row.map_err(|err| format!("Unable to read search index: {err}"))?;
+            known.insert(doc_key, stamp);
+        }
+    }
+
+    let transaction = connection
+        .unchecked_transaction()
+        .map_err(|err| format!("Unable to update search index: {err}"))?;
+    let mut changed = 0;
+    let entries =
+        fs::read_dir(camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+    for entry in entries.flatten() {
+        let camp_dir = entry.path();
+        if !camp_dir.is_dir() || !crate::camp_config_path(&camp_dir).exists() {
+            continue;
+        }
+        let Some(camp_id) = camp_dir.file_name().and_then(|value| value.to_str()) else {
+            continue;
+        };
+
+        for source in collect_camp_sources(&camp_dir, camp_id) {
+            let stamp = (source.modified_at_ms, source.size_bytes);
+            if known.remove(&source.doc_key) == Some(stamp) {
+                continue;
+            }
+
+            delete_document(&transaction, &workspace_key, &source.doc_key)?;
+            changed += 1;
+
+            transaction
+                .execute(
+                    "
+                    INSERT INTO search_documents (
+                      workspace_path, doc_key, camp_id, kind, path, modified_at_ms, size_bytes
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    ",
+                    params![
+                        workspace_key,
+                        source.doc_key,
+                        source.camp_id,
+                        source.kind.as_str(),
+                        source.path,
+                        source.modified_at_ms,
+                        source.size_bytes,
+                    ],
+                )
+                .map_err(|err| format!("Unable to update search index: {err}"))?;
+
+            let Some((title, body)) = extract_document(&camp_dir, &source) else {
+                continue;
+            };
+            transaction
+                .execute(
+                    "INSERT INTO search_fts (workspace_path, doc_key, title, body) VALUES (?1, ?2, ?3, ?4)",
+                    params![workspace_key, source.doc_key, title, body],
+                )
+                .map_err(|err| format!("Unable to update search index: {err}"))?;
+        }
+    }
+
+    for stale_key in known.keys() {
+        delete_document(&transaction, &workspace_key, stale_key)?;
+        changed += 1;
+    }
+
+    transaction
+        .commit()
+        .map_err(|err| format!("Unable to update search index: {err}"))?;
+    Ok(changed)
+}
+
+/// Turns free-form user input into a safe FTS5 query: every term is quoted
+/// (so operators and punctuation are literal) and prefix-matched.
+fn build_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.replace('"', "\"\""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{term}\"*"))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Ranked hits for `query`, optionally limited to some kinds or to one camp.
+pub fn search_index(
+    connection: &Connection,
+    workspace_path: &Path,
+    query: &str,
+    limit: usize,
+    kinds: Option<&[SearchHitKind]>,
+    camp_id: Option<&str>,
+) -> Result<Vec<WorkspaceSearchHit>, String> {
+    let Some(match_query) = build_match_query(query) else {
+        return Ok(Vec::new());
+    };
+    let workspace_key = workspace_path.to_string_lossy().into_owned();
+
+    let mut statement = connection
+        .prepare(
+            "
+            SELECT
+              d.camp_id,
+              d.kind,
+              d.path,
+              search_fts.title,
+              snippet(search_fts, 3, '[', ']', '…', ?4),
+              bm25(search_fts)
+            FROM search_fts
+            JOIN search_documents d
+              ON d.workspace_path = search_fts.workspace_path AND d.doc_key = search_fts.doc_key
+            WHERE search_fts MATCH ?1 AND search_fts.workspace_path = ?2
+              AND (?5 IS NULL OR d.camp_id = ?5)
+            ORDER BY bm25(search_fts)
+            LIMIT ?3
+            ",
+        )
+        .map_err(|err| format!("Unable to prepare workspace search: {err}"))?;
+
+    // Fetch extra rows when filtering by kind so the limit still applies after filtering.
+    let fetch_limit = if kinds.is_some() {
+        SEARCH_MAX_LIMIT as i64
+    } else {
+        limit as i64
+    };
+    let rows = statement
+        .query_map(
+            params![
+                match_query,
+                workspace_key,
+                fetch_limit,
+                SEARCH_SNIPPET_TOKENS,
+                camp_id
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, f64>(5)?,
+                ))
+            },
+        )
+        .map_err(|err| format!("Unable to run workspace search: {err}"))?;
+
+    let mut hits = Vec::new();
+    for row in rows {
+        let (camp_id, kind, path, title, snippet, rank) =
+            row.map_err(|err| format!("Unable to read search result: {err}"))?;
+        let Some(kind) = SearchHitKind::parse(&kind) else {
+            continue;
+        };
+        if kinds.is_some_and(|allowed| !allowed.contains(&kind)) {
+            continue;
+        }
+
+        hits.push(WorkspaceSearchHit {
+            camp_id,
+            kind,
+            path,
+            title,
+            snippet,
+            // bm25 is lower-is-better; flip it so callers can sort descending.
+            score: -rank,
+        });
+        if hits.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(hits)
+}
+
+#[tauri::command]
+pub fn search_workspace(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: SearchWorkspacePayload,
+) -> Result<Vec<WorkspaceSearchHit>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let camps_root = ensure_camps_root(&connection)?;
+
+    refresh_workspace_index(&connection, &workspace_path, &camps_root)?;
+
+    let limit = payload
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .clamp(1, SEARCH_MAX_LIMIT);
+    search_index(
+        &connection,
+        &workspace_path,
+        &payload.query,
+        limit,
+        payload.kinds.as_deref(),
+        None,
+    )
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptSearchHit {
+    pub message_id: String,
+    pub role: String,
+    pub created_at: i64,
+    /// Text around the first match, with every match in it wrapped in `[` `]`
+    /// like workspace search snippets.
+    pub snippet: String,
+    pub match_count: usize,
+}
+
+fn transcript_search_pattern(query: &str, use_regex: bool) -> Result<Option<Regex>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(None);
+    }
+    let pattern = if use_regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .size_limit(TRANSCRIPT_REGEX_SIZE_LIMIT)
+        .build()
+        .map(Some)
+        .map_err(|err| format!("Invalid search pattern: {err}"))
+}
+
+/// Byte offset `count` characters before `index`, or 0.
+fn chars_before(text: &str, index: usize, count: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .rev()
+        .nth(count.saturating_sub(1))
+        .map(|(offset, _)| offset)
+        .unwrap_or(0)
+}
+
+/// Byte offset `count` characters after `index`, or the end of `text`.
+fn chars_after(text: &str, index: usize, count: usize) -> usize {
+    text[index..]
+        .char_indices()
+        .nth(count)
+        .map(|(offset, _)| index + offset)
+        .unwrap_or(text.len())
+}
+
+fn transcript_snippet(content: &str, matches: &[(usize, usize)]) -> String {
+    let Some(&(first_start, first_end)) = matches.first() else {
+        return String::new();
+    };
+    let start = chars_before(content, first_start, TRANSCRIPT_SNIPPET_CONTEXT_CHARS);
+    let end = chars_after(content, first_end, TRANSCRIPT_SNIPPET_CONTEXT_CHARS);
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut cursor = start;
+    for &(match_start, match_end) in matches {
+        if match_start < cursor || match_end > end {
+            continue;
+        }
+        snippet.push_str(&content[cursor..match_start]);
+        snippet.push('[');
+        snippet.push_str(&content[match_start..match_end]);
+        snippet.push(']');
+        cursor = match_end;
+    }
+    snippet.push_str(&content[cursor..end]);
+    if end < content.len() {
+        snippet.push('…');
+    }
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn search_transcript_messages(
+    messages: &[CampMessage],
+    pattern: &Regex,
+    limit: usize,
+) -> Vec<TranscriptSearchHit> {
+    messages
+        .iter()
+        .filter_map(|message| {
+            let matches: Vec<(usize, usize)> = pattern
+                .find_iter(&message.content)
+                .filter(|found| !found.is_empty())
+                .map(|found| (found.start(), found.end()))
+                .collect();
+            (!matches.is_empty()).then(|| TranscriptSearchHit {
+                message_id: message.id.clone(),
+                role: message.role.clone(),
+                created_at: message.created_at,
+                snippet: transcript_snippet(&message.content, &matches),
+                match_count: matches.len(),
+            })
+        })
+        .take(limit)
+        .collect()
+}
+
+/// Finds messages in one camp's transcript, oldest first. Matching is
+/// case-insensitive; with `regex` set, `query` is a regular expression.
+#[tauri::command]
+pub fn camp_search_transcript(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    query: String,
+    regex: Option<bool>,
+    limit: Option<usize>,
+) -> Result<Vec<TranscriptSearchHit>, String> {
+    ensure_main_window(&window)?;
+    let Some(pattern) = transcript_search_pattern(&query, regex.unwrap_or(false))? else {
+        return Ok(Vec::new());
+    };
+    let camp_dir = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        resolve_existing_camp_dir(&camps_root, &camp_id)?
+    };
+    let messages = read_transcript(&camp_transcript_path(&camp_dir))?;
+    let limit = limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .clamp(1, SEARCH_MAX_LIMIT);
+    Ok(search_transcript_messages(&messages, &pattern, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{make_temp_dir, TempDir};
+
+    fn make_workspace() -> TempDir {
+        let dir = make_temp_dir("basecamp-search");
+        fs::create_dir_all(dir.join("camps/alpha-1a2b/context/notes"))
+            .expect("camp folders should be created");
+        fs::create_dir_all(dir.join("camps/alpha-1a2b/artifacts"))
+            .expect("artifacts folder should be created");
+        fs::write(
+            dir.join("camps/alpha-1a2b/camp.json"),
+            r#"{"schema_version":"0.3","id":"alpha-1a2b","name":"Alpha Launch","model":"openrouter/auto","provider_kind":"openrouter","model_id":"auto","tools_enabled":false,"is_team":false,"created_at":1700000000000,"updated_at":1700000000000}"#,
+        )
+        .expect("camp config should write");
+        fs::write(
+            dir.join("camps/alpha-1a2b/system_prompt.md"),
+            "You are a careful release manager.",
+        )
+        .expect("system prompt should write");
+        fs::write(
+            dir.join("camps/alpha-1a2b/transcript.jsonl"),
+            "{\"id\":\"m1\",\"role\":\"user\",\"content\":\"Draft the rollout checklist\",\"created_at\":1}\n",
+        )
+        .expect("transcript should write");
+        fs::write(
+            dir.join("camps/alpha-1a2b/artifacts/a1.md"),
+            "# Rollout Plan\n\nStage canaries before the global rollout.",
+        )
+        .expect("artifact should write");
+        fs::write(
+            dir.join("camps/alpha-1a2b/context/notes/vendors.txt"),
+            "Vendor contact: Zephyr Logistics",
+        )
+        .expect("context file should write");
+        fs::write(
+            dir.join("camps/alpha-1a2b/context/notes/logo.bin"),
+            [0xff, 0xfe, 0x00, 0x01],
+        )
+        .expect("binary context file should write");
+        dir
+    }
+
+    #[test]
+    fn workspace_search_should_index_all_sources_and_refresh_incrementally() {
+        let workspace = make_workspace();
+        let camps_root = workspace.join("camps");
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        create_search_tables(&connection).expect("search tables should be created");
+
+        let indexed = refresh_workspace_index(&connection, &workspace, &camps_root)
+            .expect("initial index should build");
+        assert_eq!(indexed, 6);
+        assert_eq!(
+            refresh_workspace_index(&connection, &workspace, &camps_root)
+                .expect("refresh should succeed"),
+            0
+        );
+
+        let hits = search_index(&connection, &workspace, "rollout", 10, None, None)
+            .expect("search should succeed");
+        let kinds: Vec<SearchHitKind> = hits.iter().map(|hit| hit.kind).collect();
+        assert!(kinds.contains(&SearchHitKind::Transcript));
+        assert!(kinds.contains(&SearchHitKind::Artifact));
+        assert!(hits.iter().all(|hit| hit.camp_id == "alpha-1a2b"));
+
+        let camp_hits = search_index(
+            &connection,
+            &workspace,
+            "rollout",
+            10,
+            None,
+            Some("alpha-1a2b"),
+        )
+        .expect("camp search should succeed");
+        assert_eq!(camp_hits.len(), hits.len());
+        assert!(
+            search_index(&connection, &workspace, "rollout", 10, None, Some("other"))
+                .expect("camp search should succeed")
+                .is_empty()
+        );
+
+        let context_hits = search_index(&connection, &workspace, "zephyr", 10, None, None)
+            .expect("search should succeed");
+        assert_eq!(context_hits.len(), 1);
+        assert_eq!(context_hits[0].path, "context/notes/vendors.txt");
+
+        fs::remove_file(workspace.join("camps/alpha-1a2b/context/notes/vendors.txt"))
+            .expect("context file should be removed");
+        assert_eq!(
+            refresh_workspace_index(&connection, &workspace, &camps_root)
+                .expect("refresh should succeed"),
+            1
+        );
+        assert!(
+            search_index(&connection, &workspace, "zephyr", 10, None, None)
+                .expect("search should succeed")
+                .is_empty()
+        );
+
+        let name_hits = search_index(
+            &connection,
+            &workspace,
+            "alpha \"launch",
+            10,
+            Some(&[SearchHitKind::CampName]),
+            None,
+        )
+        .expect("quoted input should not break the query");
+        assert_eq!(name_hits.len(), 1);
+        assert_eq!(name_hits[0].title, "Alpha Launch");
+    }
+
+    #[test]
+    fn transcript_search_should_match_case_insensitively_and_highlight_snippets() {
+        let message = |id: &str, content: &str| CampMessage::for_test(id, "assistant", content);
+        let messages = vec![
+            message(
+                "m1",
+                "Ship the Rollout on Friday.\nThen review the rollout notes.",
+            ),
+            message("m2", "Nothing to see here."),
+            message("m3", &format!("{}rollout", "x".repeat(100))),
+        ];
+
+        let pattern = transcript_search_pattern("ROLLOUT", false)
+            .expect("plain query")
+            .expect("non-empty query");
+        let hits = search_transcript_messages(&messages, &pattern, 10);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].message_id, "m1");
+        assert_eq!(hits[0].match_count, 2);
+        assert_eq!(
+            hits[0].snippet,
+            "Ship the [Rollout] on Friday. Then review the [rollout] notes."
+        );
+        assert!(hits[1].snippet.starts_with('…'));
+        assert!(hits[1].snippet.ends_with("[rollout]"));
+
+        let pattern = transcript_search_pattern(r"fri\w+", true)
+            .expect("regex query")
+            .expect("non-empty query");
+        assert_eq!(search_transcript_messages(&messages, &pattern, 10).len(), 1);
+        assert!(transcript_search_pattern("(", true).is_err());
+        assert!(transcript_search_pattern("(", false).is_ok());
+        assert!(transcript_search_pattern("  ", false)
+            .expect("blank query")
+            .is_none());
+    }
+}
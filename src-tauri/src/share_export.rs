@@ -0,0 +1,337 @@
+//! `camp_share_export`: saves a transcript as one self-contained HTML file for
+//! emailing or publishing. Markdown goes through the same sanitizer as the
+//! transcript view, the page has no scripts or external resources, and
+//! emails, phone numbers, API keys and any extra patterns are masked before
+//! anything is rendered. Artifacts follow the conversation as appendices.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::markdown::{escape_html, render_markdown_html};
+use crate::privacy::compile_scrub_patterns;
+use crate::scrubber::{scrub_texts, OutboundScrubSettings, ScrubAudit};
+use crate::workspace_manifest::format_utc;
+use crate::{
+    camp_transcript_path, ensure_camps_root, load_artifact, now_timestamp_ms,
+    parse_artifact_markdown, read_camp_config, read_transcript, resolve_existing_camp_dir,
+    slugify_camp_name, AppState, CampMessage,
+};
+
+const SHARE_STYLE: &str = "body{font-family:system-ui,sans-serif;line-height:1.55;max-width:48rem;\
+margin:2rem auto;padding:0 1rem;color:#1f2328}header p{color:#59636e;margin-top:0}\
+.message{border:1px solid #d1d9e0;border-radius:8px;padding:.25rem 1rem;margin:1rem 0}\
+.message.user{background:#f6f8fa}.role{font-size:.8rem;font-weight:600;color:#59636e;\
+text-transform:uppercase;margin:.5rem 0 0}details{margin:.5rem 0}pre{background:#f6f8fa;\
+padding:.75rem;overflow-x:auto;border-radius:6px}table{border-collapse:collapse}\
+th,td{border:1px solid #d1d9e0;padding:.25rem .5rem}img{max-width:100%}";
+
+#[derive(Debug, Deserialize)]
+pub struct CampShareExportPayload {
+    pub camp_id: String,
+    /// Keep tool calls and their results; by default only the conversation is
+    /// shared.
+    #[serde(default)]
+    pub include_tool_calls: bool,
+    /// Mask emails, phone numbers and API keys.
+    #[serde(default = "default_true")]
+    pub mask_sensitive: bool,
+    /// Extra regular expressions to replace with `[REDACTED]`.
+    #[serde(default)]
+    pub redact_patterns: Vec<String>,
+    /// Artifacts to append, in order. Defaults to the ones messages in the
+    /// transcript included.
+    pub artifact_ids: Option<Vec<String>>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShareExportResult {
+    pub path: String,
+    pub message_count: usize,
+    pub artifact_count: usize,
+    pub redactions: ScrubAudit,
+}
+
+struct ShareMessage {
+    role: String,
+    created_at: i64,
+    content: String,
+    /// `(tool name, arguments)` for assistant tool calls.
+    tool_calls: Vec<(String, String)>,
+    image_count: usize,
+}
+
+struct ShareArtifact {
+    title: String,
+    body: String,
+}
+
+struct ShareDocument {
+    camp_name: String,
+    exported_at: i64,
+    messages: Vec<ShareMessage>,
+    artifacts: Vec<ShareArtifact>,
+}
+
+/// Drops what shouldn't be shared: reasoning always, tool traffic unless asked
+/// for, and messages left with nothing to show.
+fn share_messages(transcript: Vec<CampMessage>, include_tool_calls: bool) -> Vec<ShareMessage> {
+    transcript
+        .into_iter()
+        .filter(|message| include_tool_calls || message.role != "tool")
+        .filter_map(|message| {
+            let tool_calls = if include_tool_calls {
+                message
+                    .tool_calls
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| (call.function.name, call.function.arguments))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let image_count = message.attachments.as_ref().map_or(0, Vec::len);
+            if message.content.trim().is_empty() && tool_calls.is_empty() && image_count == 0 {
+                return None;
+            }
+            Some(ShareMessage {
+                role: message.role,
+                created_at: message.created_at,
+                content: message.content,
+                tool_calls,
+                image_count,
+            })
+        })
+        .collect()
+}
+
+fn redact_document(document: &mut ShareDocument, settings: &OutboundScrubSettings) -> ScrubAudit {
+    let texts = document
+        .messages
+        .iter_mut()
+        .flat_map(|message| {
+            std::iter::once(&mut message.content).chain(
+                message
+                    .tool_calls
+                    .iter_mut()
+                    .map(|(_, arguments)| arguments),
+            )
+        })
+        .chain(
+            document
+                .artifacts
+                .iter_mut()
+                .flat_map(|artifact| [&mut artifact.title, &mut artifact.body]),
+        );
+    scrub_texts(texts, settings)
+}
+
+fn role_label(role: &str) -> &str {
+    match role {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "tool" => "Tool result",
+        "system" => "System",
+        other => other,
+    }
+}
+
+fn render_share_html(document: &ShareDocument) -> String {
+    let title = escape_html(&document.camp_name);
+    let mut body = format!(
+        "<header>\n<h1>{title}</h1>\n<p>Shared from Basecamp on {}</p>\n</header>\n<main>\n",
+        format_utc(document.exported_at)
+    );
+
+    for message in &document.messages {
+        body.push_str(&format!(
+            "<section class=\"message {}\">\n<p class=\"role\">{} · {}</p>\n",
+            escape_html(&message.role),
+            escape_html(role_label(&message.role)),
+            format_utc(message.created_at)
+        ));
+        if message.role == "tool" {
+            body.push_str(&format!(
+                "<details><summary>Output</summary><pre><code>{}</code></pre></details>\n",
+                escape_html(&message.content)
+            ));
+        } else if !message.content.trim().is_empty() {
+            body.push_str(&render_markdown_html(&message.content));
+        }
+        for (name, arguments) in &message.tool_calls {
+            body.push_str(&format!(
+                "<details><summary>Tool call: {}</summary><pre><code>{}</code></pre></details>\n",
+                escape_html(name),
+                escape_html(arguments)
+            ));
+        }
+        if message.image_count > 0 {
+            body.push_str(&format!(
+                "<p><em>{} image attachment(s) not included.</em></p>\n",
+                message.image_count
+            ));
+        }
+        body.push_str("</section>\n");
+    }
+    body.push_str("</main>\n");
+
+    for (index, artifact) in document.artifacts.iter().enumerate() {
+        body.push_str(&format!(
+            "<section class=\"appendix\">\n<h2>Appendix {}: {}</h2>\n{}</section>\n",
+            index + 1,
+            escape_html(&artifact.title),
+            render_markdown_html(&artifact.body)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <meta http-equiv=\"Content-Security-Policy\" content=\"default-src 'none'; style-src 'unsafe-inline'; img-src data:\">\n\
+         <title>{title}</title>\n<style>{SHARE_STYLE}</style>\n</head>\n<body>\n{body}</body>\n</html>\n"
+    )
+}
+
+/// Asks where to save, then writes the shared transcript there. Returns
+/// `None` when the save dialog is cancelled.
+#[tauri::command]
+pub async fn camp_share_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: CampShareExportPayload,
+) -> CommandResult<Option<ShareExportResult>> {
+    compile_scrub_patterns(&payload.redact_patterns).map_err(BasecampError::validation)?;
+    let camp_dir = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        resolve_existing_camp_dir(&camps_root, &payload.camp_id)?
+    };
+
+    let config = read_camp_config(&camp_dir)?;
+    let transcript = read_transcript(&camp_transcript_path(&camp_dir))?;
+    let artifacts = match payload.artifact_ids {
+        Some(artifact_ids) => artifact_ids
+            .iter()
+            .map(|artifact_id| load_artifact(&camp_dir, artifact_id))
+            .collect::<Result<Vec<_>, String>>()?,
+        None => {
+            let mut ids: Vec<&String> = Vec::new();
+            for id in transcript
+                .iter()
+                .flat_map(|message| message.included_artifact_ids.iter().flatten())
+            {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+            // Artifacts deleted since the conversation are skipped.
+            ids.into_iter()
+                .filter_map(|artifact_id| load_artifact(&camp_dir, artifact_id).ok())
+                .collect()
+        }
+    };
+
+    let mut document = ShareDocument {
+        camp_name: config.name,
+        exported_at: now_timestamp_ms(),
+        messages: share_messages(transcript, payload.include_tool_calls),
+        artifacts: artifacts
+            .into_iter()
+            .map(|artifact| {
+                let (title, body) =
+                    parse_artifact_markdown(&artifact.body, &artifact.metadata.title);
+                ShareArtifact { title, body }
+            })
+            .collect(),
+    };
+    let redactions = redact_document(
+        &mut document,
+        &OutboundScrubSettings {
+            enabled: true,
+            mask_emails: payload.mask_sensitive,
+            mask_phone_numbers: payload.mask_sensitive,
+            mask_api_keys: payload.mask_sensitive,
+            custom_patterns: payload.redact_patterns,
+        },
+    );
+    let html = render_share_html(&document);
+
+    let (tx, mut rx) = tauri::async_runtime::channel::<Option<PathBuf>>(1);
+    app.dialog()
+        .file()
+        .set_title("Share Conversation")
+        .set_file_name(format!("{}.html", slugify_camp_name(&document.camp_name)))
+        .add_filter("HTML", &["html"])
+        .save_file(move |selected| {
+            let _ = tx.blocking_send(selected.and_then(|path| path.into_path().ok()));
+        });
+    let Some(destination) = rx.recv().await.flatten() else {
+        return Ok(None);
+    };
+    fs::write(&destination, html)?;
+    Ok(Some(ShareExportResult {
+        path: destination.to_string_lossy().into_owned(),
+        message_count: document.messages.len(),
+        artifact_count: document.artifacts.len(),
+        redactions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_share_html_should_drop_tool_traffic_and_redact_before_rendering() {
+        let mut document = ShareDocument {
+            camp_name: "Launch <plan>".to_string(),
+            exported_at: 0,
+            messages: share_messages(
+                vec![
+                    CampMessage::for_test("u1", "user", "Mail ada@example.com about **Q3**")
+                        .with_reasoning("private"),
+                    CampMessage::for_test("a1", "assistant", "").with_reasoning("private"),
+                    CampMessage::for_test("t1", "tool", "{\"files\":[]}").with_reasoning("private"),
+                    CampMessage::for_test(
+                        "a2",
+                        "assistant",
+                        "Done. <script>alert(1)</script> ticket ACME-42",
+                    )
+                    .with_reasoning("private"),
+                ],
+                false,
+            ),
+            artifacts: vec![ShareArtifact {
+                title: "Notes".to_string(),
+                body: "Key sk-abcdefghijklmnop1234".to_string(),
+            }],
+        };
+        let audit = redact_document(
+            &mut document,
+            &OutboundScrubSettings {
+                enabled: true,
+                custom_patterns: vec![r"ACME-\d+".to_string()],
+                ..OutboundScrubSettings::default()
+            },
+        );
+        let html = render_share_html(&document);
+
+        assert_eq!(document.messages.len(), 2);
+        assert_eq!(audit.total_masked, 3);
+        assert!(html.contains("<title>Launch &lt;plan&gt;</title>"));
+        assert!(html.contains("Mail [EMAIL] about <strong>Q3</strong>"));
+        assert!(html.contains("ticket [REDACTED]"));
+        assert!(html.contains("<h2>Appendix 1: Notes</h2>\n<p>Key [API_KEY]</p>"));
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("private"));
+        assert!(!html.contains("files"));
+    }
+}
@@ -0,0 +1,481 @@
+//! Text-to-speech for artifacts and messages. Audio is rendered by a local
+//! engine (Piper or Coqui) or an OpenAI-compatible speech endpoint and kept
+//! under the camp's artifacts dir, with an index linking each clip back to the
+//! text it was made from.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{State, Window};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::privacy;
+use crate::{
+    camp_artifacts_dir, camp_transcript_path, ensure_camps_root, ensure_main_window,
+    get_setting_value, load_artifact, now_timestamp_ms, read_json_file, read_transcript,
+    resolve_existing_camp_dir, set_setting_value, validate_identifier, write_file_atomic,
+    write_json_file, AppState,
+};
+
+const SETTING_TTS_ENGINE: &str = "tts_engine";
+const CAMP_AUDIO_DIR: &str = "audio";
+const CAMP_AUDIO_INDEX_FILE: &str = "index.json";
+const CAMP_AUDIO_SCHEMA_VERSION: &str = "0.1";
+const MAX_SPEECH_CHARS: usize = 20_000;
+const SPEECH_TIMEOUT: Duration = Duration::from_secs(300);
+const CODE_BLOCK_PLACEHOLDER: &str = "(code block omitted)";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TtsEngine {
+    /// `piper --model <voice.onnx> --output_file <clip.wav>`, text on stdin.
+    Piper { binary: String, model_path: String },
+    /// Coqui `tts --text <text> --out_path <clip.wav>`.
+    Coqui {
+        binary: String,
+        model_name: Option<String>,
+    },
+    /// `POST {base_url}/audio/speech` on an OpenAI-compatible server such as a
+    /// local Kokoro or openedai-speech instance.
+    Http {
+        base_url: String,
+        model: String,
+        voice: String,
+    },
+}
+
+impl TtsEngine {
+    fn label(&self) -> String {
+        match self {
+            Self::Piper { model_path, .. } => format!("piper:{model_path}"),
+            Self::Coqui { model_name, .. } => {
+                format!("coqui:{}", model_name.as_deref().unwrap_or("default"))
+            }
+            Self::Http { model, voice, .. } => format!("http:{model}/{voice}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpeechSource {
+    Artifact { id: String },
+    Message { id: String },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SynthesizeSpeechPayload {
+    pub camp_id: String,
+    pub source: SpeechSource,
+}
+
+/// One rendered clip. `text_sha256` identifies the exact text that was read,
+/// so a clip can be told apart from a later edit of its source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampAudioClip {
+    pub id: String,
+    pub filename: String,
+    pub source: SpeechSource,
+    pub text_sha256: String,
+    pub text_excerpt: String,
+    pub engine: String,
+    pub bytes: u64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SynthesizedSpeech {
+    #[serde(flatten)]
+    pub clip: CampAudioClip,
+    pub path: String,
+    /// True when an existing clip of the same text and engine was returned.
+    pub reused: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CampAudioIndex {
+    schema_version: String,
+    clips: Vec<CampAudioClip>,
+}
+
+fn camp_audio_dir(camp_dir: &Path) -> PathBuf {
+    camp_artifacts_dir(camp_dir).join(CAMP_AUDIO_DIR)
+}
+
+fn read_audio_index(camp_dir: &Path) -> Result<CampAudioIndex, String> {
+    let path = camp_audio_dir(camp_dir).join(CAMP_AUDIO_INDEX_FILE);
+    if !path.exists() {
+        return Ok(CampAudioIndex {
+            schema_version: CAMP_AUDIO_SCHEMA_VERSION.to_string(),
+            clips: Vec::new(),
+        });
+    }
+
+    read_json_file(&path)
+}
+
+fn load_tts_engine(connection: &Connection) -> Result<Option<TtsEngine>, String> {
+    let raw = get_setting_value(connection, SETTING_TTS_ENGINE)
+        .map_err(|err| format!("Unable to load TTS engine: {err}"))?;
+    Ok(raw
+        .filter(|raw| !raw.trim().is_empty())
+        .and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+fn validate_tts_engine(engine: TtsEngine) -> Result<TtsEngine, String> {
+    let required = |value: &str, field: &str| -> Result<String, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(format!("{field} is required for this TTS engine."));
+        }
+        Ok(value.to_string())
+    };
+    Ok(match engine {
+        TtsEngine::Piper { binary, model_path } => TtsEngine::Piper {
+            binary: required(&binary, "binary")?,
+            model_path: required(&model_path, "model_path")?,
+        },
+        TtsEngine::Coqui { binary, model_name } => TtsEngine::Coqui {
+            binary: required(&binary, "binary")?,
+            model_name: model_name
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty()),
+        },
+        TtsEngine::Http {
+            base_url,
+            model,
+            voice,
+        } => {
+            let base_url = required(&base_url, "base_url")?;
+            if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+                return Err("base_url must start with http:// or https://.".to_string());
+            }
+            TtsEngine::Http {
+                base_url: base_url.trim_end_matches('/').to_string(),
+                model: required(&model, "model")?,
+                voice: required(&voice, "voice")?,
+            }
+        }
+    })
+}
+
+/// Prepares text for reading aloud: fenced code is replaced with a short
+/// placeholder and markdown emphasis and heading markers are dropped.
+fn speakable_text(raw: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+    for line in raw.lines() {
+        if line.trim_start().starts_with("```") {
+            if !in_code {
+                lines.push(CODE_BLOCK_PLACEHOLDER.to_string());
+            }
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let line = line.trim_start().trim_start_matches('#').trim_start();
+        lines.push(line.replace(['*', '`'], ""));
+    }
+    lines.join("\n").trim().to_string()
+}
+
+fn text_sha256(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+fn source_text(camp_dir: &Path, source: &SpeechSource) -> Result<String, String> {
+    match source {
+        SpeechSource::Artifact { id } => Ok(load_artifact(camp_dir, id)?.body),
+        SpeechSource::Message { id } => {
+            let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+            transcript
+                .into_iter()
+                .find(|message| &message.id == id)
+                .filter(|message| matches!(message.role.as_str(), "user" | "assistant"))
+                .map(|message| message.content)
+                .ok_or_else(|| format!("Message {id} was not found or cannot be read aloud."))
+        }
+    }
+}
+
+async fn run_engine_process(mut command: Command, stdin: Option<&str>) -> Result<(), String> {
+    command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("Unable to start TTS engine: {err}"))?;
+    if let (Some(text), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(text.as_bytes())
+            .await
+            .map_err(|err| format!("Unable to send text to TTS engine: {err}"))?;
+    }
+    let output = tokio::time::timeout(SPEECH_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| "TTS engine timed out.".to_string())?
+        .map_err(|err| format!("TTS engine failed: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "TTS engine exited with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+async fn render_speech(
+    client: &reqwest::Client,
+    engine: &TtsEngine,
+    text: &str,
+    output_path: &Path,
+) -> Result<(), String> {
+    match engine {
+        TtsEngine::Piper { binary, model_path } => {
+            let mut command = Command::new(binary);
+            command
+                .arg("--model")
+                .arg(model_path)
+                .arg("--output_file")
+                .arg(output_path);
+            run_engine_process(command, Some(text)).await
+        }
+        TtsEngine::Coqui { binary, model_name } => {
+            let mut command = Command::new(binary);
+            command
+                .arg("--text")
+                .arg(text)
+                .arg("--out_path")
+                .arg(output_path);
+            if let Some(model_name) = model_name {
+                command.arg("--model_name").arg(model_name);
+            }
+            run_engine_process(command, None).await
+        }
+        TtsEngine::Http {
+            base_url,
+            model,
+            voice,
+        } => {
+            let response = client
+                .post(format!("{base_url}/audio/speech"))
+                .timeout(SPEECH_TIMEOUT)
+                .json(&serde_json::json!({
+                    "model": model,
+                    "voice": voice,
+                    "input": text,
+                    "response_format": "wav",
+                }))
+                .send()
+                .await
+                .map_err(|err| format!("Speech request failed: {err}"))?;
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!(
+                    "Speech endpoint returned {status}: {}",
+                    body.trim()
+                ));
+            }
+            let audio = response
+                .bytes()
+                .await
+                .map_err(|err| format!("Unable to read speech audio: {err}"))?;
+            write_file_atomic(output_path, &audio)
+        }
+    }
+}
+
+/// Reads an artifact or transcript message aloud with the configured TTS
+/// engine and saves the clip under `artifacts/audio/`. Asking again for text
+/// that already has a clip from the same engine returns that clip.
+#[tauri::command]
+pub async fn synthesize_speech(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: SynthesizeSpeechPayload,
+) -> Result<SynthesizedSpeech, String> {
+    ensure_main_window(&window)?;
+    let source = match payload.source {
+        SpeechSource::Artifact { id } => SpeechSource::Artifact {
+            id: validate_identifier(&id, "artifact_id")?,
+        },
+        SpeechSource::Message { id } => SpeechSource::Message {
+            id: validate_identifier(&id, "message_id")?,
+        },
+    };
+    let (camp_dir, engine) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let engine = load_tts_engine(&connection)?
+            .ok_or_else(|| "No TTS engine is configured. Set one in Settings.".to_string())?;
+        // The HTTP engine sends the text to a server, which a local-only camp
+        // forbids wherever that server runs.
+        if matches!(engine, TtsEngine::Http { .. })
+            && privacy::camp_local_only(&connection, &payload.camp_id)?
+        {
+            return Err("This camp is local-only, so its text cannot be sent to a speech server. Use Piper or Coqui, or turn off local-only for the camp.".to_string());
+        }
+        (camp_dir, engine)
+    };
+
+    let text = speakable_text(&source_text(&camp_dir, &source)?);
+    if text.is_empty() {
+        return Err("The source has no text to read aloud.".to_string());
+    }
+    if text.chars().count() > MAX_SPEECH_CHARS {
+        return Err(format!(
+            "Text is too long to read aloud (limit {MAX_SPEECH_CHARS} characters)."
+        ));
+    }
+    let text_hash = text_sha256(&text);
+    let engine_label = engine.label();
+    let audio_dir = camp_audio_dir(&camp_dir);
+
+    let mut index = read_audio_index(&camp_dir)?;
+    if let Some(existing) = index.clips.iter().find(|clip| {
+        clip.source == source && clip.text_sha256 == text_hash && clip.engine == engine_label
+    }) {
+        let path = audio_dir.join(&existing.filename);
+        if path.exists() {
+            return Ok(SynthesizedSpeech {
+                clip: existing.clone(),
+                path: path.to_string_lossy().to_string(),
+                reused: true,
+            });
+        }
+    }
+
+    fs::create_dir_all(&audio_dir)
+        .map_err(|err| format!("Unable to create {}: {err}", audio_dir.to_string_lossy()))?;
+    let id = uuid::Uuid::new_v4().to_string();
+    let filename = format!("{id}.wav");
+    let path = audio_dir.join(&filename);
+    if let Err(err) = render_speech(&state.provider_client, &engine, &text, &path).await {
+        let _ = fs::remove_file(&path);
+        return Err(err);
+    }
+    let bytes = fs::metadata(&path)
+        .map_err(|err| format!("TTS engine produced no audio: {err}"))?
+        .len();
+
+    let clip = CampAudioClip {
+        id,
+        filename,
+        source,
+        text_sha256: text_hash,
+        text_excerpt: text.chars().take(120).collect(),
+        engine: engine_label,
+        bytes,
+        created_at: now_timestamp_ms(),
+    };
+    index
+        .clips
+        .retain(|existing| audio_dir.join(&existing.filename).exists());
+    index.clips.push(clip.clone());
+    write_json_file(&audio_dir.join(CAMP_AUDIO_INDEX_FILE), &index)?;
+
+    Ok(SynthesizedSpeech {
+        clip,
+        path: path.to_string_lossy().to_string(),
+        reused: false,
+    })
+}
+
+#[tauri::command]
+pub fn list_speech_clips(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> Result<Vec<CampAudioClip>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    Ok(read_audio_index(&camp_dir)?.clips)
+}
+
+#[tauri::command]
+pub fn get_tts_engine(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<Option<TtsEngine>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_tts_engine(&connection)
+}
+
+#[tauri::command]
+pub fn set_tts_engine(
+    window: Window,
+    state: State<'_, AppState>,
+    engine: Option<TtsEngine>,
+) -> Result<Option<TtsEngine>, String> {
+    ensure_main_window(&window)?;
+    let engine = engine.map(validate_tts_engine).transpose()?;
+    let serialized = match &engine {
+        Some(engine) => serde_json::to_string(engine)
+            .map_err(|err| format!("Unable to serialize TTS engine: {err}"))?,
+        None => String::new(),
+    };
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(&connection, SETTING_TTS_ENGINE, &serialized)
+        .map_err(|err| format!("Unable to save TTS engine: {err}"))?;
+    Ok(engine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speakable_text_should_skip_code_and_markdown_markers() {
+        let text = speakable_text("## Setup\nRun **this**:\n```sh\nnpm install\n```\nDone.");
+        assert_eq!(text, "Setup\nRun this:\n(code block omitted)\nDone.");
+        assert_eq!(text_sha256(&text).len(), 64);
+
+        let engine = validate_tts_engine(TtsEngine::Http {
+            base_url: "http://localhost:8880/v1/".to_string(),
+            model: "kokoro".to_string(),
+            voice: "af_bella".to_string(),
+        })
+        .expect("http engine is valid");
+        assert_eq!(engine.label(), "http:kokoro/af_bella");
+        assert!(validate_tts_engine(TtsEngine::Piper {
+            binary: "piper".to_string(),
+            model_path: " ".to_string(),
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,306 @@
+//! App startup. The database is opened, migrated and checked on a background
+//! task while the splash is up; each step is reported on
+//! `startup://progress` and the main window is shown once `AppState` exists.
+
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::{
+    db_encryption, demo_mode, digest, get_setting_value, migrations, new_app_state,
+    now_timestamp_ms, quick_capture, recovery, set_setting_value, tray, AppState, DB_FILE_NAME,
+};
+
+pub const STARTUP_PROGRESS_EVENT: &str = "startup://progress";
+const SETTING_SPLASH_DURATION_MS: &str = "splash_duration_ms";
+const DEFAULT_SPLASH_DURATION_MS: u64 = 1_500;
+const MAX_SPLASH_DURATION_MS: u64 = 10_000;
+const SPLASH_WINDOW_LABEL: &str = "splashscreen";
+const MAIN_WINDOW_LABEL: &str = "main";
+
+const INIT_TOTAL_STEPS: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InitStage {
+    Pending,
+    OpeningDatabase,
+    Migrating,
+    CheckingIntegrity,
+    ScanningWorkspace,
+    Ready,
+    Failed,
+}
+
+impl InitStage {
+    fn step(self) -> u8 {
+        match self {
+            Self::Pending => 0,
+            Self::OpeningDatabase => 1,
+            Self::Migrating => 2,
+            Self::CheckingIntegrity => 3,
+            Self::ScanningWorkspace | Self::Failed => 4,
+            Self::Ready => INIT_TOTAL_STEPS,
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            Self::Pending => "Starting Basecamp…",
+            Self::OpeningDatabase => "Opening database…",
+            Self::Migrating => "Upgrading database…",
+            Self::CheckingIntegrity => "Checking database integrity…",
+            Self::ScanningWorkspace => "Checking workspace…",
+            Self::Ready => "Ready.",
+            Self::Failed => "Basecamp could not start.",
+        }
+    }
+}
+
+/// Payload of `startup://progress` and the result of `get_init_status`.
+#[derive(Debug, Clone, Serialize)]
+pub struct InitStatus {
+    pub stage: InitStage,
+    pub message: String,
+    pub step: u8,
+    pub total_steps: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub started_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>,
+}
+
+impl InitStatus {
+    fn at(stage: InitStage, started_at: i64) -> Self {
+        Self {
+            stage,
+            message: stage.message().to_string(),
+            step: stage.step(),
+            total_steps: INIT_TOTAL_STEPS,
+            error: None,
+            started_at,
+            finished_at: None,
+        }
+    }
+}
+
+/// Managed from the first moment of setup, unlike `AppState`, so the UI can
+/// ask how far startup got.
+pub struct InitState(Mutex<InitStatus>);
+
+impl Default for InitState {
+    fn default() -> Self {
+        Self(Mutex::new(InitStatus::at(
+            InitStage::Pending,
+            now_timestamp_ms(),
+        )))
+    }
+}
+
+fn report(app: &AppHandle, stage: InitStage, error: Option<String>) {
+    let Some(state) = app.try_state::<InitState>() else {
+        return;
+    };
+    let status = {
+        let Ok(mut status) = state.0.lock() else {
+            return;
+        };
+        let mut next = InitStatus::at(stage, status.started_at);
+        if matches!(stage, InitStage::Ready | InitStage::Failed) {
+            next.finished_at = Some(now_timestamp_ms());
+        }
+        next.error = error;
+        *status = next.clone();
+        next
+    };
+    let _ = app.emit(STARTUP_PROGRESS_EVENT, status);
+}
+
+/// `PRAGMA quick_check` returns the single row "ok" for a sound database and
+/// one row per problem otherwise.
+fn check_integrity(connection: &Connection) -> Result<(), String> {
+    let mut statement = connection
+        .prepare("PRAGMA quick_check")
+        .map_err(|err| format!("Unable to check database integrity: {err}"))?;
+    let problems: Vec<String> = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|err| format!("Unable to check database integrity: {err}"))?
+        .filter_map(Result::ok)
+        .filter(|row| row != "ok")
+        .collect();
+    if problems.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "Database integrity check failed: {}",
+        problems.join("; ")
+    ))
+}
+
+/// Runs every startup step and manages `AppState`. Returns the configured
+/// splash duration.
+fn initialize(app: &AppHandle) -> Result<Duration, String> {
+    report(app, InitStage::OpeningDatabase, None);
+    let app_data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|err| format!("Unable to resolve app data folder: {err}"))?;
+    fs::create_dir_all(&app_data_dir)
+        .map_err(|err| format!("Unable to create app data folder: {err}"))?;
+    let connection = db_encryption::open_database(&app_data_dir.join(DB_FILE_NAME))
+        .map_err(|err| format!("Unable to open database: {err}"))?;
+
+    report(app, InitStage::Migrating, None);
+    migrations::run_migrations(&connection, false)
+        .map_err(|err| format!("Database migration failed: {err}"))?;
+
+    report(app, InitStage::CheckingIntegrity, None);
+    check_integrity(&connection)?;
+
+    report(app, InitStage::ScanningWorkspace, None);
+    let startup_diagnostics = recovery::run_startup_recovery(&connection);
+    let read_only = demo_mode::load_read_only_mode(&connection);
+    let splash_duration = load_splash_duration(&connection).unwrap_or_default();
+    let capture_shortcut = quick_capture::load_quick_capture_settings(&connection)
+        .ok()
+        .and_then(|settings| settings.shortcut);
+    let keep_running_in_tray = tray::load_keep_running(&connection);
+    app.manage(new_app_state(connection, startup_diagnostics, read_only));
+    tray::set_keep_running(app, keep_running_in_tray);
+    tray::setup(app);
+    // A shortcut taken by another app shouldn't stop Basecamp from starting.
+    let _ = quick_capture::replace_shortcut(app, None, capture_shortcut.as_deref());
+    digest::spawn_scheduler(app.clone());
+
+    report(app, InitStage::Ready, None);
+    Ok(splash_duration)
+}
+
+/// Starts initialization off the setup thread. The main window is shown when
+/// it finishes, and also when it fails so the UI can show the error.
+pub(crate) fn spawn_initialization(app: AppHandle) {
+    let started = Instant::now();
+    tauri::async_runtime::spawn(async move {
+        let worker = app.clone();
+        let outcome = tauri::async_runtime::spawn_blocking(move || initialize(&worker)).await;
+        let splash_duration = match outcome {
+            Ok(Ok(duration)) => duration,
+            Ok(Err(err)) => {
+                report(&app, InitStage::Failed, Some(err));
+                Duration::ZERO
+            }
+            Err(err) => {
+                report(
+                    &app,
+                    InitStage::Failed,
+                    Some(format!("Startup task stopped: {err}")),
+                );
+                Duration::ZERO
+            }
+        };
+        reveal_main_window(&app, splash_duration, started.elapsed());
+    });
+}
+
+/// Shortest time the splash stays up, counted from launch. Zero shows the
+/// main window as soon as initialization finishes.
+fn load_splash_duration(connection: &Connection) -> Result<Duration, String> {
+    let value = get_setting_value(connection, SETTING_SPLASH_DURATION_MS)
+        .map_err(|err| format!("Failed to read splash_duration_ms: {err}"))?;
+    Ok(Duration::from_millis(
+        value
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_SPLASH_DURATION_MS)
+            .min(MAX_SPLASH_DURATION_MS),
+    ))
+}
+
+/// How much longer the splash should stay up once initialization has taken
+/// `elapsed`. Slow startups never wait on top of the time already spent.
+fn remaining_splash(minimum: Duration, elapsed: Duration) -> Duration {
+    minimum.saturating_sub(elapsed)
+}
+
+/// Closes the splash and shows the main window. Called once initialization has
+/// finished; waits out whatever is left of the configured splash duration.
+fn reveal_main_window(app: &AppHandle, minimum: Duration, elapsed: Duration) {
+    let app = app.clone();
+    let hold = remaining_splash(minimum, elapsed);
+    tauri::async_runtime::spawn(async move {
+        if !hold.is_zero() {
+            tokio::time::sleep(hold).await;
+        }
+        if let Some(splash) = app.get_webview_window(SPLASH_WINDOW_LABEL) {
+            let _ = splash.close();
+        }
+        if let Some(main) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+            let _ = main.show();
+            let _ = main.set_focus();
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_init_status(state: State<'_, InitState>) -> Result<InitStatus, String> {
+    let status = state
+        .0
+        .lock()
+        .map_err(|_| "Startup status lock error".to_string())?;
+    Ok(status.clone())
+}
+
+#[tauri::command]
+pub fn set_splash_duration_ms(state: State<'_, AppState>, value: u64) -> Result<u64, String> {
+    let clamped = value.min(MAX_SPLASH_DURATION_MS);
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(
+        &connection,
+        SETTING_SPLASH_DURATION_MS,
+        &clamped.to_string(),
+    )
+    .map_err(|err| format!("Failed to save splash_duration_ms: {err}"))?;
+    Ok(clamped)
+}
+
+#[tauri::command]
+pub fn get_splash_duration_ms(state: State<'_, AppState>) -> Result<u64, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    Ok(load_splash_duration(&connection)?.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_splash_should_count_initialization_time() {
+        let minimum = Duration::from_millis(1_500);
+        assert_eq!(
+            remaining_splash(minimum, Duration::from_millis(400)),
+            Duration::from_millis(1_100)
+        );
+        assert!(remaining_splash(minimum, Duration::from_secs(3)).is_zero());
+        assert!(remaining_splash(Duration::ZERO, Duration::ZERO).is_zero());
+    }
+
+    #[test]
+    fn check_integrity_should_accept_a_sound_database() {
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        connection
+            .execute_batch("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT);")
+            .expect("schema applies");
+        assert!(check_integrity(&connection).is_ok());
+        assert_eq!(InitStage::Ready.step(), INIT_TOTAL_STEPS);
+    }
+}
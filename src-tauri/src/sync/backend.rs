@@ -0,0 +1,478 @@
+//! Where synced files live. A backend only stores opaque blobs by key
+//! (`manifest.json` and `objects/<sha256>`), so a folder, a WebDAV share and
+//! an S3 bucket all work the same way. Objects are content-addressed and
+//! never change; the manifest is only replaced with a conditional write, so
+//! two devices syncing at once cannot silently drop each other's changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Client, Method, Response, StatusCode};
+use sha2::{Digest, Sha256};
+
+use crate::write_file_atomic;
+
+/// A folder lock older than this was left by a crashed run and is broken.
+const FOLDER_LOCK_STALE_AFTER: Duration = Duration::from_secs(60);
+
+/// A stored blob and the version token `put_if_version` compares against.
+pub struct Versioned {
+    pub body: Vec<u8>,
+    pub version: String,
+}
+
+#[async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// `None` when nothing is stored under `key`.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String>;
+    /// Like `get`, with the version to pass to `put_if_version`.
+    async fn get_versioned(&self, key: &str) -> Result<Option<Versioned>, String>;
+    /// Writes `key` only if it still has version `expected`, or is still
+    /// absent when `expected` is `None`. `Ok(false)` means another device
+    /// wrote it first and nothing was written.
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        expected: Option<&str>,
+    ) -> Result<bool, String>;
+    /// Creates whatever `objects/` needs before the first upload.
+    async fn prepare(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+/// A folder, typically on a network drive or a USB stick both machines see.
+pub struct FolderBackend {
+    pub root: PathBuf,
+}
+
+/// An exclusive `<key>.lock` file beside the key, removed on drop.
+struct FolderLock(PathBuf);
+
+impl FolderLock {
+    /// `None` while another device holds a lock that is not yet stale.
+    fn acquire(path: &Path) -> Result<Option<Self>, String> {
+        let lock_path = path.with_extension("lock");
+        for _ in 0..2 {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+            {
+                Ok(_) => return Ok(Some(Self(lock_path))),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&lock_path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                        .is_some_and(|age| age > FOLDER_LOCK_STALE_AFTER);
+                    if !stale {
+                        return Ok(None);
+                    }
+                    let _ = fs::remove_file(&lock_path);
+                }
+                Err(err) => return Err(format!("Unable to lock the sync folder: {err}")),
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Drop for FolderLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[async_trait]
+impl SyncBackend for FolderBackend {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        match fs::read(self.root.join(key)) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(format!("Unable to read {key} from the sync folder: {err}")),
+        }
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Unable to create sync folder: {err}"))?;
+        }
+        write_file_atomic(&path, &body)
+    }
+
+    async fn get_versioned(&self, key: &str) -> Result<Option<Versioned>, String> {
+        Ok(self.get(key).await?.map(|body| Versioned {
+            version: sha256_hex(&body),
+            body,
+        }))
+    }
+
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        expected: Option<&str>,
+    ) -> Result<bool, String> {
+        let path = self.root.join(key);
+        let Some(_lock) = FolderLock::acquire(&path)? else {
+            return Ok(false);
+        };
+        let current = self.get_versioned(key).await?;
+        if current.as_ref().map(|current| current.version.as_str()) != expected {
+            return Ok(false);
+        }
+        write_file_atomic(&path, &body)?;
+        Ok(true)
+    }
+}
+
+/// The `ETag` of a response, which both WebDAV and S3 use as the version.
+fn response_etag(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// `If-Match` for a known version, `If-None-Match: *` for a key that must
+/// still be absent.
+fn with_precondition(
+    request: reqwest::RequestBuilder,
+    expected: Option<&str>,
+) -> reqwest::RequestBuilder {
+    match expected {
+        Some(version) => request.header(IF_MATCH, version),
+        None => request.header(IF_NONE_MATCH, "*"),
+    }
+}
+
+pub struct WebdavBackend {
+    pub client: Client,
+    /// Collection URL without a trailing slash.
+    pub base_url: String,
+    pub username: String,
+    pub password: String,
+}
+
+impl WebdavBackend {
+    fn request(&self, method: Method, key: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}/{key}", self.base_url))
+            .basic_auth(&self.username, Some(&self.password))
+    }
+
+    /// The body and `ETag` stored under `key`, `None` when there is none.
+    async fn fetch(&self, key: &str) -> Result<Option<(Vec<u8>, Option<String>)>, String> {
+        let response = self
+            .request(Method::GET, key)
+            .send()
+            .await
+            .map_err(|err| format!("WebDAV request failed: {err}"))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV GET {key} returned {}.", response.status()));
+        }
+        let etag = response_etag(&response);
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|err| format!("Unable to read WebDAV response: {err}"))?;
+        Ok(Some((bytes.to_vec(), etag)))
+    }
+}
+
+#[async_trait]
+impl SyncBackend for WebdavBackend {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.fetch(key).await?.map(|(body, _)| body))
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        let response = self
+            .request(Method::PUT, key)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| format!("WebDAV request failed: {err}"))?;
+        if !response.status().is_success() {
+            return Err(format!("WebDAV PUT {key} returned {}.", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn get_versioned(&self, key: &str) -> Result<Option<Versioned>, String> {
+        match self.fetch(key).await? {
+            None => Ok(None),
+            Some((body, Some(version))) => Ok(Some(Versioned { body, version })),
+            Some((_, None)) => Err(
+                "The WebDAV server does not send ETags, so sync cannot detect changes from other devices."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        expected: Option<&str>,
+    ) -> Result<bool, String> {
+        let response = with_precondition(self.request(Method::PUT, key), expected)
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| format!("WebDAV request failed: {err}"))?;
+        if response.status() == StatusCode::PRECONDITION_FAILED {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(format!("WebDAV PUT {key} returned {}.", response.status()));
+        }
+        Ok(true)
+    }
+
+    async fn prepare(&self) -> Result<(), String> {
+        let mkcol = Method::from_bytes(b"MKCOL").expect("MKCOL is a valid method");
+        let response = self
+            .request(mkcol, "objects/")
+            .send()
+            .await
+            .map_err(|err| format!("WebDAV request failed: {err}"))?;
+        // 405 means the collection already exists.
+        if response.status().is_success() || response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(());
+        }
+        Err(format!(
+            "WebDAV MKCOL objects/ returned {}.",
+            response.status()
+        ))
+    }
+}
+
+/// Any S3-compatible store, addressed path-style and signed with SigV4.
+pub struct S3Backend {
+    pub client: Client,
+    /// Such as `https://s3.us-east-1.amazonaws.com`, without a trailing slash.
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    /// Key prefix inside the bucket, without slashes at either end.
+    pub prefix: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(data);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// SigV4 signing key for `date` (`YYYYMMDD`).
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> [u8; 32] {
+    let date_key = hmac_sha256(format!("AWS4{secret}").as_bytes(), date.as_bytes());
+    let region_key = hmac_sha256(&date_key, region.as_bytes());
+    let service_key = hmac_sha256(&region_key, service.as_bytes());
+    hmac_sha256(&service_key, b"aws4_request")
+}
+
+fn uri_encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+impl S3Backend {
+    fn object_path(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            format!("/{}/{key}", self.bucket)
+        } else {
+            format!("/{}/{}/{key}", self.bucket, self.prefix)
+        }
+    }
+
+    fn signed_request(
+        &self,
+        method: Method,
+        key: &str,
+        body: Vec<u8>,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let url = reqwest::Url::parse(&format!(
+            "{}{}",
+            self.endpoint,
+            uri_encode_path(&self.object_path(key))
+        ))
+        .map_err(|err| format!("Invalid S3 endpoint: {err}"))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err("S3 endpoint has no host.".to_string()),
+        };
+
+        let now = time::OffsetDateTime::now_utc();
+        let date = format!(
+            "{:04}{:02}{:02}",
+            now.year(),
+            u8::from(now.month()),
+            now.day()
+        );
+        let amz_date = format!(
+            "{date}T{:02}{:02}{:02}Z",
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        let payload_hash = sha256_hex(&body);
+        let canonical_request = format!(
+            "{method}\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}",
+            url.path()
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let signature = hex(&hmac_sha256(
+            &signing_key(&self.secret_access_key, &date, &self.region, "s3"),
+            string_to_sign.as_bytes(),
+        ));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+            self.access_key_id
+        );
+
+        Ok(self
+            .client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("authorization", authorization)
+            .body(body))
+    }
+
+    /// The body and `ETag` stored under `key`, `None` when there is none.
+    async fn fetch(&self, key: &str) -> Result<Option<(Vec<u8>, Option<String>)>, String> {
+        let response = self
+            .signed_request(Method::GET, key, Vec::new())?
+            .send()
+            .await
+            .map_err(|err| format!("S3 request failed: {err}"))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("S3 GET {key} returned {}.", response.status()));
+        }
+        let etag = response_etag(&response);
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|err| format!("Unable to read S3 response: {err}"))?;
+        Ok(Some((bytes.to_vec(), etag)))
+    }
+}
+
+#[async_trait]
+impl SyncBackend for S3Backend {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.fetch(key).await?.map(|(body, _)| body))
+    }
+
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+        let response = self
+            .signed_request(Method::PUT, key, body)?
+            .send()
+            .await
+            .map_err(|err| format!("S3 request failed: {err}"))?;
+        if !response.status().is_success() {
+            return Err(format!("S3 PUT {key} returned {}.", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn get_versioned(&self, key: &str) -> Result<Option<Versioned>, String> {
+        match self.fetch(key).await? {
+            None => Ok(None),
+            Some((body, Some(version))) => Ok(Some(Versioned { body, version })),
+            Some((_, None)) => Err(format!("S3 GET {key} returned no ETag.")),
+        }
+    }
+
+    async fn put_if_version(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        expected: Option<&str>,
+    ) -> Result<bool, String> {
+        let response = with_precondition(self.signed_request(Method::PUT, key, body)?, expected)
+            .send()
+            .await
+            .map_err(|err| format!("S3 request failed: {err}"))?;
+        // 409 is S3's answer to a conditional write racing another one.
+        if matches!(
+            response.status(),
+            StatusCode::PRECONDITION_FAILED | StatusCode::CONFLICT
+        ) {
+            return Ok(false);
+        }
+        if !response.status().is_success() {
+            return Err(format!("S3 PUT {key} returned {}.", response.status()));
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_key_should_match_the_published_sigv4_example() {
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+        assert_eq!(uri_encode_path("/bucket/a b+c"), "/bucket/a%20b%2Bc");
+    }
+}
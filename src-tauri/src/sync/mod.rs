@@ -0,0 +1,891 @@
+//! Optional workspace sync between Basecamp installs, with no hosted
+//! service: the user points each machine at the same folder, WebDAV share or
+//! S3 bucket. The remote holds `manifest.json` (path -> content hash) and
+//! content-addressed blobs under `objects/`. Each run compares local files,
+//! the remote manifest and the hashes agreed at the last sync: one-sided
+//! changes are copied across, and when both sides changed the newer write
+//! wins while the other version is kept as a conflicted copy beside it.
+
+mod backend;
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use keyring::{Entry, Error as KeyringError};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+use crate::{
+    ensure_camps_root, ensure_main_window, get_setting_value, now_timestamp_ms, set_setting_value,
+    write_file_atomic, AppState, KEYRING_SERVICE,
+};
+use backend::{sha256_hex, FolderBackend, S3Backend, SyncBackend, WebdavBackend};
+
+const SETTING_SYNC: &str = "sync_settings";
+const SETTING_SYNC_STATE: &str = "sync_state";
+const KEYRING_SYNC_SECRET_ACCOUNT: &str = "sync_secret";
+const MANIFEST_KEY: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+/// WebDAV sends the password with every request as basic auth.
+const WEBDAV_HTTPS_REQUIRED: &str =
+    "WebDAV sync needs an https:// URL; basic auth would send the password in the clear.";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncTarget {
+    Folder {
+        path: String,
+    },
+    Webdav {
+        url: String,
+        username: String,
+    },
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        #[serde(default)]
+        prefix: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncSettings {
+    /// `None` turns sync off.
+    pub target: Option<SyncTarget>,
+    /// Names this machine in the manifest and in conflicted copies.
+    #[serde(default)]
+    pub device_name: String,
+    pub last_synced_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ManifestEntry {
+    /// `None` marks a file deleted on some device.
+    hash: Option<String>,
+    modified_at: i64,
+    device: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncManifest {
+    version: u32,
+    /// Keyed by path relative to the camps folder, with `/` separators.
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+impl Default for SyncManifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            files: BTreeMap::new(),
+        }
+    }
+}
+
+/// Hashes both sides agreed on at the end of the last sync. Kept in the
+/// local database rather than the workspace so it is never synced itself.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// The target these hashes were agreed with; switching targets starts
+    /// from an empty base.
+    target: Option<SyncTarget>,
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone)]
+struct LocalFile {
+    hash: String,
+    modified_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SyncAction {
+    Upload(String),
+    Download(String),
+    DeleteLocal(String),
+    DeleteRemote(String),
+    /// Both sides changed. `local_wins` follows the later modification time.
+    Conflict {
+        path: String,
+        local_wins: bool,
+    },
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct SyncReport {
+    pub uploaded: usize,
+    pub downloaded: usize,
+    pub deleted_local: usize,
+    pub deleted_remote: usize,
+    /// Conflicted copies written this run, relative to the camps folder.
+    pub conflicts: Vec<String>,
+    /// Files edited locally while the run was in progress. They are left as
+    /// they are and compared again on the next run.
+    pub skipped: Vec<String>,
+    pub finished_at: i64,
+}
+
+/// Three-way comparison of local files, the remote manifest and the last
+/// agreed hashes.
+fn plan_sync(
+    local: &BTreeMap<String, LocalFile>,
+    remote: &BTreeMap<String, ManifestEntry>,
+    base: &BTreeMap<String, String>,
+) -> Vec<SyncAction> {
+    let paths: BTreeSet<&String> = local
+        .keys()
+        .chain(remote.keys())
+        .chain(base.keys())
+        .collect();
+
+    let mut actions = Vec::new();
+    for path in paths {
+        let local_hash = local.get(path).map(|file| &file.hash);
+        let remote_hash = remote.get(path).and_then(|entry| entry.hash.as_ref());
+        let base_hash = base.get(path);
+
+        let action = if local_hash == remote_hash {
+            continue;
+        } else if remote_hash == base_hash {
+            if local_hash.is_some() {
+                SyncAction::Upload(path.clone())
+            } else {
+                SyncAction::DeleteRemote(path.clone())
+            }
+        } else if local_hash == base_hash {
+            if remote_hash.is_some() {
+                SyncAction::Download(path.clone())
+            } else {
+                SyncAction::DeleteLocal(path.clone())
+            }
+        } else {
+            // Both changed. An edit beats a deletion on the other side.
+            match (local.get(path), remote.get(path)) {
+                (None, _) => SyncAction::Download(path.clone()),
+                (Some(_), None) => SyncAction::Upload(path.clone()),
+                (Some(_), Some(entry)) if entry.hash.is_none() => SyncAction::Upload(path.clone()),
+                (Some(file), Some(entry)) => SyncAction::Conflict {
+                    path: path.clone(),
+                    local_wins: file.modified_at >= entry.modified_at,
+                },
+            }
+        };
+        actions.push(action);
+    }
+    actions
+}
+
+/// `notes/plan.md` -> `notes/plan (laptop's conflicted copy 2024-05-01).md`,
+/// the Dropbox naming `detect_sync_conflicts` already recognizes.
+fn conflict_copy_path(path: &str, device: &str, date: &str) -> String {
+    let device: String = device
+        .chars()
+        .filter(|c| !matches!(c, '(' | ')' | '/' | '\\'))
+        .collect();
+    let device = if device.trim().is_empty() {
+        "another device"
+    } else {
+        device.trim()
+    };
+    let (dir, name) = match path.rfind('/') {
+        Some(index) => path.split_at(index + 1),
+        None => ("", path),
+    };
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => name.split_at(index),
+        _ => (name, ""),
+    };
+    format!("{dir}{stem} ({device}'s conflicted copy {date}){extension}")
+}
+
+/// Maps a manifest path onto the camps folder, refusing anything that could
+/// escape it or land in a hidden folder.
+fn local_path(camps_root: &Path, path: &str) -> Result<PathBuf, String> {
+    let mut resolved = camps_root.to_path_buf();
+    for component in path.split('/') {
+        if component.is_empty() || component.starts_with('.') || component.contains(['\\', ':']) {
+            return Err(format!("Refusing to sync unsafe path: {path}"));
+        }
+        resolved.push(component);
+    }
+    Ok(resolved)
+}
+
+fn modified_at_ms(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+/// Every file under the camps folder, skipping hidden entries such as each
+/// camp's `.trash`.
+fn scan_local(
+    dir: &Path,
+    prefix: &str,
+    files: &mut BTreeMap<String, LocalFile>,
+) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|err| format!("Unable to read folder: {err}"))?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if name.starts_with('.') {
+            continue;
+        }
+        let key = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}/{name}")
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            scan_local(&entry.path(), &key, files)?;
+        } else if metadata.is_file() {
+            let bytes = fs::read(entry.path())
+                .map_err(|err| format!("Unable to read {key} for sync: {err}"))?;
+            files.insert(
+                key,
+                LocalFile {
+                    hash: sha256_hex(&bytes),
+                    modified_at: modified_at_ms(&metadata),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+fn sync_secret_entry() -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, KEYRING_SYNC_SECRET_ACCOUNT)
+        .map_err(|err| format!("Keyring entry error: {err}"))
+}
+
+fn read_sync_secret() -> Result<Option<String>, String> {
+    match sync_secret_entry()?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(KeyringError::NoEntry) => Ok(None),
+        Err(err) => Err(format!("Unable to read sync secret: {err}")),
+    }
+}
+
+fn load_sync_settings(connection: &Connection) -> Result<SyncSettings, String> {
+    let raw = get_setting_value(connection, SETTING_SYNC)
+        .map_err(|err| format!("Failed to read sync settings: {err}"))?;
+    Ok(raw
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default())
+}
+
+fn save_json_setting<T: Serialize>(
+    connection: &Connection,
+    key: &str,
+    value: &T,
+) -> Result<(), String> {
+    let serialized =
+        serde_json::to_string(value).map_err(|err| format!("Unable to serialize {key}: {err}"))?;
+    set_setting_value(connection, key, &serialized)
+        .map_err(|err| format!("Failed to save {key}: {err}"))
+}
+
+fn build_backend(target: &SyncTarget) -> Result<Box<dyn SyncBackend>, String> {
+    let secret =
+        || read_sync_secret()?.ok_or_else(|| "Sync password or secret key is missing.".to_string());
+    Ok(match target {
+        SyncTarget::Folder { path } => Box::new(FolderBackend {
+            root: PathBuf::from(path),
+        }),
+        SyncTarget::Webdav { url, .. } if !url.starts_with("https://") => {
+            return Err(WEBDAV_HTTPS_REQUIRED.to_string());
+        }
+        SyncTarget::Webdav { url, username } => Box::new(WebdavBackend {
+            client: reqwest::Client::new(),
+            base_url: url.trim_end_matches('/').to_string(),
+            username: username.clone(),
+            password: secret()?,
+        }),
+        SyncTarget::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            prefix,
+        } => Box::new(S3Backend {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: bucket.clone(),
+            region: region.clone(),
+            prefix: prefix.trim_matches('/').to_string(),
+            access_key_id: access_key_id.clone(),
+            secret_access_key: secret()?,
+        }),
+    })
+}
+
+async fn fetch_object(backend: &dyn SyncBackend, hash: &str) -> Result<Vec<u8>, String> {
+    let bytes = backend
+        .get(&format!("objects/{hash}"))
+        .await?
+        .ok_or_else(|| format!("Sync object {hash} is missing from the remote."))?;
+    if sha256_hex(&bytes) != hash {
+        return Err(format!("Sync object {hash} is corrupt."));
+    }
+    Ok(bytes)
+}
+
+struct SyncRun<'a> {
+    backend: &'a dyn SyncBackend,
+    camps_root: &'a Path,
+    device: &'a str,
+    /// The local files `plan_sync` compared.
+    planned: &'a BTreeMap<String, LocalFile>,
+    manifest: SyncManifest,
+    stored_objects: HashSet<String>,
+    report: SyncReport,
+}
+
+impl SyncRun<'_> {
+    async fn upload(&mut self, path: &str) -> Result<(), String> {
+        let file_path = local_path(self.camps_root, path)?;
+        let bytes =
+            fs::read(&file_path).map_err(|err| format!("Unable to read {path} for sync: {err}"))?;
+        let metadata = fs::metadata(&file_path)
+            .map_err(|err| format!("Unable to read {path} for sync: {err}"))?;
+        let hash = sha256_hex(&bytes);
+        if self.stored_objects.insert(hash.clone()) {
+            self.backend.put(&format!("objects/{hash}"), bytes).await?;
+        }
+        self.manifest.files.insert(
+            path.to_string(),
+            ManifestEntry {
+                hash: Some(hash),
+                modified_at: modified_at_ms(&metadata),
+                device: self.device.to_string(),
+            },
+        );
+        self.report.uploaded += 1;
+        Ok(())
+    }
+
+    fn write_local(&mut self, path: &str, bytes: &[u8]) -> Result<(), String> {
+        let file_path = local_path(self.camps_root, path)?;
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| format!("Unable to create folder for {path}: {err}"))?;
+        }
+        write_file_atomic(&file_path, bytes)?;
+        self.report.downloaded += 1;
+        Ok(())
+    }
+
+    async fn download_to(&mut self, hash: &str, path: &str) -> Result<(), String> {
+        let bytes = fetch_object(self.backend, hash).await?;
+        self.write_local(path, &bytes)
+    }
+
+    /// Whether the local file still has the hash `plan_sync` saw. One edited
+    /// since is recorded as skipped rather than overwritten or deleted.
+    fn unchanged_since_plan(&mut self, path: &str) -> Result<bool, String> {
+        let current = match fs::read(local_path(self.camps_root, path)?) {
+            Ok(bytes) => Some(sha256_hex(&bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(format!("Unable to read {path} for sync: {err}")),
+        };
+        if current.as_ref() == self.planned.get(path).map(|file| &file.hash) {
+            return Ok(true);
+        }
+        self.report.skipped.push(path.to_string());
+        Ok(false)
+    }
+
+    fn remote_entry(&self, path: &str) -> Result<(String, String), String> {
+        self.manifest
+            .files
+            .get(path)
+            .and_then(|entry| Some((entry.hash.clone()?, entry.device.clone())))
+            .ok_or_else(|| format!("{path} is missing from the sync manifest."))
+    }
+
+    async fn apply(&mut self, action: SyncAction, date: &str) -> Result<(), String> {
+        match action {
+            SyncAction::Upload(path) => self.upload(&path).await,
+            SyncAction::Download(path) => {
+                let (hash, _) = self.remote_entry(&path)?;
+                let bytes = fetch_object(self.backend, &hash).await?;
+                if !self.unchanged_since_plan(&path)? {
+                    return Ok(());
+                }
+                self.write_local(&path, &bytes)
+            }
+            SyncAction::DeleteLocal(path) => {
+                if !self.unchanged_since_plan(&path)? {
+                    return Ok(());
+                }
+                match fs::remove_file(local_path(self.camps_root, &path)?) {
+                    Ok(()) => {}
+                    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(err) => return Err(format!("Unable to delete {path}: {err}")),
+                }
+                self.report.deleted_local += 1;
+                Ok(())
+            }
+            SyncAction::DeleteRemote(path) => {
+                self.manifest.files.insert(
+                    path,
+                    ManifestEntry {
+                        hash: None,
+                        modified_at: now_timestamp_ms(),
+                        device: self.device.to_string(),
+                    },
+                );
+                self.report.deleted_remote += 1;
+                Ok(())
+            }
+            SyncAction::Conflict { path, local_wins } => {
+                let (remote_hash, remote_device) = self.remote_entry(&path)?;
+                let copy = if local_wins {
+                    // Keep the remote version as a copy, then publish ours.
+                    let copy = conflict_copy_path(&path, &remote_device, date);
+                    self.download_to(&remote_hash, &copy).await?;
+                    self.upload(&path).await?;
+                    copy
+                } else {
+                    // Move ours aside, then take the remote version.
+                    let copy = conflict_copy_path(&path, self.device, date);
+                    fs::rename(
+                        local_path(self.camps_root, &path)?,
+                        local_path(self.camps_root, &copy)?,
+                    )
+                    .map_err(|err| format!("Unable to keep conflicted copy of {path}: {err}"))?;
+                    self.download_to(&remote_hash, &path).await?;
+                    copy
+                };
+                self.upload(&copy).await?;
+                self.report.conflicts.push(copy);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn today_utc() -> String {
+    let date = time::OffsetDateTime::now_utc().date();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+#[tauri::command]
+pub fn get_sync_settings(state: State<'_, AppState>) -> Result<SyncSettings, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_sync_settings(&connection)
+}
+
+/// Saves the sync target. `secret` is the WebDAV password or S3 secret
+/// access key; it goes to the keyring and is left unchanged when omitted.
+#[tauri::command]
+pub fn set_sync_settings(
+    state: State<'_, AppState>,
+    settings: SyncSettings,
+    secret: Option<String>,
+) -> Result<SyncSettings, String> {
+    let device_name = settings.device_name.trim().to_string();
+    if settings.target.is_some() && device_name.is_empty() {
+        return Err("Give this device a name before turning on sync.".to_string());
+    }
+    match &settings.target {
+        Some(SyncTarget::Folder { path }) if !Path::new(path.trim()).is_dir() => {
+            return Err("Sync folder does not exist.".to_string());
+        }
+        Some(SyncTarget::Webdav { url, .. }) if !url.starts_with("https://") => {
+            return Err(WEBDAV_HTTPS_REQUIRED.to_string());
+        }
+        Some(SyncTarget::Webdav { url, .. }) | Some(SyncTarget::S3 { endpoint: url, .. })
+            if !(url.starts_with("https://") || url.starts_with("http://")) =>
+        {
+            return Err("Sync URL must start with http:// or https://.".to_string());
+        }
+        _ => {}
+    }
+    if let Some(secret) = secret.filter(|secret| !secret.is_empty()) {
+        sync_secret_entry()?
+            .set_password(&secret)
+            .map_err(|err| format!("Unable to save sync secret: {err}"))?;
+    }
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let previous = load_sync_settings(&connection)?;
+    let settings = SyncSettings {
+        target: settings.target.map(|target| match target {
+            SyncTarget::Folder { path } => SyncTarget::Folder {
+                path: path.trim().to_string(),
+            },
+            other => other,
+        }),
+        device_name,
+        last_synced_at: previous.last_synced_at,
+    };
+    save_json_setting(&connection, SETTING_SYNC, &settings)?;
+    Ok(settings)
+}
+
+/// Compares the camps folder with `backend`, copies changes both ways and
+/// publishes the new manifest. Returns the report and the hashes both sides
+/// now agree on. The manifest is only replaced if no other device replaced it
+/// during the pass; otherwise the pass fails and can simply be run again,
+/// since uploaded objects are content-addressed and harmless on their own.
+async fn sync_pass(
+    backend: &dyn SyncBackend,
+    camps_root: &Path,
+    device: &str,
+    base: &BTreeMap<String, String>,
+) -> Result<(SyncReport, BTreeMap<String, String>), String> {
+    let (manifest, manifest_version) = match backend.get_versioned(MANIFEST_KEY).await? {
+        Some(stored) => (
+            serde_json::from_slice::<SyncManifest>(&stored.body)
+                .map_err(|err| format!("Remote sync manifest is unreadable: {err}"))?,
+            Some(stored.version),
+        ),
+        None => (SyncManifest::default(), None),
+    };
+    if manifest.version > MANIFEST_VERSION {
+        return Err("The remote was synced by a newer version of Basecamp.".to_string());
+    }
+
+    let mut local = BTreeMap::new();
+    scan_local(camps_root, "", &mut local)?;
+    let actions = plan_sync(&local, &manifest.files, base);
+
+    let mut run = SyncRun {
+        backend,
+        camps_root,
+        device,
+        planned: &local,
+        stored_objects: manifest
+            .files
+            .values()
+            .filter_map(|entry| entry.hash.clone())
+            .collect(),
+        manifest,
+        report: SyncReport::default(),
+    };
+    let date = today_utc();
+    for action in actions {
+        run.apply(action, &date).await?;
+    }
+
+    let manifest_bytes = serde_json::to_vec_pretty(&run.manifest)
+        .map_err(|err| format!("Unable to serialize sync manifest: {err}"))?;
+    if !backend
+        .put_if_version(MANIFEST_KEY, manifest_bytes, manifest_version.as_deref())
+        .await?
+    {
+        return Err(
+            "Another device synced at the same time. Sync again to include its changes."
+                .to_string(),
+        );
+    }
+
+    let mut agreed: BTreeMap<String, String> = run
+        .manifest
+        .files
+        .iter()
+        .filter_map(|(path, entry)| Some((path.clone(), entry.hash.clone()?)))
+        .collect();
+    // Skipped files keep their old base, so the next run sees both sides changed.
+    for path in &run.report.skipped {
+        match base.get(path) {
+            Some(hash) => agreed.insert(path.clone(), hash.clone()),
+            None => agreed.remove(path),
+        };
+    }
+    Ok((run.report, agreed))
+}
+
+/// Runs one sync pass against the configured target.
+#[tauri::command]
+pub async fn run_sync(window: Window, state: State<'_, AppState>) -> Result<SyncReport, String> {
+    ensure_main_window(&window)?;
+    let (settings, camps_root, state_base) = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let sync_state: SyncState = get_setting_value(&connection, SETTING_SYNC_STATE)
+            .ok()
+            .flatten()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        (
+            load_sync_settings(&connection)?,
+            ensure_camps_root(&connection)?,
+            sync_state,
+        )
+    };
+    let target = settings
+        .target
+        .clone()
+        .ok_or_else(|| "Sync is not set up.".to_string())?;
+    let base = if state_base.target.as_ref() == Some(&target) {
+        state_base.files
+    } else {
+        BTreeMap::new()
+    };
+
+    let backend = build_backend(&target)?;
+    backend.prepare().await?;
+    let (mut report, files) =
+        sync_pass(backend.as_ref(), &camps_root, &settings.device_name, &base).await?;
+    report.finished_at = now_timestamp_ms();
+    let agreed = SyncState {
+        target: Some(target),
+        files,
+    };
+
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    save_json_setting(&connection, SETTING_SYNC_STATE, &agreed)?;
+    save_json_setting(
+        &connection,
+        SETTING_SYNC,
+        &SyncSettings {
+            last_synced_at: Some(report.finished_at),
+            ..settings
+        },
+    )?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use backend::Versioned;
+
+    type Interference = Box<dyn FnOnce() + Send>;
+
+    /// A sync folder another device touches mid-pass: the first object read or
+    /// write runs `interfere`.
+    struct RacingFolder {
+        inner: FolderBackend,
+        interfere: Mutex<Option<Interference>>,
+    }
+
+    impl RacingFolder {
+        fn new(root: &Path, interfere: impl FnOnce() + Send + 'static) -> Self {
+            Self {
+                inner: FolderBackend {
+                    root: root.to_path_buf(),
+                },
+                interfere: Mutex::new(Some(Box::new(interfere))),
+            }
+        }
+
+        fn race(&self, key: &str) {
+            if key.starts_with("objects/") {
+                if let Some(interfere) = self.interfere.lock().expect("lock").take() {
+                    interfere();
+                }
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SyncBackend for RacingFolder {
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+            self.race(key);
+            self.inner.get(key).await
+        }
+
+        async fn put(&self, key: &str, body: Vec<u8>) -> Result<(), String> {
+            self.race(key);
+            self.inner.put(key, body).await
+        }
+
+        async fn get_versioned(&self, key: &str) -> Result<Option<Versioned>, String> {
+            self.inner.get_versioned(key).await
+        }
+
+        async fn put_if_version(
+            &self,
+            key: &str,
+            body: Vec<u8>,
+            expected: Option<&str>,
+        ) -> Result<bool, String> {
+            self.inner.put_if_version(key, body, expected).await
+        }
+    }
+
+    fn write(root: &Path, path: &str, contents: &str) {
+        let file = root.join(path);
+        fs::create_dir_all(file.parent().expect("file has a parent")).expect("folder is created");
+        fs::write(file, contents).expect("file is written");
+    }
+
+    fn local_file(hash: &str, modified_at: i64) -> LocalFile {
+        LocalFile {
+            hash: hash.to_string(),
+            modified_at,
+        }
+    }
+
+    fn remote_entry(hash: Option<&str>, modified_at: i64) -> ManifestEntry {
+        ManifestEntry {
+            hash: hash.map(str::to_string),
+            modified_at,
+            device: "desktop".to_string(),
+        }
+    }
+
+    #[test]
+    fn plan_sync_should_copy_one_sided_changes_and_flag_conflicts() {
+        let local = BTreeMap::from([
+            ("a/same.md".to_string(), local_file("h1", 10)),
+            ("a/edited_here.md".to_string(), local_file("h2-new", 10)),
+            ("a/both.md".to_string(), local_file("h3-local", 50)),
+            ("a/deleted_there.md".to_string(), local_file("h4", 10)),
+        ]);
+        let remote = BTreeMap::from([
+            ("a/same.md".to_string(), remote_entry(Some("h1"), 10)),
+            ("a/edited_here.md".to_string(), remote_entry(Some("h2"), 10)),
+            ("a/both.md".to_string(), remote_entry(Some("h3-remote"), 40)),
+            ("a/deleted_there.md".to_string(), remote_entry(None, 20)),
+            ("a/new_there.md".to_string(), remote_entry(Some("h5"), 20)),
+            ("a/deleted_here.md".to_string(), remote_entry(Some("h6"), 5)),
+        ]);
+        let base = BTreeMap::from([
+            ("a/same.md".to_string(), "h1".to_string()),
+            ("a/edited_here.md".to_string(), "h2".to_string()),
+            ("a/both.md".to_string(), "h3".to_string()),
+            ("a/deleted_there.md".to_string(), "h4".to_string()),
+            ("a/deleted_here.md".to_string(), "h6".to_string()),
+        ]);
+
+        assert_eq!(
+            plan_sync(&local, &remote, &base),
+            vec![
+                SyncAction::Conflict {
+                    path: "a/both.md".to_string(),
+                    local_wins: true,
+                },
+                SyncAction::DeleteRemote("a/deleted_here.md".to_string()),
+                SyncAction::DeleteLocal("a/deleted_there.md".to_string()),
+                SyncAction::Upload("a/edited_here.md".to_string()),
+                SyncAction::Download("a/new_there.md".to_string()),
+            ]
+        );
+        assert_eq!(
+            conflict_copy_path("a/both.md", "Work (Mac)", "2024-05-01"),
+            "a/both (Work Mac's conflicted copy 2024-05-01).md"
+        );
+        assert!(local_path(Path::new("/camps"), "a/../etc").is_err());
+    }
+
+    #[tokio::test]
+    async fn sync_pass_should_round_trip_through_a_folder_and_refuse_a_replaced_manifest() {
+        let remote = make_temp_dir("basecamp-sync-remote");
+        let laptop = make_temp_dir("basecamp-sync-laptop");
+        let desktop = make_temp_dir("basecamp-sync-desktop");
+        let folder = FolderBackend {
+            root: remote.to_path_buf(),
+        };
+
+        write(&laptop, "camp/notes.md", "from the laptop");
+        let (report, laptop_base) = sync_pass(&folder, &laptop, "laptop", &BTreeMap::new())
+            .await
+            .expect("laptop syncs");
+        assert_eq!(report.uploaded, 1);
+        let (report, _) = sync_pass(&folder, &desktop, "desktop", &BTreeMap::new())
+            .await
+            .expect("desktop syncs");
+        assert_eq!(report.downloaded, 1);
+        assert_eq!(
+            fs::read_to_string(desktop.join("camp/notes.md")).expect("file arrived"),
+            "from the laptop"
+        );
+
+        // The desktop publishes a manifest while the laptop is uploading.
+        write(&laptop, "camp/todo.md", "ship it");
+        let manifest_path = remote.join(MANIFEST_KEY);
+        let racing = RacingFolder::new(&remote, {
+            let manifest_path = manifest_path.clone();
+            move || fs::write(manifest_path, br#"{"version":1,"files":{}}"#).expect("raced")
+        });
+        let error = sync_pass(&racing, &laptop, "laptop", &laptop_base)
+            .await
+            .expect_err("a replaced manifest is not overwritten");
+        assert!(error.contains("Another device synced"));
+        assert_eq!(
+            fs::read(&manifest_path).expect("manifest exists"),
+            br#"{"version":1,"files":{}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn sync_pass_should_not_overwrite_a_file_edited_during_the_pass() {
+        let remote = make_temp_dir("basecamp-sync-remote");
+        let laptop = make_temp_dir("basecamp-sync-laptop");
+        let desktop = make_temp_dir("basecamp-sync-desktop");
+        let folder = FolderBackend {
+            root: remote.to_path_buf(),
+        };
+
+        write(&laptop, "camp/notes.md", "v1");
+        let (_, laptop_base) = sync_pass(&folder, &laptop, "laptop", &BTreeMap::new())
+            .await
+            .expect("laptop syncs");
+        let (_, desktop_base) = sync_pass(&folder, &desktop, "desktop", &BTreeMap::new())
+            .await
+            .expect("desktop syncs");
+        write(&laptop, "camp/notes.md", "v2");
+        sync_pass(&folder, &laptop, "laptop", &laptop_base)
+            .await
+            .expect("laptop publishes v2");
+
+        // The desktop user saves the file while v2 is being fetched.
+        let racing = RacingFolder::new(&remote, {
+            let desktop = desktop.to_path_buf();
+            move || write(&desktop, "camp/notes.md", "edited on the desktop")
+        });
+        let (report, agreed) = sync_pass(&racing, &desktop, "desktop", &desktop_base)
+            .await
+            .expect("desktop syncs");
+        assert_eq!(report.downloaded, 0);
+        assert_eq!(report.skipped, vec!["camp/notes.md"]);
+        assert_eq!(
+            fs::read_to_string(desktop.join("camp/notes.md")).expect("file kept"),
+            "edited on the desktop"
+        );
+        assert_eq!(agreed["camp/notes.md"], desktop_base["camp/notes.md"]);
+
+        // The next pass sees both sides changed and keeps both versions.
+        let (report, _) = sync_pass(&folder, &desktop, "desktop", &agreed)
+            .await
+            .expect("desktop syncs again");
+        assert_eq!(report.conflicts.len(), 1);
+    }
+}
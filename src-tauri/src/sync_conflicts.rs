@@ -0,0 +1,365 @@
+//! Guards for workspaces kept in synced folders (iCloud Drive, Dropbox,
+//! OneDrive, Syncthing). When two machines edit the same file those services
+//! keep both versions, saving one as a "conflicted copy" beside the original;
+//! for a transcript that silently forks the conversation. `detect_sync_conflicts`
+//! finds such copies, plus camps whose camp.json is older than their newest
+//! message, and `merge_sync_conflict` folds a transcript copy back in.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::{
+    camp_artifacts_dir, camp_config_path, camp_transcript_path, ensure_camps_root,
+    ensure_main_window, move_to_trash, read_camp_config, read_transcript,
+    resolve_existing_camp_dir, touch_camp_updated_at, write_transcript, AppState, CampMessage,
+    CampTrashKind, CAMP_ARTIFACTS_DIR, CAMP_ARTIFACTS_INDEX_FILE, CAMP_CONFIG_FILE,
+    CAMP_MEMORY_FILE, CAMP_SYSTEM_PROMPT_FILE, CAMP_TRANSCRIPT_FILE,
+};
+
+/// Clock skew allowed between camp.json and the newest message before the
+/// config counts as overwritten by an older copy.
+const STALE_CONFIG_TOLERANCE_MS: i64 = 60_000;
+
+/// Files Basecamp writes at a camp's top level. Only these are matched
+/// against the looser iCloud and OneDrive naming schemes.
+const CAMP_ROOT_FILES: &[&str] = &[
+    CAMP_CONFIG_FILE,
+    CAMP_TRANSCRIPT_FILE,
+    CAMP_MEMORY_FILE,
+    CAMP_SYSTEM_PROMPT_FILE,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictKind {
+    /// A sync service's copy of a file, saved beside the original.
+    ConflictedCopy,
+    /// camp.json was last written before the newest transcript message.
+    StaleConfig,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub camp_id: String,
+    pub camp_name: String,
+    pub kind: SyncConflictKind,
+    /// Relative to the camp folder, with `/` separators.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_path: Option<String>,
+    pub detail: String,
+    /// Whether `merge_sync_conflict` can resolve it.
+    pub mergeable: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncMergeReport {
+    pub original_path: String,
+    /// Messages in the merged transcript.
+    pub merged_count: usize,
+    /// Messages only the conflicted copy had.
+    pub added_count: usize,
+    /// Messages in the copy that were already in the original.
+    pub duplicate_count: usize,
+    /// `false` for a preview; nothing was written.
+    pub applied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_entry_id: Option<String>,
+}
+
+struct ConflictPatterns {
+    dropbox: Regex,
+    syncthing: Regex,
+    icloud: Regex,
+    onedrive: Regex,
+}
+
+fn conflict_patterns() -> &'static ConflictPatterns {
+    static PATTERNS: OnceLock<ConflictPatterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let compile =
+            |pattern: &str| Regex::new(pattern).expect("sync conflict pattern should compile");
+        ConflictPatterns {
+            // `transcript (Sam's conflicted copy 2024-05-01).jsonl`
+            dropbox: compile(r"(?i)^(.+?) \([^()]*conflicted copy[^()]*\)(\.[^.]+)?$"),
+            // `transcript.sync-conflict-20240501-101500-ABCDEF1.jsonl`
+            syncthing: compile(r"^(.+?)\.sync-conflict-\d{8}-\d{6}(?:-[A-Z0-9]+)?(\.[^.]+)?$"),
+            // `transcript 2.jsonl`
+            icloud: compile(r"^(.+?) \d+(\.[^.]+)?$"),
+            // `transcript-DESKTOP-4F2K9.jsonl`
+            onedrive: compile(r"^(.+?)-[A-Z0-9][A-Z0-9-]*(\.[^.]+)?$"),
+        }
+    })
+}
+
+/// The file a sync service's conflicted copy belongs to, or `None` when
+/// `file_name` doesn't look like one. `known_files` limits the looser iCloud
+/// and OneDrive patterns, which also match ordinary names.
+fn conflict_original_name(file_name: &str, known_files: &[&str]) -> Option<String> {
+    let patterns = conflict_patterns();
+    let original = |captures: regex::Captures| {
+        format!(
+            "{}{}",
+            &captures[1],
+            captures.get(2).map_or("", |ext| ext.as_str())
+        )
+    };
+    if let Some(captures) = patterns
+        .dropbox
+        .captures(file_name)
+        .or_else(|| patterns.syncthing.captures(file_name))
+    {
+        return Some(original(captures));
+    }
+    [&patterns.icloud, &patterns.onedrive]
+        .into_iter()
+        .filter_map(|pattern| pattern.captures(file_name).map(original))
+        .find(|name| known_files.contains(&name.as_str()))
+}
+
+fn conflicts_in_dir(
+    dir: &Path,
+    prefix: &str,
+    known_files: &[&str],
+    found: &mut Vec<(String, String)>,
+) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.path().is_file() {
+            continue;
+        }
+        let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if let Some(original) = conflict_original_name(&file_name, known_files) {
+            found.push((
+                format!("{prefix}{file_name}"),
+                format!("{prefix}{original}"),
+            ));
+        }
+    }
+}
+
+fn camp_sync_conflicts(camp_dir: &Path) -> Result<Vec<SyncConflict>, String> {
+    let config = read_camp_config(camp_dir)?;
+    let mut copies = Vec::new();
+    conflicts_in_dir(camp_dir, "", CAMP_ROOT_FILES, &mut copies);
+    conflicts_in_dir(
+        &camp_artifacts_dir(camp_dir),
+        &format!("{CAMP_ARTIFACTS_DIR}/"),
+        &[CAMP_ARTIFACTS_INDEX_FILE],
+        &mut copies,
+    );
+    copies.sort();
+
+    let mut conflicts: Vec<SyncConflict> = copies
+        .into_iter()
+        .map(|(path, original_path)| {
+            let mergeable = original_path == CAMP_TRANSCRIPT_FILE;
+            let detail = if mergeable {
+                "A synced copy of the transcript; merge it to keep messages from both.".to_string()
+            } else {
+                format!(
+                    "A synced copy of {original_path}; compare the two and delete the one \
+                     you don't need."
+                )
+            };
+            SyncConflict {
+                camp_id: config.id.clone(),
+                camp_name: config.name.clone(),
+                kind: SyncConflictKind::ConflictedCopy,
+                detail,
+                path,
+                original_path: Some(original_path),
+                mergeable,
+            }
+        })
+        .collect();
+
+    let newest_message = read_transcript(&camp_transcript_path(camp_dir))
+        .unwrap_or_default()
+        .iter()
+        .map(|message| message.created_at)
+        .max();
+    if let Some(newest) = newest_message {
+        if config.updated_at + STALE_CONFIG_TOLERANCE_MS < newest {
+            conflicts.push(SyncConflict {
+                camp_id: config.id.clone(),
+                camp_name: config.name.clone(),
+                kind: SyncConflictKind::StaleConfig,
+                path: CAMP_CONFIG_FILE.to_string(),
+                original_path: None,
+                detail: format!(
+                    "camp.json was last updated at {} but the newest message is from {newest}; \
+                     another device may have overwritten settings changed here.",
+                    config.updated_at
+                ),
+                mergeable: false,
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// `original` followed by the messages only `copy` has, ordered by time.
+/// Returns the merged transcript and how many of the copy's messages were
+/// duplicates.
+fn merge_transcripts(
+    original: Vec<CampMessage>,
+    copy: Vec<CampMessage>,
+) -> (Vec<CampMessage>, usize) {
+    let mut seen: HashSet<String> = original.iter().map(|message| message.id.clone()).collect();
+    let mut merged = original;
+    let mut duplicates = 0;
+    for message in copy {
+        if seen.insert(message.id.clone()) {
+            merged.push(message);
+        } else {
+            duplicates += 1;
+        }
+    }
+    // Stable, so messages sharing a timestamp (a reply and its tool results)
+    // keep their order.
+    merged.sort_by_key(|message| message.created_at);
+    (merged, duplicates)
+}
+
+/// Lists conflicted copies and stale camp.json files across every camp.
+#[tauri::command]
+pub fn detect_sync_conflicts(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<Vec<SyncConflict>, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let entries =
+        fs::read_dir(&camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+
+    let mut conflicts = Vec::new();
+    for entry in entries.flatten() {
+        let camp_dir = entry.path();
+        if !camp_dir.is_dir() || !camp_config_path(&camp_dir).exists() {
+            continue;
+        }
+        if let Ok(found) = camp_sync_conflicts(&camp_dir) {
+            conflicts.extend(found);
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Merges a conflicted copy of a camp's transcript into the transcript,
+/// dropping messages both already share, and moves the copy to the camp
+/// trash. With `dry_run` it only reports what the merge would do.
+#[tauri::command]
+pub fn merge_sync_conflict(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<SyncMergeReport, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+
+    let is_transcript_copy = !path.contains(|c| c == '/' || c == '\\')
+        && conflict_original_name(&path, CAMP_ROOT_FILES).as_deref() == Some(CAMP_TRANSCRIPT_FILE);
+    if !is_transcript_copy {
+        return Err("Only conflicted copies of the transcript can be merged.".to_string());
+    }
+    let copy_path = camp_dir.join(&path);
+    if !copy_path.is_file() {
+        return Err("Conflicted copy not found.".to_string());
+    }
+
+    let transcript_path = camp_transcript_path(&camp_dir);
+    let original = read_transcript(&transcript_path)?;
+    let copy = read_transcript(&copy_path)
+        .map_err(|err| format!("Unable to read the conflicted copy: {err}"))?;
+    let original_count = original.len();
+    let (merged, duplicate_count) = merge_transcripts(original, copy);
+
+    let mut report = SyncMergeReport {
+        original_path: CAMP_TRANSCRIPT_FILE.to_string(),
+        merged_count: merged.len(),
+        added_count: merged.len() - original_count,
+        duplicate_count,
+        applied: false,
+        trash_entry_id: None,
+    };
+    if dry_run.unwrap_or(false) {
+        return Ok(report);
+    }
+
+    write_transcript(&transcript_path, &merged)?;
+    let entry = move_to_trash(
+        &camp_dir,
+        &copy_path,
+        CampTrashKind::SyncConflict,
+        path,
+        None,
+    )?;
+    touch_camp_updated_at(&camp_dir)?;
+
+    report.applied = true;
+    report.trash_entry_id = Some(entry.id);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conflicted_copies_should_be_recognized_and_transcripts_merged() {
+        let original = |name: &str| conflict_original_name(name, CAMP_ROOT_FILES);
+        let transcript = Some(CAMP_TRANSCRIPT_FILE.to_string());
+        assert_eq!(
+            original("transcript (Sam's conflicted copy 2024-05-01).jsonl"),
+            transcript
+        );
+        assert_eq!(
+            original("transcript.sync-conflict-20240501-101500-ABCDEF1.jsonl"),
+            transcript
+        );
+        assert_eq!(original("transcript 2.jsonl"), transcript);
+        assert_eq!(
+            original("camp-DESKTOP-4F2K9.json"),
+            Some(CAMP_CONFIG_FILE.to_string())
+        );
+        assert_eq!(original("transcript.jsonl"), None);
+        assert_eq!(original("notes 2.md"), None);
+
+        let (merged, duplicates) = merge_transcripts(
+            vec![
+                CampMessage::for_test("a", "user", "message a").with_created_at(1),
+                CampMessage::for_test("b", "user", "message b").with_created_at(3),
+            ],
+            vec![
+                CampMessage::for_test("a", "user", "message a").with_created_at(1),
+                CampMessage::for_test("c", "user", "message c").with_created_at(2),
+                CampMessage::for_test("d", "user", "message d").with_created_at(4),
+            ],
+        );
+        let ids: Vec<&str> = merged.iter().map(|message| message.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "c", "b", "d"]);
+        assert_eq!(duplicates, 1);
+    }
+}
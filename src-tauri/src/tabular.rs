@@ -0,0 +1,889 @@
+//! Spreadsheets as context. CSV, TSV and Excel (`.xlsx`) context files can be
+//! previewed (first rows, inferred column types, row count) and converted to
+//! a Markdown table, which is also what default context sends for them in
+//! place of raw bytes. The first row is taken as the header.
+//!
+//! An `.xlsx` file is a zip of XML parts. Only the parts a table needs are
+//! read (workbook, relationships, shared strings and one worksheet), streamed
+//! through `zip` and `quick-xml`. Cells come through as their stored text,
+//! so dates formatted in Excel show up as serial numbers. Only the first
+//! rows of a table are kept in memory; the rest are counted.
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::error::{BasecampError, CommandResult};
+use crate::{
+    camp_context_dir, canonicalize_context_root, ensure_camps_root, ensure_main_window,
+    resolve_existing_camp_dir, resolve_existing_context_target, AppState,
+};
+
+const MAX_TABLE_FILE_BYTES: u64 = 20 * 1024 * 1024;
+/// Guards against zip bombs; no single sheet part should inflate past this.
+const MAX_XLSX_PART_BYTES: u64 = 100 * 1024 * 1024;
+const DEFAULT_PREVIEW_ROWS: usize = 20;
+const MAX_PREVIEW_ROWS: usize = 500;
+pub(crate) const DEFAULT_MARKDOWN_ROWS: usize = 200;
+const MAX_MARKDOWN_ROWS: usize = 2_000;
+/// Rows kept in memory, header included; later rows are only counted.
+const MAX_TABLE_ROWS: usize = MAX_MARKDOWN_ROWS + 1;
+/// Cells kept after padding, so one very wide row cannot multiply the rest.
+const MAX_TABLE_CELLS: usize = 1_000_000;
+/// Excel's own limit (column XFD).
+const MAX_TABLE_COLUMNS: usize = 16_384;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    Xlsx,
+}
+
+impl TableFormat {
+    /// `None` for files that aren't tables; legacy `.xls` is an error so the
+    /// user knows to re-save it.
+    pub(crate) fn from_path(path: &str) -> CommandResult<Option<Self>> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        Ok(match extension.as_deref() {
+            Some("csv") => Some(Self::Csv),
+            Some("tsv") => Some(Self::Tsv),
+            Some("xlsx") => Some(Self::Xlsx),
+            Some("xls") => {
+                return Err(BasecampError::validation(
+                    "Legacy .xls files aren't supported; save the workbook as .xlsx.",
+                ))
+            }
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnType {
+    Integer,
+    Number,
+    Boolean,
+    /// `YYYY-MM-DD`, optionally followed by a time.
+    Date,
+    Text,
+    /// Every cell below the header is blank.
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableColumn {
+    pub name: String,
+    pub inferred_type: ColumnType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TablePreview {
+    pub path: String,
+    pub format: TableFormat,
+    /// The worksheet read, for Excel files.
+    pub sheet: Option<String>,
+    pub sheet_names: Vec<String>,
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<Vec<String>>,
+    /// Rows below the header.
+    pub row_count: usize,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CampTablePayload {
+    pub camp_id: String,
+    pub path: String,
+    /// Worksheet name for Excel files; the first sheet when omitted.
+    pub sheet: Option<String>,
+    pub max_rows: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Table {
+    pub sheet: Option<String>,
+    pub sheet_names: Vec<String>,
+    /// Header first. Every row is padded to the same width. Only the first
+    /// rows are kept; `row_count` counts them all.
+    pub rows: Vec<Vec<String>>,
+    /// Rows below the header, including those that were not kept.
+    pub row_count: usize,
+}
+
+impl Table {
+    fn header(&self) -> Vec<String> {
+        self.rows
+            .first()
+            .map(|header| {
+                header
+                    .iter()
+                    .enumerate()
+                    .map(|(index, name)| match name.trim() {
+                        "" => format!("column_{}", index + 1),
+                        name => name.to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn data_rows(&self) -> &[Vec<String>] {
+        self.rows.get(1..).unwrap_or_default()
+    }
+}
+
+/// Collects rows into a `Table` in bounded memory. Blank rows are only
+/// counted until a non-blank row follows, so trailing ones are dropped and a
+/// gap up to row 1,048,576 costs nothing. Rows past `MAX_TABLE_ROWS`, or that
+/// would pad the table past `MAX_TABLE_CELLS`, are counted but not kept.
+#[derive(Debug, Default)]
+struct TableBuilder {
+    rows: Vec<Vec<String>>,
+    width: usize,
+    seen: usize,
+    pending_blank: usize,
+    full: bool,
+}
+
+impl TableBuilder {
+    fn push_blank(&mut self, count: usize) {
+        self.pending_blank = self.pending_blank.saturating_add(count);
+    }
+
+    fn push(&mut self, row: Vec<String>) {
+        if row.iter().all(|cell| cell.trim().is_empty()) {
+            self.push_blank(1);
+            return;
+        }
+        let blank = std::mem::take(&mut self.pending_blank);
+        self.seen = self.seen.saturating_add(blank).saturating_add(1);
+        for _ in 0..blank {
+            if !self.keep(Vec::new()) {
+                return;
+            }
+        }
+        self.keep(row);
+    }
+
+    /// Once a row is turned away no later row is kept, so the kept rows stay
+    /// contiguous.
+    fn keep(&mut self, mut row: Vec<String>) -> bool {
+        row.truncate(MAX_TABLE_COLUMNS);
+        let width = self.width.max(row.len());
+        self.full = self.full
+            || self.rows.len() >= MAX_TABLE_ROWS
+            || width.saturating_mul(self.rows.len() + 1) > MAX_TABLE_CELLS;
+        if self.full {
+            return false;
+        }
+        self.width = width;
+        self.rows.push(row);
+        true
+    }
+
+    fn finish(mut self) -> Table {
+        for row in &mut self.rows {
+            row.resize(self.width, String::new());
+        }
+        Table {
+            rows: self.rows,
+            row_count: self.seen.saturating_sub(1),
+            ..Table::default()
+        }
+    }
+}
+
+// ----- CSV / TSV -----
+
+fn decode_text(bytes: &[u8]) -> Cow<'_, str> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes)
+}
+
+/// Excel in many locales writes `;`-separated "CSV"; the header line decides.
+fn sniff_csv_delimiter(text: &str) -> char {
+    let first_line = text.lines().next().unwrap_or_default();
+    let mut in_quotes = false;
+    let (mut commas, mut semicolons) = (0, 0);
+    for ch in first_line.chars() {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => commas += 1,
+            ';' if !in_quotes => semicolons += 1,
+            _ => {}
+        }
+    }
+    if semicolons > commas {
+        ';'
+    } else {
+        ','
+    }
+}
+
+/// RFC 4180 fields: quoted fields may hold delimiters, newlines and `""`.
+fn parse_delimited(text: &str, delimiter: char) -> Table {
+    let mut table = TableBuilder::default();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            if ch == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(ch);
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() => in_quotes = true,
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                row.push(std::mem::take(&mut field));
+                table.push(std::mem::take(&mut row));
+            }
+            // Fields past the widest row a table keeps are dropped as read.
+            ch if ch == delimiter && row.len() >= MAX_TABLE_COLUMNS => field.clear(),
+            ch if ch == delimiter => row.push(std::mem::take(&mut field)),
+            ch => field.push(ch),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        table.push(row);
+    }
+    table.finish()
+}
+
+// ----- XLSX -----
+
+type Workbook<'a> = ZipArchive<Cursor<&'a [u8]>>;
+
+fn invalid_xlsx(reason: &str) -> BasecampError {
+    BasecampError::validation(format!("File is not a readable .xlsx workbook: {reason}."))
+}
+
+/// Parses one part of the workbook as it is inflated; `None` when the part
+/// is missing. Parts that inflate past `MAX_XLSX_PART_BYTES` are rejected.
+fn read_part<T>(
+    workbook: &mut Workbook<'_>,
+    name: &str,
+    parse: impl FnOnce(&mut dyn BufRead) -> CommandResult<T>,
+) -> CommandResult<Option<T>> {
+    let part = match workbook.by_name(name) {
+        Ok(part) => part,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(_) => return Err(invalid_xlsx("damaged zip entry")),
+    };
+    let mut source = BufReader::new(part.take(MAX_XLSX_PART_BYTES + 1));
+    let parsed = parse(&mut source);
+    if source.get_ref().limit() == 0 {
+        return Err(invalid_xlsx("a part of the workbook is too large"));
+    }
+    parsed.map(Some)
+}
+
+fn for_each_xml_event(
+    source: &mut dyn BufRead,
+    mut handle: impl FnMut(Event<'_>),
+) -> CommandResult<()> {
+    let mut reader = Reader::from_reader(source);
+    let mut buffer = Vec::new();
+    loop {
+        match reader
+            .read_event_into(&mut buffer)
+            .map_err(|_| invalid_xlsx("damaged XML"))?
+        {
+            Event::Eof => return Ok(()),
+            event => handle(event),
+        }
+        buffer.clear();
+    }
+}
+
+/// An attribute by its name without namespace prefix, so `r:id` is `id`.
+fn xml_attr(element: &BytesStart<'_>, key: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.local_name().as_ref() == key.as_bytes())
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(Cow::into_owned)
+}
+
+/// Appends character data; quick-xml reports entity references such as
+/// `&amp;` as events of their own.
+fn push_xml_text(out: &mut String, event: &Event<'_>) {
+    match event {
+        Event::Text(text) => out.push_str(&text.xml_content().unwrap_or_default()),
+        Event::CData(data) => out.push_str(&data.xml_content().unwrap_or_default()),
+        Event::GeneralRef(reference) => match reference.resolve_char_ref() {
+            Ok(Some(ch)) => out.push(ch),
+            _ => {
+                let name = reference.decode().unwrap_or_default();
+                out.push_str(resolve_predefined_entity(&name).unwrap_or_default());
+            }
+        },
+        _ => {}
+    }
+}
+
+fn parse_shared_strings(source: &mut dyn BufRead) -> CommandResult<Vec<String>> {
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    // Phonetic runs repeat the reading of East Asian text; skip them.
+    let mut in_phonetic = false;
+    for_each_xml_event(source, |event| match &event {
+        Event::Start(element) => match element.local_name().as_ref() {
+            b"si" => current.clear(),
+            b"rPh" => in_phonetic = true,
+            b"t" => in_text = !in_phonetic,
+            _ => {}
+        },
+        Event::Empty(element) if element.local_name().as_ref() == b"si" => {
+            strings.push(String::new());
+        }
+        Event::End(element) => match element.local_name().as_ref() {
+            b"si" => strings.push(std::mem::take(&mut current)),
+            b"rPh" => in_phonetic = false,
+            b"t" => in_text = false,
+            _ => {}
+        },
+        _ if in_text => push_xml_text(&mut current, &event),
+        _ => {}
+    })?;
+    Ok(strings)
+}
+
+/// Zero-based column of a cell reference such as `AB12`.
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference
+        .bytes()
+        .take_while(u8::is_ascii_alphabetic)
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .iter()
+        .try_fold(0_usize, |index, letter| {
+            index
+                .checked_mul(26)?
+                .checked_add((letter.to_ascii_uppercase() - b'A' + 1) as usize)
+        })
+        .map(|index| index - 1)
+        .filter(|index| *index < MAX_TABLE_COLUMNS)
+}
+
+fn cell_text(kind: &str, value: &mut String, shared_strings: &[String]) -> String {
+    match kind {
+        "s" => value
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| shared_strings.get(index))
+            .cloned()
+            .unwrap_or_default(),
+        "b" => match value.trim() {
+            "1" => "TRUE".to_string(),
+            _ => "FALSE".to_string(),
+        },
+        _ => std::mem::take(value),
+    }
+}
+
+fn parse_worksheet(
+    source: &mut dyn BufRead,
+    shared_strings: &[String],
+) -> CommandResult<TableBuilder> {
+    let mut table = TableBuilder::default();
+    let mut next_row = 1_usize;
+    let mut row: Vec<String> = Vec::new();
+    let mut row_number: Option<usize> = None;
+    let mut cell: Option<(Option<usize>, String)> = None;
+    let mut value = String::new();
+    let mut in_value = false;
+
+    for_each_xml_event(source, |event| match &event {
+        Event::Start(element) => match element.local_name().as_ref() {
+            b"row" => {
+                row.clear();
+                row_number = xml_attr(element, "r").and_then(|number| number.parse().ok());
+            }
+            b"c" => {
+                let column = xml_attr(element, "r").and_then(|reference| column_index(&reference));
+                cell = Some((column, xml_attr(element, "t").unwrap_or_default()));
+                value.clear();
+            }
+            b"v" | b"t" if cell.is_some() => in_value = true,
+            _ => {}
+        },
+        Event::End(element) => match element.local_name().as_ref() {
+            b"row" => {
+                // Keep blank rows Excel skipped so later rows stay in place.
+                if let Some(number) = row_number.filter(|number| *number <= 1_048_576) {
+                    if number > next_row {
+                        table.push_blank(number - next_row);
+                        next_row = number;
+                    }
+                }
+                table.push(std::mem::take(&mut row));
+                next_row += 1;
+            }
+            b"v" | b"t" => in_value = false,
+            b"c" => {
+                let Some((column, kind)) = cell.take() else {
+                    return;
+                };
+                let text = cell_text(&kind, &mut value, shared_strings);
+                let column = column.unwrap_or(row.len());
+                if column >= MAX_TABLE_COLUMNS {
+                    return;
+                }
+                if row.len() <= column {
+                    row.resize(column + 1, String::new());
+                }
+                row[column] = text;
+            }
+            _ => {}
+        },
+        _ if in_value => push_xml_text(&mut value, &event),
+        _ => {}
+    })?;
+    Ok(table)
+}
+
+/// `(name, part path)` of each worksheet, in workbook order.
+fn workbook_sheets(workbook: &mut Workbook<'_>) -> CommandResult<Vec<(String, String)>> {
+    let mut targets: Vec<(String, String)> = Vec::new();
+    read_part(workbook, "xl/_rels/workbook.xml.rels", |source| {
+        for_each_xml_event(source, |event| {
+            if let Event::Start(element) | Event::Empty(element) = &event {
+                if element.local_name().as_ref() == b"Relationship" {
+                    if let (Some(id), Some(target)) =
+                        (xml_attr(element, "Id"), xml_attr(element, "Target"))
+                    {
+                        targets.push((id, target));
+                    }
+                }
+            }
+        })
+    })?;
+
+    let mut sheets: Vec<(String, Option<String>)> = Vec::new();
+    read_part(workbook, "xl/workbook.xml", |source| {
+        for_each_xml_event(source, |event| {
+            if let Event::Start(element) | Event::Empty(element) = &event {
+                if element.local_name().as_ref() == b"sheet" {
+                    if let Some(name) = xml_attr(element, "name") {
+                        sheets.push((name, xml_attr(element, "id")));
+                    }
+                }
+            }
+        })
+    })?
+    .ok_or_else(|| invalid_xlsx("no workbook part"))?;
+
+    Ok(sheets
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, relationship_id))| {
+            let target = relationship_id
+                .and_then(|id| targets.iter().find(|(target_id, _)| *target_id == id))
+                .map(|(_, target)| match target.strip_prefix('/') {
+                    Some(absolute) => absolute.to_string(),
+                    None => format!("xl/{target}"),
+                })
+                .unwrap_or_else(|| format!("xl/worksheets/sheet{}.xml", index + 1));
+            (name, target)
+        })
+        .collect())
+}
+
+fn read_xlsx(bytes: &[u8], sheet: Option<&str>) -> CommandResult<Table> {
+    let mut workbook =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|_| invalid_xlsx("not a zip archive"))?;
+    let sheets = workbook_sheets(&mut workbook)?;
+    let sheet_names: Vec<String> = sheets.iter().map(|(name, _)| name.clone()).collect();
+
+    let (sheet_name, part) = match sheet.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(wanted) => sheets
+            .iter()
+            .find(|(name, _)| name == wanted)
+            .ok_or_else(|| {
+                BasecampError::not_found(format!("Worksheet `{wanted}` not found."))
+                    .with_context("sheets", sheet_names.clone())
+            })?,
+        None => sheets
+            .first()
+            .ok_or_else(|| invalid_xlsx("the workbook has no worksheets"))?,
+    };
+    let shared_strings =
+        read_part(&mut workbook, "xl/sharedStrings.xml", parse_shared_strings)?.unwrap_or_default();
+    let table = read_part(&mut workbook, part, |source| {
+        parse_worksheet(source, &shared_strings)
+    })?
+    .ok_or_else(|| invalid_xlsx("a worksheet part is missing"))?;
+
+    Ok(Table {
+        sheet: Some(sheet_name.clone()),
+        sheet_names,
+        ..table.finish()
+    })
+}
+
+pub(crate) fn read_table(
+    bytes: &[u8],
+    format: TableFormat,
+    sheet: Option<&str>,
+) -> CommandResult<Table> {
+    match format {
+        TableFormat::Csv => {
+            let text = decode_text(bytes);
+            Ok(parse_delimited(&text, sniff_csv_delimiter(&text)))
+        }
+        TableFormat::Tsv => Ok(parse_delimited(&decode_text(bytes), '\t')),
+        TableFormat::Xlsx => read_xlsx(bytes, sheet),
+    }
+}
+
+// ----- Preview and Markdown -----
+
+fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() >= 10
+        && bytes[..10]
+            .iter()
+            .enumerate()
+            .all(|(index, byte)| match index {
+                4 | 7 => *byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+        && matches!(bytes.get(10), None | Some(b'T' | b' '))
+}
+
+fn infer_column_type<'a>(values: impl Iterator<Item = &'a str>) -> ColumnType {
+    let mut inferred: Option<ColumnType> = None;
+    for value in values.map(str::trim).filter(|value| !value.is_empty()) {
+        let this = if value.parse::<i64>().is_ok() {
+            ColumnType::Integer
+        } else if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            ColumnType::Number
+        } else if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+            ColumnType::Boolean
+        } else if is_iso_date(value) {
+            ColumnType::Date
+        } else {
+            return ColumnType::Text;
+        };
+        inferred = Some(match (inferred, this) {
+            (None, this) => this,
+            (Some(previous), this) if previous == this => this,
+            (Some(ColumnType::Integer), ColumnType::Number)
+            | (Some(ColumnType::Number), ColumnType::Integer) => ColumnType::Number,
+            _ => return ColumnType::Text,
+        });
+    }
+    inferred.unwrap_or(ColumnType::Empty)
+}
+
+pub(crate) fn preview_table(
+    table: &Table,
+    path: &str,
+    format: TableFormat,
+    max_rows: usize,
+) -> TablePreview {
+    let data_rows = table.data_rows();
+    let columns = table
+        .header()
+        .into_iter()
+        .enumerate()
+        .map(|(index, name)| TableColumn {
+            name,
+            inferred_type: infer_column_type(data_rows.iter().map(|row| row[index].as_str())),
+        })
+        .collect();
+    TablePreview {
+        path: path.to_string(),
+        format,
+        sheet: table.sheet.clone(),
+        sheet_names: table.sheet_names.clone(),
+        columns,
+        rows: data_rows.iter().take(max_rows).cloned().collect(),
+        row_count: table.row_count,
+        truncated: table.row_count > max_rows.min(data_rows.len()),
+    }
+}
+
+fn markdown_cell(value: &str) -> String {
+    value
+        .trim()
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\r', '\n'], "<br>")
+}
+
+/// A pipe table of the header and up to `max_rows` rows, with a note when
+/// rows were left out.
+pub(crate) fn table_to_markdown(table: &Table, max_rows: usize) -> String {
+    let header = table.header();
+    if header.is_empty() {
+        return "_The table is empty._\n".to_string();
+    }
+    let line = |cells: &mut dyn Iterator<Item = String>| {
+        let cells: Vec<String> = cells.collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut markdown = String::new();
+    if let Some(sheet) = &table.sheet {
+        markdown.push_str(&format!("Sheet: {}\n\n", markdown_cell(sheet)));
+    }
+    markdown.push_str(&line(&mut header.iter().map(|name| markdown_cell(name))));
+    markdown.push_str(&line(&mut header.iter().map(|_| "---".to_string())));
+    let shown = table.data_rows().len().min(max_rows);
+    for row in &table.data_rows()[..shown] {
+        markdown.push_str(&line(&mut row.iter().map(|cell| markdown_cell(cell))));
+    }
+    if table.row_count > shown {
+        markdown.push_str(&format!(
+            "\n_Showing {shown} of {} rows._\n",
+            table.row_count
+        ));
+    }
+    markdown
+}
+
+fn load_context_table(
+    state: &State<'_, AppState>,
+    payload: &CampTablePayload,
+) -> CommandResult<(TableFormat, Table)> {
+    let format = TableFormat::from_path(&payload.path)?.ok_or_else(|| {
+        BasecampError::validation("Only .csv, .tsv and .xlsx files can be read as tables.")
+            .with_context("path", payload.path.clone())
+    })?;
+    let target = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let context_root = canonicalize_context_root(&camp_context_dir(&camp_dir))?;
+        resolve_existing_context_target(&context_root, &payload.path, "path", false)?
+    };
+    let metadata = fs::metadata(&target)?;
+    if !metadata.is_file() {
+        return Err(BasecampError::validation("Requested path is not a file."));
+    }
+    if metadata.len() > MAX_TABLE_FILE_BYTES {
+        return Err(BasecampError::validation(format!(
+            "Tables are limited to {} MB.",
+            MAX_TABLE_FILE_BYTES / (1024 * 1024)
+        ))
+        .with_context("bytes", metadata.len()));
+    }
+    let table = read_table(&fs::read(&target)?, format, payload.sheet.as_deref())?;
+    Ok((format, table))
+}
+
+/// First rows, column types and row count of a CSV, TSV or Excel context file.
+#[tauri::command]
+pub fn camp_preview_table(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampTablePayload,
+) -> CommandResult<TablePreview> {
+    ensure_main_window(&window)?;
+    let (format, table) = load_context_table(&state, &payload)?;
+    let max_rows = payload
+        .max_rows
+        .unwrap_or(DEFAULT_PREVIEW_ROWS)
+        .clamp(1, MAX_PREVIEW_ROWS);
+    Ok(preview_table(&table, &payload.path, format, max_rows))
+}
+
+#[tauri::command]
+pub fn camp_table_to_markdown(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: CampTablePayload,
+) -> CommandResult<String> {
+    ensure_main_window(&window)?;
+    let (_, table) = load_context_table(&state, &payload)?;
+    let max_rows = payload
+        .max_rows
+        .unwrap_or(DEFAULT_MARKDOWN_ROWS)
+        .clamp(1, MAX_MARKDOWN_ROWS);
+    Ok(table_to_markdown(&table, max_rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    /// A zip with deflated entries, as Excel writes them.
+    fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, body) in files {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .expect("zip entry");
+            writer.write_all(body.as_bytes()).expect("zip entry");
+        }
+        writer.finish().expect("zip").into_inner()
+    }
+
+    #[test]
+    fn read_table_should_preview_csv_and_xlsx_and_render_markdown() {
+        let csv = "\u{feff}name;qty;price;shipped;note\r\n\
+                   tent;2;199.5;2024-05-01;\"roomy; sleeps 4\"\r\n\
+                   stove;1;49;2024-05-03;\"says \"\"hot\"\"\nline two\"\r\n";
+        let table = read_table(csv.as_bytes(), TableFormat::Csv, None).expect("csv");
+        let preview = preview_table(&table, "gear.csv", TableFormat::Csv, 1);
+        let types: Vec<ColumnType> = preview
+            .columns
+            .iter()
+            .map(|column| column.inferred_type)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ColumnType::Text,
+                ColumnType::Integer,
+                ColumnType::Number,
+                ColumnType::Date,
+                ColumnType::Text
+            ]
+        );
+        assert_eq!(
+            preview.rows,
+            vec![vec!["tent", "2", "199.5", "2024-05-01", "roomy; sleeps 4"]]
+        );
+        assert_eq!((preview.row_count, preview.truncated), (2, true));
+        assert_eq!(
+            table_to_markdown(&table, 1),
+            "| name | qty | price | shipped | note |\n\
+             | --- | --- | --- | --- | --- |\n\
+             | tent | 2 | 199.5 | 2024-05-01 | roomy; sleeps 4 |\n\
+             \n_Showing 1 of 2 rows._\n"
+        );
+        assert!(table_to_markdown(&table, 5).contains("| says \"hot\"<br>line two |"));
+
+        let workbook = zip(&[
+            (
+                "xl/workbook.xml",
+                r#"<?xml version="1.0"?><workbook xmlns:r="urn:r"><sheets>
+                   <sheet name="Notes" sheetId="1" r:id="rId2"/>
+                   <sheet name="Q1 &amp; Q2" sheetId="2" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/>
+                   <Relationship Id="rId2" Target="/xl/worksheets/sheet2.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>region</t></si><si><r><t>Nor</t></r><r><t xml:space="preserve">th | 1</t></r></si>
+                   <si><t>total</t></si></sst>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData>
+                   <row r="1"><c r="A1" t="s"><v>0</v></c><c r="C1" t="s"><v>2</v></c></row>
+                   <row r="3"><c r="A3" t="s"><v>1</v></c><c r="B3" t="b"><v>1</v></c><c r="C3"><v>12.5</v></c></row>
+                   <row r="4"><c r="A4" t="inlineStr"><is><t>South</t></is></c><c r="C4" s="2"/></row>
+                   </sheetData></worksheet>"#,
+            ),
+            (
+                "xl/worksheets/sheet2.xml",
+                "<worksheet><sheetData/></worksheet>",
+            ),
+        ]);
+        let table =
+            read_table(&workbook, TableFormat::Xlsx, Some("Q1 & Q2")).expect("xlsx should read");
+        assert_eq!(table.sheet_names, vec!["Notes", "Q1 & Q2"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["region", "", "total"],
+                vec!["", "", ""],
+                vec!["North | 1", "TRUE", "12.5"],
+                vec!["South", "", ""],
+            ]
+        );
+        let preview = preview_table(&table, "sales.xlsx", TableFormat::Xlsx, 10);
+        assert_eq!(preview.columns[1].name, "column_2");
+        assert_eq!(preview.columns[1].inferred_type, ColumnType::Boolean);
+        assert_eq!(preview.columns[2].inferred_type, ColumnType::Number);
+        assert!(table_to_markdown(&table, 10).contains("| North \\| 1 | TRUE | 12.5 |"));
+
+        let first = read_table(&workbook, TableFormat::Xlsx, None).expect("first sheet");
+        assert_eq!(first.sheet.as_deref(), Some("Notes"));
+        assert!(first.rows.is_empty());
+        assert!(read_table(&workbook, TableFormat::Xlsx, Some("Missing")).is_err());
+        assert!(read_table(b"name,qty", TableFormat::Xlsx, None).is_err());
+        assert!(TableFormat::from_path("old.xls").is_err());
+        assert_eq!(TableFormat::from_path("notes.md").ok(), Some(None));
+    }
+
+    #[test]
+    fn read_table_should_keep_bounded_rows_and_cells_but_count_every_row() {
+        let csv: String = std::iter::once("id,name\n".to_string())
+            .chain((1..=5_000).map(|id| format!("{id},item {id}\n\n")))
+            .collect();
+        let table = read_table(csv.as_bytes(), TableFormat::Csv, None).expect("csv");
+        assert_eq!(table.rows.len(), MAX_TABLE_ROWS);
+        assert_eq!(table.row_count, 9_999);
+        let preview = preview_table(&table, "items.csv", TableFormat::Csv, 10);
+        assert_eq!((preview.row_count, preview.truncated), (9_999, true));
+        assert!(table_to_markdown(&table, MAX_MARKDOWN_ROWS)
+            .ends_with("\n_Showing 2000 of 9999 rows._\n"));
+
+        let workbook = zip(&[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="urn:r"><sheets><sheet name="Wide" r:id="rId1"/></sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="worksheets/sheet1.xml"/></Relationships>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData>
+                   <row r="1"><c r="A1"><v>1</v></c><c r="XFD1"><v>2</v></c></row>
+                   <row r="1048576"><c r="A1048576"><v>3</v></c></row>
+                   <row r="1048577"><c r="A1048577"><v>4</v></c></row>
+                   </sheetData></worksheet>"#,
+            ),
+        ]);
+        let table = read_table(&workbook, TableFormat::Xlsx, None).expect("xlsx should read");
+        assert_eq!(table.rows.len(), MAX_TABLE_CELLS / MAX_TABLE_COLUMNS);
+        assert!(table.rows.iter().all(|row| row.len() == MAX_TABLE_COLUMNS));
+        assert_eq!(table.row_count, 1_048_576);
+    }
+}
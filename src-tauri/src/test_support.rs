@@ -0,0 +1,42 @@
+//! Helpers shared by the unit tests of several modules.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use uuid::Uuid;
+
+/// A scratch directory that is removed when dropped, so a failing test does
+/// not leave it behind.
+pub(crate) struct TempDir(PathBuf);
+
+impl Deref for TempDir {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &PathBuf {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+pub(crate) fn make_temp_dir(prefix: &str) -> TempDir {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should move forward")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}-{timestamp}-{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir).expect("temporary directory should be created");
+    TempDir(dir)
+}
@@ -0,0 +1,184 @@
+//! Stops agent tool loops that are spinning. A turn is aborted when the model
+//! asks for the same tool with the same arguments more than
+//! `max_repeated_calls` times, or when the tokens spent across the turn's
+//! requests pass `max_turn_tokens`. The camp loop runs in the webview and
+//! applies the same limits from `run_start`'s config; the team loop uses
+//! `ToolLoopGuard` directly.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub(crate) const DEFAULT_MAX_REPEATED_TOOL_CALLS: u32 = 3;
+pub(crate) const DEFAULT_MAX_TURN_TOKENS: u64 = 200_000;
+const MAX_TURN_TOKENS_CEILING: u64 = 2_000_000;
+
+/// Why a tool loop was stopped early. Recorded on the run (camp) or the step
+/// result (team) so the abort is visible after the fact.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum ToolLoopAbort {
+    RepeatedToolCall { tool_name: String, count: u32 },
+    TokenCeiling { tokens_used: u64, limit: u64 },
+}
+
+impl ToolLoopAbort {
+    pub(crate) fn message(&self) -> String {
+        match self {
+            Self::RepeatedToolCall { tool_name, count } => format!(
+                "Tool loop aborted: `{tool_name}` was called {count} times with the same arguments."
+            ),
+            Self::TokenCeiling { tokens_used, limit } => format!(
+                "Tool loop aborted: the turn used {tokens_used} tokens, over its {limit}-token limit."
+            ),
+        }
+    }
+}
+
+/// Clamps the limits a caller asked for to the supported range.
+pub(crate) fn clamp_loop_limits(max_repeated_calls: u32, max_turn_tokens: u64) -> (u32, u64) {
+    (
+        max_repeated_calls.clamp(1, 20),
+        max_turn_tokens.clamp(1_000, MAX_TURN_TOKENS_CEILING),
+    )
+}
+
+/// Arguments with object keys sorted, so reordered JSON still counts as a
+/// repeat. Unparseable arguments are compared as written.
+fn canonical_arguments(arguments: &str) -> String {
+    fn write(value: &Value, out: &mut String) {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                out.push('{');
+                for (index, key) in keys.into_iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::String(key.clone()).to_string());
+                    out.push(':');
+                    write(&map[key], out);
+                }
+                out.push('}');
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    write(item, out);
+                }
+                out.push(']');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(value) => {
+            let mut out = String::new();
+            write(&value, &mut out);
+            out
+        }
+        Err(_) => arguments.trim().to_string(),
+    }
+}
+
+pub(crate) struct ToolLoopGuard {
+    max_repeated_calls: u32,
+    max_turn_tokens: u64,
+    call_counts: HashMap<(String, String), u32>,
+    tokens_used: u64,
+}
+
+impl Default for ToolLoopGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_REPEATED_TOOL_CALLS, DEFAULT_MAX_TURN_TOKENS)
+    }
+}
+
+impl ToolLoopGuard {
+    pub(crate) fn new(max_repeated_calls: u32, max_turn_tokens: u64) -> Self {
+        let (max_repeated_calls, max_turn_tokens) =
+            clamp_loop_limits(max_repeated_calls, max_turn_tokens);
+        Self {
+            max_repeated_calls,
+            max_turn_tokens,
+            call_counts: HashMap::new(),
+            tokens_used: 0,
+        }
+    }
+
+    /// Adds one request's usage to the turn.
+    pub(crate) fn record_tokens(&mut self, tokens: u64) -> Result<(), ToolLoopAbort> {
+        self.tokens_used = self.tokens_used.saturating_add(tokens);
+        if self.tokens_used > self.max_turn_tokens {
+            return Err(ToolLoopAbort::TokenCeiling {
+                tokens_used: self.tokens_used,
+                limit: self.max_turn_tokens,
+            });
+        }
+        Ok(())
+    }
+
+    /// Counts a tool call the model asked for, before it runs.
+    pub(crate) fn record_call(
+        &mut self,
+        tool_name: &str,
+        arguments: &str,
+    ) -> Result<(), ToolLoopAbort> {
+        let count = self
+            .call_counts
+            .entry((tool_name.to_string(), canonical_arguments(arguments)))
+            .or_insert(0);
+        *count += 1;
+        if *count > self.max_repeated_calls {
+            return Err(ToolLoopAbort::RepeatedToolCall {
+                tool_name: tool_name.to_string(),
+                count: *count,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_loop_guard_should_abort_on_repeats_and_token_ceiling() {
+        let mut guard = ToolLoopGuard::new(2, 5_000);
+        assert!(guard
+            .record_call("read_file", r#"{"path":"a.md","limit":1}"#)
+            .is_ok());
+        assert!(guard.record_call("read_file", r#"{"path":"b.md"}"#).is_ok());
+        assert!(guard
+            .record_call("read_file", r#"{ "limit": 1, "path": "a.md" }"#)
+            .is_ok());
+        assert_eq!(
+            guard.record_call("read_file", r#"{"path":"a.md","limit":1}"#),
+            Err(ToolLoopAbort::RepeatedToolCall {
+                tool_name: "read_file".to_string(),
+                count: 3,
+            })
+        );
+
+        assert!(guard.record_tokens(4_000).is_ok());
+        let abort = guard.record_tokens(1_500).expect_err("ceiling");
+        assert_eq!(
+            abort,
+            ToolLoopAbort::TokenCeiling {
+                tokens_used: 5_500,
+                limit: 5_000,
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&abort).expect("serializes")["reason"],
+            "token_ceiling"
+        );
+    }
+}
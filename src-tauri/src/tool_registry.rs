@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use crate::mcp::McpToolDef;
+
+/// Prefix reserved for MCP tools. Built-in camp and team tools never use it, so
+/// a server cannot shadow `read_file` or any other tool the app ships with.
+const MCP_TOOL_PREFIX: &str = "mcp__";
+const SEGMENT_SEPARATOR: &str = "__";
+/// Providers reject function names longer than this or outside `[A-Za-z0-9_-]`.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+fn sanitize_segment(raw: &str) -> String {
+    let sanitized: String = raw
+        .trim()
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.is_empty() {
+        "_".to_string()
+    } else {
+        sanitized
+    }
+}
+
+fn truncate_with_suffix(base: &str, suffix: &str) -> String {
+    let keep = MAX_TOOL_NAME_LEN
+        .saturating_sub(suffix.len())
+        .min(base.len());
+    format!("{}{suffix}", &base[..keep])
+}
+
+/// The provider-safe name an MCP tool is exposed under before any collision is
+/// resolved: `mcp__{server}__{tool}`.
+pub(crate) fn qualify_mcp_tool_name(server_id: &str, tool_name: &str) -> String {
+    let name = format!(
+        "{MCP_TOOL_PREFIX}{}{SEGMENT_SEPARATOR}{}",
+        sanitize_segment(server_id),
+        sanitize_segment(tool_name)
+    );
+    truncate_with_suffix(&name, "")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RegisteredTool {
+    server_id: String,
+    tool_name: String,
+}
+
+/// Maps the qualified names handed to providers back to the server and tool
+/// they were discovered on. Sanitizing and truncating can make two tools land
+/// on the same name; the later one gets a numeric suffix so every qualified
+/// name stays unique.
+#[derive(Debug, Default)]
+pub struct ToolRegistry {
+    by_name: BTreeMap<String, RegisteredTool>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces a server's tools and rewrites each `qualified_name` to the name
+    /// it was registered under.
+    pub fn register_server_tools(&mut self, server_id: &str, tools: &mut [McpToolDef]) {
+        self.forget_server(server_id);
+        for tool in tools.iter_mut() {
+            let entry = RegisteredTool {
+                server_id: server_id.to_string(),
+                tool_name: tool.name.clone(),
+            };
+            let base = qualify_mcp_tool_name(server_id, &tool.name);
+            let mut qualified = base.clone();
+            let mut attempt = 2;
+            while self
+                .by_name
+                .get(&qualified)
+                .is_some_and(|existing| *existing != entry)
+            {
+                qualified = truncate_with_suffix(&base, &format!("_{attempt}"));
+                attempt += 1;
+            }
+            self.by_name.insert(qualified.clone(), entry);
+            tool.qualified_name = qualified;
+        }
+    }
+
+    pub fn forget_server(&mut self, server_id: &str) {
+        self.by_name.retain(|_, tool| tool.server_id != server_id);
+    }
+
+    /// Resolves a qualified name to `(server_id, tool_name)`.
+    pub fn resolve(&self, qualified_name: &str) -> Option<(&str, &str)> {
+        self.by_name
+            .get(qualified_name)
+            .map(|tool| (tool.server_id.as_str(), tool.tool_name.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tool(name: &str) -> McpToolDef {
+        McpToolDef {
+            server_id: String::new(),
+            name: name.to_string(),
+            qualified_name: String::new(),
+            description: String::new(),
+            input_schema: json!({}),
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn qualify_mcp_tool_name_should_be_provider_safe() {
+        assert_eq!(
+            qualify_mcp_tool_name("my-server", "read_file"),
+            "mcp__my-server__read_file"
+        );
+        assert_eq!(
+            qualify_mcp_tool_name("files", "search/text"),
+            "mcp__files__search_text"
+        );
+        assert_eq!(
+            qualify_mcp_tool_name("server", &"x".repeat(100)).len(),
+            MAX_TOOL_NAME_LEN
+        );
+    }
+
+    #[test]
+    fn register_server_tools_should_suffix_collisions_and_resolve_back() {
+        let mut registry = ToolRegistry::new();
+        let mut first = vec![tool("search.text")];
+        registry.register_server_tools("files", &mut first);
+        let mut second = vec![tool("search/text"), tool("search.text")];
+        registry.register_server_tools("files", &mut second);
+        assert_eq!(second[0].qualified_name, "mcp__files__search_text");
+        assert_eq!(second[1].qualified_name, "mcp__files__search_text_2");
+        assert_eq!(
+            registry.resolve("mcp__files__search_text_2"),
+            Some(("files", "search.text"))
+        );
+
+        registry.forget_server("files");
+        assert!(registry.resolve("mcp__files__search_text").is_none());
+    }
+}
@@ -0,0 +1,106 @@
+//! Checks tool call arguments against the JSON schema the tool declared
+//! before the tool runs. Failures come back as a list of `{path, message}`
+//! entries the model can read and correct on its next call, instead of a
+//! tool-specific error about a missing or mistyped field.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Enough to correct a call; a badly malformed call would otherwise flood the
+/// tool result.
+const MAX_REPORTED_ERRORS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ToolArgumentError {
+    /// JSON pointer to the offending value; empty for the arguments object
+    /// itself.
+    pub path: String,
+    pub message: String,
+}
+
+/// The `function.parameters` schema of an OpenAI-style tool spec.
+pub(crate) fn tool_parameters_schema(spec: &Value) -> Option<&Value> {
+    spec.get("function")
+        .and_then(|function| function.get("parameters"))
+}
+
+/// Validates `arguments` against `schema`. A schema that doesn't compile is
+/// our bug, not the model's, so it lets the call through rather than
+/// blocking the tool.
+pub(crate) fn validate_tool_arguments(
+    schema: &Value,
+    arguments: &Value,
+) -> Result<(), Vec<ToolArgumentError>> {
+    let Ok(validator) = jsonschema::validator_for(schema) else {
+        return Ok(());
+    };
+    let errors: Vec<ToolArgumentError> = validator
+        .iter_errors(arguments)
+        .take(MAX_REPORTED_ERRORS)
+        .map(|error| ToolArgumentError {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The tool result a rejected call gets, so the model sees every violation.
+pub(crate) fn invalid_arguments_result(tool_name: &str, errors: &[ToolArgumentError]) -> Value {
+    json!({
+        "error": format!("Invalid arguments for `{tool_name}`; correct them and call the tool again."),
+        "validation_errors": errors,
+        "tool": tool_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_tool_arguments_should_report_each_violation_with_its_path() {
+        let spec = json!({
+            "type": "function",
+            "function": {
+                "name": "write_file",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "path": { "type": "string" },
+                        "content": { "type": "string" },
+                        "encoding": { "type": "string", "enum": ["utf-8", "base64"] }
+                    },
+                    "required": ["path", "content"],
+                    "additionalProperties": false
+                }
+            }
+        });
+        let schema = tool_parameters_schema(&spec).expect("spec has parameters");
+
+        assert!(
+            validate_tool_arguments(schema, &json!({ "path": "a.md", "content": "x" })).is_ok()
+        );
+
+        let errors = validate_tool_arguments(
+            schema,
+            &json!({ "path": 42, "encoding": "utf-16", "extra": true }),
+        )
+        .expect_err("arguments should be rejected");
+        let paths: Vec<&str> = errors.iter().map(|error| error.path.as_str()).collect();
+        assert!(paths.contains(&"/path"));
+        assert!(paths.contains(&"/encoding"));
+        assert!(errors
+            .iter()
+            .any(|error| error.path.is_empty() && error.message.contains("content")));
+        assert!(errors
+            .iter()
+            .any(|error| error.path.is_empty() && error.message.contains("extra")));
+
+        assert!(validate_tool_arguments(&json!({ "type": 7 }), &json!({})).is_ok());
+    }
+}
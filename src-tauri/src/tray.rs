@@ -0,0 +1,253 @@
+//! Tray icon and resident mode. The menu offers a new camp, recent camps, the
+//! inspect mode toggle and quit; with resident mode on, closing the main
+//! window hides it and Basecamp keeps running in the tray.
+
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Manager, State, Window, WindowEvent};
+
+use crate::deep_link::{focus_main_window, open_target, DeepLinkTarget};
+use crate::{
+    camp_config_path, ensure_camps_root, get_developer_inspect_mode_db, get_setting_value,
+    read_camp_config, set_setting_value, AppState, SETTING_DEVELOPER_INSPECT,
+};
+
+const SETTING_KEEP_RUNNING_IN_TRAY: &str = "keep_running_in_tray";
+const TRAY_ID: &str = "basecamp";
+const TRAY_ACTION_EVENT: &str = "tray://action";
+const RECENT_CAMPS_LIMIT: usize = 5;
+const MENU_OPEN: &str = "tray:open";
+const MENU_NEW_CAMP: &str = "tray:new_camp";
+const MENU_INSPECT_MODE: &str = "tray:inspect_mode";
+const MENU_QUIT: &str = "tray:quit";
+const MENU_RECENT_PREFIX: &str = "tray:recent:";
+
+/// Read from window events, so kept outside the database.
+#[derive(Default)]
+pub struct TrayState {
+    keep_running: AtomicBool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraySettings {
+    /// Closing the main window hides it instead of quitting.
+    pub keep_running_in_tray: bool,
+}
+
+/// Sent on `tray://action` for menu entries the UI has to act on.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum TrayAction {
+    NewCamp,
+    InspectMode { enabled: bool },
+}
+
+pub(crate) fn load_keep_running(connection: &Connection) -> bool {
+    get_setting_value(connection, SETTING_KEEP_RUNNING_IN_TRAY)
+        .ok()
+        .flatten()
+        .is_some_and(|value| value == "1")
+}
+
+/// Most recently updated camps as `(id, name)`, newest first.
+fn recent_camps(connection: &Connection) -> Vec<(String, String)> {
+    let Ok(camps_root) = ensure_camps_root(connection) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&camps_root) else {
+        return Vec::new();
+    };
+    let mut camps: Vec<(i64, String, String)> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|camp_dir| camp_config_path(camp_dir).exists())
+        .filter_map(|camp_dir| read_camp_config(&camp_dir).ok())
+        .map(|config| (config.updated_at, config.id, config.name))
+        .collect();
+    camps.sort_by(|a, b| b.0.cmp(&a.0));
+    camps
+        .into_iter()
+        .take(RECENT_CAMPS_LIMIT)
+        .map(|(_, id, name)| (id, name))
+        .collect()
+}
+
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let (camps, inspect_mode) = match app.try_state::<AppState>() {
+        Some(state) => match state.connection.lock() {
+            Ok(connection) => (
+                recent_camps(&connection),
+                get_developer_inspect_mode_db(&connection).unwrap_or(false),
+            ),
+            Err(_) => (Vec::new(), false),
+        },
+        None => (Vec::new(), false),
+    };
+
+    let recent = Submenu::new(app, "Recent Camps", !camps.is_empty())?;
+    for (id, name) in &camps {
+        recent.append(&MenuItem::with_id(
+            app,
+            format!("{MENU_RECENT_PREFIX}{id}"),
+            name,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, MENU_OPEN, "Open Basecamp", true, None::<&str>)?,
+            &MenuItem::with_id(app, MENU_NEW_CAMP, "New Camp", true, None::<&str>)?,
+            &recent,
+            &PredefinedMenuItem::separator(app)?,
+            &CheckMenuItem::with_id(
+                app,
+                MENU_INSPECT_MODE,
+                "Inspect Mode",
+                true,
+                inspect_mode,
+                None::<&str>,
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_QUIT, "Quit Basecamp", true, None::<&str>)?,
+        ],
+    )
+}
+
+/// Rebuilds the menu so recent camps and the inspect toggle are current.
+fn refresh_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    if let Ok(menu) = build_menu(app) {
+        let _ = tray.set_menu(Some(menu));
+    }
+}
+
+fn toggle_inspect_mode(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if state.read_only.load(Ordering::SeqCst) {
+        return;
+    }
+    let enabled = {
+        let Ok(connection) = state.connection.lock() else {
+            return;
+        };
+        let enabled = !get_developer_inspect_mode_db(&connection).unwrap_or(false);
+        let value = if enabled { "1" } else { "0" };
+        if set_setting_value(&connection, SETTING_DEVELOPER_INSPECT, value).is_err() {
+            return;
+        }
+        enabled
+    };
+    let _ = app.emit(TRAY_ACTION_EVENT, TrayAction::InspectMode { enabled });
+    refresh_menu(app);
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        MENU_OPEN => focus_main_window(app),
+        MENU_NEW_CAMP => {
+            let _ = app.emit(TRAY_ACTION_EVENT, TrayAction::NewCamp);
+            focus_main_window(app);
+        }
+        MENU_INSPECT_MODE => toggle_inspect_mode(app),
+        MENU_QUIT => app.exit(0),
+        _ => {
+            if let Some(camp_id) = id.strip_prefix(MENU_RECENT_PREFIX) {
+                open_target(
+                    app,
+                    DeepLinkTarget::Camp {
+                        id: camp_id.to_string(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Creates the tray icon. Runs on the main thread, which macOS requires.
+pub(crate) fn setup(app: &AppHandle) {
+    let handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let Ok(menu) = build_menu(&handle) else {
+            return;
+        };
+        let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+            .tooltip("Basecamp")
+            .menu(&menu)
+            .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()))
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Enter { .. } = event {
+                    refresh_menu(tray.app_handle());
+                }
+            });
+        if let Some(icon) = handle.default_window_icon() {
+            builder = builder.icon(icon.clone());
+        }
+        let _ = builder.build(&handle);
+    });
+}
+
+/// Hides the main window instead of closing it while resident mode is on.
+pub(crate) fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    if window.label() != "main" {
+        return;
+    }
+    let keep_running = window
+        .try_state::<TrayState>()
+        .is_some_and(|tray| tray.keep_running.load(Ordering::SeqCst));
+    if keep_running {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
+
+pub(crate) fn set_keep_running(app: &AppHandle, keep_running: bool) {
+    if let Some(tray) = app.try_state::<TrayState>() {
+        tray.keep_running.store(keep_running, Ordering::SeqCst);
+    }
+}
+
+#[tauri::command]
+pub fn get_tray_settings(state: State<'_, AppState>) -> Result<TraySettings, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    Ok(TraySettings {
+        keep_running_in_tray: load_keep_running(&connection),
+    })
+}
+
+#[tauri::command]
+pub fn set_tray_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    settings: TraySettings,
+) -> Result<TraySettings, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let value = if settings.keep_running_in_tray {
+        "1"
+    } else {
+        "0"
+    };
+    set_setting_value(&connection, SETTING_KEEP_RUNNING_IN_TRAY, value)
+        .map_err(|err| format!("Failed to save keep_running_in_tray: {err}"))?;
+    set_keep_running(&app, settings.keep_running_in_tray);
+    Ok(settings)
+}
@@ -0,0 +1,230 @@
+//! In-app updates through tauri-plugin-updater. Release builds embed the
+//! updater public key at compile time (`BASECAMP_UPDATER_PUBKEY`); builds
+//! without it report that updates are unavailable instead of failing later
+//! on signature checks.
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Emitter, Runtime, State, Url, Window};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::{
+    ensure_main_window, get_setting_value, set_setting_value, AppState, CAMP_SCHEMA_VERSION,
+};
+
+const SETTING_UPDATE_CHANNEL: &str = "update_channel";
+const UPDATER_PUBKEY: Option<&str> = option_env!("BASECAMP_UPDATER_PUBKEY");
+const STABLE_ENDPOINT: &str =
+    "https://github.com/coltonbatts/basecamp/releases/latest/download/latest.json";
+const BETA_ENDPOINT: &str =
+    "https://github.com/coltonbatts/basecamp/releases/download/beta/latest.json";
+const UPDATE_PROGRESS_EVENT: &str = "updater://progress";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+
+    fn from_setting(value: &str) -> Self {
+        match value {
+            "beta" => Self::Beta,
+            _ => Self::Stable,
+        }
+    }
+
+    fn endpoint(self) -> &'static str {
+        match self {
+            Self::Stable => STABLE_ENDPOINT,
+            Self::Beta => BETA_ENDPOINT,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub channel: UpdateChannel,
+    pub current_version: String,
+    pub available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+    pub current_camp_schema_version: String,
+    /// Camp schema the new build writes, when the release manifest says.
+    /// Camps are migrated on first open after updating.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub camp_schema_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct UpdateProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+pub(crate) fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    let builder = tauri_plugin_updater::Builder::new();
+    match UPDATER_PUBKEY {
+        Some(pubkey) => builder.pubkey(pubkey).build(),
+        None => builder.build(),
+    }
+}
+
+fn load_update_channel(connection: &Connection) -> Result<UpdateChannel, String> {
+    let value = get_setting_value(connection, SETTING_UPDATE_CHANNEL)
+        .map_err(|err| format!("Failed to read update_channel: {err}"))?;
+    Ok(value
+        .map(|value| UpdateChannel::from_setting(&value))
+        .unwrap_or_default())
+}
+
+fn update_check(
+    channel: UpdateChannel,
+    current_version: String,
+    update: Option<&Update>,
+) -> UpdateCheck {
+    UpdateCheck {
+        channel,
+        current_version,
+        available: update.is_some(),
+        version: update.map(|update| update.version.clone()),
+        published_at: update
+            .and_then(|update| update.date)
+            .map(|date| date.unix_timestamp() * 1_000),
+        release_notes: update.and_then(|update| update.body.clone()),
+        current_camp_schema_version: CAMP_SCHEMA_VERSION.to_string(),
+        camp_schema_version: update.and_then(|update| {
+            update.raw_json["camp_schema_version"]
+                .as_str()
+                .map(str::to_string)
+        }),
+    }
+}
+
+async fn find_update(app: &AppHandle, channel: UpdateChannel) -> Result<Option<Update>, String> {
+    if UPDATER_PUBKEY.is_none() {
+        return Err("This build was not signed for in-app updates.".to_string());
+    }
+    let endpoint =
+        Url::parse(channel.endpoint()).map_err(|err| format!("Invalid update endpoint: {err}"))?;
+    app.updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|err| format!("Unable to set up the updater: {err}"))?
+        .check()
+        .await
+        .map_err(|err| format!("Update check failed: {err}"))
+}
+
+fn current_channel(state: &AppState) -> Result<UpdateChannel, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    load_update_channel(&connection)
+}
+
+/// Looks for a newer build on the configured channel. Release notes and the
+/// camp schema the update writes are returned so the UI can show them before
+/// the user installs.
+#[tauri::command]
+pub async fn check_for_updates(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<UpdateCheck, String> {
+    ensure_main_window(&window)?;
+    let channel = current_channel(&state)?;
+    let update = find_update(&app, channel).await?;
+    Ok(update_check(
+        channel,
+        app.package_info().version.to_string(),
+        update.as_ref(),
+    ))
+}
+
+/// Downloads and installs the latest build on the configured channel, then
+/// restarts. Download progress is emitted on `updater://progress`.
+#[tauri::command]
+pub async fn install_update(
+    window: Window,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    ensure_main_window(&window)?;
+    let channel = current_channel(&state)?;
+    let update = find_update(&app, channel)
+        .await?
+        .ok_or_else(|| "Basecamp is already up to date.".to_string())?;
+
+    let mut downloaded = 0_u64;
+    let progress_app = app.clone();
+    update
+        .download_and_install(
+            move |chunk_length, total| {
+                downloaded += chunk_length as u64;
+                let _ =
+                    progress_app.emit(UPDATE_PROGRESS_EVENT, UpdateProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await
+        .map_err(|err| format!("Update install failed: {err}"))?;
+
+    app.restart();
+}
+
+#[tauri::command]
+pub fn get_update_channel(state: State<'_, AppState>) -> Result<UpdateChannel, String> {
+    current_channel(&state)
+}
+
+#[tauri::command]
+pub fn set_update_channel(
+    state: State<'_, AppState>,
+    channel: UpdateChannel,
+) -> Result<UpdateChannel, String> {
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    set_setting_value(&connection, SETTING_UPDATE_CHANNEL, channel.as_str())
+        .map_err(|err| format!("Failed to save update_channel: {err}"))?;
+    Ok(channel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_channel_should_fall_back_to_stable() {
+        assert_eq!(UpdateChannel::from_setting("beta"), UpdateChannel::Beta);
+        assert_eq!(
+            UpdateChannel::from_setting("nightly"),
+            UpdateChannel::Stable
+        );
+        assert_ne!(
+            UpdateChannel::Stable.endpoint(),
+            UpdateChannel::Beta.endpoint()
+        );
+
+        let check = update_check(UpdateChannel::Beta, "0.1.0".to_string(), None);
+        assert!(!check.available);
+        assert_eq!(check.current_camp_schema_version, CAMP_SCHEMA_VERSION);
+    }
+}
@@ -0,0 +1,536 @@
+//! SQLite files the user registers from their workspace, queried by the
+//! `query_database` camp tool. Registrations live in the app database and
+//! store the path relative to the workspace, which is re-checked on every
+//! query. Queries are held to a single SELECT (or WITH ... SELECT): the text
+//! is checked before it reaches SQLite, the file is opened read-only with
+//! `query_only` set, and SQLite's own read-only flag on the prepared
+//! statement has the final say. Results come back as JSON rows with a row cap.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{State, Window};
+
+use crate::error::{BasecampError, CommandResult};
+use crate::{ensure_main_window, now_timestamp_ms, require_workspace_path, AppState};
+
+const MAX_DATABASE_NAME_CHARS: usize = 64;
+const MAX_QUERY_CHARS: usize = 20_000;
+const DEFAULT_MAX_ROWS: usize = 100;
+const MAX_ROWS_CEILING: usize = 1_000;
+const MAX_CELL_CHARS: usize = 2_000;
+/// Stops a wide result from flooding the tool result even under the row cap.
+const MAX_RESULT_CHARS: usize = 200_000;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+pub fn create_user_databases_table(connection: &Connection) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(
+        "
+        CREATE TABLE IF NOT EXISTS user_databases (
+            name TEXT PRIMARY KEY,
+            relative_path TEXT NOT NULL,
+            created_at INTEGER NOT NULL
+        );
+        ",
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UserDatabase {
+    pub name: String,
+    /// Relative to the workspace folder.
+    pub path: String,
+    pub created_at: i64,
+    /// False when the file has since been moved or deleted.
+    pub available: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterUserDatabasePayload {
+    pub name: String,
+    /// Absolute, or relative to the workspace folder.
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryUserDatabasePayload {
+    pub database: String,
+    pub sql: String,
+    pub max_rows: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueryResult {
+    pub database: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub row_count: usize,
+    /// More rows matched than were returned.
+    pub truncated: bool,
+}
+
+fn normalize_database_name(name: &str) -> CommandResult<String> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_DATABASE_NAME_CHARS {
+        return Err(BasecampError::validation(format!(
+            "Database names must be 1 to {MAX_DATABASE_NAME_CHARS} characters."
+        )));
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(BasecampError::validation(
+            "Database names may only use letters, digits, `_` and `-`.",
+        )
+        .with_context("name", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Canonical path of a database file, which must sit inside the workspace
+/// once symlinks are resolved and start with the SQLite header.
+fn resolve_database_file(workspace: &Path, path: &Path) -> CommandResult<PathBuf> {
+    let workspace = workspace.canonicalize()?;
+    let candidate = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        workspace.join(path)
+    };
+    let resolved = candidate.canonicalize().map_err(|_| {
+        BasecampError::not_found("Database file not found.")
+            .with_context("path", path.to_string_lossy().into_owned())
+    })?;
+    if !resolved.starts_with(&workspace) {
+        return Err(BasecampError::validation(
+            "Database files must be inside the workspace folder.",
+        )
+        .with_context("path", path.to_string_lossy().into_owned()));
+    }
+    if !resolved.is_file() {
+        return Err(BasecampError::validation("Database path is not a file.")
+            .with_context("path", path.to_string_lossy().into_owned()));
+    }
+
+    let mut header = [0_u8; 16];
+    let is_sqlite = File::open(&resolved)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+        && &header == SQLITE_HEADER;
+    if !is_sqlite {
+        return Err(BasecampError::validation("File is not a SQLite database.")
+            .with_context("path", path.to_string_lossy().into_owned()));
+    }
+    Ok(resolved)
+}
+
+/// Checks that `sql` is one SELECT, WITH or VALUES statement and returns it
+/// without a trailing `;`. Comments and quoted text are skipped so a `;`
+/// inside a string doesn't count as a second statement.
+fn ensure_single_select(sql: &str) -> CommandResult<&str> {
+    if sql.chars().count() > MAX_QUERY_CHARS {
+        return Err(BasecampError::validation(format!(
+            "Queries are limited to {MAX_QUERY_CHARS} characters."
+        )));
+    }
+
+    let bytes = sql.as_bytes();
+    let mut first_keyword: Option<&str> = None;
+    let mut statement_end: Option<usize> = None;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let rest = &bytes[index..];
+        if rest.starts_with(b"--") {
+            index = rest
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(bytes.len(), |offset| index + offset + 1);
+            continue;
+        }
+        if rest.starts_with(b"/*") {
+            index = sql[index + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |offset| index + 2 + offset + 2);
+            continue;
+        }
+        if byte.is_ascii_whitespace() {
+            index += 1;
+            continue;
+        }
+        if statement_end.is_some() {
+            return Err(BasecampError::validation(
+                "Only one statement can be run at a time.",
+            ));
+        }
+        match byte {
+            b';' => statement_end = Some(index),
+            b'\'' | b'"' | b'`' | b'[' => {
+                first_keyword.get_or_insert("");
+                let close = if byte == b'[' { b']' } else { byte };
+                let mut cursor = index + 1;
+                loop {
+                    match bytes[cursor..].iter().position(|&b| b == close) {
+                        // SQL escapes a quote by doubling it.
+                        Some(offset)
+                            if close != b']' && bytes.get(cursor + offset + 1) == Some(&close) =>
+                        {
+                            cursor += offset + 2;
+                        }
+                        Some(offset) => {
+                            cursor += offset + 1;
+                            break;
+                        }
+                        None => {
+                            return Err(BasecampError::validation(
+                                "Query has an unterminated quote.",
+                            ));
+                        }
+                    }
+                }
+                index = cursor;
+                continue;
+            }
+            _ if first_keyword.is_none() => {
+                let end = rest
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+                    .map_or(bytes.len(), |offset| index + offset);
+                first_keyword = Some(&sql[index..end]);
+                if end > index {
+                    index = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    match first_keyword {
+        Some(keyword)
+            if ["SELECT", "WITH", "VALUES"]
+                .iter()
+                .any(|allowed| keyword.eq_ignore_ascii_case(allowed)) => {}
+        Some(_) => {
+            return Err(BasecampError::validation(
+                "Only SELECT queries can be run against registered databases.",
+            ));
+        }
+        None => return Err(BasecampError::validation("Query is empty.")),
+    }
+    Ok(sql[..statement_end.unwrap_or(sql.len())].trim())
+}
+
+fn cell_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(number) => Value::from(number),
+        ValueRef::Real(number) => serde_json::Number::from_f64(number)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ValueRef::Text(text) => {
+            let text = String::from_utf8_lossy(text);
+            if text.chars().count() > MAX_CELL_CHARS {
+                let cut: String = text.chars().take(MAX_CELL_CHARS).collect();
+                Value::String(format!("{cut}…"))
+            } else {
+                Value::String(text.into_owned())
+            }
+        }
+        ValueRef::Blob(blob) => Value::String(format!("<blob: {} bytes>", blob.len())),
+    }
+}
+
+fn read_rows(
+    statement: &mut rusqlite::Statement<'_>,
+    column_count: usize,
+    max_rows: usize,
+) -> rusqlite::Result<(Vec<Vec<Value>>, bool)> {
+    let mut rows = Vec::new();
+    let mut result_chars = 0;
+    let mut cursor = statement.query([])?;
+    while let Some(row) = cursor.next()? {
+        if rows.len() >= max_rows || result_chars >= MAX_RESULT_CHARS {
+            return Ok((rows, true));
+        }
+        let values = (0..column_count)
+            .map(|index| row.get_ref(index).map(cell_value))
+            .collect::<Result<Vec<_>, _>>()?;
+        result_chars += values
+            .iter()
+            .map(|value| value.to_string().len())
+            .sum::<usize>();
+        rows.push(values);
+    }
+    Ok((rows, false))
+}
+
+/// Runs a checked query against `path`, interrupting it after
+/// `QUERY_TIMEOUT`.
+fn run_query(
+    path: &Path,
+    database: &str,
+    sql: &str,
+    max_rows: usize,
+) -> CommandResult<QueryResult> {
+    let sql = ensure_single_select(sql)?;
+    let connection = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    connection.pragma_update(None, "query_only", true)?;
+    let mut statement = connection
+        .prepare(sql)
+        .map_err(|err| BasecampError::validation(format!("Query is not valid: {err}")))?;
+    if !statement.readonly() {
+        return Err(BasecampError::validation(
+            "Only read-only queries can be run against registered databases.",
+        ));
+    }
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let interrupt = connection.get_interrupt_handle();
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let watchdog = thread::spawn(move || {
+        if done_rx.recv_timeout(QUERY_TIMEOUT) == Err(mpsc::RecvTimeoutError::Timeout) {
+            interrupt.interrupt();
+        }
+    });
+    let result = read_rows(&mut statement, columns.len(), max_rows);
+    drop(done_tx);
+    let _ = watchdog.join();
+
+    let (rows, truncated) = result.map_err(|err| match err {
+        rusqlite::Error::SqliteFailure(failure, _)
+            if failure.code == ErrorCode::OperationInterrupted =>
+        {
+            BasecampError::unavailable(format!(
+                "Query took longer than {} seconds and was stopped.",
+                QUERY_TIMEOUT.as_secs()
+            ))
+        }
+        err => BasecampError::from(err).with_context("database", database),
+    })?;
+    Ok(QueryResult {
+        database: database.to_string(),
+        columns,
+        row_count: rows.len(),
+        rows,
+        truncated,
+    })
+}
+
+fn load_registered_databases(connection: &Connection) -> CommandResult<Vec<(String, String, i64)>> {
+    let mut statement = connection.prepare(
+        "SELECT name, relative_path, created_at FROM user_databases ORDER BY name COLLATE NOCASE",
+    )?;
+    let rows = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Registers (or re-points) a SQLite file in the workspace under `name`.
+#[tauri::command]
+pub fn register_user_database(
+    window: Window,
+    state: State<'_, AppState>,
+    payload: RegisterUserDatabasePayload,
+) -> CommandResult<UserDatabase> {
+    ensure_main_window(&window)?;
+    let name = normalize_database_name(&payload.name)?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let workspace = require_workspace_path(&connection)?.canonicalize()?;
+    let resolved = resolve_database_file(&workspace, Path::new(payload.path.trim()))?;
+    let relative_path = resolved
+        .strip_prefix(&workspace)
+        .map_err(|_| BasecampError::internal("Database path escaped the workspace."))?
+        .to_string_lossy()
+        .into_owned();
+
+    let created_at = now_timestamp_ms();
+    connection.execute(
+        "INSERT INTO user_databases (name, relative_path, created_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(name) DO UPDATE SET relative_path = excluded.relative_path",
+        params![name, relative_path, created_at],
+    )?;
+    let created_at: i64 = connection.query_row(
+        "SELECT created_at FROM user_databases WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+    Ok(UserDatabase {
+        name,
+        path: relative_path,
+        created_at,
+        available: true,
+    })
+}
+
+#[tauri::command]
+pub fn list_user_databases(state: State<'_, AppState>) -> CommandResult<Vec<UserDatabase>> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let workspace = require_workspace_path(&connection)?;
+    Ok(load_registered_databases(&connection)?
+        .into_iter()
+        .map(|(name, path, created_at)| {
+            let available = resolve_database_file(&workspace, Path::new(&path)).is_ok();
+            UserDatabase {
+                name,
+                path,
+                created_at,
+                available,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn unregister_user_database(
+    window: Window,
+    state: State<'_, AppState>,
+    name: String,
+) -> CommandResult<()> {
+    ensure_main_window(&window)?;
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    connection.execute(
+        "DELETE FROM user_databases WHERE name = ?1",
+        params![name.trim()],
+    )?;
+    Ok(())
+}
+
+/// Backs the `query_database` tool: one read-only SELECT against a
+/// registered database, at most `max_rows` rows (default 100, up to 1000).
+#[tauri::command]
+pub async fn query_user_database(
+    state: State<'_, AppState>,
+    payload: QueryUserDatabasePayload,
+) -> CommandResult<QueryResult> {
+    let max_rows = payload
+        .max_rows
+        .unwrap_or(DEFAULT_MAX_ROWS)
+        .clamp(1, MAX_ROWS_CEILING);
+    let (database, path) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let workspace = require_workspace_path(&connection)?;
+        let name = payload.database.trim();
+        let relative_path: Option<String> = connection
+            .query_row(
+                "SELECT relative_path FROM user_databases WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(relative_path) = relative_path else {
+            let known: Vec<String> = load_registered_databases(&connection)?
+                .into_iter()
+                .map(|(name, _, _)| name)
+                .collect();
+            return Err(BasecampError::not_found(format!(
+                "No database is registered as `{name}`. Registered databases: {}.",
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            )));
+        };
+        (
+            name.to_string(),
+            resolve_database_file(&workspace, Path::new(&relative_path))?,
+        )
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        run_query(&path, &database, &payload.sql, max_rows)
+    })
+    .await
+    .map_err(|err| BasecampError::internal(format!("Query failed: {err}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use serde_json::json;
+
+    #[test]
+    fn run_query_should_only_allow_single_read_only_selects() {
+        assert_eq!(
+            ensure_single_select("  -- totals\nSELECT ';' AS semi; /* done */ ")
+                .expect("query should pass"),
+            "-- totals\nSELECT ';' AS semi"
+        );
+        assert!(ensure_single_select("with t as (select 1) select * from t").is_ok());
+        assert!(ensure_single_select("SELECT 1; DROP TABLE items").is_err());
+        assert!(ensure_single_select("DELETE FROM items").is_err());
+        assert!(ensure_single_select("ATTACH 'x.db' AS x").is_err());
+        assert!(ensure_single_select("SELECT 'open").is_err());
+        assert!(ensure_single_select("  /* nothing */ ").is_err());
+
+        let workspace = make_temp_dir("basecamp-user-db");
+        std::fs::create_dir_all(workspace.join("data")).expect("workspace should be created");
+        let path = workspace.join("data").join("shop.sqlite");
+        let seed = Connection::open(&path).expect("database should open");
+        seed.execute_batch(
+            "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price REAL, image BLOB);
+             INSERT INTO items (name, price, image) VALUES
+                ('tent', 199.5, x'0102'), ('stove', 49.0, NULL), ('lamp', 12.25, NULL);",
+        )
+        .expect("database should be seeded");
+        drop(seed);
+        std::fs::write(workspace.join("notes.txt"), "not a database").expect("file is written");
+
+        let resolved = resolve_database_file(&workspace, Path::new("data/shop.sqlite"))
+            .expect("database inside the workspace should resolve");
+        assert!(resolve_database_file(&workspace, Path::new("notes.txt")).is_err());
+        assert!(resolve_database_file(&workspace.join("data"), Path::new("../notes.txt")).is_err());
+
+        let result = run_query(
+            &resolved,
+            "shop",
+            "SELECT name, price, image FROM items ORDER BY id",
+            2,
+        )
+        .expect("select should run");
+        assert_eq!(result.columns, vec!["name", "price", "image"]);
+        assert_eq!(
+            result.rows,
+            vec![
+                vec![json!("tent"), json!(199.5), json!("<blob: 2 bytes>")],
+                vec![json!("stove"), json!(49.0), Value::Null],
+            ]
+        );
+        assert!(result.truncated);
+
+        assert!(run_query(
+            &resolved,
+            "shop",
+            "WITH gone AS (SELECT 1) DELETE FROM items",
+            10
+        )
+        .is_err());
+        let count: i64 = Connection::open(&resolved)
+            .and_then(|connection| {
+                connection.query_row("SELECT COUNT(*) FROM items", [], |row| row.get(0))
+            })
+            .expect("items should still be readable");
+        assert_eq!(count, 3);
+    }
+}
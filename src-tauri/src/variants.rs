@@ -0,0 +1,503 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+
+use crate::chat_stream::{
+    assistant_message, load_max_continuations, run_payload, stream_chat_turn,
+};
+use crate::context_assembly::assemble_turn_messages;
+use crate::error::{BasecampError, CommandResult};
+use crate::providers::{
+    BasecampChatMetadata, BasecampChatRequest, ChatStreamEvent, ProviderChatResponse,
+    RequestPriority,
+};
+use crate::{
+    camp_transcript_path, ensure_camps_root, load_camp_from_dir, now_timestamp_ms,
+    parse_model_reference, read_json_file, read_transcript, resolve_existing_camp_dir, store_run,
+    touch_camp_updated_at, write_json_file, write_transcript, AppState, CampMessage,
+};
+
+const CAMP_VARIANTS_FILE: &str = "variants.json";
+const CAMP_VARIANTS_SCHEMA_VERSION: &str = "0.1";
+
+/// One candidate reply to a user turn: the assistant message plus any tool
+/// messages that followed it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageVariant {
+    pub id: String,
+    pub messages: Vec<CampMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub created_at: i64,
+}
+
+/// All replies generated for one user message. The selected variant is the
+/// one written to the transcript after that message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantGroup {
+    pub user_message_id: String,
+    pub variants: Vec<MessageVariant>,
+    pub selected_variant_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CampVariantsIndex {
+    schema_version: String,
+    groups: Vec<VariantGroup>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegenerateMessagePayload {
+    pub camp_id: String,
+    /// Defaults to the camp's model.
+    pub model: Option<String>,
+    /// Tool specs the composer would send with this turn.
+    #[serde(default)]
+    pub tools: Option<Vec<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectVariantPayload {
+    pub camp_id: String,
+    pub user_message_id: String,
+    pub variant_id: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegenerateMessageResult {
+    pub run_id: String,
+    pub response: ProviderChatResponse,
+    pub group: VariantGroup,
+}
+
+pub(crate) fn camp_variants_path(camp_dir: &Path) -> PathBuf {
+    camp_dir.join(CAMP_VARIANTS_FILE)
+}
+
+pub(crate) fn read_variants_index(camp_dir: &Path) -> Result<CampVariantsIndex, String> {
+    let path = camp_variants_path(camp_dir);
+    if !path.exists() {
+        return Ok(CampVariantsIndex {
+            schema_version: CAMP_VARIANTS_SCHEMA_VERSION.to_string(),
+            groups: Vec::new(),
+        });
+    }
+
+    read_json_file(&path)
+}
+
+/// Position of the last user message. Only that turn can be regenerated or
+/// switched, since every later message was written in reply to it.
+pub(crate) fn last_user_position(transcript: &[CampMessage]) -> Result<usize, String> {
+    transcript
+        .iter()
+        .rposition(|message| message.role == "user")
+        .ok_or_else(|| "The camp has no user message to regenerate.".to_string())
+}
+
+/// Range of the reply to `user_message_id` in `transcript`, provided it still
+/// holds exactly the messages of `expected`.
+fn turn_tail(
+    transcript: &[CampMessage],
+    user_message_id: &str,
+    expected: &[CampMessage],
+) -> Result<Range<usize>, BasecampError> {
+    transcript
+        .iter()
+        .position(|message| message.id == user_message_id)
+        .map(|position| position + 1..position + 1 + expected.len())
+        .filter(|tail| {
+            transcript.get(tail.clone()).is_some_and(|current| {
+                current
+                    .iter()
+                    .map(|message| &message.id)
+                    .eq(expected.iter().map(|message| &message.id))
+            })
+        })
+        .ok_or_else(|| {
+            BasecampError::validation(
+                "The conversation changed while the reply was generated. Try again.",
+            )
+            .with_context("user_message_id", user_message_id)
+        })
+}
+
+/// Writes `transcript` back with the messages in `tail` replaced by `messages`.
+fn splice_transcript(
+    camp_dir: &Path,
+    transcript: &[CampMessage],
+    tail: Range<usize>,
+    messages: &[CampMessage],
+) -> Result<(), String> {
+    let spliced: Vec<CampMessage> = transcript[..tail.start]
+        .iter()
+        .chain(messages)
+        .chain(&transcript[tail.end..])
+        .cloned()
+        .collect();
+    write_transcript(&camp_transcript_path(camp_dir), &spliced)
+}
+
+/// Adds `variant` to the group for `user_message_id` and selects it. A group
+/// is created on first regeneration, keeping the reply already in the
+/// transcript (`current_tail`) as its first variant.
+pub(crate) fn add_variant(
+    index: &mut CampVariantsIndex,
+    user_message_id: &str,
+    current_tail: &[CampMessage],
+    variant: MessageVariant,
+) -> VariantGroup {
+    let position = match index
+        .groups
+        .iter()
+        .position(|group| group.user_message_id == user_message_id)
+    {
+        Some(position) => position,
+        None => {
+            let mut variants = Vec::new();
+            if !current_tail.is_empty() {
+                variants.push(MessageVariant {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    messages: current_tail.to_vec(),
+                    model: None,
+                    created_at: current_tail[0].created_at,
+                });
+            }
+            index.groups.push(VariantGroup {
+                user_message_id: user_message_id.to_string(),
+                variants,
+                selected_variant_id: String::new(),
+            });
+            index.groups.len() - 1
+        }
+    };
+
+    let group = &mut index.groups[position];
+    group.selected_variant_id = variant.id.clone();
+    group.variants.push(variant);
+    group.clone()
+}
+
+/// Messages of the group's selected variant; empty when it can't be found.
+pub(crate) fn selected_messages(group: &VariantGroup) -> &[CampMessage] {
+    group
+        .variants
+        .iter()
+        .find(|variant| variant.id == group.selected_variant_id)
+        .map(|variant| variant.messages.as_slice())
+        .unwrap_or_default()
+}
+
+/// A message from any variant, including ones no longer in the transcript.
+pub(crate) fn find_variant_message<'a>(
+    index: &'a CampVariantsIndex,
+    message_id: &str,
+) -> Option<&'a CampMessage> {
+    index
+        .groups
+        .iter()
+        .flat_map(|group| &group.variants)
+        .flat_map(|variant| &variant.messages)
+        .find(|message| message.id == message_id)
+}
+
+/// A streaming request for a turn built by `assemble_turn_messages`. `tools`
+/// are listed as the composer lists them, but with `tool_choice` set to
+/// `none`: regenerated and council replies are stored without a tool loop.
+pub(crate) fn turn_request(
+    camp_id: &str,
+    model: &str,
+    messages: Vec<Value>,
+    tools: Option<Vec<Value>>,
+) -> BasecampChatRequest {
+    let (provider_kind, model_id) = parse_model_reference(model);
+    let tool_choice = tools.as_ref().map(|_| json!("none"));
+
+    BasecampChatRequest {
+        provider_kind,
+        model_id,
+        messages,
+        tools,
+        tool_choice,
+        temperature: None,
+        max_tokens: None,
+        top_p: None,
+        stop: None,
+        logit_bias: None,
+        stream: true,
+        metadata: BasecampChatMetadata {
+            camp_id: Some(camp_id.to_string()),
+            correlation_id: Some(uuid::Uuid::new_v4().to_string()),
+            provider_kind: Some(provider_kind),
+            priority: RequestPriority::Interactive,
+        },
+    }
+}
+
+/// Adds `variants` to the group for the turn's user message and writes the
+/// selected one, `selected_id` or else the last added, in place of the reply
+/// the turn had when it was read (`previous_tail`). The transcript is re-read
+/// here, so messages appended during generation stay after the new reply; if
+/// the turn itself changed, nothing is written. Callers hold the connection
+/// lock, which the append paths take as well.
+pub(crate) fn commit_variants(
+    camp_dir: &Path,
+    user_message_id: &str,
+    previous_tail: &[CampMessage],
+    variants: Vec<MessageVariant>,
+    selected_id: Option<&str>,
+) -> Result<VariantGroup, BasecampError> {
+    let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+    let tail = turn_tail(&transcript, user_message_id, previous_tail)?;
+
+    let mut index = read_variants_index(camp_dir)?;
+    let mut group = None;
+    for variant in variants {
+        group = Some(add_variant(
+            &mut index,
+            user_message_id,
+            previous_tail,
+            variant,
+        ));
+    }
+    let Some(mut group) = group else {
+        return Err(BasecampError::internal("No variant to add."));
+    };
+    if let Some(selected_id) = selected_id {
+        if let Some(stored) = index
+            .groups
+            .iter_mut()
+            .find(|stored| stored.user_message_id == user_message_id)
+        {
+            stored.selected_variant_id = selected_id.to_string();
+        }
+        group.selected_variant_id = selected_id.to_string();
+    }
+
+    splice_transcript(camp_dir, &transcript, tail, selected_messages(&group))?;
+    write_json_file(&camp_variants_path(camp_dir), &index)?;
+    Ok(group)
+}
+
+/// Re-runs the last user turn and stores the reply as a new variant beside
+/// the existing one, which is kept. The new variant is selected, so it is the
+/// one the conversation continues from.
+#[tauri::command]
+pub async fn regenerate_message(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    payload: RegenerateMessagePayload,
+    on_event: Channel<ChatStreamEvent>,
+) -> CommandResult<RegenerateMessageResult> {
+    let (camp_dir, max_continuations, model, turn, request) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let mut camp = load_camp_from_dir(&camp_dir)?;
+        let position = last_user_position(&camp.transcript).map_err(BasecampError::validation)?;
+        let model = payload
+            .model
+            .clone()
+            .unwrap_or_else(|| camp.config.model.clone());
+        let messages = assemble_turn_messages(&connection, &camp_dir, &camp, &model, position)?;
+        let request = turn_request(&payload.camp_id, &model, messages, payload.tools);
+        let turn = camp.transcript.split_off(position);
+        let max_continuations = load_max_continuations(&connection)?;
+        (camp_dir, max_continuations, model, turn, request)
+    };
+    let (outcome, _) = stream_chat_turn(&state, &request, on_event, max_continuations).await;
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    store_run(
+        &app,
+        &state,
+        run_payload(&run_id, &payload.camp_id, &request, None, outcome.as_ref()),
+    )?;
+    let response = outcome?;
+
+    let reply = assistant_message(&response, &run_id);
+    let variant = MessageVariant {
+        id: uuid::Uuid::new_v4().to_string(),
+        messages: vec![reply],
+        model: Some(model),
+        created_at: now_timestamp_ms(),
+    };
+    let group = {
+        let _connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        commit_variants(&camp_dir, &turn[0].id, &turn[1..], vec![variant], None)?
+    };
+    touch_camp_updated_at(&camp_dir)?;
+
+    Ok(RegenerateMessageResult {
+        run_id,
+        response,
+        group,
+    })
+}
+
+/// Makes `variant_id` the reply the conversation continues from by writing
+/// its messages back after the last user message. Callers hold the
+/// connection lock.
+fn select_variant_in_camp(
+    camp_dir: &Path,
+    user_message_id: &str,
+    variant_id: &str,
+) -> Result<VariantGroup, BasecampError> {
+    let transcript = read_transcript(&camp_transcript_path(camp_dir))?;
+    let position = last_user_position(&transcript).map_err(BasecampError::validation)?;
+    if transcript[position].id != user_message_id {
+        return Err(
+            BasecampError::validation("Only the latest turn's variants can be selected.")
+                .with_context("user_message_id", user_message_id),
+        );
+    }
+
+    let mut index = read_variants_index(camp_dir)?;
+    let group = index
+        .groups
+        .iter_mut()
+        .find(|group| group.user_message_id == user_message_id)
+        .ok_or_else(|| {
+            BasecampError::not_found("This message has no variants.")
+                .with_context("user_message_id", user_message_id)
+        })?;
+    let variant = group
+        .variants
+        .iter()
+        .find(|variant| variant.id == variant_id)
+        .ok_or_else(|| {
+            BasecampError::not_found("Variant not found.").with_context("variant_id", variant_id)
+        })?;
+
+    splice_transcript(
+        camp_dir,
+        &transcript,
+        position + 1..transcript.len(),
+        &variant.messages,
+    )?;
+    group.selected_variant_id = variant_id.to_string();
+    let group = group.clone();
+    write_json_file(&camp_variants_path(camp_dir), &index)?;
+    Ok(group)
+}
+
+#[tauri::command]
+pub fn select_variant(
+    state: State<'_, AppState>,
+    payload: SelectVariantPayload,
+) -> CommandResult<VariantGroup> {
+    let (camp_dir, group) = {
+        let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        let camp_dir = resolve_existing_camp_dir(&camps_root, &payload.camp_id)?;
+        let group =
+            select_variant_in_camp(&camp_dir, &payload.user_message_id, &payload.variant_id)?;
+        (camp_dir, group)
+    };
+    touch_camp_updated_at(&camp_dir)?;
+    Ok(group)
+}
+
+#[tauri::command]
+pub fn list_message_variants(
+    state: State<'_, AppState>,
+    camp_id: String,
+) -> CommandResult<Vec<VariantGroup>> {
+    let connection = state.connection.lock().map_err(|_| BasecampError::lock())?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let camp_dir = resolve_existing_camp_dir(&camps_root, &camp_id)?;
+    Ok(read_variants_index(&camp_dir)?.groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::append_transcript_message;
+    use crate::test_support::make_temp_dir;
+
+    #[test]
+    fn variants_should_keep_the_original_reply_and_switch_the_transcript_tail() {
+        let camp_dir = make_temp_dir("basecamp-variants");
+        let transcript_path = camp_transcript_path(&camp_dir);
+        for entry in [
+            CampMessage::for_test("u1", "user", "Name a color"),
+            CampMessage::for_test("a1", "assistant", "Blue"),
+        ] {
+            append_transcript_message(&transcript_path, &entry).expect("message should append");
+        }
+
+        let transcript = read_transcript(&transcript_path).expect("transcript loads");
+        let position = last_user_position(&transcript).expect("user message exists");
+        let mut index = read_variants_index(&camp_dir).expect("empty index");
+        let regenerated = MessageVariant {
+            id: "v2".to_string(),
+            messages: vec![CampMessage::for_test("a2", "assistant", "Green")],
+            model: None,
+            created_at: 1,
+        };
+        let group = add_variant(&mut index, "u1", &transcript[position + 1..], regenerated);
+        assert_eq!(group.variants.len(), 2);
+        assert_eq!(group.variants[0].messages[0].id, "a1");
+        assert_eq!(group.selected_variant_id, "v2");
+        write_json_file(&camp_variants_path(&camp_dir), &index).expect("index saves");
+
+        let original_id = group.variants[0].id.clone();
+        let group = select_variant_in_camp(&camp_dir, "u1", &original_id).expect("variant selects");
+        assert_eq!(group.selected_variant_id, original_id);
+        let transcript = read_transcript(&transcript_path).expect("transcript loads");
+        let ids: Vec<&str> = transcript.iter().map(|entry| entry.id.as_str()).collect();
+        assert_eq!(ids, vec!["u1", "a1"]);
+        assert!(select_variant_in_camp(&camp_dir, "missing", "v2").is_err());
+    }
+
+    #[test]
+    fn commit_variants_should_keep_messages_appended_during_generation() {
+        let camp_dir = make_temp_dir("basecamp-variants-commit");
+        let transcript_path = camp_transcript_path(&camp_dir);
+        for entry in [
+            CampMessage::for_test("u1", "user", "Name a color"),
+            CampMessage::for_test("a1", "assistant", "Blue"),
+        ] {
+            append_transcript_message(&transcript_path, &entry).expect("message should append");
+        }
+        let turn = read_transcript(&transcript_path).expect("transcript loads");
+        append_transcript_message(
+            &transcript_path,
+            &CampMessage::for_test("q1", "user", "Captured note"),
+        )
+        .expect("capture should append");
+
+        let variant = |id: &str, reply_id: &str| MessageVariant {
+            id: id.to_string(),
+            messages: vec![CampMessage::for_test(reply_id, "assistant", "Green")],
+            model: None,
+            created_at: 1,
+        };
+        let group = commit_variants(&camp_dir, "u1", &turn[1..], vec![variant("v2", "a2")], None)
+            .expect("variant commits");
+        assert_eq!(group.selected_variant_id, "v2");
+        let ids: Vec<String> = read_transcript(&transcript_path)
+            .expect("transcript loads")
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, vec!["u1", "a2", "q1"]);
+
+        // The reply read before generation (`a1`) is gone, so the turn changed.
+        assert!(
+            commit_variants(&camp_dir, "u1", &turn[1..], vec![variant("v3", "a3")], None).is_err()
+        );
+        let ids: Vec<String> = read_transcript(&transcript_path)
+            .expect("transcript loads")
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, vec!["u1", "a2", "q1"]);
+        let index = read_variants_index(&camp_dir).expect("index loads");
+        assert_eq!(index.groups[0].variants.len(), 2);
+    }
+}
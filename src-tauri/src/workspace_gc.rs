@@ -0,0 +1,312 @@
+//! `workspace_gc`: finds data nothing points at any more. It looks for
+//! workspace context files no camp has attached, artifact markdown files
+//! missing from their camp's index, `tool_calls` rows whose run is gone, and
+//! inspect bundles left in folders whose camp was deleted. It reports them by
+//! default and removes them when asked. Orphaned artifact files go to the
+//! camp's trash rather than being deleted, so they can still be restored.
+//! Context files are user documents, so only the ones picked in the report
+//! are touched, and they go to the workspace trash.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::artifact_index::{file_timestamp_ms, recover_metadata};
+use crate::inspect::DEBUG_DIR_TOP;
+use crate::{
+    camp_artifacts_dir, camp_artifacts_index_path, camp_config_path, camp_context_dir,
+    ensure_camps_root, ensure_main_window, list_context_files_recursive, move_to_trash,
+    read_json_file, require_workspace_path, validate_artifact_filename, workspace_context_dir,
+    AppState, CampArtifactsIndex, CampTrashKind, CAMP_ARTIFACTS_DIR, CAMP_ARTIFACTS_INDEX_FILE,
+    CAMP_CONTEXT_DIR,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrphanedArtifactFile {
+    /// Camp folder name.
+    pub camp_id: String,
+    pub filename: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct WorkspaceGcReport {
+    /// Whether the orphans below were removed or only found.
+    pub cleaned: bool,
+    /// Workspace context files (relative to `context/`) no camp has attached.
+    pub unreferenced_context_files: Vec<String>,
+    /// The picked unreferenced context files, moved to the workspace trash.
+    pub trashed_context_files: Vec<String>,
+    pub orphaned_artifact_files: Vec<OrphanedArtifactFile>,
+    /// Ids of `tool_calls` rows whose run no longer exists.
+    pub orphaned_tool_calls: Vec<String>,
+    /// Camp folders that lost their `camp.json` but still hold inspect bundles.
+    pub stale_inspect_dirs: Vec<String>,
+}
+
+fn list_context_files(context_dir: &Path) -> Result<Vec<String>, String> {
+    if !context_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let context_root = fs::canonicalize(context_dir)
+        .map_err(|err| format!("Unable to resolve context directory: {err}"))?;
+    let mut entries = Vec::new();
+    list_context_files_recursive(&context_root, &context_root, &mut entries)?;
+    Ok(entries)
+}
+
+/// Markdown files in the artifacts folder that the index does not list. A
+/// camp whose index cannot be read is skipped; `camp_rebuild_artifact_index`
+/// is the tool for that.
+fn orphaned_artifact_files(camp_dir: &Path) -> Vec<String> {
+    let Ok(index) = read_json_file::<CampArtifactsIndex>(&camp_artifacts_index_path(camp_dir))
+    else {
+        return Vec::new();
+    };
+    let indexed: HashSet<String> = index
+        .artifacts
+        .into_iter()
+        .map(|artifact| artifact.filename)
+        .collect();
+    let Ok(entries) = fs::read_dir(camp_artifacts_dir(camp_dir)) else {
+        return Vec::new();
+    };
+    let mut orphans: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(ToString::to_string))
+        .filter(|filename| {
+            !filename.starts_with('.')
+                && filename != CAMP_ARTIFACTS_INDEX_FILE
+                && validate_artifact_filename(filename).is_ok()
+                && !indexed.contains(filename)
+        })
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+fn orphaned_tool_call_ids(connection: &Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        "SELECT id FROM tool_calls
+         WHERE run_id NOT IN (SELECT id FROM runs)
+         ORDER BY started_at",
+    )?;
+    let rows = statement.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+fn trash_orphaned_artifact(camp_dir: &Path, filename: &str) -> Result<(), String> {
+    let path = camp_artifacts_dir(camp_dir).join(filename);
+    let markdown = fs::read_to_string(&path).unwrap_or_default();
+    let modified_at = fs::metadata(&path)
+        .ok()
+        .and_then(|metadata| file_timestamp_ms(metadata.modified()))
+        .unwrap_or(0);
+    let metadata = recover_metadata(
+        filename,
+        &markdown,
+        modified_at,
+        modified_at,
+        &HashSet::new(),
+    );
+    move_to_trash(
+        camp_dir,
+        &path,
+        CampTrashKind::Artifact,
+        format!("{CAMP_ARTIFACTS_DIR}/{filename}"),
+        Some(metadata),
+    )?;
+    Ok(())
+}
+
+/// Moves the picked context files into the workspace trash, from where they
+/// restore like a camp's trashed context files. Only paths that are still
+/// unreferenced are moved; everything not picked stays where it is.
+fn trash_context_files(
+    workspace_path: &Path,
+    unreferenced: &[String],
+    picked: &[String],
+) -> Result<Vec<String>, String> {
+    let context_dir = workspace_context_dir(workspace_path);
+    let mut trashed = Vec::new();
+    for relative in unreferenced {
+        if !picked.contains(relative) {
+            continue;
+        }
+        move_to_trash(
+            workspace_path,
+            &context_dir.join(relative),
+            CampTrashKind::ContextFile,
+            format!("{CAMP_CONTEXT_DIR}/{relative}"),
+            None,
+        )
+        .map_err(|err| format!("Unable to trash context file {relative}: {err}"))?;
+        trashed.push(relative.clone());
+    }
+    Ok(trashed)
+}
+
+/// Reports orphaned workspace data, and removes it when `clean` is set.
+/// Unreferenced context files are only moved when listed in `context_files`.
+#[tauri::command]
+pub fn workspace_gc(
+    window: Window,
+    state: State<'_, AppState>,
+    clean: bool,
+    context_files: Option<Vec<String>>,
+) -> Result<WorkspaceGcReport, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let camps_root = ensure_camps_root(&connection)?;
+    let mut report = WorkspaceGcReport {
+        cleaned: clean,
+        ..WorkspaceGcReport::default()
+    };
+
+    let mut camp_dirs: Vec<_> = fs::read_dir(&camps_root)
+        .map_err(|err| format!("Unable to read camps folder: {err}"))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    camp_dirs.sort();
+
+    let mut attached_context = HashSet::new();
+    for camp_dir in &camp_dirs {
+        let folder = camp_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if !camp_config_path(camp_dir).exists() {
+            if camp_dir.join(DEBUG_DIR_TOP).is_dir() {
+                report.stale_inspect_dirs.push(folder);
+            }
+            continue;
+        }
+        attached_context.extend(list_context_files(&camp_context_dir(camp_dir))?);
+        for filename in orphaned_artifact_files(camp_dir) {
+            report.orphaned_artifact_files.push(OrphanedArtifactFile {
+                camp_id: folder.clone(),
+                filename,
+            });
+        }
+    }
+
+    let workspace_context = workspace_context_dir(&workspace_path);
+    let mut workspace_files = list_context_files(&workspace_context)?;
+    workspace_files.sort();
+    report.unreferenced_context_files = workspace_files
+        .into_iter()
+        .filter(|path| !attached_context.contains(path))
+        .collect();
+    report.orphaned_tool_calls = orphaned_tool_call_ids(&connection)
+        .map_err(|err| format!("Unable to query tool calls: {err}"))?;
+
+    if !clean {
+        return Ok(report);
+    }
+
+    report.trashed_context_files = trash_context_files(
+        &workspace_path,
+        &report.unreferenced_context_files,
+        &context_files.unwrap_or_default(),
+    )?;
+    for orphan in &report.orphaned_artifact_files {
+        trash_orphaned_artifact(&camps_root.join(&orphan.camp_id), &orphan.filename)?;
+    }
+    connection
+        .execute(
+            "DELETE FROM tool_calls WHERE run_id NOT IN (SELECT id FROM runs)",
+            [],
+        )
+        .map_err(|err| format!("Unable to delete orphaned tool calls: {err}"))?;
+    for folder in &report.stale_inspect_dirs {
+        let camp_dir = camps_root.join(folder);
+        fs::remove_dir_all(camp_dir.join(DEBUG_DIR_TOP))
+            .map_err(|err| format!("Unable to remove inspect bundles in {folder}: {err}"))?;
+        // Only succeeds when nothing else was left in the folder.
+        let _ = fs::remove_dir(&camp_dir);
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::make_temp_dir;
+    use crate::{read_trash_index, restore_trash_entry};
+
+    #[test]
+    fn workspace_gc_should_find_unindexed_artifacts_and_runless_tool_calls() {
+        let camp_dir = make_temp_dir("basecamp-gc");
+        let artifacts_dir = camp_artifacts_dir(&camp_dir);
+        fs::create_dir_all(&artifacts_dir).expect("artifacts dir");
+        fs::write(
+            camp_artifacts_index_path(&camp_dir),
+            r#"{"schema_version":"0.1","artifacts":[{"id":"a","title":"A","filename":"a.md","source_message_id":"","source_role":"user","tags":[],"created_at":1,"updated_at":1,"usage_count":0}]}"#,
+        )
+        .expect("index");
+        fs::write(artifacts_dir.join("a.md"), "# A").expect("indexed");
+        fs::write(artifacts_dir.join("b.md"), "# B").expect("orphan");
+        fs::write(artifacts_dir.join(".a.md.tmp"), "").expect("temp");
+        assert_eq!(orphaned_artifact_files(&camp_dir), vec!["b.md"]);
+
+        let connection = Connection::open_in_memory().expect("in-memory db should open");
+        connection
+            .execute_batch(
+                "CREATE TABLE runs (id TEXT PRIMARY KEY);
+                 CREATE TABLE tool_calls (id TEXT PRIMARY KEY, run_id TEXT NOT NULL, started_at INTEGER NOT NULL);
+                 INSERT INTO runs (id) VALUES ('run-1');
+                 INSERT INTO tool_calls VALUES ('call-1', 'run-1', 1), ('call-2', 'run-gone', 2);",
+            )
+            .expect("schema");
+        assert_eq!(
+            orphaned_tool_call_ids(&connection).expect("query"),
+            vec!["call-2"]
+        );
+    }
+
+    #[test]
+    fn trash_context_files_should_only_move_picked_files_and_keep_them_restorable() {
+        let workspace = make_temp_dir("basecamp-gc-context");
+        let context_dir = workspace_context_dir(&workspace);
+        fs::create_dir_all(context_dir.join("notes")).expect("context dir");
+        fs::write(context_dir.join("keep.md"), "not picked").expect("keep");
+        fs::write(context_dir.join("notes/old.md"), "picked").expect("old");
+        let unreferenced = vec!["keep.md".to_string(), "notes/old.md".to_string()];
+
+        let trashed = trash_context_files(
+            &workspace,
+            &unreferenced,
+            &["notes/old.md".to_string(), "../outside.md".to_string()],
+        )
+        .expect("trash");
+        assert_eq!(trashed, vec!["notes/old.md"]);
+        assert_eq!(
+            fs::read_to_string(context_dir.join("keep.md")).expect("unpicked file survives"),
+            "not picked"
+        );
+        assert!(!context_dir.join("notes/old.md").exists());
+
+        let entry = read_trash_index(&workspace)
+            .expect("trash index")
+            .entries
+            .pop()
+            .expect("trash entry");
+        assert_eq!(entry.original_path, "context/notes/old.md");
+        restore_trash_entry(&workspace, &entry.id).expect("restore");
+        assert_eq!(
+            fs::read_to_string(context_dir.join("notes/old.md")).expect("restored"),
+            "picked"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! `INDEX.md` at the top of the workspace: a plain markdown table of every
+//! camp, so the folder can be browsed from a file manager, an editor or a
+//! synced share without opening Basecamp.
+
+use std::fs;
+
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::{
+    camp_artifacts_index_path, camp_config_path, camp_transcript_path, ensure_camps_root,
+    ensure_main_window, now_timestamp_ms, read_camp_config, read_json_file, read_transcript,
+    require_workspace_path, write_file_atomic, AppState, CampArtifactsIndex, CAMPS_DIR_NAME,
+};
+
+const WORKSPACE_INDEX_FILE: &str = "INDEX.md";
+
+#[derive(Debug, Clone, PartialEq)]
+struct CampIndexEntry {
+    folder: String,
+    name: String,
+    description: Option<String>,
+    last_activity_at: i64,
+    artifact_count: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceManifestResult {
+    pub path: String,
+    pub camp_count: usize,
+}
+
+pub(crate) fn format_utc(timestamp_ms: i64) -> String {
+    match time::OffsetDateTime::from_unix_timestamp(timestamp_ms.div_euclid(1_000)) {
+        Ok(moment) => format!(
+            "{} {:02}:{:02} UTC",
+            moment.date(),
+            moment.hour(),
+            moment.minute()
+        ),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+/// Keeps a value on one line and out of the table syntax.
+fn table_cell(value: &str) -> String {
+    value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn render_index(entries: &[CampIndexEntry], generated_at: i64) -> String {
+    let mut markdown = format!(
+        "# Basecamp Workspace\n\n{} camp(s). Generated by Basecamp on {}; regenerate it from Settings rather than editing it by hand.\n\n",
+        entries.len(),
+        format_utc(generated_at)
+    );
+    if entries.is_empty() {
+        markdown.push_str("No camps yet.\n");
+        return markdown;
+    }
+
+    markdown.push_str("| Camp | Description | Last activity | Artifacts |\n");
+    markdown.push_str("| --- | --- | --- | ---: |\n");
+    for entry in entries {
+        // Folder names come from `allocate_camp_folder_id`, but spaces are
+        // still escaped so the link stays valid.
+        let link = format!("{CAMPS_DIR_NAME}/{}/", entry.folder.replace(' ', "%20"));
+        markdown.push_str(&format!(
+            "| [{}]({link}) | {} | {} | {} |\n",
+            table_cell(&entry.name).replace(|c| c == '[' || c == ']', ""),
+            table_cell(entry.description.as_deref().unwrap_or("")),
+            format_utc(entry.last_activity_at),
+            entry.artifact_count
+        ));
+    }
+    markdown
+}
+
+/// Writes `INDEX.md` to the workspace folder, listing each camp with its
+/// description, last activity and number of live artifacts.
+#[tauri::command]
+pub fn generate_workspace_manifest(
+    window: Window,
+    state: State<'_, AppState>,
+) -> Result<WorkspaceManifestResult, String> {
+    ensure_main_window(&window)?;
+    let connection = state
+        .connection
+        .lock()
+        .map_err(|_| "Database lock error".to_string())?;
+    let workspace_path = require_workspace_path(&connection)?;
+    let camps_root = ensure_camps_root(&connection)?;
+
+    let mut entries = Vec::new();
+    let dirs =
+        fs::read_dir(&camps_root).map_err(|err| format!("Unable to read camps folder: {err}"))?;
+    for camp_dir in dirs.flatten().map(|entry| entry.path()) {
+        if !camp_dir.is_dir() || !camp_config_path(&camp_dir).exists() {
+            continue;
+        }
+        let Ok(config) = read_camp_config(&camp_dir) else {
+            continue;
+        };
+        let Some(folder) = camp_dir.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let last_message_at = read_transcript(&camp_transcript_path(&camp_dir))
+            .ok()
+            .and_then(|messages| messages.iter().map(|message| message.created_at).max());
+        let artifact_count =
+            read_json_file::<CampArtifactsIndex>(&camp_artifacts_index_path(&camp_dir))
+                .map(|index| {
+                    index
+                        .artifacts
+                        .iter()
+                        .filter(|artifact| !artifact.archived)
+                        .count()
+                })
+                .unwrap_or(0);
+        entries.push(CampIndexEntry {
+            folder: folder.to_string(),
+            name: config.name,
+            description: config.description,
+            last_activity_at: last_message_at.unwrap_or(0).max(config.updated_at),
+            artifact_count,
+        });
+    }
+    entries.sort_by(|left, right| {
+        right
+            .last_activity_at
+            .cmp(&left.last_activity_at)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+
+    let index_path = workspace_path.join(WORKSPACE_INDEX_FILE);
+    write_file_atomic(
+        &index_path,
+        render_index(&entries, now_timestamp_ms()).as_bytes(),
+    )?;
+    Ok(WorkspaceManifestResult {
+        path: index_path.to_string_lossy().into_owned(),
+        camp_count: entries.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_index_should_list_camps_as_a_markdown_table() {
+        let entries = vec![CampIndexEntry {
+            folder: "research-notes".to_string(),
+            name: "Research | Notes".to_string(),
+            description: Some("Papers\nand links".to_string()),
+            last_activity_at: 1_714_572_180_000,
+            artifact_count: 4,
+        }];
+
+        let markdown = render_index(&entries, 1_714_572_180_000);
+        assert!(markdown.starts_with("# Basecamp Workspace\n\n1 camp(s)."));
+        assert!(markdown.contains(
+            "| [Research \\| Notes](camps/research-notes/) | Papers and links | 2024-05-01 14:03 UTC | 4 |\n"
+        ));
+        assert!(render_index(&[], 0).ends_with("No camps yet.\n"));
+    }
+}
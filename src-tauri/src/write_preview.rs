@@ -0,0 +1,175 @@
+//! Diff previews for agent file writes. Camps with `preview_file_writes` on
+//! hold every `write_file` tool call in the approval queue with a unified diff
+//! against the file on disk, and only write it once the user approves.
+
+use std::fs;
+
+use serde::Serialize;
+use tauri::{State, Window};
+
+use crate::prompt_versions::bounded_unified_diff;
+use crate::{
+    camp_context_dir, canonicalize_context_root, ensure_camps_root, ensure_main_window,
+    ensure_path_within_root, resolve_existing_camp_dir, validate_context_relative_path, AppState,
+};
+
+/// Largest diff table a preview may build (4 bytes per entry). Changed regions
+/// beyond it get a size summary instead of a diff.
+const WRITE_PREVIEW_MAX_DIFF_CELLS: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextWritePreview {
+    pub path: String,
+    /// False when the write would create the file.
+    pub exists: bool,
+    /// Binary writes, or text over a binary file, get no diff.
+    pub binary: bool,
+    /// The change was too large to diff, so `diff` is empty.
+    pub too_large: bool,
+    /// Unified diff from the current file to the proposed one; empty when the
+    /// write would change nothing.
+    pub diff: String,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+fn build_write_preview(
+    path: &str,
+    existing: Option<&[u8]>,
+    proposed: &[u8],
+) -> ContextWritePreview {
+    let before = existing.map(std::str::from_utf8);
+    let after = std::str::from_utf8(proposed);
+    let (binary, diff) = match (before, after) {
+        (None, Ok(after)) => (
+            false,
+            bounded_unified_diff(
+                "",
+                after,
+                "/dev/null",
+                &format!("b/{path}"),
+                WRITE_PREVIEW_MAX_DIFF_CELLS,
+            ),
+        ),
+        (Some(Ok(before)), Ok(after)) => (
+            false,
+            bounded_unified_diff(
+                before,
+                after,
+                &format!("a/{path}"),
+                &format!("b/{path}"),
+                WRITE_PREVIEW_MAX_DIFF_CELLS,
+            ),
+        ),
+        _ => (true, Some(String::new())),
+    };
+    ContextWritePreview {
+        path: path.to_string(),
+        exists: existing.is_some(),
+        binary,
+        too_large: diff.is_none(),
+        diff: diff.unwrap_or_default(),
+        bytes_before: existing.map_or(0, |bytes| bytes.len() as u64),
+        bytes_after: proposed.len() as u64,
+    }
+}
+
+/// Diffs a proposed `write_file` against the camp's context folder without
+/// writing anything. `encoding` is `base64` for binary content, as in the tool.
+#[tauri::command]
+pub fn camp_preview_context_file_write(
+    window: Window,
+    state: State<'_, AppState>,
+    camp_id: String,
+    path: String,
+    content: String,
+    encoding: Option<String>,
+) -> Result<ContextWritePreview, String> {
+    ensure_main_window(&window)?;
+    // Reading and diffing the file can be slow; keep the database free meanwhile.
+    let camp_dir = {
+        let connection = state
+            .connection
+            .lock()
+            .map_err(|_| "Database lock error".to_string())?;
+        let camps_root = ensure_camps_root(&connection)?;
+        resolve_existing_camp_dir(&camps_root, &camp_id)?
+    };
+    let context_root = canonicalize_context_root(&camp_context_dir(&camp_dir))?;
+    let relative = validate_context_relative_path(&path, "path", false)?;
+
+    let proposed = if encoding.as_deref() == Some("base64") {
+        use base64::{engine::general_purpose, Engine as _};
+        general_purpose::STANDARD
+            .decode(content)
+            .map_err(|err| format!("Invalid base64 encoding: {err}"))?
+    } else {
+        content.into_bytes()
+    };
+
+    let target = context_root.join(&relative);
+    let existing = if target.exists() {
+        let canonical_target = fs::canonicalize(&target)
+            .map_err(|err| format!("Unable to resolve destination path: {err}"))?;
+        ensure_path_within_root(&context_root, &canonical_target)?;
+        if canonical_target.is_dir() {
+            return Err("Requested path is a directory.".to_string());
+        }
+        Some(
+            fs::read(&canonical_target)
+                .map_err(|err| format!("Unable to read context file: {err}"))?,
+        )
+    } else {
+        None
+    };
+
+    let display = relative.to_string_lossy().replace('\\', "/");
+    Ok(build_write_preview(
+        &display,
+        existing.as_deref(),
+        &proposed,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_write_preview_should_diff_text_and_skip_binary() {
+        let created = build_write_preview("notes.md", None, b"one\n");
+        assert!(!created.exists);
+        assert_eq!(
+            created.diff,
+            "--- /dev/null\n+++ b/notes.md\n@@ -0,0 +1,1 @@\n+one\n"
+        );
+
+        let edited = build_write_preview("notes.md", Some(&b"one\ntwo\n"[..]), b"one\n2\n");
+        assert_eq!(
+            edited.diff,
+            "--- a/notes.md\n+++ b/notes.md\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n"
+        );
+        assert!(build_write_preview("notes.md", Some(&b"same"[..]), b"same")
+            .diff
+            .is_empty());
+
+        let binary = build_write_preview("logo.png", Some(&[0xff, 0xd8][..]), &[0x89, 0x50]);
+        assert!(binary.binary);
+        assert!(binary.diff.is_empty());
+        assert_eq!((binary.bytes_before, binary.bytes_after), (2, 2));
+
+        let lines = |prefix: &str| {
+            (0..2100)
+                .map(|index| format!("{prefix} {index}\n"))
+                .collect::<String>()
+        };
+        let rewritten = build_write_preview(
+            "big.md",
+            Some(lines("old").as_bytes()),
+            lines("new").as_bytes(),
+        );
+        assert!(rewritten.too_large);
+        assert!(!rewritten.binary);
+        assert!(rewritten.diff.is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-import { Navigate, Route, Routes } from 'react-router-dom';
+import { Navigate, Route, Routes, useNavigate } from 'react-router-dom';
 
 import { HomeView } from './views/HomeView';
 import { MainLayout } from './views/MainLayout';
@@ -6,13 +6,56 @@ import { ArenaView } from './views/ArenaView';
 import { SettingsView } from './views/SettingsView';
 import { ErrorBoundary } from './ErrorBoundary';
 import { WebGLBackground } from './components/WebGLBackground';
+import { RunDetail } from './components/RunDetail';
 import { getWebGLEnabled } from './lib/db';
+import { deepLinkRoute, listenDeepLinks, takePendingDeepLink } from './lib/deepLink';
+import { getInitStatus, listenStartupProgress } from './lib/startup';
+import { listenTrayActions } from './lib/tray';
+import type { InitStatus } from './lib/types';
 import { useEffect, useState } from 'react';
 
 export default function App() {
   const [webglEnabled, setWebglEnabled] = useState(false);
+  const [initStatus, setInitStatus] = useState<InitStatus | null>(null);
+  const ready = initStatus?.stage === 'ready';
+  const navigate = useNavigate();
 
   useEffect(() => {
+    // The database opens in the background; commands that need it fail until
+    // startup reports ready.
+    let isDisposed = false;
+    let dispose: (() => void) | null = null;
+
+    void listenStartupProgress((status) => {
+      if (!isDisposed) {
+        setInitStatus(status);
+      }
+    }).then((unlisten) => {
+      if (isDisposed) {
+        unlisten();
+        return;
+      }
+      dispose = unlisten;
+      return getInitStatus().then((status) => {
+        if (!isDisposed) {
+          setInitStatus((previous) => (previous && previous.step > status.step ? previous : status));
+        }
+      });
+    });
+
+    return () => {
+      isDisposed = true;
+      if (dispose) {
+        dispose();
+      }
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!ready) {
+      return;
+    }
+
     // Load initial state
     getWebGLEnabled().then(setWebglEnabled).catch(() => { });
 
@@ -23,7 +66,83 @@ export default function App() {
 
     window.addEventListener('webgl_enabled_changed', handleWebglChange);
     return () => window.removeEventListener('webgl_enabled_changed', handleWebglChange);
-  }, []);
+  }, [ready]);
+
+  useEffect(() => {
+    if (!ready) {
+      return;
+    }
+
+    let isDisposed = false;
+    let dispose: (() => void) | null = null;
+
+    void takePendingDeepLink()
+      .then((target) => {
+        if (target && !isDisposed) {
+          navigate(deepLinkRoute(target));
+        }
+      })
+      .catch(() => { });
+
+    void listenDeepLinks((target) => {
+      if (!isDisposed) {
+        navigate(deepLinkRoute(target));
+      }
+    }).then((unlisten) => {
+      if (isDisposed) {
+        unlisten();
+        return;
+      }
+      dispose = unlisten;
+    });
+
+    return () => {
+      isDisposed = true;
+      if (dispose) {
+        dispose();
+      }
+    };
+  }, [ready, navigate]);
+
+  useEffect(() => {
+    let isDisposed = false;
+    let dispose: (() => void) | null = null;
+
+    // New camps are created from the home screen form.
+    void listenTrayActions((action) => {
+      if (!isDisposed && action.action === 'new_camp') {
+        navigate('/home');
+      }
+    }).then((unlisten) => {
+      if (isDisposed) {
+        unlisten();
+        return;
+      }
+      dispose = unlisten;
+    });
+
+    return () => {
+      isDisposed = true;
+      if (dispose) {
+        dispose();
+      }
+    };
+  }, [navigate]);
+
+  if (!ready) {
+    return (
+      <main className="startup-status">
+        {initStatus?.stage === 'failed' ? (
+          <>
+            <h1>{initStatus.message}</h1>
+            <p className="inline-error">{initStatus.error}</p>
+          </>
+        ) : (
+          <p className="inline-status">{initStatus?.message ?? 'Starting Basecamp…'}</p>
+        )}
+      </main>
+    );
+  }
 
   return (
     <>
@@ -36,6 +155,7 @@ export default function App() {
           </ErrorBoundary>
         } />
         <Route path="/arena" element={<ArenaView />} />
+        <Route path="/runs/:id" element={<RunDetail />} />
         <Route path="/settings" element={<SettingsView />} />
         <Route path="*" element={<Navigate to="/home" replace />} />
       </Routes>
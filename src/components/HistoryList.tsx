@@ -1,7 +1,11 @@
-import type { Run } from '../lib/types';
+import { Fragment, useState } from 'react';
+
+import type { Run, RunGroup } from '../lib/types';
 
 type HistoryListProps = {
   runs: Run[];
+  /** When set, runs sharing a prompt collapse into one expandable family row. */
+  groups?: RunGroup[];
   loading: boolean;
   error: string | null;
   selectedRunIds: string[];
@@ -27,8 +31,14 @@ function isReroutedModel(requestedModel: string, resolvedModel: string | null):
   return requestedModel !== resolvedModel;
 }
 
+function describeFamily(group: RunGroup): string {
+  const variants = group.variant_count === 1 ? '1 variant' : `${group.variant_count} variants`;
+  return `${group.run_count} runs, ${variants}: ${group.models.join(', ')}`;
+}
+
 export function HistoryList({
   runs,
+  groups,
   loading,
   error,
   selectedRunIds,
@@ -37,6 +47,74 @@ export function HistoryList({
   onCompare,
 }: HistoryListProps) {
   const compareDisabled = selectedRunIds.length !== 2;
+  const [expandedKeys, setExpandedKeys] = useState<string[]>([]);
+  const runsById = new Map(runs.map((run) => [run.id, run]));
+
+  const toggleExpanded = (key: string) => {
+    setExpandedKeys((current) =>
+      current.includes(key) ? current.filter((entry) => entry !== key) : [...current, key],
+    );
+  };
+
+  const renderRunRow = (run: Run, className?: string) => (
+    <tr key={run.id} className={className} onClick={() => onOpenRun(run.id)}>
+      <td>
+        <input
+          type="checkbox"
+          checked={selectedRunIds.includes(run.id)}
+          onChange={() => onToggleSelect(run.id)}
+          onClick={(event) => event.stopPropagation()}
+          aria-label={`Select run ${run.id} for compare`}
+        />
+      </td>
+      <td>{formatTimestamp(run.timestamp)}</td>
+      <td>{run.requested_model}</td>
+      <td className="history-model-cell">
+        <span>{run.resolved_model ?? '-'}</span>
+        {isReroutedModel(run.requested_model, run.resolved_model) && (
+          <span className="model-route-badge">Rerouted</span>
+        )}
+      </td>
+      <td>{previewPrompt(run.user_prompt)}</td>
+      <td>{run.latency_ms} ms</td>
+      <td>{run.total_tokens ?? '-'}</td>
+    </tr>
+  );
+
+  const renderGroup = (group: RunGroup) => {
+    const members = group.run_ids
+      .map((runId) => runsById.get(runId))
+      .filter((run): run is Run => run !== undefined);
+    if (members.length <= 1) {
+      return members.map((run) => renderRunRow(run));
+    }
+
+    const expanded = expandedKeys.includes(group.key);
+    return (
+      <Fragment key={group.key}>
+        <tr className="history-family-row" onClick={() => toggleExpanded(group.key)}>
+          <td>
+            <button
+              type="button"
+              className="secondary"
+              aria-expanded={expanded}
+              aria-label={`${expanded ? 'Collapse' : 'Expand'} ${group.run_count} runs with this prompt`}
+            >
+              {expanded ? '▾' : '▸'}
+            </button>
+          </td>
+          <td>{formatTimestamp(group.last_timestamp)}</td>
+          <td colSpan={2}>{describeFamily(group)}</td>
+          <td>{previewPrompt(group.user_prompt_preview)}</td>
+          <td colSpan={2}>
+            {group.best_rating !== null ? `Best ${group.best_rating}` : '-'}
+            {group.error_count > 0 && ` · ${group.error_count} failed`}
+          </td>
+        </tr>
+        {expanded && members.map((run) => renderRunRow(run, 'history-family-member'))}
+      </Fragment>
+    );
+  };
 
   return (
     <section className="panel history-panel">
@@ -65,30 +143,7 @@ export function HistoryList({
               </tr>
             </thead>
             <tbody>
-              {runs.map((run) => (
-                <tr key={run.id} onClick={() => onOpenRun(run.id)}>
-                  <td>
-                    <input
-                      type="checkbox"
-                      checked={selectedRunIds.includes(run.id)}
-                      onChange={() => onToggleSelect(run.id)}
-                      onClick={(event) => event.stopPropagation()}
-                      aria-label={`Select run ${run.id} for compare`}
-                    />
-                  </td>
-                  <td>{formatTimestamp(run.timestamp)}</td>
-                  <td>{run.requested_model}</td>
-                  <td className="history-model-cell">
-                    <span>{run.resolved_model ?? '-'}</span>
-                    {isReroutedModel(run.requested_model, run.resolved_model) && (
-                      <span className="model-route-badge">Rerouted</span>
-                    )}
-                  </td>
-                  <td>{previewPrompt(run.user_prompt)}</td>
-                  <td>{run.latency_ms} ms</td>
-                  <td>{run.total_tokens ?? '-'}</td>
-                </tr>
-              ))}
+              {groups ? groups.map(renderGroup) : runs.map((run) => renderRunRow(run))}
             </tbody>
           </table>
           {runs.length === 0 && <p className="inline-status">No runs yet.</p>}
@@ -0,0 +1,80 @@
+import { useEffect, useState, type MouseEvent } from 'react';
+import { openExternalUrl, renderMarkdown } from '../lib/db';
+
+type MarkdownContentProps = {
+  markdown: string;
+};
+
+// Rendered HTML by source text. Transcripts re-render often and messages never
+// change once stored, so each one only crosses the IPC boundary once.
+const renderedCache = new Map<string, string>();
+const MAX_CACHE_ENTRIES = 500;
+
+function remember(markdown: string, html: string) {
+  if (renderedCache.size >= MAX_CACHE_ENTRIES) {
+    const oldest = renderedCache.keys().next().value;
+    if (oldest !== undefined) {
+      renderedCache.delete(oldest);
+    }
+  }
+  renderedCache.set(markdown, html);
+}
+
+// Links in rendered markdown open outside the app. Letting the webview follow
+// them would navigate the main window away from Basecamp. Fragment links
+// (footnotes) still jump within the page.
+function handleLinkClick(event: MouseEvent<HTMLDivElement>) {
+  const anchor = (event.target as HTMLElement).closest('a');
+  const href = anchor?.getAttribute('href');
+  if (!anchor || !href || href.startsWith('#')) {
+    return;
+  }
+  event.preventDefault();
+  openExternalUrl(anchor.href).catch(() => {
+    // Links the backend refuses to open simply do nothing.
+  });
+}
+
+/**
+ * Markdown rendered and sanitized by the backend. Shows the raw text until
+ * the HTML arrives, and keeps showing it if rendering fails.
+ */
+export function MarkdownContent({ markdown }: MarkdownContentProps) {
+  const [html, setHtml] = useState<string | null>(() => renderedCache.get(markdown) ?? null);
+
+  useEffect(() => {
+    const cached = renderedCache.get(markdown);
+    if (cached !== undefined) {
+      setHtml(cached);
+      return;
+    }
+
+    let cancelled = false;
+    setHtml(null);
+    renderMarkdown(markdown)
+      .then((rendered) => {
+        remember(markdown, rendered);
+        if (!cancelled) setHtml(rendered);
+      })
+      .catch(() => {
+        // Leave the plain-text fallback in place.
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [markdown]);
+
+  if (html === null) {
+    return <p>{markdown}</p>;
+  }
+
+  return (
+    <div
+      className="markdown-body"
+      onClick={handleLinkClick}
+      onAuxClick={handleLinkClick}
+      dangerouslySetInnerHTML={{ __html: html }}
+    />
+  );
+}
@@ -1,25 +1,95 @@
 import { useEffect, useState, type FormEvent } from 'react';
 
 import {
+  benchmarkEmbeddingBackends,
+  campList,
+  checkForUpdates,
+  dbListModels,
+  deleteLocalEmbeddingModel,
+  deleteModelNote,
+  downloadLocalEmbeddingModel,
+  detectSyncConflicts,
+  exportCrashReport,
+  generateWorkspaceManifest,
+  getGlobalInstructions,
   getApprovalPolicy,
+  getDigestSettings,
+  getDigestStatus,
+  getEmbeddingSettings,
+  getMaxContinuations,
   getMaxIterations,
+  getPerformanceStats,
+  getQuickCaptureSettings,
+  getSplashDurationMs,
+  getSyncSettings,
+  getTraySettings,
+  getUpdateChannel,
+  installUpdate,
+  listCrashReports,
+  listUserDatabases,
+  mergeSyncConflict,
+  getTitleModel,
   providerHealthCheck,
   providerRefreshModels,
   providersList,
+  getProviderConcurrency,
+  setProviderConcurrency,
   providerUpdate,
+  runDigestNow,
+  runSync,
   getToolsEnabled,
   getWorkspacePath,
   hasApiKey,
   pickWorkspaceFolder,
+  registerUserDatabase,
   saveApiKey,
   setApprovalPolicy as persistApprovalPolicy,
+  setDigestSettings as persistDigestSettings,
+  setEmbeddingBackend as persistEmbeddingBackend,
+  setGlobalInstructions as persistGlobalInstructions,
+  setMaxContinuations as persistMaxContinuations,
+  setModelNote as persistModelNote,
   setMaxIterations as persistMaxIterations,
+  setSplashDurationMs as persistSplashDurationMs,
+  setSyncSettings as persistSyncSettings,
+  setQuickCaptureSettings as persistQuickCaptureSettings,
+  setTitleModel as persistTitleModel,
+  setTraySettings as persistTraySettings,
+  setUpdateChannel as persistUpdateChannel,
   setToolsEnabled as persistToolsEnabled,
   setWorkspacePath,
   getWebGLEnabled,
   setWebGLEnabled as persistWebGLEnabled,
+  unregisterUserDatabase,
+  workspaceGc,
 } from '../lib/db';
-import type { ApprovalPolicy, ProviderKind, ProviderRegistryRow } from '../lib/types';
+import { commandErrorMessage } from '../lib/errors';
+import type {
+  ApprovalPolicy,
+  CampSummary,
+  CostTier,
+  CrashReportSummary,
+  DigestSettings,
+  DigestStatus,
+  EmbeddingBackendSetting,
+  EmbeddingBenchmark,
+  EmbeddingSettings,
+  ModelNote,
+  ModelPerformanceStats,
+  ModelRow,
+  ProviderKind,
+  ProviderRegistryRow,
+  QuickCaptureSettings,
+  SyncConflict,
+  SyncMergeReport,
+  SyncReport,
+  SyncSettings,
+  SyncTarget,
+  UpdateChannel,
+  UpdateCheck,
+  UserDatabase,
+  WorkspaceGcReport,
+} from '../lib/types';
 import {
   getDeveloperInspectMode,
   setDeveloperInspectMode as persistDeveloperInspectMode,
@@ -34,6 +104,14 @@ type SettingsProps = {
   onModelsSynced: () => Promise<void>;
 };
 
+function formatMegabytes(bytes: number): string {
+  return `${(bytes / (1024 * 1024)).toFixed(0)} MB`;
+}
+
+function describeEmbeddingBackend(backend: EmbeddingBackendSetting): string {
+  return backend.kind === 'ollama' ? `Ollama: ${backend.model}` : `Local: ${backend.model}`;
+}
+
 function formatLastSync(lastSync: number | null): string {
   if (!lastSync) {
     return 'never';
@@ -72,10 +150,29 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
   const [saving, setSaving] = useState(false);
   const [syncing, setSyncing] = useState(false);
   const [savingWorkspace, setSavingWorkspace] = useState(false);
+  const [generatingManifest, setGeneratingManifest] = useState(false);
+  const [runningGc, setRunningGc] = useState(false);
+  const [gcReport, setGcReport] = useState<WorkspaceGcReport | null>(null);
+  const [gcPickedContextFiles, setGcPickedContextFiles] = useState<string[]>([]);
+  const [globalInstructions, setGlobalInstructionsState] = useState('');
+  const [userDatabases, setUserDatabases] = useState<UserDatabase[]>([]);
+  const [databaseDraft, setDatabaseDraft] = useState({ name: '', path: '' });
+  const [savingDatabase, setSavingDatabase] = useState(false);
+  const [embeddingSettings, setEmbeddingSettings] = useState<EmbeddingSettings | null>(null);
+  const [embeddingDraft, setEmbeddingDraft] = useState<EmbeddingBackendSetting>({ kind: 'ollama', model: 'nomic-embed-text' });
+  const [savingEmbeddingBackend, setSavingEmbeddingBackend] = useState(false);
+  const [busyEmbeddingModel, setBusyEmbeddingModel] = useState<string | null>(null);
+  const [embeddingBenchmarks, setEmbeddingBenchmarks] = useState<EmbeddingBenchmark[]>([]);
+  const [benchmarkingEmbeddings, setBenchmarkingEmbeddings] = useState(false);
+  const [modelRows, setModelRows] = useState<ModelRow[]>([]);
+  const [noteDraft, setNoteDraft] = useState<ModelNote | null>(null);
+  const [savingModelNote, setSavingModelNote] = useState(false);
+  const [savingGlobalInstructions, setSavingGlobalInstructions] = useState(false);
   const [savingToolsEnabled, setSavingToolsEnabled] = useState(false);
   const [savingDeveloperInspect, setSavingDeveloperInspect] = useState(false);
   const [providers, setProviders] = useState<ProviderRegistryRow[]>([]);
   const [providerDrafts, setProviderDrafts] = useState<Record<string, { enabled: boolean; base_url: string }>>({});
+  const [providerConcurrency, setProviderConcurrencyState] = useState<Record<string, number>>({});
   const [savingProviderKind, setSavingProviderKind] = useState<ProviderKind | null>(null);
   const [checkingProviders, setCheckingProviders] = useState(false);
   const [refreshingProviderModels, setRefreshingProviderModels] = useState(false);
@@ -89,6 +186,37 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
   const [savingApprovalPolicy, setSavingApprovalPolicy] = useState(false);
   const [maxIterations, setMaxIterationsState] = useState(10);
   const [savingMaxIterations, setSavingMaxIterations] = useState(false);
+  const [maxContinuations, setMaxContinuationsState] = useState(0);
+  const [savingMaxContinuations, setSavingMaxContinuations] = useState(false);
+  const [titleModel, setTitleModelState] = useState('');
+  const [savingTitleModel, setSavingTitleModel] = useState(false);
+  const [splashDurationMs, setSplashDurationMsState] = useState(1500);
+  const [savingSplashDuration, setSavingSplashDuration] = useState(false);
+  const [updateChannel, setUpdateChannelState] = useState<UpdateChannel>('stable');
+  const [updateCheck, setUpdateCheck] = useState<UpdateCheck | null>(null);
+  const [checkingUpdates, setCheckingUpdates] = useState(false);
+  const [installingUpdate, setInstallingUpdate] = useState(false);
+  const [quickCapture, setQuickCaptureState] = useState<QuickCaptureSettings>({ camp_id: null, shortcut: null, target: 'message' });
+  const [savingQuickCapture, setSavingQuickCapture] = useState(false);
+  const [camps, setCamps] = useState<CampSummary[]>([]);
+  const [digest, setDigestState] = useState<DigestSettings>({ enabled: false, camp_id: null, model: null, hour: 7, utc_offset_minutes: 0 });
+  const [digestStatus, setDigestStatus] = useState<DigestStatus>({});
+  const [savingDigest, setSavingDigest] = useState(false);
+  const [runningDigest, setRunningDigest] = useState(false);
+  const [keepRunningInTray, setKeepRunningInTrayState] = useState(false);
+  const [savingTraySettings, setSavingTraySettings] = useState(false);
+  const [crashReports, setCrashReports] = useState<CrashReportSummary[]>([]);
+  const [performanceStats, setPerformanceStats] = useState<ModelPerformanceStats[]>([]);
+  const [exportingCrashReportId, setExportingCrashReportId] = useState<string | null>(null);
+  const [syncConflicts, setSyncConflicts] = useState<SyncConflict[]>([]);
+  const [checkingSyncConflicts, setCheckingSyncConflicts] = useState(false);
+  const [mergePreview, setMergePreview] = useState<{ conflict: SyncConflict; report: SyncMergeReport } | null>(null);
+  const [mergingConflict, setMergingConflict] = useState(false);
+  const [syncSettings, setSyncSettingsState] = useState<SyncSettings>({ target: null, device_name: '' });
+  const [syncSecret, setSyncSecret] = useState('');
+  const [savingSync, setSavingSync] = useState(false);
+  const [runningSync, setRunningSync] = useState(false);
+  const [lastSyncReport, setLastSyncReport] = useState<SyncReport | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [status, setStatus] = useState<string | null>(null);
   const [keyInfo, setKeyInfo] = useState<OpenRouterKeyInfo["data"] | null>(null);
@@ -99,13 +227,28 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
       setLoading(true);
 
       try {
-        const [exists, currentWorkspacePath, currentToolsEnabled, currentDeveloperInspectMode, currentApprovalPolicy, currentMaxIterations, currentWebglEnabled, providerRows] = await Promise.all([
+        const [exists, currentWorkspacePath, currentToolsEnabled, currentDeveloperInspectMode, currentApprovalPolicy, currentMaxIterations, currentMaxContinuations, currentTitleModel, currentSplashDurationMs, currentUpdateChannel, currentQuickCapture, currentCamps, currentTraySettings, currentCrashReports, currentSyncConflicts, currentPerformanceStats, currentDigest, currentDigestStatus, currentSyncSettings, currentGlobalInstructions, currentModelRows, currentWebglEnabled, providerRows] = await Promise.all([
           hasApiKey(),
           getWorkspacePath(),
           getToolsEnabled(),
           getDeveloperInspectMode(),
           getApprovalPolicy(),
           getMaxIterations(),
+          getMaxContinuations(),
+          getTitleModel(),
+          getSplashDurationMs(),
+          getUpdateChannel(),
+          getQuickCaptureSettings(),
+          campList().catch(() => []),
+          getTraySettings(),
+          listCrashReports().catch(() => []),
+          detectSyncConflicts().catch(() => []),
+          getPerformanceStats().catch(() => []),
+          getDigestSettings(),
+          getDigestStatus().catch(() => ({})),
+          getSyncSettings(),
+          getGlobalInstructions().catch(() => ''),
+          dbListModels().catch(() => []),
           getWebGLEnabled(),
           providersList(),
         ]);
@@ -117,6 +260,21 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
         setWebglEnabledState(currentWebglEnabled);
         setApprovalPolicyState(currentApprovalPolicy as ApprovalPolicy);
         setMaxIterationsState(currentMaxIterations);
+        setMaxContinuationsState(currentMaxContinuations);
+        setTitleModelState(currentTitleModel ?? '');
+        setSplashDurationMsState(currentSplashDurationMs);
+        setUpdateChannelState(currentUpdateChannel);
+        setQuickCaptureState(currentQuickCapture);
+        setCamps(currentCamps);
+        setKeepRunningInTrayState(currentTraySettings.keep_running_in_tray);
+        setCrashReports(currentCrashReports);
+        setSyncConflicts(currentSyncConflicts);
+        setPerformanceStats(currentPerformanceStats);
+        setDigestState(currentDigest);
+        setDigestStatus(currentDigestStatus);
+        setSyncSettingsState(currentSyncSettings);
+        setGlobalInstructionsState(currentGlobalInstructions);
+        setModelRows(currentModelRows);
         setProviders(providerRows);
         setProviderDrafts(
           Object.fromEntries(
@@ -139,6 +297,16 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
     void load();
   }, []);
 
+  useEffect(() => {
+    getProviderConcurrency()
+      .then((limits) => {
+        setProviderConcurrencyState(
+          Object.fromEntries(limits.map((limit) => [limit.provider_kind, limit.max_concurrent_requests])),
+        );
+      })
+      .catch(() => undefined);
+  }, []);
+
   const handleSave = async (event: FormEvent<HTMLFormElement>) => {
     event.preventDefault();
 
@@ -209,11 +377,15 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
         enabled: draft.enabled,
         base_url: draft.base_url,
       });
+      const maxConcurrentRequests = providerConcurrency[kind];
+      if (maxConcurrentRequests !== undefined) {
+        await setProviderConcurrency(kind, maxConcurrentRequests);
+      }
       const latest = await providersList();
       setProviders(latest);
       setStatus(`${providerTitle(kind)} settings saved.`);
     } catch (providerError) {
-      setError(providerError instanceof Error ? providerError.message : 'Unable to save provider settings.');
+      setError(commandErrorMessage(providerError, 'Unable to save provider settings.'));
     } finally {
       setSavingProviderKind(null);
     }
@@ -228,7 +400,7 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
       setProviders(checked);
       setStatus('Provider health check complete.');
     } catch (providerError) {
-      setError(providerError instanceof Error ? providerError.message : 'Unable to run provider health check.');
+      setError(commandErrorMessage(providerError, 'Unable to run provider health check.'));
     } finally {
       setCheckingProviders(false);
     }
@@ -243,7 +415,7 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
       await onModelsSynced();
       setStatus(`Refreshed ${refreshed.total_count} cached models from providers.`);
     } catch (providerError) {
-      setError(providerError instanceof Error ? providerError.message : 'Unable to refresh provider models.');
+      setError(commandErrorMessage(providerError, 'Unable to refresh provider models.'));
     } finally {
       setRefreshingProviderModels(false);
     }
@@ -271,6 +443,234 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
     }
   };
 
+  const handleGenerateManifest = async () => {
+    setGeneratingManifest(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const result = await generateWorkspaceManifest();
+      setStatus(`Wrote ${result.path} listing ${result.camp_count} camp(s).`);
+    } catch (manifestError) {
+      setError(commandErrorMessage(manifestError, 'Unable to write the workspace index.'));
+    } finally {
+      setGeneratingManifest(false);
+    }
+  };
+
+  useEffect(() => {
+    if (!workspacePath) {
+      setUserDatabases([]);
+      return;
+    }
+    listUserDatabases()
+      .then(setUserDatabases)
+      .catch((databasesError) => setError(commandErrorMessage(databasesError, 'Unable to load registered databases.')));
+  }, [workspacePath]);
+
+  const handleRegisterDatabase = async (event: FormEvent<HTMLFormElement>) => {
+    event.preventDefault();
+    setSavingDatabase(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const registered = await registerUserDatabase(databaseDraft);
+      setUserDatabases(await listUserDatabases());
+      setDatabaseDraft({ name: '', path: '' });
+      setStatus(`Registered ${registered.path} as "${registered.name}".`);
+    } catch (registerError) {
+      setError(commandErrorMessage(registerError, 'Unable to register the database.'));
+    } finally {
+      setSavingDatabase(false);
+    }
+  };
+
+  const handleUnregisterDatabase = async (name: string) => {
+    setSavingDatabase(true);
+    setError(null);
+
+    try {
+      await unregisterUserDatabase(name);
+      setUserDatabases((previous) => previous.filter((database) => database.name !== name));
+    } catch (unregisterError) {
+      setError(commandErrorMessage(unregisterError, 'Unable to remove the database.'));
+    } finally {
+      setSavingDatabase(false);
+    }
+  };
+
+  useEffect(() => {
+    getEmbeddingSettings()
+      .then((settings) => {
+        setEmbeddingSettings(settings);
+        setEmbeddingDraft(settings.backend);
+      })
+      .catch((embeddingError) => setError(commandErrorMessage(embeddingError, 'Unable to load embedding settings.')));
+  }, []);
+
+  const handleEmbeddingBackendSave = async () => {
+    setSavingEmbeddingBackend(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const saved = await persistEmbeddingBackend(embeddingDraft);
+      setEmbeddingDraft(saved);
+      setEmbeddingSettings((previous) => (previous ? { ...previous, backend: saved } : previous));
+      setStatus(`Embedding with ${describeEmbeddingBackend(saved)}. Indexed camps re-embed on their next refresh.`);
+    } catch (saveError) {
+      setError(commandErrorMessage(saveError, 'Unable to save the embedding backend.'));
+    } finally {
+      setSavingEmbeddingBackend(false);
+    }
+  };
+
+  const handleEmbeddingModelDownload = async (code: string) => {
+    setBusyEmbeddingModel(code);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const downloaded = await downloadLocalEmbeddingModel(code);
+      setEmbeddingSettings((previous) =>
+        previous
+          ? { ...previous, local_models: previous.local_models.map((model) => (model.code === code ? downloaded : model)) }
+          : previous,
+      );
+      setStatus(`Downloaded ${downloaded.label} (${formatMegabytes(downloaded.disk_bytes)}).`);
+    } catch (downloadError) {
+      setError(commandErrorMessage(downloadError, 'Unable to download the embedding model.'));
+    } finally {
+      setBusyEmbeddingModel(null);
+    }
+  };
+
+  const handleEmbeddingModelDelete = async (code: string) => {
+    setBusyEmbeddingModel(code);
+    setError(null);
+
+    try {
+      await deleteLocalEmbeddingModel(code);
+      setEmbeddingSettings((previous) =>
+        previous
+          ? {
+            ...previous,
+            local_models: previous.local_models.map((model) =>
+              model.code === code ? { ...model, downloaded: false, disk_bytes: 0 } : model,
+            ),
+          }
+          : previous,
+      );
+    } catch (deleteError) {
+      setError(commandErrorMessage(deleteError, 'Unable to delete the embedding model.'));
+    } finally {
+      setBusyEmbeddingModel(null);
+    }
+  };
+
+  // Compares the saved backend with every downloaded local model.
+  const handleEmbeddingBenchmark = async () => {
+    if (!embeddingSettings) return;
+    setBenchmarkingEmbeddings(true);
+    setError(null);
+
+    const candidates: EmbeddingBackendSetting[] = [embeddingSettings.backend];
+    for (const model of embeddingSettings.local_models) {
+      const alreadyListed = embeddingSettings.backend.kind === 'local' && embeddingSettings.backend.model === model.code;
+      if (model.downloaded && !alreadyListed) {
+        candidates.push({ kind: 'local', model: model.code });
+      }
+    }
+
+    try {
+      setEmbeddingBenchmarks(await benchmarkEmbeddingBackends(candidates));
+    } catch (benchmarkError) {
+      setError(commandErrorMessage(benchmarkError, 'Unable to benchmark embedding models.'));
+    } finally {
+      setBenchmarkingEmbeddings(false);
+    }
+  };
+
+  const handleWorkspaceGc = async (clean: boolean) => {
+    if (
+      clean &&
+      !window.confirm(
+        'Remove the orphaned data listed below? Orphaned artifact files go to their camp\'s trash and the picked context files go to the workspace trash; tool call records and inspect folders are deleted.',
+      )
+    ) {
+      return;
+    }
+    setRunningGc(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await workspaceGc(clean, clean ? gcPickedContextFiles : []);
+      setGcReport(report);
+      setGcPickedContextFiles([]);
+      if (clean) {
+        setStatus('Orphaned workspace data removed.');
+      }
+    } catch (gcError) {
+      setError(commandErrorMessage(gcError, 'Unable to check the workspace for orphaned data.'));
+    } finally {
+      setRunningGc(false);
+    }
+  };
+
+  const handleGlobalInstructionsSave = async () => {
+    setSavingGlobalInstructions(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      await persistGlobalInstructions(globalInstructions);
+      setStatus(globalInstructions.trim() ? 'Global instructions saved.' : 'Global instructions cleared.');
+    } catch (instructionsError) {
+      setError(commandErrorMessage(instructionsError, 'Unable to save global instructions.'));
+    } finally {
+      setSavingGlobalInstructions(false);
+    }
+  };
+
+  const handleNoteModelSelect = (modelId: string) => {
+    const row = modelRows.find((model) => model.id === modelId);
+    setNoteDraft(
+      row
+        ? row.note ?? { provider_kind: row.provider_kind, model_id: row.model_id, quality: null, speed: null, cost_tier: null, notes: '', updated_at: 0 }
+        : null,
+    );
+  };
+
+  const handleModelNoteSave = async (remove = false) => {
+    if (!noteDraft) return;
+    setSavingModelNote(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      let saved: ModelNote | undefined;
+      if (remove) {
+        await deleteModelNote(noteDraft.provider_kind, noteDraft.model_id);
+        setNoteDraft(null);
+      } else {
+        saved = await persistModelNote(noteDraft);
+        setNoteDraft(saved);
+      }
+      setModelRows((rows) =>
+        rows.map((row) =>
+          row.provider_kind === noteDraft.provider_kind && row.model_id === noteDraft.model_id ? { ...row, note: saved } : row,
+        ),
+      );
+      setStatus(remove ? 'Model note removed.' : 'Model note saved.');
+    } catch (noteError) {
+      setError(commandErrorMessage(noteError, 'Unable to update the model note.'));
+    } finally {
+      setSavingModelNote(false);
+    }
+  };
+
   const handleCheckUsage = async () => {
     setFetchingKeyInfo(true);
     setError(null);
@@ -365,6 +765,303 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
     }
   };
 
+  const handleMaxContinuationsChange = async (value: number) => {
+    setSavingMaxContinuations(true);
+    setError(null);
+    setStatus(null);
+
+    const previous = maxContinuations;
+    setMaxContinuationsState(Math.min(Math.max(value, 0), 5));
+
+    try {
+      const saved = await persistMaxContinuations(Math.max(value, 0));
+      setMaxContinuationsState(saved);
+      setStatus(saved === 0 ? 'Continuation of truncated replies turned off.' : `Truncated replies continue up to ${saved} times.`);
+    } catch (continuationError) {
+      setMaxContinuationsState(previous);
+      setError(continuationError instanceof Error ? continuationError.message : 'Unable to update continuation limit.');
+    } finally {
+      setSavingMaxContinuations(false);
+    }
+  };
+
+  const handleTitleModelSave = async () => {
+    setSavingTitleModel(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const trimmed = titleModel.trim();
+      await persistTitleModel(trimmed || null);
+      setStatus(trimmed ? `New camps are titled with ${trimmed}.` : 'Automatic camp titles turned off.');
+    } catch (titleError) {
+      setError(titleError instanceof Error ? titleError.message : 'Unable to update title model.');
+    } finally {
+      setSavingTitleModel(false);
+    }
+  };
+
+  const handleSplashDurationChange = async (value: number) => {
+    setSavingSplashDuration(true);
+    setError(null);
+    setStatus(null);
+
+    const previous = splashDurationMs;
+    setSplashDurationMsState(Math.min(Math.max(value, 0), 10000));
+
+    try {
+      const saved = await persistSplashDurationMs(Math.max(value, 0));
+      setSplashDurationMsState(saved);
+      setStatus(saved === 0 ? 'Basecamp opens as soon as it is ready.' : `Splash screen shows for at least ${saved} ms.`);
+    } catch (splashError) {
+      setSplashDurationMsState(previous);
+      setError(splashError instanceof Error ? splashError.message : 'Unable to update splash duration.');
+    } finally {
+      setSavingSplashDuration(false);
+    }
+  };
+
+  const handleUpdateChannelChange = async (channel: UpdateChannel) => {
+    setError(null);
+    setStatus(null);
+    setUpdateCheck(null);
+
+    try {
+      setUpdateChannelState(await persistUpdateChannel(channel));
+    } catch (channelError) {
+      setError(commandErrorMessage(channelError, 'Unable to update the update channel.'));
+    }
+  };
+
+  const handleCheckForUpdates = async () => {
+    setCheckingUpdates(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const check = await checkForUpdates();
+      setUpdateCheck(check);
+      if (!check.available) {
+        setStatus(`Basecamp ${check.current_version} is up to date.`);
+      }
+    } catch (updateError) {
+      setError(commandErrorMessage(updateError, 'Unable to check for updates.'));
+    } finally {
+      setCheckingUpdates(false);
+    }
+  };
+
+  const handleInstallUpdate = async () => {
+    setInstallingUpdate(true);
+    setError(null);
+    setStatus('Downloading update. Basecamp restarts when it is installed.');
+
+    try {
+      await installUpdate();
+    } catch (installError) {
+      setStatus(null);
+      setError(commandErrorMessage(installError, 'Unable to install the update.'));
+      setInstallingUpdate(false);
+    }
+  };
+
+  const handleQuickCaptureSave = async (next: QuickCaptureSettings) => {
+    setSavingQuickCapture(true);
+    setError(null);
+    setStatus(null);
+
+    const previous = quickCapture;
+    setQuickCaptureState(next);
+
+    try {
+      const saved = await persistQuickCaptureSettings(next);
+      setQuickCaptureState(saved);
+      setStatus(saved.shortcut ? `Quick capture opens with ${saved.shortcut}.` : 'Quick capture shortcut turned off.');
+    } catch (captureError) {
+      setQuickCaptureState(previous);
+      setError(commandErrorMessage(captureError, 'Unable to update quick capture.'));
+    } finally {
+      setSavingQuickCapture(false);
+    }
+  };
+
+  const handleDigestSave = async (next: DigestSettings) => {
+    setSavingDigest(true);
+    setError(null);
+    setStatus(null);
+
+    const previous = digest;
+    // The backend works in the user's calendar day, so send the current offset with every save.
+    const withOffset = { ...next, utc_offset_minutes: -new Date().getTimezoneOffset() };
+    setDigestState(withOffset);
+
+    try {
+      const saved = await persistDigestSettings(withOffset);
+      setDigestState(saved);
+      setStatus(saved.enabled ? `Daily digest runs after ${saved.hour}:00.` : 'Daily digest turned off.');
+    } catch (digestError) {
+      setDigestState(previous);
+      setError(commandErrorMessage(digestError, 'Unable to update the daily digest.'));
+    } finally {
+      setSavingDigest(false);
+    }
+  };
+
+  const handleRunDigestNow = async () => {
+    setRunningDigest(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const result = await runDigestNow();
+      setStatus(
+        result.artifact_id
+          ? `Wrote the ${result.day} digest covering ${result.camps} camp(s).`
+          : `No activity on ${result.day}; nothing to summarize.`,
+      );
+    } catch (digestError) {
+      setError(commandErrorMessage(digestError, 'Unable to write the digest.'));
+    } finally {
+      setDigestStatus(await getDigestStatus().catch(() => digestStatus));
+      setRunningDigest(false);
+    }
+  };
+
+  const handleKeepRunningInTrayToggle = async (enabled: boolean) => {
+    setSavingTraySettings(true);
+    setError(null);
+    setStatus(null);
+
+    const previous = keepRunningInTray;
+    setKeepRunningInTrayState(enabled);
+
+    try {
+      const saved = await persistTraySettings({ keep_running_in_tray: enabled });
+      setKeepRunningInTrayState(saved.keep_running_in_tray);
+      setStatus(saved.keep_running_in_tray ? 'Closing the window keeps Basecamp in the tray.' : 'Closing the window quits Basecamp.');
+    } catch (trayError) {
+      setKeepRunningInTrayState(previous);
+      setError(commandErrorMessage(trayError, 'Unable to update tray behavior.'));
+    } finally {
+      setSavingTraySettings(false);
+    }
+  };
+
+  const handleExportCrashReport = async (id: string) => {
+    setExportingCrashReportId(id);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const savedPath = await exportCrashReport(id);
+      if (savedPath) {
+        setStatus(`Crash report saved to ${savedPath}.`);
+      }
+    } catch (exportError) {
+      setError(commandErrorMessage(exportError, 'Unable to export the crash report.'));
+    } finally {
+      setExportingCrashReportId(null);
+    }
+  };
+
+  const handleCheckSyncConflicts = async () => {
+    setCheckingSyncConflicts(true);
+    setError(null);
+    setStatus(null);
+    setMergePreview(null);
+
+    try {
+      const conflicts = await detectSyncConflicts();
+      setSyncConflicts(conflicts);
+      setStatus(conflicts.length === 0 ? 'No sync conflicts found.' : `Found ${conflicts.length} sync conflict(s).`);
+    } catch (syncError) {
+      setError(commandErrorMessage(syncError, 'Unable to check for sync conflicts.'));
+    } finally {
+      setCheckingSyncConflicts(false);
+    }
+  };
+
+  const handlePreviewMerge = async (conflict: SyncConflict) => {
+    setMergingConflict(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await mergeSyncConflict(conflict.camp_id, conflict.path, true);
+      setMergePreview({ conflict, report });
+    } catch (mergeError) {
+      setError(commandErrorMessage(mergeError, 'Unable to preview the merge.'));
+    } finally {
+      setMergingConflict(false);
+    }
+  };
+
+  const handleConfirmMerge = async () => {
+    if (!mergePreview) return;
+    const { conflict } = mergePreview;
+    setMergingConflict(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await mergeSyncConflict(conflict.camp_id, conflict.path);
+      setMergePreview(null);
+      setSyncConflicts(await detectSyncConflicts().catch(() => syncConflicts.filter((item) => item !== conflict)));
+      setStatus(`Merged ${report.added_count} message(s) into ${conflict.camp_name}; the copy was moved to the camp trash.`);
+    } catch (mergeError) {
+      setError(commandErrorMessage(mergeError, 'Unable to merge the conflicted copy.'));
+    } finally {
+      setMergingConflict(false);
+    }
+  };
+
+  const handleSyncTargetKindChange = (kind: SyncTarget['kind'] | '') => {
+    const targets: Record<SyncTarget['kind'], SyncTarget> = {
+      folder: { kind: 'folder', path: '' },
+      webdav: { kind: 'webdav', url: '', username: '' },
+      s3: { kind: 's3', endpoint: '', bucket: '', region: 'us-east-1', access_key_id: '', prefix: '' },
+    };
+    setSyncSettingsState({ ...syncSettings, target: kind ? targets[kind] : null });
+  };
+
+  const handleSyncSave = async () => {
+    setSavingSync(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const saved = await persistSyncSettings(syncSettings, syncSecret);
+      setSyncSettingsState(saved);
+      setSyncSecret('');
+      setStatus(saved.target ? 'Sync settings saved.' : 'Sync turned off.');
+    } catch (syncError) {
+      setError(commandErrorMessage(syncError, 'Unable to save sync settings.'));
+    } finally {
+      setSavingSync(false);
+    }
+  };
+
+  const handleRunSync = async () => {
+    setRunningSync(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await runSync();
+      setLastSyncReport(report);
+      setSyncSettingsState({ ...syncSettings, last_synced_at: report.finished_at });
+      setStatus(
+        `Synced: ${report.uploaded} uploaded, ${report.downloaded} downloaded, ${report.deleted_local + report.deleted_remote} deleted.${
+          report.skipped.length > 0 ? ` ${report.skipped.length} file(s) changed during the sync and will be compared next time.` : ''
+        }`,
+      );
+    } catch (syncError) {
+      setError(commandErrorMessage(syncError, 'Unable to sync.'));
+    } finally {
+      setRunningSync(false);
+    }
+  };
+
   const handleDeveloperInspectToggle = async (enabled: boolean) => {
     setSavingDeveloperInspect(true);
     setError(null);
@@ -490,6 +1187,21 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
                       onChange={(event) => handleProviderDraftChange(kind, { base_url: event.target.value })}
                     />
                   </Field>
+                  {providerConcurrency[kind] !== undefined ? (
+                    <Field label="Max parallel requests">
+                      <input
+                        type="number"
+                        min={1}
+                        max={8}
+                        value={providerConcurrency[kind]}
+                        onChange={(event) =>
+                          setProviderConcurrencyState((previous) => ({
+                            ...previous,
+                            [kind]: Math.min(8, Math.max(1, Number(event.target.value) || 1)),
+                          }))}
+                      />
+                    </Field>
+                  ) : null}
                   <div className="button-row">
                     <button
                       type="button"
@@ -511,6 +1223,76 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
             <p className="settings-note">Last model sync: {formatLastSync(modelsLastSync)}</p>
           </div>
 
+          <div className="settings-subsection">
+            <h3>Model Notes</h3>
+            <Field label="Model">
+              <select
+                value={modelRows.find((row) => row.provider_kind === noteDraft?.provider_kind && row.model_id === noteDraft?.model_id)?.id ?? ''}
+                onChange={(event) => handleNoteModelSelect(event.target.value)}
+              >
+                <option value="">Choose a model</option>
+                {modelRows.map((row) => (
+                  <option key={row.id} value={row.id}>
+                    {row.name?.trim() || row.id}
+                    {row.note ? ' (noted)' : ''}
+                  </option>
+                ))}
+              </select>
+            </Field>
+            {noteDraft && (
+              <>
+                {(['quality', 'speed'] as const).map((field) => (
+                  <Field key={field} label={field === 'quality' ? 'Quality' : 'Speed'}>
+                    <select
+                      value={noteDraft[field] ?? ''}
+                      disabled={savingModelNote}
+                      onChange={(event) => setNoteDraft({ ...noteDraft, [field]: event.target.value ? Number(event.target.value) : null })}
+                    >
+                      <option value="">Not rated</option>
+                      {[1, 2, 3, 4, 5].map((value) => (
+                        <option key={value} value={value}>
+                          {value} / 5
+                        </option>
+                      ))}
+                    </select>
+                  </Field>
+                ))}
+                <Field label="Cost Tier">
+                  <select
+                    value={noteDraft.cost_tier ?? ''}
+                    disabled={savingModelNote}
+                    onChange={(event) => setNoteDraft({ ...noteDraft, cost_tier: (event.target.value || null) as CostTier | null })}
+                  >
+                    <option value="">Not set</option>
+                    <option value="free">Free</option>
+                    <option value="low">Low</option>
+                    <option value="medium">Medium</option>
+                    <option value="high">High</option>
+                  </select>
+                </Field>
+                <Field label="Notes">
+                  <textarea
+                    rows={3}
+                    value={noteDraft.notes}
+                    disabled={savingModelNote}
+                    onChange={(event) => setNoteDraft({ ...noteDraft, notes: event.target.value })}
+                  />
+                </Field>
+                <div className="button-row">
+                  <button type="button" className="secondary" onClick={() => void handleModelNoteSave()} disabled={savingModelNote}>
+                    {savingModelNote ? 'Saving...' : 'Save Note'}
+                  </button>
+                  {noteDraft.updated_at > 0 && (
+                    <button type="button" className="secondary" onClick={() => void handleModelNoteSave(true)} disabled={savingModelNote}>
+                      Remove Note
+                    </button>
+                  )}
+                </div>
+              </>
+            )}
+            <p className="settings-note">Your own ratings show next to each model in the model pickers, so you can choose by what has worked for you.</p>
+          </div>
+
           <hr className="settings-divider" />
 
           <div className="settings-subsection">
@@ -564,9 +1346,240 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
 
           <div className="settings-subsection">
             <p className="settings-note">Workspace folder: {workspacePath ?? 'None selected'}</p>
-            <button type="button" onClick={() => void handlePickWorkspaceFolder()} disabled={savingWorkspace}>
-              {savingWorkspace ? 'Picking...' : 'Pick Workspace Folder'}
-            </button>
+            <div className="button-row">
+              <button type="button" onClick={() => void handlePickWorkspaceFolder()} disabled={savingWorkspace}>
+                {savingWorkspace ? 'Picking...' : 'Pick Workspace Folder'}
+              </button>
+              <button type="button" className="secondary" onClick={() => void handleGenerateManifest()} disabled={generatingManifest || !workspacePath}>
+                {generatingManifest ? 'Writing...' : 'Write INDEX.md'}
+              </button>
+            </div>
+            <p className="settings-note">INDEX.md lists every camp with its description, last activity and artifact count, so the workspace folder makes sense outside Basecamp.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Orphaned Data</h3>
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleWorkspaceGc(false)} disabled={runningGc || !workspacePath}>
+                {runningGc ? 'Checking...' : 'Find Orphaned Data'}
+              </button>
+              <button
+                type="button"
+                className="secondary"
+                onClick={() => void handleWorkspaceGc(true)}
+                disabled={runningGc || !gcReport || gcReport.cleaned}
+              >
+                Remove Orphaned Data
+              </button>
+            </div>
+            {gcReport && (
+              <ul className="crash-report-list">
+                <li>
+                  {gcReport.unreferenced_context_files.length} workspace context file(s) not attached to any camp
+                  {!gcReport.cleaned && gcReport.unreferenced_context_files.length > 0 && ' (pick the ones to move to the trash)'}
+                  {!gcReport.cleaned &&
+                    gcReport.unreferenced_context_files.map((path) => (
+                      <label key={path} className="settings-toggle">
+                        <input
+                          type="checkbox"
+                          checked={gcPickedContextFiles.includes(path)}
+                          onChange={(event) =>
+                            setGcPickedContextFiles((current) =>
+                              event.target.checked ? [...current, path] : current.filter((entry) => entry !== path),
+                            )
+                          }
+                        />
+                        <span>{path}</span>
+                      </label>
+                    ))}
+                </li>
+                <li>{gcReport.orphaned_artifact_files.length} artifact file(s) missing from their camp's index</li>
+                <li>{gcReport.orphaned_tool_calls.length} tool call record(s) without a run</li>
+                <li>{gcReport.stale_inspect_dirs.length} inspect folder(s) left behind by deleted camps</li>
+              </ul>
+            )}
+            {gcReport?.cleaned && (
+              <p className="settings-note">
+                Orphaned artifacts, tool call records and inspect folders were removed.{' '}
+                {gcReport.trashed_context_files.length} picked context file(s) went to the workspace .trash folder.
+              </p>
+            )}
+            <p className="settings-note">Finds leftovers that nothing in the workspace points at any more. Nothing is removed until you confirm.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Databases</h3>
+            {userDatabases.length > 0 && (
+              <ul className="crash-report-list">
+                {userDatabases.map((database) => (
+                  <li key={database.name}>
+                    <strong>{database.name}</strong> {database.path}
+                    {!database.available && ' (missing)'}{' '}
+                    <button
+                      type="button"
+                      className="secondary"
+                      onClick={() => void handleUnregisterDatabase(database.name)}
+                      disabled={savingDatabase}
+                    >
+                      Remove
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            )}
+            <form onSubmit={(event) => void handleRegisterDatabase(event)}>
+              <Field label="Name">
+                <input
+                  value={databaseDraft.name}
+                  placeholder="sales"
+                  disabled={savingDatabase || !workspacePath}
+                  onChange={(event) => setDatabaseDraft((previous) => ({ ...previous, name: event.target.value }))}
+                />
+              </Field>
+              <Field label="SQLite File">
+                <input
+                  value={databaseDraft.path}
+                  placeholder="data/sales.sqlite"
+                  disabled={savingDatabase || !workspacePath}
+                  onChange={(event) => setDatabaseDraft((previous) => ({ ...previous, path: event.target.value }))}
+                />
+              </Field>
+              <div className="button-row">
+                <button
+                  type="submit"
+                  className="secondary"
+                  disabled={savingDatabase || !workspacePath || !databaseDraft.name.trim() || !databaseDraft.path.trim()}
+                >
+                  {savingDatabase ? 'Saving...' : 'Register Database'}
+                </button>
+              </div>
+            </form>
+            <p className="settings-note">Camps can run read-only SELECT queries against these files with the query_database tool. Files must be inside the workspace folder.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Embeddings</h3>
+            <Field label="Backend">
+              <select
+                value={embeddingDraft.kind}
+                disabled={savingEmbeddingBackend || !embeddingSettings}
+                onChange={(event) =>
+                  setEmbeddingDraft(
+                    event.target.value === 'local'
+                      ? { kind: 'local', model: embeddingSettings?.local_models[0]?.code ?? '' }
+                      : { kind: 'ollama', model: 'nomic-embed-text' },
+                  )
+                }
+              >
+                <option value="ollama">Ollama</option>
+                <option value="local" disabled={!embeddingSettings?.local_supported}>
+                  Local (no server)
+                </option>
+              </select>
+            </Field>
+            {embeddingDraft.kind === 'ollama' ? (
+              <Field label="Ollama Model">
+                <input
+                  value={embeddingDraft.model}
+                  placeholder="nomic-embed-text"
+                  disabled={savingEmbeddingBackend}
+                  onChange={(event) => setEmbeddingDraft({ kind: 'ollama', model: event.target.value })}
+                />
+              </Field>
+            ) : (
+              <Field label="Local Model">
+                <select
+                  value={embeddingDraft.model}
+                  disabled={savingEmbeddingBackend}
+                  onChange={(event) => setEmbeddingDraft({ kind: 'local', model: event.target.value })}
+                >
+                  {(embeddingSettings?.local_models ?? []).map((model) => (
+                    <option key={model.code} value={model.code}>
+                      {model.label}{model.downloaded ? '' : ' (not downloaded)'}
+                    </option>
+                  ))}
+                </select>
+              </Field>
+            )}
+            <div className="button-row">
+              <button
+                type="button"
+                className="secondary"
+                onClick={() => void handleEmbeddingBackendSave()}
+                disabled={savingEmbeddingBackend || !embeddingDraft.model.trim()}
+              >
+                {savingEmbeddingBackend ? 'Saving...' : 'Save Embedding Backend'}
+              </button>
+              <button
+                type="button"
+                className="secondary"
+                onClick={() => void handleEmbeddingBenchmark()}
+                disabled={benchmarkingEmbeddings || !embeddingSettings}
+              >
+                {benchmarkingEmbeddings ? 'Benchmarking...' : 'Benchmark'}
+              </button>
+            </div>
+            {embeddingSettings?.local_supported && (
+              <ul className="crash-report-list">
+                {embeddingSettings.local_models.map((model) => (
+                  <li key={model.code}>
+                    <strong>{model.label}</strong> {model.dimensions} dims, {model.note}
+                    {model.downloaded && ` ${formatMegabytes(model.disk_bytes)} on disk.`}{' '}
+                    {model.downloaded ? (
+                      <button
+                        type="button"
+                        className="secondary"
+                        onClick={() => void handleEmbeddingModelDelete(model.code)}
+                        disabled={busyEmbeddingModel !== null}
+                      >
+                        Delete
+                      </button>
+                    ) : (
+                      <button
+                        type="button"
+                        className="secondary"
+                        onClick={() => void handleEmbeddingModelDownload(model.code)}
+                        disabled={busyEmbeddingModel !== null}
+                      >
+                        {busyEmbeddingModel === model.code ? 'Downloading...' : 'Download'}
+                      </button>
+                    )}
+                  </li>
+                ))}
+              </ul>
+            )}
+            {embeddingBenchmarks.length > 0 && (
+              <ul className="crash-report-list">
+                {embeddingBenchmarks.map((benchmark) => (
+                  <li key={describeEmbeddingBackend(benchmark.backend)}>
+                    <strong>{describeEmbeddingBackend(benchmark.backend)}</strong>{' '}
+                    {benchmark.error
+                      ? benchmark.error
+                      : `${benchmark.texts_per_second.toFixed(1)} texts/s, ${Math.round(benchmark.top1_accuracy * 100)}% top-1, ${benchmark.dimensions} dims, ${benchmark.load_ms} ms to load`}
+                  </li>
+                ))}
+              </ul>
+            )}
+            <p className="settings-note">Context files are embedded for semantic search. Local models run inside Basecamp and are downloaded once from Hugging Face; Ollama models must be pulled in Ollama. Changing the model re-embeds indexed camps on their next refresh.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Global Instructions</h3>
+            <Field label="Instructions For Every Camp">
+              <textarea
+                rows={6}
+                value={globalInstructions}
+                placeholder="Preferences every camp should follow, e.g. tone, spelling or formatting."
+                disabled={savingGlobalInstructions || !workspacePath}
+                onChange={(event) => setGlobalInstructionsState(event.target.value)}
+              />
+            </Field>
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleGlobalInstructionsSave()} disabled={savingGlobalInstructions || !workspacePath}>
+                {savingGlobalInstructions ? 'Saving...' : 'Save Global Instructions'}
+              </button>
+            </div>
+            <p className="settings-note">Saved as global_instructions.md in the workspace folder and sent above each camp's own system prompt, under separate headings.</p>
           </div>
 
           <hr className="settings-divider" />
@@ -606,6 +1619,427 @@ export function Settings({ cachedModelCount, modelsLastSync, onModelsSynced }: S
             </Field>
             <p className="settings-note">Maximum tool-use loop iterations per agent run (1–50, default 10).</p>
           </div>
+
+          <div className="settings-subsection">
+            <Field label="Continue Truncated Replies">
+              <input
+                type="number"
+                min={0}
+                max={5}
+                value={maxContinuations}
+                disabled={savingMaxContinuations}
+                onChange={(event) => {
+                  const parsed = parseInt(event.target.value, 10);
+                  if (!Number.isNaN(parsed)) {
+                    void handleMaxContinuationsChange(parsed);
+                  }
+                }}
+              />
+            </Field>
+            <p className="settings-note">When a reply hits the token limit, ask the model to continue and join the parts (0–5 follow-ups, 0 turns this off).</p>
+          </div>
+
+          <div className="settings-subsection">
+            <Field label="Title Model">
+              <input
+                type="text"
+                value={titleModel}
+                placeholder="openrouter/openai/gpt-4o-mini"
+                disabled={savingTitleModel}
+                onChange={(event) => setTitleModelState(event.target.value)}
+                onBlur={() => void handleTitleModelSave()}
+              />
+            </Field>
+            <p className="settings-note">A cheap model that names "Untitled Camp" camps after the first exchange. Leave empty to keep default names.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <Field label="Splash Duration (ms)">
+              <input
+                type="number"
+                min={0}
+                max={10000}
+                step={250}
+                value={splashDurationMs}
+                disabled={savingSplashDuration}
+                onChange={(event) => {
+                  const parsed = parseInt(event.target.value, 10);
+                  if (!Number.isNaN(parsed)) {
+                    void handleSplashDurationChange(parsed);
+                  }
+                }}
+              />
+            </Field>
+            <p className="settings-note">Minimum time the splash screen stays up at launch, counted from startup (0–10000, 0 opens Basecamp as soon as it is ready).</p>
+          </div>
+
+          <div className="settings-subsection">
+            <label className="settings-toggle">
+              <input
+                type="checkbox"
+                checked={keepRunningInTray}
+                disabled={savingTraySettings}
+                onChange={(event) => {
+                  void handleKeepRunningInTrayToggle(event.target.checked);
+                }}
+              />
+              <span>Keep running in tray</span>
+            </label>
+            <p className="settings-note">Closing the main window hides it and leaves Basecamp in the tray, so quick capture keeps working. Quit from the tray menu.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Quick Capture</h3>
+            <Field label="Capture Camp">
+              <select
+                value={quickCapture.camp_id ?? ''}
+                disabled={savingQuickCapture}
+                onChange={(event) => void handleQuickCaptureSave({ ...quickCapture, camp_id: event.target.value || null })}
+              >
+                <option value="">Choose a camp</option>
+                {camps.map((camp) => (
+                  <option key={camp.id} value={camp.id}>
+                    {camp.name}
+                  </option>
+                ))}
+              </select>
+            </Field>
+            <Field label="Save As">
+              <select
+                value={quickCapture.target}
+                disabled={savingQuickCapture}
+                onChange={(event) =>
+                  void handleQuickCaptureSave({ ...quickCapture, target: event.target.value as QuickCaptureSettings['target'] })
+                }
+              >
+                <option value="message">User message</option>
+                <option value="note">Note artifact</option>
+              </select>
+            </Field>
+            <Field label="Shortcut">
+              <input
+                type="text"
+                value={quickCapture.shortcut ?? ''}
+                placeholder="CommandOrControl+Shift+Space"
+                disabled={savingQuickCapture}
+                onChange={(event) => setQuickCaptureState({ ...quickCapture, shortcut: event.target.value })}
+                onBlur={() => void handleQuickCaptureSave({ ...quickCapture, shortcut: quickCapture.shortcut?.trim() || null })}
+              />
+            </Field>
+            <p className="settings-note">A global shortcut opens a small input that saves straight into the chosen camp, even with the main window closed. Leave the shortcut empty to turn it off.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Daily Digest</h3>
+            <label className="settings-toggle">
+              <input
+                type="checkbox"
+                checked={digest.enabled}
+                disabled={savingDigest}
+                onChange={(event) => void handleDigestSave({ ...digest, enabled: event.target.checked })}
+              />
+              <span>Write a daily digest</span>
+            </label>
+            <Field label="Digest Camp">
+              <select
+                value={digest.camp_id ?? ''}
+                disabled={savingDigest}
+                onChange={(event) => void handleDigestSave({ ...digest, camp_id: event.target.value || null })}
+              >
+                <option value="">Choose a camp</option>
+                {camps.map((camp) => (
+                  <option key={camp.id} value={camp.id}>
+                    {camp.name}
+                  </option>
+                ))}
+              </select>
+            </Field>
+            <Field label="Model">
+              <input
+                type="text"
+                value={digest.model ?? ''}
+                placeholder="Default model"
+                disabled={savingDigest}
+                onChange={(event) => setDigestState({ ...digest, model: event.target.value })}
+                onBlur={() => void handleDigestSave({ ...digest, model: digest.model?.trim() || null })}
+              />
+            </Field>
+            <Field label="Run After (hour)">
+              <input
+                type="number"
+                min={0}
+                max={23}
+                value={digest.hour}
+                disabled={savingDigest}
+                onChange={(event) => void handleDigestSave({ ...digest, hour: Math.min(23, Math.max(0, Number(event.target.value) || 0)) })}
+              />
+            </Field>
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleRunDigestNow()} disabled={runningDigest || !digest.camp_id}>
+                {runningDigest ? 'Writing...' : "Write Yesterday's Digest"}
+              </button>
+            </div>
+            {digestStatus.last_run_at && (
+              <p className="settings-note">Last run {new Date(digestStatus.last_run_at).toLocaleString()}.</p>
+            )}
+            {digestStatus.last_error && <p className="settings-note">Last digest failed: {digestStatus.last_error}</p>}
+            <p className="settings-note">Once a day, yesterday's new messages, saved artifacts and spend across all camps are summarized by the chosen model and saved as a note in the digest camp. Camps in privacy mode contribute counts only.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Updates</h3>
+            <Field label="Update Channel">
+              <select
+                value={updateChannel}
+                onChange={(event) => void handleUpdateChannelChange(event.target.value as UpdateChannel)}
+              >
+                <option value="stable">Stable</option>
+                <option value="beta">Beta</option>
+              </select>
+            </Field>
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleCheckForUpdates()} disabled={checkingUpdates || installingUpdate}>
+                {checkingUpdates ? 'Checking...' : 'Check for Updates'}
+              </button>
+              {updateCheck?.available && (
+                <button type="button" onClick={() => void handleInstallUpdate()} disabled={installingUpdate}>
+                  {installingUpdate ? 'Installing...' : `Install ${updateCheck.version}`}
+                </button>
+              )}
+            </div>
+            {updateCheck?.available && (
+              <>
+                <p className="settings-note">
+                  Basecamp {updateCheck.version} is available (you have {updateCheck.current_version}).
+                </p>
+                {updateCheck.camp_schema_version && updateCheck.camp_schema_version !== updateCheck.current_camp_schema_version && (
+                  <p className="settings-note">
+                    This update moves camps from schema {updateCheck.current_camp_schema_version} to {updateCheck.camp_schema_version}. Camps are upgraded when first opened and can't be read by older builds afterwards.
+                  </p>
+                )}
+                {updateCheck.release_notes && <pre className="settings-note">{updateCheck.release_notes}</pre>}
+              </>
+            )}
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Performance</h3>
+            {performanceStats.length === 0 ? (
+              <p className="settings-note">No requests recorded yet.</p>
+            ) : (
+              <table className="performance-table">
+                <thead>
+                  <tr>
+                    <th>Model</th>
+                    <th>Requests</th>
+                    <th>Latency p50 / p90</th>
+                    <th>First token p50</th>
+                    <th>Tokens/sec p50</th>
+                  </tr>
+                </thead>
+                <tbody>
+                  {performanceStats.map((stats) => (
+                    <tr key={`${stats.provider_kind}:${stats.model_id}`}>
+                      <td>
+                        {stats.model_id}
+                        <span className="settings-note"> {stats.provider_kind}{stats.is_local ? ' · local' : ''}</span>
+                      </td>
+                      <td>
+                        {stats.request_count}
+                        {stats.error_count > 0 && <span className="settings-note"> ({stats.error_count} failed)</span>}
+                      </td>
+                      <td>{stats.latency_ms ? `${Math.round(stats.latency_ms.p50)} / ${Math.round(stats.latency_ms.p90)} ms` : '—'}</td>
+                      <td>{stats.ttfb_ms ? `${Math.round(stats.ttfb_ms.p50)} ms` : '—'}</td>
+                      <td>{stats.tokens_per_second ? stats.tokens_per_second.p50.toFixed(1) : '—'}</td>
+                    </tr>
+                  ))}
+                </tbody>
+              </table>
+            )}
+            <p className="settings-note">Measured on this computer for every chat request, so local and cloud models can be compared directly.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Sync</h3>
+            <Field label="Sync Target">
+              <select
+                value={syncSettings.target?.kind ?? ''}
+                disabled={savingSync}
+                onChange={(event) => handleSyncTargetKindChange(event.target.value as SyncTarget['kind'] | '')}
+              >
+                <option value="">Off</option>
+                <option value="folder">Folder</option>
+                <option value="webdav">WebDAV</option>
+                <option value="s3">S3-compatible bucket</option>
+              </select>
+            </Field>
+            {syncSettings.target?.kind === 'folder' && (
+              <Field label="Folder">
+                <input
+                  type="text"
+                  value={syncSettings.target.path}
+                  placeholder="/Volumes/Shared/basecamp-sync"
+                  onChange={(event) => setSyncSettingsState({ ...syncSettings, target: { kind: 'folder', path: event.target.value } })}
+                />
+              </Field>
+            )}
+            {syncSettings.target?.kind === 'webdav' && (
+              <>
+                <Field label="URL">
+                  <input
+                    type="text"
+                    value={syncSettings.target.url}
+                    placeholder="https://dav.example.com/basecamp"
+                    onChange={(event) =>
+                      setSyncSettingsState({ ...syncSettings, target: { ...(syncSettings.target as Extract<SyncTarget, { kind: 'webdav' }>), url: event.target.value } })
+                    }
+                  />
+                </Field>
+                <Field label="Username">
+                  <input
+                    type="text"
+                    value={syncSettings.target.username}
+                    onChange={(event) =>
+                      setSyncSettingsState({ ...syncSettings, target: { ...(syncSettings.target as Extract<SyncTarget, { kind: 'webdav' }>), username: event.target.value } })
+                    }
+                  />
+                </Field>
+              </>
+            )}
+            {syncSettings.target?.kind === 's3' && (
+              <>
+                {(['endpoint', 'bucket', 'region', 'access_key_id', 'prefix'] as const).map((field) => (
+                  <Field key={field} label={{ endpoint: 'Endpoint', bucket: 'Bucket', region: 'Region', access_key_id: 'Access Key ID', prefix: 'Prefix' }[field]}>
+                    <input
+                      type="text"
+                      value={(syncSettings.target as Extract<SyncTarget, { kind: 's3' }>)[field]}
+                      placeholder={field === 'endpoint' ? 'https://s3.us-east-1.amazonaws.com' : undefined}
+                      onChange={(event) =>
+                        setSyncSettingsState({ ...syncSettings, target: { ...(syncSettings.target as Extract<SyncTarget, { kind: 's3' }>), [field]: event.target.value } })
+                      }
+                    />
+                  </Field>
+                ))}
+              </>
+            )}
+            {(syncSettings.target?.kind === 'webdav' || syncSettings.target?.kind === 's3') && (
+              <Field label={syncSettings.target.kind === 's3' ? 'Secret Access Key' : 'Password'}>
+                <input
+                  type="password"
+                  value={syncSecret}
+                  placeholder="Leave empty to keep the saved one"
+                  onChange={(event) => setSyncSecret(event.target.value)}
+                />
+              </Field>
+            )}
+            {syncSettings.target && (
+              <Field label="Device Name">
+                <input
+                  type="text"
+                  value={syncSettings.device_name}
+                  placeholder="Laptop"
+                  onChange={(event) => setSyncSettingsState({ ...syncSettings, device_name: event.target.value })}
+                />
+              </Field>
+            )}
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleSyncSave()} disabled={savingSync}>
+                {savingSync ? 'Saving...' : 'Save Sync Settings'}
+              </button>
+              <button type="button" className="secondary" onClick={() => void handleRunSync()} disabled={runningSync || !syncSettings.target}>
+                {runningSync ? 'Syncing...' : 'Sync Now'}
+              </button>
+            </div>
+            {syncSettings.last_synced_at && (
+              <p className="settings-note">Last synced {new Date(syncSettings.last_synced_at).toLocaleString()}.</p>
+            )}
+            {lastSyncReport && lastSyncReport.conflicts.length > 0 && (
+              <ul className="crash-report-list">
+                {lastSyncReport.conflicts.map((path) => (
+                  <li key={path}>Kept conflicted copy {path}</li>
+                ))}
+              </ul>
+            )}
+            <p className="settings-note">Keeps camps in step with another Basecamp install through a shared folder, WebDAV server or S3 bucket you control. Files edited on both machines keep the newer version and save the other as a conflicted copy beside it.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Sync Conflicts</h3>
+            {syncConflicts.length === 0 ? (
+              <p className="settings-note">No sync conflicts.</p>
+            ) : (
+              <ul className="crash-report-list">
+                {syncConflicts.map((conflict) => (
+                  <li key={`${conflict.camp_id}/${conflict.path}/${conflict.kind}`}>
+                    <div>
+                      <strong>{conflict.camp_name}</strong>
+                      <span className="settings-note"> {conflict.path}</span>
+                      <p className="settings-note">{conflict.detail}</p>
+                    </div>
+                    {conflict.mergeable && (
+                      <button
+                        type="button"
+                        className="secondary"
+                        onClick={() => void handlePreviewMerge(conflict)}
+                        disabled={mergingConflict}
+                      >
+                        Merge...
+                      </button>
+                    )}
+                  </li>
+                ))}
+              </ul>
+            )}
+            {mergePreview && (
+              <>
+                <p className="settings-note">
+                  Merging {mergePreview.conflict.path} into {mergePreview.conflict.camp_name} adds {mergePreview.report.added_count} message(s) and skips {mergePreview.report.duplicate_count} duplicate(s), for {mergePreview.report.merged_count} in total. The copy moves to the camp trash.
+                </p>
+                <div className="button-row">
+                  <button type="button" onClick={() => void handleConfirmMerge()} disabled={mergingConflict}>
+                    {mergingConflict ? 'Merging...' : 'Merge'}
+                  </button>
+                  <button type="button" className="secondary" onClick={() => setMergePreview(null)} disabled={mergingConflict}>
+                    Cancel
+                  </button>
+                </div>
+              </>
+            )}
+            <div className="button-row">
+              <button type="button" className="secondary" onClick={() => void handleCheckSyncConflicts()} disabled={checkingSyncConflicts}>
+                {checkingSyncConflicts ? 'Checking...' : 'Check Again'}
+              </button>
+            </div>
+            <p className="settings-note">Workspaces in iCloud Drive, Dropbox, OneDrive or Syncthing can end up with conflicted copies when two computers edit a camp at once. Transcript copies can be merged; other files need to be compared by hand.</p>
+          </div>
+
+          <div className="settings-subsection">
+            <h3>Crash Reports</h3>
+            {crashReports.length === 0 ? (
+              <p className="settings-note">No crash reports.</p>
+            ) : (
+              <ul className="crash-report-list">
+                {crashReports.map((report) => (
+                  <li key={report.id}>
+                    <div>
+                      <strong>{report.kind === 'panic' ? 'Crash' : report.command ?? 'Command failure'}</strong>
+                      <span className="settings-note"> {new Date(report.created_at).toLocaleString()}</span>
+                      <p className="settings-note">{report.summary}</p>
+                    </div>
+                    <button
+                      type="button"
+                      className="secondary"
+                      onClick={() => void handleExportCrashReport(report.id)}
+                      disabled={exportingCrashReportId !== null}
+                    >
+                      {exportingCrashReportId === report.id ? 'Exporting...' : 'Export'}
+                    </button>
+                  </li>
+                ))}
+              </ul>
+            )}
+            <p className="settings-note">Crashes and unexpected errors are saved on this computer with keys, emails and your home folder masked. Nothing is sent anywhere; export a report to attach it to an issue.</p>
+          </div>
         </>
       )}
 
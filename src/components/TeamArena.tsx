@@ -1,11 +1,16 @@
 import { useEffect, useMemo, useState } from 'react';
 
 import {
+  answerClarification,
+  archiveTeamRuns,
   decomposeTask,
   executeAgentStep,
   getTeamBus,
+  getTeamRunReport,
   getTeamStatus,
+  promoteAllPassing,
   promoteArtifact,
+  runReflectionBatch,
   runReflectionLoop,
 } from '../lib/db';
 import {
@@ -14,13 +19,26 @@ import {
   listenTeamReflectionRound,
   listenTeamStepComplete,
 } from '../lib/team';
-import type { TeamBusEntry, TeamStatus } from '../lib/types';
+import type {
+  ClarificationRequest,
+  DecompositionPlan,
+  TeamBusEntry,
+  TeamRunReport,
+  TeamStatus,
+} from '../lib/types';
 import { AgentCard } from './AgentCard';
 
 type TeamArenaProps = {
   campId: string;
 };
 
+/** Where a run stopped when the supervisor or an agent asked the user a question. */
+type PausedRun = {
+  plan: DecompositionPlan;
+  completed: string[];
+  drafts: string[];
+};
+
 function toContentPreview(content: unknown): string {
   if (typeof content === 'string') {
     return content.slice(0, 180);
@@ -34,6 +52,17 @@ function toContentPreview(content: unknown): string {
   }
 }
 
+function formatCost(cost: number | null): string {
+  return cost === null ? 'unknown cost' : `$${cost.toFixed(4)}`;
+}
+
+function formatDuration(durationMs: number | null | undefined): string {
+  if (durationMs === null || durationMs === undefined) {
+    return '—';
+  }
+  return durationMs < 60_000 ? `${(durationMs / 1000).toFixed(1)}s` : `${Math.round(durationMs / 60_000)}m`;
+}
+
 function sumTokenUsage(entries: TeamBusEntry[]): Record<string, { input: number; output: number }> {
   const totals: Record<string, { input: number; output: number }> = {};
   for (const entry of entries) {
@@ -49,19 +78,27 @@ function sumTokenUsage(entries: TeamBusEntry[]): Record<string, { input: number;
 export function TeamArena({ campId }: TeamArenaProps) {
   const [status, setStatus] = useState<TeamStatus | null>(null);
   const [busEntries, setBusEntries] = useState<TeamBusEntry[]>([]);
+  const [runReport, setRunReport] = useState<TeamRunReport | null>(null);
   const [task, setTask] = useState('');
   const [isRunning, setIsRunning] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [lastReflectionRound, setLastReflectionRound] = useState<number | null>(null);
+  const [pausedRun, setPausedRun] = useState<PausedRun | null>(null);
+  const [clarificationAnswer, setClarificationAnswer] = useState('');
 
   const loadArenaState = async () => {
     setIsLoading(true);
     setError(null);
     try {
-      const [nextStatus, nextBus] = await Promise.all([getTeamStatus(campId), getTeamBus(campId)]);
+      const [nextStatus, nextBus, nextReport] = await Promise.all([
+        getTeamStatus(campId),
+        getTeamBus(campId),
+        getTeamRunReport(campId),
+      ]);
       setStatus(nextStatus);
       setBusEntries(nextBus);
+      setRunReport(nextReport);
     } catch (loadError) {
       setError(loadError instanceof Error ? loadError.message : 'Unable to load team arena data.');
     } finally {
@@ -132,6 +169,39 @@ export function TeamArena({ campId }: TeamArenaProps) {
 
   const tokenTotals = useMemo(() => sumTokenUsage(busEntries), [busEntries]);
 
+  const runPlan = async (plan: DecompositionPlan, completedSteps: string[], drafts: string[]) => {
+    const completed = new Set<string>(completedSteps);
+    const emittedDrafts = [...drafts];
+
+    while (completed.size < plan.steps.length) {
+      const readySteps = plan.steps.filter(
+        (step) => !completed.has(step.step_id) && step.depends_on.every((dependency) => completed.has(dependency)),
+      );
+
+      if (readySteps.length === 0) {
+        throw new Error('No executable steps found. Check decomposition dependencies.');
+      }
+
+      for (const step of readySteps) {
+        const result = await executeAgentStep(campId, step.assigned_to, step);
+        if (result.clarification) {
+          setPausedRun({ plan, completed: [...completed], drafts: emittedDrafts });
+          return;
+        }
+        completed.add(step.step_id);
+        emittedDrafts.push(result.draft_path);
+      }
+    }
+
+    setPausedRun(null);
+    if (plan.reflection_required && status?.reflection_loops) {
+      const rounds = status.max_reflection_rounds > 0 ? status.max_reflection_rounds : 2;
+      for (const draftPath of emittedDrafts) {
+        await runReflectionLoop(campId, draftPath, rounds);
+      }
+    }
+  };
+
   const handleRunTask = async () => {
     if (!task.trim()) {
       return;
@@ -139,39 +209,54 @@ export function TeamArena({ campId }: TeamArenaProps) {
 
     setIsRunning(true);
     setError(null);
+    // A new task starts a new bus run.
+    setBusEntries([]);
 
     try {
       const plan = await decomposeTask(campId, task.trim());
+      if (plan.clarification) {
+        setPausedRun({ plan, completed: [], drafts: [] });
+      } else {
+        await runPlan(plan, [], []);
+      }
 
-      const completed = new Set<string>();
-      const emittedDrafts: string[] = [];
-
-      while (completed.size < plan.steps.length) {
-        const readySteps = plan.steps.filter(
-          (step) => !completed.has(step.step_id) && step.depends_on.every((dependency) => completed.has(dependency)),
-        );
+      await loadArenaState();
+    } catch (runError) {
+      setError(runError instanceof Error ? runError.message : 'Team execution failed.');
+    } finally {
+      setIsRunning(false);
+    }
+  };
 
-        if (readySteps.length === 0) {
-          throw new Error('No executable steps found. Check decomposition dependencies.');
-        }
+  const handleAnswerClarification = async (request: ClarificationRequest) => {
+    if (!clarificationAnswer.trim()) {
+      return;
+    }
 
-        for (const step of readySteps) {
-          const result = await executeAgentStep(campId, step.assigned_to, step);
-          completed.add(step.step_id);
-          emittedDrafts.push(result.draft_path);
-        }
-      }
+    setIsRunning(true);
+    setError(null);
 
-      if (plan.reflection_required && status?.reflection_loops) {
-        const rounds = status.max_reflection_rounds > 0 ? status.max_reflection_rounds : 2;
-        for (const draftPath of emittedDrafts) {
-          await runReflectionLoop(campId, draftPath, rounds);
+    try {
+      const resolution = await answerClarification(campId, request.request_id, clarificationAnswer.trim());
+      setClarificationAnswer('');
+
+      if (resolution.kind === 'plan') {
+        if (resolution.plan.clarification) {
+          setPausedRun({ plan: resolution.plan, completed: [], drafts: [] });
+        } else {
+          await runPlan(resolution.plan, [], []);
         }
+      } else if (pausedRun && !resolution.result.clarification) {
+        await runPlan(
+          pausedRun.plan,
+          [...pausedRun.completed, resolution.result.step_id],
+          [...pausedRun.drafts, resolution.result.draft_path],
+        );
       }
 
       await loadArenaState();
-    } catch (runError) {
-      setError(runError instanceof Error ? runError.message : 'Team execution failed.');
+    } catch (answerError) {
+      setError(answerError instanceof Error ? answerError.message : 'Unable to answer clarification.');
     } finally {
       setIsRunning(false);
     }
@@ -186,6 +271,42 @@ export function TeamArena({ campId }: TeamArenaProps) {
     }
   };
 
+  const handleReflectAllDrafts = async () => {
+    setIsRunning(true);
+    setError(null);
+    try {
+      const results = await runReflectionBatch(campId, status?.max_reflection_rounds ?? 0, true);
+      const failed = results.filter((item) => item.error);
+      if (failed.length > 0) {
+        setError(failed.map((item) => `${item.artifact_path}: ${item.error}`).join('\n'));
+      }
+      await loadArenaState();
+    } catch (reflectError) {
+      setError(reflectError instanceof Error ? reflectError.message : 'Unable to reflect on drafts.');
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  const handlePromoteAllPassing = async () => {
+    try {
+      await promoteAllPassing(campId);
+      await loadArenaState();
+    } catch (promoteError) {
+      setError(promoteError instanceof Error ? promoteError.message : 'Unable to promote passing drafts.');
+    }
+  };
+
+  const handleArchiveRuns = async () => {
+    try {
+      await archiveTeamRuns(campId);
+    } catch (archiveError) {
+      setError(archiveError instanceof Error ? archiveError.message : 'Unable to archive team runs.');
+    }
+  };
+
+  const pendingClarification = status?.pending_clarifications[0] ?? null;
+
   const critiqueEntries = useMemo(
     () => busEntries.filter((entry) => entry.type === 'critique').slice(-4).reverse(),
     [busEntries],
@@ -195,6 +316,9 @@ export function TeamArena({ campId }: TeamArenaProps) {
     <section className="team-arena-shell">
       <header className="team-arena-header">
         <h3>Team Arena</h3>
+        <button type="button" onClick={() => void handleArchiveRuns()} disabled={isLoading || isRunning}>
+          Archive Old Runs
+        </button>
         <button type="button" onClick={() => void loadArenaState()} disabled={isLoading || isRunning}>
           Refresh
         </button>
@@ -218,6 +342,32 @@ export function TeamArena({ campId }: TeamArenaProps) {
       )}
       {error && <p className="error-line">{error}</p>}
 
+      {pendingClarification && (
+        <div className="team-clarification">
+          <p>
+            <strong>{pendingClarification.from}</strong>
+            {pendingClarification.step_id ? ` (step ${pendingClarification.step_id})` : ''} asks:{' '}
+            {pendingClarification.question}
+          </p>
+          <textarea
+            value={clarificationAnswer}
+            onChange={(event) => setClarificationAnswer(event.target.value)}
+            placeholder="Your answer"
+            disabled={isRunning}
+          />
+          <button
+            type="button"
+            onClick={() => void handleAnswerClarification(pendingClarification)}
+            disabled={isRunning || !clarificationAnswer.trim()}
+          >
+            Answer and resume
+          </button>
+          {!pausedRun && pendingClarification.step_id && (
+            <p className="hint">Only this step resumes; run the task again to continue the rest of the plan.</p>
+          )}
+        </div>
+      )}
+
       <div className="team-arena-grid">
         <div className="team-timeline-panel">
           <h4>Timeline</h4>
@@ -263,6 +413,27 @@ export function TeamArena({ campId }: TeamArenaProps) {
             {!status?.steps.length && <li className="hint">No active decomposition plan.</li>}
           </ul>
 
+          <h4>Run Report</h4>
+          {runReport?.plan_id ? (
+            <div className="team-run-report">
+              <p>
+                {runReport.total_usage.input} in / {runReport.total_usage.output} out ·{' '}
+                {formatCost(runReport.estimated_cost)} · {formatDuration(runReport.duration_ms)}
+              </p>
+              <ul className="team-step-list">
+                {runReport.steps.map((step) => (
+                  <li key={step.step_id} className="team-step">
+                    <strong>{step.step_id}</strong>
+                    <span>{formatCost(step.estimated_cost)}</span>
+                    <span>{formatDuration(step.duration_ms)}</span>
+                  </li>
+                ))}
+              </ul>
+            </div>
+          ) : (
+            <p className="hint">No run to report yet.</p>
+          )}
+
           <h4>Artifacts</h4>
           <div className="team-artifacts">
             <div>
@@ -278,6 +449,16 @@ export function TeamArena({ campId }: TeamArenaProps) {
                 ))}
                 {(status?.artifacts.drafts ?? []).length === 0 && <li className="hint">No drafts.</li>}
               </ul>
+              {(status?.artifacts.drafts ?? []).length > 0 && (
+                <div className="team-artifact-actions">
+                  <button type="button" onClick={() => void handleReflectAllDrafts()} disabled={isRunning}>
+                    Reflect on all
+                  </button>
+                  <button type="button" onClick={() => void handlePromoteAllPassing()} disabled={isRunning}>
+                    Promote passing
+                  </button>
+                </div>
+              )}
             </div>
             <div>
               <h5>Promoted</h5>
@@ -1,8 +1,15 @@
 import { useEffect, useMemo, useState } from 'react';
 import type { FormEvent } from 'react';
 
-import { createTeamAgent, getTeamStatus, removeTeamAgent, updateTeamSettings } from '../lib/db';
-import type { TeamAgentCreateInput, TeamStatus } from '../lib/types';
+import {
+  createTeamAgent,
+  createTeamFromTemplate,
+  getTeamStatus,
+  listTeamTemplates,
+  removeTeamAgent,
+  updateTeamSettings,
+} from '../lib/db';
+import type { CritiqueRubric, TeamAgentCreateInput, TeamStatus, TeamTemplate } from '../lib/types';
 import { AgentCard } from './AgentCard';
 
 type TeamConfigProps = {
@@ -19,6 +26,26 @@ function parseTools(raw: string): string[] {
     .filter((item) => item.length > 0);
 }
 
+/** One criterion per line, as `name: description` or just `name`. */
+function parseRubricCriteria(raw: string): CritiqueRubric['criteria'] {
+  return raw
+    .split('\n')
+    .map((line) => line.trim())
+    .filter((line) => line.length > 0)
+    .map((line) => {
+      const separator = line.indexOf(':');
+      return separator === -1
+        ? { name: line, description: '' }
+        : { name: line.slice(0, separator).trim(), description: line.slice(separator + 1).trim() };
+    });
+}
+
+function formatRubricCriteria(rubric: CritiqueRubric): string {
+  return rubric.criteria
+    .map((criterion) => (criterion.description ? `${criterion.name}: ${criterion.description}` : criterion.name))
+    .join('\n');
+}
+
 export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
   const [status, setStatus] = useState<TeamStatus | null>(null);
   const [isLoading, setIsLoading] = useState(false);
@@ -28,12 +55,19 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
   const [supervisorModel, setSupervisorModel] = useState('');
   const [reflectionLoops, setReflectionLoops] = useState(true);
   const [maxRounds, setMaxRounds] = useState(2);
+  const [mirrorToTranscript, setMirrorToTranscript] = useState(true);
+  const [rubricCriteria, setRubricCriteria] = useState('');
+  const [rubricThreshold, setRubricThreshold] = useState(4);
 
   const [agentId, setAgentId] = useState('researcher');
   const [agentRole, setAgentRole] = useState('Researcher');
   const [agentModel, setAgentModel] = useState(modelOptions[0] ?? 'openrouter/auto');
   const [agentTools, setAgentTools] = useState(DEFAULT_TOOLS);
   const [agentDescription, setAgentDescription] = useState('');
+  const [agentContinueContext, setAgentContinueContext] = useState(false);
+
+  const [templates, setTemplates] = useState<TeamTemplate[]>([]);
+  const [templateId, setTemplateId] = useState('');
 
   const sortedModels = useMemo(() => {
     const values = modelOptions.filter((value) => value.trim().length > 0);
@@ -52,6 +86,9 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
       setSupervisorModel(next.supervisor_model || sortedModels[0] || 'openrouter/auto');
       setReflectionLoops(next.reflection_loops);
       setMaxRounds(next.max_reflection_rounds);
+      setMirrorToTranscript(next.mirror_to_transcript);
+      setRubricCriteria(formatRubricCriteria(next.critique_rubric));
+      setRubricThreshold(next.critique_rubric.pass_threshold);
     } catch (loadError) {
       setError(loadError instanceof Error ? loadError.message : 'Unable to load team settings.');
     } finally {
@@ -64,6 +101,15 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [campId]);
 
+  useEffect(() => {
+    void listTeamTemplates()
+      .then((next) => {
+        setTemplates(next);
+        setTemplateId((current) => current || next[0]?.id || '');
+      })
+      .catch(() => setTemplates([]));
+  }, []);
+
   useEffect(() => {
     if (!sortedModels.includes(agentModel)) {
       setAgentModel(sortedModels[0]);
@@ -79,6 +125,11 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
         supervisor_model: supervisorModel,
         reflection_loops: reflectionLoops,
         max_reflection_rounds: maxRounds,
+        mirror_to_transcript: mirrorToTranscript,
+        critique_rubric: {
+          criteria: parseRubricCriteria(rubricCriteria),
+          pass_threshold: rubricThreshold,
+        },
       });
       setStatus(next);
     } catch (saveError) {
@@ -99,6 +150,7 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
         model: agentModel,
         tool_subset: parseTools(agentTools),
         description: agentDescription,
+        continue_context: agentContinueContext,
       };
       await createTeamAgent(campId, payload);
       await loadStatus();
@@ -109,6 +161,23 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
     }
   };
 
+  const handleApplyTemplate = async (event: FormEvent) => {
+    event.preventDefault();
+    if (!templateId) {
+      return;
+    }
+    setSaving(true);
+    setError(null);
+    try {
+      const next = await createTeamFromTemplate(campId, templateId, supervisorModel);
+      setStatus(next);
+    } catch (templateError) {
+      setError(templateError instanceof Error ? templateError.message : 'Unable to apply team template.');
+    } finally {
+      setSaving(false);
+    }
+  };
+
   const handleRemoveAgent = async (id: string) => {
     setSaving(true);
     setError(null);
@@ -166,11 +235,61 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
           />
         </label>
 
+        <label className="settings-toggle">
+          <input
+            type="checkbox"
+            checked={mirrorToTranscript}
+            onChange={(event) => setMirrorToTranscript(event.target.checked)}
+          />
+          Mirror team activity into camp chat
+        </label>
+
+        <label>
+          Critique rubric (one criterion per line, optional `: description`)
+          <textarea
+            value={rubricCriteria}
+            onChange={(event) => setRubricCriteria(event.target.value)}
+            rows={3}
+            placeholder={'Accuracy: claims are supported\nClarity'}
+          />
+        </label>
+
+        <label>
+          Rubric pass threshold (1-5)
+          <input
+            type="number"
+            min={1}
+            max={5}
+            step={0.5}
+            value={rubricThreshold}
+            onChange={(event) => setRubricThreshold(Number(event.target.value))}
+          />
+        </label>
+
         <button type="submit" disabled={saving}>
           Save Team Settings
         </button>
       </form>
 
+      {status && status.agents.length === 0 && templates.length > 0 && (
+        <form className="team-settings-form" onSubmit={handleApplyTemplate}>
+          <label>
+            Start from a template
+            <select value={templateId} onChange={(event) => setTemplateId(event.target.value)}>
+              {templates.map((template) => (
+                <option key={template.id} value={template.id}>
+                  {template.name}
+                </option>
+              ))}
+            </select>
+          </label>
+          <p className="hint">{templates.find((template) => template.id === templateId)?.description}</p>
+          <button type="submit" disabled={saving}>
+            Apply Template
+          </button>
+        </form>
+      )}
+
       <div className="team-agent-list">
         {(status?.agents ?? []).map((agent) => (
           <AgentCard key={agent.id} agent={agent} onRemove={handleRemoveAgent} />
@@ -213,6 +332,14 @@ export function TeamConfig({ campId, modelOptions }: TeamConfigProps) {
             placeholder="Gathers information and writes findings"
           />
         </label>
+        <label className="settings-toggle">
+          <input
+            type="checkbox"
+            checked={agentContinueContext}
+            onChange={(event) => setAgentContinueContext(event.target.checked)}
+          />
+          Continue from earlier steps in the same plan
+        </label>
         <button type="submit" disabled={saving}>
           Add / Update Agent
         </button>
@@ -1,14 +1,20 @@
 import type { Camp, CampArtifactMetadata, CampMessage } from '../lib/types';
+import { MarkdownContent } from './MarkdownContent';
 import { ViewState } from './ui/ViewState';
 
 type TranscriptViewProps = {
   selectedCamp: Camp | null;
   streamingText: string;
+  /** Live generation speed while the reply streams, when the provider reports it. */
+  streamingTokensPerSecond?: number | null;
   artifactById: Map<string, CampArtifactMetadata>;
   isSending: boolean;
   promotingMessageId: string | null;
   onBranchFromMessage: (message: CampMessage) => void;
   onReplayFromMessage: (message: CampMessage) => void;
+  /** Opens the stored run behind an assistant reply. */
+  onOpenRunForMessage: (message: CampMessage) => void;
+  onUndoLastExchange: () => void;
   onPromoteMessageToArtifact: (message: CampMessage) => void;
 };
 
@@ -35,13 +41,17 @@ export function TranscriptView(props: TranscriptViewProps) {
         <article className="message message-assistant streaming">
           <header>
             <span>Assistant</span>
-            <time>streaming...</time>
+            <time>
+              {props.streamingTokensPerSecond != null
+                ? `streaming · ${props.streamingTokensPerSecond.toFixed(1)} tok/s`
+                : 'streaming...'}
+            </time>
           </header>
           <p>{props.streamingText}</p>
         </article>
       ) : null}
 
-      {[...(props.selectedCamp?.transcript || [])].reverse().map((message) => (
+      {[...(props.selectedCamp?.transcript || [])].reverse().map((message, index) => (
         <article key={message.id} className={`message message-${message.role}`}>
           <header>
             <span>{roleLabel(message.role)}</span>
@@ -78,11 +88,32 @@ export function TranscriptView(props: TranscriptViewProps) {
                   >
                     [REPLAY]
                   </button>
+                  {message.run_id ? (
+                    <button
+                      type="button"
+                      className="icon-button"
+                      onClick={() => props.onOpenRunForMessage(message)}
+                      title="Open the run record: request, tool calls and latency"
+                    >
+                      [RUN]
+                    </button>
+                  ) : null}
                 </>
               ) : null}
+              {index === 0 ? (
+                <button
+                  type="button"
+                  className="icon-button"
+                  onClick={props.onUndoLastExchange}
+                  disabled={!props.selectedCamp || props.isSending}
+                  title="Undo last exchange and edit the prompt"
+                >
+                  [UNDO]
+                </button>
+              ) : null}
             </div>
           </header>
-          <p>{message.content}</p>
+          {message.role === 'tool' ? <p>{message.content}</p> : <MarkdownContent markdown={message.content} />}
           {message.role === 'assistant' && message.tool_calls && message.tool_calls.length > 0 ? (
             <p className="hint">Tool calls: {message.tool_calls.map((toolCall) => toolCall.function.name).join(', ')}</p>
           ) : null}
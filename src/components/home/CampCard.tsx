@@ -1,10 +1,13 @@
-import type { CampSummary } from '../../lib/types';
+import type { CampProject, CampSummary } from '../../lib/types';
 
 type CampCardProps = {
   camp: CampSummary;
   promptPreview: string;
   onOpen: () => void;
   onDelete?: () => void;
+  onToggleFavorite?: () => void;
+  projects?: CampProject[];
+  onProjectChange?: (projectId: string | null) => void;
 };
 
 function formatRelativeTime(ts: number): string {
@@ -47,6 +50,18 @@ export function CampCard(props: CampCardProps) {
         </button>
       )}
 
+      {props.onToggleFavorite && (
+        <button
+          type="button"
+          className="camp-card-favorite"
+          onClick={props.onToggleFavorite}
+          aria-pressed={props.camp.favorite ?? false}
+          aria-label={props.camp.favorite ? `Unfavorite ${props.camp.name}` : `Favorite ${props.camp.name}`}
+        >
+          {props.camp.favorite ? '★' : '☆'}
+        </button>
+      )}
+
       <button type="button" className="camp-card-open" onClick={props.onOpen} aria-label={`Open ${props.camp.name}`}>
         <header className="camp-card-header">
           <div className="camp-card-title">
@@ -58,6 +73,22 @@ export function CampCard(props: CampCardProps) {
 
         <p className="camp-card-prompt">{props.promptPreview}</p>
       </button>
+
+      {props.onProjectChange && props.projects && props.projects.length > 0 ? (
+        <select
+          className="camp-card-project"
+          value={props.camp.project_id ?? ''}
+          onChange={(event) => props.onProjectChange?.(event.target.value || null)}
+          aria-label={`Project for ${props.camp.name}`}
+        >
+          <option value="">No project</option>
+          {props.projects.map((project) => (
+            <option key={project.id} value={project.id}>
+              {project.name}
+            </option>
+          ))}
+        </select>
+      ) : null}
     </article>
   );
 }
@@ -3,11 +3,13 @@ import { runAppendEvent, runStart } from './db';
 import {
   runToolUseLoop,
   streamOpenRouterChatCompletion,
+  toolLoopAbortMessage,
   type OpenRouterChatMessage,
   type OpenRouterChatRequestPayload,
   type OpenRouterTelemetryHooks,
   type OpenRouterToolLoopExecutionInput,
   type OpenRouterToolSpec,
+  type StreamProgressEvent,
 } from './openrouter';
 import type {
   ApprovalPolicy,
@@ -15,9 +17,11 @@ import type {
   CampAppendMessagePayload,
   CampArtifact,
   CampToolCall,
+  DefaultContextFile,
   RunEventKind,
   RunStateEvent,
   TokenUsage,
+  ToolLoopAbort,
 } from './types';
 
 export type CampRuntimeTranscriptPayload = Omit<CampAppendMessagePayload, 'camp_id' | 'included_artifact_ids'>;
@@ -26,9 +30,13 @@ export type RunCampChatRuntimeInput = {
   campId: string;
   camp: Camp;
   selectedArtifacts: CampArtifact[];
+  /** The camp's default context files, read just before the run. */
+  contextFiles?: DefaultContextFile[];
   temperature: number;
   maxTokens: number;
   onToken: (token: string) => void;
+  onProgress?: (progress: StreamProgressEvent) => void;
+  onQueued?: (position: number) => void;
   tools?: OpenRouterToolSpec[];
   executeToolCall?: (input: OpenRouterToolLoopExecutionInput) => Promise<string>;
   correlationId?: string;
@@ -56,6 +64,8 @@ export type RunCampChatRuntimeResult = {
   resolvedModel: string | null;
   composedInputBreakdown: ComposedInputBreakdown;
   runId?: string;
+  /** Set when the tool loop guard stopped the turn early. */
+  loopAborted?: ToolLoopAbort;
 };
 
 function messageContentToString(content: OpenRouterChatMessage['content']): string {
@@ -177,6 +187,7 @@ export async function runCampChatRuntime(input: RunCampChatRuntimeInput): Promis
   const composed = composeCampOpenRouterRequestWithBreakdown({
     camp: input.camp,
     selectedArtifacts: input.selectedArtifacts,
+    contextFiles: input.contextFiles,
     userMessage: '',
     temperature: input.temperature,
     maxTokens: input.maxTokens,
@@ -195,6 +206,8 @@ export async function runCampChatRuntime(input: RunCampChatRuntimeInput): Promis
   // Start a durable run when using tools
   let runId: string | undefined;
   if (tools && input.executeToolCall) {
+    let maxRepeatedToolCalls: number | undefined;
+    let maxTurnTokens: number | undefined;
     try {
       const runResult = await runStart(input.campId, {
         max_iterations: maxIterations,
@@ -202,6 +215,10 @@ export async function runCampChatRuntime(input: RunCampChatRuntimeInput): Promis
         approval_policy: approvalPolicy,
       });
       runId = runResult.run_id;
+      // The backend clamps the loop limits; use what it settled on.
+      const { max_repeated_tool_calls: repeatLimit, max_turn_tokens: tokenLimit } = runResult.config;
+      maxRepeatedToolCalls = typeof repeatLimit === 'number' ? repeatLimit : undefined;
+      maxTurnTokens = typeof tokenLimit === 'number' ? tokenLimit : undefined;
     } catch {
       // If run_start fails (e.g. workspace not set), continue without run state.
     }
@@ -222,15 +239,27 @@ export async function runCampChatRuntime(input: RunCampChatRuntimeInput): Promis
         model: requestPayload.model,
         temperature: requestPayload.temperature,
         max_tokens: requestPayload.max_tokens,
+        stop: requestPayload.stop,
+        logit_bias: requestPayload.logit_bias,
         executeToolCall: wrappedExecuteToolCall,
         maxIterations,
+        maxRepeatedToolCalls,
+        maxTurnTokens,
         correlationId: input.correlationId,
         telemetry: input.telemetry,
       },
     );
 
     if (runId) {
-      await emitRunEvent(input.campId, makeRunStateEvent(runId, 'run_completed'));
+      await emitRunEvent(
+        input.campId,
+        looped.loopAborted
+          ? makeRunStateEvent(runId, 'loop_aborted', {
+            result_json: JSON.stringify(looped.loopAborted),
+            error: toolLoopAbortMessage(looped.loopAborted),
+          })
+          : makeRunStateEvent(runId, 'run_completed'),
+      );
     }
 
     assertNonEmptyOutput(looped.outputText, looped.transcriptMessages.length > 1);
@@ -245,12 +274,16 @@ export async function runCampChatRuntime(input: RunCampChatRuntimeInput): Promis
       resolvedModel: looped.resolvedModel,
       composedInputBreakdown: composed.breakdown,
       runId,
+      loopAborted: looped.loopAborted,
     };
   }
 
   const streamed = await streamOpenRouterChatCompletion(requestPayload, input.onToken, {
+    campId: input.campId,
     correlationId: input.correlationId,
     telemetry: input.telemetry,
+    onProgress: input.onProgress,
+    onQueued: input.onQueued,
   });
   assertNonEmptyOutput(streamed.outputText);
 
@@ -295,7 +328,7 @@ function wrapToolCallWithRunState(
 
     try {
       const result = await Promise.race([
-        executeToolCall(input),
+        executeToolCall({ ...input, runId }),
         new Promise<never>((_, reject) =>
           setTimeout(() => reject(new Error(`Tool "${toolName}" timed out after ${toolTimeoutSecs}s`)), toolTimeoutSecs * 1000),
         ),
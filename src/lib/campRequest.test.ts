@@ -15,7 +15,7 @@ function makeCamp(transcript: CampMessage[] = []): Camp {
       updated_at: 1,
     },
     system_prompt: '  Be precise.  ',
-    memory: { z: 3, a: 1 },
+    memory: { z: 3, a: 1, scratchpad: { plan: 'draft the outline' } },
     transcript,
     context_path: '/tmp/context',
   };
@@ -211,6 +211,36 @@ describe('campRequest composition', () => {
     });
   });
 
+  it('should layer global instructions above the camp system prompt', () => {
+    const messages = composeCampMessages({
+      camp: { ...makeCamp([]), global_instructions: 'Use British spelling.\n' },
+      userMessage: 'hello',
+    });
+
+    expect(messages[0]).toEqual({
+      role: 'system',
+      content: '# Global instructions\n\nUse British spelling.\n\n---\n\n# Camp instructions\n\nBe precise.',
+    });
+    expect(composeCampMessages({ camp: makeCamp([]), userMessage: 'hello' })[0].content).toBe('Be precise.');
+  });
+
+  it('should attach readable default context files before the transcript', () => {
+    const messages = composeCampMessages({
+      camp: makeCamp([]),
+      userMessage: 'hello',
+      contextFiles: [
+        { path: 'docs/spec.md', status: 'outdated', content: 'Ship v2.', modified_at: 1, bytes: 8 },
+        { path: 'gone.md', status: 'missing', content: null, modified_at: null, bytes: 0 },
+      ],
+    });
+
+    const contextMessages = messages.filter(
+      (message) => typeof message.content === 'string' && message.content.startsWith('Context file: '),
+    );
+    expect(contextMessages).toEqual([{ role: 'system', content: 'Context file: docs/spec.md\n\nShip v2.' }]);
+    expect(messages[messages.length - 1]).toEqual({ role: 'user', content: 'hello' });
+  });
+
   it('should only set tool_choice when tools are provided', () => {
     const camp = makeCamp([]);
 
@@ -1,8 +1,11 @@
-import type { Camp, CampArtifact, CampMessageAttachment } from './types';
+import type { Camp, CampArtifact, CampMessageAttachment, DefaultContextFile } from './types';
 import type { OpenRouterChatMessage, OpenRouterChatMessageContentPart, OpenRouterChatRequestPayload, OpenRouterToolSpec } from './openrouter';
+import { qualifyToolName } from './tools/registry';
 
 const MAX_ARTIFACT_CHARS_PER_ITEM = 8_000;
 const MAX_ARTIFACT_CHARS_TOTAL = 40_000;
+const MAX_CONTEXT_FILE_CHARS_PER_ITEM = 16_000;
+const MAX_CONTEXT_FILE_CHARS_TOTAL = 48_000;
 const TRUNCATION_MARKER = '[TRUNCATED]';
 
 export type ComposedArtifactBreakdown = {
@@ -13,10 +16,18 @@ export type ComposedArtifactBreakdown = {
   bytes: number;
 };
 
+export type ComposedContextFileBreakdown = {
+  path: string;
+  body: string;
+  truncated: boolean;
+  bytes: number;
+};
+
 export type ComposedInputBreakdown = {
   system_prompt: string | null;
   memory: string;
   artifacts: ComposedArtifactBreakdown[];
+  context_files: ComposedContextFileBreakdown[];
   transcript: {
     truncated: boolean;
     total_messages: number;
@@ -45,8 +56,31 @@ function stableJsonStringify(value: unknown): string {
   return JSON.stringify(value);
 }
 
+/** Mirrors `layer_system_prompt` in the backend so both build the same system prompt. */
+export function layerSystemPrompt(globalInstructions: string, campPrompt: string): string {
+  const globalLayer = globalInstructions.trim();
+  const campLayer = campPrompt.trim();
+  if (!globalLayer) return campLayer;
+  if (!campLayer) return globalLayer;
+  return `# Global instructions\n\n${globalLayer}\n\n---\n\n# Camp instructions\n\n${campLayer}`;
+}
+
+/** Memory key of the model's scratchpad notes; read on demand with `recall`. */
+const SCRATCHPAD_MEMORY_KEY = 'scratchpad';
+
+function isPlainObject(value: unknown): value is Record<string, unknown> {
+  return Boolean(value) && typeof value === 'object' && !Array.isArray(value);
+}
+
+/** Mirrors `without_scratchpad` in the backend: scratchpad notes stay out of requests. */
 function toMemorySystemMessage(memory: unknown): string {
-  const serialized = stableJsonStringify(memory);
+  let sent = memory;
+  if (isPlainObject(memory) && isPlainObject(memory[SCRATCHPAD_MEMORY_KEY])) {
+    const withoutScratchpad = { ...memory };
+    delete withoutScratchpad[SCRATCHPAD_MEMORY_KEY];
+    sent = withoutScratchpad;
+  }
+  const serialized = stableJsonStringify(sent);
   return `Structured memory (JSON):\n${serialized}`;
 }
 
@@ -65,7 +99,7 @@ function normalizeTranscript(messages: Camp['transcript']): OpenRouterChatMessag
         role: 'tool',
         content: trimmedContent,
         tool_call_id: message.tool_call_id,
-        name: message.name,
+        name: qualifyToolName(message.name),
       });
       continue;
     }
@@ -74,7 +108,10 @@ function normalizeTranscript(messages: Camp['transcript']): OpenRouterChatMessag
       normalized.push({
         role: 'assistant',
         content: trimmedContent,
-        tool_calls: message.tool_calls,
+        tool_calls: message.tool_calls.map((toolCall) => ({
+          ...toolCall,
+          function: { ...toolCall.function, name: qualifyToolName(toolCall.function.name) },
+        })),
       });
       continue;
     }
@@ -168,14 +205,51 @@ function toArtifactSystemMessagesWithBreakdown(artifacts: CampArtifact[]): {
   return { messages, breakdown };
 }
 
+/** Same header as the backend's context assembly uses for context files. */
+function toContextFileSystemMessagesWithBreakdown(files: DefaultContextFile[]): {
+  messages: OpenRouterChatMessage[];
+  breakdown: ComposedContextFileBreakdown[];
+} {
+  const messages: OpenRouterChatMessage[] = [];
+  const breakdown: ComposedContextFileBreakdown[] = [];
+  let remainingChars = MAX_CONTEXT_FILE_CHARS_TOTAL;
+
+  for (const file of files) {
+    if (file.content === null) {
+      continue;
+    }
+    if (remainingChars <= 0) {
+      break;
+    }
+
+    const truncatedBody = truncateWithMarker(file.content, Math.min(MAX_CONTEXT_FILE_CHARS_PER_ITEM, remainingChars));
+    remainingChars -= truncatedBody.length;
+
+    messages.push({
+      role: 'system',
+      content: `Context file: ${file.path}\n\n${truncatedBody}`,
+    });
+
+    breakdown.push({
+      path: file.path,
+      body: truncatedBody,
+      truncated: truncatedBody !== file.content,
+      bytes: byteLength(truncatedBody),
+    });
+  }
+
+  return { messages, breakdown };
+}
+
 function composeCampMessagesWithBreakdown(input: {
   camp: Camp;
   userMessage: string;
   userAttachments?: CampMessageAttachment[];
   selectedArtifacts?: CampArtifact[];
+  contextFiles?: DefaultContextFile[];
 }): { messages: OpenRouterChatMessage[]; breakdown: ComposedInputBreakdown } {
   const messages: OpenRouterChatMessage[] = [];
-  const systemPrompt = input.camp.system_prompt.trim();
+  const systemPrompt = layerSystemPrompt(input.camp.global_instructions ?? '', input.camp.system_prompt);
   if (systemPrompt) {
     messages.push({
       role: 'system',
@@ -192,6 +266,9 @@ function composeCampMessagesWithBreakdown(input: {
   const artifactResult = toArtifactSystemMessagesWithBreakdown(input.selectedArtifacts ?? []);
   messages.push(...artifactResult.messages);
 
+  const contextFileResult = toContextFileSystemMessagesWithBreakdown(input.contextFiles ?? []);
+  messages.push(...contextFileResult.messages);
+
   const transcriptMessages = normalizeTranscript(input.camp.transcript);
   messages.push(...transcriptMessages);
 
@@ -226,6 +303,7 @@ function composeCampMessagesWithBreakdown(input: {
       system_prompt: systemPrompt || null,
       memory: memoryMessage,
       artifacts: artifactResult.breakdown,
+      context_files: contextFileResult.breakdown,
       transcript: {
         truncated: false,
         total_messages: transcriptMessages.length,
@@ -241,6 +319,7 @@ export function composeCampMessages(input: {
   userMessage: string;
   userAttachments?: CampMessageAttachment[];
   selectedArtifacts?: CampArtifact[];
+  contextFiles?: DefaultContextFile[];
 }): OpenRouterChatMessage[] {
   return composeCampMessagesWithBreakdown(input).messages;
 }
@@ -250,6 +329,7 @@ export function composeCampOpenRouterRequestWithBreakdown(input: {
   userMessage: string;
   userAttachments?: CampMessageAttachment[];
   selectedArtifacts?: CampArtifact[];
+  contextFiles?: DefaultContextFile[];
   temperature: number;
   maxTokens: number;
   tools?: OpenRouterToolSpec[];
@@ -263,6 +343,7 @@ export function composeCampOpenRouterRequestWithBreakdown(input: {
     userMessage: input.userMessage,
     userAttachments: input.userAttachments,
     selectedArtifacts: input.selectedArtifacts,
+    contextFiles: input.contextFiles,
   });
 
   return {
@@ -271,6 +352,8 @@ export function composeCampOpenRouterRequestWithBreakdown(input: {
       messages: composed.messages,
       temperature: input.temperature,
       max_tokens: input.maxTokens,
+      stop: input.camp.config.generation_defaults?.stop,
+      logit_bias: input.camp.config.generation_defaults?.logit_bias,
       tools,
       tool_choice: tools ? 'auto' : undefined,
     },
@@ -283,6 +366,7 @@ export function composeCampOpenRouterRequest(input: {
   userMessage: string;
   userAttachments?: CampMessageAttachment[];
   selectedArtifacts?: CampArtifact[];
+  contextFiles?: DefaultContextFile[];
   temperature: number;
   maxTokens: number;
   tools?: OpenRouterToolSpec[];
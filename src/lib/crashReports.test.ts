@@ -0,0 +1,44 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+
+vi.mock('@tauri-apps/api/core', () => ({
+  invoke: vi.fn(),
+}));
+
+import { invoke } from '@tauri-apps/api/core';
+
+import { invokeWithCrashReports, isUnexpectedFailure } from './crashReports';
+
+const invokeMock = vi.mocked(invoke);
+
+describe('crash reports', () => {
+  beforeEach(() => {
+    invokeMock.mockReset();
+  });
+
+  it('treats user-facing failures as expected', () => {
+    expect(isUnexpectedFailure('Camp not found')).toBe(false);
+    expect(isUnexpectedFailure({ kind: 'validation', message: 'Name is required' })).toBe(false);
+    expect(isUnexpectedFailure({ kind: 'provider', message: 'Rate limited' })).toBe(false);
+    expect(isUnexpectedFailure({ kind: 'internal', message: 'Unexpected state' })).toBe(true);
+    expect(isUnexpectedFailure({ kind: 'database', message: 'disk I/O error' })).toBe(true);
+    expect(isUnexpectedFailure(new TypeError('x is undefined'))).toBe(true);
+  });
+
+  it('records only unexpected rejections and rethrows every one', async () => {
+    invokeMock.mockImplementation(async (command) => {
+      if (command === 'record_command_failure') {
+        return undefined;
+      }
+      throw command === 'camp_load' ? 'Camp not found' : { kind: 'internal', message: 'Unexpected state' };
+    });
+
+    await expect(invokeWithCrashReports('camp_load')).rejects.toBe('Camp not found');
+    expect(invokeMock).not.toHaveBeenCalledWith('record_command_failure', expect.anything());
+
+    await expect(invokeWithCrashReports('camp_save')).rejects.toMatchObject({ kind: 'internal' });
+    expect(invokeMock).toHaveBeenCalledWith('record_command_failure', {
+      command: 'camp_save',
+      error: { kind: 'internal', message: 'Unexpected state' },
+    });
+  });
+});
@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+
+import { isBasecampError } from './errors';
+
+/**
+ * Failures that point at a bug rather than at something the user can fix.
+ * Provider, MCP and I/O errors are usually the network or the user's files.
+ */
+const UNEXPECTED_ERROR_KINDS = new Set(['internal', 'database']);
+
+/**
+ * Older commands reject with plain strings, which are messages for the user
+ * ("Camp not found", "Name is required"), so only structured errors of an
+ * unexpected kind and thrown `Error`s are reported.
+ */
+export function isUnexpectedFailure(error: unknown): boolean {
+  if (isBasecampError(error)) {
+    return UNEXPECTED_ERROR_KINDS.has(error.kind);
+  }
+  return error instanceof Error;
+}
+
+/**
+ * `invoke` that records unexpected rejections as local crash reports before
+ * rethrowing. Reports never leave the machine unless the user exports one.
+ */
+export async function invokeWithCrashReports<T>(command: string, args?: Record<string, unknown>): Promise<T> {
+  try {
+    return await invoke<T>(command, args);
+  } catch (error) {
+    if (isUnexpectedFailure(error)) {
+      const payload = error instanceof Error ? { message: error.message, name: error.name } : error;
+      void invoke('record_command_failure', { command, error: payload ?? null }).catch(() => undefined);
+    }
+    throw error;
+  }
+}
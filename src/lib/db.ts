@@ -1,47 +1,167 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invokeWithCrashReports as invoke } from './crashReports';
 
 import type {
   ApprovalPolicy,
   AgentStepResult,
+  BuiltinToolName,
+  ClarificationResolution,
   Camp,
   CampArtifact,
+  ArtifactIndexRebuildReport,
   CampArtifactMetadata,
   CampAppendMessagePayload,
+  CampFetchUrlPayload,
+  CampTablePayload,
+  DatabaseQueryResult,
   DecompositionPlan,
   DelegationStep,
+  CampCreateArtifactFromFilePayload,
   CampCreateArtifactFromMessagePayload,
+  CampCreateArtifactPayload,
   CampCreatePayload,
+  CampListSort,
+  CampProject,
+  CampProjectGroup,
+  DefaultContextFile,
+  EmbeddingBackendSetting,
+  EmbeddingBenchmark,
+  EmbeddingRefreshReport,
+  EmbeddingSettings,
+  HybridSearchPayload,
+  HybridSearchResults,
+  ListRunGroupsPayload,
+  LocalEmbeddingModel,
+  FetchedPage,
+  QueryUserDatabasePayload,
+  RegisterUserDatabasePayload,
+  TablePreview,
+  UserDatabase,
+  CampCreateFromTemplatePayload,
+  CampTemplate,
+  CampDeleteArtifactPayload,
+  CampEmbeddingStatus,
   CampMessage,
+  CampRenameFolderPayload,
+  CampReorderAlwaysIncludePayload,
+  CampRestoreTrashEntryPayload,
   CampSearchTranscriptPayload,
+  CampSetArtifactAlwaysIncludePayload,
   CampSummary,
   CampTranscriptSearchMatch,
+  CampTrashEntry,
   CampToggleArtifactArchivePayload,
   CampUpdateConfigPayload,
   CampUpdateArtifactPayload,
   CampUpdateMemoryPayload,
+  CampPatchMemoryPayload,
+  MemorySnapshot,
   CampUpdateSystemPromptPayload,
+  CampVerifyTranscriptPayload,
   ModelRow,
+  ProviderConcurrency,
   ProviderKind,
   ProviderModelsRefreshResult,
+  ProviderQueueStatus,
   ProviderRegistryRow,
   ReflectionSummary,
   Run,
+  RunGroup,
   RunInsertPayload,
   RunSearchDbArgs,
   RunSearchDbRow,
   RunStartConfig,
   RunStartResult,
+  RunBuiltinToolPayload,
   RunStateEvent,
+  ScratchpadRememberPayload,
+  ScratchpadRememberResult,
   TeamAgentConfig,
   TeamAgentCreateInput,
+  AgentTranscriptEntry,
+  DraftReflectionItem,
   TeamBusEntry,
+  TeamRunReport,
+  TeamRunSummary,
   TeamSettingsUpdateInput,
   TeamStatus,
+  TeamTemplate,
   RunUpdatePayload,
+  SearchWorkspacePayload,
   ToolCallRow,
   ToolCallStartPayload,
+  TranscriptVerifyReport,
   WriteNotePayload,
+  WorkspaceSearchHit,
+  TranscriptSearchHit,
+  CampActivityTimeline,
+  CampExportContextPackPayload,
+  ContextPackExport,
+  CampShareExportPayload,
+  ShareExportResult,
   WriteNoteResult,
+  CampAssembleContextPayload,
+  ContextAssembly,
+  CampCheckpoint,
+  CampCreateCheckpointPayload,
+  CampRollbackResult,
+  CampUndoExchangeResult,
+  CampRollbackToCheckpointPayload,
+  CampDiffPromptVersionsPayload,
+  CampRestorePromptVersionPayload,
+  ContextFileVersion,
+  CampRestoreContextFileVersionPayload,
+  ContextWritePreview,
+  RenderArtifactRichPayload,
+  RichRender,
+  PromptVersion,
+  ImproveSystemPromptPayload,
+  SystemPromptSuggestion,
+  CampBookmark,
+  CampPinMessagePayload,
+  CampUnpinMessagePayload,
+  PinnedMessage,
+  CampSuggestTitlePayload,
+  CampTitleSuggestion,
+  PromptSuggestions,
+  CampTranslateMessagesPayload,
+  TranslatedMessage,
+  TtsEngine,
+  SynthesizeSpeechPayload,
+  CampAudioClip,
+  SynthesizedSpeech,
+  UpdateChannel,
+  UpdateCheck,
+  QuickCaptureSettings,
+  QuickCaptureResult,
+  TraySettings,
+  CrashReportSummary,
+  ModelPerformanceStats,
+  DigestSettings,
+  DigestStatus,
+  DigestResult,
+  SyncConflict,
+  SyncMergeReport,
+  SyncSettings,
+  SyncReport,
+  WorkspaceManifestResult,
+  WorkspaceGcReport,
+  ModelNote,
+  RecommendModelPayload,
+  RecommendModelResult,
+  SelectVariantPayload,
+  VariantGroup,
+  StartupDiagnostics,
+  MigrationStatus,
+  DbEncryptionStatus,
+  PrivacySettings,
+  OutboundScrubSettings,
+  McpSamplingPolicy,
+  McpOAuthStatus,
+  McpServerConfig,
+  McpServerStatus,
+  McpCatalogEntry,
+  McpCatalogInstallResult,
+  McpInstallFromCatalogPayload,
 } from './types';
 
 export async function saveApiKey(apiKey: string): Promise<void> {
@@ -69,6 +189,21 @@ export async function providersList(): Promise<ProviderRegistryRow[]> {
   return invoke<ProviderRegistryRow[]>('providers_list');
 }
 
+export async function getQueueStatus(): Promise<ProviderQueueStatus[]> {
+  return invoke<ProviderQueueStatus[]>('get_queue_status');
+}
+
+export async function getProviderConcurrency(): Promise<ProviderConcurrency[]> {
+  return invoke<ProviderConcurrency[]>('get_provider_concurrency');
+}
+
+export async function setProviderConcurrency(
+  providerKind: ProviderKind,
+  maxConcurrentRequests: number,
+): Promise<ProviderConcurrency> {
+  return invoke<ProviderConcurrency>('set_provider_concurrency', { providerKind, maxConcurrentRequests });
+}
+
 export async function providerUpdate(payload: {
   provider_kind: ProviderKind;
   enabled: boolean;
@@ -93,14 +228,28 @@ export async function insertRun(payload: RunInsertPayload): Promise<void> {
   await invoke('insert_run', { payload });
 }
 
+export async function openrouterEnrichRun(runId: string): Promise<void> {
+  await invoke('openrouter_enrich_run', { runId });
+}
+
 export async function listRuns(): Promise<Run[]> {
   return invoke<Run[]>('list_runs');
 }
 
+/** Runs folded into prompt families, newest family first. */
+export async function listRunGroups(payload: ListRunGroupsPayload = {}): Promise<RunGroup[]> {
+  return invoke<RunGroup[]>('list_run_groups', { payload });
+}
+
 export async function getRunById(id: string): Promise<Run | null> {
   return invoke<Run | null>('get_run_by_id', { id });
 }
 
+/** The run behind a transcript message; `null` when the message has no stored run. */
+export async function getRunForMessage(campId: string, messageId: string): Promise<Run | null> {
+  return invoke<Run | null>('get_run_for_message', { campId, messageId });
+}
+
 export async function updateRunRatingAndTags(payload: RunUpdatePayload): Promise<void> {
   await invoke('update_run_rating_and_tags', { payload });
 }
@@ -129,6 +278,44 @@ export async function pickWorkspaceFolder(): Promise<string | null> {
   return invoke<string | null>('pick_workspace_folder');
 }
 
+export async function getGlobalInstructions(): Promise<string> {
+  return invoke<string>('get_global_instructions');
+}
+
+/** Saving an empty string removes the workspace's global instructions. */
+export async function setGlobalInstructions(content: string): Promise<void> {
+  await invoke('set_global_instructions', { content });
+}
+
+export async function listModelNotes(): Promise<ModelNote[]> {
+  return invoke<ModelNote[]>('list_model_notes');
+}
+
+export async function setModelNote(note: ModelNote): Promise<ModelNote> {
+  return invoke<ModelNote>('set_model_note', { note });
+}
+
+export async function deleteModelNote(providerKind: string, modelId: string): Promise<boolean> {
+  return invoke<boolean>('delete_model_note', { providerKind, modelId });
+}
+
+export async function recommendModel(payload: RecommendModelPayload): Promise<RecommendModelResult> {
+  return invoke<RecommendModelResult>('recommend_model', { payload });
+}
+
+/** Writes INDEX.md, a markdown list of every camp, to the workspace folder. */
+export async function generateWorkspaceManifest(): Promise<WorkspaceManifestResult> {
+  return invoke<WorkspaceManifestResult>('generate_workspace_manifest');
+}
+
+/**
+ * Finds orphaned workspace data; removes it when `clean` is set. Unreferenced
+ * context files are only moved to the workspace trash when listed in `contextFiles`.
+ */
+export async function workspaceGc(clean: boolean, contextFiles: string[] = []): Promise<WorkspaceGcReport> {
+  return invoke<WorkspaceGcReport>('workspace_gc', { clean, contextFiles });
+}
+
 export async function setToolsEnabled(enabled: boolean): Promise<void> {
   await invoke('set_tools_enabled', { enabled });
 }
@@ -137,6 +324,94 @@ export async function getToolsEnabled(): Promise<boolean> {
   return invoke<boolean>('get_tools_enabled');
 }
 
+export async function getPrivacySettings(): Promise<PrivacySettings> {
+  return invoke<PrivacySettings>('get_privacy_settings');
+}
+
+export async function setPrivacySettings(settings: PrivacySettings): Promise<PrivacySettings> {
+  return invoke<PrivacySettings>('set_privacy_settings', { settings });
+}
+
+export async function getOutboundScrubSettings(): Promise<OutboundScrubSettings> {
+  return invoke<OutboundScrubSettings>('get_outbound_scrub_settings');
+}
+
+export async function setOutboundScrubSettings(
+  settings: OutboundScrubSettings,
+): Promise<OutboundScrubSettings> {
+  return invoke<OutboundScrubSettings>('set_outbound_scrub_settings', { settings });
+}
+
+export async function getReadOnlyMode(): Promise<boolean> {
+  return invoke<boolean>('get_read_only_mode');
+}
+
+export async function setReadOnlyMode(enabled: boolean): Promise<boolean> {
+  return invoke<boolean>('set_read_only_mode', { enabled });
+}
+
+export async function getMcpSamplingPolicy(): Promise<McpSamplingPolicy> {
+  return invoke<McpSamplingPolicy>('get_mcp_sampling_policy');
+}
+
+export async function setMcpSamplingPolicy(policy: McpSamplingPolicy): Promise<McpSamplingPolicy> {
+  return invoke<McpSamplingPolicy>('set_mcp_sampling_policy', { policy });
+}
+
+export async function mcpResolveSamplingRequest(requestId: string, approved: boolean): Promise<void> {
+  await invoke('mcp_resolve_sampling_request', { requestId, approved });
+}
+
+export async function mcpUpdateServer(config: McpServerConfig): Promise<McpServerConfig> {
+  return invoke<McpServerConfig>('mcp_update_server', { config });
+}
+
+export async function mcpDeleteServer(serverId: string): Promise<void> {
+  await invoke('mcp_delete_server', { serverId });
+}
+
+export async function mcpSetEnabled(serverId: string, enabled: boolean): Promise<McpServerStatus> {
+  return invoke<McpServerStatus>('mcp_set_enabled', { serverId, enabled });
+}
+
+export async function mcpGetServerLogs(serverId: string): Promise<string[]> {
+  return invoke<string[]>('mcp_get_server_logs', { serverId });
+}
+
+export async function getMcpToolCacheTtl(): Promise<number> {
+  return invoke<number>('get_mcp_tool_cache_ttl');
+}
+
+export async function setMcpToolCacheTtl(seconds: number): Promise<number> {
+  return invoke<number>('set_mcp_tool_cache_ttl', { seconds });
+}
+
+export async function mcpListCatalog(): Promise<McpCatalogEntry[]> {
+  return invoke<McpCatalogEntry[]>('mcp_list_catalog');
+}
+
+export async function mcpInstallFromCatalog(
+  payload: McpInstallFromCatalogPayload,
+): Promise<McpCatalogInstallResult> {
+  return invoke<McpCatalogInstallResult>('mcp_install_from_catalog', { payload });
+}
+
+export async function mcpSetActiveCamp(campId: string | null): Promise<void> {
+  return invoke<void>('mcp_set_active_camp', { campId });
+}
+
+export async function mcpOAuthAuthorize(serverId: string): Promise<McpOAuthStatus> {
+  return invoke<McpOAuthStatus>('mcp_oauth_authorize', { serverId });
+}
+
+export async function mcpOAuthStatus(serverId: string): Promise<McpOAuthStatus> {
+  return invoke<McpOAuthStatus>('mcp_oauth_status', { serverId });
+}
+
+export async function mcpOAuthSignOut(serverId: string): Promise<McpOAuthStatus> {
+  return invoke<McpOAuthStatus>('mcp_oauth_sign_out', { serverId });
+}
+
 export async function setDefaultModel(model: string): Promise<void> {
   await invoke('set_default_model', { model });
 }
@@ -169,8 +444,16 @@ export async function writeNoteToWorkspace(payload: WriteNotePayload): Promise<W
   return invoke<WriteNoteResult>('write_note_to_workspace', { payload });
 }
 
-export async function campList(): Promise<CampSummary[]> {
-  return invoke<CampSummary[]>('camp_list');
+export async function campList(sort?: CampListSort): Promise<CampSummary[]> {
+  return invoke<CampSummary[]>('camp_list', { sort });
+}
+
+export async function campMarkOpened(campId: string): Promise<number> {
+  return invoke<number>('camp_mark_opened', { campId });
+}
+
+export async function campSetFavorite(campId: string, favorite: boolean): Promise<void> {
+  await invoke('camp_set_favorite', { campId, favorite });
 }
 
 export async function campDelete(id: string): Promise<void> {
@@ -181,6 +464,35 @@ export async function campCreate(payload: CampCreatePayload): Promise<Camp> {
   return invoke<Camp>('camp_create', { payload });
 }
 
+export async function projectList(): Promise<CampProject[]> {
+  return invoke<CampProject[]>('project_list');
+}
+
+export async function projectCreate(name: string, color?: string): Promise<CampProject> {
+  return invoke<CampProject>('project_create', { name, color });
+}
+
+export async function projectDelete(projectId: string): Promise<void> {
+  await invoke('project_delete', { projectId });
+}
+
+/** Pass `null` to take the camp out of its project. */
+export async function campSetProject(campId: string, projectId: string | null): Promise<void> {
+  await invoke('camp_set_project', { campId, projectId });
+}
+
+export async function campListGrouped(): Promise<CampProjectGroup[]> {
+  return invoke<CampProjectGroup[]>('camp_list_grouped');
+}
+
+export async function listCampTemplates(): Promise<CampTemplate[]> {
+  return invoke<CampTemplate[]>('list_camp_templates');
+}
+
+export async function campCreateFromTemplate(payload: CampCreateFromTemplatePayload): Promise<Camp> {
+  return invoke<Camp>('camp_create_from_template', { payload });
+}
+
 export async function campLoad(campId: string): Promise<Camp> {
   return invoke<Camp>('camp_load', { campId });
 }
@@ -283,22 +595,123 @@ export async function campSearchTranscript(
   return matches.slice(0, normalizedLimit);
 }
 
+export async function searchWorkspace(payload: SearchWorkspacePayload): Promise<WorkspaceSearchHit[]> {
+  return invoke<WorkspaceSearchHit[]>('search_workspace', { payload });
+}
+
+/** Ranks BM25 and embedding matches together; see `HybridSearchHit` for the breakdown. */
+export async function hybridSearch(payload: HybridSearchPayload): Promise<HybridSearchResults> {
+  return invoke<HybridSearchResults>('hybrid_search', { payload });
+}
+
+export async function campExportContextPack(payload: CampExportContextPackPayload): Promise<ContextPackExport> {
+  return invoke<ContextPackExport>('camp_export_context_pack', { payload });
+}
+
+/** Saves the transcript as a standalone HTML file; `null` when the save dialog is cancelled. */
+export async function campShareExport(payload: CampShareExportPayload): Promise<ShareExportResult | null> {
+  return invoke<ShareExportResult | null>('camp_share_export', { payload });
+}
+
+export async function campActivityTimeline(campId: string, days?: number): Promise<CampActivityTimeline> {
+  return invoke<CampActivityTimeline>('camp_activity_timeline', {
+    campId,
+    days,
+    // getTimezoneOffset is minutes west of UTC.
+    utcOffsetMinutes: -new Date().getTimezoneOffset(),
+  });
+}
+
+/** Server-side find within one camp's transcript. */
+export async function campFindInTranscript(
+  campId: string,
+  query: string,
+  options?: { regex?: boolean; limit?: number },
+): Promise<TranscriptSearchHit[]> {
+  return invoke<TranscriptSearchHit[]>('camp_search_transcript', {
+    campId,
+    query,
+    regex: options?.regex,
+    limit: options?.limit,
+  });
+}
+
 export async function campUpdateConfig(payload: CampUpdateConfigPayload): Promise<void> {
   await invoke('camp_update_config', { payload });
 }
 
+export async function campRenameFolder(payload: CampRenameFolderPayload): Promise<Camp> {
+  return invoke<Camp>('camp_rename_folder', { payload });
+}
+
 export async function campUpdateSystemPrompt(payload: CampUpdateSystemPromptPayload): Promise<void> {
   await invoke('camp_update_system_prompt', { payload });
 }
 
+export async function campListPromptVersions(campId: string): Promise<PromptVersion[]> {
+  return invoke<PromptVersion[]>('camp_list_prompt_versions', { campId });
+}
+
+export async function campDiffPromptVersions(payload: CampDiffPromptVersionsPayload): Promise<string> {
+  return invoke<string>('camp_diff_prompt_versions', { payload });
+}
+
+export async function campRestorePromptVersion(payload: CampRestorePromptVersionPayload): Promise<string> {
+  return invoke<string>('camp_restore_prompt_version', { payload });
+}
+
+export async function campListContextFileVersions(campId: string, path: string): Promise<ContextFileVersion[]> {
+  return invoke<ContextFileVersion[]>('camp_list_context_file_versions', { campId, path });
+}
+
+export async function campRestoreContextFileVersion(payload: CampRestoreContextFileVersionPayload): Promise<void> {
+  await invoke('camp_restore_context_file_version', { payload });
+}
+
+export async function improveSystemPrompt(payload: ImproveSystemPromptPayload): Promise<SystemPromptSuggestion> {
+  return invoke<SystemPromptSuggestion>('improve_system_prompt', { payload });
+}
+
 export async function campUpdateMemory(payload: CampUpdateMemoryPayload): Promise<void> {
   await invoke('camp_update_memory', { payload });
 }
 
+export async function campGetMemory(campId: string): Promise<MemorySnapshot> {
+  return invoke<MemorySnapshot>('camp_get_memory', { campId });
+}
+
+export async function campPatchMemory(payload: CampPatchMemoryPayload): Promise<MemorySnapshot> {
+  return invoke<MemorySnapshot>('camp_patch_memory', { payload });
+}
+
+export async function campScratchpadRemember(payload: ScratchpadRememberPayload): Promise<ScratchpadRememberResult> {
+  return invoke<ScratchpadRememberResult>('camp_scratchpad_remember', { payload });
+}
+
+/** One note (or `null`) when `key` is given, otherwise every note by key. */
+export async function campScratchpadRecall(campId: string, key?: string): Promise<unknown> {
+  return invoke<unknown>('camp_scratchpad_recall', { campId, key: key ?? null });
+}
+
+/** Runs a local deterministic tool, passing along the user's time zone. */
+export async function runBuiltinTool(name: BuiltinToolName, args: Record<string, unknown>): Promise<unknown> {
+  const payload: RunBuiltinToolPayload = {
+    name,
+    arguments: args,
+    local_utc_offset_minutes: -new Date().getTimezoneOffset(),
+    local_time_zone: Intl.DateTimeFormat().resolvedOptions().timeZone,
+  };
+  return invoke<unknown>('run_builtin_tool', { payload });
+}
+
 export async function campAppendMessage(payload: CampAppendMessagePayload): Promise<CampMessage> {
   return invoke<CampMessage>('camp_append_message', { payload });
 }
 
+export async function campVerifyTranscript(payload: CampVerifyTranscriptPayload): Promise<TranscriptVerifyReport> {
+  return invoke<TranscriptVerifyReport>('camp_verify_transcript', { payload });
+}
+
 export async function campListArtifacts(campId: string): Promise<CampArtifactMetadata[]> {
   return invoke<CampArtifactMetadata[]>('camp_list_artifacts', { campId });
 }
@@ -311,6 +724,22 @@ export async function campCreateArtifactFromMessage(payload: CampCreateArtifactF
   return invoke<CampArtifact>('camp_create_artifact_from_message', { payload });
 }
 
+export async function campExtractCodeArtifacts(campId: string, messageId: string): Promise<CampArtifact[]> {
+  return invoke<CampArtifact[]>('camp_extract_code_artifacts', { campId, messageId });
+}
+
+export async function campCreateArtifact(payload: CampCreateArtifactPayload): Promise<CampArtifact> {
+  return invoke<CampArtifact>('camp_create_artifact', { payload });
+}
+
+export async function campCreateArtifactFromFile(payload: CampCreateArtifactFromFilePayload): Promise<CampArtifact> {
+  return invoke<CampArtifact>('camp_create_artifact_from_file', { payload });
+}
+
+export async function campRebuildArtifactIndex(campId: string): Promise<ArtifactIndexRebuildReport> {
+  return invoke<ArtifactIndexRebuildReport>('camp_rebuild_artifact_index', { campId });
+}
+
 export async function campUpdateArtifact(payload: CampUpdateArtifactPayload): Promise<CampArtifact> {
   return invoke<CampArtifact>('camp_update_artifact', { payload });
 }
@@ -319,10 +748,189 @@ export async function campToggleArtifactArchive(payload: CampToggleArtifactArchi
   return invoke<CampArtifactMetadata>('camp_toggle_artifact_archive', { payload });
 }
 
+export async function campSetArtifactAlwaysInclude(payload: CampSetArtifactAlwaysIncludePayload): Promise<CampArtifactMetadata> {
+  return invoke<CampArtifactMetadata>('camp_set_artifact_always_include', { payload });
+}
+
+export async function campReorderAlwaysIncludeArtifacts(payload: CampReorderAlwaysIncludePayload): Promise<CampArtifactMetadata[]> {
+  return invoke<CampArtifactMetadata[]>('camp_reorder_always_include_artifacts', { payload });
+}
+
+export async function campSetDefaultContextPaths(campId: string, paths: string[]): Promise<string[]> {
+  return invoke<string[]>('camp_set_default_context_paths', { campId, paths });
+}
+
+export async function campSetFetchAllowedDomains(campId: string, domains: string[]): Promise<string[]> {
+  return invoke<string[]>('camp_set_fetch_allowed_domains', { campId, domains });
+}
+
+export async function campFetchUrl(payload: CampFetchUrlPayload): Promise<FetchedPage> {
+  return invoke<FetchedPage>('camp_fetch_url', { payload });
+}
+
+export async function registerUserDatabase(payload: RegisterUserDatabasePayload): Promise<UserDatabase> {
+  return invoke<UserDatabase>('register_user_database', { payload });
+}
+
+export async function listUserDatabases(): Promise<UserDatabase[]> {
+  return invoke<UserDatabase[]>('list_user_databases');
+}
+
+export async function unregisterUserDatabase(name: string): Promise<void> {
+  return invoke<void>('unregister_user_database', { name });
+}
+
+export async function queryUserDatabase(payload: QueryUserDatabasePayload): Promise<DatabaseQueryResult> {
+  return invoke<DatabaseQueryResult>('query_user_database', { payload });
+}
+
+export async function campPreviewTable(payload: CampTablePayload): Promise<TablePreview> {
+  return invoke<TablePreview>('camp_preview_table', { payload });
+}
+
+export async function campTableToMarkdown(payload: CampTablePayload): Promise<string> {
+  return invoke<string>('camp_table_to_markdown', { payload });
+}
+
+/** Re-embeds new and changed context files; `onlyIfIndexed` skips camps never indexed. */
+export async function campRefreshEmbeddingIndex(campId: string, onlyIfIndexed = false): Promise<EmbeddingRefreshReport> {
+  return invoke<EmbeddingRefreshReport>('camp_refresh_embedding_index', {
+    payload: { camp_id: campId, only_if_indexed: onlyIfIndexed },
+  });
+}
+
+/** One camp's status, or every indexed camp's when `campId` is omitted. */
+export async function embeddingIndexStatus(campId?: string): Promise<CampEmbeddingStatus[]> {
+  return invoke<CampEmbeddingStatus[]>('embedding_index_status', { campId: campId ?? null });
+}
+
+export async function getEmbeddingSettings(): Promise<EmbeddingSettings> {
+  return invoke<EmbeddingSettings>('get_embedding_settings');
+}
+
+/** Existing vectors turn stale and are re-embedded on the next refresh. */
+export async function setEmbeddingBackend(backend: EmbeddingBackendSetting): Promise<EmbeddingBackendSetting> {
+  return invoke<EmbeddingBackendSetting>('set_embedding_backend', { backend });
+}
+
+export async function downloadLocalEmbeddingModel(model: string): Promise<LocalEmbeddingModel> {
+  return invoke<LocalEmbeddingModel>('download_local_embedding_model', { model });
+}
+
+export async function deleteLocalEmbeddingModel(model: string): Promise<void> {
+  await invoke('delete_local_embedding_model', { model });
+}
+
+/** Benchmarks the saved backend when `backends` is empty. */
+export async function benchmarkEmbeddingBackends(backends: EmbeddingBackendSetting[] = []): Promise<EmbeddingBenchmark[]> {
+  return invoke<EmbeddingBenchmark[]>('benchmark_embedding_backends', { backends });
+}
+
+export async function campLoadDefaultContextFiles(campId: string): Promise<DefaultContextFile[]> {
+  return invoke<DefaultContextFile[]>('camp_load_default_context_files', { campId });
+}
+
+export async function campListAlwaysIncludeArtifacts(campId: string): Promise<CampArtifact[]> {
+  return invoke<CampArtifact[]>('camp_list_always_include_artifacts', { campId });
+}
+
 export async function campIncrementArtifactUsage(campId: string, artifactIds: string[]): Promise<void> {
   await invoke('camp_increment_artifact_usage', { campId, artifactIds });
 }
 
+export async function campDeleteArtifact(payload: CampDeleteArtifactPayload): Promise<CampTrashEntry> {
+  return invoke<CampTrashEntry>('camp_delete_artifact', { payload });
+}
+
+export async function campListTrash(campId: string): Promise<CampTrashEntry[]> {
+  return invoke<CampTrashEntry[]>('camp_list_trash', { campId });
+}
+
+export async function campRestoreTrashEntry(payload: CampRestoreTrashEntryPayload): Promise<CampTrashEntry> {
+  return invoke<CampTrashEntry>('camp_restore_trash_entry', { payload });
+}
+
+export async function campEmptyTrash(campId: string): Promise<void> {
+  await invoke('camp_empty_trash', { campId });
+}
+
+export async function campCreateCheckpoint(payload: CampCreateCheckpointPayload): Promise<CampCheckpoint> {
+  return invoke<CampCheckpoint>('camp_create_checkpoint', { payload });
+}
+
+export async function campListCheckpoints(campId: string): Promise<CampCheckpoint[]> {
+  return invoke<CampCheckpoint[]>('camp_list_checkpoints', { campId });
+}
+
+export async function campUndoLastExchange(campId: string): Promise<CampUndoExchangeResult> {
+  return invoke<CampUndoExchangeResult>('camp_undo_last_exchange', { campId });
+}
+
+export async function campRollbackToCheckpoint(payload: CampRollbackToCheckpointPayload): Promise<CampRollbackResult> {
+  return invoke<CampRollbackResult>('camp_rollback_to_checkpoint', { payload });
+}
+
+export async function campPinMessage(payload: CampPinMessagePayload): Promise<CampBookmark> {
+  return invoke<CampBookmark>('camp_pin_message', { payload });
+}
+
+export async function campUnpinMessage(payload: CampUnpinMessagePayload): Promise<void> {
+  await invoke('camp_unpin_message', { payload });
+}
+
+export async function campListPinnedMessages(campId: string): Promise<PinnedMessage[]> {
+  return invoke<PinnedMessage[]>('camp_list_pinned_messages', { campId });
+}
+
+export async function campSuggestTitle(payload: CampSuggestTitlePayload): Promise<CampTitleSuggestion> {
+  return invoke<CampTitleSuggestion>('camp_suggest_title', { payload });
+}
+
+/** Next-prompt ideas from the camp's memory, artifacts and recent messages. */
+export async function suggestPrompts(campId: string): Promise<PromptSuggestions> {
+  return invoke<PromptSuggestions>('suggest_prompts', { campId });
+}
+
+export async function setTitleModel(model: string | null): Promise<void> {
+  await invoke('set_title_model', { model });
+}
+
+export async function getTitleModel(): Promise<string | null> {
+  return invoke<string | null>('get_title_model');
+}
+
+export async function campTranslateMessages(payload: CampTranslateMessagesPayload): Promise<TranslatedMessage[]> {
+  return invoke<TranslatedMessage[]>('camp_translate_messages', { payload });
+}
+
+export async function campListTranslations(campId: string, language: string): Promise<TranslatedMessage[]> {
+  return invoke<TranslatedMessage[]>('camp_list_translations', { campId, language });
+}
+
+export async function synthesizeSpeech(payload: SynthesizeSpeechPayload): Promise<SynthesizedSpeech> {
+  return invoke<SynthesizedSpeech>('synthesize_speech', { payload });
+}
+
+export async function listSpeechClips(campId: string): Promise<CampAudioClip[]> {
+  return invoke<CampAudioClip[]>('list_speech_clips', { campId });
+}
+
+export async function getTtsEngine(): Promise<TtsEngine | null> {
+  return invoke<TtsEngine | null>('get_tts_engine');
+}
+
+export async function setTtsEngine(engine: TtsEngine | null): Promise<TtsEngine | null> {
+  return invoke<TtsEngine | null>('set_tts_engine', { engine });
+}
+
+export async function selectVariant(payload: SelectVariantPayload): Promise<VariantGroup> {
+  return invoke<VariantGroup>('select_variant', { payload });
+}
+
+export async function listMessageVariants(campId: string): Promise<VariantGroup[]> {
+  return invoke<VariantGroup[]>('list_message_variants', { campId });
+}
+
 export async function campReadContextFile(campId: string, path: string): Promise<string> {
   return invoke<string>('tauri_cmd_read_context_file', { campId, path });
 }
@@ -347,6 +955,30 @@ export async function campListContextFiles(campId: string, path?: string): Promi
   return invoke<string[]>('tauri_cmd_list_context_files', { campId, path });
 }
 
+/** Sanitized HTML for a markdown string; safe to render with `dangerouslySetInnerHTML`. */
+export async function renderMarkdown(markdown: string): Promise<string> {
+  return invoke<string>('render_markdown', { markdown });
+}
+
+/** Opens an http(s) or mailto link in the system browser or mail client. */
+export async function openExternalUrl(url: string): Promise<void> {
+  await invoke('open_external_url', { url });
+}
+
+/** Artifact HTML with math and Mermaid diagrams pre-rendered, for exports. */
+export async function renderArtifactRich(payload: RenderArtifactRichPayload): Promise<RichRender> {
+  return invoke<RichRender>('render_artifact_rich', { payload });
+}
+
+export async function campPreviewContextFileWrite(
+  campId: string,
+  path: string,
+  content: string,
+  encoding?: 'utf-8' | 'base64',
+): Promise<ContextWritePreview> {
+  return invoke<ContextWritePreview>('camp_preview_context_file_write', { campId, path, content, encoding });
+}
+
 export async function campWriteContextFile(campId: string, path: string, content: string): Promise<void> {
   await invoke('tauri_cmd_write_context_file', { campId, path, content });
 }
@@ -355,6 +987,14 @@ export async function createTeamAgent(campId: string, agentConfig: TeamAgentCrea
   return invoke<TeamAgentConfig>('create_team_agent', { campId, agentConfig });
 }
 
+export async function listTeamTemplates(): Promise<TeamTemplate[]> {
+  return invoke<TeamTemplate[]>('list_team_templates');
+}
+
+export async function createTeamFromTemplate(campId: string, templateId: string, model?: string): Promise<TeamStatus> {
+  return invoke<TeamStatus>('create_team_from_template', { campId, templateId, model: model ?? null });
+}
+
 export async function removeTeamAgent(campId: string, agentId: string): Promise<void> {
   await invoke('remove_team_agent', { campId, agentId });
 }
@@ -371,12 +1011,44 @@ export async function executeAgentStep(campId: string, agentId: string, step: De
   return invoke<AgentStepResult>('execute_agent_step', { campId, agentId, step });
 }
 
+export async function answerClarification(
+  campId: string,
+  requestId: string,
+  answer: string,
+): Promise<ClarificationResolution> {
+  return invoke<ClarificationResolution>('answer_clarification', { campId, requestId, answer });
+}
+
 export async function runReflectionLoop(campId: string, artifactPath: string, rounds: number): Promise<ReflectionSummary> {
   return invoke<ReflectionSummary>('run_reflection_loop', { campId, artifactPath, rounds });
 }
 
-export async function getTeamBus(campId: string): Promise<TeamBusEntry[]> {
-  return invoke<TeamBusEntry[]>('get_team_bus', { campId });
+export async function runReflectionBatch(campId: string, rounds: number, parallel: boolean): Promise<DraftReflectionItem[]> {
+  return invoke<DraftReflectionItem[]>('run_reflection_batch', { campId, rounds, parallel });
+}
+
+export async function promoteAllPassing(campId: string): Promise<string[]> {
+  return invoke<string[]>('promote_all_passing', { campId });
+}
+
+export async function getAgentTranscript(campId: string, agentId: string): Promise<AgentTranscriptEntry[]> {
+  return invoke<AgentTranscriptEntry[]>('get_agent_transcript', { campId, agentId });
+}
+
+export async function getTeamBus(campId: string, runId?: string): Promise<TeamBusEntry[]> {
+  return invoke<TeamBusEntry[]>('get_team_bus', { campId, runId: runId ?? null });
+}
+
+export async function listTeamRuns(campId: string): Promise<TeamRunSummary[]> {
+  return invoke<TeamRunSummary[]>('list_team_runs', { campId });
+}
+
+export async function archiveTeamRuns(campId: string, keepRecent?: number): Promise<string[]> {
+  return invoke<string[]>('archive_team_runs', { campId, keepRecent: keepRecent ?? null });
+}
+
+export async function getTeamRunReport(campId: string): Promise<TeamRunReport> {
+  return invoke<TeamRunReport>('get_team_run_report', { campId });
 }
 
 export async function promoteArtifact(campId: string, draftPath: string): Promise<string> {
@@ -421,6 +1093,114 @@ export async function getMaxIterations(): Promise<number> {
   return invoke<number>('get_max_iterations');
 }
 
+export async function setMaxContinuations(value: number): Promise<number> {
+  return invoke<number>('set_max_continuations', { value });
+}
+
+export async function getMaxContinuations(): Promise<number> {
+  return invoke<number>('get_max_continuations');
+}
+
+export async function setSplashDurationMs(value: number): Promise<number> {
+  return invoke<number>('set_splash_duration_ms', { value });
+}
+
+export async function getSplashDurationMs(): Promise<number> {
+  return invoke<number>('get_splash_duration_ms');
+}
+
+export async function checkForUpdates(): Promise<UpdateCheck> {
+  return invoke<UpdateCheck>('check_for_updates');
+}
+
+export async function installUpdate(): Promise<void> {
+  await invoke('install_update');
+}
+
+export async function getUpdateChannel(): Promise<UpdateChannel> {
+  return invoke<UpdateChannel>('get_update_channel');
+}
+
+export async function setUpdateChannel(channel: UpdateChannel): Promise<UpdateChannel> {
+  return invoke<UpdateChannel>('set_update_channel', { channel });
+}
+
+export async function quickCapture(text: string): Promise<QuickCaptureResult> {
+  return invoke<QuickCaptureResult>('quick_capture', { text });
+}
+
+export async function dismissQuickCapture(): Promise<void> {
+  await invoke('dismiss_quick_capture');
+}
+
+export async function getQuickCaptureSettings(): Promise<QuickCaptureSettings> {
+  return invoke<QuickCaptureSettings>('get_quick_capture_settings');
+}
+
+export async function setQuickCaptureSettings(settings: QuickCaptureSettings): Promise<QuickCaptureSettings> {
+  return invoke<QuickCaptureSettings>('set_quick_capture_settings', { settings });
+}
+
+export async function getTraySettings(): Promise<TraySettings> {
+  return invoke<TraySettings>('get_tray_settings');
+}
+
+export async function setTraySettings(settings: TraySettings): Promise<TraySettings> {
+  return invoke<TraySettings>('set_tray_settings', { settings });
+}
+
+export async function listCrashReports(): Promise<CrashReportSummary[]> {
+  return invoke<CrashReportSummary[]>('list_crash_reports');
+}
+
+/** Opens a save dialog; resolves to the saved path, or null if cancelled. */
+export async function exportCrashReport(id: string): Promise<string | null> {
+  return invoke<string | null>('export_crash_report', { id });
+}
+
+export async function getPerformanceStats(since?: number): Promise<ModelPerformanceStats[]> {
+  return invoke<ModelPerformanceStats[]>('get_performance_stats', { since: since ?? null });
+}
+
+export async function getDigestSettings(): Promise<DigestSettings> {
+  return invoke<DigestSettings>('get_digest_settings');
+}
+
+export async function setDigestSettings(settings: DigestSettings): Promise<DigestSettings> {
+  return invoke<DigestSettings>('set_digest_settings', { settings });
+}
+
+export async function getDigestStatus(): Promise<DigestStatus> {
+  return invoke<DigestStatus>('get_digest_status');
+}
+
+export async function detectSyncConflicts(): Promise<SyncConflict[]> {
+  return invoke<SyncConflict[]>('detect_sync_conflicts');
+}
+
+/** With `dryRun`, reports what merging the conflicted transcript copy would do without writing. */
+export async function mergeSyncConflict(campId: string, path: string, dryRun = false): Promise<SyncMergeReport> {
+  return invoke<SyncMergeReport>('merge_sync_conflict', { campId, path, dryRun });
+}
+
+export async function getSyncSettings(): Promise<SyncSettings> {
+  return invoke<SyncSettings>('get_sync_settings');
+}
+
+/** `secret` is the WebDAV password or S3 secret key; omit it to keep the saved one. */
+export async function setSyncSettings(settings: SyncSettings, secret?: string): Promise<SyncSettings> {
+  return invoke<SyncSettings>('set_sync_settings', { settings, secret: secret || null });
+}
+
+export async function runSync(): Promise<SyncReport> {
+  return invoke<SyncReport>('run_sync');
+}
+
+/** Writes yesterday's digest immediately. */
+export async function runDigestNow(): Promise<DigestResult> {
+  return invoke<DigestResult>('run_digest_now');
+}
+
 export async function campWriteContextFileBytes(campId: string, path: string, contentBase64: string): Promise<void> {
   await invoke('tauri_cmd_write_context_file_bytes', { campId, path, contentBase64 });
 }
@@ -433,3 +1213,23 @@ export async function setWebGLEnabled(enabled: boolean): Promise<void> {
 export async function getWebGLEnabled(): Promise<boolean> {
   return localStorage.getItem('webgl_enabled') === 'true';
 }
+
+export async function campAssembleContext(payload: CampAssembleContextPayload): Promise<ContextAssembly> {
+  return invoke<ContextAssembly>('camp_assemble_context', { payload });
+}
+
+export async function getStartupDiagnostics(): Promise<StartupDiagnostics> {
+  return invoke<StartupDiagnostics>('get_startup_diagnostics');
+}
+
+export async function dbMigrationStatus(): Promise<MigrationStatus[]> {
+  return invoke<MigrationStatus[]>('db_migration_status');
+}
+
+export async function dbEncryptionStatus(): Promise<DbEncryptionStatus> {
+  return invoke<DbEncryptionStatus>('db_encryption_status');
+}
+
+export async function dbSetEncryptionPassphrase(passphrase: string): Promise<DbEncryptionStatus> {
+  return invoke<DbEncryptionStatus>('db_set_encryption_passphrase', { payload: { passphrase } });
+}
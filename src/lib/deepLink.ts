@@ -0,0 +1,21 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+import type { DeepLinkTarget } from './types';
+
+export const DEEP_LINK_EVENT = 'deeplink://open';
+
+export async function takePendingDeepLink(): Promise<DeepLinkTarget | null> {
+  return invoke<DeepLinkTarget | null>('take_pending_deep_link');
+}
+
+export async function listenDeepLinks(callback: (target: DeepLinkTarget) => void): Promise<UnlistenFn> {
+  return listen<DeepLinkTarget>(DEEP_LINK_EVENT, (event) => {
+    callback(event.payload);
+  });
+}
+
+export function deepLinkRoute(target: DeepLinkTarget): string {
+  const id = encodeURIComponent(target.id);
+  return target.kind === 'camp' ? `/camp/${id}` : `/runs/${id}`;
+}
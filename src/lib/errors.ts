@@ -0,0 +1,27 @@
+import type { BasecampError } from './types';
+
+export function isBasecampError(error: unknown): error is BasecampError {
+  if (typeof error !== 'object' || error === null) {
+    return false;
+  }
+  const candidate = error as Partial<BasecampError>;
+  return typeof candidate.kind === 'string' && typeof candidate.message === 'string';
+}
+
+export function isReadOnlyError(error: unknown): boolean {
+  return isBasecampError(error) && error.kind === 'read_only';
+}
+
+/** Commands reject with a structured `BasecampError`, a plain string, or an `Error`. */
+export function commandErrorMessage(error: unknown, fallback: string): string {
+  if (isBasecampError(error)) {
+    return error.message;
+  }
+  if (error instanceof Error) {
+    return error.message;
+  }
+  if (typeof error === 'string' && error.trim()) {
+    return error;
+  }
+  return fallback;
+}
@@ -0,0 +1,28 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+import type { McpSamplingRequest } from './types';
+
+export const MCP_SAMPLING_REQUEST_EVENT = 'mcp://sampling_request';
+
+export async function listenMcpSamplingRequests(
+  callback: (payload: McpSamplingRequest) => void,
+): Promise<UnlistenFn> {
+  return listen<McpSamplingRequest>(MCP_SAMPLING_REQUEST_EVENT, (event) => {
+    callback(event.payload);
+  });
+}
+
+export function describeSamplingRequest(request: McpSamplingRequest): string {
+  const prompt = request.messages
+    .map((message) => message.content.text ?? `[${message.content.type}]`)
+    .join('\n')
+    .trim();
+  const preview = prompt.length > 400 ? `${prompt.slice(0, 400)}…` : prompt;
+  return [
+    `MCP server "${request.server_id}" wants to run a completion with ${request.model} (up to ${request.max_tokens} tokens).`,
+    '',
+    preview,
+    '',
+    'Allow this request?',
+  ].join('\n');
+}
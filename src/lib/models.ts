@@ -1,4 +1,23 @@
 import { providerRefreshModels } from './db';
+import type { CostTier, ModelNote } from './types';
+
+const COST_TIER_LABELS: Record<CostTier, string> = {
+  free: 'free',
+  low: '$',
+  medium: '$$',
+  high: '$$$',
+};
+
+/** Compact verdict such as `★4 ⚡5 $$` for model pickers; empty without ratings. */
+export function modelNoteSummary(note: ModelNote | undefined): string {
+  if (!note) return '';
+  const parts = [
+    note.quality ? `★${note.quality}` : null,
+    note.speed ? `⚡${note.speed}` : null,
+    note.cost_tier ? COST_TIER_LABELS[note.cost_tier] : null,
+  ].filter((part): part is string => part !== null);
+  return parts.join(' ');
+}
 
 export async function syncModelsToDb(): Promise<{ count: number }> {
   try {
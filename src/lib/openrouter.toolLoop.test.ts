@@ -228,4 +228,55 @@ describe('runToolUseLoop', () => {
 
     expect(executeToolCall).toHaveBeenCalledTimes(2);
   });
+
+  it('aborts when the same tool call repeats past the limit', async () => {
+    invokeMock.mockResolvedValue(
+      makeCommandResult(
+        {
+          model: 'openrouter/auto',
+          choices: [
+            {
+              message: {
+                content: '',
+                tool_calls: [
+                  {
+                    id: 'tool-1',
+                    type: 'function',
+                    function: {
+                      name: 'read_file',
+                      arguments: '{"path":"loop.md"}',
+                    },
+                  },
+                ],
+              },
+            },
+          ],
+        },
+        '',
+      ),
+    );
+
+    const executeToolCall = vi.fn(async () => '{"ok":true}');
+
+    const result = await runToolUseLoop(
+      'camp-1',
+      [{ role: 'user', content: 'loop forever' }],
+      TEST_TOOLS,
+      vi.fn(),
+      {
+        model: 'openrouter/auto',
+        temperature: 0.3,
+        max_tokens: 300,
+        executeToolCall,
+        maxIterations: 10,
+        maxRepeatedToolCalls: 2,
+      },
+    );
+
+    expect(executeToolCall).toHaveBeenCalledTimes(2);
+    expect(result.loopAborted).toEqual({ reason: 'repeated_tool_call', tool_name: 'read_file', count: 3 });
+    const lastMessage = result.transcriptMessages[result.transcriptMessages.length - 1];
+    expect(lastMessage).toMatchObject({ role: 'tool', name: 'read_file' });
+    expect(String(lastMessage.content)).toContain('Tool loop aborted');
+  });
 });
@@ -1,7 +1,7 @@
 import { Channel, invoke } from '@tauri-apps/api/core';
 import { z } from 'zod';
 
-import type { RunFormValues, TokenUsage } from './types';
+import type { CampMessage, RunFormValues, ScrubAudit, TokenUsage, ToolLoopAbort, VariantGroup } from './types';
 
 const OpenRouterRequestSchema = z.object({
   model: z.string().min(1),
@@ -25,6 +25,8 @@ const OpenRouterChatRequestSchema = z
     max_tokens: z.number().int().positive(),
     tools: z.array(z.unknown()).optional(),
     tool_choice: z.unknown().optional(),
+    stop: z.array(z.string()).max(4).optional(),
+    logit_bias: z.record(z.string(), z.number().min(-100).max(100)).optional(),
   })
   .passthrough();
 
@@ -115,6 +117,8 @@ export type OpenRouterChatRequestPayload = {
   max_tokens: number;
   tools?: OpenRouterToolSpec[];
   tool_choice?: 'auto';
+  stop?: string[];
+  logit_bias?: Record<string, number>;
   stream?: boolean;
 };
 
@@ -135,14 +139,22 @@ export type OpenRouterChatRunResult = OpenRouterRunResult & {
 export type OpenRouterToolLoopExecutionInput = {
   campId: string;
   toolCall: OpenRouterToolCall & { id: string };
+  /** Durable run the call belongs to, when the loop started one. */
+  runId?: string;
 };
 
 export type OpenRouterToolLoopOptions = {
   model: string;
   temperature: number;
   max_tokens: number;
+  stop?: string[];
+  logit_bias?: Record<string, number>;
   executeToolCall: (input: OpenRouterToolLoopExecutionInput) => Promise<string>;
   maxIterations?: number;
+  /** Times one tool may be called with the same arguments before the loop is aborted. */
+  maxRepeatedToolCalls?: number;
+  /** Tokens the turn may spend across its requests before the loop is aborted. */
+  maxTurnTokens?: number;
   correlationId?: string;
   telemetry?: OpenRouterTelemetryHooks;
 };
@@ -150,8 +162,13 @@ export type OpenRouterToolLoopOptions = {
 export type OpenRouterToolLoopResult = OpenRouterRunResult & {
   transcriptMessages: OpenRouterChatMessage[];
   requestPayloads: OpenRouterChatRequestPayload[];
+  /** Set when the loop guard stopped the turn; pending tool calls were answered with an error. */
+  loopAborted?: ToolLoopAbort;
 };
 
+export const DEFAULT_MAX_REPEATED_TOOL_CALLS = 3;
+export const DEFAULT_MAX_TURN_TOKENS = 200_000;
+
 export type OpenRouterHttpRequestStartEvent = {
   timestamp_ms: number;
   request_payload: OpenRouterChatRequestPayload;
@@ -210,8 +227,14 @@ export type OpenRouterTelemetryHooks = {
 };
 
 type OpenRouterRequestOptions = {
+  /** Camp the request is made for; its routing policy applies. */
+  campId?: string;
   correlationId?: string;
   telemetry?: OpenRouterTelemetryHooks;
+  onReasoningToken?: (token: string) => void;
+  onProgress?: (progress: StreamProgressEvent) => void;
+  /** Called while a local provider is busy; 1 means next in line. */
+  onQueued?: (position: number) => void;
 };
 
 export class OpenRouterRequestError extends Error {
@@ -335,6 +358,7 @@ type OpenRouterCompletionCommandResult = {
     role: string;
     content: unknown;
     tool_calls: unknown[];
+    reasoning?: string | null;
   };
   usage: TokenUsage;
   resolved_model: string | null;
@@ -345,6 +369,7 @@ type OpenRouterCompletionCommandResult = {
   stream_chunk_count: number;
   sanitized_request_payload?: unknown;
   sanitized_response_payload?: unknown;
+  scrub_audit?: ScrubAudit;
 };
 
 type OpenRouterCompletionCommandError = {
@@ -364,6 +389,8 @@ type BasecampChatRequestPayload = {
   temperature?: number;
   max_tokens?: number;
   top_p?: number;
+  stop?: string[];
+  logit_bias?: Record<string, number>;
   stream: boolean;
   metadata: {
     correlation_id?: string;
@@ -371,13 +398,18 @@ type BasecampChatRequestPayload = {
   };
 };
 
-type OpenRouterStreamEventPayload =
+export type OpenRouterStreamEventPayload =
   | {
     type: 'chat_delta';
     correlation_id: string;
     role: string;
     content_delta: string;
   }
+  | {
+    type: 'reasoning_delta';
+    correlation_id: string;
+    reasoning_delta: string;
+  }
   | {
     type: 'tool_call_delta';
     correlation_id: string;
@@ -395,7 +427,22 @@ type OpenRouterStreamEventPayload =
     type: 'chat_error';
     correlation_id: string;
     message: string;
-  };
+  }
+  | {
+    type: 'chat_queued';
+    correlation_id: string;
+    position: number;
+  }
+  | StreamProgressEvent;
+
+/** Live generation speed; the last one for a reply carries its average. */
+export type StreamProgressEvent = {
+  type: 'chat_progress';
+  correlation_id: string;
+  tokens: number;
+  tokens_per_second: number;
+  elapsed_ms: number;
+};
 
 function parseModelReference(model: string): { providerKind: ProviderKind; modelId: string } {
   const trimmed = model.trim();
@@ -479,6 +526,8 @@ async function invokeOpenRouterCompletion(
     tool_choice: commandRequestPayload.tool_choice,
     temperature: commandRequestPayload.temperature,
     max_tokens: commandRequestPayload.max_tokens,
+    stop: commandRequestPayload.stop,
+    logit_bias: commandRequestPayload.logit_bias,
     stream,
     metadata: {
       correlation_id: options?.correlationId,
@@ -509,6 +558,21 @@ async function invokeOpenRouterCompletion(
       return;
     }
 
+    if (event.type === 'reasoning_delta' && event.reasoning_delta) {
+      options?.onReasoningToken?.(event.reasoning_delta);
+      return;
+    }
+
+    if (event.type === 'chat_progress') {
+      options?.onProgress?.(event);
+      return;
+    }
+
+    if (event.type === 'chat_queued') {
+      options?.onQueued?.(event.position);
+      return;
+    }
+
     if (event.type === 'chat_error') {
       callTelemetry(() => {
         options?.telemetry?.onHttpRequestError?.({
@@ -524,10 +588,16 @@ async function invokeOpenRouterCompletion(
   };
 
   try {
-    const result = await invoke<OpenRouterCompletionCommandResult>('cmd_send_chat', {
-      request: backendRequestPayload,
-      onEvent,
-    });
+    const result = options?.campId
+      ? await invoke<OpenRouterCompletionCommandResult>('camp_send_chat', {
+        campId: options.campId,
+        request: backendRequestPayload,
+        onEvent,
+      })
+      : await invoke<OpenRouterCompletionCommandResult>('cmd_send_chat', {
+        request: backendRequestPayload,
+        onEvent,
+      });
 
     const chunkCount = Math.max(result.stream_chunk_count ?? 0, streamedChunkCount);
     callTelemetry(() => {
@@ -571,6 +641,151 @@ async function invokeOpenRouterCompletion(
   }
 }
 
+export type SendChatStreamingResult = {
+  run_id: string;
+  response: OpenRouterCompletionCommandResult;
+  message: CampMessage;
+  inspect_bundle_path?: string;
+  continuations: number;
+};
+
+// The backend stores the run, writes inspect files and appends the assistant
+// reply to the camp transcript, so callers only render the stream.
+export async function sendChatStreaming(
+  campId: string,
+  requestPayload: OpenRouterChatRequestPayload,
+  onEvent: (event: OpenRouterStreamEventPayload) => void,
+  options?: { correlationId?: string; userPrompt?: string },
+): Promise<SendChatStreamingResult> {
+  const validatedRequestPayload = OpenRouterChatRequestSchema.parse(requestPayload) as OpenRouterChatRequestPayload;
+  const modelRef = parseModelReference(validatedRequestPayload.model);
+  const request: BasecampChatRequestPayload = {
+    provider_kind: modelRef.providerKind,
+    model_id: modelRef.modelId,
+    messages: validatedRequestPayload.messages,
+    tools: validatedRequestPayload.tools,
+    tool_choice: validatedRequestPayload.tool_choice,
+    temperature: validatedRequestPayload.temperature,
+    max_tokens: validatedRequestPayload.max_tokens,
+    stop: validatedRequestPayload.stop,
+    logit_bias: validatedRequestPayload.logit_bias,
+    stream: true,
+    metadata: {
+      correlation_id: options?.correlationId,
+      provider_kind: modelRef.providerKind,
+    },
+  };
+
+  const channel = new Channel<OpenRouterStreamEventPayload>();
+  channel.onmessage = onEvent;
+
+  try {
+    return await invoke<SendChatStreamingResult>('send_chat_streaming', {
+      payload: {
+        camp_id: campId,
+        request,
+        user_prompt: options?.userPrompt,
+      },
+      onEvent: channel,
+    });
+  } catch (error) {
+    const parsed = normalizeCommandError(error);
+    throw new OpenRouterRequestError(parsed.message, validatedRequestPayload, parsed.response_payload);
+  }
+}
+
+export type RegenerateMessageResult = {
+  run_id: string;
+  response: OpenRouterCompletionCommandResult;
+  group: VariantGroup;
+};
+
+// Re-runs the camp's last user turn; the reply is stored as a new, selected
+// variant and the previous reply stays available through selectVariant. The
+// backend rebuilds the turn's context; pass the tool specs the composer would
+// send so the model sees the same request.
+export async function regenerateMessage(
+  campId: string,
+  onEvent: (event: OpenRouterStreamEventPayload) => void,
+  model?: string,
+  tools?: OpenRouterToolSpec[],
+): Promise<RegenerateMessageResult> {
+  const channel = new Channel<OpenRouterStreamEventPayload>();
+  channel.onmessage = onEvent;
+  return invoke<RegenerateMessageResult>('regenerate_message', {
+    payload: { camp_id: campId, model, tools },
+    onEvent: channel,
+  });
+}
+
+export type CouncilStreamEvent = {
+  model: string;
+  synthesizer: boolean;
+  event: OpenRouterStreamEventPayload;
+};
+
+export type CouncilMemberResult = {
+  model: string;
+  run_id: string;
+  variant_id?: string;
+  usage: TokenUsage;
+  error?: string;
+};
+
+export type CouncilResult = {
+  members: CouncilMemberResult[];
+  synthesis?: CouncilMemberResult;
+  group: VariantGroup;
+};
+
+// Sends the camp's last user message to 2–4 models at once. Each answer is
+// stored as a variant; the synthesizer's merged answer is selected when given.
+export async function runCouncil(
+  campId: string,
+  models: string[],
+  onEvent: (event: CouncilStreamEvent) => void,
+  synthesizerModel?: string,
+  tools?: OpenRouterToolSpec[],
+): Promise<CouncilResult> {
+  const channel = new Channel<CouncilStreamEvent>();
+  channel.onmessage = onEvent;
+  return invoke<CouncilResult>('run_council', {
+    payload: { camp_id: campId, models, synthesizer_model: synthesizerModel, tools },
+    onEvent: channel,
+  });
+}
+
+function sortJsonKeys(value: unknown): unknown {
+  if (Array.isArray(value)) {
+    return value.map(sortJsonKeys);
+  }
+  if (value && typeof value === 'object') {
+    return Object.fromEntries(
+      Object.keys(value)
+        .sort()
+        .map((key) => [key, sortJsonKeys((value as Record<string, unknown>)[key])]),
+    );
+  }
+  return value;
+}
+
+// Reordered keys still count as the same call.
+function canonicalToolArguments(argumentsJson: string | undefined): string {
+  const raw = argumentsJson ?? '';
+  try {
+    return JSON.stringify(sortJsonKeys(JSON.parse(raw)));
+  } catch {
+    return raw.trim();
+  }
+}
+
+export function toolLoopAbortMessage(abort: ToolLoopAbort): string {
+  if (abort.reason === 'repeated_tool_call') {
+    return `Tool loop aborted: \`${abort.tool_name}\` was called ${abort.count} times with the same arguments.`;
+  }
+  return `Tool loop aborted: the turn used ${abort.tokens_used} tokens, over its ${abort.limit}-token limit.`;
+}
+
 function isToolResultSuccess(result: string): boolean {
   try {
     const parsed = JSON.parse(result) as { error?: unknown };
@@ -661,6 +876,10 @@ export async function runToolUseLoop(
   const transcriptMessages: OpenRouterChatMessage[] = [];
   const conversationMessages: OpenRouterChatMessage[] = [...messages];
   const maxIterations = options.maxIterations ?? 8;
+  const maxRepeatedToolCalls = options.maxRepeatedToolCalls ?? DEFAULT_MAX_REPEATED_TOOL_CALLS;
+  const maxTurnTokens = options.maxTurnTokens ?? DEFAULT_MAX_TURN_TOKENS;
+  const callCounts = new Map<string, number>();
+  let tokensUsed = 0;
 
   let usage: TokenUsage = {
     prompt_tokens: null,
@@ -677,18 +896,23 @@ export async function runToolUseLoop(
       messages: [...conversationMessages],
       temperature: options.temperature,
       max_tokens: options.max_tokens,
+      stop: options.stop,
+      logit_bias: options.logit_bias,
       tools,
       tool_choice: 'auto',
     };
     requestPayloads.push(requestPayload);
 
     const completion = await runOpenRouterChatCompletion(requestPayload, {
+      campId,
       correlationId: options.correlationId,
       telemetry: options.telemetry,
     });
     responsePayload = completion.responsePayload;
     usage = completion.usage;
     resolvedModel = completion.resolvedModel ?? resolvedModel;
+    tokensUsed +=
+      completion.usage.total_tokens ?? (completion.usage.prompt_tokens ?? 0) + (completion.usage.completion_tokens ?? 0);
 
     const normalizedToolCalls = normalizeToolCallIds(completion.assistantMessage.toolCalls, iteration);
     const assistantText = normalizeMessageContent(completion.assistantMessage.content).trim();
@@ -722,6 +946,45 @@ export async function runToolUseLoop(
       };
     }
 
+    let loopAborted: ToolLoopAbort | undefined =
+      tokensUsed > maxTurnTokens ? { reason: 'token_ceiling', tokens_used: tokensUsed, limit: maxTurnTokens } : undefined;
+    for (const toolCall of normalizedToolCalls) {
+      if (loopAborted) break;
+      const key = `${toolCall.function.name}\u0000${canonicalToolArguments(toolCall.function.arguments)}`;
+      const count = (callCounts.get(key) ?? 0) + 1;
+      callCounts.set(key, count);
+      if (count > maxRepeatedToolCalls) {
+        loopAborted = { reason: 'repeated_tool_call', tool_name: toolCall.function.name, count };
+      }
+    }
+
+    if (loopAborted) {
+      const abortMessage = toolLoopAbortMessage(loopAborted);
+      // Answer every pending call so the stored turn stays replayable.
+      for (const toolCall of normalizedToolCalls) {
+        const toolMessage: OpenRouterChatMessage = {
+          role: 'tool',
+          tool_call_id: toolCall.id,
+          name: toolCall.function.name,
+          content: JSON.stringify({ error: abortMessage }),
+        };
+        conversationMessages.push(toolMessage);
+        transcriptMessages.push(toolMessage);
+      }
+
+      outputText = assistantText || abortMessage;
+      onToken(outputText);
+      return {
+        responsePayload,
+        outputText,
+        usage,
+        resolvedModel,
+        transcriptMessages,
+        requestPayloads,
+        loopAborted,
+      };
+    }
+
     for (const toolCall of normalizedToolCalls) {
       const toolStartedAt = Date.now();
       callTelemetry(() => {
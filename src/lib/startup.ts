@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+import type { InitStatus } from './types';
+
+export const STARTUP_PROGRESS_EVENT = 'startup://progress';
+
+export async function getInitStatus(): Promise<InitStatus> {
+  return invoke<InitStatus>('get_init_status');
+}
+
+export async function listenStartupProgress(
+  callback: (status: InitStatus) => void,
+): Promise<UnlistenFn> {
+  return listen<InitStatus>(STARTUP_PROGRESS_EVENT, (event) => {
+    callback(event.payload);
+  });
+}
@@ -1,6 +1,7 @@
 import { describe, expect, it, vi } from 'vitest';
 
 import { executeCampToolCall, getToolKind, type CampToolHandlers } from './tools';
+import { buildMcpToolEntry, getMcpTools, qualifyToolName, setMcpTools } from './tools/registry';
 import type { CampArtifact, CampArtifactMetadata, CampTranscriptSearchMatch } from './types';
 
 const ACTIVE_ARTIFACT: CampArtifactMetadata = {
@@ -54,6 +55,42 @@ function makeHandlers(overrides: Partial<CampToolHandlers> = {}): CampToolHandle
     searchTranscript: vi.fn(async () => [TRANSCRIPT_MATCH]),
     updateCampPrompt: vi.fn(async () => {}),
     updateCampMemory: vi.fn(async () => {}),
+    remember: vi.fn(async (key: string, note: string) => ({ key, stored: note !== '', note_count: 1 })),
+    recall: vi.fn(async (key?: string) => (key ? 'Draft outline first' : { plan: 'Draft outline first' })),
+    runBuiltinTool: vi.fn(async () => ({ expression: '6*7', result: 42 })),
+    fetchUrl: vi.fn(async (url: string) => ({
+      url,
+      final_url: url,
+      status: 200,
+      content_type: 'text/html',
+      title: 'Example',
+      markdown: '# Example',
+      bytes: 120,
+      truncated: false,
+    })),
+    queryDatabase: vi.fn(async ({ database }: { database: string }) => ({
+      database,
+      columns: ['name'],
+      rows: [['tent']],
+      row_count: 1,
+      truncated: false,
+    })),
+    searchContext: vi.fn(async () => ({
+      hits: [
+        {
+          camp_id: 'camp-1',
+          kind: 'context_file' as const,
+          path: 'context/notes/pricing.md',
+          title: 'context/notes/pricing.md',
+          snippet: 'Annual [pricing] tiers',
+          score: 0.032,
+          lexical: { rank: 1, score: 4.2, contribution: 0.016 },
+          vector: { rank: 1, score: 0.81, contribution: 0.016 },
+        },
+      ],
+      embedding_model: 'nomic-embed-text',
+      vector_error: null,
+    })),
     ...overrides,
   };
 }
@@ -138,6 +175,78 @@ describe('executeCampToolCall', () => {
     });
   });
 
+  it('executes remember and recall against the scratchpad handlers', async () => {
+    const handlers = makeHandlers();
+    const call = (name: string, args: Record<string, unknown>) =>
+      executeCampToolCall({ type: 'function', function: { name, arguments: JSON.stringify(args) } }, handlers);
+
+    expect(JSON.parse(await call('remember', { key: ' plan ', note: 'Draft outline first' }))).toEqual({
+      key: 'plan',
+      stored: true,
+      note_count: 1,
+    });
+    expect(handlers.remember).toHaveBeenCalledWith('plan', 'Draft outline first');
+    expect(JSON.parse(await call('recall', { key: 'plan' }))).toEqual({
+      key: 'plan',
+      note: 'Draft outline first',
+    });
+    expect(JSON.parse(await call('recall', {}))).toEqual({ notes: { plan: 'Draft outline first' } });
+    expect(getToolKind('remember')).toBe('mutate');
+    expect(getToolKind('recall')).toBe('read');
+  });
+
+  it('routes built-in tools to the backend with defaulted args', async () => {
+    const handlers = makeHandlers();
+    const result = await executeCampToolCall(
+      { type: 'function', function: { name: 'calculate', arguments: JSON.stringify({ expression: ' 6*7 ' }) } },
+      handlers,
+    );
+    expect(JSON.parse(result)).toEqual({ expression: '6*7', result: 42 });
+    expect(handlers.runBuiltinTool).toHaveBeenCalledWith('calculate', { expression: '6*7' });
+
+    await executeCampToolCall({ type: 'function', function: { name: 'random_uuid', arguments: '{}' } }, handlers);
+    expect(handlers.runBuiltinTool).toHaveBeenLastCalledWith('random_uuid', { count: 1 });
+    expect(getToolKind('current_datetime')).toBe('read');
+  });
+
+  it('passes query_database args through as a read tool', async () => {
+    const handlers = makeHandlers();
+    const result = await executeCampToolCall(
+      {
+        type: 'function',
+        function: {
+          name: 'query_database',
+          arguments: JSON.stringify({ database: 'shop', sql: 'SELECT name FROM items', max_rows: 5 }),
+        },
+      },
+      handlers,
+    );
+    expect(JSON.parse(result)).toMatchObject({ database: 'shop', rows: [['tent']] });
+    expect(handlers.queryDatabase).toHaveBeenCalledWith({ database: 'shop', sql: 'SELECT name FROM items', maxRows: 5 });
+    expect(getToolKind('query_database')).toBe('read');
+  });
+
+  it('returns search_context hits with paths relative to the context folder', async () => {
+    const handlers = makeHandlers();
+    const result = await executeCampToolCall(
+      {
+        type: 'function',
+        function: {
+          name: 'search_context',
+          arguments: JSON.stringify({ query: 'pricing tiers' }),
+        },
+      },
+      handlers,
+    );
+
+    expect(handlers.searchContext).toHaveBeenCalledWith({ query: 'pricing tiers', limit: 5 });
+    expect(JSON.parse(result)).toEqual({
+      query: 'pricing tiers',
+      results: [{ path: 'notes/pricing.md', snippet: 'Annual [pricing] tiers', score: 0.032 }],
+    });
+    expect(getToolKind('search_context')).toBe('read');
+  });
+
   it('executes search_transcript with validated args', async () => {
     const handlers = makeHandlers();
     const result = await executeCampToolCall(
@@ -176,3 +285,28 @@ describe('getToolKind', () => {
     expect(getToolKind('unknown_tool')).toBeNull();
   });
 });
+
+describe('MCP tool registry', () => {
+  it('drops names that shadow built-in tools and resolves legacy names', () => {
+    const raw = {
+      server_id: 'files',
+      name: 'search',
+      description: 'Search files',
+      input_schema: { type: 'object', properties: {} },
+      read_only: true,
+    };
+    setMcpTools([
+      buildMcpToolEntry({ ...raw, qualified_name: 'mcp__files__search' }),
+      buildMcpToolEntry({ ...raw, qualified_name: 'read_file', read_only: false }),
+      buildMcpToolEntry({ ...raw, qualified_name: 'mcp__files__search', read_only: false }),
+    ]);
+
+    expect(getMcpTools()).toHaveLength(1);
+    expect(getToolKind('read_file')).toBe('read');
+    expect(getToolKind('files/search')).toBe('read');
+    expect(qualifyToolName('files/search')).toBe('mcp__files__search');
+    expect(qualifyToolName('read_file')).toBe('read_file');
+
+    setMcpTools([]);
+  });
+});
@@ -1,13 +1,31 @@
 import { invoke } from '@tauri-apps/api/core';
 
+import { isBasecampError } from './errors';
 import type { OpenRouterToolCall, OpenRouterToolSpec } from './openrouter';
-import type { CampArtifact, CampArtifactMetadata, CampTranscriptSearchMatch } from './types';
+import type {
+  BuiltinToolName,
+  CampArtifact,
+  CampArtifactMetadata,
+  CampTranscriptSearchMatch,
+  DatabaseQueryResult,
+  FetchedPage,
+  HybridSearchResults,
+  ScratchpadRememberResult,
+} from './types';
 import {
+  campCalculateArgsSchema,
   campCreateArtifactArgsSchema,
+  campCurrentDatetimeArgsSchema,
+  campFetchUrlArgsSchema,
   campGetArtifactArgsSchema,
   campListArtifactsArgsSchema,
   campListFilesArgsSchema,
+  campQueryDatabaseArgsSchema,
+  campRandomUuidArgsSchema,
   campReadFileArgsSchema,
+  campRecallArgsSchema,
+  campRememberArgsSchema,
+  campSearchContextArgsSchema,
   campSearchTranscriptArgsSchema,
   campToolSpecs,
   campUpdateArtifactArgsSchema,
@@ -19,6 +37,7 @@ import {
   getMcpToolSpecs,
   isCampToolName,
   parseToolArguments,
+  resolveMcpTool,
   type ToolKind,
 } from './tools/registry';
 
@@ -37,6 +56,13 @@ export type CampToolHandlers = {
   }) => Promise<CampTranscriptSearchMatch[]>;
   updateCampPrompt: (systemPrompt: string) => Promise<void>;
   updateCampMemory: (memory: Record<string, unknown>) => Promise<void>;
+  remember: (key: string, note: string) => Promise<ScratchpadRememberResult>;
+  /** One note (or `null`) when `key` is given, otherwise every note by key. */
+  recall: (key?: string) => Promise<unknown>;
+  runBuiltinTool: (name: BuiltinToolName, args: Record<string, unknown>) => Promise<unknown>;
+  fetchUrl: (url: string) => Promise<FetchedPage>;
+  queryDatabase: (input: { database: string; sql: string; maxRows?: number }) => Promise<DatabaseQueryResult>;
+  searchContext: (input: { query: string; limit: number }) => Promise<HybridSearchResults>;
 };
 
 export const CAMP_TOOLS: OpenRouterToolSpec[] = campToolSpecs;
@@ -82,20 +108,19 @@ type McpToolResultPayload = {
 
 export async function executeMcpToolCall(
   toolCall: OpenRouterToolCall,
+  campId?: string,
 ): Promise<string> {
-  const name = toolCall.function.name;
-  const slashIndex = name.indexOf('/');
-  if (slashIndex === -1) {
-    throw new Error(`Invalid MCP tool name (expected serverId/toolName): ${name}`);
+  const entry = resolveMcpTool(toolCall.function.name);
+  if (!entry) {
+    throw new Error(`Unknown MCP tool: ${toolCall.function.name}`);
   }
-  const serverId = name.slice(0, slashIndex);
-  const toolName = name.slice(slashIndex + 1);
   const args = parseToolArguments(toolCall.function.arguments ?? '{}');
 
   const result = await invoke<McpToolResultPayload>('mcp_call_tool', {
-    serverId,
-    toolName,
+    serverId: entry.serverId,
+    toolName: entry.name,
     arguments: args,
+    campId,
   });
 
   if (result.is_error) {
@@ -195,12 +220,61 @@ export async function executeCampToolCall(
     }
     case 'update_camp_memory': {
       const args = campUpdateMemoryArgsSchema.parse(rawArgs);
-      await handlers.updateCampMemory(args.memory);
+      try {
+        await handlers.updateCampMemory(args.memory);
+      } catch (error) {
+        // Hand schema violations back to the model so it can fix the memory and retry.
+        if (isBasecampError(error) && error.kind === 'validation' && error.context?.violations) {
+          return toJsonString({ updated: false, error: error.message, violations: error.context.violations });
+        }
+        throw error;
+      }
       return toJsonString({
         updated: true,
         memory_keys: Object.keys(args.memory).sort((left, right) => left.localeCompare(right)),
       });
     }
+    case 'remember': {
+      const args = campRememberArgsSchema.parse(rawArgs);
+      return toJsonString(await handlers.remember(args.key, args.note));
+    }
+    case 'recall': {
+      const args = campRecallArgsSchema.parse(rawArgs);
+      const recalled = await handlers.recall(args.key);
+      return toJsonString(args.key ? { key: args.key, note: recalled ?? null } : { notes: recalled });
+    }
+    case 'calculate': {
+      const args = campCalculateArgsSchema.parse(rawArgs);
+      return toJsonString(await handlers.runBuiltinTool('calculate', args));
+    }
+    case 'current_datetime': {
+      const args = campCurrentDatetimeArgsSchema.parse(rawArgs);
+      return toJsonString(await handlers.runBuiltinTool('current_datetime', args));
+    }
+    case 'random_uuid': {
+      const args = campRandomUuidArgsSchema.parse(rawArgs);
+      return toJsonString(await handlers.runBuiltinTool('random_uuid', args));
+    }
+    case 'fetch_url': {
+      const args = campFetchUrlArgsSchema.parse(rawArgs);
+      return toJsonString(await handlers.fetchUrl(args.url));
+    }
+    case 'query_database': {
+      const args = campQueryDatabaseArgsSchema.parse(rawArgs);
+      return toJsonString(
+        await handlers.queryDatabase({ database: args.database, sql: args.sql, maxRows: args.max_rows }),
+      );
+    }
+    case 'search_context': {
+      const args = campSearchContextArgsSchema.parse(rawArgs);
+      const results = await handlers.searchContext({ query: args.query, limit: args.limit });
+      return toJsonString({
+        query: args.query,
+        // Paths are made relative to the context folder, as read_file expects.
+        results: results.hits.map((hit) => ({ path: hit.path.replace(/^context\//, ''), snippet: hit.snippet, score: hit.score })),
+        ...(results.vector_error ? { note: `Keyword matches only: ${results.vector_error}` } : {}),
+      });
+    }
     default: {
       throw new Error(`Unhandled tool: ${toolCall.function.name}`);
     }
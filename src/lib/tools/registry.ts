@@ -175,8 +175,16 @@ export type CampToolName =
   | 'create_artifact'
   | 'update_artifact'
   | 'search_transcript'
+  | 'search_context'
   | 'update_camp_prompt'
-  | 'update_camp_memory';
+  | 'update_camp_memory'
+  | 'remember'
+  | 'recall'
+  | 'calculate'
+  | 'current_datetime'
+  | 'random_uuid'
+  | 'fetch_url'
+  | 'query_database';
 
 export const campReadFileArgsSchema = z.object({
   path: z.string().trim().min(1),
@@ -235,6 +243,42 @@ export const campUpdateMemoryArgsSchema = z.object({
   memory: z.record(z.string(), z.unknown()),
 }).strict();
 
+export const campRememberArgsSchema = z.object({
+  key: z.string().trim().min(1).max(100),
+  note: z.string().max(8000),
+}).strict();
+
+export const campRecallArgsSchema = z.object({
+  key: z.string().trim().min(1).optional(),
+}).strict();
+
+export const campCalculateArgsSchema = z.object({
+  expression: z.string().trim().min(1).max(1000),
+}).strict();
+
+export const campCurrentDatetimeArgsSchema = z.object({
+  utc_offset: z.string().trim().min(1).optional(),
+}).strict();
+
+export const campRandomUuidArgsSchema = z.object({
+  count: z.number().int().min(1).max(20).optional().default(1),
+}).strict();
+
+export const campFetchUrlArgsSchema = z.object({
+  url: z.string().trim().url(),
+}).strict();
+
+export const campQueryDatabaseArgsSchema = z.object({
+  database: z.string().trim().min(1),
+  sql: z.string().trim().min(1),
+  max_rows: z.number().int().min(1).max(1000).optional(),
+}).strict();
+
+export const campSearchContextArgsSchema = z.object({
+  query: z.string().trim().min(1),
+  limit: z.number().int().min(1).max(20).optional().default(5),
+}).strict();
+
 type CampToolDefinition = {
   kind: ToolKind;
   spec: OpenRouterToolSpec;
@@ -461,6 +505,35 @@ const CAMP_TOOL_DEFINITIONS: Record<CampToolName, CampToolDefinition> = {
       },
     },
   },
+  search_context: {
+    kind: 'read',
+    argsSchema: campSearchContextArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'search_context',
+        description:
+          "Find the camp's context files most relevant to a question, matching both keywords and meaning. Returns paths with snippets; use read_file for full contents.",
+        parameters: {
+          type: 'object',
+          properties: {
+            query: {
+              type: 'string',
+              description: 'Question or keywords to look for.',
+            },
+            limit: {
+              type: 'integer',
+              minimum: 1,
+              maximum: 20,
+              default: 5,
+            },
+          },
+          required: ['query'],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
   update_camp_prompt: {
     kind: 'mutate',
     argsSchema: campUpdatePromptArgsSchema,
@@ -505,6 +578,180 @@ const CAMP_TOOL_DEFINITIONS: Record<CampToolName, CampToolDefinition> = {
       },
     },
   },
+  remember: {
+    kind: 'mutate',
+    argsSchema: campRememberArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'remember',
+        description:
+          'Save a short note to the camp scratchpad under a key, replacing any earlier note with that key. Notes persist across turns; use them for working state instead of context files.',
+        parameters: {
+          type: 'object',
+          properties: {
+            key: {
+              type: 'string',
+              description: 'Note key, up to 100 characters.',
+            },
+            note: {
+              type: 'string',
+              description: 'Note text, up to 8000 characters. An empty string forgets the key.',
+            },
+          },
+          required: ['key', 'note'],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  recall: {
+    kind: 'read',
+    argsSchema: campRecallArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'recall',
+        description: 'Read notes saved with `remember`: one note by key, or every note when no key is given.',
+        parameters: {
+          type: 'object',
+          properties: {
+            key: {
+              type: 'string',
+              description: 'Optional note key.',
+            },
+          },
+          required: [],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  calculate: {
+    kind: 'read',
+    argsSchema: campCalculateArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'calculate',
+        description:
+          'Evaluate an arithmetic expression exactly instead of computing it yourself. Supports + - * / % ^, parentheses, pi, e, and sqrt, cbrt, abs, exp, ln, log, log2, log10, sin, cos, tan, asin, acos, atan, floor, ceil, round, trunc, pow, min, max.',
+        parameters: {
+          type: 'object',
+          properties: {
+            expression: {
+              type: 'string',
+              description: 'Expression to evaluate, e.g. "(1250 * 0.07) / 12".',
+            },
+          },
+          required: ['expression'],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  current_datetime: {
+    kind: 'read',
+    argsSchema: campCurrentDatetimeArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'current_datetime',
+        description: "Get the current date, time and weekday. Defaults to the user's local time zone.",
+        parameters: {
+          type: 'object',
+          properties: {
+            utc_offset: {
+              type: 'string',
+              description: 'Optional UTC offset such as "+05:30", "-08:00" or "Z".',
+            },
+          },
+          required: [],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  random_uuid: {
+    kind: 'read',
+    argsSchema: campRandomUuidArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'random_uuid',
+        description: 'Generate random version 4 UUIDs.',
+        parameters: {
+          type: 'object',
+          properties: {
+            count: {
+              type: 'integer',
+              minimum: 1,
+              maximum: 20,
+              default: 1,
+            },
+          },
+          required: [],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  fetch_url: {
+    kind: 'read',
+    argsSchema: campFetchUrlArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'fetch_url',
+        description:
+          "Download a web page with GET and return its main content as Markdown. Only domains on the camp's fetch allowlist can be fetched.",
+        parameters: {
+          type: 'object',
+          properties: {
+            url: {
+              type: 'string',
+              description: 'Absolute http or https URL.',
+            },
+          },
+          required: ['url'],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
+  query_database: {
+    kind: 'read',
+    argsSchema: campQueryDatabaseArgsSchema,
+    spec: {
+      type: 'function',
+      function: {
+        name: 'query_database',
+        description:
+          'Run one read-only SQLite SELECT against a database the user registered in Settings and return the rows as JSON. Query sqlite_master to discover tables.',
+        parameters: {
+          type: 'object',
+          properties: {
+            database: {
+              type: 'string',
+              description: 'Name the database was registered under.',
+            },
+            sql: {
+              type: 'string',
+              description: 'A single SELECT or WITH ... SELECT statement.',
+            },
+            max_rows: {
+              type: 'integer',
+              minimum: 1,
+              maximum: 1000,
+              description: 'Row cap; defaults to 100.',
+            },
+          },
+          required: ['database', 'sql'],
+          additionalProperties: false,
+        },
+      },
+    },
+  },
 };
 
 const CAMP_TOOL_NAME_ORDER: CampToolName[] = [
@@ -516,8 +763,16 @@ const CAMP_TOOL_NAME_ORDER: CampToolName[] = [
   'create_artifact',
   'update_artifact',
   'search_transcript',
+  'search_context',
   'update_camp_prompt',
   'update_camp_memory',
+  'remember',
+  'recall',
+  'calculate',
+  'current_datetime',
+  'random_uuid',
+  'fetch_url',
+  'query_database',
 ];
 
 export const campToolSpecs: OpenRouterToolSpec[] = CAMP_TOOL_NAME_ORDER.map((name) => CAMP_TOOL_DEFINITIONS[name].spec);
@@ -562,8 +817,47 @@ export type McpToolEntry = {
 
 let mcpToolRegistry: McpToolEntry[] = [];
 
+/**
+ * Registers discovered MCP tools. The backend already hands out unique
+ * `mcp__{server}__{tool}` names; anything that would still shadow a built-in
+ * tool or an earlier entry is dropped so providers never see duplicate names.
+ */
 export function setMcpTools(tools: McpToolEntry[]): void {
-  mcpToolRegistry = tools;
+  const taken = new Set<string>([
+    ...CAMP_TOOL_NAME_ORDER,
+    ...TOOL_SPECS.map((spec) => spec.function.name),
+  ]);
+  mcpToolRegistry = tools.filter((entry) => {
+    if (taken.has(entry.qualifiedName)) {
+      return false;
+    }
+    taken.add(entry.qualifiedName);
+    return true;
+  });
+}
+
+/**
+ * Finds the MCP tool behind a provider-facing name. Transcripts saved before
+ * qualified names existed used `serverId/toolName`, which still resolves.
+ */
+export function resolveMcpTool(name: string): McpToolEntry | null {
+  const exact = mcpToolRegistry.find((t) => t.qualifiedName === name);
+  if (exact) {
+    return exact;
+  }
+
+  const slashIndex = name.indexOf('/');
+  if (slashIndex === -1) {
+    return null;
+  }
+  const serverId = name.slice(0, slashIndex);
+  const toolName = name.slice(slashIndex + 1);
+  return mcpToolRegistry.find((t) => t.serverId === serverId && t.name === toolName) ?? null;
+}
+
+/** Rewrites legacy MCP tool names to their qualified form; other names pass through. */
+export function qualifyToolName(name: string): string {
+  return resolveMcpTool(name)?.qualifiedName ?? name;
 }
 
 export function getMcpTools(): McpToolEntry[] {
@@ -575,12 +869,11 @@ export function getMcpToolSpecs(): OpenRouterToolSpec[] {
 }
 
 export function getMcpToolKind(qualifiedName: string): ToolKind | null {
-  const entry = mcpToolRegistry.find((t) => t.qualifiedName === qualifiedName);
-  return entry?.kind ?? null;
+  return resolveMcpTool(qualifiedName)?.kind ?? null;
 }
 
 export function isMcpToolName(name: string): boolean {
-  return mcpToolRegistry.some((t) => t.qualifiedName === name);
+  return resolveMcpTool(name) !== null;
 }
 
 export function buildMcpToolSpec(entry: {
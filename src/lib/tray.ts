@@ -0,0 +1,11 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+import type { TrayAction } from './types';
+
+export const TRAY_ACTION_EVENT = 'tray://action';
+
+export async function listenTrayActions(callback: (action: TrayAction) => void): Promise<UnlistenFn> {
+  return listen<TrayAction>(TRAY_ACTION_EVENT, (event) => {
+    callback(event.payload);
+  });
+}
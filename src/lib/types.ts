@@ -18,6 +18,21 @@ export type ProviderRegistryRow = {
   last_error: string | null;
 };
 
+export type RequestPriority = 'interactive' | 'team' | 'scheduled';
+
+export type ProviderQueueStatus = {
+  provider_kind: ProviderKind;
+  /** Requests the provider may run at once. */
+  capacity: number;
+  running: number;
+  waiting: Record<RequestPriority, number>;
+};
+
+export type ProviderConcurrency = {
+  provider_kind: ProviderKind;
+  max_concurrent_requests: number;
+};
+
 export type ProviderModelsRefreshItem = {
   provider_kind: string;
   count: number;
@@ -45,6 +60,50 @@ export type ModelRow = {
   capabilities_json: string;
   raw_json: string;
   updated_at: number;
+  /** The user's own verdict, when one has been recorded. */
+  note?: ModelNote;
+};
+
+export type CostTier = 'free' | 'low' | 'medium' | 'high';
+
+export type ModelNote = {
+  provider_kind: string;
+  model_id: string;
+  /** 1 (poor) to 5 (excellent). */
+  quality: number | null;
+  /** 1 (slow) to 5 (fast). */
+  speed: number | null;
+  cost_tier: CostTier | null;
+  notes: string;
+  updated_at: number;
+};
+
+export type RecommendModelPayload = {
+  prompt: string;
+  camp_id?: string | null;
+  has_images?: boolean;
+  /** Overrides the camp's tools setting. */
+  needs_tools?: boolean | null;
+  limit?: number | null;
+};
+
+export type ModelRecommendation = {
+  /** Model reference such as `ollama/llama3.2`. */
+  model: string;
+  display_name: string;
+  is_local: boolean;
+  score: number;
+  reasons: string[];
+};
+
+export type RecommendModelResult = {
+  estimated_prompt_tokens: number;
+  needs_tools: boolean;
+  has_images: boolean;
+  local_only: boolean;
+  recommendations: ModelRecommendation[];
+  /** Registry models ruled out by a hard requirement. */
+  excluded_count: number;
 };
 
 export type ModelRowPayload = {
@@ -89,6 +148,40 @@ export type Run = {
   error: string | null;
   rating: number | null;
   tags: string | null;
+  generation_id: string | null;
+  total_cost: number | null;
+  native_tokens_prompt: number | null;
+  native_tokens_completion: number | null;
+  native_tokens_reasoning: number | null;
+  provider_name: string | null;
+  generation_json: string | null;
+  scrub_audit_json: string | null;
+};
+
+export type ListRunGroupsPayload = {
+  camp_id?: string;
+  limit?: number;
+  /** Hides families smaller than this; `2` lists only repeated prompts. */
+  min_runs?: number;
+};
+
+/** Runs that share a prompt (whitespace ignored) across models and parameters. */
+export type RunGroup = {
+  /** The prompt hash, or the run id for runs that have none (e.g. redacted ones). */
+  key: string;
+  prompt_hash: string | null;
+  system_prompt_preview: string;
+  user_prompt_preview: string;
+  run_count: number;
+  /** Distinct model, temperature and max token combinations. */
+  variant_count: number;
+  models: string[];
+  /** Newest first. */
+  run_ids: string[];
+  first_timestamp: number;
+  last_timestamp: number;
+  best_rating: number | null;
+  error_count: number;
 };
 
 export type RunInsertPayload = {
@@ -111,6 +204,9 @@ export type RunInsertPayload = {
   error: string | null;
   rating: number | null;
   tags: string | null;
+  generation_id?: string | null;
+  camp_id?: string | null;
+  scrub_audit_json?: string | null;
 };
 
 export type RunUpdatePayload = {
@@ -198,21 +294,99 @@ export type CampConfig = {
     temperature?: number;
     max_tokens?: number;
     top_p?: number;
+    stop?: string[];
+    logit_bias?: Record<string, number>;
   } | null;
   tools_enabled: boolean;
   is_team?: boolean;
+  description?: string;
+  color?: string;
+  emoji?: string;
+  pinned?: boolean;
+  favorite?: boolean;
+  generation_defaults?: CampGenerationDefaults | null;
+  tags?: string[];
+  /** Runs from this camp are logged without prompt or response content. */
+  privacy_mode?: boolean;
+  /** Only local models are recommended for this camp. */
+  local_only?: boolean;
+  /** Run in order over assistant replies before they are stored. */
+  post_processors?: PostProcessor[];
+  /** Fenced code blocks in assistant replies are saved as artifacts. */
+  auto_extract_code_blocks?: boolean;
+  /** Agent write_file calls wait for approval with a diff of the change. */
+  preview_file_writes?: boolean;
+  /** JSON Schema that memory writes must satisfy. */
+  memory_schema?: Record<string, unknown>;
+  project_id?: string;
+  /** Context files attached to every request from this camp. */
+  default_context_paths?: string[];
+  /** Hosts the fetch_url tool may download from; subdomains are included. */
+  fetch_allowed_domains?: string[];
   created_at: number;
   updated_at: number;
 };
 
+export type DefaultContextStatus = 'fresh' | 'outdated' | 'missing' | 'too_large';
+
+export type DefaultContextFile = {
+  path: string;
+  status: DefaultContextStatus;
+  /** Null unless the file goes into the request. */
+  content: string | null;
+  modified_at: number | null;
+  bytes: number;
+};
+
+export type PostProcessor =
+  | { kind: 'strip_markdown_fences' }
+  | { kind: 'wrap_lines'; width: number }
+  | { kind: 'regex_replace'; pattern: string; replacement?: string }
+  | { kind: 'extract_code_blocks'; min_lines?: number };
+
+export type CampGenerationDefaults = {
+  temperature?: number;
+  max_tokens?: number;
+  top_p?: number;
+  /** Up to four sequences; generation halts before emitting any of them. */
+  stop?: string[];
+  /** Token id to bias in the -100..100 range. Ignored by Ollama. */
+  logit_bias?: Record<string, number>;
+};
+
 export type CampSummary = {
   id: string;
   name: string;
   model: string;
+  description?: string;
+  color?: string;
+  emoji?: string;
+  pinned?: boolean;
+  favorite?: boolean;
+  tags?: string[];
+  project_id?: string;
+  /** When the camp was last opened on this machine. */
+  last_opened_at?: number;
   updated_at: number;
   path: string;
 };
 
+/** Pinned camps stay on top in every order. */
+export type CampListSort = 'updated' | 'recent' | 'alphabetical' | 'favorites_first';
+
+export type CampProject = {
+  id: string;
+  name: string;
+  color?: string;
+  created_at: number;
+};
+
+export type CampProjectGroup = {
+  /** `null` for camps that aren't in any project. */
+  project: CampProject | null;
+  camps: CampSummary[];
+};
+
 export type CampMessage = {
   id: string;
   role: 'system' | 'user' | 'assistant' | 'tool';
@@ -223,6 +397,9 @@ export type CampMessage = {
   tool_calls?: CampToolCall[];
   included_artifact_ids?: string[];
   attachments?: CampMessageAttachment[];
+  reasoning?: string;
+  /** The stored run behind an assistant reply; see `getRunForMessage`. */
+  run_id?: string;
 };
 
 export type CampMessageAttachment = {
@@ -244,6 +421,8 @@ export type CampToolCall = {
 export type Camp = {
   config: CampConfig;
   system_prompt: string;
+  /** The workspace's global instructions, sent above `system_prompt`. */
+  global_instructions?: string;
   memory: unknown;
   transcript: CampMessage[];
   context_path: string;
@@ -257,11 +436,58 @@ export type CampCreatePayload = {
   tools_enabled?: boolean;
 };
 
+export type CampTemplateArtifact = {
+  title: string;
+  body: string;
+  tags: string[];
+  always_include: boolean;
+};
+
+export type CampTemplate = {
+  id: string;
+  name: string;
+  description: string;
+  emoji: string | null;
+  color: string | null;
+  tags: string[];
+  tools_enabled: boolean;
+  auto_extract_code_blocks: boolean;
+  system_prompt: string;
+  memory: unknown;
+  memory_schema: Record<string, unknown> | null;
+  artifacts: CampTemplateArtifact[];
+};
+
+export type CampCreateFromTemplatePayload = {
+  template_id: string;
+  model: string;
+  /** Defaults to the template's name. */
+  name?: string;
+};
+
 export type CampUpdateConfigPayload = {
   camp_id: string;
   name: string;
   model: string;
   tools_enabled: boolean;
+  description?: string;
+  color?: string;
+  emoji?: string;
+  pinned?: boolean;
+  favorite?: boolean;
+  generation_defaults?: CampGenerationDefaults;
+  tags?: string[];
+  privacy_mode?: boolean;
+  local_only?: boolean;
+  post_processors?: PostProcessor[];
+  auto_extract_code_blocks?: boolean;
+  preview_file_writes?: boolean;
+  /** Pass `{}` to remove the schema. */
+  memory_schema?: Record<string, unknown>;
+};
+
+export type CampRenameFolderPayload = {
+  camp_id: string;
 };
 
 export type CampUpdateSystemPromptPayload = {
@@ -269,11 +495,260 @@ export type CampUpdateSystemPromptPayload = {
   system_prompt: string;
 };
 
+export type PromptVersion = {
+  id: string;
+  created_at: number;
+  size_bytes: number;
+  current: boolean;
+};
+
+export type CampDiffPromptVersionsPayload = {
+  camp_id: string;
+  from_version_id: string;
+  to_version_id?: string;
+};
+
+export type CampRestorePromptVersionPayload = {
+  camp_id: string;
+  version_id: string;
+};
+
+export type ContextFileVersion = {
+  id: string;
+  /** When these contents were replaced. */
+  created_at: number;
+  size_bytes: number;
+};
+
+export type CampRestoreContextFileVersionPayload = {
+  camp_id: string;
+  path: string;
+  version_id: string;
+};
+
+export type ContextWritePreview = {
+  path: string;
+  /** False when the write would create the file. */
+  exists: boolean;
+  /** Binary content gets no diff. */
+  binary: boolean;
+  /** The change was too large to diff. */
+  too_large: boolean;
+  /** Unified diff; empty when the write would change nothing. */
+  diff: string;
+  bytes_before: number;
+  bytes_after: number;
+};
+
+export type RenderArtifactRichPayload = {
+  camp_id: string;
+  artifact_id: string;
+  /** Wrap the result in a complete HTML document for saving or printing. */
+  standalone?: boolean;
+};
+
+export type RichRender = {
+  html: string;
+  math_rendered: number;
+  diagrams_rendered: number;
+  /** Formulas and diagrams left as source, with the reason. */
+  warnings: string[];
+};
+
+export type ImproveSystemPromptPayload = {
+  camp_id: string;
+  model?: string;
+  max_failures?: number;
+};
+
+export type PromptFailure = {
+  source: 'tool_error' | 'run_error' | 'low_rating';
+  reference_id: string;
+  excerpt: string;
+};
+
+export type SystemPromptSuggestion = {
+  model: string;
+  current_prompt: string;
+  suggested_prompt: string;
+  rationale: string;
+  failures: PromptFailure[];
+};
+
 export type CampUpdateMemoryPayload = {
   camp_id: string;
   memory: unknown;
 };
 
+export type JsonPatchOperation =
+  | { op: 'add' | 'replace' | 'test'; path: string; value: unknown }
+  | { op: 'remove'; path: string }
+  | { op: 'move' | 'copy'; from: string; path: string };
+
+export type MemoryPatch =
+  | { format: 'json_patch'; patch: JsonPatchOperation[] }
+  | { format: 'merge_patch'; patch: unknown };
+
+export type CampPatchMemoryPayload = MemoryPatch & {
+  camp_id: string;
+  expected_version?: string;
+};
+
+export type MemorySnapshot = {
+  memory: unknown;
+  version: string;
+};
+
+export type ScratchpadRememberPayload = {
+  camp_id: string;
+  key: string;
+  /** An empty note forgets the key. */
+  note: string;
+};
+
+export type ScratchpadRememberResult = {
+  key: string;
+  stored: boolean;
+  note_count: number;
+};
+
+export type BuiltinToolName = 'calculate' | 'current_datetime' | 'random_uuid';
+
+export type CampFetchUrlPayload = {
+  camp_id: string;
+  url: string;
+  /** Run the fetch is recorded under in the tool call log. */
+  run_id?: string;
+  max_bytes?: number;
+};
+
+export type FetchedPage = {
+  url: string;
+  final_url: string;
+  status: number;
+  content_type: string;
+  title: string | null;
+  markdown: string;
+  bytes: number;
+  truncated: boolean;
+};
+
+export type TableFormat = 'csv' | 'tsv' | 'xlsx';
+
+export type TableColumnType = 'integer' | 'number' | 'boolean' | 'date' | 'text' | 'empty';
+
+export type CampTablePayload = {
+  camp_id: string;
+  path: string;
+  /** Worksheet of an Excel file; the first sheet when omitted. */
+  sheet?: string;
+  max_rows?: number;
+};
+
+export type TablePreview = {
+  path: string;
+  format: TableFormat;
+  sheet: string | null;
+  sheet_names: string[];
+  columns: Array<{ name: string; inferred_type: TableColumnType }>;
+  rows: string[][];
+  /** Rows below the header. */
+  row_count: number;
+  truncated: boolean;
+};
+
+export type EmbeddingBackendSetting =
+  | { kind: 'ollama'; model: string }
+  /** `model` is a fastembed model code such as `Xenova/bge-small-en-v1.5`. */
+  | { kind: 'local'; model: string };
+
+export type LocalEmbeddingModel = {
+  code: string;
+  label: string;
+  dimensions: number;
+  note: string;
+  downloaded: boolean;
+  disk_bytes: number;
+};
+
+export type EmbeddingSettings = {
+  backend: EmbeddingBackendSetting;
+  /** False when the app was built without local embedding support. */
+  local_supported: boolean;
+  local_models: LocalEmbeddingModel[];
+};
+
+export type EmbeddingBenchmark = {
+  backend: EmbeddingBackendSetting;
+  dimensions: number;
+  load_ms: number;
+  embed_ms: number;
+  texts_per_second: number;
+  /** 0 to 1: how often a test question's own passage ranked first. */
+  top1_accuracy: number;
+  error: string | null;
+};
+
+export type EmbeddingFileState = 'fresh' | 'changed' | 'new' | 'removed';
+
+export type CampEmbeddingStatus = {
+  camp_id: string;
+  model: string;
+  /** Chunks the camp's context files make up now. */
+  total_chunks: number;
+  /** Chunks that still need (re-)embedding. */
+  stale_chunks: number;
+  files: Array<{ path: string; state: EmbeddingFileState; chunks: number }>;
+};
+
+export type EmbeddingRefreshReport = {
+  camp_id: string;
+  model: string;
+  embedded_files: number;
+  embedded_chunks: number;
+  unchanged_files: number;
+  removed_files: number;
+  /** Another refresh of the camp was already running. */
+  skipped: boolean;
+};
+
+export type UserDatabase = {
+  name: string;
+  /** Relative to the workspace folder. */
+  path: string;
+  created_at: number;
+  /** False when the file has been moved or deleted since it was registered. */
+  available: boolean;
+};
+
+export type RegisterUserDatabasePayload = {
+  name: string;
+  /** Absolute, or relative to the workspace folder. */
+  path: string;
+};
+
+export type QueryUserDatabasePayload = {
+  database: string;
+  sql: string;
+  max_rows?: number;
+};
+
+export type DatabaseQueryResult = {
+  database: string;
+  columns: string[];
+  rows: unknown[][];
+  row_count: number;
+  truncated: boolean;
+};
+
+export type RunBuiltinToolPayload = {
+  name: BuiltinToolName;
+  arguments: Record<string, unknown>;
+  /** Used by `current_datetime` when the model doesn't ask for an offset. */
+  local_utc_offset_minutes: number;
+  local_time_zone?: string;
+};
+
 export type CampSearchTranscriptPayload = {
   query: string;
   limit?: number;
@@ -299,6 +774,30 @@ export type CampAppendMessagePayload = {
   tool_calls?: CampToolCall[];
   included_artifact_ids?: string[];
   attachments?: CampMessageAttachment[];
+  reasoning?: string;
+};
+
+export type CampVerifyTranscriptPayload = {
+  camp_id: string;
+  repair?: boolean;
+};
+
+export type TranscriptIssueKind = 'malformed' | 'duplicate_id' | 'out_of_order' | 'orphaned_tool';
+
+export type TranscriptIssue = {
+  line: number;
+  kind: TranscriptIssueKind;
+  message_id?: string;
+  detail: string;
+  quarantined: boolean;
+};
+
+export type TranscriptVerifyReport = {
+  total_lines: number;
+  valid_messages: number;
+  issues: TranscriptIssue[];
+  repaired: boolean;
+  rejected_path?: string;
 };
 
 export type CampArtifactMetadata = {
@@ -312,6 +811,9 @@ export type CampArtifactMetadata = {
   updated_at: number;
   usage_count: number;
   archived: boolean;
+  source_path?: string;
+  always_include?: boolean;
+  include_order?: number;
 };
 
 export type CampArtifact = {
@@ -319,6 +821,23 @@ export type CampArtifact = {
   body: string;
 };
 
+export type UnreconciledArtifactFile = {
+  filename: string;
+  reason: string;
+};
+
+export type ArtifactIndexRebuildReport = {
+  /** Entries kept from the existing index. */
+  kept: number;
+  /** Markdown files that were missing from the index and have been added. */
+  recovered: string[];
+  /** Index entries dropped because their markdown file is gone. */
+  missing: string[];
+  unreconciled: UnreconciledArtifactFile[];
+  /** Set when the old index could not be parsed and was moved aside. */
+  corrupt_index_backup: string | null;
+};
+
 export type CampCreateArtifactFromMessagePayload = {
   camp_id: string;
   message_id: string;
@@ -326,6 +845,20 @@ export type CampCreateArtifactFromMessagePayload = {
   tags?: string[];
 };
 
+export type CampCreateArtifactPayload = {
+  camp_id: string;
+  title: string;
+  body: string;
+  tags?: string[];
+};
+
+export type CampCreateArtifactFromFilePayload = {
+  camp_id: string;
+  path: string;
+  title?: string;
+  tags?: string[];
+};
+
 export type CampUpdateArtifactPayload = {
   camp_id: string;
   artifact_id: string;
@@ -334,12 +867,325 @@ export type CampUpdateArtifactPayload = {
   tags?: string[];
 };
 
+export type CampSetArtifactAlwaysIncludePayload = {
+  camp_id: string;
+  artifact_id: string;
+  always_include: boolean;
+};
+
+export type CampReorderAlwaysIncludePayload = {
+  camp_id: string;
+  artifact_ids: string[];
+};
+
 export type CampToggleArtifactArchivePayload = {
   camp_id: string;
   artifact_id: string;
   archived: boolean;
 };
 
+export type CampDeleteArtifactPayload = {
+  camp_id: string;
+  artifact_id: string;
+};
+
+export type CampTrashKind = 'artifact' | 'context_file' | 'sync_conflict';
+
+export type CampTrashEntry = {
+  id: string;
+  kind: CampTrashKind;
+  original_path: string;
+  trashed_filename: string;
+  artifact?: CampArtifactMetadata;
+  trashed_at: number;
+};
+
+export type CampRestoreTrashEntryPayload = {
+  camp_id: string;
+  entry_id: string;
+};
+
+export type CampCheckpoint = {
+  id: string;
+  name: string;
+  message_count: number;
+  last_message_id?: string;
+  memory_filename: string;
+  created_at: number;
+};
+
+export type CampCreateCheckpointPayload = {
+  camp_id: string;
+  name: string;
+};
+
+export type CampRollbackToCheckpointPayload = {
+  camp_id: string;
+  checkpoint_id: string;
+};
+
+export type CampRollbackResult = {
+  checkpoint: CampCheckpoint;
+  archived_message_count: number;
+  archive_filename?: string;
+  removed_checkpoint_ids: string[];
+};
+
+export type CampUndoExchangeResult = {
+  removed_messages: CampMessage[];
+  rolled_back_artifact_ids: string[];
+  removed_checkpoint_ids: string[];
+};
+
+export type CampBookmark = {
+  message_id: string;
+  note?: string;
+  exclude_from_compaction: boolean;
+  created_at: number;
+};
+
+export type PinnedMessage = {
+  bookmark: CampBookmark;
+  position: number;
+  role: CampMessage['role'];
+  snippet: string;
+  message_created_at: number;
+};
+
+export type CampPinMessagePayload = {
+  camp_id: string;
+  message_id: string;
+  note?: string;
+  exclude_from_compaction?: boolean;
+};
+
+export type CampUnpinMessagePayload = {
+  camp_id: string;
+  message_id: string;
+};
+
+export type CampSuggestTitlePayload = {
+  camp_id: string;
+  model?: string;
+  apply?: boolean;
+};
+
+export type CampTitleSuggestion = {
+  title: string;
+  model: string;
+  applied: boolean;
+};
+
+export type PromptSuggestions = {
+  camp_id: string;
+  prompts: string[];
+  model: string;
+  /** Reused because the transcript hasn't changed since they were made. */
+  cached: boolean;
+};
+
+export type CampTranslateMessagesPayload = {
+  camp_id: string;
+  target_language: string;
+  message_ids?: string[];
+  model?: string;
+};
+
+export type TranslatedMessage = {
+  message_id: string;
+  language: string;
+  text: string;
+};
+
+export type TtsEngine =
+  | { kind: 'piper'; binary: string; model_path: string }
+  | { kind: 'coqui'; binary: string; model_name?: string | null }
+  | { kind: 'http'; base_url: string; model: string; voice: string };
+
+export type SpeechSource = { kind: 'artifact'; id: string } | { kind: 'message'; id: string };
+
+export type SynthesizeSpeechPayload = {
+  camp_id: string;
+  source: SpeechSource;
+};
+
+export type CampAudioClip = {
+  id: string;
+  filename: string;
+  source: SpeechSource;
+  text_sha256: string;
+  text_excerpt: string;
+  engine: string;
+  bytes: number;
+  created_at: number;
+};
+
+export type SynthesizedSpeech = CampAudioClip & {
+  path: string;
+  reused: boolean;
+};
+
+export type MessageVariant = {
+  id: string;
+  messages: CampMessage[];
+  model?: string;
+  created_at: number;
+};
+
+export type VariantGroup = {
+  user_message_id: string;
+  variants: MessageVariant[];
+  selected_variant_id: string;
+};
+
+export type SelectVariantPayload = {
+  camp_id: string;
+  user_message_id: string;
+  variant_id: string;
+};
+
+export type WorkspaceSearchHitKind = 'camp_name' | 'system_prompt' | 'transcript' | 'artifact' | 'context_file';
+
+export type SearchWorkspacePayload = {
+  query: string;
+  limit?: number;
+  kinds?: WorkspaceSearchHitKind[];
+};
+
+export type WorkspaceSearchHit = {
+  camp_id: string;
+  kind: WorkspaceSearchHitKind;
+  path: string;
+  title: string;
+  snippet: string;
+  score: number;
+};
+
+export type HybridSearchPayload = SearchWorkspacePayload & {
+  /** Limits results to one camp, for context retrieval. */
+  camp_id?: string;
+};
+
+/** One ranker's view of a hybrid hit; `contribution` is its share of the fused score. */
+export type SearchSourceScore = {
+  rank: number;
+  score: number;
+  contribution: number;
+};
+
+export type HybridSearchHit = WorkspaceSearchHit & {
+  /** BM25 side; `score` is flipped so higher is better. */
+  lexical: SearchSourceScore | null;
+  /** Embedding side; `score` is cosine similarity. */
+  vector: SearchSourceScore | null;
+};
+
+export type HybridSearchResults = {
+  hits: HybridSearchHit[];
+  embedding_model: string | null;
+  /** Set when the embedding backend failed and results are BM25 only. */
+  vector_error: string | null;
+};
+
+export type ContextPackFormat = 'markdown' | 'json';
+
+export type CampExportContextPackPayload = {
+  camp_id: string;
+  format?: ContextPackFormat;
+  /** Defaults to every artifact that is not archived. */
+  artifact_ids?: string[];
+};
+
+export type ContextPackExport = {
+  format: ContextPackFormat;
+  content: string;
+  estimated_tokens: number;
+  sections: { title: string; estimated_tokens: number }[];
+};
+
+export type CampShareExportPayload = {
+  camp_id: string;
+  /** Keep tool calls and their results. Defaults to false. */
+  include_tool_calls?: boolean;
+  /** Mask emails, phone numbers and API keys. Defaults to true. */
+  mask_sensitive?: boolean;
+  /** Extra regular expressions replaced with `[REDACTED]`. */
+  redact_patterns?: string[];
+  /** Defaults to the artifacts included in the conversation. */
+  artifact_ids?: string[];
+};
+
+export type ShareExportResult = {
+  path: string;
+  message_count: number;
+  artifact_count: number;
+  redactions: ScrubAudit;
+};
+
+export type ActivityDay = {
+  /** Local date, YYYY-MM-DD. */
+  date: string;
+  message_count: number;
+  messages_by_role: Record<string, number>;
+  run_count: number;
+  prompt_tokens: number;
+  completion_tokens: number;
+  total_tokens: number;
+};
+
+export type CampActivityTimeline = {
+  camp_id: string;
+  /** Oldest first, ending today; days without activity are included. */
+  days: ActivityDay[];
+  busiest_day_message_count: number;
+};
+
+export type TranscriptSearchHit = {
+  message_id: string;
+  role: string;
+  created_at: number;
+  /** Matches are wrapped in `[` `]`. */
+  snippet: string;
+  match_count: number;
+};
+
+export type ContextItemKind = 'system_prompt' | 'memory' | 'artifact' | 'context_file' | 'transcript';
+
+export type ContextItemStatus = 'included' | 'summarized' | 'dropped';
+
+export type CampAssembleContextPayload = {
+  camp_id: string;
+  model?: string;
+  artifact_ids?: string[];
+  context_paths?: string[];
+  context_length?: number;
+  reserved_output_tokens?: number;
+};
+
+export type ContextAssemblyItem = {
+  kind: ContextItemKind;
+  id: string;
+  label: string;
+  priority: number;
+  estimated_tokens: number;
+  included_tokens: number;
+  status: ContextItemStatus;
+};
+
+export type ContextAssemblyReport = {
+  context_length: number;
+  reserved_output_tokens: number;
+  budget_tokens: number;
+  used_tokens: number;
+  items: ContextAssemblyItem[];
+  unavailable_context_paths: string[];
+};
+
+export type ContextAssembly = {
+  messages: Array<Record<string, unknown>>;
+  report: ContextAssemblyReport;
+};
+
 // ── Agent Run State ──────────────────────────────────────────────
 
 export type RunEventKind =
@@ -351,7 +1197,13 @@ export type RunEventKind =
   | 'tool_result'
   | 'run_completed'
   | 'run_cancelled'
-  | 'run_failed';
+  | 'run_failed'
+  | 'loop_aborted';
+
+/** Why a tool loop was stopped early; stored as `result_json` on a `loop_aborted` event. */
+export type ToolLoopAbort =
+  | { reason: 'repeated_tool_call'; tool_name: string; count: number }
+  | { reason: 'token_ceiling'; tokens_used: number; limit: number };
 
 export type RunStateEvent = {
   run_id: string;
@@ -369,6 +1221,10 @@ export type RunStartConfig = {
   max_iterations?: number;
   tool_timeout_secs?: number;
   approval_policy?: ApprovalPolicy;
+  /** Times one tool may be called with the same arguments in a turn. Defaults to 3. */
+  max_repeated_tool_calls?: number;
+  /** Tokens a turn may spend across its requests. Defaults to 200,000. */
+  max_turn_tokens?: number;
 };
 
 export type RunStartResult = {
@@ -386,6 +1242,7 @@ export type TeamAgentConfig = {
   model: string;
   tool_subset: string[];
   description: string;
+  continue_context: boolean;
 };
 
 export type TeamAgentCreateInput = {
@@ -394,12 +1251,52 @@ export type TeamAgentCreateInput = {
   model: string;
   tool_subset: string[];
   description?: string;
+  /** Start each step from the agent's earlier steps in the same plan. */
+  continue_context?: boolean;
+};
+
+export type AgentTranscriptEntry = {
+  timestamp: string;
+  plan_id?: string | null;
+  step_id: string;
+  message: unknown;
+};
+
+export type TeamTemplate = {
+  id: string;
+  name: string;
+  description: string;
+  supervisor_guidance: string;
+  agents: Array<{
+    id: string;
+    role: string;
+    description: string;
+    tool_subset: string[];
+    instructions: string;
+  }>;
+  reflection_loops: boolean;
+  critique_rubric: CritiqueRubric;
 };
 
 export type TeamSettingsUpdateInput = {
   supervisor_model: string;
   reflection_loops: boolean;
   max_reflection_rounds: number;
+  /** Copy plan summaries, step results and promotions into the camp transcript. Omitted keeps the current setting. */
+  mirror_to_transcript?: boolean;
+  /** Replaces the critique rubric when present. */
+  critique_rubric?: CritiqueRubric;
+};
+
+export type RubricCriterion = {
+  name: string;
+  description: string;
+};
+
+export type CritiqueRubric = {
+  criteria: RubricCriterion[];
+  /** Score (1-5) every criterion must reach for a draft to pass. */
+  pass_threshold: number;
 };
 
 export type DelegationStep = {
@@ -410,10 +1307,19 @@ export type DelegationStep = {
   expected_output: string;
 };
 
+export type ClarificationRequest = {
+  request_id: string;
+  from: string;
+  step_id?: string | null;
+  question: string;
+};
+
 export type DecompositionPlan = {
   task_summary: string;
   steps: DelegationStep[];
   reflection_required: boolean;
+  /** Set when the supervisor paused planning to ask the user something. */
+  clarification?: ClarificationRequest | null;
 };
 
 export type BusTokenUsage = {
@@ -427,7 +1333,9 @@ export type TeamBusEntryType =
   | 'result'
   | 'critique'
   | 'promotion'
-  | 'error';
+  | 'error'
+  | 'clarification_request'
+  | 'clarification_answer';
 
 export type TeamBusEntry = {
   id: string;
@@ -447,8 +1355,16 @@ export type AgentStepResult = {
   draft_path: string;
   context_writes: string[];
   token_usage: BusTokenUsage;
+  /** Set when the agent paused the step to ask the user something. */
+  clarification?: ClarificationRequest | null;
+  /** Set when the agent's tool loop was stopped for repeating itself or overspending. */
+  loop_aborted?: ToolLoopAbort | null;
 };
 
+export type ClarificationResolution =
+  | { kind: 'plan'; plan: DecompositionPlan }
+  | { kind: 'step'; result: AgentStepResult };
+
 export type ReflectionSummary = {
   artifact_path: string;
   promoted_path: string;
@@ -458,9 +1374,18 @@ export type ReflectionSummary = {
     issues: string[];
     suggestions: string[];
     pass: boolean;
+    scores: Array<{ criterion: string; score: number; comment: string }>;
   }>;
 };
 
+export type DraftReflectionItem = {
+  artifact_path: string;
+  rounds_completed: number;
+  pass: boolean;
+  critiques: ReflectionSummary['critiques'];
+  error?: string | null;
+};
+
 export type TeamStepStatus = {
   step_id: string;
   assigned_to: string;
@@ -488,8 +1413,387 @@ export type TeamStatus = {
   supervisor_model: string;
   reflection_loops: boolean;
   max_reflection_rounds: number;
+  mirror_to_transcript: boolean;
+  critique_rubric: CritiqueRubric;
   agents: TeamAgentStatus[];
   steps: TeamStepStatus[];
   bus_entries: number;
   artifacts: TeamArtifactsStatus;
+  pending_clarifications: ClarificationRequest[];
+};
+
+/** One bus segment; every new team task starts its own run. */
+export type TeamRunSummary = {
+  run_id: string;
+  active: boolean;
+  archived: boolean;
+  size_bytes: number;
+};
+
+export type TeamRunAgentReport = {
+  id: string;
+  model?: string | null;
+  token_usage: BusTokenUsage;
+  estimated_cost: number | null;
+};
+
+export type TeamRunStepReport = {
+  step_id: string;
+  assigned_to: string;
+  token_usage: BusTokenUsage;
+  estimated_cost: number | null;
+  started_at?: string | null;
+  finished_at?: string | null;
+  duration_ms?: number | null;
+};
+
+export type TeamRunReport = {
+  plan_id?: string | null;
+  task_summary: string;
+  agents: TeamRunAgentReport[];
+  steps: TeamRunStepReport[];
+  total_usage: BusTokenUsage;
+  estimated_cost: number | null;
+  duration_ms?: number | null;
+};
+
+export type StartupIssueKind =
+  | 'missing_camp_config'
+  | 'unreadable_camp_config'
+  | 'missing_camp_file'
+  | 'stale_temp_file'
+  | 'corrupt_inspect_file'
+  | 'interrupted_inspect_turn';
+
+export type StartupIssue = {
+  kind: StartupIssueKind;
+  camp_id: string;
+  path: string;
+  message: string;
+  repaired: boolean;
+};
+
+export type StartupDiagnostics = {
+  scanned_at: number;
+  workspace_path?: string;
+  camps_scanned: number;
+  issues: StartupIssue[];
+  error?: string;
+};
+
+export type MigrationStatus = {
+  version: number;
+  name: string;
+  applied_at: number | null;
+};
+
+export type DbEncryptionStatus = {
+  supported: boolean;
+  enabled: boolean;
+};
+
+export type PrivacySettings = {
+  do_not_log_content: boolean;
+  scrub_patterns: string[];
+};
+
+export type OutboundScrubSettings = {
+  enabled: boolean;
+  mask_emails: boolean;
+  mask_phone_numbers: boolean;
+  mask_api_keys: boolean;
+  custom_patterns: string[];
+};
+
+export type ScrubCategory = 'email' | 'phone_number' | 'api_key' | 'custom';
+
+/** Counts of masked matches; the masked values are never recorded. */
+export type ScrubAudit = {
+  total_masked: number;
+  entries: Array<{
+    category: ScrubCategory;
+    pattern?: string;
+    count: number;
+  }>;
+};
+
+export type McpTransport =
+  | { type: 'stdio'; command: string; args: string[] }
+  | { type: 'sse'; url: string };
+
+export type McpServerConfig = {
+  id: string;
+  name: string;
+  transport: McpTransport;
+  enabled: boolean;
+  /** Write-only; an empty string clears the stored token on update. */
+  auth_token?: string | null;
+};
+
+export type McpServerStatus = {
+  id: string;
+  name: string;
+  transport: McpTransport;
+  enabled: boolean;
+  connected: boolean;
+};
+
+export type McpToolDef = {
+  server_id: string;
+  name: string;
+  qualified_name: string;
+  description: string;
+  input_schema: Record<string, unknown>;
+  read_only: boolean;
+};
+
+export type McpCatalogEntry = {
+  id: string;
+  name: string;
+  description: string;
+  homepage?: string;
+  transport: McpTransport;
+  argument_hint?: string;
+};
+
+export type McpInstallFromCatalogPayload = {
+  entry_id: string;
+  extra_args?: string[];
+  auth_token?: string | null;
+};
+
+export type McpCatalogInstallResult = {
+  server: McpServerConfig;
+  tools: McpToolDef[];
+};
+
+export type McpSamplingPolicy = 'deny' | 'ask' | 'allow';
+
+export type McpSamplingRequest = {
+  request_id: string;
+  server_id: string;
+  camp_id: string | null;
+  model: string;
+  system_prompt: string | null;
+  messages: Array<{ role: 'user' | 'assistant'; content: { type: string; text?: string } }>;
+  max_tokens: number;
+};
+
+export type McpOAuthStatus = {
+  server_id: string;
+  authorized: boolean;
+  expires_at: number | null;
+};
+
+export type QuickCaptureTarget = 'message' | 'note';
+
+export type QuickCaptureSettings = {
+  camp_id: string | null;
+  shortcut: string | null;
+  target: QuickCaptureTarget;
+};
+
+export type QuickCaptureResult = {
+  camp_id: string;
+  target: QuickCaptureTarget;
+  id: string;
+};
+
+export type TraySettings = {
+  keep_running_in_tray: boolean;
+};
+
+export type TrayAction = { action: 'new_camp' } | { action: 'inspect_mode'; enabled: boolean };
+
+export type Percentiles = {
+  p50: number;
+  p90: number;
+  p99: number;
+};
+
+export type LatencyBucket = {
+  /** `null` for the open-ended last bucket. */
+  upper_ms: number | null;
+  count: number;
+};
+
+export type ModelPerformanceStats = {
+  provider_kind: ProviderKind;
+  model_id: string;
+  is_local: boolean;
+  request_count: number;
+  error_count: number;
+  latency_ms?: Percentiles;
+  ttfb_ms?: Percentiles;
+  tokens_per_second?: Percentiles;
+  latency_histogram: LatencyBucket[];
+};
+
+export type SyncConflictKind = 'conflicted_copy' | 'stale_config';
+
+export type SyncConflict = {
+  camp_id: string;
+  camp_name: string;
+  kind: SyncConflictKind;
+  /** Relative to the camp folder. */
+  path: string;
+  original_path?: string;
+  detail: string;
+  mergeable: boolean;
+};
+
+export type SyncMergeReport = {
+  original_path: string;
+  merged_count: number;
+  added_count: number;
+  duplicate_count: number;
+  /** `false` for a preview. */
+  applied: boolean;
+  trash_entry_id?: string;
+};
+
+export type SyncTarget =
+  | { kind: 'folder'; path: string }
+  | { kind: 'webdav'; url: string; username: string }
+  | { kind: 's3'; endpoint: string; bucket: string; region: string; access_key_id: string; prefix: string };
+
+export type SyncSettings = {
+  /** `null` turns sync off. */
+  target: SyncTarget | null;
+  /** Names this machine in conflicted copies. */
+  device_name: string;
+  last_synced_at?: number | null;
+};
+
+export type SyncReport = {
+  uploaded: number;
+  downloaded: number;
+  deleted_local: number;
+  deleted_remote: number;
+  /** Conflicted copies written, relative to the camps folder. */
+  conflicts: string[];
+  /** Files edited during the run; compared again on the next sync. */
+  skipped: string[];
+  finished_at: number;
+};
+
+export type WorkspaceManifestResult = {
+  /** Absolute path of the written INDEX.md. */
+  path: string;
+  camp_count: number;
+};
+
+export type OrphanedArtifactFile = {
+  /** Camp folder name. */
+  camp_id: string;
+  filename: string;
+};
+
+export type WorkspaceGcReport = {
+  /** Whether the orphans below were removed or only found. */
+  cleaned: boolean;
+  /** Workspace context files (relative to `context/`) no camp has attached. */
+  unreferenced_context_files: string[];
+  /** The picked unreferenced context files, moved to the workspace trash. */
+  trashed_context_files: string[];
+  orphaned_artifact_files: OrphanedArtifactFile[];
+  /** Ids of tool call rows whose run no longer exists. */
+  orphaned_tool_calls: string[];
+  /** Camp folders that lost their camp.json but still hold inspect bundles. */
+  stale_inspect_dirs: string[];
+};
+
+export type DigestSettings = {
+  enabled: boolean;
+  camp_id: string | null;
+  /** Model reference; `null` uses the default model. */
+  model: string | null;
+  /** Local hour (0-23) after which yesterday's digest is written. */
+  hour: number;
+  /** Minutes east of UTC. */
+  utc_offset_minutes: number;
+};
+
+export type DigestStatus = {
+  last_day?: number | null;
+  last_run_at?: number | null;
+  last_artifact_id?: string | null;
+  last_error?: string | null;
+};
+
+export type DigestResult = {
+  day: string;
+  camp_id: string;
+  /** `null` when there was no activity to summarize. */
+  artifact_id: string | null;
+  camps: number;
+};
+
+export type CrashKind = 'panic' | 'command_error';
+
+export type CrashReportSummary = {
+  id: string;
+  kind: CrashKind;
+  summary: string;
+  command?: string;
+  created_at: number;
+};
+
+export type DeepLinkTarget = { kind: 'camp'; id: string } | { kind: 'run'; id: string };
+
+export type UpdateChannel = 'stable' | 'beta';
+
+export type UpdateCheck = {
+  channel: UpdateChannel;
+  current_version: string;
+  available: boolean;
+  version?: string;
+  published_at?: number;
+  release_notes?: string;
+  current_camp_schema_version: string;
+  camp_schema_version?: string;
+};
+
+export type InitStage =
+  | 'pending'
+  | 'opening_database'
+  | 'migrating'
+  | 'checking_integrity'
+  | 'scanning_workspace'
+  | 'ready'
+  | 'failed';
+
+export type InitStatus = {
+  stage: InitStage;
+  message: string;
+  step: number;
+  total_steps: number;
+  error?: string;
+  started_at: number;
+  finished_at?: number;
+};
+
+export type BasecampErrorKind =
+  | 'validation'
+  | 'not_found'
+  | 'unavailable'
+  | 'io'
+  | 'database'
+  | 'provider'
+  | 'mcp'
+  | 'read_only'
+  | 'context_overflow'
+  | 'local_only'
+  | 'conflict'
+  | 'internal';
+
+export type ContextOverflowSuggestion = 'compact_transcript' | 'drop_artifacts' | 'reduce_max_tokens';
+
+export type BasecampError = {
+  kind: BasecampErrorKind;
+  message: string;
+  retryable: boolean;
+  context?: Record<string, unknown>;
+  status: number | null;
+  response_payload: unknown;
 };
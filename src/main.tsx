@@ -3,6 +3,7 @@ import { createRoot } from 'react-dom/client';
 import { HashRouter } from 'react-router-dom';
 
 import App from './App';
+import { QuickCaptureView } from './views/QuickCaptureView';
 import './index.css';
 
 // @ts-expect-error - Mocking Tauri for web debugging
@@ -11,6 +12,7 @@ if (!window.__TAURI__) {
   window.__TAURI__ = {
     invoke: async (cmd: string, args: any) => {
       console.log(`Mock invoked: ${cmd}`, args);
+      if (cmd === 'get_init_status') return { stage: 'ready', message: 'Ready.', step: 4, total_steps: 4, started_at: Date.now() };
       if (cmd === 'camp_list') return [];
       if (cmd === 'db_list_models') return [];
       if (cmd === 'providers_list' || cmd === 'provider_health_check') return [];
@@ -51,10 +53,17 @@ if (!window.__TAURI__) {
   };
 }
 
+// The quick capture window loads the same bundle but only needs its input.
+const isQuickCaptureWindow = window.location.hash.startsWith('#/quick-capture');
+
 createRoot(document.getElementById('root')!).render(
   <StrictMode>
-    <HashRouter>
-      <App />
-    </HashRouter>
+    {isQuickCaptureWindow ? (
+      <QuickCaptureView />
+    ) : (
+      <HashRouter>
+        <App />
+      </HashRouter>
+    )}
   </StrictMode>,
 );
@@ -5,16 +5,23 @@ import { CampCard } from '../components/home/CampCard';
 import { Field } from '../components/ui/Field';
 import {
   campCreate,
+  campCreateFromTemplate,
   campList,
   campLoad,
   campDelete,
+  campSetFavorite,
+  campSetProject,
   dbListModels,
   ensureDefaultWorkspace,
   getDefaultModel,
+  listCampTemplates,
+  projectCreate,
+  projectList,
   providersList,
 } from '../lib/db';
-import { syncModelsToDb } from '../lib/models';
-import type { Camp, CampSummary, ModelRow } from '../lib/types';
+import { commandErrorMessage } from '../lib/errors';
+import { modelNoteSummary, syncModelsToDb } from '../lib/models';
+import type { Camp, CampListSort, CampProject, CampSummary, CampTemplate, ModelRow } from '../lib/types';
 import './HomeView.css';
 
 const FALLBACK_MODEL = 'openrouter/auto';
@@ -65,8 +72,9 @@ function campPreview(camp: Camp): string {
 function modelDisplayLabel(model: ModelRow): string {
   const ctx = model.context_length ? ` · ${(model.context_length / 1000).toFixed(0)}k ctx` : '';
   const name = model.name?.trim() ? model.name : model.id;
+  const verdict = modelNoteSummary(model.note);
   // Previously this was `[${model.provider_kind}] ${name}`
-  return `${name}${ctx}`;
+  return `${name}${ctx}${verdict ? ` · ${verdict}` : ''}`;
 }
 
 export function HomeView() {
@@ -82,7 +90,12 @@ export function HomeView() {
   const [newCampName, setNewCampName] = useState('New Camp');
   const [newCampProviderFilter, setNewCampProviderFilter] = useState('all');
   const [newCampModel, setNewCampModel] = useState(FALLBACK_MODEL);
+  const [campTemplates, setCampTemplates] = useState<CampTemplate[]>([]);
+  const [newCampTemplateId, setNewCampTemplateId] = useState('');
   const [campQuery, setCampQuery] = useState('');
+  const [projects, setProjects] = useState<CampProject[]>([]);
+  const [projectFilter, setProjectFilter] = useState('all');
+  const [campSort, setCampSort] = useState<CampListSort>('recent');
 
   const [isBooting, setIsBooting] = useState(true);
   const [isCreatingCamp, setIsCreatingCamp] = useState(false);
@@ -118,12 +131,20 @@ export function HomeView() {
   const visibleCamps = useMemo(() => {
     const normalizedQuery = campQuery.trim().toLowerCase();
 
-    const sorted = [...camps].sort((left, right) => right.updated_at - left.updated_at);
+    const knownProjectIds = new Set(projects.map((project) => project.id));
+    // camp_list already returns camps in `campSort` order.
+    const inProject = camps.filter((camp) => {
+      if (projectFilter === 'all') {
+        return true;
+      }
+      const projectId = camp.project_id && knownProjectIds.has(camp.project_id) ? camp.project_id : 'none';
+      return projectId === projectFilter;
+    });
     if (!normalizedQuery) {
-      return sorted;
+      return inProject;
     }
 
-    return sorted.filter((camp) => {
+    return inProject.filter((camp) => {
       const preview = campMetaById[camp.id]?.promptPreview ?? '';
       return (
         camp.name.toLowerCase().includes(normalizedQuery) ||
@@ -131,7 +152,7 @@ export function HomeView() {
         preview.toLowerCase().includes(normalizedQuery)
       );
     });
-  }, [campMetaById, campQuery, camps]);
+  }, [campMetaById, campQuery, camps, projectFilter, projects]);
 
   const resetFeedback = () => {
     setError(null);
@@ -157,8 +178,12 @@ export function HomeView() {
     setNewCampModel(normalized);
   }, []);
 
+  const loadProjects = useCallback(async () => {
+    setProjects(await projectList());
+  }, []);
+
   const loadCamps = useCallback(async () => {
-    const rows = await campList();
+    const rows = await campList(campSort);
     setCamps(rows);
 
     const metadataEntries = await Promise.all(
@@ -173,7 +198,7 @@ export function HomeView() {
     );
 
     setCampMetaById(Object.fromEntries(metadataEntries));
-  }, []);
+  }, [campSort]);
 
   useEffect(() => {
     const boot = async () => {
@@ -184,7 +209,7 @@ export function HomeView() {
         const defaultWorkspacePath = await ensureDefaultWorkspace();
         setWorkspacePath(defaultWorkspacePath);
 
-        await Promise.all([loadModels(), loadDefaultModel(), loadCamps(), loadProviders()]);
+        await Promise.all([loadModels(), loadDefaultModel(), loadProjects(), loadProviders()]);
       } catch (bootError) {
         setError(bootError instanceof Error ? bootError.message : 'Unable to load home view.');
       } finally {
@@ -193,7 +218,24 @@ export function HomeView() {
     };
 
     void boot();
-  }, [loadCamps, loadDefaultModel, loadModels, loadProviders]);
+  }, [loadDefaultModel, loadModels, loadProjects, loadProviders]);
+
+  // Separate from boot so changing the sort only reloads the camp list.
+  useEffect(() => {
+    if (!workspacePath) {
+      return;
+    }
+    void loadCamps().catch((loadError) => {
+      setError(commandErrorMessage(loadError, 'Unable to load camps.'));
+    });
+  }, [loadCamps, workspacePath]);
+
+  useEffect(() => {
+    // Templates are optional; a blank camp is still available if they fail to load.
+    void listCampTemplates()
+      .then(setCampTemplates)
+      .catch(() => setCampTemplates([]));
+  }, []);
 
   const handleCreateCamp = async () => {
     if (!workspacePath) {
@@ -205,18 +247,25 @@ export function HomeView() {
     resetFeedback();
 
     try {
-      const created = await campCreate({
-        name: newCampName.trim() || 'New Camp',
-        model: newCampModel.trim() || defaultModel,
-        system_prompt: 'You are Basecamp, an expert AI assistant. You have access to tools that allow you to read, write, and manage files in the user\'s workspace. You can generate rich multimodal artifacts like PDFs, images, and HTML. When asked to create a file, image, or PDF, you MUST use the `write_file` or `create_artifact` tools to generate it. For binary formats like images or PDFs, always use the `base64` encoding parameter. Do NOT refuse to create files, and do NOT output raw base64 or binary data into the chat. Always use the provided tools.',
-        tools_enabled: true,
-      });
+      const model = newCampModel.trim() || defaultModel;
+      const created = newCampTemplateId
+        ? await campCreateFromTemplate({
+            template_id: newCampTemplateId,
+            model,
+            name: newCampName.trim() && newCampName.trim() !== 'New Camp' ? newCampName.trim() : undefined,
+          })
+        : await campCreate({
+            name: newCampName.trim() || 'New Camp',
+            model,
+            system_prompt: 'You are Basecamp, an expert AI assistant. You have access to tools that allow you to read, write, and manage files in the user\'s workspace. You can generate rich multimodal artifacts like PDFs, images, and HTML. When asked to create a file, image, or PDF, you MUST use the `write_file` or `create_artifact` tools to generate it. For binary formats like images or PDFs, always use the `base64` encoding parameter. Do NOT refuse to create files, and do NOT output raw base64 or binary data into the chat. Always use the provided tools.',
+            tools_enabled: true,
+          });
 
       setNewCampName('New Camp');
       await loadCamps();
       navigate(`/camp/${created.config.id}`);
     } catch (createError) {
-      setError(createError instanceof Error ? createError.message : 'Unable to create camp.');
+      setError(commandErrorMessage(createError, 'Unable to create camp.'));
     } finally {
       setIsCreatingCamp(false);
     }
@@ -237,6 +286,43 @@ export function HomeView() {
     }
   };
 
+  const handleCreateProject = async () => {
+    const name = window.prompt('Project name')?.trim();
+    if (!name) {
+      return;
+    }
+
+    resetFeedback();
+    try {
+      const project = await projectCreate(name);
+      await loadProjects();
+      setProjectFilter(project.id);
+      setStatus(`Created project "${project.name}".`);
+    } catch (projectError) {
+      setError(commandErrorMessage(projectError, 'Unable to create project.'));
+    }
+  };
+
+  const handleToggleFavorite = async (camp: CampSummary) => {
+    resetFeedback();
+    try {
+      await campSetFavorite(camp.id, !camp.favorite);
+      await loadCamps();
+    } catch (favoriteError) {
+      setError(commandErrorMessage(favoriteError, 'Unable to update favorite.'));
+    }
+  };
+
+  const handleMoveCamp = async (campId: string, projectId: string | null) => {
+    resetFeedback();
+    try {
+      await campSetProject(campId, projectId);
+      await loadCamps();
+    } catch (moveError) {
+      setError(commandErrorMessage(moveError, 'Unable to move camp.'));
+    }
+  };
+
   const handleRefreshModels = async () => {
     setIsRefreshingModels(true);
     resetFeedback();
@@ -306,6 +392,17 @@ export function HomeView() {
           </select>
         </Field>
 
+        <Field label="Template">
+          <select value={newCampTemplateId} onChange={(event) => setNewCampTemplateId(event.target.value)}>
+            <option value="">Blank camp</option>
+            {campTemplates.map((template) => (
+              <option key={template.id} value={template.id} title={template.description}>
+                {template.emoji ? `${template.emoji} ${template.name}` : template.name}
+              </option>
+            ))}
+          </select>
+        </Field>
+
         <button type="button" className="primary-action" onClick={handleCreateCamp} disabled={isCreatingCamp || isBooting}>
           {isCreatingCamp ? 'INITIALIZING CAMP...' : 'ESTABLISH BASECAMP'}
         </button>
@@ -316,6 +413,28 @@ export function HomeView() {
           <h2>Camps</h2>
           <div>
             <span>{visibleCamps.length} shown</span>
+            <Field label="Sort">
+              <select value={campSort} onChange={(event) => setCampSort(event.target.value as CampListSort)}>
+                <option value="recent">Recently opened</option>
+                <option value="updated">Recently updated</option>
+                <option value="favorites_first">Favorites first</option>
+                <option value="alphabetical">A–Z</option>
+              </select>
+            </Field>
+            <Field label="Project">
+              <select value={projectFilter} onChange={(event) => setProjectFilter(event.target.value)}>
+                <option value="all">All projects</option>
+                {projects.map((project) => (
+                  <option key={project.id} value={project.id}>
+                    {project.name}
+                  </option>
+                ))}
+                <option value="none">No project</option>
+              </select>
+            </Field>
+            <button type="button" onClick={() => void handleCreateProject()} disabled={isBooting}>
+              New Project
+            </button>
             <Field label="Search">
               <input
                 value={campQuery}
@@ -335,9 +454,12 @@ export function HomeView() {
               promptPreview={campMetaById[camp.id]?.promptPreview ?? 'No context yet.'}
               onOpen={() => navigate(`/camp/${camp.id}`)}
               onDelete={() => void handleDeleteCamp(camp.id, camp.name)}
+              onToggleFavorite={() => void handleToggleFavorite(camp)}
+              projects={projects}
+              onProjectChange={(projectId) => void handleMoveCamp(camp.id, projectId)}
             />
           ))}
-          {visibleCamps.length === 0 ? <p className="empty-state">No camps match this search.</p> : null}
+          {visibleCamps.length === 0 ? <p className="empty-state">No camps match these filters.</p> : null}
         </div>
       </section>
     </div>
@@ -17,29 +17,57 @@ import { useArtifactComposerState } from '../hooks/useArtifactComposerState';
 import {
   campAppendMessage,
   campCreate,
+  campMarkOpened,
   campCreateArtifactFromMessage,
+  campRefreshEmbeddingIndex,
+  campExportContextPack,
+  campFetchUrl,
+  campPreviewTable,
+  campTableToMarkdown,
+  campShareExport,
   campGetArtifact,
+  campListAlwaysIncludeArtifacts,
+  campLoadDefaultContextFiles,
   campIncrementArtifactUsage,
   campListContextFiles,
+  campPreviewContextFileWrite,
   campList,
   campListArtifacts,
   campLoad,
   campReadContextFile,
+  campRebuildArtifactIndex,
   campReadContextFileBase64,
+  campScratchpadRecall,
+  campScratchpadRemember,
   campSearchTranscript,
+  campSuggestTitle,
   campUpdateConfig,
   campUpdateArtifact,
   campUpdateMemory,
+  campUndoLastExchange,
+  campSetDefaultContextPaths,
+  campSetFetchAllowedDomains,
   campUpdateSystemPrompt,
   campWriteContextFile,
   campWriteContextFileBytes,
   dbListModels,
+  embeddingIndexStatus,
   ensureDefaultWorkspace,
+  getRunForMessage,
+  getTitleModel,
+  hybridSearch,
+  mcpResolveSamplingRequest,
+  mcpSetActiveCamp,
   pickWorkspaceFolder,
   providersList,
+  queryUserDatabase,
+  recommendModel,
+  runBuiltinTool,
   setWorkspacePath,
+  suggestPrompts,
 } from '../lib/db';
 import { runCampChatRuntime } from '../lib/campChatRuntime';
+import { describeSamplingRequest, listenMcpSamplingRequests } from '../lib/mcpSampling';
 import {
   getDeveloperInspectMode,
   inspectEmitEvent,
@@ -52,11 +80,13 @@ import {
   type InspectEmitEventPayload,
   type InspectEventRecord,
 } from '../lib/inspect';
-import { syncModelsToDb } from '../lib/models';
-import { OpenRouterRequestError, type OpenRouterToolCall } from '../lib/openrouter';
+import { modelNoteSummary, syncModelsToDb } from '../lib/models';
+import { listenTrayActions } from '../lib/tray';
+import { commandErrorMessage } from '../lib/errors';
+import { OpenRouterRequestError, toolLoopAbortMessage, type OpenRouterToolCall } from '../lib/openrouter';
 import { executeCampToolCall, executeMcpToolCall, getAllToolSpecs, getToolKind, isMcpToolName } from '../lib/tools';
 import { buildMcpToolEntry, setMcpTools } from '../lib/tools/registry';
-import type { Camp, CampArtifact, CampArtifactMetadata, CampMessage, CampSummary, ModelRow, CampMessageAttachment, ProviderRegistryRow } from '../lib/types';
+import type { Camp, CampArtifact, CampArtifactMetadata, CampEmbeddingStatus, CampMessage, CampSummary, ModelRow, CampMessageAttachment, ProviderRegistryRow, TablePreview } from '../lib/types';
 
 const FALLBACK_MODEL = 'openrouter/auto';
 const DEFAULT_MAX_TOKENS = 1200;
@@ -89,7 +119,8 @@ function buildCorrelationId(): string {
 function modelDisplayLabel(model: ModelRow): string {
   const ctx = model.context_length ? ` · ${(model.context_length / 1000).toFixed(0)}k ctx` : '';
   const name = model.name?.trim() ? model.name : model.id;
-  return `[${model.provider_kind ?? 'openrouter'}] ${name}${ctx}`;
+  const verdict = modelNoteSummary(model.note);
+  return `[${model.provider_kind ?? 'openrouter'}] ${name}${ctx}${verdict ? ` · ${verdict}` : ''}`;
 }
 
 function modelSupportsTools(model: ModelRow | null): boolean {
@@ -136,6 +167,8 @@ type ToolApprovalItem = {
   status: ToolApprovalStatus;
   resultPreview: string | null;
   errorMessage: string | null;
+  /** Diff of a held `write_file` call, for camps that preview file writes. */
+  diff: string | null;
   createdAt: number;
 };
 
@@ -147,6 +180,30 @@ function truncatePreview(value: string, maxLength = 160): string {
   return `${value.slice(0, maxLength - 3)}...`;
 }
 
+async function previewToolFileWrite(campId: string, argsJson: string): Promise<string> {
+  try {
+    const args = JSON.parse(argsJson) as { path?: unknown; content?: unknown; encoding?: unknown };
+    if (typeof args.path !== 'string' || typeof args.content !== 'string') {
+      return 'No preview: the call is missing a path or content.';
+    }
+    const preview = await campPreviewContextFileWrite(
+      campId,
+      args.path,
+      args.content,
+      args.encoding === 'base64' ? 'base64' : undefined,
+    );
+    if (preview.binary) {
+      return `Binary write to ${preview.path}: ${preview.bytes_before} -> ${preview.bytes_after} bytes.`;
+    }
+    if (preview.too_large) {
+      return `Too large to diff ${preview.path}: ${preview.bytes_before} -> ${preview.bytes_after} bytes.`;
+    }
+    return preview.diff || `No changes to ${preview.path}.`;
+  } catch (previewError) {
+    return `No preview: ${commandErrorMessage(previewError, 'unable to diff this write.')}`;
+  }
+}
+
 function shouldRequireToolApproval(mode: ToolApprovalMode, kind: ToolQueueItemKind): boolean {
   if (mode === 'manual') {
     return true;
@@ -226,9 +283,14 @@ export function MainLayout() {
   const [draftName, setDraftName] = useState('');
   const [draftModel, setDraftModel] = useState(FALLBACK_MODEL);
   const [draftToolsEnabled, setDraftToolsEnabled] = useState(false);
+  const [draftLocalOnly, setDraftLocalOnly] = useState(false);
+  const [draftAutoExtractCode, setDraftAutoExtractCode] = useState(false);
+  const [draftPreviewFileWrites, setDraftPreviewFileWrites] = useState(false);
   const [draftSystemPrompt, setDraftSystemPrompt] = useState('');
 
   const [userMessage, setUserMessage] = useState('');
+  const [promptSuggestions, setPromptSuggestions] = useState<string[]>([]);
+  const [isSuggestingPrompts, setIsSuggestingPrompts] = useState(false);
   const [userAttachments, setUserAttachments] = useState<CampMessageAttachment[]>([]);
 
   // Minimal settings for now
@@ -236,9 +298,14 @@ export function MainLayout() {
   const maxTokens = DEFAULT_MAX_TOKENS;
 
   const [streamingText, setStreamingText] = useState('');
+  const [streamingTokensPerSecond, setStreamingTokensPerSecond] = useState<number | null>(null);
   const [isSavingContextFile, setIsSavingContextFile] = useState(false);
   const [isSending, setIsSending] = useState(false);
   const [isSyncingModels, setIsSyncingModels] = useState(false);
+  const [isSuggestingModel, setIsSuggestingModel] = useState(false);
+  const [isRebuildingArtifactIndex, setIsRebuildingArtifactIndex] = useState(false);
+  const [isExportingContextPack, setIsExportingContextPack] = useState(false);
+  const [isSharingConversation, setIsSharingConversation] = useState(false);
   const [isRefreshingContext, setIsRefreshingContext] = useState(false);
   const [promotingMessageId, setPromotingMessageId] = useState<string | null>(null);
   const [error, setError] = useState<string | null>(null);
@@ -246,6 +313,9 @@ export function MainLayout() {
   const [selectedContextFilePath, setSelectedContextFilePath] = useState<string | null>(null);
   const [selectedContextFileContent, setSelectedContextFileContent] = useState('');
   const [contextFileDraft, setContextFileDraft] = useState('');
+  const [selectedTablePreview, setSelectedTablePreview] = useState<TablePreview | null>(null);
+  const [embeddingStatus, setEmbeddingStatus] = useState<CampEmbeddingStatus | null>(null);
+  const [isIndexingEmbeddings, setIsIndexingEmbeddings] = useState(false);
   const [isLoadingContextFile, setIsLoadingContextFile] = useState(false);
   const [collapsedContextDirs, setCollapsedContextDirs] = useState<string[]>([]);
   const [toolApprovalQueue, setToolApprovalQueue] = useState<ToolApprovalItem[]>([]);
@@ -322,6 +392,10 @@ export function MainLayout() {
   } = useArtifactComposerState(artifacts);
 
   const contextTree = useMemo(() => buildContextTree(attachedContextFiles), [attachedContextFiles]);
+  const defaultContextPaths = useMemo(
+    () => selectedCamp?.config.default_context_paths ?? [],
+    [selectedCamp],
+  );
   const contextFileDirty = selectedContextFilePath ? contextFileDraft !== selectedContextFileContent : false;
 
   const loadModels = useCallback(async () => {
@@ -355,6 +429,23 @@ export function MainLayout() {
     setArtifacts(rows);
   }, []);
 
+  const autoTitleCamp = useCallback(async (campId: string) => {
+    try {
+      if (!(await getTitleModel())) {
+        return;
+      }
+      const suggestion = await campSuggestTitle({ camp_id: campId, apply: true });
+      if (!suggestion.applied) {
+        return;
+      }
+      const refreshed = await campLoad(campId);
+      setSelectedCamp((current) => (current?.config.id === campId ? refreshed : current));
+      await loadCamps();
+    } catch {
+      // Titling is best-effort; the camp keeps its default name.
+    }
+  }, [loadCamps]);
+
   const loadCampContextFiles = useCallback(async (campId: string): Promise<string[]> => {
     const pendingDirectories = [''];
     const visitedDirectories = new Set<string>(['']);
@@ -393,6 +484,9 @@ export function MainLayout() {
       setDraftName(camp.config.name);
       setDraftModel(camp.config.model);
       setDraftToolsEnabled(camp.config.tools_enabled);
+      setDraftLocalOnly(camp.config.local_only ?? false);
+      setDraftAutoExtractCode(camp.config.auto_extract_code_blocks ?? false);
+      setDraftPreviewFileWrites(camp.config.preview_file_writes ?? false);
       setDraftSystemPrompt(camp.system_prompt);
     },
     [loadCampContextFiles]
@@ -461,6 +555,30 @@ export function MainLayout() {
       });
   }, []);
 
+  useEffect(() => {
+    let isDisposed = false;
+    let dispose: (() => void) | null = null;
+
+    void listenTrayActions((action) => {
+      if (!isDisposed && action.action === 'inspect_mode') {
+        setDeveloperInspectMode(action.enabled);
+      }
+    }).then((unlisten) => {
+      if (isDisposed) {
+        unlisten();
+        return;
+      }
+      dispose = unlisten;
+    });
+
+    return () => {
+      isDisposed = true;
+      if (dispose) {
+        dispose();
+      }
+    };
+  }, []);
+
   useEffect(() => {
     if (!developerInspectMode) {
       return;
@@ -506,6 +624,34 @@ export function MainLayout() {
     };
   }, [developerInspectMode]);
 
+  useEffect(() => {
+    let isDisposed = false;
+    let dispose: (() => void) | null = null;
+
+    void listenMcpSamplingRequests((request) => {
+      if (isDisposed) {
+        return;
+      }
+      const approved = window.confirm(describeSamplingRequest(request));
+      void mcpResolveSamplingRequest(request.request_id, approved).catch(() => {
+        // The request already timed out on the Rust side.
+      });
+    }).then((unlisten) => {
+      if (isDisposed) {
+        unlisten();
+        return;
+      }
+      dispose = unlisten;
+    });
+
+    return () => {
+      isDisposed = true;
+      if (dispose) {
+        dispose();
+      }
+    };
+  }, []);
+
   const emitInspectEventForTurn = useCallback(
     async (
       campId: string,
@@ -655,6 +801,24 @@ export function MainLayout() {
     ],
   );
 
+  const loadEmbeddingStatus = useCallback(async (campId: string) => {
+    const [campStatus] = await embeddingIndexStatus(campId);
+    setEmbeddingStatus(campStatus ?? null);
+  }, []);
+
+  // Keeps an existing index current after context edits; camps never indexed stay untouched.
+  const refreshEmbeddingsQuietly = useCallback(
+    (campId: string) => {
+      void campRefreshEmbeddingIndex(campId, true)
+        .catch(() => {
+          // The embedding backend may be offline; the status button shows what is stale.
+        })
+        .then(() => loadEmbeddingStatus(campId))
+        .catch(() => {});
+    },
+    [loadEmbeddingStatus],
+  );
+
   const recordFileWriteForTurn = useCallback(
     async <T,>(campId: string, relativePath: string, operation: () => Promise<T>, summary: string): Promise<T> =>
       recordFileWritesForTurn(campId, [relativePath], operation, summary),
@@ -697,6 +861,33 @@ export function MainLayout() {
     });
   }, [selectedCampId, loadSelectedCamp]);
 
+  useEffect(() => {
+    if (!selectedCampId) {
+      return;
+    }
+    void campMarkOpened(selectedCampId).catch(() => {
+      // Only feeds the "recent" ordering on the home screen.
+    });
+  }, [selectedCampId]);
+
+  useEffect(() => {
+    setPromptSuggestions([]);
+  }, [selectedCampId]);
+
+  useEffect(() => {
+    setEmbeddingStatus(null);
+    if (!selectedCampId) {
+      return;
+    }
+    refreshEmbeddingsQuietly(selectedCampId);
+  }, [selectedCampId, refreshEmbeddingsQuietly]);
+
+  useEffect(() => {
+    void mcpSetActiveCamp(selectedCampId).catch(() => {
+      // Roots are advisory; servers keep working without them.
+    });
+  }, [selectedCampId]);
+
   useEffect(() => {
     if (!selectedCamp?.config.is_team) {
       if (centerMode === 'team') {
@@ -821,10 +1012,13 @@ export function MainLayout() {
     async (
       campId: string,
       toolCall: OpenRouterToolCall & { id: string },
+      previewFileWrites = false,
+      runId?: string,
     ): Promise<string> => {
       const argsJson = toolCall.function.arguments ?? '{}';
       const toolKind = getToolKind(toolCall.function.name) ?? 'unknown';
-      const requiresApproval = shouldRequireToolApproval(TOOL_APPROVAL_MODE, toolKind);
+      const holdForDiff = previewFileWrites && toolCall.function.name === 'write_file';
+      const requiresApproval = holdForDiff || shouldRequireToolApproval(TOOL_APPROVAL_MODE, toolKind);
       const initialItem: ToolApprovalItem = {
         id: toolCall.id,
         name: toolCall.function.name,
@@ -833,17 +1027,42 @@ export function MainLayout() {
         status: requiresApproval ? 'pending' : 'running',
         resultPreview: null,
         errorMessage: null,
+        diff: holdForDiff ? await previewToolFileWrite(campId, argsJson) : null,
         createdAt: Date.now(),
       };
       upsertToolApprovalItem(initialItem);
 
       if (requiresApproval) {
-        const decision = await new Promise<ToolApprovalDecision>((resolve) => {
-          toolApprovalResolversRef.current.set(toolCall.id, resolve);
-        });
+        let shownDiff = initialItem.diff;
+        for (;;) {
+          const decision = await new Promise<ToolApprovalDecision>((resolve) => {
+            toolApprovalResolversRef.current.set(toolCall.id, resolve);
+          });
 
-        if (decision === 'reject') {
-          return JSON.stringify({ error: TOOL_REJECT_MESSAGE });
+          if (decision === 'reject') {
+            return JSON.stringify({ error: TOOL_REJECT_MESSAGE });
+          }
+          if (!holdForDiff) {
+            break;
+          }
+
+          // The file may have changed while the call waited; only the diff the user saw is approved.
+          const currentDiff = await previewToolFileWrite(campId, argsJson);
+          if (currentDiff === shownDiff) {
+            break;
+          }
+          shownDiff = currentDiff;
+          setToolApprovalQueue((previous) =>
+            previous.map((item) =>
+              item.id === toolCall.id
+                ? {
+                  ...item,
+                  status: 'pending',
+                  diff: `The file changed since the last preview. Review the new diff:\n\n${currentDiff}`,
+                }
+                : item,
+            ),
+          );
         }
 
         setToolApprovalQueue((previous) =>
@@ -853,9 +1072,12 @@ export function MainLayout() {
 
       try {
         const toolResult = isMcpToolName(toolCall.function.name)
-          ? await executeMcpToolCall(toolCall)
+          ? await executeMcpToolCall(toolCall, campId)
           : await executeCampToolCall(toolCall, {
-            readFile: async (path) => campReadContextFile(campId, path),
+            readFile: async (path) =>
+              /\.xlsx$/i.test(path)
+                ? campTableToMarkdown({ camp_id: campId, path })
+                : campReadContextFile(campId, path),
             listFiles: async (path) => campListContextFiles(campId, path),
             writeFile: async (path, content, encoding) => {
               const normalizedPath = path.trim().replace(/^\/+/, '');
@@ -867,6 +1089,7 @@ export function MainLayout() {
                   : campWriteContextFile(campId, path, content),
                 `Tool write_file -> ${normalizedPath}`,
               );
+              refreshEmbeddingsQuietly(campId);
             },
             listArtifacts: async () => campListArtifacts(campId),
             getArtifact: async (artifactId) => campGetArtifact(campId, artifactId),
@@ -952,6 +1175,20 @@ export function MainLayout() {
                 'Tool update_camp_memory',
               );
             },
+            remember: async (key, note) =>
+              recordFileWritesForTurn(
+                campId,
+                ['memory.json', 'camp.json'],
+                () => campScratchpadRemember({ camp_id: campId, key, note }),
+                'Tool remember',
+              ),
+            recall: async (key) => campScratchpadRecall(campId, key),
+            runBuiltinTool,
+            fetchUrl: async (url) => campFetchUrl({ camp_id: campId, url, run_id: runId }),
+            queryDatabase: async ({ database, sql, maxRows }) =>
+              queryUserDatabase({ database, sql, max_rows: maxRows }),
+            searchContext: async ({ query, limit }) =>
+              hybridSearch({ query, limit, camp_id: campId, kinds: ['context_file'] }),
           });
 
         setToolApprovalQueue((previous) =>
@@ -1001,11 +1238,13 @@ export function MainLayout() {
       commitFileWritesToInspectTurn,
       emitInspectEventForActiveTurn,
       recordFileWritesForTurn,
+      refreshEmbeddingsQuietly,
       upsertToolApprovalItem,
     ],
   );
 
   useEffect(() => {
+    setSelectedTablePreview(null);
     if (!selectedCampId || !selectedContextFilePath) {
       setSelectedContextFileContent('');
       setContextFileDraft('');
@@ -1016,6 +1255,22 @@ export function MainLayout() {
     setIsLoadingContextFile(true);
     setError(null);
 
+    if (/\.xlsx$/i.test(selectedContextFilePath)) {
+      setSelectedContextFileContent('');
+      setContextFileDraft('');
+      void campPreviewTable({ camp_id: selectedCampId, path: selectedContextFilePath, max_rows: 200 })
+        .then((preview) => {
+          if (!ignore) setSelectedTablePreview(preview);
+        })
+        .catch((tableError) => {
+          if (!ignore) setError(commandErrorMessage(tableError, 'Unable to read spreadsheet.'));
+        })
+        .finally(() => {
+          if (!ignore) setIsLoadingContextFile(false);
+        });
+      return () => { ignore = true; };
+    }
+
     const isBinary = /\.(png|jpe?g|gif|webp|pdf)$/i.test(selectedContextFilePath);
     const readPromise = isBinary
       ? campReadContextFileBase64(selectedCampId, selectedContextFilePath)
@@ -1095,6 +1350,9 @@ export function MainLayout() {
           name: draftName,
           model: draftModel,
           tools_enabled: draftToolsEnabled && selectedModelSupportsTools,
+          local_only: draftLocalOnly,
+          auto_extract_code_blocks: draftAutoExtractCode,
+          preview_file_writes: draftPreviewFileWrites,
         }),
       'Persist camp config before send',
     );
@@ -1116,8 +1374,11 @@ export function MainLayout() {
 
     return refreshedCamp;
   }, [
+    draftAutoExtractCode,
+    draftLocalOnly,
     draftModel,
     draftName,
+    draftPreviewFileWrites,
     draftSystemPrompt,
     draftToolsEnabled,
     selectedModelSupportsTools,
@@ -1143,6 +1404,103 @@ export function MainLayout() {
     }
   };
 
+  const handleSuggestModel = async () => {
+    if (!selectedCampId) return;
+    setIsSuggestingModel(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const result = await recommendModel({
+        prompt: userMessage,
+        camp_id: selectedCampId,
+        has_images: userAttachments.length > 0,
+        needs_tools: draftToolsEnabled,
+        limit: 3,
+      });
+      const [best] = result.recommendations;
+      if (!best) {
+        setStatus(
+          result.local_only
+            ? 'No local model meets this prompt\'s requirements.'
+            : 'No model meets this prompt\'s requirements.',
+        );
+        return;
+      }
+      if (modelOptions.includes(best.model)) {
+        setDraftModel(best.model);
+      }
+      setStatus(`Suggested ${best.display_name}: ${best.reasons.join(' ') || 'best overall match.'}`);
+    } catch (suggestError) {
+      setError(commandErrorMessage(suggestError, 'Unable to suggest a model.'));
+    } finally {
+      setIsSuggestingModel(false);
+    }
+  };
+
+  const handleRebuildArtifactIndex = async () => {
+    if (!selectedCampId) return;
+    setIsRebuildingArtifactIndex(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await campRebuildArtifactIndex(selectedCampId);
+      await loadArtifacts(selectedCampId);
+      const parts = [`Artifact index rebuilt: ${report.kept} kept, ${report.recovered.length} recovered`];
+      if (report.missing.length > 0) {
+        parts.push(`${report.missing.length} missing file(s) dropped`);
+      }
+      if (report.unreconciled.length > 0) {
+        parts.push(`skipped ${report.unreconciled.map((file) => `${file.filename} (${file.reason})`).join(', ')}`);
+      }
+      if (report.corrupt_index_backup) {
+        parts.push(`corrupt index saved to ${report.corrupt_index_backup}`);
+      }
+      setStatus(`${parts.join('; ')}.`);
+    } catch (rebuildError) {
+      setError(commandErrorMessage(rebuildError, 'Unable to rebuild the artifact index.'));
+    } finally {
+      setIsRebuildingArtifactIndex(false);
+    }
+  };
+
+  const handleCopyContextPack = async () => {
+    if (!selectedCampId) return;
+    setIsExportingContextPack(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const pack = await campExportContextPack({ camp_id: selectedCampId });
+      await navigator.clipboard.writeText(pack.content);
+      setStatus(`Context pack copied: ${pack.sections.length} sections, about ${pack.estimated_tokens} tokens.`);
+    } catch (exportError) {
+      setError(commandErrorMessage(exportError, 'Unable to export the context pack.'));
+    } finally {
+      setIsExportingContextPack(false);
+    }
+  };
+
+  const handleShareConversation = async () => {
+    if (!selectedCampId) return;
+    setIsSharingConversation(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const result = await campShareExport({ camp_id: selectedCampId });
+      if (result) {
+        const masked = result.redactions.total_masked > 0 ? `, ${result.redactions.total_masked} values masked` : '';
+        setStatus(`Shared ${result.message_count} messages and ${result.artifact_count} artifacts to ${result.path}${masked}.`);
+      }
+    } catch (shareError) {
+      setError(commandErrorMessage(shareError, 'Unable to export the conversation.'));
+    } finally {
+      setIsSharingConversation(false);
+    }
+  };
+
   const handleRefreshContext = async () => {
     setIsRefreshingContext(true);
     setError(null);
@@ -1169,6 +1527,84 @@ export function MainLayout() {
     });
   };
 
+  const handleToggleDefaultContextFile = async () => {
+    if (!selectedCampId || !selectedCamp || !selectedContextFilePath) return;
+
+    const current = selectedCamp.config.default_context_paths ?? [];
+    const next = current.includes(selectedContextFilePath)
+      ? current.filter((path) => path !== selectedContextFilePath)
+      : [...current, selectedContextFilePath];
+    setError(null);
+
+    try {
+      const stored = await campSetDefaultContextPaths(selectedCampId, next);
+      setSelectedCamp((previous) =>
+        previous ? { ...previous, config: { ...previous.config, default_context_paths: stored } } : previous,
+      );
+    } catch (defaultContextError) {
+      setError(commandErrorMessage(defaultContextError, 'Unable to update default context files.'));
+    }
+  };
+
+  const handleEditFetchAllowedDomains = async () => {
+    if (!selectedCampId || !selectedCamp) return;
+
+    const current = (selectedCamp.config.fetch_allowed_domains ?? []).join(', ');
+    const input = window.prompt('Domains fetch_url may download from (comma separated)', current);
+    if (input === null) return;
+    setError(null);
+
+    try {
+      const stored = await campSetFetchAllowedDomains(
+        selectedCampId,
+        input.split(',').map((domain) => domain.trim()).filter(Boolean),
+      );
+      setSelectedCamp((previous) =>
+        previous ? { ...previous, config: { ...previous.config, fetch_allowed_domains: stored } } : previous,
+      );
+    } catch (fetchDomainsError) {
+      setError(commandErrorMessage(fetchDomainsError, 'Unable to update the fetch allowlist.'));
+    }
+  };
+
+  const handleSuggestPrompts = async () => {
+    if (!selectedCampId) return;
+
+    setIsSuggestingPrompts(true);
+    setError(null);
+
+    try {
+      const suggestions = await suggestPrompts(selectedCampId);
+      setPromptSuggestions(suggestions.prompts);
+    } catch (suggestError) {
+      setError(commandErrorMessage(suggestError, 'Unable to suggest prompts.'));
+    } finally {
+      setIsSuggestingPrompts(false);
+    }
+  };
+
+  const handleRefreshEmbeddingIndex = async () => {
+    if (!selectedCampId) return;
+
+    setIsIndexingEmbeddings(true);
+    setError(null);
+    setStatus(null);
+
+    try {
+      const report = await campRefreshEmbeddingIndex(selectedCampId);
+      await loadEmbeddingStatus(selectedCampId);
+      setStatus(
+        report.skipped
+          ? 'Context is already being indexed.'
+          : `Embedded ${report.embedded_chunks} chunks from ${report.embedded_files} files (${report.unchanged_files} unchanged, ${report.removed_files} removed).`,
+      );
+    } catch (indexError) {
+      setError(commandErrorMessage(indexError, 'Unable to index context files.'));
+    } finally {
+      setIsIndexingEmbeddings(false);
+    }
+  };
+
   const handleSaveContextFile = async () => {
     if (!selectedCampId || !selectedContextFilePath) return;
 
@@ -1181,6 +1617,7 @@ export function MainLayout() {
       setSelectedContextFileContent(contextFileDraft);
       await loadCamps();
       setStatus(`Saved ${selectedContextFilePath}`);
+      refreshEmbeddingsQuietly(selectedCampId);
     } catch (contextWriteError) {
       setError(contextWriteError instanceof Error ? contextWriteError.message : 'Unable to save context file.');
     } finally {
@@ -1223,6 +1660,7 @@ export function MainLayout() {
 
     setIsSending(true);
     setStreamingText('');
+    setStreamingTokensPerSecond(null);
     setError(null);
     setStatus(null);
     clearToolApprovalQueue();
@@ -1250,9 +1688,22 @@ export function MainLayout() {
 
     try {
       await persistCampDraftsForSend();
-      const selectedArtifactsForRequest: CampArtifact[] = await Promise.all(
-        selectedArtifactIds.map((artifactId) => campGetArtifact(selectedCampId, artifactId)),
+      const alwaysIncludedArtifacts = await campListAlwaysIncludeArtifacts(selectedCampId);
+      const manuallySelectedArtifacts: CampArtifact[] = await Promise.all(
+        selectedArtifactIds
+          .filter((artifactId) => !alwaysIncludedArtifacts.some((artifact) => artifact.metadata.id === artifactId))
+          .map((artifactId) => campGetArtifact(selectedCampId, artifactId)),
       );
+      const selectedArtifactsForRequest: CampArtifact[] = [...alwaysIncludedArtifacts, ...manuallySelectedArtifacts];
+      const defaultContextFiles = await campLoadDefaultContextFiles(selectedCampId);
+      const staleContextFiles = defaultContextFiles.filter((file) => file.status !== 'fresh');
+      if (staleContextFiles.length > 0) {
+        setStatus(
+          `Check default context files: ${staleContextFiles
+            .map((file) => `${file.path} (${file.status.replace('_', ' ')})`)
+            .join(', ')}`,
+        );
+      }
 
       await recordFileWritesForTurn(
         selectedCampId,
@@ -1276,11 +1727,23 @@ export function MainLayout() {
           campId: selectedCampId,
           camp: campForRuntime,
           selectedArtifacts: selectedArtifactsForRequest,
+          contextFiles: defaultContextFiles,
           temperature,
           maxTokens,
           onToken: (token) => {
             setStreamingText((previous) => previous + token);
           },
+          onProgress: (progress) => {
+            setStreamingTokensPerSecond(progress.tokens_per_second);
+            setStatus(null);
+          },
+          onQueued: (position) => {
+            setStatus(
+              position === 1
+                ? 'Waiting for the model: next in line.'
+                : `Waiting for the model: position ${position} in line.`,
+            );
+          },
           tools: selectedModelSupportsTools ? getAllToolSpecs() : undefined,
           correlationId: correlationId ?? undefined,
           onComposeStart: correlationId
@@ -1457,8 +1920,13 @@ export function MainLayout() {
               },
             }
             : undefined,
-          executeToolCall: async ({ campId, toolCall }) => {
-            return executeToolCallWithApproval(campId, toolCall);
+          executeToolCall: async ({ campId, toolCall, runId }) => {
+            return executeToolCallWithApproval(
+              campId,
+              toolCall,
+              campForRuntime.config.preview_file_writes ?? false,
+              runId,
+            );
           },
         });
 
@@ -1515,6 +1983,12 @@ export function MainLayout() {
 
       const updatedCamp = await campLoad(selectedCampId);
       setSelectedCamp(updatedCamp);
+      if (
+        updatedCamp.config.name === 'Untitled Camp' &&
+        updatedCamp.transcript.filter((message) => message.role === 'user').length === 1
+      ) {
+        void autoTitleCamp(selectedCampId);
+      }
       const usageIncrementPromise =
         selectedArtifactIds.length > 0
           ? recordFileWritesForTurn(
@@ -1574,13 +2048,18 @@ export function MainLayout() {
       }
 
       setUserMessage('');
+      setPromptSuggestions([]);
       setUserAttachments([]);
       setStreamingText('');
-      setStatus(
-        runtimeResult.usingTools
-          ? 'Response completed with tool use and saved to transcript.jsonl'
-          : 'Response streamed and saved to transcript.jsonl',
-      );
+      if (runtimeResult.loopAborted) {
+        setError(toolLoopAbortMessage(runtimeResult.loopAborted));
+      } else {
+        setStatus(
+          runtimeResult.usingTools
+            ? 'Response completed with tool use and saved to transcript.jsonl'
+            : 'Response streamed and saved to transcript.jsonl',
+        );
+      }
     } catch (sendError) {
       if (correlationId) {
         await emitInspectEventForTurn(selectedCampId, correlationId, {
@@ -1607,10 +2086,49 @@ export function MainLayout() {
     setStatus(`Branch from message ${message.id.slice(0, 8)} is coming soon.`);
   };
 
+  const handleUndoLastExchange = async () => {
+    if (!selectedCampId || isSending) return;
+
+    setError(null);
+    setStatus(null);
+
+    try {
+      const result = await campUndoLastExchange(selectedCampId);
+      const [prompt] = result.removed_messages;
+      if (prompt) {
+        setUserMessage(prompt.content);
+        setUserAttachments(prompt.attachments ?? []);
+      }
+      const updatedCamp = await campLoad(selectedCampId);
+      setSelectedCamp(updatedCamp);
+      await Promise.all([loadCamps(), loadArtifacts(selectedCampId)]);
+      setStatus(`Removed ${result.removed_messages.length} message(s). Edit the prompt and send again.`);
+      composerTextareaRef.current?.focus();
+    } catch (undoError) {
+      setError(commandErrorMessage(undoError, 'Unable to undo the last exchange.'));
+    }
+  };
+
   const handleReplayFromMessage = (message: CampMessage) => {
     setStatus(`Replay from message ${message.id.slice(0, 8)} is coming soon.`);
   };
 
+  const handleOpenRunForMessage = async (message: CampMessage) => {
+    if (!selectedCampId) return;
+    setError(null);
+
+    try {
+      const run = await getRunForMessage(selectedCampId, message.id);
+      if (run) {
+        navigate(`/runs/${run.id}`);
+      } else {
+        setStatus('The run for this message is no longer stored.');
+      }
+    } catch (runError) {
+      setError(commandErrorMessage(runError, 'Unable to open the run.'));
+    }
+  };
+
   const handleComposerKeyDown = (event: KeyboardEvent<HTMLTextAreaElement>) => {
     if ((event.metaKey || event.ctrlKey) && event.key === 'Enter') {
       event.preventDefault();
@@ -1713,8 +2231,9 @@ export function MainLayout() {
           className={`tree-row tree-file ${node.path === selectedContextFilePath ? 'active' : ''}`}
           onClick={() => setSelectedContextFilePath(node.path)}
           style={{ paddingLeft: `${0.55 + depth * 0.8}rem` }}
+          title={defaultContextPaths.includes(node.path) ? 'Attached to every request' : undefined}
         >
-          <span className="tree-glyph">*</span>
+          <span className="tree-glyph">{defaultContextPaths.includes(node.path) ? '📌' : '*'}</span>
           <span className="tree-label">{node.name}</span>
         </button>
       );
@@ -1752,6 +2271,17 @@ export function MainLayout() {
                 ))}
               </select>
             )}
+            {selectedCamp && (
+              <button
+                type="button"
+                className="icon-button"
+                onClick={() => void handleSuggestModel()}
+                disabled={isSuggestingModel}
+                title="Suggest a model for the current draft"
+              >
+                {isSuggestingModel ? '...' : 'SUGGEST'}
+              </button>
+            )}
             {error && <span className="error-line" style={{ margin: 0, padding: 'var(--space-1) var(--space-2)' }}>{error}</span>}
             {status && <span className="status-line" style={{ margin: 0, padding: 'var(--space-1) var(--space-2)' }}>{status}</span>}
           </div>
@@ -1843,6 +2373,42 @@ export function MainLayout() {
               {selectedCamp && visibleArtifacts.length === 0 ? (
                 <ViewState.Empty title="No Match" message="No artifacts found." />
               ) : null}
+              {selectedCamp ? (
+                <button
+                  type="button"
+                  className="secondary"
+                  style={{ marginTop: 'var(--space-2)' }}
+                  onClick={() => void handleRebuildArtifactIndex()}
+                  disabled={isRebuildingArtifactIndex || isSending}
+                  title="Re-scan the artifacts folder and repair index.json"
+                >
+                  {isRebuildingArtifactIndex ? 'Rebuilding…' : 'Rebuild Index'}
+                </button>
+              ) : null}
+              {selectedCamp ? (
+                <button
+                  type="button"
+                  className="secondary"
+                  style={{ marginTop: 'var(--space-2)' }}
+                  onClick={() => void handleCopyContextPack()}
+                  disabled={isExportingContextPack}
+                  title="Copy the system prompt, memory and active artifacts as one Markdown document"
+                >
+                  {isExportingContextPack ? 'Exporting…' : 'Copy Context Pack'}
+                </button>
+              ) : null}
+              {selectedCamp ? (
+                <button
+                  type="button"
+                  className="secondary"
+                  style={{ marginTop: 'var(--space-2)' }}
+                  onClick={() => void handleShareConversation()}
+                  disabled={isSharingConversation}
+                  title="Save the conversation as a standalone HTML page, without tool calls and with emails, phone numbers and keys masked"
+                >
+                  {isSharingConversation ? 'Exporting…' : 'Share as HTML'}
+                </button>
+              ) : null}
             </div>
           )}
         />
@@ -1854,6 +2420,22 @@ export function MainLayout() {
           onModeChange={setCenterMode}
           renderHeaderActions={() => (
             <>
+              {centerMode === 'editor' ? (
+                <button
+                  type="button"
+                  className="icon-button"
+                  onClick={() => void handleToggleDefaultContextFile()}
+                  disabled={!selectedCamp || !selectedContextFilePath}
+                  aria-pressed={selectedContextFilePath ? defaultContextPaths.includes(selectedContextFilePath) : false}
+                  title={
+                    selectedContextFilePath && defaultContextPaths.includes(selectedContextFilePath)
+                      ? 'Stop attaching this file to every request'
+                      : 'Attach this file to every request'
+                  }
+                >
+                  📌
+                </button>
+              ) : null}
               {centerMode === 'editor' ? (
                 <button
                   type="button"
@@ -1897,6 +2479,30 @@ export function MainLayout() {
                         <p>Browser cannot display PDF.</p>
                       </object>
                     </div>
+                  ) : selectedTablePreview ? (
+                    <div style={{ flex: 1, minHeight: 0, overflow: 'auto' }}>
+                      <p className="settings-note">
+                        {selectedTablePreview.sheet ? `${selectedTablePreview.sheet} · ` : ''}
+                        {selectedTablePreview.row_count} row(s)
+                        {selectedTablePreview.truncated ? `, first ${selectedTablePreview.rows.length} shown` : ''}
+                      </p>
+                      <table className="table-preview">
+                        <thead>
+                          <tr>
+                            {selectedTablePreview.columns.map((column, index) => (
+                              <th key={index} title={column.inferred_type}>{column.name}</th>
+                            ))}
+                          </tr>
+                        </thead>
+                        <tbody>
+                          {selectedTablePreview.rows.map((row, rowIndex) => (
+                            <tr key={rowIndex}>
+                              {row.map((cell, cellIndex) => <td key={cellIndex}>{cell}</td>)}
+                            </tr>
+                          ))}
+                        </tbody>
+                      </table>
+                    </div>
                   ) : /\.html?$/i.test(selectedContextFilePath) ? (
                     <div style={{ width: '100%', height: '100%', background: 'white' }}>
                       <iframe srcDoc={contextFileDraft} sandbox="allow-scripts allow-popups" style={{ width: '100%', height: '100%', border: 'none' }} title="Preview" />
@@ -1956,6 +2562,84 @@ export function MainLayout() {
                         <span style={{ fontSize: '0.8rem' }}>Tools</span>
                       </label>
                     ) : null}
+                    {rightMode === 'chat' ? (
+                      <label
+                        className="settings-toggle"
+                        style={{ margin: 0, display: 'flex', gap: '6px' }}
+                        title="Only send this camp's requests to models that run on this machine"
+                      >
+                        <input
+                          type="checkbox"
+                          checked={draftLocalOnly}
+                          onChange={(event) => setDraftLocalOnly(event.target.checked)}
+                        />
+                        <span style={{ fontSize: '0.8rem' }}>Local only</span>
+                      </label>
+                    ) : null}
+                    {rightMode === 'chat' ? (
+                      <label
+                        className="settings-toggle"
+                        style={{ margin: 0, display: 'flex', gap: '6px' }}
+                        title="Save code blocks from replies as artifacts"
+                      >
+                        <input
+                          type="checkbox"
+                          checked={draftAutoExtractCode}
+                          onChange={(event) => setDraftAutoExtractCode(event.target.checked)}
+                        />
+                        <span style={{ fontSize: '0.8rem' }}>Code to artifacts</span>
+                      </label>
+                    ) : null}
+                    {rightMode === 'chat' && draftToolsEnabled ? (
+                      <label
+                        className="settings-toggle"
+                        style={{ margin: 0, display: 'flex', gap: '6px' }}
+                        title="Hold file writes from tools for approval with a diff"
+                      >
+                        <input
+                          type="checkbox"
+                          checked={draftPreviewFileWrites}
+                          onChange={(event) => setDraftPreviewFileWrites(event.target.checked)}
+                        />
+                        <span style={{ fontSize: '0.8rem' }}>Preview writes</span>
+                      </label>
+                    ) : null}
+                    {rightMode === 'chat' && draftToolsEnabled ? (
+                      <button
+                        type="button"
+                        className="secondary"
+                        style={{ fontSize: '0.8rem' }}
+                        title={
+                          (selectedCamp?.config.fetch_allowed_domains ?? []).join(', ') ||
+                          'fetch_url is blocked until domains are allowed'
+                        }
+                        onClick={() => {
+                          void handleEditFetchAllowedDomains();
+                        }}
+                      >
+                        Fetch domains ({selectedCamp?.config.fetch_allowed_domains?.length ?? 0})
+                      </button>
+                    ) : null}
+                    {rightMode === 'chat' && selectedCampId ? (
+                      <button
+                        type="button"
+                        className="secondary"
+                        style={{ fontSize: '0.8rem' }}
+                        disabled={isIndexingEmbeddings}
+                        title={
+                          embeddingStatus
+                            ? `${embeddingStatus.stale_chunks} of ${embeddingStatus.total_chunks} chunks need embedding with ${embeddingStatus.model}`
+                            : 'Embed context files for semantic search'
+                        }
+                        onClick={() => {
+                          void handleRefreshEmbeddingIndex();
+                        }}
+                      >
+                        {isIndexingEmbeddings
+                          ? 'Indexing...'
+                          : `Index context (${embeddingStatus?.stale_chunks ?? 0}/${embeddingStatus?.total_chunks ?? 0} stale)`}
+                      </button>
+                    ) : null}
                   </div>
                 )}
               </div>
@@ -1969,11 +2653,18 @@ export function MainLayout() {
                     <TranscriptView
                       selectedCamp={selectedCamp}
                       streamingText={streamingText}
+                      streamingTokensPerSecond={streamingTokensPerSecond}
                       artifactById={artifactById}
                       isSending={isSending}
                       promotingMessageId={promotingMessageId}
                       onBranchFromMessage={handleBranchFromMessage}
                       onReplayFromMessage={handleReplayFromMessage}
+                      onOpenRunForMessage={(message) => {
+                        void handleOpenRunForMessage(message);
+                      }}
+                      onUndoLastExchange={() => {
+                        void handleUndoLastExchange();
+                      }}
                       onPromoteMessageToArtifact={(message) => {
                         void handlePromoteMessageToArtifact(message);
                       }}
@@ -2012,6 +2703,9 @@ export function MainLayout() {
                               <strong style={{ fontSize: '10px' }}>{item.name}</strong>
                               <span style={{ fontSize: '10px' }}>{item.status}</span>
                             </header>
+                            {item.diff && item.status === 'pending' ? (
+                              <pre className="tool-queue-args tool-queue-diff">{item.diff}</pre>
+                            ) : null}
                             {item.status === 'pending' ? (
                               <div className="tool-queue-item-actions" style={{ marginTop: 'var(--space-1)' }}>
                                 <button type="button" onClick={() => handleApproveToolCall(item.id)}>[APPROVE]</button>
@@ -2056,6 +2750,24 @@ export function MainLayout() {
                     ))}
                   </div>
                 )}
+                {promptSuggestions.length > 0 && (
+                  <div className="prompt-suggestions">
+                    {promptSuggestions.map((suggestion) => (
+                      <button
+                        key={suggestion}
+                        type="button"
+                        className="secondary"
+                        onClick={() => {
+                          setUserMessage(suggestion);
+                          setPromptSuggestions([]);
+                          composerTextareaRef.current?.focus();
+                        }}
+                      >
+                        {suggestion}
+                      </button>
+                    ))}
+                  </div>
+                )}
                 <textarea
                   ref={composerTextareaRef}
                   value={userMessage}
@@ -2073,6 +2785,18 @@ export function MainLayout() {
                     <label htmlFor="composer-file-upload" className="secondary-action icon-button" style={{ cursor: 'pointer', padding: '4px 8px' }} title="Attach File">
                       [ATTACH]
                     </label>
+                    <button
+                      type="button"
+                      className="secondary-action icon-button"
+                      style={{ padding: '4px 8px' }}
+                      title="Suggest next prompts"
+                      disabled={!selectedCamp || isSuggestingPrompts}
+                      onClick={() => {
+                        void handleSuggestPrompts();
+                      }}
+                    >
+                      {isSuggestingPrompts ? '...' : '[IDEAS]'}
+                    </button>
                   </div>
                   <button type="submit" className="primary-action icon-button" disabled={isSending || !selectedCamp} title="Send Message" style={{ padding: '4px 8px' }}>
                     {isSending ? '...' : '[SEND]'}
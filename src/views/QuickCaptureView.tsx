@@ -0,0 +1,53 @@
+import { useState, type KeyboardEvent } from 'react';
+
+import { dismissQuickCapture, quickCapture } from '../lib/db';
+import { commandErrorMessage } from '../lib/errors';
+
+/** Rendered alone in the quick capture window; Enter saves, Escape closes. */
+export function QuickCaptureView() {
+  const [text, setText] = useState('');
+  const [saving, setSaving] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const save = async () => {
+    if (!text.trim() || saving) {
+      return;
+    }
+    setSaving(true);
+    setError(null);
+
+    try {
+      // The window closes itself once the capture is stored.
+      await quickCapture(text);
+      setText('');
+    } catch (captureError) {
+      setError(commandErrorMessage(captureError, 'Unable to save capture.'));
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  const handleKeyDown = (event: KeyboardEvent<HTMLTextAreaElement>) => {
+    if (event.key === 'Escape') {
+      event.preventDefault();
+      void dismissQuickCapture();
+    } else if (event.key === 'Enter' && !event.shiftKey) {
+      event.preventDefault();
+      void save();
+    }
+  };
+
+  return (
+    <main className="quick-capture">
+      <textarea
+        autoFocus
+        value={text}
+        disabled={saving}
+        placeholder="Capture a thought… (Enter to save, Esc to close)"
+        onChange={(event) => setText(event.target.value)}
+        onKeyDown={handleKeyDown}
+      />
+      {error && <p className="inline-error">{error}</p>}
+    </main>
+  );
+}